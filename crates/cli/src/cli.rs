@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(
@@ -55,6 +55,14 @@ pub enum Commands {
     #[command(subcommand)]
     Permissions(PermissionsCommand),
 
+    /// Manage MCP servers
+    #[command(subcommand)]
+    Mcp(McpCommand),
+
+    /// Manage scheduled agent jobs
+    #[command(subcommand)]
+    Cron(CronCommand),
+
     /// Launch interactive TUI dashboard
     Tui,
 }
@@ -111,6 +119,124 @@ pub enum PluginsCommand {
     List,
 }
 
+#[derive(Subcommand)]
+pub enum McpCommand {
+    /// List MCP servers and their status
+    List,
+    /// Add a dynamic MCP server (persisted across restarts)
+    Add {
+        /// Server name (alphanumeric, '_' and '-', max 64 chars)
+        name: String,
+        /// Command that starts the server
+        #[arg(long, required_unless_present = "code_file")]
+        command: Option<String>,
+        /// Python file with tool definitions; the kernel wraps it in a server script
+        #[arg(long, value_name = "FILE", conflicts_with = "command")]
+        code_file: Option<std::path::PathBuf>,
+        /// Description
+        #[arg(long)]
+        description: Option<String>,
+        /// Arguments passed to the command (after `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Remove a dynamic MCP server
+    Remove {
+        /// Server name
+        name: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+    /// Restart a server
+    Restart {
+        /// Server name
+        name: String,
+    },
+    /// Start a stopped server
+    Start {
+        /// Server name
+        name: String,
+    },
+    /// Stop a running server
+    Stop {
+        /// Server name
+        name: String,
+    },
+    /// List the tools a server provides
+    Tools {
+        /// Server name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CronCommand {
+    /// List cron jobs and their next run
+    List {
+        /// Only show jobs of this agent
+        #[arg(long)]
+        agent: Option<String>,
+    },
+    /// Schedule a message to an agent
+    #[command(group(
+        ArgGroup::new("schedule")
+            .required(true)
+            .args(["every", "cron", "at", "after"])
+    ))]
+    Create {
+        /// Agent ID
+        agent: String,
+        /// Job name
+        #[arg(long)]
+        name: String,
+        /// Run every N seconds (min 60)
+        #[arg(long, value_name = "SECS")]
+        every: Option<u64>,
+        /// Cron expression (5 fields crontab, or 6-7 with seconds)
+        #[arg(long, value_name = "EXPR")]
+        cron: Option<String>,
+        /// Run once at an RFC 3339 time
+        #[arg(long, value_name = "TIME")]
+        at: Option<String>,
+        /// Run after another job finishes
+        #[arg(long, value_name = "JOB_ID")]
+        after: Option<String>,
+        /// IANA timezone for cron expressions (default UTC)
+        #[arg(long)]
+        timezone: Option<String>,
+        /// Reasoning engine to use instead of the agent's default
+        #[arg(long)]
+        engine: Option<String>,
+        /// Message sent to the agent
+        #[arg(required = true)]
+        message: Vec<String>,
+    },
+    /// Enable a job
+    Enable {
+        /// Job ID
+        id: String,
+    },
+    /// Disable a job without deleting it
+    Disable {
+        /// Job ID
+        id: String,
+    },
+    /// Run a job now, outside its schedule
+    Run {
+        /// Job ID
+        id: String,
+    },
+    /// Delete a job
+    Delete {
+        /// Job ID
+        id: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum PermissionsCommand {
     /// List pending permission requests
@@ -134,7 +260,7 @@ pub enum PermissionsCommand {
     Grant {
         /// Plugin ID
         plugin: String,
-        /// Permission to grant (NetworkAccess, FileRead, FileWrite, ProcessExecution, VisionRead, AdminAccess, MemoryRead, MemoryWrite, InputControl)
+        /// Permission to grant (NetworkAccess, FileRead, FileWrite, ProcessExecution, VisionRead, AdminAccess, MemoryRead, MemoryWrite, InputControl, ClipboardAccess, CameraRead, ChannelRelay)
         permission: String,
     },
     /// Revoke a permission from a plugin
@@ -102,28 +102,7 @@ impl ClotoClient {
 
     /// DELETE agent by ID.
     pub async fn delete_agent(&self, agent_id: &str) -> Result<serde_json::Value> {
-        let req = self
-            .client
-            .delete(self.url(&format!("/api/agents/{agent_id}")));
-        let resp = self
-            .add_auth(req)
-            .send()
-            .await
-            .context("Failed to connect to Cloto kernel")?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            let body: serde_json::Value = resp.json().await.unwrap_or_default();
-            let msg = body
-                .get("error")
-                .and_then(|e| e.get("message"))
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown error");
-            anyhow::bail!("{status}: {msg}");
-        }
-        resp.json::<serde_json::Value>()
-            .await
-            .context("Failed to parse response")
+        self.delete(&format!("/api/agents/{agent_id}")).await
     }
 
     /// POST power toggle.
@@ -215,10 +194,101 @@ impl ClotoClient {
             .context("Failed to parse response")
     }
 
+    /// DELETE request, returning deserialized JSON. Error responses are
+    /// reported with the kernel's error message.
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let req = self.client.delete(self.url(path));
+        let resp = self
+            .add_auth(req)
+            .send()
+            .await
+            .context("Failed to connect to Cloto kernel")?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body: serde_json::Value = resp.json().await.unwrap_or_default();
+            let msg = body
+                .get("error")
+                .and_then(|e| e.get("message"))
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown error");
+            anyhow::bail!("{status}: {msg}");
+        }
+        resp.json::<T>().await.context("Failed to parse response")
+    }
+
+    /// GET MCP servers (`{ "servers": [...], "count": n }`).
+    pub async fn get_mcp_servers(&self) -> Result<Vec<serde_json::Value>> {
+        let body: serde_json::Value = self.get("/api/mcp/servers").await?;
+        Ok(body
+            .get("servers")
+            .and_then(|s| s.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// POST create a dynamic MCP server.
+    pub async fn create_mcp_server(&self, req: &serde_json::Value) -> Result<serde_json::Value> {
+        self.post("/api/mcp/servers", req).await
+    }
+
+    /// DELETE a dynamic MCP server.
+    pub async fn delete_mcp_server(&self, name: &str) -> Result<serde_json::Value> {
+        self.delete(&format!("/api/mcp/servers/{name}")).await
+    }
+
+    /// POST an MCP server lifecycle action (`start`, `stop`, `restart`).
+    pub async fn mcp_server_action(&self, name: &str, action: &str) -> Result<serde_json::Value> {
+        self.post(
+            &format!("/api/mcp/servers/{name}/{action}"),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// GET cron jobs (`{ "jobs": [...], "count": n }`), optionally of one agent.
+    pub async fn get_cron_jobs(&self, agent_id: Option<&str>) -> Result<Vec<serde_json::Value>> {
+        let path = match agent_id {
+            Some(id) => format!("/api/cron/jobs?agent_id={id}"),
+            None => "/api/cron/jobs".to_string(),
+        };
+        let body: serde_json::Value = self.get(&path).await?;
+        Ok(body
+            .get("jobs")
+            .and_then(|j| j.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// POST create a cron job.
+    pub async fn create_cron_job(&self, req: &serde_json::Value) -> Result<serde_json::Value> {
+        self.post("/api/cron/jobs", req).await
+    }
+
+    /// POST enable or disable a cron job.
+    pub async fn toggle_cron_job(&self, id: &str, enabled: bool) -> Result<serde_json::Value> {
+        self.post(
+            &format!("/api/cron/jobs/{id}/toggle"),
+            &serde_json::json!({ "enabled": enabled }),
+        )
+        .await
+    }
+
+    /// POST run a cron job immediately.
+    pub async fn run_cron_job(&self, id: &str) -> Result<serde_json::Value> {
+        self.post(&format!("/api/cron/jobs/{id}/run"), &serde_json::json!({}))
+            .await
+    }
+
+    /// DELETE a cron job.
+    pub async fn delete_cron_job(&self, id: &str) -> Result<serde_json::Value> {
+        self.delete(&format!("/api/cron/jobs/{id}")).await
+    }
+
     /// GET SSE stream (raw response for line-by-line parsing).
-    #[allow(dead_code)]
-    pub async fn sse_stream(&self) -> Result<reqwest::Response> {
-        let req = self.client.get(self.url("/api/events"));
+    /// `filter` is passed as query parameters (`types`, `agent_id`).
+    pub async fn sse_stream(&self, filter: &[(&str, &str)]) -> Result<reqwest::Response> {
+        let req = self.client.get(self.url("/api/events")).query(filter);
         let resp = self
             .add_auth(req)
             .send()
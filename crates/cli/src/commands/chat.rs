@@ -22,6 +22,7 @@ pub async fn run(client: &ClotoClient, agent: &str, message: &str, json_mode: bo
         content: message.to_string(),
         timestamp: chrono::Utc::now(),
         metadata: std::collections::HashMap::new(),
+        attachments: Vec::new(),
     };
 
     // Send chat message
@@ -38,7 +39,10 @@ pub async fn run(client: &ClotoClient, agent: &str, message: &str, json_mode: bo
     };
 
     let response = client
-        .sse_stream()
+        .sse_stream(&[
+            ("types", "ThoughtResponse,ThoughtResponseChunk"),
+            ("agent_id", agent),
+        ])
         .await
         .context("Failed to connect to event stream")?;
 
@@ -48,6 +52,7 @@ pub async fn run(client: &ClotoClient, agent: &str, message: &str, json_mode: bo
     let mut stream = response.bytes_stream();
     let mut buffer = String::new();
     let mut reply = None;
+    let mut streamed = false;
 
     // bug-030: Use tokio::time::timeout to enforce deadline on each chunk read,
     // preventing indefinite blocking when server goes silent after keep-alive.
@@ -87,6 +92,34 @@ pub async fn run(client: &ClotoClient, agent: &str, message: &str, json_mode: bo
                                     let event_type =
                                         event_data.get("type").and_then(|t| t.as_str());
 
+                                    // Print the reply as it is generated
+                                    if event_type == Some("ThoughtResponseChunk") && !json_mode {
+                                        if let Some(inner) = event_data.get("data") {
+                                            if inner.get("agent_id").and_then(|a| a.as_str())
+                                                == Some(agent)
+                                                && inner
+                                                    .get("source_message_id")
+                                                    .and_then(|m| m.as_str())
+                                                    == Some(msg.id.as_str())
+                                            {
+                                                let delta = inner
+                                                    .get("delta")
+                                                    .and_then(|d| d.as_str())
+                                                    .unwrap_or("");
+                                                if !streamed {
+                                                    if let Some(ref sp) = sp {
+                                                        sp.finish_and_clear();
+                                                    }
+                                                    print!("  {}: ", agent.cyan().bold());
+                                                    streamed = true;
+                                                }
+                                                print!("{delta}");
+                                                let _ =
+                                                    std::io::Write::flush(&mut std::io::stdout());
+                                            }
+                                        }
+                                    }
+
                                     if event_type == Some("ThoughtResponse") {
                                         if let Some(inner) = event_data.get("data") {
                                             let resp_agent = inner
@@ -154,6 +187,8 @@ pub async fn run(client: &ClotoClient, agent: &str, message: &str, json_mode: bo
                     "latency_ms": latency_ms,
                 });
                 println!("{}", serde_json::to_string_pretty(&data)?);
+            } else if streamed {
+                println!();
             } else {
                 println!("  {}: {content}", agent.cyan().bold());
             }
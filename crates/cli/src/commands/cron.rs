@@ -0,0 +1,280 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use colored::Colorize;
+use comfy_table::{presets::NOTHING, ContentArrangement, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::cli::CronCommand;
+use crate::client::ClotoClient;
+use crate::output;
+
+pub async fn run(client: &ClotoClient, cmd: CronCommand, json_mode: bool) -> Result<()> {
+    match cmd {
+        CronCommand::List { agent } => list(client, agent.as_deref(), json_mode).await,
+        CronCommand::Create {
+            agent,
+            name,
+            every,
+            cron,
+            at,
+            after,
+            timezone,
+            engine,
+            message,
+        } => {
+            let (schedule_type, schedule_value) = match (every, cron, at, after) {
+                (Some(secs), ..) => ("interval", secs.to_string()),
+                (_, Some(expr), ..) => ("cron", expr),
+                (_, _, Some(time), _) => ("once", time),
+                (.., Some(job_id)) => ("after", job_id),
+                // clap requires one of the schedule flags
+                _ => unreachable!(),
+            };
+            let body = serde_json::json!({
+                "agent_id": agent,
+                "name": name,
+                "schedule_type": schedule_type,
+                "schedule_value": schedule_value,
+                "message": message.join(" "),
+                "engine_id": engine,
+                "timezone": timezone,
+            });
+            create(client, &body, json_mode).await
+        }
+        CronCommand::Enable { id } => toggle(client, &id, true, json_mode).await,
+        CronCommand::Disable { id } => toggle(client, &id, false, json_mode).await,
+        CronCommand::Run { id } => run_now(client, &id, json_mode).await,
+        CronCommand::Delete { id, force } => delete(client, &id, force, json_mode).await,
+    }
+}
+
+async fn list(client: &ClotoClient, agent: Option<&str>, json_mode: bool) -> Result<()> {
+    let sp = if json_mode {
+        None
+    } else {
+        Some(output::spinner("Loading cron jobs..."))
+    };
+    let jobs = client.get_cron_jobs(agent).await?;
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&jobs)?);
+        return Ok(());
+    }
+
+    output::print_header("Cron Jobs");
+
+    if jobs.is_empty() {
+        println!("  {}", "No cron jobs scheduled.".dimmed());
+        println!();
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    let now = Utc::now();
+    for job in &jobs {
+        let str_field = |key: &str| job.get(key).and_then(|v| v.as_str()).unwrap_or("-");
+        let enabled = job
+            .get("enabled")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        let next_run = if enabled {
+            job.get("next_run_at")
+                .and_then(serde_json::Value::as_i64)
+                .map_or_else(|| "-".to_string(), |ms| format_next_run(ms, now))
+        } else {
+            "disabled".to_string()
+        };
+        let dot = if enabled {
+            "●".green().to_string()
+        } else {
+            "○".dimmed().to_string()
+        };
+
+        table.add_row(vec![
+            format!("  {dot}"),
+            str_field("name").bold().to_string(),
+            str_field("agent_id").to_string(),
+            describe_schedule(str_field("schedule_type"), str_field("schedule_value")),
+            next_run,
+            last_status(job),
+            str_field("id").dimmed().to_string(),
+        ]);
+    }
+
+    println!("{table}");
+    println!();
+    Ok(())
+}
+
+async fn create(client: &ClotoClient, body: &serde_json::Value, json_mode: bool) -> Result<()> {
+    // Catch typos before they reach the kernel
+    let schedule_type = body["schedule_type"].as_str().unwrap_or_default();
+    let schedule_value = body["schedule_value"].as_str().unwrap_or_default();
+    cloto_shared::schedule::validate_schedule(schedule_type, schedule_value)?;
+
+    let sp = if json_mode {
+        None
+    } else {
+        Some(output::spinner("Creating cron job..."))
+    };
+    let result = client.create_cron_job(body).await;
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+    let result = result?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let id = result.get("id").and_then(|v| v.as_str()).unwrap_or("-");
+    println!(
+        "  {} Cron job {} created",
+        "✓".green().bold(),
+        body["name"].as_str().unwrap_or_default().bold()
+    );
+    println!("  {}", id.dimmed());
+
+    if schedule_type == "after" {
+        println!(
+            "  {}",
+            format!("Runs after {schedule_value} finishes").dimmed()
+        );
+    } else {
+        let now = Utc::now();
+        let runs: Vec<i64> = result
+            .get("next_runs")
+            .and_then(|v| v.as_array())
+            .map(|runs| {
+                runs.iter()
+                    .filter_map(|r| r.as_str())
+                    .filter_map(|r| DateTime::parse_from_rfc3339(r).ok())
+                    .map(|dt| dt.timestamp_millis())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !runs.is_empty() {
+            println!();
+            println!("  {}", "Next runs:".bold());
+            for ms in runs {
+                println!("    {}", format_next_run(ms, now));
+            }
+        }
+    }
+    println!();
+    Ok(())
+}
+
+async fn toggle(client: &ClotoClient, id: &str, enabled: bool, json_mode: bool) -> Result<()> {
+    let result = client.toggle_cron_job(id, enabled).await?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        let state = if enabled { "enabled" } else { "disabled" };
+        println!("  {} {} {}", "✓".green().bold(), id.bold(), state);
+    }
+    Ok(())
+}
+
+async fn run_now(client: &ClotoClient, id: &str, json_mode: bool) -> Result<()> {
+    let result = client.run_cron_job(id).await?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("  {} {} dispatched", "✓".green().bold(), id.bold());
+    if let Some(message_id) = result.get("message_id").and_then(|v| v.as_str()) {
+        println!("  {}", format!("message {message_id}").dimmed());
+    }
+    Ok(())
+}
+
+async fn delete(client: &ClotoClient, id: &str, force: bool, json_mode: bool) -> Result<()> {
+    if !force && !json_mode {
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("  Delete cron job {id}?"))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("  Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let result = client.delete_cron_job(id).await?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!("  {} Cron job {} deleted", "✓".green().bold(), id.bold());
+    }
+    Ok(())
+}
+
+fn describe_schedule(schedule_type: &str, schedule_value: &str) -> String {
+    match schedule_type {
+        "interval" => schedule_value.parse::<i64>().map_or_else(
+            |_| format!("every {schedule_value}s"),
+            |secs| format!("every {}", format_duration(secs)),
+        ),
+        "cron" => schedule_value.to_string(),
+        "once" => "once".to_string(),
+        "after" => format!("after {schedule_value}"),
+        other => other.to_string(),
+    }
+}
+
+/// `last_status` of a job, colored.
+fn last_status(job: &serde_json::Value) -> String {
+    match job.get("last_status").and_then(|v| v.as_str()) {
+        Some("success") => "success".green().to_string(),
+        Some(status) => status.red().to_string(),
+        None => "never run".dimmed().to_string(),
+    }
+}
+
+/// Local time of a run plus how far away it is, e.g.
+/// `2026-03-01 09:00 (in 2h 5m)`.
+fn format_next_run(ms: i64, now: DateTime<Utc>) -> String {
+    // "after" jobs are never due on their own
+    if ms == i64::MAX {
+        return "on trigger".to_string();
+    }
+    let Some(at) = Utc.timestamp_millis_opt(ms).single() else {
+        return "-".to_string();
+    };
+    let local = at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+    let delta = (at - now).num_seconds();
+    if delta < 0 {
+        format!("{local} ({})", "overdue".yellow())
+    } else {
+        format!(
+            "{local} {}",
+            format!("(in {})", format_duration(delta)).dimmed()
+        )
+    }
+}
+
+/// Coarse duration: the two largest units of days, hours, minutes.
+fn format_duration(secs: i64) -> String {
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    match (days, hours, mins) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, m) => format!("{m}m"),
+        (0, h, 0) => format!("{h}h"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, 0, _) => format!("{d}d"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
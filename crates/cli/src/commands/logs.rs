@@ -89,7 +89,7 @@ async fn follow_stream(client: &ClotoClient, json_mode: bool) -> Result<()> {
     }
 
     let response = client
-        .sse_stream()
+        .sse_stream(&[])
         .await
         .context("Failed to connect to event stream")?;
 
@@ -112,6 +112,10 @@ async fn follow_stream(client: &ClotoClient, json_mode: bool) -> Result<()> {
                     }
 
                     if let Ok(event) = serde_json::from_str::<serde_json::Value>(data) {
+                        // Streamed response text; the full reply follows as ThoughtResponse
+                        if event["data"]["type"] == "ThoughtResponseChunk" {
+                            continue;
+                        }
                         if json_mode {
                             println!("{}", serde_json::to_string(&event)?);
                         } else {
@@ -190,6 +194,37 @@ fn print_event(event: &serde_json::Value) {
                 format!("{agent}: {preview}"),
             )
         }
+        "AgentMessage" => {
+            let from = data
+                .get("from_agent")
+                .and_then(|a| a.as_str())
+                .unwrap_or("?");
+            let to = data.get("to_agent").and_then(|a| a.as_str()).unwrap_or("?");
+            let content = data.get("content").and_then(|c| c.as_str()).unwrap_or("");
+            let preview = truncate_preview(content, 60);
+            (
+                format!("[{}]", "AgentMessage".cyan()),
+                format!("{from} → {to}: {preview}"),
+            )
+        }
+        "ToolApprovalRequested" => {
+            let tool = data
+                .get("tool_name")
+                .and_then(|t| t.as_str())
+                .unwrap_or("?");
+            let server = data
+                .get("server_id")
+                .and_then(|s| s.as_str())
+                .unwrap_or("?");
+            let id = data
+                .get("request_id")
+                .and_then(|r| r.as_str())
+                .unwrap_or("?");
+            (
+                format!("[{}]", "ToolApprovalRequested".yellow().bold()),
+                format!("{server}/{tool} awaiting approval ({id})"),
+            )
+        }
         "AgentPowerChanged" => {
             let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
             let enabled = data
@@ -206,6 +241,29 @@ fn print_event(event: &serde_json::Value) {
                 format!("{agent} → {state}"),
             )
         }
+        "AgentOnline" => {
+            let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
+            (
+                format!("[{}]", "Heartbeat".magenta()),
+                format!("{agent} → {}", "ONLINE".green()),
+            )
+        }
+        "AgentOffline" => {
+            let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
+            let reason = data.get("reason").and_then(|r| r.as_str()).unwrap_or("");
+            (
+                format!("[{}]", "Heartbeat".magenta()),
+                format!("{agent} → {} ({reason})", "OFFLINE".red()),
+            )
+        }
+        "AgenticLoopAborted" => {
+            let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
+            let reason = data.get("reason").and_then(|r| r.as_str()).unwrap_or("");
+            (
+                format!("[{}]", "LoopAborted".red()),
+                format!("{agent}: {reason}"),
+            )
+        }
         "SystemNotification" => {
             let msg = if data.is_string() {
                 data.as_str().unwrap_or("").to_string()
@@ -0,0 +1,282 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use comfy_table::{presets::NOTHING, ContentArrangement, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::cli::McpCommand;
+use crate::client::ClotoClient;
+use crate::output;
+
+pub async fn run(client: &ClotoClient, cmd: McpCommand, json_mode: bool) -> Result<()> {
+    match cmd {
+        McpCommand::List => list(client, json_mode).await,
+        McpCommand::Add {
+            name,
+            command,
+            code_file,
+            description,
+            args,
+        } => {
+            add(
+                client,
+                &name,
+                command,
+                code_file,
+                description,
+                args,
+                json_mode,
+            )
+            .await
+        }
+        McpCommand::Remove { name, force } => remove(client, &name, force, json_mode).await,
+        McpCommand::Restart { name } => action(client, &name, "restart", json_mode).await,
+        McpCommand::Start { name } => action(client, &name, "start", json_mode).await,
+        McpCommand::Stop { name } => action(client, &name, "stop", json_mode).await,
+        McpCommand::Tools { name } => tools(client, &name, json_mode).await,
+    }
+}
+
+/// Same rules as the kernel applies on `POST /api/mcp/servers`.
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 64 {
+        anyhow::bail!("Server name must be 1-64 characters");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        anyhow::bail!(
+            "Server name must contain only alphanumeric characters, underscores, and hyphens"
+        );
+    }
+    Ok(())
+}
+
+fn status_dot(status: &str) -> String {
+    match status {
+        "Connected" => "●".green().to_string(),
+        "Disconnected" => "○".dimmed().to_string(),
+        _ => "✗".red().to_string(),
+    }
+}
+
+async fn list(client: &ClotoClient, json_mode: bool) -> Result<()> {
+    let sp = if json_mode {
+        None
+    } else {
+        Some(output::spinner("Loading MCP servers..."))
+    };
+    let servers = client.get_mcp_servers().await?;
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&servers)?);
+        return Ok(());
+    }
+
+    output::print_header("MCP Servers");
+
+    if servers.is_empty() {
+        println!("  {}", "No MCP servers configured.".dimmed());
+        println!();
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    for server in &servers {
+        let id = server.get("id").and_then(|v| v.as_str()).unwrap_or("-");
+        let status = server.get("status").and_then(|v| v.as_str()).unwrap_or("-");
+        let source = server.get("source").and_then(|v| v.as_str()).unwrap_or("-");
+        let tool_count = server
+            .get("tools")
+            .and_then(|v| v.as_array())
+            .map_or(0, Vec::len);
+        let detail = server
+            .get("status_message")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        table.add_row(vec![
+            format!("  {}", status_dot(status)),
+            id.bold().to_string(),
+            status.to_string(),
+            source.dimmed().to_string(),
+            format!("{tool_count} tools"),
+            detail.dimmed().to_string(),
+        ]);
+    }
+
+    println!("{table}");
+    println!();
+    Ok(())
+}
+
+async fn add(
+    client: &ClotoClient,
+    name: &str,
+    command: Option<String>,
+    code_file: Option<std::path::PathBuf>,
+    description: Option<String>,
+    args: Vec<String>,
+    json_mode: bool,
+) -> Result<()> {
+    validate_name(name)?;
+
+    let mut body = serde_json::json!({
+        "name": name,
+        "description": description,
+    });
+    if let Some(path) = code_file {
+        let code = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        body["code"] = serde_json::json!(code);
+    } else {
+        body["command"] = serde_json::json!(command);
+        body["args"] = serde_json::json!(args);
+    }
+
+    let sp = if json_mode {
+        None
+    } else {
+        Some(output::spinner(&format!("Starting {name}...")))
+    };
+    let result = client.create_mcp_server(&body).await;
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+    let result = result?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let tools = tool_names(&result);
+    println!(
+        "  {} MCP server {} added ({} tools)",
+        "✓".green().bold(),
+        name.bold(),
+        tools.len()
+    );
+    if !tools.is_empty() {
+        println!("  {}", tools.join(", ").dimmed());
+    }
+    println!();
+    Ok(())
+}
+
+async fn remove(client: &ClotoClient, name: &str, force: bool, json_mode: bool) -> Result<()> {
+    if !force && !json_mode {
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("  Remove MCP server {name}?"))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("  Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let sp = if json_mode {
+        None
+    } else {
+        Some(output::spinner(&format!("Removing {name}...")))
+    };
+    let result = client.delete_mcp_server(name).await;
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+    let result = result?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!(
+            "  {} MCP server {} removed",
+            "✓".green().bold(),
+            name.bold()
+        );
+    }
+    Ok(())
+}
+
+/// `start`, `stop` or `restart`.
+async fn action(client: &ClotoClient, name: &str, action: &str, json_mode: bool) -> Result<()> {
+    let sp = if json_mode {
+        None
+    } else {
+        let verb = match action {
+            "start" => "Starting",
+            "stop" => "Stopping",
+            _ => "Restarting",
+        };
+        Some(output::spinner(&format!("{verb} {name}...")))
+    };
+    let result = client.mcp_server_action(name, action).await;
+    if let Some(sp) = sp {
+        sp.finish_and_clear();
+    }
+    let result = result?;
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let status = result
+        .get("status")
+        .and_then(|s| s.as_str())
+        .unwrap_or(action);
+    println!("  {} {} {}", "✓".green().bold(), name.bold(), status);
+    if result.get("tools").is_some() {
+        println!(
+            "  {}",
+            format!("{} tools", tool_names(&result).len()).dimmed()
+        );
+    }
+    Ok(())
+}
+
+async fn tools(client: &ClotoClient, name: &str, json_mode: bool) -> Result<()> {
+    let servers = client.get_mcp_servers().await?;
+    let server = servers
+        .iter()
+        .find(|s| s.get("id").and_then(|v| v.as_str()) == Some(name))
+        .ok_or_else(|| anyhow::anyhow!("MCP server '{name}' not found"))?;
+    let tools = tool_names(server);
+
+    if json_mode {
+        println!("{}", serde_json::to_string_pretty(&tools)?);
+        return Ok(());
+    }
+
+    output::print_header(&format!("Tools: {name}"));
+    if tools.is_empty() {
+        println!("  {}", "No tools (server not running?)".dimmed());
+    }
+    for tool in &tools {
+        println!("  {tool}");
+    }
+    println!();
+    Ok(())
+}
+
+/// The `tools` array of a server or lifecycle response.
+fn tool_names(value: &serde_json::Value) -> Vec<String> {
+    value
+        .get("tools")
+        .and_then(|t| t.as_array())
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|t| t.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
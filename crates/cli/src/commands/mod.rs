@@ -1,7 +1,9 @@
 pub mod agents;
 pub mod chat;
 pub mod config_cmd;
+pub mod cron;
 pub mod logs;
+pub mod mcp;
 pub mod permissions;
 pub mod plugins;
 pub mod status;
@@ -26,6 +28,8 @@ pub async fn dispatch(cli: Cli) -> Result<()> {
         Commands::Logs { follow, limit } => logs::run(&client, follow, limit, cli.json).await,
         Commands::Config(cmd) => config_cmd::run(cmd, &config),
         Commands::Permissions(cmd) => permissions::run(&client, cmd, cli.json).await,
+        Commands::Mcp(cmd) => mcp::run(&client, cmd, cli.json).await,
+        Commands::Cron(cmd) => cron::run(&client, cmd, cli.json).await,
         Commands::Tui => crate::tui::run().await,
     }
 }
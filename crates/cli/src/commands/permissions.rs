@@ -16,6 +16,9 @@ const VALID_PERMISSIONS: &[&str] = &[
     "MemoryRead",
     "MemoryWrite",
     "AdminAccess",
+    "ClipboardAccess",
+    "CameraRead",
+    "ChannelRelay",
 ];
 
 pub async fn run(client: &ClotoClient, cmd: PermissionsCommand, json: bool) -> Result<()> {
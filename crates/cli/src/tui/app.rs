@@ -5,23 +5,88 @@ use cloto_shared::{AgentMetadata, PluginManifest};
 pub enum Pane {
     Agents,
     Events,
+    Chat,
 }
 
 impl Pane {
     pub fn next(self) -> Self {
         match self {
             Pane::Agents => Pane::Events,
-            Pane::Events => Pane::Agents,
+            Pane::Events => Pane::Chat,
+            Pane::Chat => Pane::Agents,
         }
     }
 }
 
+/// Maximum lines kept in the chat scrollback.
+const CHAT_SCROLLBACK: usize = 500;
+
+/// Author of a chat scrollback line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    User,
+    Agent,
+    Error,
+}
+
+pub struct ChatLine {
+    pub role: ChatRole,
+    pub agent_id: String,
+    pub content: String,
+}
+
+/// Message sent from the chat pane whose reply has not arrived yet.
+pub struct PendingReply {
+    pub message_id: String,
+    pub agent_id: String,
+    /// Reply text streamed so far (`ThoughtResponseChunk`).
+    pub partial: String,
+}
+
+/// State of the chat pane.
+#[derive(Default)]
+pub struct ChatState {
+    pub input: String,
+    pub lines: Vec<ChatLine>,
+    /// Messages scrolled up from the bottom of the scrollback.
+    pub scroll: usize,
+    /// Index into `App::agents` of the agent messages are sent to.
+    pub agent_index: usize,
+    pub pending: Option<PendingReply>,
+    /// Submitted message, taken by the main loop and posted to `/api/chat`.
+    pub outgoing: Option<cloto_shared::ClotoMessage>,
+}
+
+impl ChatState {
+    fn push(&mut self, role: ChatRole, agent_id: &str, content: String) {
+        self.lines.push(ChatLine {
+            role,
+            agent_id: agent_id.to_string(),
+            content,
+        });
+        if self.lines.len() > CHAT_SCROLLBACK {
+            self.lines.drain(..self.lines.len() - CHAT_SCROLLBACK);
+        }
+        self.scroll = 0;
+    }
+}
+
+/// `(type, data)` of an SSE event (`{"data": {"type": .., "data": ..}}`).
+fn event_payload(event: &serde_json::Value) -> Option<(&str, &serde_json::Value)> {
+    let data = event.get("data")?;
+    Some((data.get("type")?.as_str()?, data.get("data")?))
+}
+
 /// Actions that can be dispatched into the App state.
 pub enum AppAction {
     AgentsUpdated(Vec<AgentMetadata>),
     PluginsUpdated(Vec<PluginManifest>),
     MetricsUpdated(serde_json::Value),
     NewEvent(serde_json::Value),
+    /// `ThoughtResponseChunk` SSE event (chat pane only, not logged).
+    ChatChunk(serde_json::Value),
+    /// Posting a chat message failed.
+    ChatFailed(String),
     #[allow(dead_code)]
     Tick,
 }
@@ -35,6 +100,7 @@ pub struct App {
     pub active_pane: Pane,
     pub agent_scroll: usize,
     pub event_scroll: usize,
+    pub chat: ChatState,
     pub show_help: bool,
     pub should_quit: bool,
     pub endpoint: String,
@@ -52,6 +118,7 @@ impl App {
             active_pane: Pane::Agents,
             agent_scroll: 0,
             event_scroll: 0,
+            chat: ChatState::default(),
             show_help: false,
             should_quit: false,
             endpoint,
@@ -69,6 +136,7 @@ impl App {
                 } else {
                     self.agent_scroll = self.agent_scroll.min(agents.len() - 1);
                 }
+                self.chat.agent_index = self.chat.agent_index.min(agents.len().saturating_sub(1));
                 self.agents = agents;
                 self.connected = true;
                 self.last_refresh = std::time::Instant::now();
@@ -80,6 +148,7 @@ impl App {
                 self.metrics = Some(metrics);
             }
             AppAction::NewEvent(event) => {
+                self.on_chat_response(&event);
                 self.events.push(event);
                 // Keep a rolling window
                 if self.events.len() > 200 {
@@ -92,10 +161,94 @@ impl App {
                     self.event_scroll = self.event_scroll.min(self.events.len() - 1);
                 }
             }
+            AppAction::ChatChunk(event) => self.on_chat_chunk(&event),
+            AppAction::ChatFailed(error) => {
+                if let Some(pending) = self.chat.pending.take() {
+                    self.chat.push(ChatRole::Error, &pending.agent_id, error);
+                }
+            }
             AppAction::Tick => {}
         }
     }
 
+    /// Agent the chat pane sends to.
+    pub fn chat_target(&self) -> Option<&AgentMetadata> {
+        self.agents.get(self.chat.agent_index)
+    }
+
+    pub fn cycle_chat_target(&mut self, forward: bool) {
+        let len = self.agents.len();
+        if len == 0 {
+            return;
+        }
+        self.chat.agent_index = if forward {
+            (self.chat.agent_index + 1) % len
+        } else {
+            (self.chat.agent_index + len - 1) % len
+        };
+    }
+
+    /// Queue the input line for sending to the selected agent. One message
+    /// at a time: input is kept while a reply is pending.
+    pub fn submit_chat(&mut self) {
+        let content = self.chat.input.trim().to_string();
+        if content.is_empty() || self.chat.pending.is_some() {
+            return;
+        }
+        let Some(agent_id) = self.chat_target().map(|a| a.id.clone()) else {
+            self.chat
+                .push(ChatRole::Error, "", "No agent to send to".to_string());
+            return;
+        };
+        let msg = cloto_shared::ClotoMessage {
+            id: cloto_shared::ClotoId::new().to_string(),
+            source: cloto_shared::MessageSource::User {
+                id: "cli-user".to_string(),
+                name: "CLI".to_string(),
+            },
+            target_agent: Some(agent_id.clone()),
+            content: content.clone(),
+            timestamp: chrono::Utc::now(),
+            metadata: std::collections::HashMap::new(),
+            attachments: Vec::new(),
+        };
+        self.chat.push(ChatRole::User, &agent_id, content);
+        self.chat.pending = Some(PendingReply {
+            message_id: msg.id.clone(),
+            agent_id,
+            partial: String::new(),
+        });
+        self.chat.outgoing = Some(msg);
+        self.chat.input.clear();
+    }
+
+    fn on_chat_chunk(&mut self, event: &serde_json::Value) {
+        let Some(("ThoughtResponseChunk", data)) = event_payload(event) else {
+            return;
+        };
+        if let Some(pending) = self.chat.pending.as_mut() {
+            if data["source_message_id"] == pending.message_id.as_str() {
+                pending
+                    .partial
+                    .push_str(data["delta"].as_str().unwrap_or_default());
+            }
+        }
+    }
+
+    fn on_chat_response(&mut self, event: &serde_json::Value) {
+        let Some(("ThoughtResponse", data)) = event_payload(event) else {
+            return;
+        };
+        let answers_pending = self.chat.pending.as_ref().is_some_and(|p| {
+            data["source_message_id"] == p.message_id.as_str()
+                && data["agent_id"] == p.agent_id.as_str()
+        });
+        if let (true, Some(pending)) = (answers_pending, self.chat.pending.take()) {
+            let content = data["content"].as_str().unwrap_or_default().to_string();
+            self.chat.push(ChatRole::Agent, &pending.agent_id, content);
+        }
+    }
+
     pub fn scroll_up(&mut self) {
         match self.active_pane {
             Pane::Agents => {
@@ -104,6 +257,10 @@ impl App {
             Pane::Events => {
                 self.event_scroll = self.event_scroll.saturating_sub(1);
             }
+            Pane::Chat => {
+                self.chat.scroll =
+                    (self.chat.scroll + 1).min(self.chat.lines.len().saturating_sub(1));
+            }
         }
     }
 
@@ -119,6 +276,9 @@ impl App {
                     self.event_scroll = (self.event_scroll + 1).min(self.events.len() - 1);
                 }
             }
+            Pane::Chat => {
+                self.chat.scroll = self.chat.scroll.saturating_sub(1);
+            }
         }
     }
 
@@ -1,7 +1,7 @@
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use std::time::Duration;
 
-use super::app::App;
+use super::app::{App, Pane};
 
 /// Poll for keyboard events with a timeout.
 /// Returns true if the app should continue running.
@@ -20,6 +20,12 @@ pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
                 return Ok(true);
             }
 
+            // The chat pane takes typed characters as input
+            if app.active_pane == Pane::Chat {
+                handle_chat_key(app, key.code);
+                return Ok(true);
+            }
+
             match key.code {
                 KeyCode::Char('q') => {
                     app.should_quit = true;
@@ -49,3 +55,20 @@ pub fn handle_events(app: &mut App) -> anyhow::Result<bool> {
     }
     Ok(true)
 }
+
+fn handle_chat_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Tab | KeyCode::BackTab => app.active_pane = app.active_pane.next(),
+        KeyCode::Esc => app.active_pane = Pane::Agents,
+        KeyCode::Enter => app.submit_chat(),
+        KeyCode::Backspace => {
+            app.chat.input.pop();
+        }
+        KeyCode::Left => app.cycle_chat_target(false),
+        KeyCode::Right => app.cycle_chat_target(true),
+        KeyCode::Up | KeyCode::PageUp => app.scroll_up(),
+        KeyCode::Down | KeyCode::PageDown => app.scroll_down(),
+        KeyCode::Char(c) => app.chat.input.push(c),
+        _ => {}
+    }
+}
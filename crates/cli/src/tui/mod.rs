@@ -75,7 +75,7 @@ pub async fn run() -> Result<()> {
     let sse_tx = tx.clone();
     tokio::spawn(async move {
         loop {
-            if let Ok(response) = sse_client.sse_stream().await {
+            if let Ok(response) = sse_client.sse_stream(&[]).await {
                 let mut stream = response.bytes_stream();
                 let mut buffer = String::new();
 
@@ -94,7 +94,14 @@ pub async fn run() -> Result<()> {
                                     continue;
                                 }
                                 if let Ok(event) = serde_json::from_str::<serde_json::Value>(data) {
-                                    let _ = sse_tx.send(AppAction::NewEvent(event)).await;
+                                    // Streamed response text is not a loggable event
+                                    let action = if event["data"]["type"] == "ThoughtResponseChunk"
+                                    {
+                                        AppAction::ChatChunk(event)
+                                    } else {
+                                        AppAction::NewEvent(event)
+                                    };
+                                    let _ = sse_tx.send(action).await;
                                 }
                             }
                         }
@@ -119,7 +126,8 @@ pub async fn run() -> Result<()> {
     let mut app = App::new(endpoint);
 
     // bug-024: Main loop with guaranteed cleanup on error
-    let result = run_main_loop(&mut terminal, &mut app, &mut rx).await;
+    let chat_client = std::sync::Arc::new(client);
+    let result = run_main_loop(&mut terminal, &mut app, &mut rx, &tx, &chat_client).await;
 
     // Restore terminal — always runs regardless of error
     restore_terminal(&mut terminal);
@@ -132,6 +140,8 @@ async fn run_main_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
     rx: &mut mpsc::Receiver<AppAction>,
+    tx: &mpsc::Sender<AppAction>,
+    client: &std::sync::Arc<ClotoClient>,
 ) -> Result<()> {
     loop {
         terminal.draw(|f| ui::draw(f, app))?;
@@ -144,6 +154,16 @@ async fn run_main_loop(
             break;
         }
 
+        // Post a message submitted in the chat pane; the reply arrives over SSE
+        if let Some(msg) = app.chat.outgoing.take() {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                if let Err(e) = client.send_chat(&msg).await {
+                    let _ = tx.send(AppAction::ChatFailed(format!("{e:#}"))).await;
+                }
+            });
+        }
+
         if app.should_quit {
             break;
         }
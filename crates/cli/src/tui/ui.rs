@@ -7,14 +7,15 @@ use super::widgets;
 pub fn draw(f: &mut Frame, app: &App) {
     let area = f.area();
 
-    // Main layout: Header | Content | Metrics | Footer
+    // Main layout: Header | Content | Chat | Metrics | Footer
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Min(8),    // Content (agents + events)
-            Constraint::Length(3), // Metrics
-            Constraint::Length(1), // Footer
+            Constraint::Length(3),      // Header
+            Constraint::Min(8),         // Content (agents + events)
+            Constraint::Percentage(40), // Chat
+            Constraint::Length(3),      // Metrics
+            Constraint::Length(1),      // Footer
         ])
         .split(area);
 
@@ -30,11 +31,13 @@ pub fn draw(f: &mut Frame, app: &App) {
     widgets::agents::render(f, content_chunks[0], app, app.active_pane == Pane::Agents);
     widgets::events::render(f, content_chunks[1], app, app.active_pane == Pane::Events);
 
+    widgets::chat::render(f, main_chunks[2], app, app.active_pane == Pane::Chat);
+
     // Metrics
-    widgets::metrics::render(f, main_chunks[2], app);
+    widgets::metrics::render(f, main_chunks[3], app);
 
     // Footer
-    render_footer(f, main_chunks[3], app);
+    render_footer(f, main_chunks[4], app);
 
     // Help overlay
     if app.show_help {
@@ -77,19 +80,34 @@ fn render_header(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(paragraph, area);
 }
 
-fn render_footer(f: &mut Frame, area: Rect, _app: &App) {
-    let footer = Line::from(vec![
-        Span::styled("  [Tab]", Style::default().fg(Color::Cyan)),
-        Span::styled(" Pane  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("[↑↓]", Style::default().fg(Color::Cyan)),
-        Span::styled(" Navigate  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("[r]", Style::default().fg(Color::Cyan)),
-        Span::styled(" Refresh  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("[q]", Style::default().fg(Color::Cyan)),
-        Span::styled(" Quit  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("[?]", Style::default().fg(Color::Cyan)),
-        Span::styled(" Help", Style::default().fg(Color::DarkGray)),
-    ]);
+fn render_footer(f: &mut Frame, area: Rect, app: &App) {
+    let footer = if app.active_pane == Pane::Chat {
+        Line::from(vec![
+            Span::styled("  [Tab]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Pane  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[Enter]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Send  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[←→]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Agent  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[↑↓]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Scroll  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[Esc]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Leave chat", Style::default().fg(Color::DarkGray)),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("  [Tab]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Pane  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[↑↓]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Navigate  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[r]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Refresh  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[q]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Quit  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[?]", Style::default().fg(Color::Cyan)),
+            Span::styled(" Help", Style::default().fg(Color::DarkGray)),
+        ])
+    };
 
     let paragraph = Paragraph::new(footer);
     f.render_widget(paragraph, area);
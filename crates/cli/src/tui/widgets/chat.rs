@@ -0,0 +1,103 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+
+use crate::tui::app::{App, ChatRole};
+
+pub fn render(f: &mut Frame, area: Rect, app: &App, is_active: bool) {
+    let border_style = if is_active {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    let target = app
+        .chat_target()
+        .map_or_else(|| "no agent".to_string(), |a| a.id.clone());
+    let block = Block::default()
+        .title(format!(" Chat ◂ {target} ▸ "))
+        .borders(Borders::ALL)
+        .border_style(border_style);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    render_scrollback(f, chunks[0], app);
+
+    let prompt = if app.chat.pending.is_some() {
+        Span::styled("  … ", Style::default().fg(Color::DarkGray))
+    } else {
+        Span::styled("  > ", Style::default().fg(Color::Cyan))
+    };
+    let input = Paragraph::new(Line::from(vec![prompt, Span::raw(app.chat.input.as_str())]));
+    f.render_widget(input, chunks[1]);
+
+    if is_active && !app.show_help {
+        let width = u16::try_from(app.chat.input.chars().count()).unwrap_or(u16::MAX);
+        let x = chunks[1]
+            .x
+            .saturating_add(4)
+            .saturating_add(width)
+            .min(chunks[1].right().saturating_sub(1));
+        f.set_cursor_position((x, chunks[1].y));
+    }
+}
+
+fn render_scrollback(f: &mut Frame, area: Rect, app: &App) {
+    if app.chat.lines.is_empty() && app.chat.pending.is_none() {
+        let hint = Paragraph::new(Span::styled(
+            "  Type a message and press Enter. ←/→ selects the agent.",
+            Style::default().fg(Color::DarkGray),
+        ));
+        f.render_widget(hint, area);
+        return;
+    }
+
+    let shown = app.chat.lines.len() - app.chat.scroll.min(app.chat.lines.len());
+    let mut lines: Vec<Line> = app.chat.lines[..shown]
+        .iter()
+        .flat_map(|line| match line.role {
+            ChatRole::User => message_lines("You", Color::White, &line.content),
+            ChatRole::Agent => message_lines(&line.agent_id, Color::Cyan, &line.content),
+            ChatRole::Error => vec![Line::from(Span::styled(
+                format!("  ! {}", line.content),
+                Style::default().fg(Color::Red),
+            ))],
+        })
+        .collect();
+    if let (0, Some(pending)) = (app.chat.scroll, &app.chat.pending) {
+        let text = if pending.partial.is_empty() {
+            "…"
+        } else {
+            pending.partial.as_str()
+        };
+        lines.extend(message_lines(&pending.agent_id, Color::Cyan, text));
+    }
+
+    // Keep the newest message at the bottom of the pane
+    let width = usize::from(area.width.max(1));
+    let height: usize = lines.iter().map(|l| l.width().div_ceil(width).max(1)).sum();
+    let offset = u16::try_from(height.saturating_sub(usize::from(area.height))).unwrap_or(0);
+
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((offset, 0));
+    f.render_widget(paragraph, area);
+}
+
+/// `author: content`, with further lines of a multi-line message indented.
+fn message_lines<'a>(author: &str, color: Color, content: &'a str) -> Vec<Line<'a>> {
+    let mut text = content.lines();
+    let mut lines = vec![Line::from(vec![
+        Span::styled(
+            format!("  {author}: "),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(text.next().unwrap_or_default()),
+    ])];
+    lines.extend(text.map(|l| Line::from(vec![Span::raw("    "), Span::raw(l)])));
+    lines
+}
@@ -71,6 +71,21 @@ pub fn render(f: &mut Frame, area: Rect, app: &App, is_active: bool) {
                     let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
                     (Color::Green, format!("{agent} responded"))
                 }
+                "AgentMessage" => {
+                    let from = data
+                        .get("from_agent")
+                        .and_then(|a| a.as_str())
+                        .unwrap_or("?");
+                    let to = data.get("to_agent").and_then(|a| a.as_str()).unwrap_or("?");
+                    (Color::Cyan, format!("{from} → {to}"))
+                }
+                "ToolApprovalRequested" => {
+                    let tool = data
+                        .get("tool_name")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("?");
+                    (Color::Yellow, format!("{tool} awaiting approval"))
+                }
                 "AgentPowerChanged" => {
                     let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
                     let on = data
@@ -82,6 +97,18 @@ pub fn render(f: &mut Frame, area: Rect, app: &App, is_active: bool) {
                         format!("{agent} {}", if on { "ON" } else { "OFF" }),
                     )
                 }
+                "AgentOnline" => {
+                    let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
+                    (Color::Green, format!("{agent} back online"))
+                }
+                "AgentOffline" => {
+                    let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
+                    (Color::Red, format!("{agent} offline"))
+                }
+                "AgenticLoopAborted" => {
+                    let agent = data.get("agent_id").and_then(|a| a.as_str()).unwrap_or("?");
+                    (Color::Red, format!("{agent} loop aborted"))
+                }
                 "SystemNotification" => (Color::Yellow, "System notification".to_string()),
                 "ConfigUpdated" => {
                     let plugin = data
@@ -6,7 +6,7 @@ pub fn render(f: &mut Frame) {
 
     // Center the help overlay
     let width = 44.min(area.width.saturating_sub(4));
-    let height = 14.min(area.height.saturating_sub(4));
+    let height = 17.min(area.height.saturating_sub(4));
     let x = (area.width.saturating_sub(width)) / 2;
     let y = (area.height.saturating_sub(height)) / 2;
     let popup = Rect::new(x, y, width, height);
@@ -15,60 +15,15 @@ pub fn render(f: &mut Frame) {
 
     let text = vec![
         Line::from(""),
-        Line::from(vec![
-            Span::styled(
-                "  Tab       ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("Switch pane"),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                "  ↑/k ↓/j   ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("Navigate list"),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                "  r         ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("Force refresh"),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                "  q         ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("Quit"),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                "  Ctrl+C    ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("Force quit"),
-        ]),
-        Line::from(vec![
-            Span::styled(
-                "  ?         ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw("Toggle help"),
-        ]),
+        key_line("  Tab       ", "Switch pane"),
+        key_line("  ↑/k ↓/j   ", "Navigate list"),
+        key_line("  r         ", "Force refresh"),
+        key_line("  Enter     ", "Send chat message"),
+        key_line("  ←/→       ", "Chat: select agent"),
+        key_line("  Esc       ", "Leave chat input"),
+        key_line("  q         ", "Quit"),
+        key_line("  Ctrl+C    ", "Force quit"),
+        key_line("  ?         ", "Toggle help"),
         Line::from(""),
         Line::from(Span::styled(
             "  Press any key to close",
@@ -84,3 +39,15 @@ pub fn render(f: &mut Frame) {
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, popup);
 }
+
+fn key_line(key: &'static str, description: &'static str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            key,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(description),
+    ])
+}
@@ -1,4 +1,5 @@
 pub mod agents;
+pub mod chat;
 pub mod events;
 pub mod help;
 pub mod metrics;
@@ -4,10 +4,10 @@
 // - cloto_core/src/db.rs:32-45 (SqliteDataStore::get_json)
 // - cloto_core/src/managers.rs:274-291 (PluginManager::fetch_plugin_configs)
 
+use cloto_core::db::DbPool;
 use cloto_core::db::SqliteDataStore;
 use cloto_shared::PluginDataStore;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use sqlx::SqlitePool;
 
 mod helpers;
 
@@ -24,7 +24,7 @@ fn json_serialization_benchmark(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::new("set_json", size), &json_data, |b, data| {
             b.to_async(&runtime).iter(|| async {
-                let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+                let pool = DbPool::connect("sqlite::memory:").await.unwrap();
                 cloto_core::db::init_db(&pool, "sqlite::memory:")
                     .await
                     .unwrap();
@@ -48,7 +48,7 @@ fn get_json_benchmark(c: &mut Criterion) {
 
     c.bench_function("db_get_json", |b| {
         b.to_async(&runtime).iter(|| async {
-            let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+            let pool = DbPool::connect("sqlite::memory:").await.unwrap();
             cloto_core::db::init_db(&pool, "sqlite::memory:")
                 .await
                 .unwrap();
@@ -71,7 +71,7 @@ fn get_all_json_benchmark(c: &mut Criterion) {
 
     c.bench_function("db_get_all_json", |b| {
         b.to_async(&runtime).iter(|| async {
-            let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+            let pool = DbPool::connect("sqlite::memory:").await.unwrap();
             cloto_core::db::init_db(&pool, "sqlite::memory:")
                 .await
                 .unwrap();
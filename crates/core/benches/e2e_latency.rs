@@ -0,0 +1,586 @@
+// End-to-End Latency Load Generator
+// Critical path: events.rs (EventProcessor::process_loop) → registry.rs (dispatch_event)
+//                → handlers/system.rs (SystemHandler agentic loop) → broadcast
+// Measures: per-stage P50/P95/P99 of chat → thought → tool → response cycles
+//
+// Not a criterion bench: it runs a fixed number of cycles against an in-process
+// kernel (real event loop, registry and SystemHandler; mock reasoning engine and
+// tool) and prints a latency table. The workload is deterministic, so two runs
+// on the same machine are directly comparable.
+//
+//   cargo bench -p cloto_core --bench e2e_latency -- --iterations 5000 --concurrency 16
+//
+// Options: --iterations N (2000), --warmup N (200), --concurrency N (8),
+//          --think-ms N (0), --tool-ms N (0), --json PATH (write results as JSON)
+
+use async_trait::async_trait;
+use cloto_core::events::{EventProcessor, SerializedEvent};
+use cloto_core::handlers::system::SystemHandler;
+use cloto_core::managers::{AgentManager, PluginManager, PluginRegistry, SystemMetrics};
+use cloto_shared::{
+    AgentMetadata, ClotoEvent, ClotoEventData, ClotoMessage, MessageSource, Plugin, PluginCast,
+    PluginCategory, PluginManifest, ReasoningEngine, ServiceType, ThinkResult, Tool, ToolCall,
+};
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
+
+const AGENT_ID: &str = "agent.bench";
+const ENGINE_ID: &str = "bench.engine";
+const TOOL_ID: &str = "bench.tool";
+const TOOL_NAME: &str = "bench_echo";
+
+struct Options {
+    iterations: usize,
+    warmup: usize,
+    concurrency: usize,
+    think_latency: Duration,
+    tool_latency: Duration,
+    json_path: Option<String>,
+}
+
+impl Options {
+    fn from_args() -> Self {
+        let mut options = Self {
+            iterations: 2000,
+            warmup: 200,
+            concurrency: 8,
+            think_latency: Duration::ZERO,
+            tool_latency: Duration::ZERO,
+            json_path: None,
+        };
+        // cargo passes `--bench`; anything unrecognised is ignored
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().unwrap_or_default();
+            match arg.as_str() {
+                "--iterations" => options.iterations = value().parse().unwrap_or(2000),
+                "--warmup" => options.warmup = value().parse().unwrap_or(200),
+                "--concurrency" => options.concurrency = value().parse().unwrap_or(8).max(1),
+                "--think-ms" => {
+                    options.think_latency = Duration::from_millis(value().parse().unwrap_or(0));
+                }
+                "--tool-ms" => {
+                    options.tool_latency = Duration::from_millis(value().parse().unwrap_or(0));
+                }
+                "--json" => options.json_path = Some(value()),
+                _ => {}
+            }
+        }
+        options
+    }
+}
+
+/// Timestamps of one cycle, keyed by the user message ID.
+#[derive(Default, Clone, Copy)]
+struct Marks {
+    submitted: Option<Instant>,
+    first_think: Option<Instant>,
+    tool: Option<Instant>,
+    second_think: Option<Instant>,
+    responded: Option<Instant>,
+}
+
+#[derive(Default)]
+struct Recorder {
+    marks: Mutex<HashMap<String, Marks>>,
+}
+
+impl Recorder {
+    fn mark(&self, message_id: &str, f: impl FnOnce(&mut Marks)) {
+        let mut marks = self.marks.lock().unwrap();
+        f(marks.entry(message_id.to_string()).or_default());
+    }
+
+    fn take(&self, message_id: &str) -> Marks {
+        self.marks
+            .lock()
+            .unwrap()
+            .remove(message_id)
+            .unwrap_or_default()
+    }
+}
+
+fn bench_manifest(id: &str, category: PluginCategory, service_type: ServiceType) -> PluginManifest {
+    PluginManifest {
+        id: id.to_string(),
+        name: id.to_string(),
+        description: "Mock plugin for the e2e latency bench".to_string(),
+        version: "1.0.0".to_string(),
+        category,
+        service_type,
+        tags: vec![],
+        is_active: true,
+        is_configured: true,
+        required_config_keys: vec![],
+        config_schema: vec![],
+        action_icon: None,
+        action_target: None,
+        icon_data: None,
+        magic_seal: 0,
+        sdk_version: "0.1.0".to_string(),
+        required_permissions: vec![],
+        provided_capabilities: vec![],
+        provided_tools: vec![],
+        subscribes: vec![],
+    }
+}
+
+/// Reasoning engine that calls the bench tool once, then answers.
+struct MockEngine {
+    recorder: Arc<Recorder>,
+    latency: Duration,
+}
+
+impl PluginCast for MockEngine {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_reasoning(&self) -> Option<&dyn ReasoningEngine> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl Plugin for MockEngine {
+    fn manifest(&self) -> PluginManifest {
+        bench_manifest(ENGINE_ID, PluginCategory::Agent, ServiceType::Reasoning)
+    }
+
+    async fn on_event(&self, _event: &ClotoEvent) -> anyhow::Result<Option<ClotoEventData>> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl ReasoningEngine for MockEngine {
+    fn name(&self) -> &str {
+        ENGINE_ID
+    }
+
+    async fn think(
+        &self,
+        _agent: &AgentMetadata,
+        message: &ClotoMessage,
+        _context: Vec<ClotoMessage>,
+    ) -> anyhow::Result<String> {
+        Ok(format!("echo: {}", message.content))
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    async fn think_with_tools(
+        &self,
+        _agent: &AgentMetadata,
+        message: &ClotoMessage,
+        _context: Vec<ClotoMessage>,
+        _tools: &[serde_json::Value],
+        tool_history: &[serde_json::Value],
+    ) -> anyhow::Result<ThinkResult> {
+        let now = Instant::now();
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        if tool_history.is_empty() {
+            self.recorder
+                .mark(&message.id, |m| m.first_think = Some(now));
+            return Ok(ThinkResult::ToolCalls {
+                assistant_content: None,
+                calls: vec![ToolCall {
+                    id: format!("call-{}", message.id),
+                    name: TOOL_NAME.to_string(),
+                    arguments: serde_json::json!({ "message_id": message.id }),
+                }],
+            });
+        }
+        self.recorder
+            .mark(&message.id, |m| m.second_think = Some(now));
+        Ok(ThinkResult::Final(format!("done: {}", message.content)))
+    }
+}
+
+/// Tool that records when it ran and echoes its arguments.
+struct MockTool {
+    recorder: Arc<Recorder>,
+    latency: Duration,
+}
+
+impl PluginCast for MockTool {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_tool(&self) -> Option<&dyn Tool> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl Plugin for MockTool {
+    fn manifest(&self) -> PluginManifest {
+        bench_manifest(TOOL_ID, PluginCategory::Tool, ServiceType::Skill)
+    }
+
+    async fn on_event(&self, _event: &ClotoEvent) -> anyhow::Result<Option<ClotoEventData>> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl Tool for MockTool {
+    fn name(&self) -> &str {
+        TOOL_NAME
+    }
+
+    fn description(&self) -> &'static str {
+        "Echo the arguments back"
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        if let Some(id) = args.get("message_id").and_then(|v| v.as_str()) {
+            self.recorder.mark(id, |m| m.tool = Some(Instant::now()));
+        }
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        Ok(args)
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": { "message_id": { "type": "string" } },
+            "required": ["message_id"]
+        })
+    }
+}
+
+type Waiters = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+
+struct Kernel {
+    event_tx: mpsc::Sender<cloto_core::EnvelopedEvent>,
+    recorder: Arc<Recorder>,
+    waiters: Waiters,
+    db_path: std::path::PathBuf,
+}
+
+/// Wire the kernel the way `run_kernel` does, minus HTTP and MCP.
+async fn start_kernel(options: &Options) -> Kernel {
+    // A file database, so the pool behaves like production (sqlx's in-memory
+    // database runs in shared-cache mode, which locks whole tables)
+    let db_path = std::env::temp_dir().join(format!("cloto-e2e-bench-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&db_path);
+    let database_url = format!("sqlite://{}", db_path.display());
+    let pool = cloto_core::db::connect(&database_url, 10, Duration::from_secs(30))
+        .await
+        .unwrap();
+    cloto_core::db::init_db(&pool, &database_url).await.unwrap();
+    sqlx::query(
+        "INSERT INTO agents (id, name, description, status, default_engine_id, \
+         required_capabilities, metadata, enabled) \
+         VALUES ($1, 'Bench Agent', 'Load generator target', 'online', $2, '[\"Reasoning\"]', '{}', TRUE)",
+    )
+    .bind(AGENT_ID)
+    .bind(ENGINE_ID)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (event_tx, event_rx) = mpsc::channel(1000);
+    let (tx, _rx) = broadcast::channel::<SerializedEvent>(4096);
+    let registry = Arc::new(PluginRegistry::new(30, 10));
+    let agent_manager = AgentManager::new(pool.clone());
+    let plugin_manager = Arc::new(PluginManager::new(pool.clone(), vec![], 30, 10).unwrap());
+    let metrics = Arc::new(SystemMetrics::new());
+    let recorder = Arc::new(Recorder::default());
+
+    let system_handler = Arc::new(SystemHandler::new(
+        registry.clone(),
+        agent_manager.clone(),
+        AGENT_ID.to_string(),
+        event_tx.clone(),
+        10,
+        metrics.clone(),
+        vec![],
+        16,
+        30,
+    ));
+    {
+        let mut plugins = registry.plugins.write().await;
+        plugins.insert("kernel.system".to_string(), system_handler);
+        plugins.insert(
+            ENGINE_ID.to_string(),
+            Arc::new(MockEngine {
+                recorder: recorder.clone(),
+                latency: options.think_latency,
+            }),
+        );
+        plugins.insert(
+            TOOL_ID.to_string(),
+            Arc::new(MockTool {
+                recorder: recorder.clone(),
+                latency: options.tool_latency,
+            }),
+        );
+    }
+
+    // Responses are observed where the dashboard sees them: on the broadcast
+    let waiters: Waiters = Arc::default();
+    tokio::spawn(collect_responses(
+        tx.subscribe(),
+        recorder.clone(),
+        waiters.clone(),
+    ));
+
+    let processor = Arc::new(EventProcessor::new(
+        registry,
+        plugin_manager,
+        agent_manager,
+        tx,
+        Arc::new(RwLock::new(VecDeque::new())),
+        metrics,
+        1000,
+        24,
+        None,
+    ));
+    let loop_tx = event_tx.clone();
+    tokio::spawn(async move { processor.process_loop(event_rx, loop_tx).await });
+
+    Kernel {
+        event_tx,
+        recorder,
+        waiters,
+        db_path,
+    }
+}
+
+/// Mark each ThoughtResponse and wake the client waiting for it.
+async fn collect_responses(
+    mut rx: broadcast::Receiver<SerializedEvent>,
+    recorder: Arc<Recorder>,
+    waiters: Waiters,
+) {
+    loop {
+        match rx.recv().await {
+            Ok(event) => {
+                if let ClotoEventData::ThoughtResponse {
+                    source_message_id, ..
+                } = &event.data
+                {
+                    recorder.mark(source_message_id, |m| m.responded = Some(Instant::now()));
+                    let waiter = waiters.lock().unwrap().remove(source_message_id);
+                    if let Some(waiter) = waiter {
+                        let _ = waiter.send(());
+                    }
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                eprintln!("warning: response collector lagged by {} events", n);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Submit one user message and wait for its ThoughtResponse.
+async fn run_cycle(kernel: &Kernel, content: String) -> Marks {
+    let msg = ClotoMessage::new(
+        MessageSource::User {
+            id: "bench_user".to_string(),
+            name: "Benchmark User".to_string(),
+        },
+        content,
+    );
+    let id = msg.id.clone();
+    let (done_tx, done_rx) = oneshot::channel();
+    kernel.waiters.lock().unwrap().insert(id.clone(), done_tx);
+
+    kernel
+        .recorder
+        .mark(&id, |m| m.submitted = Some(Instant::now()));
+    kernel
+        .event_tx
+        .send(cloto_core::EnvelopedEvent {
+            event: Arc::new(ClotoEvent::new(ClotoEventData::MessageReceived(msg))),
+            issuer: None,
+            correlation_id: None,
+            depth: 0,
+        })
+        .await
+        .unwrap();
+
+    if tokio::time::timeout(Duration::from_secs(30), done_rx)
+        .await
+        .is_err()
+    {
+        eprintln!("warning: cycle {} timed out", id);
+    }
+    kernel.recorder.take(&id)
+}
+
+/// Run `total` cycles across `concurrency` clients, each sending sequentially.
+async fn run_load(kernel: &Arc<Kernel>, total: usize, concurrency: usize) -> Vec<Marks> {
+    let mut clients = Vec::with_capacity(concurrency);
+    for client in 0..concurrency {
+        let kernel = kernel.clone();
+        let share = total / concurrency + usize::from(client < total % concurrency);
+        clients.push(tokio::spawn(async move {
+            let mut results = Vec::with_capacity(share);
+            for i in 0..share {
+                results.push(run_cycle(&kernel, format!("client {} message {}", client, i)).await);
+            }
+            results
+        }));
+    }
+    let mut results = Vec::with_capacity(total);
+    for client in clients {
+        results.extend(client.await.unwrap());
+    }
+    results
+}
+
+struct StageStats {
+    stage: &'static str,
+    samples: usize,
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    max: Duration,
+}
+
+/// Nearest-rank percentile of a sorted slice.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn stage_stats(
+    stage: &'static str,
+    results: &[Marks],
+    span: impl Fn(&Marks) -> Option<(Instant, Instant)>,
+) -> StageStats {
+    let mut samples: Vec<Duration> = results
+        .iter()
+        .filter_map(|m| span(m).map(|(from, to)| to.saturating_duration_since(from)))
+        .collect();
+    samples.sort_unstable();
+    StageStats {
+        stage,
+        samples: samples.len(),
+        p50: percentile(&samples, 0.50),
+        p95: percentile(&samples, 0.95),
+        p99: percentile(&samples, 0.99),
+        max: samples.last().copied().unwrap_or_default(),
+    }
+}
+
+fn summarize(results: &[Marks]) -> Vec<StageStats> {
+    vec![
+        // Event queue → registry dispatch → SystemHandler → agent lookup → engine
+        stage_stats("dispatch", results, |m| {
+            Some((m.submitted?, m.first_think?))
+        }),
+        // Tool call handed back by the engine → tool execution starts
+        stage_stats("tool_call", results, |m| Some((m.first_think?, m.tool?))),
+        // Tool started → engine called again with the result
+        stage_stats("tool_return", results, |m| Some((m.tool?, m.second_think?))),
+        // Final answer → ThoughtResponse through the event loop to the broadcast
+        stage_stats("response", results, |m| {
+            Some((m.second_think?, m.responded?))
+        }),
+        stage_stats("total", results, |m| Some((m.submitted?, m.responded?))),
+    ]
+}
+
+fn micros(d: Duration) -> f64 {
+    d.as_secs_f64() * 1_000_000.0
+}
+
+fn print_table(stats: &[StageStats]) {
+    println!(
+        "{:<12} {:>8} {:>12} {:>12} {:>12} {:>12}",
+        "stage", "samples", "p50 (µs)", "p95 (µs)", "p99 (µs)", "max (µs)"
+    );
+    for s in stats {
+        println!(
+            "{:<12} {:>8} {:>12.1} {:>12.1} {:>12.1} {:>12.1}",
+            s.stage,
+            s.samples,
+            micros(s.p50),
+            micros(s.p95),
+            micros(s.p99),
+            micros(s.max)
+        );
+    }
+}
+
+fn to_json(options: &Options, elapsed: Duration, stats: &[StageStats]) -> serde_json::Value {
+    let stages: serde_json::Map<String, serde_json::Value> = stats
+        .iter()
+        .map(|s| {
+            (
+                s.stage.to_string(),
+                serde_json::json!({
+                    "samples": s.samples,
+                    "p50_us": micros(s.p50),
+                    "p95_us": micros(s.p95),
+                    "p99_us": micros(s.p99),
+                    "max_us": micros(s.max),
+                }),
+            )
+        })
+        .collect();
+    serde_json::json!({
+        "iterations": options.iterations,
+        "concurrency": options.concurrency,
+        "think_ms": options.think_latency.as_millis(),
+        "tool_ms": options.tool_latency.as_millis(),
+        "elapsed_secs": elapsed.as_secs_f64(),
+        "stages": stages,
+    })
+}
+
+fn main() {
+    let options = Options::from_args();
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let kernel = Arc::new(start_kernel(&options).await);
+
+        run_load(&kernel, options.warmup, options.concurrency).await;
+
+        let start = Instant::now();
+        let results = run_load(&kernel, options.iterations, options.concurrency).await;
+        let elapsed = start.elapsed();
+
+        let completed = results.iter().filter(|m| m.responded.is_some()).count();
+        println!(
+            "e2e_latency: {} cycles ({} completed) at concurrency {} in {:.2}s ({:.0} cycles/s)",
+            options.iterations,
+            completed,
+            options.concurrency,
+            elapsed.as_secs_f64(),
+            completed as f64 / elapsed.as_secs_f64()
+        );
+        let stats = summarize(&results);
+        print_table(&stats);
+
+        if let Some(path) = &options.json_path {
+            let json = to_json(&options, elapsed, &stats);
+            std::fs::write(path, serde_json::to_string_pretty(&json).unwrap()).unwrap();
+            println!("Results written to {}", path);
+        }
+
+        let _ = std::fs::remove_file(&kernel.db_path);
+    });
+}
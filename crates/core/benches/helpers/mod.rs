@@ -2,13 +2,13 @@
 // Reusable infrastructure for Cloto performance benchmarks
 // Pattern inspired by: cloto_core/tests/handlers_http_test.rs:18-60
 
+use cloto_core::db::DbPool;
 use cloto_core::{
     config::AppConfig,
     managers::{AgentManager, McpClientManager, PluginManager, PluginRegistry, SystemMetrics},
     AppState, DynamicRouter,
 };
 use cloto_shared::{ClotoEvent, ClotoEventData, ClotoMessage, MessageSource};
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, Notify, RwLock};
@@ -17,7 +17,7 @@ use tokio::sync::{broadcast, mpsc, Notify, RwLock};
 /// Uses larger buffer sizes (1000 vs 100) for high-throughput scenarios
 #[allow(dead_code)]
 pub async fn create_bench_app_state() -> Arc<AppState> {
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
@@ -40,9 +40,31 @@ pub async fn create_bench_app_state() -> Arc<AppState> {
     config.admin_api_key = Some("bench-key".to_string());
 
     let rate_limiter = Arc::new(cloto_core::middleware::RateLimiter::new(100, 200));
+    let slow_requests = Arc::new(cloto_core::middleware::SlowRequestLog::new(
+        config.slow_request_threshold_ms,
+    ));
 
     let mcp_manager = Arc::new(McpClientManager::new(pool.clone(), false));
 
+    let rooms = Arc::new(cloto_core::managers::RoomManager::new(
+        pool.clone(),
+        agent_manager.clone(),
+        event_tx.clone(),
+        tx.clone(),
+    ));
+    let summarizer = Arc::new(cloto_core::managers::ConversationSummarizer::new(
+        pool.clone(),
+        registry.clone(),
+        agent_manager.clone(),
+        config.summary_threshold,
+    ));
+    let attachments = Arc::new(cloto_core::managers::AttachmentProcessor::new(
+        pool.clone(),
+        mcp_manager.clone(),
+        None,
+        cloto_core::managers::attachments::DEFAULT_MAX_DIMENSION,
+    ));
+
     Arc::new(AppState {
         tx,
         registry,
@@ -52,12 +74,21 @@ pub async fn create_bench_app_state() -> Arc<AppState> {
         plugin_manager,
         mcp_manager,
         dynamic_router,
+        runtime_config: Arc::new(std::sync::RwLock::new(config.clone())),
         config,
         event_history,
         metrics,
         rate_limiter,
         shutdown: Arc::new(Notify::new()),
+        drain: Arc::new(cloto_core::drain::DrainTracker::new()),
         revoked_keys: Arc::new(std::sync::RwLock::new(std::collections::HashSet::new())),
+        api_keys: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+        users: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+        session_key: Arc::new(cloto_core::handlers::auth::SessionKey::generate()),
+        slow_requests,
+        rooms,
+        summarizer,
+        attachments,
     })
 }
 
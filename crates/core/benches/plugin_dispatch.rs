@@ -3,11 +3,13 @@
 // Measures: FuturesUnordered concurrency, semaphore contention, plugin execution
 
 use async_trait::async_trait;
+use cloto_core::managers::PluginMap;
 use cloto_shared::{
     ClotoEvent, ClotoEventData, Plugin, PluginCast, PluginCategory, PluginManifest, ServiceType,
 };
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -40,6 +42,7 @@ impl Plugin for BenchPlugin {
             is_active: true,
             is_configured: true,
             required_config_keys: vec![],
+            config_schema: vec![],
             action_icon: None,
             action_target: None,
             icon_data: None,
@@ -48,6 +51,7 @@ impl Plugin for BenchPlugin {
             required_permissions: vec![],
             provided_capabilities: vec![],
             provided_tools: vec![],
+            subscribes: vec![],
         }
     }
 
@@ -226,12 +230,84 @@ fn plugin_depth_limit_benchmark(c: &mut Criterion) {
     });
 }
 
+/// Registry read path under concurrent dispatch: the former async
+/// `RwLock<HashMap>` against the copy-on-write `PluginMap` snapshot.
+fn registry_read_contention(c: &mut Criterion) {
+    const READERS: usize = 64;
+    const LOOKUPS: usize = 100;
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let plugins: Vec<(String, Arc<dyn Plugin>)> = (0..20)
+        .map(|i| {
+            let plugin: Arc<dyn Plugin> = Arc::new(BenchPlugin {
+                id: format!("plugin_{}", i),
+                latency_ms: 0,
+            });
+            (format!("plugin_{}", i), plugin)
+        })
+        .collect();
+
+    let rwlock = Arc::new(tokio::sync::RwLock::new(
+        plugins.iter().cloned().collect::<HashMap<_, _>>(),
+    ));
+    let snapshot = Arc::new(PluginMap::default());
+    runtime.block_on(async {
+        snapshot.write().await.extend(plugins.iter().cloned());
+    });
+
+    let mut group = c.benchmark_group("registry_read_contention");
+    group.bench_function("rwlock", |b| {
+        b.to_async(&runtime).iter(|| {
+            let rwlock = rwlock.clone();
+            async move {
+                let handles: Vec<_> = (0..READERS)
+                    .map(|_| {
+                        let rwlock = rwlock.clone();
+                        tokio::spawn(async move {
+                            for i in 0..LOOKUPS {
+                                let plugins = rwlock.read().await;
+                                let _ = plugins.get(&format!("plugin_{}", i % 20)).cloned();
+                            }
+                        })
+                    })
+                    .collect();
+                for h in handles {
+                    h.await.unwrap();
+                }
+            }
+        });
+    });
+    group.bench_function("snapshot", |b| {
+        b.to_async(&runtime).iter(|| {
+            let snapshot = snapshot.clone();
+            async move {
+                let handles: Vec<_> = (0..READERS)
+                    .map(|_| {
+                        let snapshot = snapshot.clone();
+                        tokio::spawn(async move {
+                            for i in 0..LOOKUPS {
+                                let plugins = snapshot.load();
+                                let _ = plugins.get(&format!("plugin_{}", i % 20)).cloned();
+                            }
+                        })
+                    })
+                    .collect();
+                for h in handles {
+                    h.await.unwrap();
+                }
+            }
+        });
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     plugin_dispatch_single,
     plugin_dispatch_concurrent,
     plugin_dispatch_no_latency,
     plugin_semaphore_contention,
-    plugin_depth_limit_benchmark
+    plugin_depth_limit_benchmark,
+    registry_read_contention
 );
 criterion_main!(benches);
@@ -0,0 +1,17 @@
+-- Per-execution history for cron jobs (cron_jobs only keeps the last run)
+CREATE TABLE IF NOT EXISTS cron_job_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id TEXT NOT NULL,
+    trigger TEXT NOT NULL DEFAULT 'scheduler',        -- 'scheduler' | 'manual'
+    message_id TEXT NOT NULL,                         -- dispatched MessageReceived ID
+    started_at INTEGER NOT NULL,                      -- unix milliseconds
+    finished_at INTEGER,
+    duration_ms INTEGER,
+    status TEXT NOT NULL DEFAULT 'running',           -- 'running' | 'success' | 'error' | 'timeout'
+    response_message_id TEXT,                         -- agent reply ID ("{message_id}-resp")
+    error TEXT,
+    FOREIGN KEY(job_id) REFERENCES cron_jobs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_cron_runs_job ON cron_job_runs(job_id, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_cron_runs_message ON cron_job_runs(message_id) WHERE status = 'running';
@@ -0,0 +1,3 @@
+-- Misfire (catch-up) policy and start jitter for cron jobs
+ALTER TABLE cron_jobs ADD COLUMN misfire_policy TEXT NOT NULL DEFAULT 'run_once';  -- 'run_once' | 'skip' | 'run_all'
+ALTER TABLE cron_jobs ADD COLUMN jitter_secs INTEGER NOT NULL DEFAULT 0;            -- random delay 0..=jitter_secs per run
@@ -0,0 +1,4 @@
+-- Job chaining: schedule_type 'after' runs a job when the job in schedule_value finishes
+ALTER TABLE cron_jobs ADD COLUMN chain_on TEXT NOT NULL DEFAULT 'success';  -- 'success' | 'failure' | 'always'
+
+CREATE INDEX IF NOT EXISTS idx_cron_chain ON cron_jobs(schedule_value) WHERE schedule_type = 'after';
@@ -0,0 +1,3 @@
+-- Calendar modifiers: occurrences falling on a blocked day are skipped
+ALTER TABLE cron_jobs ADD COLUMN weekdays_only INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE cron_jobs ADD COLUMN skip_dates TEXT NOT NULL DEFAULT '';  -- comma-separated YYYY-MM-DD (UTC)
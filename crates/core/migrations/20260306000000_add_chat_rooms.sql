@@ -0,0 +1,34 @@
+-- Group chat rooms: several agents and the user share one conversation
+
+CREATE TABLE IF NOT EXISTS chat_rooms (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    turn_policy TEXT NOT NULL DEFAULT 'round_robin'
+        CHECK (turn_policy IN ('round_robin', 'addressed')),
+    next_turn INTEGER NOT NULL DEFAULT 0,  -- round-robin cursor into the member list
+    created_at INTEGER NOT NULL            -- Unix timestamp ms
+);
+
+CREATE TABLE IF NOT EXISTS chat_room_members (
+    room_id TEXT NOT NULL,
+    agent_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (room_id, agent_id),
+    FOREIGN KEY (room_id) REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+);
+
+-- Merged transcript; speaker_id is the user or agent ID
+CREATE TABLE IF NOT EXISTS chat_room_messages (
+    id TEXT PRIMARY KEY,
+    room_id TEXT NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('user', 'agent', 'system')),
+    speaker_id TEXT NOT NULL,
+    speaker_name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (room_id) REFERENCES chat_rooms(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_room_messages_room_time
+    ON chat_room_messages(room_id, created_at DESC);
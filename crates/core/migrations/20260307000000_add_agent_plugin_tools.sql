@@ -0,0 +1,12 @@
+-- Per-tool allow/deny rules on an agent's plugin bindings.
+-- A deny rule blocks the tool. If a binding has any allow rules, only the
+-- allowed tools are usable (allowlist mode); with no rules every tool is.
+CREATE TABLE IF NOT EXISTS agent_plugin_tools (
+    agent_id   TEXT NOT NULL,
+    plugin_id  TEXT NOT NULL,
+    tool_name  TEXT NOT NULL,
+    permission TEXT NOT NULL CHECK (permission IN ('allow', 'deny')),
+    PRIMARY KEY (agent_id, plugin_id, tool_name),
+    FOREIGN KEY (agent_id, plugin_id)
+        REFERENCES agent_plugins(agent_id, plugin_id) ON DELETE CASCADE
+);
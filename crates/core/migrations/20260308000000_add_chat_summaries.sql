@@ -0,0 +1,11 @@
+-- Rolling summaries of long chat threads, one per agent/user thread
+CREATE TABLE IF NOT EXISTS chat_summaries (
+    agent_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    summarized_until INTEGER NOT NULL,  -- created_at (ms) of the newest folded-in message
+    message_count INTEGER NOT NULL,     -- messages folded in so far
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (agent_id, user_id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+);
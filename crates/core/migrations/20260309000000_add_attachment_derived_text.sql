@@ -0,0 +1,2 @@
+-- Text derived from an attachment (document extraction, audio transcript)
+ALTER TABLE chat_attachments ADD COLUMN derived_text TEXT;
@@ -0,0 +1,4 @@
+-- Queries now write `enabled = TRUE` (valid on SQLite and PostgreSQL);
+-- SQLite only uses a partial index whose WHERE clause matches literally
+DROP INDEX IF EXISTS idx_cron_next;
+CREATE INDEX IF NOT EXISTS idx_cron_next ON cron_jobs(next_run_at) WHERE enabled = TRUE;
@@ -0,0 +1,32 @@
+-- Named conversation sessions: chat history is grouped per session and the
+-- agent's context comes from the active one. Existing history becomes each
+-- thread's "Default" session.
+CREATE TABLE IF NOT EXISTS chat_sessions (
+    id TEXT PRIMARY KEY,
+    agent_id TEXT NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL DEFAULT 'default',
+    name TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT FALSE,
+    archived BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at INTEGER NOT NULL,  -- Unix timestamp ms
+    updated_at INTEGER NOT NULL   -- last message or switch, ms
+);
+CREATE INDEX IF NOT EXISTS idx_chat_sessions_thread
+    ON chat_sessions(agent_id, user_id, updated_at DESC);
+-- At most one active session per agent/user thread
+CREATE UNIQUE INDEX IF NOT EXISTS idx_chat_sessions_active
+    ON chat_sessions(agent_id, user_id) WHERE active = TRUE;
+
+ALTER TABLE chat_messages ADD COLUMN session_id TEXT REFERENCES chat_sessions(id);
+CREATE INDEX IF NOT EXISTS idx_chat_messages_session
+    ON chat_messages(session_id, created_at DESC);
+
+INSERT INTO chat_sessions (id, agent_id, user_id, name, active, archived, created_at, updated_at)
+SELECT 'session.' || agent_id || '.' || user_id, agent_id, user_id, 'Default', TRUE, FALSE,
+       MIN(created_at), MAX(created_at)
+FROM chat_messages
+WHERE agent_id IN (SELECT id FROM agents)
+GROUP BY agent_id, user_id;
+
+UPDATE chat_messages SET session_id = 'session.' || agent_id || '.' || user_id
+WHERE agent_id IN (SELECT id FROM agents);
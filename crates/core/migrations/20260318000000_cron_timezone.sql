@@ -0,0 +1,2 @@
+-- IANA timezone for cron expressions and calendar modifiers (e.g. Asia/Tokyo); NULL = UTC
+ALTER TABLE cron_jobs ADD COLUMN timezone TEXT;
@@ -0,0 +1,16 @@
+-- Events a plugin failed to handle after its retries (CLOTO_EVENT_RETRY_MAX).
+-- Listed via GET /api/events/dead-letter and redelivered to the plugin with
+-- POST /api/events/dead-letter/:id/replay.
+CREATE TABLE IF NOT EXISTS dead_letter_events (
+    id TEXT PRIMARY KEY,
+    plugin_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    event TEXT NOT NULL,          -- JSON (ClotoEvent)
+    depth INTEGER NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,  -- Unix timestamp ms
+    replayed_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_dead_letter_events_created
+    ON dead_letter_events(created_at);
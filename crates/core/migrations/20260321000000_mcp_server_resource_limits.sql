@@ -0,0 +1,3 @@
+-- Per-server CPU/memory limits (JSON, e.g. {"memory_mb":512,"cpu_secs":600})
+-- set via the settings API. Overrides [servers.resource_limits] in mcp.toml.
+ALTER TABLE mcp_servers ADD COLUMN resource_limits TEXT;
@@ -0,0 +1,20 @@
+-- One row per agent tool call (executed, failed or refused) for compliance
+-- review. Arguments are redacted (CLOTO_AUDIT_REDACT_KEYS) and truncated.
+-- Queried via GET /api/tools/invocations.
+CREATE TABLE IF NOT EXISTS tool_invocations (
+    id TEXT PRIMARY KEY,
+    agent_id TEXT NOT NULL,
+    engine_id TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    server_id TEXT,               -- native plugin or MCP server owning the tool
+    arguments TEXT NOT NULL,      -- JSON
+    status TEXT NOT NULL,         -- SUCCESS / FAILURE / DENIED
+    error TEXT,
+    duration_ms INTEGER NOT NULL,
+    trace_id TEXT,
+    created_at INTEGER NOT NULL        -- Unix timestamp ms
+);
+CREATE INDEX IF NOT EXISTS idx_tool_invocations_agent
+    ON tool_invocations(agent_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_tool_invocations_created
+    ON tool_invocations(created_at);
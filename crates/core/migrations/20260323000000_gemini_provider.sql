@@ -0,0 +1,5 @@
+-- Google Gemini (native generateContent API) for the mind.gemini MCP server.
+-- The proxy fills {model} from the request and sends the key as x-goog-api-key.
+INSERT OR IGNORE INTO llm_providers (id, display_name, api_url, model_id)
+VALUES
+  ('gemini', 'Google Gemini', 'https://generativelanguage.googleapis.com/v1beta/models/{model}:generateContent', 'gemini-2.0-flash');
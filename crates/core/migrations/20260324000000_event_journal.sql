@@ -0,0 +1,16 @@
+-- Event journal (CLOTO_EVENT_JOURNAL): every recorded event as broadcast
+-- over SSE, so GET /api/history can page and filter past the in-memory ring
+-- buffer. Pruned after CLOTO_EVENT_JOURNAL_RETENTION_DAYS.
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trace_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    event TEXT NOT NULL,               -- JSON (ClotoEvent)
+    created_at INTEGER NOT NULL        -- Event timestamp, Unix ms
+);
+CREATE INDEX IF NOT EXISTS idx_events_created
+    ON events(created_at);
+CREATE INDEX IF NOT EXISTS idx_events_type
+    ON events(event_type, created_at);
+CREATE INDEX IF NOT EXISTS idx_events_trace
+    ON events(trace_id);
@@ -0,0 +1,12 @@
+-- Tool-call transcript of the agentic run that answered a chat message,
+-- keyed by the message that started the run. Served by
+-- GET /api/chat/:agent_id/messages?include_tools=true.
+CREATE TABLE IF NOT EXISTS chat_tool_history (
+    message_id TEXT PRIMARY KEY,
+    agent_id TEXT NOT NULL,
+    engine_id TEXT NOT NULL,
+    history TEXT NOT NULL,             -- JSON array of OpenAI-style messages
+    created_at INTEGER NOT NULL        -- Unix timestamp ms
+);
+CREATE INDEX IF NOT EXISTS idx_chat_tool_history_agent
+    ON chat_tool_history(agent_id);
@@ -0,0 +1,305 @@
+-- PostgreSQL schema (built with the `postgres` feature).
+--
+-- Equivalent to the SQLite migrations in the parent directory up to
+-- 20260310000000, consolidated into one file. Integer columns are BIGINT
+-- because the kernel reads them as i64; JSON documents stay TEXT as on
+-- SQLite. Schema changes from here on need a migration in both directories.
+
+-- ─── Plugins ───
+
+CREATE TABLE plugin_configs (
+    plugin_id TEXT NOT NULL,
+    config_key TEXT NOT NULL,
+    config_value TEXT,
+    PRIMARY KEY (plugin_id, config_key)
+);
+
+CREATE TABLE plugin_settings (
+    plugin_id TEXT PRIMARY KEY,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    allowed_permissions TEXT DEFAULT '[]'
+);
+
+CREATE TABLE plugin_data (
+    plugin_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (plugin_id, key)
+);
+
+CREATE TABLE runtime_plugins (
+    plugin_id TEXT PRIMARY KEY,
+    script_name TEXT NOT NULL,
+    description TEXT,
+    code_content TEXT NOT NULL,
+    permissions TEXT DEFAULT '[]',
+    created_at BIGINT NOT NULL,
+    created_by TEXT,
+    generation_number BIGINT,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE
+);
+CREATE INDEX idx_runtime_plugins_active ON runtime_plugins(is_active);
+
+-- ─── Agents ───
+
+CREATE TABLE agents (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    default_engine_id TEXT NOT NULL,
+    status TEXT DEFAULT 'offline',
+    metadata TEXT DEFAULT '{}',
+    required_capabilities TEXT DEFAULT '["Reasoning", "Memory"]',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_seen BIGINT NOT NULL DEFAULT 0,
+    power_password_hash TEXT DEFAULT NULL
+);
+
+CREATE TABLE agent_plugins (
+    agent_id TEXT NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    plugin_id TEXT NOT NULL,
+    pos_x BIGINT NOT NULL DEFAULT 0,
+    pos_y BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (agent_id, plugin_id)
+);
+CREATE INDEX idx_agent_plugins_agent ON agent_plugins(agent_id);
+
+CREATE TABLE agent_plugin_tools (
+    agent_id TEXT NOT NULL,
+    plugin_id TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    permission TEXT NOT NULL CHECK (permission IN ('allow', 'deny')),
+    PRIMARY KEY (agent_id, plugin_id, tool_name),
+    FOREIGN KEY (agent_id, plugin_id)
+        REFERENCES agent_plugins(agent_id, plugin_id) ON DELETE CASCADE
+);
+
+-- ─── Security ───
+
+CREATE TABLE audit_logs (
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    actor_id TEXT,
+    target_id TEXT,
+    permission TEXT,
+    result TEXT NOT NULL,
+    reason TEXT,
+    metadata TEXT,
+    trace_id TEXT
+);
+CREATE INDEX idx_audit_timestamp ON audit_logs(timestamp);
+CREATE INDEX idx_audit_actor ON audit_logs(actor_id);
+CREATE INDEX idx_audit_event_type ON audit_logs(event_type);
+CREATE INDEX idx_audit_trace_id ON audit_logs(trace_id);
+
+-- Audit log retention: auto-delete entries older than 90 days
+CREATE FUNCTION audit_log_cleanup() RETURNS trigger AS $$
+BEGIN
+    DELETE FROM audit_logs
+    WHERE timestamp < to_char(now() AT TIME ZONE 'UTC' - INTERVAL '90 days', 'YYYY-MM-DD HH24:MI:SS');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_cleanup
+AFTER INSERT ON audit_logs
+FOR EACH STATEMENT EXECUTE FUNCTION audit_log_cleanup();
+
+CREATE TABLE permission_requests (
+    id BIGSERIAL PRIMARY KEY,
+    request_id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    plugin_id TEXT NOT NULL,
+    permission_type TEXT NOT NULL,
+    target_resource TEXT,
+    justification TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    approved_by TEXT,
+    approved_at TEXT,
+    expires_at TEXT,
+    metadata TEXT
+);
+CREATE INDEX idx_permission_status ON permission_requests(status);
+CREATE INDEX idx_permission_plugin ON permission_requests(plugin_id);
+CREATE INDEX idx_permission_created ON permission_requests(created_at);
+
+CREATE TABLE revoked_keys (
+    key_hash TEXT PRIMARY KEY,
+    revoked_at BIGINT NOT NULL
+);
+
+-- ─── Chat ───
+
+CREATE TABLE chat_messages (
+    id TEXT PRIMARY KEY,
+    agent_id TEXT NOT NULL REFERENCES agents(id),
+    user_id TEXT NOT NULL DEFAULT 'default',
+    source TEXT NOT NULL CHECK (source IN ('user', 'agent', 'system')),
+    content TEXT NOT NULL,         -- JSON array of ContentBlock[]
+    metadata TEXT,                 -- optional JSON metadata
+    created_at BIGINT NOT NULL     -- Unix timestamp ms
+);
+CREATE INDEX idx_chat_messages_agent_time
+    ON chat_messages(agent_id, user_id, created_at DESC);
+
+CREATE TABLE chat_attachments (
+    id TEXT PRIMARY KEY,
+    message_id TEXT NOT NULL REFERENCES chat_messages(id) ON DELETE CASCADE,
+    filename TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_type TEXT NOT NULL CHECK (storage_type IN ('inline', 'disk')),
+    inline_data BYTEA,            -- for <=64KB files
+    disk_path TEXT,               -- for >64KB files
+    created_at BIGINT NOT NULL,
+    derived_text TEXT
+);
+
+CREATE TABLE chat_summaries (
+    agent_id TEXT NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    summarized_until BIGINT NOT NULL,  -- created_at (ms) of the newest folded-in message
+    message_count BIGINT NOT NULL,     -- messages folded in so far
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (agent_id, user_id)
+);
+
+CREATE TABLE chat_rooms (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    turn_policy TEXT NOT NULL DEFAULT 'round_robin'
+        CHECK (turn_policy IN ('round_robin', 'addressed')),
+    next_turn BIGINT NOT NULL DEFAULT 0,  -- round-robin cursor into the member list
+    created_at BIGINT NOT NULL            -- Unix timestamp ms
+);
+
+CREATE TABLE chat_room_members (
+    room_id TEXT NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    agent_id TEXT NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    position BIGINT NOT NULL,
+    PRIMARY KEY (room_id, agent_id)
+);
+
+CREATE TABLE chat_room_messages (
+    id TEXT PRIMARY KEY,
+    room_id TEXT NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    source TEXT NOT NULL CHECK (source IN ('user', 'agent', 'system')),
+    speaker_id TEXT NOT NULL,
+    speaker_name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    -- Insertion order, standing in for SQLite's implicit rowid (the
+    -- transcript query uses it to order messages within a millisecond)
+    rowid BIGSERIAL NOT NULL
+);
+CREATE INDEX idx_chat_room_messages_room_time
+    ON chat_room_messages(room_id, created_at DESC);
+
+-- ─── MCP ───
+
+CREATE TABLE mcp_servers (
+    name TEXT PRIMARY KEY,
+    command TEXT NOT NULL,
+    args TEXT NOT NULL DEFAULT '[]',
+    script_content TEXT,
+    description TEXT,
+    created_at BIGINT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    default_policy TEXT NOT NULL DEFAULT 'opt-in',
+    env TEXT NOT NULL DEFAULT '{}'
+);
+
+CREATE TABLE mcp_access_control (
+    id BIGSERIAL PRIMARY KEY,
+    entry_type TEXT NOT NULL CHECK (entry_type IN ('capability', 'server_grant', 'tool_grant')),
+    agent_id TEXT NOT NULL,
+    server_id TEXT NOT NULL,
+    tool_name TEXT,
+    permission TEXT NOT NULL DEFAULT 'allow',
+    granted_by TEXT,
+    granted_at TEXT NOT NULL,
+    expires_at TEXT,
+    justification TEXT,
+    metadata TEXT
+);
+CREATE INDEX idx_ac_agent_server_tool ON mcp_access_control(agent_id, server_id, tool_name);
+CREATE INDEX idx_ac_server ON mcp_access_control(server_id);
+CREATE INDEX idx_ac_entry_type ON mcp_access_control(entry_type);
+
+-- ─── Cron ───
+
+CREATE TABLE cron_jobs (
+    id TEXT PRIMARY KEY,
+    agent_id TEXT NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    schedule_type TEXT NOT NULL DEFAULT 'interval',  -- 'interval' | 'cron' | 'once' | 'after'
+    schedule_value TEXT NOT NULL,
+    engine_id TEXT,                                   -- NULL = use agent default
+    message TEXT NOT NULL,                            -- prompt sent to agent
+    next_run_at BIGINT NOT NULL,                      -- unix milliseconds
+    last_run_at BIGINT,
+    last_status TEXT,                                 -- 'success' | 'error'
+    last_error TEXT,
+    max_iterations INTEGER DEFAULT 8,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS'),
+    misfire_policy TEXT NOT NULL DEFAULT 'run_once',
+    jitter_secs BIGINT NOT NULL DEFAULT 0,
+    chain_on TEXT NOT NULL DEFAULT 'success',
+    weekdays_only BOOLEAN NOT NULL DEFAULT FALSE,
+    skip_dates TEXT NOT NULL DEFAULT ''
+);
+CREATE INDEX idx_cron_next ON cron_jobs(next_run_at) WHERE enabled = TRUE;
+CREATE INDEX idx_cron_agent ON cron_jobs(agent_id);
+CREATE INDEX idx_cron_chain ON cron_jobs(schedule_value) WHERE schedule_type = 'after';
+
+CREATE TABLE cron_job_runs (
+    id BIGSERIAL PRIMARY KEY,
+    job_id TEXT NOT NULL REFERENCES cron_jobs(id) ON DELETE CASCADE,
+    trigger TEXT NOT NULL DEFAULT 'scheduler',        -- 'scheduler' | 'manual'
+    message_id TEXT NOT NULL,                         -- dispatched MessageReceived ID
+    started_at BIGINT NOT NULL,                       -- unix milliseconds
+    finished_at BIGINT,
+    duration_ms BIGINT,
+    status TEXT NOT NULL DEFAULT 'running',           -- 'running' | 'success' | 'error' | 'timeout'
+    response_message_id TEXT,                         -- agent reply ID ("{message_id}-resp")
+    error TEXT
+);
+CREATE INDEX idx_cron_runs_job ON cron_job_runs(job_id, started_at DESC);
+CREATE INDEX idx_cron_runs_message ON cron_job_runs(message_id) WHERE status = 'running';
+
+-- ─── LLM Providers ───
+
+CREATE TABLE llm_providers (
+    id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    api_url TEXT NOT NULL,
+    api_key TEXT DEFAULT '',
+    model_id TEXT NOT NULL,
+    timeout_secs INTEGER NOT NULL DEFAULT 120,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TEXT NOT NULL DEFAULT to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')
+);
+
+-- ─── Seeds ───
+
+INSERT INTO plugin_settings (plugin_id, is_active, allowed_permissions) VALUES
+    ('memory.ks22', TRUE, '["MemoryRead","MemoryWrite"]'),
+    ('mind.deepseek', TRUE, '["NetworkAccess"]'),
+    ('mind.cerebras', TRUE, '["NetworkAccess"]');
+
+INSERT INTO agents (id, name, description, default_engine_id, status, metadata, required_capabilities, enabled, last_seen) VALUES
+    ('agent.cloto_default', 'Cloto Assistant', 'The primary system assistant.', 'mind.deepseek', 'online', '{}',
+     '["Reasoning", "Memory"]', TRUE, (EXTRACT(EPOCH FROM now()) * 1000)::BIGINT);
+
+INSERT INTO agent_plugins (agent_id, plugin_id, pos_x, pos_y) VALUES
+    ('agent.cloto_default', 'mind.cerebras', 0, 0),
+    ('agent.cloto_default', 'memory.ks22', 1, 0);
+
+INSERT INTO llm_providers (id, display_name, api_url, model_id) VALUES
+    ('deepseek', 'DeepSeek', 'https://api.deepseek.com/chat/completions', 'deepseek-chat'),
+    ('cerebras', 'Cerebras', 'https://api.cerebras.ai/v1/chat/completions', 'llama-3.3-70b'),
+    ('ollama', 'Ollama (Local)', 'http://localhost:11434/api/chat', 'glm-4.7-flash');
@@ -0,0 +1,10 @@
+-- Scoped API keys managed via /api/system/keys (CLOTO_API_KEY stays the
+-- bootstrap admin key). Only the SHA-256 of the key is stored.
+CREATE TABLE IF NOT EXISTS api_keys (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    key_prefix TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK (scope IN ('read_only', 'chat', 'admin')),
+    created_at BIGINT NOT NULL
+);
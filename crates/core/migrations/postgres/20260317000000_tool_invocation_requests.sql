@@ -0,0 +1,17 @@
+-- Per-call approval of MCP tools listed in a server's approval_required_tools.
+-- A row is created when such a call is made (outside YOLO mode) and decided
+-- via POST /api/tool-calls/:id/approve|deny, or expires after
+-- CLOTO_TOOL_APPROVAL_TIMEOUT_SECS.
+CREATE TABLE IF NOT EXISTS tool_invocation_requests (
+    id TEXT PRIMARY KEY,
+    server_id TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    arguments TEXT NOT NULL,      -- JSON
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'denied', 'expired')),
+    created_at BIGINT NOT NULL,   -- Unix timestamp ms
+    decided_at BIGINT,
+    decided_by TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_tool_invocation_requests_status
+    ON tool_invocation_requests(status, created_at);
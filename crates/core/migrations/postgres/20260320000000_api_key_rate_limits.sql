@@ -0,0 +1,4 @@
+-- Optional per-key rate limit overriding the chat/management route group
+-- quotas (CLOTO_RATE_*) for requests made with the key. NULL = group quota.
+ALTER TABLE api_keys ADD COLUMN rate_per_second INTEGER;
+ALTER TABLE api_keys ADD COLUMN rate_burst INTEGER;
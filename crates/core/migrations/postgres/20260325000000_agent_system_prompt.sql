@@ -0,0 +1,3 @@
+-- Per-agent system prompt template. NULL keeps the built-in prompt built
+-- from name and description.
+ALTER TABLE agents ADD COLUMN system_prompt TEXT;
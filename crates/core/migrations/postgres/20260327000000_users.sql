@@ -0,0 +1,10 @@
+-- Dashboard user accounts. Signing in via POST /api/auth/login issues a
+-- session cookie carrying the user's role (same values as API key scopes).
+-- Only the Argon2id hash of the password is stored.
+CREATE TABLE IF NOT EXISTS users (
+    id TEXT PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('read_only', 'chat', 'admin')),
+    created_at BIGINT NOT NULL
+);
@@ -1,11 +1,12 @@
 use async_trait::async_trait;
 use cloto_shared::{
-    FileCapability, HttpRequest, HttpResponse, NetworkCapability, ProcessCapability,
+    ClipboardCapability, FileCapability, HttpRequest, HttpResponse, NetworkCapability,
+    ProcessCapability,
 };
 use std::collections::HashSet;
 use std::net::IpAddr;
 use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 use tokio::net::lookup_host;
 use tracing::warn;
 
@@ -293,6 +294,23 @@ impl ProcessCapability for AllowedProcessCapability {
     }
 }
 
+// ── Host-provided capabilities ──
+
+/// System clipboard supplied by the embedding host (the Tauri desktop shell).
+static CLIPBOARD: OnceLock<Arc<dyn ClipboardCapability>> = OnceLock::new();
+
+/// Register the system clipboard implementation.
+/// Must be called before `run_kernel()`; returns false if one is already registered.
+pub fn register_clipboard(provider: Arc<dyn ClipboardCapability>) -> bool {
+    CLIPBOARD.set(provider).is_ok()
+}
+
+/// The host clipboard, if the kernel is embedded in a desktop shell.
+#[must_use]
+pub fn clipboard() -> Option<Arc<dyn ClipboardCapability>> {
+    CLIPBOARD.get().cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,242 @@
+//! Preflight checks for `cloto_system --check`.
+//!
+//! Validates configuration without starting the kernel: config layers,
+//! database connectivity, CORS origins, `mcp.toml` syntax and MCP server
+//! configuration completeness. Nothing is created or modified, so the check
+//! is safe to run next to a live kernel (e.g. in a deployment pipeline).
+
+use serde::Serialize;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::{AppConfig, ConfigLayers};
+use crate::managers::mcp_protocol::McpConfigFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CheckReport {
+    /// `false` if any check failed; warnings do not fail the report.
+    pub ok: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    fn push(&mut self, name: &'static str, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(CheckResult {
+            name,
+            status,
+            detail: detail.into(),
+        });
+    }
+}
+
+/// Run all checks. Later checks are skipped if the configuration itself
+/// cannot be loaded.
+pub async fn run() -> CheckReport {
+    let mut report = CheckReport::default();
+    match AppConfig::load() {
+        Ok(config) => {
+            let source = config.config_file.as_ref().map_or_else(
+                || "environment and defaults".to_string(),
+                |p| format!("environment and {}", p.display()),
+            );
+            report.push("config", CheckStatus::Ok, format!("Loaded from {}", source));
+            check_database(&mut report, &config.database_url).await;
+            check_cors(&mut report);
+            check_mcp_config(&mut report, &crate::mcp_config_path(&config));
+        }
+        Err(e) => report.push("config", CheckStatus::Fail, format!("{:#}", e)),
+    }
+    report.ok = report.checks.iter().all(|c| c.status != CheckStatus::Fail);
+    report
+}
+
+async fn check_database(report: &mut CheckReport, database_url: &str) {
+    if let Some(path) = database_url.strip_prefix("sqlite:") {
+        let path = path.split('?').next().unwrap_or(path);
+        if path != ":memory:" && !Path::new(path).exists() {
+            report.push(
+                "database",
+                CheckStatus::Warn,
+                format!("{} does not exist yet; it will be created on start", path),
+            );
+            return;
+        }
+    }
+    // Read-only so the check never creates or migrates the database
+    let result = async {
+        #[cfg(not(feature = "postgres"))]
+        let opts = sqlx::sqlite::SqliteConnectOptions::from_str(database_url)?.read_only(true);
+        #[cfg(feature = "postgres")]
+        let opts = sqlx::postgres::PgConnectOptions::from_str(database_url)?
+            .options([("default_transaction_read_only", "on")]);
+        let pool = crate::db::DbPool::connect_with(opts).await?;
+        sqlx::query("SELECT 1").execute(&pool).await?;
+        pool.close().await;
+        Ok::<_, sqlx::Error>(())
+    }
+    .await;
+    match result {
+        Ok(()) => report.push(
+            "database",
+            CheckStatus::Ok,
+            format!("Connected to {}", database_url),
+        ),
+        Err(e) => report.push("database", CheckStatus::Fail, e.to_string()),
+    }
+}
+
+fn check_cors(report: &mut CheckReport) {
+    let raw = ConfigLayers::load()
+        .ok()
+        .and_then(|layers| layers.var("CORS_ORIGINS").ok());
+    let Some(raw) = raw else {
+        report.push("cors", CheckStatus::Ok, "Using default development origins");
+        return;
+    };
+    let mut problems = Vec::new();
+    let mut count = 0usize;
+    for origin in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        count += 1;
+        if let Err(e) = crate::config::parse_cors_origin(origin) {
+            problems.push(e.to_string());
+        } else if origin
+            .trim_start_matches("http://")
+            .trim_start_matches("https://")
+            .contains('/')
+        {
+            // Browsers send the origin without a path, so this never matches
+            problems.push(format!("'{}' has a path and will never match", origin));
+        }
+    }
+    if problems.is_empty() {
+        report.push(
+            "cors",
+            CheckStatus::Ok,
+            format!("{} origin(s) valid", count),
+        );
+    } else {
+        report.push("cors", CheckStatus::Fail, problems.join("; "));
+    }
+}
+
+fn check_mcp_config(report: &mut CheckReport, config_path: &str) {
+    let path = Path::new(config_path);
+    if !path.exists() {
+        report.push(
+            "mcp_config",
+            CheckStatus::Warn,
+            format!("No MCP config file at {}", config_path),
+        );
+        return;
+    }
+    let parsed = std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(toml::from_str::<McpConfigFile>(&content)?));
+    let config = match parsed {
+        Ok(config) => config,
+        Err(e) => {
+            report.push(
+                "mcp_config",
+                CheckStatus::Fail,
+                format!("{}: {:#}", config_path, e),
+            );
+            return;
+        }
+    };
+    report.push(
+        "mcp_config",
+        CheckStatus::Ok,
+        format!("{}: {} server(s)", config_path, config.servers.len()),
+    );
+
+    let (errors, missing) = mcp_server_problems(&config);
+    if !errors.is_empty() {
+        report.push("mcp_servers", CheckStatus::Fail, errors.join("; "));
+    } else if !missing.is_empty() {
+        // Servers still start with an empty value; optional features may be off
+        report.push("mcp_servers", CheckStatus::Warn, missing.join("; "));
+    } else {
+        report.push(
+            "mcp_servers",
+            CheckStatus::Ok,
+            "All server definitions complete",
+        );
+    }
+}
+
+/// Definition errors that would make a server fail to start, and `${VAR}`
+/// env references that are unset (the server starts without the setting).
+fn mcp_server_problems(config: &McpConfigFile) -> (Vec<String>, Vec<String>) {
+    let mut problems = Vec::new();
+    let mut unset = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for server in &config.servers {
+        let id = &server.id;
+        if !seen.insert(id.as_str()) {
+            problems.push(format!("{}: duplicate server id", id));
+        }
+        if let Err(e) = crate::managers::mcp_transport::validate_server_config(server) {
+            problems.push(format!("{}: {}", id, e));
+        }
+        if server.instances == 0 {
+            problems.push(format!("{}: instances must be at least 1", id));
+        }
+        let mut missing: Vec<&str> = server
+            .env
+            .values()
+            .filter_map(|v| v.strip_prefix("${").and_then(|s| s.strip_suffix('}')))
+            .filter(|var| std::env::var(var).map_or(true, |v| v.is_empty()))
+            .collect();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            unset.push(format!(
+                "{}: unset environment variable(s) {}",
+                id,
+                missing.join(", ")
+            ));
+        }
+    }
+    (problems, unset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mcp_server_problems() {
+        let config: McpConfigFile = toml::from_str(
+            r#"
+            [[servers]]
+            id = "tool.ok"
+            command = "python3"
+
+            [[servers]]
+            id = "tool.ok"
+            command = "/bin/sh"
+            env = { TOKEN = "${CLOTO_TEST_CHECK_UNSET_VAR}" }
+            "#,
+        )
+        .unwrap();
+        let (errors, unset) = mcp_server_problems(&config);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("duplicate"));
+        assert_eq!(unset.len(), 1);
+        assert!(unset[0].contains("CLOTO_TEST_CHECK_UNSET_VAR"));
+    }
+}
@@ -1,5 +1,6 @@
 use clap::{Parser, Subcommand};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::info;
 
@@ -10,10 +11,47 @@ use tracing::info;
     about = "Cloto System - AI Agent Orchestration Platform"
 )]
 pub struct Cli {
+    /// Run the kernel as a headless server (browser dashboard, no desktop shell)
+    #[arg(long)]
+    pub headless: bool,
+    /// Validate configuration, database, CORS and MCP config, print a JSON report and exit
+    #[arg(long)]
+    pub check: bool,
+    /// Configuration file (default: cloto.toml in the working directory or next to the binary)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// HTTP port (overrides PORT and cloto.toml)
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Bind address (overrides BIND_ADDRESS and cloto.toml)
+    #[arg(long)]
+    pub bind_address: Option<String>,
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+impl Cli {
+    /// Kernel settings given as flags, keyed by env var name.
+    /// These take precedence over env vars and `cloto.toml`.
+    #[must_use]
+    pub fn config_overrides(&self) -> HashMap<String, String> {
+        let mut overrides = HashMap::new();
+        if self.headless {
+            overrides.insert("CLOTO_HEADLESS".to_string(), "true".to_string());
+        }
+        if let Some(path) = &self.config {
+            overrides.insert("CLOTO_CONFIG_FILE".to_string(), path.display().to_string());
+        }
+        if let Some(port) = self.port {
+            overrides.insert("PORT".to_string(), port.to_string());
+        }
+        if let Some(addr) = &self.bind_address {
+            overrides.insert("BIND_ADDRESS".to_string(), addr.clone());
+        }
+        overrides
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Install Cloto to a directory (self-install)
@@ -21,7 +59,7 @@ pub enum Commands {
         /// Installation directory
         #[arg(long, default_value_os_t = default_prefix())]
         prefix: PathBuf,
-        /// Register as OS service (systemd on Linux, sc.exe on Windows)
+        /// Register as OS service (systemd on Linux, launchd on macOS, sc.exe on Windows)
         #[arg(long)]
         service: bool,
         /// Service user (Linux only, default: current user)
@@ -51,6 +89,11 @@ pub enum Commands {
         #[arg(long, short = 'y')]
         yes: bool,
     },
+    /// Manage the encrypted secrets file (referenced as `secret:<name>` in config)
+    Secrets {
+        #[command(subcommand)]
+        action: SecretsAction,
+    },
     /// Print version and build information
     Version,
     /// Internal: perform exe swap after parent exits (used by update mechanism)
@@ -82,6 +125,16 @@ pub enum ServiceAction {
     Status,
 }
 
+#[derive(Subcommand)]
+pub enum SecretsAction {
+    /// Encrypt a value (read from stdin) under a name
+    Set { name: String },
+    /// Remove a secret
+    Remove { name: String },
+    /// List secret names
+    List,
+}
+
 fn default_prefix() -> PathBuf {
     if cfg!(windows) {
         PathBuf::from(r"C:\ProgramData\Cloto")
@@ -90,6 +143,43 @@ fn default_prefix() -> PathBuf {
     }
 }
 
+fn secrets_command(action: SecretsAction) -> anyhow::Result<()> {
+    use crate::secrets::{passphrase, secrets_file_path, SecretsFile};
+    let path = secrets_file_path();
+    match action {
+        SecretsAction::Set { name } => {
+            let passphrase = passphrase()?;
+            let mut value = String::new();
+            std::io::stdin().read_line(&mut value)?;
+            let value = value.trim_end_matches(['\r', '\n']);
+            if value.is_empty() {
+                anyhow::bail!("No value given on stdin");
+            }
+            let mut file = SecretsFile::load_or_new(&path)?;
+            file.set(&name, value, &passphrase)?;
+            file.save(&path)?;
+            println!("Stored secret '{}' in {}", name, path.display());
+            println!("Reference it as: secret:{}", name);
+        }
+        SecretsAction::Remove { name } => {
+            let mut file = SecretsFile::load(&path)?;
+            if !file.remove(&name) {
+                anyhow::bail!("Secret '{}' not found", name);
+            }
+            file.save(&path)?;
+            println!("Removed secret '{}'", name);
+        }
+        SecretsAction::List => {
+            if path.exists() {
+                for name in SecretsFile::load(&path)?.names() {
+                    println!("{}", name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Dispatch CLI subcommands
 pub async fn dispatch(cmd: Commands) -> anyhow::Result<()> {
     match cmd {
@@ -123,6 +213,7 @@ pub async fn dispatch(cmd: Commands) -> anyhow::Result<()> {
             version,
             yes,
         } => update_command(check, version, yes).await,
+        Commands::Secrets { action } => secrets_command(action),
         Commands::Version => {
             println!("Cloto System v{}", env!("CARGO_PKG_VERSION"));
             println!("Build target: {}", env!("TARGET"));
@@ -1,7 +1,10 @@
 use anyhow::Context;
 use axum::http::HeaderValue;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 /// Returns the directory containing the running executable.
 /// Falls back to CWD if the exe path cannot be determined.
@@ -13,24 +16,223 @@ pub fn exe_dir() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("."))
 }
 
+/// The `.env` file the kernel loads at startup: `CLOTO_ENV_FILE` if set,
+/// otherwise the nearest `.env` in the working directory or its parents
+/// (as `dotenvy` searches), falling back to the executable's directory.
+#[must_use]
+pub fn env_file_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var("CLOTO_ENV_FILE") {
+        return Some(PathBuf::from(path));
+    }
+    let cwd = env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(".env"))
+        .chain(std::iter::once(exe_dir().join(".env")))
+        .find(|p| p.is_file())
+}
+
+/// Parse one `CORS_ORIGINS` entry.
+pub fn parse_cors_origin(origin: &str) -> anyhow::Result<HeaderValue> {
+    // Reject non-HTTP(S) schemes (prevent file://, javascript://, data://)
+    if !origin.starts_with("http://") && !origin.starts_with("https://") {
+        anyhow::bail!(
+            "invalid scheme in '{}': must be http:// or https://",
+            origin
+        );
+    }
+    origin
+        .parse::<HeaderValue>()
+        .with_context(|| format!("invalid origin '{}'", origin))
+}
+
+/// OTLP trace export settings (`CLOTO_OTLP_*`). Export is disabled unless
+/// `CLOTO_OTLP_ENDPOINT` is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpConfig {
+    /// OTLP/HTTP traces endpoint, e.g. `http://localhost:4318/v1/traces`.
+    pub endpoint: String,
+    /// Extra request headers (`CLOTO_OTLP_HEADERS=key=value,key2=value2`),
+    /// typically an auth token for a hosted collector.
+    pub headers: HashMap<String, String>,
+    pub service_name: String,
+}
+
+impl OtlpConfig {
+    /// Read OTLP settings from the environment. Returns `None` when export
+    /// is not configured.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let layers = ConfigLayers::load()?;
+        let otlp = Self::from_layers(&layers)?;
+        layers.into_sources()?;
+        Ok(otlp)
+    }
+
+    fn from_layers(layers: &ConfigLayers) -> anyhow::Result<Option<Self>> {
+        let Some(endpoint) = layers
+            .var("CLOTO_OTLP_ENDPOINT")
+            .ok()
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            anyhow::bail!(
+                "CLOTO_OTLP_ENDPOINT must be an http:// or https:// URL (got '{}')",
+                endpoint
+            );
+        }
+
+        let headers = parse_header_list(&layers.var("CLOTO_OTLP_HEADERS").unwrap_or_default())
+            .context("Failed to parse CLOTO_OTLP_HEADERS")?;
+        let service_name = layers
+            .var("CLOTO_OTLP_SERVICE_NAME")
+            .unwrap_or_else(|_| "cloto-kernel".to_string());
+
+        Ok(Some(Self {
+            endpoint,
+            headers,
+            service_name,
+        }))
+    }
+}
+
+/// Parse a `key=value,key2=value2` list. Empty entries are ignored.
+fn parse_header_list(raw: &str) -> anyhow::Result<HashMap<String, String>> {
+    let mut headers = HashMap::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("expected key=value, got '{}'", entry))?;
+        let key = key.trim();
+        if key.is_empty() {
+            anyhow::bail!("empty header name in '{}'", entry);
+        }
+        headers.insert(key.to_string(), value.trim().to_string());
+    }
+    Ok(headers)
+}
+
+/// Token-bucket quota for one rate-limited route group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateQuota {
+    pub per_second: u32,
+    pub burst: u32,
+}
+
+/// Per-IP rate limits by route group (see `middleware::RouteGroup`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Chat endpoints (`/chat`, `/chat/:agent_id/messages`, attachments).
+    pub chat: RateQuota,
+    /// Plugin, agent, MCP, cron and settings management.
+    pub management: RateQuota,
+    /// `/system/shutdown`.
+    pub shutdown: RateQuota,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            chat: RateQuota {
+                per_second: 20,
+                burst: 40,
+            },
+            management: RateQuota {
+                per_second: 10,
+                burst: 20,
+            },
+            shutdown: RateQuota {
+                per_second: 1,
+                burst: 3,
+            },
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Read `CLOTO_RATE_{CHAT,MANAGEMENT,SHUTDOWN}_{PER_SEC,BURST}`.
+    fn from_layers(layers: &ConfigLayers) -> anyhow::Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            chat: load_rate_quota(layers, "CHAT", defaults.chat)?,
+            management: load_rate_quota(layers, "MANAGEMENT", defaults.management)?,
+            shutdown: load_rate_quota(layers, "SHUTDOWN", defaults.shutdown)?,
+        })
+    }
+}
+
+fn load_rate_quota(
+    layers: &ConfigLayers,
+    group: &str,
+    default: RateQuota,
+) -> anyhow::Result<RateQuota> {
+    let load = |suffix: &str, default: u32| -> anyhow::Result<u32> {
+        let name = format!("CLOTO_RATE_{}_{}", group, suffix);
+        let value = match layers.var(&name) {
+            Ok(v) => v
+                .parse::<u32>()
+                .with_context(|| format!("Failed to parse {}", name))?,
+            Err(_) => default,
+        };
+        if value == 0 || value > 10_000 {
+            anyhow::bail!("{} must be between 1 and 10000 (got {})", name, value);
+        }
+        Ok(value)
+    };
+    Ok(RateQuota {
+        per_second: load("PER_SEC", default.per_second)?,
+        burst: load("BURST", default.burst)?,
+    })
+}
+
 #[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct AppConfig {
     pub database_url: String,
+    /// Maximum number of pooled SQLite connections.
+    pub db_pool_size: u32,
+    /// How long a query waits for a free pooled connection before failing.
+    pub db_acquire_timeout_secs: u64,
     pub port: u16,
     pub bind_address: String,
     pub cors_origins: Vec<HeaderValue>,
     pub default_agent_id: String,
     pub allowed_hosts: Vec<String>,
     pub plugin_event_timeout_secs: u64,
+    /// Retries of a plugin's failed `on_event` before the event is
+    /// dead-lettered.
+    pub event_retry_max: u32,
+    /// Delay before the first retry, doubled on each further one.
+    pub event_retry_backoff_ms: u64,
     pub max_event_depth: u8,
     pub memory_context_limit: usize,
     pub admin_api_key: Option<String>,
     pub consensus_engines: Vec<String>,
+    /// Argument key fragments whose values are redacted in tool audit entries.
+    pub audit_redact_keys: Vec<String>,
     pub event_history_size: usize,
     pub event_retention_hours: u64,
+    /// Persist events to the `events` table so `GET /api/history` can query
+    /// past the in-memory ring buffer and across restarts.
+    pub event_journal_enabled: bool,
+    /// Days journaled events are kept before being pruned.
+    pub event_journal_retention_days: u64,
     pub max_agentic_iterations: u8,
     pub tool_execution_timeout_secs: u64,
+    /// How long a tool call listed in a server's `approval_required_tools`
+    /// waits for an administrator's decision before failing.
+    pub tool_approval_timeout_secs: u64,
+    /// Unsummarized chat messages a thread may hold before the oldest are
+    /// folded into a rolling summary (0 = disabled).
+    pub summary_threshold: usize,
+    /// Long-term memories an agent may hold in the MCP memory server before
+    /// the oldest are compacted into a summary entry (0 = disabled).
+    pub memory_compaction_threshold: usize,
     pub mcp_config_path: Option<String>,
+    /// Inbound webhook definitions (`adapter.webhook`).
+    pub webhooks_config_path: Option<String>,
     pub mcp_sdk_secret: Option<String>,
     /// YOLO mode: auto-approve all permission requests (ARCHITECTURE.md §5.7).
     /// SafetyGate remains active even in YOLO mode.
@@ -41,17 +243,74 @@ pub struct AppConfig {
     pub cron_check_interval_secs: u64,
     /// Port for internal LLM proxy (MGP §13.4).
     pub llm_proxy_port: u16,
+    /// OpenTelemetry trace export; `None` when disabled.
+    pub otlp: Option<OtlpConfig>,
+    /// Per-route-group API rate limits.
+    pub rate_limits: RateLimitConfig,
+    /// Requests slower than this are recorded in the slow-request log.
+    pub slow_request_threshold_ms: u64,
+    /// Maximum concurrent API requests before shedding load with 503.
+    pub max_in_flight_requests: usize,
+    /// Maximum concurrent chat requests (subset of the global limit).
+    pub max_in_flight_chat: usize,
+    /// Headless server mode: no desktop shell, the browser dashboard is the UI.
+    /// Requires an admin API key and stops on SIGTERM/Ctrl+C.
+    pub headless: bool,
+    /// How long shutdown waits for in-flight agentic runs and tool calls
+    /// before stopping anyway (0 = do not wait).
+    pub shutdown_drain_secs: u64,
+    /// How often (seconds) the `.env` file is checked for changes; 0 disables hot reload.
+    pub config_reload_interval_secs: u64,
+    /// The `cloto.toml` file that was read, if any.
+    pub config_file: Option<PathBuf>,
+    /// Where each explicitly set key came from; keys absent here use defaults.
+    pub sources: BTreeMap<String, ConfigSource>,
 }
 
 impl AppConfig {
-    #[allow(clippy::too_many_lines)]
+    /// Load configuration with precedence flags > env > `cloto.toml` > defaults.
     pub fn load() -> anyhow::Result<Self> {
-        let database_url = env::var("DATABASE_URL").unwrap_or_else(|_| {
+        let layers = ConfigLayers::load()?;
+        let mut config = Self::from_layers(&layers)?;
+        config.config_file = layers.file_path.clone();
+        config.sources = layers.into_sources()?;
+        Ok(config)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn from_layers(layers: &ConfigLayers) -> anyhow::Result<Self> {
+        let database_url = layers.var("DATABASE_URL").unwrap_or_else(|_| {
             let db_path = exe_dir().join("data").join("cloto_memories.db");
             format!("sqlite:{}", db_path.display())
         });
 
-        let admin_api_key = env::var("CLOTO_API_KEY").ok();
+        let db_pool_size = layers
+            .var("CLOTO_DB_POOL_SIZE")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<u32>()
+            .context("Failed to parse CLOTO_DB_POOL_SIZE")?;
+
+        if db_pool_size == 0 || db_pool_size > 100 {
+            anyhow::bail!(
+                "CLOTO_DB_POOL_SIZE must be between 1 and 100 (got {})",
+                db_pool_size
+            );
+        }
+
+        let db_acquire_timeout_secs = layers
+            .var("CLOTO_DB_ACQUIRE_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_DB_ACQUIRE_TIMEOUT_SECS")?;
+
+        if db_acquire_timeout_secs == 0 || db_acquire_timeout_secs > 600 {
+            anyhow::bail!(
+                "CLOTO_DB_ACQUIRE_TIMEOUT_SECS must be between 1 and 600 (got {})",
+                db_acquire_timeout_secs
+            );
+        }
+
+        let admin_api_key = layers.var("CLOTO_API_KEY").ok();
 
         if let Some(ref key) = admin_api_key {
             if key.len() < 32 {
@@ -59,10 +318,12 @@ impl AppConfig {
             }
         }
 
-        let default_agent_id =
-            env::var("DEFAULT_AGENT_ID").unwrap_or_else(|_| "agent.cloto_default".to_string());
+        let default_agent_id = layers
+            .var("DEFAULT_AGENT_ID")
+            .unwrap_or_else(|_| "agent.cloto_default".to_string());
 
-        let plugin_event_timeout_secs = env::var("PLUGIN_EVENT_TIMEOUT_SECS")
+        let plugin_event_timeout_secs = layers
+            .var("PLUGIN_EVENT_TIMEOUT_SECS")
             .unwrap_or_else(|_| "120".to_string())
             .parse::<u64>()
             .context("Failed to parse PLUGIN_EVENT_TIMEOUT_SECS")?;
@@ -75,7 +336,34 @@ impl AppConfig {
             );
         }
 
-        let max_event_depth = env::var("MAX_EVENT_DEPTH")
+        let event_retry_max = layers
+            .var("CLOTO_EVENT_RETRY_MAX")
+            .unwrap_or_else(|_| crate::managers::DEFAULT_EVENT_RETRY_MAX.to_string())
+            .parse::<u32>()
+            .context("Failed to parse CLOTO_EVENT_RETRY_MAX")?;
+
+        if event_retry_max > 10 {
+            anyhow::bail!(
+                "CLOTO_EVENT_RETRY_MAX must be between 0 and 10 (got {})",
+                event_retry_max
+            );
+        }
+
+        let event_retry_backoff_ms = layers
+            .var("CLOTO_EVENT_RETRY_BACKOFF_MS")
+            .unwrap_or_else(|_| crate::managers::DEFAULT_EVENT_RETRY_BACKOFF_MS.to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_EVENT_RETRY_BACKOFF_MS")?;
+
+        if event_retry_backoff_ms > 60_000 {
+            anyhow::bail!(
+                "CLOTO_EVENT_RETRY_BACKOFF_MS must be between 0 and 60000 (got {})",
+                event_retry_backoff_ms
+            );
+        }
+
+        let max_event_depth = layers
+            .var("MAX_EVENT_DEPTH")
             .unwrap_or_else(|_| "10".to_string())
             .parse::<u8>()
             .context("Failed to parse MAX_EVENT_DEPTH")?;
@@ -87,12 +375,13 @@ impl AppConfig {
             );
         }
 
-        let memory_context_limit = env::var("MEMORY_CONTEXT_LIMIT")
+        let memory_context_limit = layers
+            .var("MEMORY_CONTEXT_LIMIT")
             .unwrap_or_else(|_| "10".to_string())
             .parse::<usize>()
             .context("Failed to parse MEMORY_CONTEXT_LIMIT")?;
 
-        let port_str = env::var("PORT").unwrap_or_else(|_| "8081".to_string());
+        let port_str = layers.var("PORT").unwrap_or_else(|_| "8081".to_string());
         let port = port_str.parse::<u16>().map_err(|_| {
             anyhow::anyhow!(
                 "Invalid PORT value '{}': must be an integer between 1 and 65535",
@@ -106,7 +395,7 @@ impl AppConfig {
 
         // BIND_ADDRESS: defaults to 127.0.0.1 (loopback only) for safety.
         // Set to 0.0.0.0 explicitly in .env if network access from other hosts is required.
-        let bind_address = match env::var("BIND_ADDRESS") {
+        let bind_address = match layers.var("BIND_ADDRESS") {
             Ok(addr) => {
                 addr.parse::<std::net::IpAddr>()
                     .with_context(|| format!(
@@ -118,30 +407,23 @@ impl AppConfig {
             Err(_) => "127.0.0.1".to_string(),
         };
 
-        let cors_origins_str = env::var("CORS_ORIGINS")
+        let cors_origins_str = layers
+            .var("CORS_ORIGINS")
             .unwrap_or_else(|_| "http://localhost:5173,http://127.0.0.1:5173".to_string());
 
         // M-02: Skip invalid CORS origins with warning instead of failing entirely
         let cors_origins: Vec<HeaderValue> = cors_origins_str
             .split(',')
-            .filter_map(|s| {
-                let trimmed = s.trim();
-                // Reject non-HTTP(S) schemes (prevent file://, javascript://, data://)
-                if !trimmed.starts_with("http://") && !trimmed.starts_with("https://") {
-                    tracing::warn!("Skipping CORS origin with invalid scheme '{}': must be http:// or https://", trimmed);
-                    return None;
-                }
-                match trimmed.parse::<HeaderValue>() {
-                    Ok(v) => Some(v),
-                    Err(e) => {
-                        tracing::warn!("Skipping invalid CORS origin '{}': {}", trimmed, e);
-                        None
-                    }
+            .filter_map(|s| match parse_cors_origin(s.trim()) {
+                Ok(v) => Some(v),
+                Err(e) => {
+                    tracing::warn!("Skipping CORS origin: {}", e);
+                    None
                 }
             })
             .collect();
 
-        let allowed_hosts_str = env::var("ALLOWED_HOSTS").unwrap_or_default();
+        let allowed_hosts_str = layers.var("ALLOWED_HOSTS").unwrap_or_default();
         let allowed_hosts = if allowed_hosts_str.is_empty() {
             vec![]
         } else {
@@ -151,7 +433,8 @@ impl AppConfig {
                 .collect()
         };
 
-        let consensus_engines_str = env::var("CONSENSUS_ENGINES")
+        let consensus_engines_str = layers
+            .var("CONSENSUS_ENGINES")
             .unwrap_or_else(|_| "mind.deepseek,mind.cerebras".to_string());
         let consensus_engines = consensus_engines_str
             .split(',')
@@ -159,13 +442,23 @@ impl AppConfig {
             .filter(|s| !s.is_empty())
             .collect();
 
-        let event_history_size = env::var("EVENT_HISTORY_SIZE")
+        let audit_redact_keys = layers
+            .var("CLOTO_AUDIT_REDACT_KEYS")
+            .unwrap_or_else(|_| DEFAULT_AUDIT_REDACT_KEYS.to_string())
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let event_history_size = layers
+            .var("EVENT_HISTORY_SIZE")
             .unwrap_or_else(|_| "1000".to_string())
             .parse::<usize>()
             .context("Failed to parse EVENT_HISTORY_SIZE")?;
 
         // M-10: Configurable event retention period (default 24 hours)
-        let event_retention_hours = env::var("EVENT_RETENTION_HOURS")
+        let event_retention_hours = layers
+            .var("EVENT_RETENTION_HOURS")
             .unwrap_or_else(|_| "24".to_string())
             .parse::<u64>()
             .context("Failed to parse EVENT_RETENTION_HOURS")?;
@@ -177,7 +470,25 @@ impl AppConfig {
             );
         }
 
-        let max_agentic_iterations = env::var("CLOTO_MAX_AGENTIC_ITERATIONS")
+        let event_journal_enabled = layers
+            .var("CLOTO_EVENT_JOURNAL")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("Failed to parse CLOTO_EVENT_JOURNAL")?;
+        let event_journal_retention_days = layers
+            .var("CLOTO_EVENT_JOURNAL_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_EVENT_JOURNAL_RETENTION_DAYS")?;
+        if event_journal_retention_days == 0 || event_journal_retention_days > 3650 {
+            anyhow::bail!(
+                "CLOTO_EVENT_JOURNAL_RETENTION_DAYS must be between 1 and 3650 (got {})",
+                event_journal_retention_days
+            );
+        }
+
+        let max_agentic_iterations = layers
+            .var("CLOTO_MAX_AGENTIC_ITERATIONS")
             .unwrap_or_else(|_| "16".to_string())
             .parse::<u8>()
             .context("Failed to parse CLOTO_MAX_AGENTIC_ITERATIONS")?;
@@ -189,7 +500,8 @@ impl AppConfig {
             );
         }
 
-        let tool_execution_timeout_secs = env::var("CLOTO_TOOL_TIMEOUT_SECS")
+        let tool_execution_timeout_secs = layers
+            .var("CLOTO_TOOL_TIMEOUT_SECS")
             .unwrap_or_else(|_| "30".to_string())
             .parse::<u64>()
             .context("Failed to parse CLOTO_TOOL_TIMEOUT_SECS")?;
@@ -201,9 +513,52 @@ impl AppConfig {
             );
         }
 
-        let mcp_config_path = env::var("CLOTO_MCP_CONFIG").ok();
-        let mcp_sdk_secret = env::var("CLOTO_SDK_SECRET").ok();
-        let yolo_mode = env::var("CLOTO_YOLO")
+        let tool_approval_timeout_secs = layers
+            .var("CLOTO_TOOL_APPROVAL_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_TOOL_APPROVAL_TIMEOUT_SECS")?;
+
+        if tool_approval_timeout_secs == 0 || tool_approval_timeout_secs > 3600 {
+            anyhow::bail!(
+                "CLOTO_TOOL_APPROVAL_TIMEOUT_SECS must be between 1 and 3600 (got {})",
+                tool_approval_timeout_secs
+            );
+        }
+
+        let summary_threshold = layers
+            .var("CLOTO_SUMMARY_THRESHOLD")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<usize>()
+            .context("Failed to parse CLOTO_SUMMARY_THRESHOLD")?;
+
+        if summary_threshold != 0 && !(10..=10_000).contains(&summary_threshold) {
+            anyhow::bail!(
+                "CLOTO_SUMMARY_THRESHOLD must be 0 or between 10 and 10000 (got {})",
+                summary_threshold
+            );
+        }
+
+        let memory_compaction_threshold = layers
+            .var("CLOTO_MEMORY_COMPACTION_THRESHOLD")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<usize>()
+            .context("Failed to parse CLOTO_MEMORY_COMPACTION_THRESHOLD")?;
+
+        if memory_compaction_threshold != 0
+            && !(20..=100_000).contains(&memory_compaction_threshold)
+        {
+            anyhow::bail!(
+                "CLOTO_MEMORY_COMPACTION_THRESHOLD must be 0 or between 20 and 100000 (got {})",
+                memory_compaction_threshold
+            );
+        }
+
+        let mcp_config_path = layers.var("CLOTO_MCP_CONFIG").ok();
+        let webhooks_config_path = layers.var("CLOTO_WEBHOOKS_CONFIG").ok();
+        let mcp_sdk_secret = layers.var("CLOTO_SDK_SECRET").ok();
+        let yolo_mode = layers
+            .var("CLOTO_YOLO")
             .unwrap_or_else(|_| "false".to_string())
             .parse::<bool>()
             .unwrap_or(false);
@@ -212,47 +567,454 @@ impl AppConfig {
             tracing::warn!("YOLO mode enabled: MCP server permissions will be auto-approved");
         }
 
-        let cron_enabled = env::var("CLOTO_CRON_ENABLED")
+        let headless = layers
+            .var("CLOTO_HEADLESS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .unwrap_or(false);
+
+        let config_reload_interval_secs = layers
+            .var("CLOTO_CONFIG_RELOAD_INTERVAL_SECS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_CONFIG_RELOAD_INTERVAL_SECS")?;
+
+        let cron_enabled = layers
+            .var("CLOTO_CRON_ENABLED")
             .unwrap_or_else(|_| "true".to_string())
             .parse::<bool>()
             .unwrap_or(true);
-        let cron_check_interval_secs = env::var("CLOTO_CRON_INTERVAL")
+        let cron_check_interval_secs = layers
+            .var("CLOTO_CRON_INTERVAL")
             .unwrap_or_else(|_| "60".to_string())
             .parse::<u64>()
             .unwrap_or(60)
             .max(10); // minimum 10 seconds
 
-        let llm_proxy_port = env::var("CLOTO_LLM_PROXY_PORT")
+        let llm_proxy_port = layers
+            .var("CLOTO_LLM_PROXY_PORT")
             .unwrap_or_else(|_| "8082".to_string())
             .parse::<u16>()
             .unwrap_or(8082);
 
+        let max_in_flight_requests = layers
+            .var("CLOTO_MAX_IN_FLIGHT")
+            .unwrap_or_else(|_| "256".to_string())
+            .parse::<usize>()
+            .context("Failed to parse CLOTO_MAX_IN_FLIGHT")?;
+
+        if max_in_flight_requests == 0 || max_in_flight_requests > 10_000 {
+            anyhow::bail!(
+                "CLOTO_MAX_IN_FLIGHT must be between 1 and 10000 (got {})",
+                max_in_flight_requests
+            );
+        }
+
+        let max_in_flight_chat = layers
+            .var("CLOTO_MAX_IN_FLIGHT_CHAT")
+            .unwrap_or_else(|_| "32".to_string())
+            .parse::<usize>()
+            .context("Failed to parse CLOTO_MAX_IN_FLIGHT_CHAT")?;
+
+        if max_in_flight_chat == 0 || max_in_flight_chat > max_in_flight_requests {
+            anyhow::bail!(
+                "CLOTO_MAX_IN_FLIGHT_CHAT must be between 1 and CLOTO_MAX_IN_FLIGHT ({}) (got {})",
+                max_in_flight_requests,
+                max_in_flight_chat
+            );
+        }
+
+        let otlp = OtlpConfig::from_layers(layers)?;
+        let rate_limits = RateLimitConfig::from_layers(layers)?;
+
+        let slow_request_threshold_ms = layers
+            .var("CLOTO_SLOW_REQUEST_MS")
+            .unwrap_or_else(|_| "2000".to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_SLOW_REQUEST_MS")?;
+
+        if slow_request_threshold_ms == 0 || slow_request_threshold_ms > 600_000 {
+            anyhow::bail!(
+                "CLOTO_SLOW_REQUEST_MS must be between 1 and 600000 (got {})",
+                slow_request_threshold_ms
+            );
+        }
+
+        let shutdown_drain_secs = layers
+            .var("CLOTO_SHUTDOWN_DRAIN_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u64>()
+            .context("Failed to parse CLOTO_SHUTDOWN_DRAIN_SECS")?;
+
+        if shutdown_drain_secs > 600 {
+            anyhow::bail!(
+                "CLOTO_SHUTDOWN_DRAIN_SECS must be between 0 and 600 (got {})",
+                shutdown_drain_secs
+            );
+        }
+
         Ok(Self {
             database_url,
+            db_pool_size,
+            db_acquire_timeout_secs,
             port,
             bind_address,
             cors_origins,
             default_agent_id,
             allowed_hosts,
             plugin_event_timeout_secs,
+            event_retry_max,
+            event_retry_backoff_ms,
             max_event_depth,
             memory_context_limit,
             admin_api_key,
             consensus_engines,
+            audit_redact_keys,
             event_history_size,
             event_retention_hours,
+            event_journal_enabled,
+            event_journal_retention_days,
             max_agentic_iterations,
             tool_execution_timeout_secs,
+            tool_approval_timeout_secs,
+            summary_threshold,
+            memory_compaction_threshold,
             mcp_config_path,
+            webhooks_config_path,
             mcp_sdk_secret,
             yolo_mode,
             cron_enabled,
             cron_check_interval_secs,
             llm_proxy_port,
+            otlp,
+            rate_limits,
+            slow_request_threshold_ms,
+            max_in_flight_requests,
+            max_in_flight_chat,
+            headless,
+            shutdown_drain_secs,
+            config_reload_interval_secs,
+            config_file: None,
+            sources: BTreeMap::new(),
         })
     }
 }
 
+// ── Layered Configuration ──
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigSource {
+    Flag,
+    Env,
+    File,
+    Default,
+}
+
+/// `cloto.toml` keys (`section.key`) and the env var each one stands for.
+/// The env var name is the canonical key for flags, sources and the API.
+pub const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("server.port", "PORT"),
+    ("server.bind_address", "BIND_ADDRESS"),
+    ("server.cors_origins", "CORS_ORIGINS"),
+    ("server.headless", "CLOTO_HEADLESS"),
+    ("server.max_in_flight", "CLOTO_MAX_IN_FLIGHT"),
+    ("server.max_in_flight_chat", "CLOTO_MAX_IN_FLIGHT_CHAT"),
+    ("server.slow_request_ms", "CLOTO_SLOW_REQUEST_MS"),
+    ("server.shutdown_drain_secs", "CLOTO_SHUTDOWN_DRAIN_SECS"),
+    ("database.url", "DATABASE_URL"),
+    ("database.pool_size", "CLOTO_DB_POOL_SIZE"),
+    (
+        "database.acquire_timeout_secs",
+        "CLOTO_DB_ACQUIRE_TIMEOUT_SECS",
+    ),
+    ("security.api_key", "CLOTO_API_KEY"),
+    ("security.sdk_secret", "CLOTO_SDK_SECRET"),
+    ("security.allowed_hosts", "ALLOWED_HOSTS"),
+    ("security.yolo", "CLOTO_YOLO"),
+    ("security.audit_redact_keys", "CLOTO_AUDIT_REDACT_KEYS"),
+    ("agent.default_agent_id", "DEFAULT_AGENT_ID"),
+    ("agent.consensus_engines", "CONSENSUS_ENGINES"),
+    (
+        "agent.max_agentic_iterations",
+        "CLOTO_MAX_AGENTIC_ITERATIONS",
+    ),
+    ("agent.memory_context_limit", "MEMORY_CONTEXT_LIMIT"),
+    ("agent.tool_timeout_secs", "CLOTO_TOOL_TIMEOUT_SECS"),
+    (
+        "agent.tool_approval_timeout_secs",
+        "CLOTO_TOOL_APPROVAL_TIMEOUT_SECS",
+    ),
+    ("agent.summary_threshold", "CLOTO_SUMMARY_THRESHOLD"),
+    (
+        "agent.memory_compaction_threshold",
+        "CLOTO_MEMORY_COMPACTION_THRESHOLD",
+    ),
+    ("events.history_size", "EVENT_HISTORY_SIZE"),
+    ("events.retention_hours", "EVENT_RETENTION_HOURS"),
+    ("events.journal", "CLOTO_EVENT_JOURNAL"),
+    (
+        "events.journal_retention_days",
+        "CLOTO_EVENT_JOURNAL_RETENTION_DAYS",
+    ),
+    ("events.max_depth", "MAX_EVENT_DEPTH"),
+    ("events.plugin_timeout_secs", "PLUGIN_EVENT_TIMEOUT_SECS"),
+    ("events.retry_max", "CLOTO_EVENT_RETRY_MAX"),
+    ("events.retry_backoff_ms", "CLOTO_EVENT_RETRY_BACKOFF_MS"),
+    ("mcp.config", "CLOTO_MCP_CONFIG"),
+    ("webhooks.config", "CLOTO_WEBHOOKS_CONFIG"),
+    ("cron.enabled", "CLOTO_CRON_ENABLED"),
+    ("cron.interval_secs", "CLOTO_CRON_INTERVAL"),
+    ("llm_proxy.port", "CLOTO_LLM_PROXY_PORT"),
+    ("otlp.endpoint", "CLOTO_OTLP_ENDPOINT"),
+    ("otlp.headers", "CLOTO_OTLP_HEADERS"),
+    ("otlp.service_name", "CLOTO_OTLP_SERVICE_NAME"),
+    ("rate_limits.chat_per_sec", "CLOTO_RATE_CHAT_PER_SEC"),
+    ("rate_limits.chat_burst", "CLOTO_RATE_CHAT_BURST"),
+    (
+        "rate_limits.management_per_sec",
+        "CLOTO_RATE_MANAGEMENT_PER_SEC",
+    ),
+    (
+        "rate_limits.management_burst",
+        "CLOTO_RATE_MANAGEMENT_BURST",
+    ),
+    (
+        "rate_limits.shutdown_per_sec",
+        "CLOTO_RATE_SHUTDOWN_PER_SEC",
+    ),
+    ("rate_limits.shutdown_burst", "CLOTO_RATE_SHUTDOWN_BURST"),
+    (
+        "config.reload_interval_secs",
+        "CLOTO_CONFIG_RELOAD_INTERVAL_SECS",
+    ),
+];
+
+/// Tool argument keys redacted in the audit log unless `CLOTO_AUDIT_REDACT_KEYS` is set.
+const DEFAULT_AUDIT_REDACT_KEYS: &str =
+    "password,secret,token,api_key,apikey,authorization,credential,private_key,cookie";
+
+/// Keys whose values are never shown by `GET /api/system/config`.
+const SECRET_KEYS: &[&str] = &["CLOTO_API_KEY", "CLOTO_SDK_SECRET", "CLOTO_OTLP_HEADERS"];
+
+static CLI_OVERRIDES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Register command-line flag values (keyed by env var name). They take
+/// precedence over every other layer. Only the first call has an effect.
+pub fn set_cli_overrides(overrides: impl IntoIterator<Item = (String, String)>) {
+    let _ = CLI_OVERRIDES.set(overrides.into_iter().collect());
+}
+
+/// `cloto.toml` location: `--config` / `CLOTO_CONFIG_FILE` if given (must
+/// exist), otherwise `cloto.toml` in the working directory or next to the
+/// executable.
+fn config_file_path(flags: &HashMap<String, String>) -> anyhow::Result<Option<PathBuf>> {
+    let explicit = flags
+        .get("CLOTO_CONFIG_FILE")
+        .cloned()
+        .or_else(|| env::var("CLOTO_CONFIG_FILE").ok());
+    if let Some(path) = explicit {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            anyhow::bail!("Config file not found: {}", path.display());
+        }
+        return Ok(Some(path));
+    }
+    Ok([PathBuf::from("cloto.toml"), exe_dir().join("cloto.toml")]
+        .into_iter()
+        .find(|p| p.is_file()))
+}
+
+fn toml_scalar(key: &str, value: &toml::Value) -> anyhow::Result<String> {
+    Ok(match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Integer(i) => i.to_string(),
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| toml_scalar(key, item))
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .join(","),
+        other => anyhow::bail!("'{}': unsupported value type {}", key, other.type_str()),
+    })
+}
+
+/// Parse `cloto.toml` into env-var-keyed values. Unknown keys are errors so
+/// typos do not silently fall back to defaults.
+fn parse_config_file(content: &str) -> anyhow::Result<HashMap<String, String>> {
+    let table: toml::Table = toml::from_str(content)?;
+    let mut values = HashMap::new();
+    for (section, entries) in &table {
+        let toml::Value::Table(entries) = entries else {
+            anyhow::bail!("'{}': expected a [section]", section);
+        };
+        for (key, value) in entries {
+            let path = format!("{}.{}", section, key);
+            let Some((_, env_name)) = CONFIG_KEYS.iter().find(|(k, _)| *k == path) else {
+                anyhow::bail!("Unknown config key '{}'", path);
+            };
+            values.insert((*env_name).to_string(), toml_scalar(&path, value)?);
+        }
+    }
+    Ok(values)
+}
+
+/// Value lookup across flags, environment and `cloto.toml`, recording the
+/// source of every value that was found.
+pub struct ConfigLayers {
+    flags: HashMap<String, String>,
+    file: HashMap<String, String>,
+    file_path: Option<PathBuf>,
+    sources: RefCell<BTreeMap<String, ConfigSource>>,
+    errors: RefCell<Vec<String>>,
+}
+
+impl ConfigLayers {
+    pub fn load() -> anyhow::Result<Self> {
+        let flags = CLI_OVERRIDES.get().cloned().unwrap_or_default();
+        let file_path = config_file_path(&flags)?;
+        let file = match &file_path {
+            Some(path) => Self::read_file(path)?,
+            None => HashMap::new(),
+        };
+        Ok(Self {
+            flags,
+            file,
+            file_path,
+            sources: RefCell::new(BTreeMap::new()),
+            errors: RefCell::new(Vec::new()),
+        })
+    }
+
+    fn read_file(path: &Path) -> anyhow::Result<HashMap<String, String>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        parse_config_file(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Drop-in replacement for `env::var` honouring layer precedence.
+    pub fn var(&self, name: &str) -> Result<String, env::VarError> {
+        let found = if let Some(v) = self.flags.get(name) {
+            Some((v.clone(), ConfigSource::Flag))
+        } else if let Ok(v) = env::var(name) {
+            Some((v, ConfigSource::Env))
+        } else {
+            self.file.get(name).map(|v| (v.clone(), ConfigSource::File))
+        };
+        let (value, source) = found.ok_or(env::VarError::NotPresent)?;
+        self.sources.borrow_mut().insert(name.to_string(), source);
+        if !crate::secrets::is_reference(&value) {
+            return Ok(value);
+        }
+        // Resolution errors surface from `into_sources` so a broken
+        // reference fails the load instead of falling back to a default.
+        crate::secrets::resolve(&value).map_err(|e| {
+            self.errors.borrow_mut().push(format!("{}: {:#}", name, e));
+            env::VarError::NotPresent
+        })
+    }
+
+    fn into_sources(self) -> anyhow::Result<BTreeMap<String, ConfigSource>> {
+        let errors = self.errors.into_inner();
+        if !errors.is_empty() {
+            anyhow::bail!("Failed to resolve secrets: {}", errors.join("; "));
+        }
+        Ok(self.sources.into_inner())
+    }
+}
+
+impl AppConfig {
+    /// Effective value of a setting by env var name, for display.
+    fn effective_value(&self, key: &str) -> serde_json::Value {
+        use serde_json::json;
+        let joined = |items: &[String]| json!(items.join(","));
+        match key {
+            "PORT" => json!(self.port),
+            "BIND_ADDRESS" => json!(self.bind_address),
+            "CORS_ORIGINS" => json!(self
+                .cors_origins
+                .iter()
+                .filter_map(|o| o.to_str().ok())
+                .collect::<Vec<_>>()
+                .join(",")),
+            "CLOTO_HEADLESS" => json!(self.headless),
+            "CLOTO_SHUTDOWN_DRAIN_SECS" => json!(self.shutdown_drain_secs),
+            "CLOTO_MAX_IN_FLIGHT" => json!(self.max_in_flight_requests),
+            "CLOTO_MAX_IN_FLIGHT_CHAT" => json!(self.max_in_flight_chat),
+            "CLOTO_SLOW_REQUEST_MS" => json!(self.slow_request_threshold_ms),
+            "DATABASE_URL" => json!(self.database_url),
+            "CLOTO_DB_POOL_SIZE" => json!(self.db_pool_size),
+            "CLOTO_DB_ACQUIRE_TIMEOUT_SECS" => json!(self.db_acquire_timeout_secs),
+            "CLOTO_API_KEY" => json!(self.admin_api_key),
+            "CLOTO_SDK_SECRET" => json!(self.mcp_sdk_secret),
+            "ALLOWED_HOSTS" => joined(&self.allowed_hosts),
+            "CLOTO_YOLO" => json!(self.yolo_mode),
+            "CLOTO_AUDIT_REDACT_KEYS" => joined(&self.audit_redact_keys),
+            "DEFAULT_AGENT_ID" => json!(self.default_agent_id),
+            "CONSENSUS_ENGINES" => joined(&self.consensus_engines),
+            "CLOTO_MAX_AGENTIC_ITERATIONS" => json!(self.max_agentic_iterations),
+            "MEMORY_CONTEXT_LIMIT" => json!(self.memory_context_limit),
+            "CLOTO_TOOL_TIMEOUT_SECS" => json!(self.tool_execution_timeout_secs),
+            "CLOTO_TOOL_APPROVAL_TIMEOUT_SECS" => json!(self.tool_approval_timeout_secs),
+            "CLOTO_SUMMARY_THRESHOLD" => json!(self.summary_threshold),
+            "CLOTO_MEMORY_COMPACTION_THRESHOLD" => json!(self.memory_compaction_threshold),
+            "EVENT_HISTORY_SIZE" => json!(self.event_history_size),
+            "EVENT_RETENTION_HOURS" => json!(self.event_retention_hours),
+            "CLOTO_EVENT_JOURNAL" => json!(self.event_journal_enabled),
+            "CLOTO_EVENT_JOURNAL_RETENTION_DAYS" => json!(self.event_journal_retention_days),
+            "MAX_EVENT_DEPTH" => json!(self.max_event_depth),
+            "PLUGIN_EVENT_TIMEOUT_SECS" => json!(self.plugin_event_timeout_secs),
+            "CLOTO_EVENT_RETRY_MAX" => json!(self.event_retry_max),
+            "CLOTO_EVENT_RETRY_BACKOFF_MS" => json!(self.event_retry_backoff_ms),
+            "CLOTO_MCP_CONFIG" => json!(self.mcp_config_path),
+            "CLOTO_WEBHOOKS_CONFIG" => json!(self.webhooks_config_path),
+            "CLOTO_CRON_ENABLED" => json!(self.cron_enabled),
+            "CLOTO_CRON_INTERVAL" => json!(self.cron_check_interval_secs),
+            "CLOTO_LLM_PROXY_PORT" => json!(self.llm_proxy_port),
+            "CLOTO_OTLP_ENDPOINT" => json!(self.otlp.as_ref().map(|o| &o.endpoint)),
+            "CLOTO_OTLP_HEADERS" => json!(self.otlp.as_ref().map(|o| !o.headers.is_empty())),
+            "CLOTO_OTLP_SERVICE_NAME" => json!(self.otlp.as_ref().map(|o| &o.service_name)),
+            "CLOTO_RATE_CHAT_PER_SEC" => json!(self.rate_limits.chat.per_second),
+            "CLOTO_RATE_CHAT_BURST" => json!(self.rate_limits.chat.burst),
+            "CLOTO_RATE_MANAGEMENT_PER_SEC" => json!(self.rate_limits.management.per_second),
+            "CLOTO_RATE_MANAGEMENT_BURST" => json!(self.rate_limits.management.burst),
+            "CLOTO_RATE_SHUTDOWN_PER_SEC" => json!(self.rate_limits.shutdown.per_second),
+            "CLOTO_RATE_SHUTDOWN_BURST" => json!(self.rate_limits.shutdown.burst),
+            "CLOTO_CONFIG_RELOAD_INTERVAL_SECS" => json!(self.config_reload_interval_secs),
+            _ => serde_json::Value::Null,
+        }
+    }
+
+    /// Every setting with its effective value and source. Secrets are
+    /// reported only as set/unset.
+    #[must_use]
+    pub fn effective_settings(&self) -> Vec<serde_json::Value> {
+        CONFIG_KEYS
+            .iter()
+            .map(|(file_key, env_name)| {
+                let source = self
+                    .sources
+                    .get(*env_name)
+                    .copied()
+                    .unwrap_or(ConfigSource::Default);
+                let value = if SECRET_KEYS.contains(env_name) {
+                    let set = source != ConfigSource::Default;
+                    serde_json::json!(if set { "********" } else { "" })
+                } else {
+                    self.effective_value(env_name)
+                };
+                serde_json::json!({
+                    "key": env_name,
+                    "file_key": file_key,
+                    "value": value,
+                    "source": source,
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +1032,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_file_parsing() {
+        let values = parse_config_file(
+            "[server]\nport = 9000\ncors_origins = [\"http://a\", \"http://b\"]\n\n[security]\nyolo = true\n",
+        )
+        .unwrap();
+        assert_eq!(values["PORT"], "9000");
+        assert_eq!(values["CORS_ORIGINS"], "http://a,http://b");
+        assert_eq!(values["CLOTO_YOLO"], "true");
+
+        assert!(parse_config_file("[server]\nprot = 9000\n").is_err());
+        assert!(parse_config_file("port = 9000\n").is_err());
+    }
+
+    #[test]
+    fn test_layer_precedence() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let layers = ConfigLayers {
+            flags: HashMap::from([("PORT".to_string(), "1".to_string())]),
+            file: HashMap::from([
+                ("PORT".to_string(), "3".to_string()),
+                ("MAX_EVENT_DEPTH".to_string(), "7".to_string()),
+                ("CLOTO_API_KEY".to_string(), "secret".to_string()),
+            ]),
+            file_path: None,
+            sources: RefCell::new(BTreeMap::new()),
+            errors: RefCell::new(Vec::new()),
+        };
+        std::env::set_var("PORT", "2");
+        let _port = EnvGuard("PORT");
+        std::env::set_var("MAX_EVENT_DEPTH", "6");
+        let _depth = EnvGuard("MAX_EVENT_DEPTH");
+
+        let mut config = AppConfig::from_layers(&layers).unwrap();
+        config.sources = layers.into_sources().unwrap();
+        assert_eq!(config.port, 1);
+        assert_eq!(config.max_event_depth, 6);
+        assert_eq!(config.sources["PORT"], ConfigSource::Flag);
+        assert_eq!(config.sources["MAX_EVENT_DEPTH"], ConfigSource::Env);
+        assert_eq!(config.sources["CLOTO_API_KEY"], ConfigSource::File);
+
+        let settings = config.effective_settings();
+        let find = |key: &str| settings.iter().find(|s| s["key"] == key).cloned().unwrap();
+        assert_eq!(find("CLOTO_API_KEY")["value"], "********");
+        assert_eq!(find("EVENT_HISTORY_SIZE")["source"], "default");
+    }
+
     #[test]
     fn test_consensus_engines_parsing() {
         let _lock = ENV_LOCK.lock().unwrap();
@@ -309,4 +1118,40 @@ mod tests {
         assert_eq!(config.consensus_engines[1], "mind.anthropic");
         assert_eq!(config.consensus_engines[2], "mind.openai");
     }
+
+    #[test]
+    fn test_otlp_header_list_parsing() {
+        let headers = parse_header_list(" authorization=Bearer abc , x-scope=team=a ,").unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["authorization"], "Bearer abc");
+        assert_eq!(headers["x-scope"], "team=a");
+
+        assert!(parse_header_list("").unwrap().is_empty());
+        assert!(parse_header_list("missing-separator").is_err());
+        assert!(parse_header_list("=value").is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_overrides() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        std::env::set_var("CLOTO_RATE_CHAT_PER_SEC", "50");
+        let _guard = EnvGuard("CLOTO_RATE_CHAT_PER_SEC");
+
+        let config = AppConfig::load().unwrap();
+        assert_eq!(config.rate_limits.chat.per_second, 50);
+        assert_eq!(config.rate_limits.chat.burst, 40);
+        assert_eq!(
+            config.rate_limits.management,
+            RateLimitConfig::default().management
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_rejects_zero() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        std::env::set_var("CLOTO_RATE_SHUTDOWN_BURST", "0");
+        let _guard = EnvGuard("CLOTO_RATE_SHUTDOWN_BURST");
+
+        assert!(AppConfig::load().is_err());
+    }
 }
@@ -0,0 +1,253 @@
+//! Hot reload of `AppConfig` from the `.env` and `cloto.toml` files.
+//!
+//! Both files are polled every `CLOTO_CONFIG_RELOAD_INTERVAL_SECS`. On change
+//! `.env` is re-read over the process environment, `AppConfig` is rebuilt with
+//! the usual layer precedence and the
+//! settings that are safe to change at runtime are applied in place. Every
+//! reload is announced with a `SystemNotification` listing the applied and
+//! the restart-required changes. Keys removed from the file keep their last
+//! value until the next restart.
+
+use axum::http::HeaderValue;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::config::AppConfig;
+use crate::events::EventProcessor;
+use crate::handlers::system::SystemHandler;
+use crate::{AppState, EnvelopedEvent};
+
+/// Shared, reloadable list of allowed CORS origins.
+pub type CorsOrigins = Arc<std::sync::RwLock<Vec<HeaderValue>>>;
+
+/// Components whose settings are updated in place on reload.
+pub struct ReloadTargets {
+    pub state: Arc<AppState>,
+    pub processor: Arc<EventProcessor>,
+    pub system_handler: Arc<SystemHandler>,
+    pub cors_origins: CorsOrigins,
+}
+
+/// Settings that differ between two configurations, by env var name.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    pub applied: Vec<&'static str>,
+    pub restart_required: Vec<&'static str>,
+}
+
+impl ConfigChanges {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.restart_required.is_empty()
+    }
+}
+
+/// Compare two configurations and classify each changed setting.
+#[must_use]
+pub fn diff(old: &AppConfig, new: &AppConfig) -> ConfigChanges {
+    let mut changes = ConfigChanges::default();
+    macro_rules! check {
+        ($list:ident: $($field:ident => $name:literal),* $(,)?) => {
+            $(if old.$field != new.$field {
+                changes.$list.push($name);
+            })*
+        };
+    }
+    check!(applied:
+        rate_limits => "CLOTO_RATE_*",
+        cors_origins => "CORS_ORIGINS",
+        event_history_size => "EVENT_HISTORY_SIZE",
+        plugin_event_timeout_secs => "PLUGIN_EVENT_TIMEOUT_SECS",
+        event_retry_max => "CLOTO_EVENT_RETRY_MAX",
+        event_retry_backoff_ms => "CLOTO_EVENT_RETRY_BACKOFF_MS",
+        tool_execution_timeout_secs => "CLOTO_TOOL_TIMEOUT_SECS",
+        slow_request_threshold_ms => "CLOTO_SLOW_REQUEST_MS",
+        shutdown_drain_secs => "CLOTO_SHUTDOWN_DRAIN_SECS",
+    );
+    check!(restart_required:
+        database_url => "DATABASE_URL",
+        db_pool_size => "CLOTO_DB_POOL_SIZE",
+        db_acquire_timeout_secs => "CLOTO_DB_ACQUIRE_TIMEOUT_SECS",
+        port => "PORT",
+        bind_address => "BIND_ADDRESS",
+        default_agent_id => "DEFAULT_AGENT_ID",
+        allowed_hosts => "ALLOWED_HOSTS",
+        max_event_depth => "MAX_EVENT_DEPTH",
+        memory_context_limit => "MEMORY_CONTEXT_LIMIT",
+        admin_api_key => "CLOTO_API_KEY",
+        consensus_engines => "CONSENSUS_ENGINES",
+        audit_redact_keys => "CLOTO_AUDIT_REDACT_KEYS",
+        event_retention_hours => "EVENT_RETENTION_HOURS",
+        event_journal_enabled => "CLOTO_EVENT_JOURNAL",
+        event_journal_retention_days => "CLOTO_EVENT_JOURNAL_RETENTION_DAYS",
+        max_agentic_iterations => "CLOTO_MAX_AGENTIC_ITERATIONS",
+        tool_approval_timeout_secs => "CLOTO_TOOL_APPROVAL_TIMEOUT_SECS",
+        summary_threshold => "CLOTO_SUMMARY_THRESHOLD",
+        memory_compaction_threshold => "CLOTO_MEMORY_COMPACTION_THRESHOLD",
+        mcp_config_path => "CLOTO_MCP_CONFIG",
+        webhooks_config_path => "CLOTO_WEBHOOKS_CONFIG",
+        mcp_sdk_secret => "CLOTO_SDK_SECRET",
+        yolo_mode => "CLOTO_YOLO",
+        cron_enabled => "CLOTO_CRON_ENABLED",
+        cron_check_interval_secs => "CLOTO_CRON_INTERVAL",
+        llm_proxy_port => "CLOTO_LLM_PROXY_PORT",
+        otlp => "CLOTO_OTLP_*",
+        max_in_flight_requests => "CLOTO_MAX_IN_FLIGHT",
+        max_in_flight_chat => "CLOTO_MAX_IN_FLIGHT_CHAT",
+        headless => "CLOTO_HEADLESS",
+        config_reload_interval_secs => "CLOTO_CONFIG_RELOAD_INTERVAL_SECS",
+    );
+    changes
+}
+
+fn apply(targets: &ReloadTargets, new: &AppConfig) {
+    let state = &targets.state;
+    state.rate_limiter.update(&new.rate_limits);
+    if let Ok(mut origins) = targets.cors_origins.write() {
+        origins.clone_from(&new.cors_origins);
+    }
+    targets
+        .processor
+        .set_max_history_size(new.event_history_size);
+    state.registry.event_timeout_secs.store(
+        new.plugin_event_timeout_secs,
+        std::sync::atomic::Ordering::Relaxed,
+    );
+    state
+        .registry
+        .set_event_retry(new.event_retry_max, new.event_retry_backoff_ms);
+    targets
+        .system_handler
+        .set_tool_execution_timeout_secs(new.tool_execution_timeout_secs);
+    state
+        .slow_requests
+        .set_threshold_ms(new.slow_request_threshold_ms);
+
+    if let Ok(mut runtime) = state.runtime_config.write() {
+        runtime.rate_limits = new.rate_limits;
+        runtime.cors_origins.clone_from(&new.cors_origins);
+        runtime.event_history_size = new.event_history_size;
+        runtime.plugin_event_timeout_secs = new.plugin_event_timeout_secs;
+        runtime.event_retry_max = new.event_retry_max;
+        runtime.event_retry_backoff_ms = new.event_retry_backoff_ms;
+        runtime.tool_execution_timeout_secs = new.tool_execution_timeout_secs;
+        runtime.slow_request_threshold_ms = new.slow_request_threshold_ms;
+        runtime.shutdown_drain_secs = new.shutdown_drain_secs;
+        runtime.sources.clone_from(&new.sources);
+    }
+}
+
+async fn notify(state: &AppState, message: String) {
+    let event = EnvelopedEvent::system(cloto_shared::ClotoEventData::SystemNotification(message));
+    if let Err(e) = state.event_tx.send(event).await {
+        warn!("Failed to publish config reload notification: {}", e);
+    }
+}
+
+async fn reload(targets: &ReloadTargets, env_file: Option<&Path>, current: &mut AppConfig) {
+    let loaded = env_file
+        .map_or(Ok(()), dotenvy::from_path_override)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| AppConfig::load());
+    let new = match loaded {
+        Ok(new) => new,
+        Err(e) => {
+            warn!("Config reload failed: {:#}", e);
+            notify(
+                &targets.state,
+                format!(
+                    "Configuration reload failed, keeping current settings: {:#}",
+                    e
+                ),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let changes = diff(current, &new);
+    if changes.is_empty() {
+        return;
+    }
+    apply(targets, &new);
+    *current = new;
+
+    let none = || "none".to_string();
+    let applied = Some(changes.applied.join(", ")).filter(|s| !s.is_empty());
+    let restart = Some(changes.restart_required.join(", ")).filter(|s| !s.is_empty());
+    info!(
+        applied = applied.as_deref().unwrap_or("none"),
+        restart_required = restart.as_deref().unwrap_or("none"),
+        "🔄 Configuration reloaded"
+    );
+    notify(
+        &targets.state,
+        format!(
+            "Configuration reloaded. Applied: {}. Restart required: {}.",
+            applied.unwrap_or_else(none),
+            restart.unwrap_or_else(none)
+        ),
+    )
+    .await;
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Poll the `.env` and `cloto.toml` files and apply configuration changes
+/// until shutdown.
+pub fn spawn_config_watcher(
+    targets: ReloadTargets,
+    env_file: Option<PathBuf>,
+    config_file: Option<PathBuf>,
+    interval_secs: u64,
+) {
+    let shutdown = targets.state.shutdown.clone();
+    let mut current = targets.state.config.clone();
+    let paths: Vec<PathBuf> = env_file.iter().chain(&config_file).cloned().collect();
+    tokio::spawn(async move {
+        let mut last_modified: Vec<_> = paths.iter().map(|p| modified(p)).collect();
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+        for path in &paths {
+            info!("👀 Watching {} for configuration changes", path.display());
+        }
+        loop {
+            tokio::select! {
+                () = shutdown.notified() => break,
+                _ = interval.tick() => {
+                    let now: Vec<_> = paths.iter().map(|p| modified(p)).collect();
+                    if now != last_modified && now.iter().any(Option::is_some) {
+                        last_modified = now;
+                        reload(&targets, env_file.as_deref(), &mut current).await;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_classifies_changes() {
+        let old = AppConfig::load().unwrap();
+        let mut new = old.clone();
+        assert!(diff(&old, &new).is_empty());
+
+        new.event_history_size += 1;
+        new.cors_origins = vec![HeaderValue::from_static("https://example.com")];
+        new.port = old.port.wrapping_add(1);
+        new.rate_limits.chat.burst += 1;
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes.applied,
+            vec!["CLOTO_RATE_*", "CORS_ORIGINS", "EVENT_HISTORY_SIZE"]
+        );
+        assert_eq!(changes.restart_required, vec!["PORT"]);
+    }
+}
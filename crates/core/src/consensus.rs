@@ -2,11 +2,16 @@
 //!
 //! Ported from `plugins/moderator/src/lib.rs` (~150 lines of state machine).
 //! Manages multi-engine consensus sessions: collecting proposals from engines,
-//! then synthesizing a unified response via a designated synthesizer engine.
+//! then combining them according to the session's `ConsensusStrategy`
+//! (synthesis, majority vote, ranked choice, a judge's pick, or debate
+//! rounds followed by synthesis). The final `ThoughtResponse` carries the
+//! per-engine results in its `metadata`.
 
 use cloto_shared::{
-    AgentMetadata, ClotoEvent, ClotoEventData, ClotoId, ClotoMessage, MessageSource,
+    AgentMetadata, ClotoEvent, ClotoEventData, ClotoId, ClotoMessage, ConsensusStrategy,
+    MessageSource,
 };
+use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -15,15 +20,24 @@ use tracing::{info, warn};
 /// Named constant for the synthetic consensus agent (prevents type confusion).
 const SYSTEM_CONSENSUS_AGENT: &str = "system.consensus";
 
+/// Agents the orchestrator asks on its own behalf. Their responses belong to
+/// a later phase of a session, never to the initial proposals.
+const SYNTHESIZER_AGENT: &str = "agent.synthesizer";
+const JUDGE_AGENT: &str = "agent.judge";
+const VOTER_AGENT: &str = "agent.voter";
+const DEBATER_AGENT: &str = "agent.debater";
+
 // ============================================================
 // Configuration
 // ============================================================
 
 #[derive(Clone)]
 pub struct ConsensusConfig {
-    /// Engine ID used for synthesis. Empty = use first engine from ConsensusRequested.
+    /// Engine ID used for synthesis (and judging, unless the strategy names a
+    /// judge). Empty = use first engine from ConsensusRequested.
     pub synthesizer_engine: String,
-    /// Minimum proposals required before synthesis starts.
+    /// Minimum proposals required before synthesis starts. Other strategies
+    /// wait for every requested engine.
     pub min_proposals: usize,
     /// Session timeout in seconds.
     pub session_timeout_secs: u64,
@@ -44,18 +58,447 @@ impl Default for ConsensusConfig {
 // ============================================================
 
 struct Proposal {
+    engine_id: String,
     content: String,
 }
 
-enum SessionState {
+enum Phase {
     /// Collecting proposals from engines.
-    Collecting {
-        proposals: Vec<Proposal>,
-        fallback_engine: String,
-        created_at: std::time::Instant,
-    },
+    Collecting,
+    /// Collecting revised answers for debate round `round` (the proposals
+    /// are round 1).
+    Debating { round: u8, revised: Vec<Proposal> },
+    /// Collecting ranked-choice ballots, one per proposing engine.
+    Balloting { ballots: Vec<(String, Vec<usize>)> },
     /// Waiting for the synthesizer to produce a final response.
-    Synthesizing { created_at: std::time::Instant },
+    Synthesizing { synthesizer: String },
+    /// Waiting for the judge to pick the best proposal.
+    Judging { judge: String },
+}
+
+struct Session {
+    strategy: ConsensusStrategy,
+    task: String,
+    /// Engines asked for a proposal.
+    engine_count: usize,
+    fallback_engine: String,
+    /// Current answers, in arrival order.
+    proposals: Vec<Proposal>,
+    /// Answers of earlier debate rounds.
+    earlier_rounds: Vec<Vec<Proposal>>,
+    phase: Phase,
+    created_at: std::time::Instant,
+}
+
+/// A question the orchestrator puts to one engine.
+struct Request {
+    agent_id: &'static str,
+    engine_id: String,
+    prompt: String,
+}
+
+/// What a session does after a response.
+enum Step {
+    Wait,
+    Ask(Vec<Request>),
+    Done {
+        content: String,
+        metadata: serde_json::Value,
+    },
+}
+
+impl Session {
+    fn on_response(
+        &mut self,
+        agent_id: &str,
+        engine_id: &str,
+        content: &str,
+        min_proposals: usize,
+        synthesizer: &str,
+    ) -> Step {
+        match &mut self.phase {
+            Phase::Collecting => {
+                if [SYNTHESIZER_AGENT, JUDGE_AGENT, VOTER_AGENT, DEBATER_AGENT].contains(&agent_id)
+                {
+                    return Step::Wait;
+                }
+                self.proposals.push(Proposal {
+                    engine_id: engine_id.to_string(),
+                    content: content.to_string(),
+                });
+                let quorum = match self.strategy {
+                    ConsensusStrategy::Synthesis => min_proposals,
+                    _ => self.engine_count.max(1),
+                };
+                info!(
+                    "📥 Collected proposal from {} ({}/{})",
+                    engine_id,
+                    self.proposals.len(),
+                    quorum,
+                );
+                if self.proposals.len() < quorum {
+                    return Step::Wait;
+                }
+                self.proposals_complete(synthesizer)
+            }
+
+            Phase::Debating { round, revised } => {
+                if agent_id != DEBATER_AGENT || revised.iter().any(|p| p.engine_id == engine_id) {
+                    return Step::Wait;
+                }
+                revised.push(Proposal {
+                    engine_id: engine_id.to_string(),
+                    content: content.to_string(),
+                });
+                if revised.len() < self.proposals.len() {
+                    return Step::Wait;
+                }
+                let next_round = *round + 1;
+                let revised = std::mem::take(revised);
+                let previous = std::mem::replace(&mut self.proposals, revised);
+                self.earlier_rounds.push(previous);
+                self.debate_round(next_round, synthesizer)
+            }
+
+            Phase::Balloting { ballots } => {
+                if agent_id != VOTER_AGENT || ballots.iter().any(|(e, _)| e == engine_id) {
+                    return Step::Wait;
+                }
+                ballots.push((
+                    engine_id.to_string(),
+                    parse_ranking(content, self.proposals.len()),
+                ));
+                if ballots.len() < self.proposals.len() {
+                    return Step::Wait;
+                }
+                let ballots = std::mem::take(ballots);
+                self.ranked_choice_result(&ballots)
+            }
+
+            Phase::Synthesizing { synthesizer } => {
+                if agent_id != SYNTHESIZER_AGENT {
+                    return Step::Wait;
+                }
+                info!("🏁 Synthesis complete via {}", engine_id);
+                let synthesizer = synthesizer.clone();
+                let mut metadata = self.metadata(self.results());
+                metadata["synthesizer"] = json!(synthesizer);
+                Step::Done {
+                    content: content.to_string(),
+                    metadata,
+                }
+            }
+
+            Phase::Judging { judge } => {
+                if agent_id != JUDGE_AGENT {
+                    return Step::Wait;
+                }
+                let judge = judge.clone();
+                let pick = parse_ranking(content, self.proposals.len())
+                    .first()
+                    .copied();
+                if pick.is_none() {
+                    warn!(judge = %judge, "Judge named no valid answer; using the first proposal");
+                }
+                let winner = pick.unwrap_or(0);
+                let mut results = self.results();
+                results[winner]["winner"] = json!(true);
+                let mut metadata = self.metadata(results);
+                metadata["judge"] = json!(judge);
+                metadata["judge_reply"] = json!(content);
+                Step::Done {
+                    content: self.proposals[winner].content.clone(),
+                    metadata,
+                }
+            }
+        }
+    }
+
+    /// All proposals are in; start the strategy's next phase.
+    fn proposals_complete(&mut self, synthesizer: &str) -> Step {
+        match &self.strategy {
+            ConsensusStrategy::Synthesis => self.synthesize(synthesizer),
+            ConsensusStrategy::MajorityVote => self.majority_vote_result(),
+            ConsensusStrategy::RankedChoice => {
+                self.phase = Phase::Balloting {
+                    ballots: Vec::new(),
+                };
+                let prompt = format!(
+                    "{}\n\nRank the following answers from best to worst. Reply with the answer numbers only, best first (e.g. `2, 1, 3`).\n\n{}",
+                    self.task,
+                    self.numbered_proposals()
+                );
+                Step::Ask(
+                    self.proposals
+                        .iter()
+                        .map(|p| Request {
+                            agent_id: VOTER_AGENT,
+                            engine_id: p.engine_id.clone(),
+                            prompt: prompt.clone(),
+                        })
+                        .collect(),
+                )
+            }
+            ConsensusStrategy::BestOfN { judge } => {
+                let judge = judge.clone().unwrap_or_else(|| synthesizer.to_string());
+                info!(judge = %judge, "⚖️ Asking judge to pick the best answer...");
+                self.phase = Phase::Judging {
+                    judge: judge.clone(),
+                };
+                Step::Ask(vec![Request {
+                    agent_id: JUDGE_AGENT,
+                    engine_id: judge,
+                    prompt: format!(
+                        "{}\n\nYou are a judge. Pick the best of the following answers. Reply with the number of the best answer only.\n\n{}",
+                        self.task,
+                        self.numbered_proposals()
+                    ),
+                }])
+            }
+            ConsensusStrategy::Debate { .. } => self.debate_round(2, synthesizer),
+        }
+    }
+
+    fn synthesize(&mut self, synthesizer: &str) -> Step {
+        info!(synthesizer = %synthesizer, "⚗️ Starting synthesis phase...");
+        let combined_views = self
+            .proposals
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("## Opinion {}:\n{}", i + 1, p.content))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        self.phase = Phase::Synthesizing {
+            synthesizer: synthesizer.to_string(),
+        };
+        Step::Ask(vec![Request {
+            agent_id: SYNTHESIZER_AGENT,
+            engine_id: synthesizer.to_string(),
+            prompt: format!(
+                "You are a wise moderator. Synthesize the following opinions into a single, coherent conclusion.\n\n{}",
+                combined_views
+            ),
+        }])
+    }
+
+    /// Ask every engine to revise its answer, or synthesize once the last
+    /// round is done.
+    fn debate_round(&mut self, round: u8, synthesizer: &str) -> Step {
+        let rounds = match self.strategy {
+            ConsensusStrategy::Debate { rounds } => rounds,
+            _ => 1,
+        };
+        if round > rounds {
+            return self.synthesize(synthesizer);
+        }
+        info!("🗣️ Debate round {}/{}", round, rounds);
+        self.phase = Phase::Debating {
+            round,
+            revised: Vec::new(),
+        };
+        Step::Ask(
+            self.proposals
+                .iter()
+                .map(|own| {
+                    let others = self
+                        .proposals
+                        .iter()
+                        .filter(|p| p.engine_id != own.engine_id)
+                        .map(|p| format!("## {}:\n{}", p.engine_id, p.content))
+                        .collect::<Vec<_>>()
+                        .join("\n\n");
+                    Request {
+                        agent_id: DEBATER_AGENT,
+                        engine_id: own.engine_id.clone(),
+                        prompt: format!(
+                            "{}\n\nYour previous answer:\n{}\n\nOther participants answered:\n\n{}\n\nConsider their arguments and give your revised answer.",
+                            self.task, own.content, others
+                        ),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// The answer given by the most engines wins; ties go to the earliest.
+    fn majority_vote_result(&self) -> Step {
+        let keys: Vec<String> = self
+            .proposals
+            .iter()
+            .map(|p| normalize_answer(&p.content))
+            .collect();
+        let votes: Vec<usize> = keys
+            .iter()
+            .map(|k| keys.iter().filter(|other| *other == k).count())
+            .collect();
+        let winner = (0..keys.len())
+            .max_by_key(|&i| (votes[i], std::cmp::Reverse(i)))
+            .unwrap_or(0);
+
+        let mut results = self.results();
+        for (i, result) in results.iter_mut().enumerate() {
+            result["votes"] = json!(votes[i]);
+            result["winner"] = json!(keys[i] == keys[winner]);
+        }
+        info!("🗳️ Majority vote: {} of {}", votes[winner], keys.len());
+        Step::Done {
+            content: self.proposals[winner].content.clone(),
+            metadata: self.metadata(results),
+        }
+    }
+
+    fn ranked_choice_result(&self, ballots: &[(String, Vec<usize>)]) -> Step {
+        let rankings: Vec<Vec<usize>> = ballots.iter().map(|(_, b)| b.clone()).collect();
+        let (winner, eliminated) = instant_runoff(self.proposals.len(), &rankings);
+
+        let engine = |i: usize| self.proposals[i].engine_id.clone();
+        let mut results = self.results();
+        for (i, result) in results.iter_mut().enumerate() {
+            let ballot = ballots
+                .iter()
+                .find(|(e, _)| *e == self.proposals[i].engine_id)
+                .map(|(_, b)| b.iter().map(|&c| engine(c)).collect::<Vec<_>>())
+                .unwrap_or_default();
+            result["ballot"] = json!(ballot);
+            result["winner"] = json!(i == winner);
+        }
+        let mut metadata = self.metadata(results);
+        metadata["eliminated"] = json!(eliminated.into_iter().map(engine).collect::<Vec<_>>());
+        info!(
+            "🗳️ Ranked choice winner: {}",
+            self.proposals[winner].engine_id
+        );
+        Step::Done {
+            content: self.proposals[winner].content.clone(),
+            metadata,
+        }
+    }
+
+    /// `## Answer N:` sections, numbered from 1 in arrival order.
+    fn numbered_proposals(&self) -> String {
+        self.proposals
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("## Answer {}:\n{}", i + 1, p.content))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// One entry per proposing engine. Debates also list every round's answer.
+    fn results(&self) -> Vec<serde_json::Value> {
+        self.proposals
+            .iter()
+            .map(|p| {
+                let mut result = json!({ "engine_id": p.engine_id, "content": p.content });
+                if !self.earlier_rounds.is_empty() {
+                    let answers: Vec<&str> = self
+                        .earlier_rounds
+                        .iter()
+                        .filter_map(|round| round.iter().find(|r| r.engine_id == p.engine_id))
+                        .chain(std::iter::once(p))
+                        .map(|r| r.content.as_str())
+                        .collect();
+                    result["answers"] = json!(answers);
+                }
+                result
+            })
+            .collect()
+    }
+
+    fn metadata(&self, results: Vec<serde_json::Value>) -> serde_json::Value {
+        let mut metadata = json!({
+            "strategy": self.strategy.name(),
+            "results": results,
+        });
+        if let ConsensusStrategy::Debate { rounds } = self.strategy {
+            metadata["rounds"] = json!(rounds);
+        }
+        metadata
+    }
+}
+
+/// Zero-based answer indices in the order `text` names them (1-based),
+/// ignoring numbers out of range and repeats.
+fn parse_ranking(text: &str, candidates: usize) -> Vec<usize> {
+    let mut ranking = Vec::new();
+    for number in text.split(|c: char| !c.is_ascii_digit()) {
+        if let Ok(n) = number.parse::<usize>() {
+            if (1..=candidates).contains(&n) && !ranking.contains(&(n - 1)) {
+                ranking.push(n - 1);
+            }
+        }
+    }
+    ranking
+}
+
+/// Instant-runoff count. Returns the winner and the eliminated candidates in
+/// order. Ties keep the earlier candidate.
+fn instant_runoff(candidates: usize, ballots: &[Vec<usize>]) -> (usize, Vec<usize>) {
+    let mut remaining = vec![true; candidates];
+    let mut eliminated = Vec::new();
+    loop {
+        let mut counts = vec![0usize; candidates];
+        let mut active = 0;
+        for ballot in ballots {
+            if let Some(&choice) = ballot.iter().find(|&&c| remaining[c]) {
+                counts[choice] += 1;
+                active += 1;
+            }
+        }
+        let live: Vec<usize> = (0..candidates).filter(|&c| remaining[c]).collect();
+        let leader = live
+            .iter()
+            .copied()
+            .max_by_key(|&c| (counts[c], std::cmp::Reverse(c)))
+            .unwrap_or(0);
+        if live.len() <= 1 || counts[leader] * 2 > active {
+            return (leader, eliminated);
+        }
+        let last = live
+            .iter()
+            .copied()
+            .min_by_key(|&c| (counts[c], std::cmp::Reverse(c)))
+            .unwrap_or(leader);
+        remaining[last] = false;
+        eliminated.push(last);
+    }
+}
+
+/// Case, punctuation and whitespace differences don't split a vote.
+fn normalize_answer(content: &str) -> String {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn thought_request(request: Request) -> ClotoEventData {
+    let (name, description) = match request.agent_id {
+        SYNTHESIZER_AGENT => ("Synthesizer", "AI Moderator"),
+        JUDGE_AGENT => ("Judge", "Consensus judge"),
+        VOTER_AGENT => ("Voter", "Consensus voter"),
+        _ => ("Debater", "Consensus debater"),
+    };
+    let agent = AgentMetadata {
+        id: request.agent_id.to_string(),
+        name: name.to_string(),
+        description: description.to_string(),
+        enabled: true,
+        last_seen: 0,
+        status: "online".to_string(),
+        default_engine_id: Some(request.engine_id.clone()),
+        required_capabilities: vec![],
+        metadata: HashMap::new(),
+        system_prompt: None,
+    };
+    ClotoEventData::ThoughtRequested {
+        agent,
+        engine_id: request.engine_id,
+        message: ClotoMessage::new(MessageSource::System, request.prompt),
+        context: vec![],
+    }
 }
 
 // ============================================================
@@ -63,7 +506,7 @@ enum SessionState {
 // ============================================================
 
 pub struct ConsensusOrchestrator {
-    sessions: RwLock<HashMap<ClotoId, SessionState>>,
+    sessions: RwLock<HashMap<ClotoId, Session>>,
     config: RwLock<ConsensusConfig>,
 }
 
@@ -83,25 +526,31 @@ impl ConsensusOrchestrator {
         *self.config.write().await = config;
     }
 
-    /// Handle a consensus-related event. Returns an optional response event.
-    pub async fn handle_event(&self, event: &ClotoEvent) -> Option<ClotoEventData> {
+    /// Handle a consensus-related event. Returns the events to publish in
+    /// response: requests to engines, or the final `ThoughtResponse`.
+    pub async fn handle_event(&self, event: &ClotoEvent) -> Vec<ClotoEventData> {
         match &event.data {
             ClotoEventData::ConsensusRequested {
-                task: _,
+                task,
                 engine_ids,
+                strategy,
             } => {
-                self.on_consensus_requested(event.trace_id, engine_ids)
-                    .await
+                self.on_consensus_requested(event.trace_id, task, engine_ids, strategy)
+                    .await;
+                Vec::new()
             }
 
             ClotoEventData::ThoughtResponse {
-                agent_id, content, ..
+                agent_id,
+                engine_id,
+                content,
+                ..
             } => {
-                self.on_thought_response(event.trace_id, agent_id, content)
+                self.on_thought_response(event.trace_id, agent_id, engine_id, content)
                     .await
             }
 
-            _ => None,
+            _ => Vec::new(),
         }
     }
 
@@ -110,156 +559,83 @@ impl ConsensusOrchestrator {
     async fn on_consensus_requested(
         &self,
         trace_id: ClotoId,
+        task: &str,
         engine_ids: &[String],
-    ) -> Option<ClotoEventData> {
+        strategy: &ConsensusStrategy,
+    ) {
         info!(
             trace_id = %trace_id,
+            strategy = strategy.name(),
             "🤝 Consensus process started for {} engines",
             engine_ids.len()
         );
 
-        let fallback_engine = engine_ids.first().cloned().unwrap_or_default();
+        let task = match task.get(..10) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("consensus:") => task[10..].trim(),
+            _ => task,
+        };
 
         let mut sessions = self.sessions.write().await;
         sessions.insert(
             trace_id,
-            SessionState::Collecting {
+            Session {
+                strategy: strategy.clone(),
+                task: task.to_string(),
+                engine_count: engine_ids.len(),
+                fallback_engine: engine_ids.first().cloned().unwrap_or_default(),
                 proposals: Vec::new(),
-                fallback_engine,
+                earlier_rounds: Vec::new(),
+                phase: Phase::Collecting,
                 created_at: std::time::Instant::now(),
             },
         );
-
-        None
     }
 
     async fn on_thought_response(
         &self,
         trace_id: ClotoId,
         agent_id: &str,
+        engine_id: &str,
         content: &str,
-    ) -> Option<ClotoEventData> {
+    ) -> Vec<ClotoEventData> {
         // Ignore responses from the consensus system itself
         if agent_id == SYSTEM_CONSENSUS_AGENT {
-            return None;
+            return Vec::new();
         }
 
-        let min_proposals = self.config.read().await.min_proposals;
+        let (min_proposals, synthesizer_engine) = {
+            let cfg = self.config.read().await;
+            (cfg.min_proposals, cfg.synthesizer_engine.clone())
+        };
         let mut sessions = self.sessions.write().await;
+        let Some(session) = sessions.get_mut(&trace_id) else {
+            return Vec::new();
+        };
+        let synthesizer = if synthesizer_engine.is_empty() {
+            session.fallback_engine.clone()
+        } else {
+            synthesizer_engine
+        };
 
-        let state = sessions.get_mut(&trace_id)?;
-
-        match state {
-            SessionState::Collecting {
-                proposals,
-                fallback_engine,
-                created_at,
-            } => {
-                // 1. Collect proposal
-                proposals.push(Proposal {
-                    content: content.to_string(),
-                });
-
-                info!(
-                    trace_id = %trace_id,
-                    "📥 Collected proposal from {} ({}/{})",
-                    agent_id,
-                    proposals.len(),
-                    min_proposals,
-                );
-
-                if proposals.len() >= min_proposals {
-                    // Build synthesis prompt
-                    let combined_views = proposals
-                        .iter()
-                        .enumerate()
-                        .map(|(i, p)| format!("## Opinion {}:\n{}", i + 1, p.content))
-                        .collect::<Vec<_>>()
-                        .join("\n\n");
-
-                    let fallback = fallback_engine.clone();
-                    let created = *created_at;
-
-                    // 2. Transition to Synthesizing
-                    *state = SessionState::Synthesizing {
-                        created_at: created,
-                    };
-
-                    // Resolve synthesizer engine (must drop sessions lock first)
-                    drop(sessions);
-                    let synthesizer = self.resolve_synthesizer(&fallback).await;
-
-                    info!(
-                        trace_id = %trace_id,
-                        synthesizer = %synthesizer,
-                        "⚗️ Starting synthesis phase...",
-                    );
-
-                    let synthesis_prompt = format!(
-                        "You are a wise moderator. Synthesize the following opinions into a single, coherent conclusion.\n\n{}",
-                        combined_views
-                    );
-
-                    let synthesizer_agent = AgentMetadata {
-                        id: "agent.synthesizer".to_string(),
-                        name: "Synthesizer".to_string(),
-                        description: "AI Moderator".to_string(),
-                        enabled: true,
-                        last_seen: 0,
-                        status: "online".to_string(),
-                        default_engine_id: Some(synthesizer.clone()),
-                        required_capabilities: vec![],
-                        metadata: HashMap::new(),
-                    };
-
-                    return Some(
-                        ClotoEvent::with_trace(
-                            trace_id,
-                            ClotoEventData::ThoughtRequested {
-                                agent: synthesizer_agent,
-                                engine_id: synthesizer,
-                                message: ClotoMessage::new(MessageSource::System, synthesis_prompt),
-                                context: vec![],
-                            },
-                        )
-                        .data,
-                    );
-                }
-
-                None
-            }
-
-            SessionState::Synthesizing { .. } => {
-                // 3. Synthesis complete — final response
-                info!(
-                    trace_id = %trace_id,
-                    "🏁 Synthesis complete via {}",
-                    agent_id
-                );
-
+        match session.on_response(agent_id, engine_id, content, min_proposals, &synthesizer) {
+            Step::Wait => Vec::new(),
+            Step::Ask(requests) => requests.into_iter().map(thought_request).collect(),
+            Step::Done { content, metadata } => {
                 sessions.remove(&trace_id);
-
-                Some(ClotoEventData::ThoughtResponse {
+                info!(trace_id = %trace_id, "🏁 Consensus reached");
+                vec![ClotoEventData::ThoughtResponse {
                     agent_id: SYSTEM_CONSENSUS_AGENT.to_string(),
                     engine_id: "consensus".to_string(),
-                    content: content.to_string(),
+                    content,
                     source_message_id: "consensus".to_string(),
-                })
+                    metadata: Some(metadata),
+                }]
             }
         }
     }
 
     // ── Helpers ──
 
-    async fn resolve_synthesizer(&self, fallback: &str) -> String {
-        let cfg = self.config.read().await;
-        if cfg.synthesizer_engine.is_empty() {
-            fallback.to_string()
-        } else {
-            cfg.synthesizer_engine.clone()
-        }
-    }
-
     fn spawn_cleanup_task(self: &Arc<Self>) {
         let this = Arc::downgrade(self);
         tokio::spawn(async move {
@@ -271,12 +647,8 @@ impl ConsensusOrchestrator {
                 let timeout_secs = orchestrator.config.read().await.session_timeout_secs;
                 let mut map = orchestrator.sessions.write().await;
                 let before = map.len();
-                map.retain(|trace_id, state| {
-                    let created_at = match state {
-                        SessionState::Collecting { created_at, .. }
-                        | SessionState::Synthesizing { created_at } => *created_at,
-                    };
-                    if created_at.elapsed().as_secs() > timeout_secs {
+                map.retain(|trace_id, session| {
+                    if session.created_at.elapsed().as_secs() > timeout_secs {
                         warn!(trace_id = %trace_id, "🕐 Consensus session timed out, removing");
                         false
                     } else {
@@ -291,3 +663,202 @@ impl ConsensusOrchestrator {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(trace_id: ClotoId, data: ClotoEventData) -> ClotoEvent {
+        ClotoEvent::with_trace(trace_id, data)
+    }
+
+    fn response(agent_id: &str, engine_id: &str, content: &str) -> ClotoEventData {
+        ClotoEventData::ThoughtResponse {
+            agent_id: agent_id.to_string(),
+            engine_id: engine_id.to_string(),
+            content: content.to_string(),
+            source_message_id: "msg".to_string(),
+            metadata: None,
+        }
+    }
+
+    async fn start(
+        strategy: ConsensusStrategy,
+        engines: &[&str],
+    ) -> (Arc<ConsensusOrchestrator>, ClotoId) {
+        let orchestrator = ConsensusOrchestrator::new(ConsensusConfig::default());
+        let trace_id = ClotoId::new();
+        let requested = ClotoEventData::ConsensusRequested {
+            task: "consensus: What is the capital of France?".to_string(),
+            engine_ids: engines.iter().map(ToString::to_string).collect(),
+            strategy,
+        };
+        assert!(orchestrator
+            .handle_event(&event(trace_id, requested))
+            .await
+            .is_empty());
+        (orchestrator, trace_id)
+    }
+
+    fn final_response(events: &[ClotoEventData]) -> (&str, &serde_json::Value) {
+        match events {
+            [ClotoEventData::ThoughtResponse {
+                agent_id,
+                content,
+                metadata: Some(metadata),
+                ..
+            }] if agent_id == SYSTEM_CONSENSUS_AGENT => (content, metadata),
+            other => panic!("expected the final response, got {:?}", other),
+        }
+    }
+
+    fn requests(events: &[ClotoEventData]) -> Vec<(&str, &str)> {
+        events
+            .iter()
+            .map(|e| match e {
+                ClotoEventData::ThoughtRequested {
+                    agent, engine_id, ..
+                } => (agent.id.as_str(), engine_id.as_str()),
+                other => panic!("expected ThoughtRequested, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_ranking() {
+        assert_eq!(parse_ranking("2, 1, 3", 3), vec![1, 0, 2]);
+        assert_eq!(
+            parse_ranking("Answer 3 is best, then 3 again, then 9", 3),
+            vec![2]
+        );
+        assert!(parse_ranking("none of them", 3).is_empty());
+    }
+
+    #[test]
+    fn test_instant_runoff_transfers_votes() {
+        // First choices 1-2-1: no majority; candidate 2 (tied last, later)
+        // is eliminated and its ballot transfers to candidate 1.
+        let ballots = vec![vec![0, 1, 2], vec![1, 0, 2], vec![2, 1, 0], vec![1, 2, 0]];
+        assert_eq!(instant_runoff(3, &ballots), (1, vec![2]));
+        assert_eq!(instant_runoff(2, &[]), (0, vec![1]));
+    }
+
+    #[tokio::test]
+    async fn test_majority_vote() {
+        let (orchestrator, trace) = start(
+            ConsensusStrategy::MajorityVote,
+            &["mind.a", "mind.b", "mind.c"],
+        )
+        .await;
+        for (engine, answer) in [("mind.a", "Lyon"), ("mind.b", "Paris.")] {
+            let out = orchestrator
+                .handle_event(&event(trace, response("agent.x", engine, answer)))
+                .await;
+            assert!(out.is_empty());
+        }
+        let out = orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.c", "paris")))
+            .await;
+        let (content, metadata) = final_response(&out);
+        assert_eq!(content, "Paris.");
+        assert_eq!(metadata["strategy"], "majority_vote");
+        assert_eq!(metadata["results"][0]["votes"], 1);
+        assert_eq!(metadata["results"][1]["votes"], 2);
+        assert_eq!(metadata["results"][2]["winner"], true);
+    }
+
+    #[tokio::test]
+    async fn test_best_of_n_uses_judge() {
+        let strategy = ConsensusStrategy::BestOfN {
+            judge: Some("mind.judge".to_string()),
+        };
+        let (orchestrator, trace) = start(strategy, &["mind.a", "mind.b"]).await;
+        orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.a", "Lyon")))
+            .await;
+        let out = orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.b", "Paris")))
+            .await;
+        assert_eq!(requests(&out), vec![(JUDGE_AGENT, "mind.judge")]);
+
+        // A late proposal is not mistaken for the verdict
+        let out = orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.c", "1")))
+            .await;
+        assert!(out.is_empty());
+
+        let out = orchestrator
+            .handle_event(&event(trace, response(JUDGE_AGENT, "mind.judge", "2")))
+            .await;
+        let (content, metadata) = final_response(&out);
+        assert_eq!(content, "Paris");
+        assert_eq!(metadata["judge"], "mind.judge");
+        assert_eq!(metadata["results"][1]["winner"], true);
+    }
+
+    #[tokio::test]
+    async fn test_ranked_choice() {
+        let (orchestrator, trace) =
+            start(ConsensusStrategy::RankedChoice, &["mind.a", "mind.b"]).await;
+        orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.a", "Lyon")))
+            .await;
+        let out = orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.b", "Paris")))
+            .await;
+        assert_eq!(
+            requests(&out),
+            vec![(VOTER_AGENT, "mind.a"), (VOTER_AGENT, "mind.b")]
+        );
+        orchestrator
+            .handle_event(&event(trace, response(VOTER_AGENT, "mind.a", "2, 1")))
+            .await;
+        let out = orchestrator
+            .handle_event(&event(trace, response(VOTER_AGENT, "mind.b", "2 then 1")))
+            .await;
+        let (content, metadata) = final_response(&out);
+        assert_eq!(content, "Paris");
+        assert_eq!(
+            metadata["results"][0]["ballot"],
+            json!(["mind.b", "mind.a"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_debate_then_synthesis() {
+        let (orchestrator, trace) = start(
+            ConsensusStrategy::Debate { rounds: 2 },
+            &["mind.a", "mind.b"],
+        )
+        .await;
+        orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.a", "Lyon")))
+            .await;
+        let out = orchestrator
+            .handle_event(&event(trace, response("agent.x", "mind.b", "Paris")))
+            .await;
+        assert_eq!(
+            requests(&out),
+            vec![(DEBATER_AGENT, "mind.a"), (DEBATER_AGENT, "mind.b")]
+        );
+        orchestrator
+            .handle_event(&event(trace, response(DEBATER_AGENT, "mind.a", "Paris")))
+            .await;
+        let out = orchestrator
+            .handle_event(&event(trace, response(DEBATER_AGENT, "mind.b", "Paris")))
+            .await;
+        // No synthesizer configured: the first engine synthesizes
+        assert_eq!(requests(&out), vec![(SYNTHESIZER_AGENT, "mind.a")]);
+
+        let out = orchestrator
+            .handle_event(&event(
+                trace,
+                response(SYNTHESIZER_AGENT, "mind.a", "Paris"),
+            ))
+            .await;
+        let (content, metadata) = final_response(&out);
+        assert_eq!(content, "Paris");
+        assert_eq!(metadata["rounds"], 2);
+        assert_eq!(metadata["results"][0]["answers"], json!(["Lyon", "Paris"]));
+    }
+}
@@ -2,7 +2,6 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use cloto_shared::PluginDataStore;
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::time::{timeout, Duration};
 use tracing::info;
@@ -10,6 +9,79 @@ use tracing::info;
 // Bug #7: Database operation timeout to prevent indefinite hangs
 const DB_TIMEOUT_SECS: u64 = 10;
 
+// ─── Backend ───
+//
+// The backend is chosen at compile time: SQLite by default, PostgreSQL with
+// the `postgres` feature (for deployments where several kernels share one
+// database, or where SQLite's single writer becomes the bottleneck). Queries
+// are written in the common subset of both dialects with `$N` placeholders;
+// the few statements that differ are selected with `cfg` below.
+
+#[cfg(not(feature = "postgres"))]
+pub type Db = sqlx::Sqlite;
+#[cfg(feature = "postgres")]
+pub type Db = sqlx::Postgres;
+
+pub type DbPool = sqlx::Pool<Db>;
+
+/// Open the connection pool for `database_url`, creating the SQLite file if
+/// it does not exist yet. Statement and acquire timings are emitted at TRACE
+/// for db_metrics, not for the log.
+pub async fn connect(
+    database_url: &str,
+    max_connections: u32,
+    acquire_timeout: Duration,
+) -> anyhow::Result<DbPool> {
+    use sqlx::pool::PoolOptions;
+    use sqlx::ConnectOptions;
+    use std::str::FromStr;
+
+    #[cfg(not(feature = "postgres"))]
+    let opts = sqlx::sqlite::SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+    #[cfg(feature = "postgres")]
+    let opts = sqlx::postgres::PgConnectOptions::from_str(database_url)?;
+
+    let pool = PoolOptions::<Db>::new()
+        .max_connections(max_connections)
+        .acquire_timeout(acquire_timeout)
+        .acquire_time_level(log::LevelFilter::Trace)
+        .connect_with(opts.log_statements(log::LevelFilter::Trace))
+        .await?;
+    Ok(pool)
+}
+
+/// JSON document stored in a TEXT column.
+///
+/// `sqlx::types::Json` only decodes PostgreSQL `json`/`jsonb` columns, so
+/// JSON is kept as text on both backends and parsed here instead.
+#[derive(Debug, Clone, Default)]
+pub struct JsonText<T>(pub T);
+
+impl<T> sqlx::Type<Db> for JsonText<T> {
+    fn type_info() -> <Db as sqlx::Database>::TypeInfo {
+        <String as sqlx::Type<Db>>::type_info()
+    }
+
+    fn compatible(ty: &<Db as sqlx::Database>::TypeInfo) -> bool {
+        <String as sqlx::Type<Db>>::compatible(ty)
+    }
+}
+
+impl<'r, T: serde::de::DeserializeOwned> sqlx::Decode<'r, Db> for JsonText<T> {
+    fn decode(
+        value: <Db as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let text = <&str as sqlx::Decode<Db>>::decode(value)?;
+        Ok(Self(serde_json::from_str(text)?))
+    }
+}
+
+/// Current UTC time in the `YYYY-MM-DD HH:MM:SS` form of SQLite's
+/// `datetime('now')`, which `expires_at` columns are compared against.
+fn sql_now() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
 /// Execute a database operation with standard timeout and error handling.
 /// Consolidates the repeated timeout+error pattern (bug-148).
 async fn db_timeout<T, F>(future: F) -> anyhow::Result<T>
@@ -23,12 +95,12 @@ where
 }
 
 pub struct SqliteDataStore {
-    pool: SqlitePool,
+    pool: DbPool,
 }
 
 impl SqliteDataStore {
     #[must_use]
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
 }
@@ -60,7 +132,8 @@ impl PluginDataStore for SqliteDataStore {
 
         // Bug #7: Add timeout to prevent indefinite hangs on database locks
         let query_future = sqlx::query(
-            "INSERT OR REPLACE INTO plugin_data (plugin_id, key, value) VALUES (?, ?, ?)",
+            "INSERT INTO plugin_data (plugin_id, key, value) VALUES ($1, $2, $3) \
+             ON CONFLICT(plugin_id, key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
         )
         .bind(plugin_id)
         .bind(key)
@@ -94,7 +167,7 @@ impl PluginDataStore for SqliteDataStore {
 
         // Bug #7: Add timeout to prevent indefinite hangs on database locks
         let query_future = sqlx::query_as::<_, (String,)>(
-            "SELECT value FROM plugin_data WHERE plugin_id = ? AND key = ?",
+            "SELECT value FROM plugin_data WHERE plugin_id = $1 AND key = $2",
         )
         .bind(plugin_id)
         .bind(key)
@@ -134,8 +207,8 @@ impl PluginDataStore for SqliteDataStore {
         // Bug #7: Add timeout to prevent indefinite hangs on database locks
         // Fetch DEFAULT_MAX_RESULTS + 1 to detect overflow without fetching all rows.
         let query_future = sqlx::query_as::<_, (String, String)>(
-            "SELECT key, value FROM plugin_data WHERE plugin_id = ? AND key LIKE ? ESCAPE '\\' \
-             ORDER BY key DESC LIMIT ?",
+            "SELECT key, value FROM plugin_data WHERE plugin_id = $1 AND key LIKE $2 ESCAPE '\\' \
+             ORDER BY key DESC LIMIT $3",
         )
         .bind(plugin_id)
         .bind(pattern)
@@ -183,8 +256,8 @@ impl PluginDataStore for SqliteDataStore {
         // Atomic UPSERT: INSERT or UPDATE in a single SQL statement
         // The RETURNING clause gives us the new value without a second query
         let query_future = sqlx::query_as::<_, (String,)>(
-            "INSERT INTO plugin_data (plugin_id, key, value) VALUES (?, ?, '1') \
-             ON CONFLICT(plugin_id, key) DO UPDATE SET value = CAST(CAST(value AS INTEGER) + 1 AS TEXT) \
+            "INSERT INTO plugin_data (plugin_id, key, value) VALUES ($1, $2, '1') \
+             ON CONFLICT(plugin_id, key) DO UPDATE SET value = CAST(CAST(plugin_data.value AS BIGINT) + 1 AS TEXT) \
              RETURNING value"
         )
             .bind(plugin_id)
@@ -244,13 +317,16 @@ impl PluginDataStore for ScopedDataStore {
     }
 }
 
-pub async fn init_db(pool: &SqlitePool, database_url: &str) -> anyhow::Result<()> {
+pub async fn init_db(pool: &DbPool, database_url: &str) -> anyhow::Result<()> {
     info!("Running database migrations & seeds...");
 
     // Run migrations from migrations/ directory
     // Bug C: Wrap migration with timeout to prevent indefinite startup hangs (30s for schema changes)
     const MIGRATION_TIMEOUT_SECS: u64 = 30;
+    #[cfg(not(feature = "postgres"))]
     let migration_future = sqlx::migrate!("./migrations").run(pool);
+    #[cfg(feature = "postgres")]
+    let migration_future = sqlx::migrate!("./migrations/postgres").run(pool);
     timeout(
         Duration::from_secs(MIGRATION_TIMEOUT_SECS),
         migration_future,
@@ -267,7 +343,8 @@ pub async fn init_db(pool: &SqlitePool, database_url: &str) -> anyhow::Result<()
     info!("Applying runtime configurations...");
 
     // Configs that depend on runtime environment
-    sqlx::query("INSERT OR REPLACE INTO plugin_configs (plugin_id, config_key, config_value) VALUES ('memory.ks22', 'database_url', ?)")
+    sqlx::query("INSERT INTO plugin_configs (plugin_id, config_key, config_value) VALUES ('memory.ks22', 'database_url', $1) \
+                 ON CONFLICT(plugin_id, config_key) DO UPDATE SET config_value = excluded.config_value")
         .bind(database_url)
         .execute(pool).await?;
 
@@ -293,14 +370,14 @@ pub struct AuditLogEntry {
 }
 
 /// Write an audit log entry to the database
-pub async fn write_audit_log(pool: &SqlitePool, entry: AuditLogEntry) -> anyhow::Result<()> {
+pub async fn write_audit_log(pool: &DbPool, entry: AuditLogEntry) -> anyhow::Result<()> {
     let timestamp = entry.timestamp.to_rfc3339();
     let metadata_str = entry.metadata.map(|v| v.to_string());
 
     // Bug #7: Add timeout to prevent indefinite hangs on database locks
     let query_future = sqlx::query(
         "INSERT INTO audit_logs (timestamp, event_type, actor_id, target_id, permission, result, reason, metadata, trace_id)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
     )
     .bind(&timestamp)
     .bind(&entry.event_type)
@@ -318,10 +395,27 @@ pub async fn write_audit_log(pool: &SqlitePool, entry: AuditLogEntry) -> anyhow:
     Ok(())
 }
 
+/// Audit writes spawned but not yet finished, flushed on shutdown.
+static PENDING_AUDIT_WRITES: std::sync::LazyLock<std::sync::Arc<crate::drain::DrainTracker>> =
+    std::sync::LazyLock::new(Default::default);
+
+/// Register a background audit write so shutdown waits for it.
+#[must_use]
+pub fn track_audit_write() -> crate::drain::DrainGuard {
+    PENDING_AUDIT_WRITES.track()
+}
+
+/// Wait for background audit writes to finish. Returns `false` on timeout.
+pub async fn flush_audit_logs(timeout: std::time::Duration) -> bool {
+    PENDING_AUDIT_WRITES.wait_idle(timeout).await
+}
+
 /// Spawn a background task to write an audit log entry with retry.
 /// M-06: Retries up to 3 times with backoff instead of fire-and-forget.
-pub fn spawn_audit_log(pool: SqlitePool, entry: AuditLogEntry) {
+pub fn spawn_audit_log(pool: DbPool, entry: AuditLogEntry) {
+    let pending = track_audit_write();
     tokio::spawn(async move {
+        let _pending = pending;
         for attempt in 0..3u32 {
             match write_audit_log(&pool, entry.clone()).await {
                 Ok(()) => return,
@@ -341,14 +435,14 @@ pub fn spawn_audit_log(pool: SqlitePool, entry: AuditLogEntry) {
 }
 
 /// Query audit logs from the database (most recent first)
-pub async fn query_audit_logs(pool: &SqlitePool, limit: i64) -> anyhow::Result<Vec<AuditLogEntry>> {
+pub async fn query_audit_logs(pool: &DbPool, limit: i64) -> anyhow::Result<Vec<AuditLogEntry>> {
     // Bug #7: Add timeout to prevent indefinite hangs on database locks
     #[allow(clippy::type_complexity)]
     let query_future = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>, String, String, Option<String>, Option<String>)>(
             "SELECT timestamp, event_type, actor_id, target_id, permission, result, reason, metadata, trace_id
              FROM audit_logs
              ORDER BY timestamp DESC
-             LIMIT ?"
+             LIMIT $1"
         )
         .bind(limit)
         .fetch_all(pool);
@@ -373,6 +467,52 @@ pub async fn query_audit_logs(pool: &SqlitePool, limit: i64) -> anyhow::Result<V
     Ok(logs)
 }
 
+/// Query audit logs with optional filters (most recent first).
+/// `before` is an RFC 3339 timestamp for paging.
+pub async fn query_audit_logs_filtered(
+    pool: &DbPool,
+    event_type: Option<&str>,
+    actor_id: Option<&str>,
+    before: Option<&str>,
+    limit: i64,
+) -> anyhow::Result<Vec<AuditLogEntry>> {
+    #[allow(clippy::type_complexity)]
+    let query_future = sqlx::query_as::<_, (String, String, Option<String>, Option<String>, Option<String>, String, String, Option<String>, Option<String>)>(
+            "SELECT timestamp, event_type, actor_id, target_id, permission, result, reason, metadata, trace_id
+             FROM audit_logs
+             WHERE ($1 IS NULL OR event_type = $1)
+               AND ($2 IS NULL OR actor_id = $2)
+               AND ($3 IS NULL OR timestamp < $3)
+             ORDER BY timestamp DESC
+             LIMIT $4"
+        )
+        .bind(event_type)
+        .bind(actor_id)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(pool);
+
+    let rows = db_timeout(query_future).await?;
+
+    rows.into_iter()
+        .map(
+            |(timestamp, event_type, actor, target, perm, result, reason, metadata, trace)| {
+                Ok(AuditLogEntry {
+                    timestamp: DateTime::parse_from_rfc3339(&timestamp)?.with_timezone(&Utc),
+                    event_type,
+                    actor_id: actor,
+                    target_id: target,
+                    permission: perm,
+                    result,
+                    reason,
+                    metadata: metadata.and_then(|s| serde_json::from_str(&s).ok()),
+                    trace_id: trace,
+                })
+            },
+        )
+        .collect()
+}
+
 /// Permission request entry for human-in-the-loop workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PermissionRequest {
@@ -391,7 +531,7 @@ pub struct PermissionRequest {
 
 /// Create a new permission request
 pub async fn create_permission_request(
-    pool: &SqlitePool,
+    pool: &DbPool,
     request: PermissionRequest,
 ) -> anyhow::Result<()> {
     let created_at = request.created_at.to_rfc3339();
@@ -401,7 +541,7 @@ pub async fn create_permission_request(
     // Bug #7: Add timeout to prevent indefinite hangs on database locks
     let query_future = sqlx::query(
         "INSERT INTO permission_requests (request_id, created_at, plugin_id, permission_type, target_resource, justification, status, expires_at, metadata)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"
     )
     .bind(&request.request_id)
     .bind(&created_at)
@@ -421,7 +561,7 @@ pub async fn create_permission_request(
 
 /// Query pending permission requests
 pub async fn get_pending_permission_requests(
-    pool: &SqlitePool,
+    pool: &DbPool,
 ) -> anyhow::Result<Vec<PermissionRequest>> {
     // Bug #7: Add timeout to prevent indefinite hangs on database locks
     #[allow(clippy::type_complexity)]
@@ -479,7 +619,7 @@ pub async fn get_pending_permission_requests(
 /// Update permission request status (approve/deny)
 /// Only transitions from 'pending' status are allowed to prevent double-approval
 pub async fn update_permission_request(
-    pool: &SqlitePool,
+    pool: &DbPool,
     request_id: &str,
     status: &str,
     approved_by: &str,
@@ -497,8 +637,8 @@ pub async fn update_permission_request(
     // Bug #7: Add timeout to prevent indefinite hangs on database locks
     let query_future = sqlx::query(
         "UPDATE permission_requests
-         SET status = ?, approved_by = ?, approved_at = ?
-         WHERE request_id = ? AND status = 'pending'",
+         SET status = $1, approved_by = $2, approved_at = $3
+         WHERE request_id = $4 AND status = 'pending'",
     )
     .bind(status)
     .bind(approved_by)
@@ -521,17 +661,37 @@ pub async fn update_permission_request(
 /// Check if a specific permission is already approved for a plugin/server.
 /// Returns true if an approved, non-expired permission exists.
 pub async fn is_permission_approved(
-    pool: &SqlitePool,
+    pool: &DbPool,
     plugin_id: &str,
     permission_type: &str,
 ) -> anyhow::Result<bool> {
-    let query_future = sqlx::query_scalar::<_, i32>(
+    let query_future = sqlx::query_scalar::<_, i64>(
         "SELECT COUNT(*) FROM permission_requests
-         WHERE plugin_id = ? AND permission_type = ? AND status = 'approved'
-           AND (expires_at IS NULL OR expires_at > datetime('now'))",
+         WHERE plugin_id = $1 AND permission_type = $2 AND status = 'approved'
+           AND (expires_at IS NULL OR expires_at > $3)",
     )
     .bind(plugin_id)
     .bind(permission_type)
+    .bind(sql_now())
+    .fetch_one(pool);
+
+    let count = db_timeout(query_future).await?;
+
+    Ok(count > 0)
+}
+
+/// Check whether a specific permission request (by ID) has been approved.
+pub async fn is_permission_request_approved(
+    pool: &DbPool,
+    request_id: &str,
+) -> anyhow::Result<bool> {
+    let query_future = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM permission_requests
+         WHERE request_id = $1 AND status = 'approved'
+           AND (expires_at IS NULL OR expires_at > $2)",
+    )
+    .bind(request_id)
+    .bind(sql_now())
     .fetch_one(pool);
 
     let count = db_timeout(query_future).await?;
@@ -550,6 +710,9 @@ pub struct ChatMessageRow {
     pub content: String, // JSON string of ContentBlock[]
     pub metadata: Option<String>,
     pub created_at: i64,
+    /// Conversation session; `None` on save means the thread's active session.
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -564,13 +727,25 @@ pub struct AttachmentRow {
     pub inline_data: Option<Vec<u8>>,
     pub disk_path: Option<String>,
     pub created_at: i64,
+    /// Extracted document text or audio transcript
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derived_text: Option<String>,
 }
 
-/// Save a chat message to the database
-pub async fn save_chat_message(pool: &SqlitePool, msg: &ChatMessageRow) -> anyhow::Result<()> {
+/// Save a chat message to the database. Messages without a `session_id`
+/// go to the thread's active session (created if there is none).
+pub async fn save_chat_message(pool: &DbPool, msg: &ChatMessageRow) -> anyhow::Result<()> {
+    let session_id = match msg.session_id {
+        Some(ref id) => id.clone(),
+        None => {
+            ensure_active_chat_session(pool, &msg.agent_id, &msg.user_id)
+                .await?
+                .id
+        }
+    };
     let query_future = sqlx::query(
-        "INSERT INTO chat_messages (id, agent_id, user_id, source, content, metadata, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO chat_messages (id, agent_id, user_id, source, content, metadata, created_at, session_id)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
     )
     .bind(&msg.id)
     .bind(&msg.agent_id)
@@ -579,19 +754,51 @@ pub async fn save_chat_message(pool: &SqlitePool, msg: &ChatMessageRow) -> anyho
     .bind(&msg.content)
     .bind(&msg.metadata)
     .bind(msg.created_at)
+    .bind(&session_id)
     .execute(pool);
 
     db_timeout(query_future).await?;
 
+    let touch_future =
+        sqlx::query("UPDATE chat_sessions SET updated_at = $1 WHERE id = $2 AND updated_at < $1")
+            .bind(msg.created_at)
+            .bind(&session_id)
+            .execute(pool);
+    db_timeout(touch_future).await?;
+
     Ok(())
 }
 
 /// Row type returned by chat message queries.
-type ChatMessageTuple = (String, String, String, String, String, Option<String>, i64);
+type ChatMessageTuple = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    i64,
+    Option<String>,
+);
+
+fn chat_message_from_tuple(
+    (id, agent_id, user_id, source, content, metadata, created_at, session_id): ChatMessageTuple,
+) -> ChatMessageRow {
+    ChatMessageRow {
+        id,
+        agent_id,
+        user_id,
+        source,
+        content,
+        metadata,
+        created_at,
+        session_id,
+    }
+}
 
 /// Get chat messages with cursor-based pagination (ordered by created_at DESC)
 pub async fn get_chat_messages(
-    pool: &SqlitePool,
+    pool: &DbPool,
     agent_id: &str,
     user_id: &str,
     before_ts: Option<i64>,
@@ -601,11 +808,11 @@ pub async fn get_chat_messages(
 
     let rows: Vec<ChatMessageTuple> = if let Some(before) = before_ts {
         let query_future = sqlx::query_as::<_, ChatMessageTuple>(
-            "SELECT id, agent_id, user_id, source, content, metadata, created_at
+            "SELECT id, agent_id, user_id, source, content, metadata, created_at, session_id
              FROM chat_messages
-             WHERE agent_id = ? AND user_id = ? AND created_at < ?
+             WHERE agent_id = $1 AND user_id = $2 AND created_at < $3
              ORDER BY created_at DESC
-             LIMIT ?",
+             LIMIT $4",
         )
         .bind(agent_id)
         .bind(user_id)
@@ -616,11 +823,11 @@ pub async fn get_chat_messages(
         db_timeout(query_future).await?
     } else {
         let query_future = sqlx::query_as::<_, ChatMessageTuple>(
-            "SELECT id, agent_id, user_id, source, content, metadata, created_at
+            "SELECT id, agent_id, user_id, source, content, metadata, created_at, session_id
              FROM chat_messages
-             WHERE agent_id = ? AND user_id = ?
+             WHERE agent_id = $1 AND user_id = $2
              ORDER BY created_at DESC
-             LIMIT ?",
+             LIMIT $3",
         )
         .bind(agent_id)
         .bind(user_id)
@@ -630,33 +837,20 @@ pub async fn get_chat_messages(
         db_timeout(query_future).await?
     };
 
-    let messages = rows
-        .into_iter()
-        .map(
-            |(id, agent_id, user_id, source, content, metadata, created_at)| ChatMessageRow {
-                id,
-                agent_id,
-                user_id,
-                source,
-                content,
-                metadata,
-                created_at,
-            },
-        )
-        .collect();
+    let messages = rows.into_iter().map(chat_message_from_tuple).collect();
 
     Ok(messages)
 }
 
 /// Delete all chat messages (and cascade to attachments) for an agent/user pair
 pub async fn delete_chat_messages(
-    pool: &SqlitePool,
+    pool: &DbPool,
     agent_id: &str,
     user_id: &str,
 ) -> anyhow::Result<u64> {
     // First get message IDs for disk attachment cleanup
     let ids_future = sqlx::query_as::<_, (String,)>(
-        "SELECT id FROM chat_messages WHERE agent_id = ? AND user_id = ?",
+        "SELECT id FROM chat_messages WHERE agent_id = $1 AND user_id = $2",
     )
     .bind(agent_id)
     .bind(user_id)
@@ -671,14 +865,32 @@ pub async fn delete_chat_messages(
     // Get disk paths for cleanup
     let disk_paths = get_disk_attachment_paths(pool, &msg_ids).await?;
 
+    let tool_history_future = sqlx::query(
+        "DELETE FROM chat_tool_history WHERE message_id IN \
+         (SELECT id FROM chat_messages WHERE agent_id = $1 AND user_id = $2)",
+    )
+    .bind(agent_id)
+    .bind(user_id)
+    .execute(pool);
+    db_timeout(tool_history_future).await?;
+
     // Delete messages (attachments cascade via ON DELETE CASCADE)
-    let delete_future = sqlx::query("DELETE FROM chat_messages WHERE agent_id = ? AND user_id = ?")
-        .bind(agent_id)
-        .bind(user_id)
-        .execute(pool);
+    let delete_future =
+        sqlx::query("DELETE FROM chat_messages WHERE agent_id = $1 AND user_id = $2")
+            .bind(agent_id)
+            .bind(user_id)
+            .execute(pool);
 
     let result = db_timeout(delete_future).await?;
 
+    // The rolling summary describes the deleted messages
+    let summary_future =
+        sqlx::query("DELETE FROM chat_summaries WHERE agent_id = $1 AND user_id = $2")
+            .bind(agent_id)
+            .bind(user_id)
+            .execute(pool);
+    db_timeout(summary_future).await?;
+
     // Clean up disk files (best-effort)
     for path in disk_paths {
         let _ = tokio::fs::remove_file(&path).await;
@@ -688,10 +900,10 @@ pub async fn delete_chat_messages(
 }
 
 /// Save a chat attachment
-pub async fn save_attachment(pool: &SqlitePool, att: &AttachmentRow) -> anyhow::Result<()> {
+pub async fn save_attachment(pool: &DbPool, att: &AttachmentRow) -> anyhow::Result<()> {
     let query_future = sqlx::query(
-        "INSERT INTO chat_attachments (id, message_id, filename, mime_type, size_bytes, storage_type, inline_data, disk_path, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO chat_attachments (id, message_id, filename, mime_type, size_bytes, storage_type, inline_data, disk_path, created_at, derived_text)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
     )
     .bind(&att.id)
     .bind(&att.message_id)
@@ -702,6 +914,7 @@ pub async fn save_attachment(pool: &SqlitePool, att: &AttachmentRow) -> anyhow::
     .bind(&att.inline_data)
     .bind(&att.disk_path)
     .bind(att.created_at)
+    .bind(&att.derived_text)
     .execute(pool);
 
     db_timeout(query_future).await?;
@@ -711,13 +924,13 @@ pub async fn save_attachment(pool: &SqlitePool, att: &AttachmentRow) -> anyhow::
 
 /// Get attachments for a specific message
 pub async fn get_attachments_for_message(
-    pool: &SqlitePool,
+    pool: &DbPool,
     message_id: &str,
 ) -> anyhow::Result<Vec<AttachmentRow>> {
-    let query_future = sqlx::query_as::<_, (String, String, String, String, i64, String, Option<Vec<u8>>, Option<String>, i64)>(
-        "SELECT id, message_id, filename, mime_type, size_bytes, storage_type, inline_data, disk_path, created_at
+    let query_future = sqlx::query_as::<_, (String, String, String, String, i64, String, Option<Vec<u8>>, Option<String>, i64, Option<String>)>(
+        "SELECT id, message_id, filename, mime_type, size_bytes, storage_type, inline_data, disk_path, created_at, derived_text
          FROM chat_attachments
-         WHERE message_id = ?"
+         WHERE message_id = $1"
     )
     .bind(message_id)
     .fetch_all(pool);
@@ -737,6 +950,7 @@ pub async fn get_attachments_for_message(
                 inline_data,
                 disk_path,
                 created_at,
+                derived_text,
             )| {
                 AttachmentRow {
                     id,
@@ -748,6 +962,7 @@ pub async fn get_attachments_for_message(
                     inline_data,
                     disk_path,
                     created_at,
+                    derived_text,
                 }
             },
         )
@@ -758,13 +973,13 @@ pub async fn get_attachments_for_message(
 
 /// Get an attachment by ID
 pub async fn get_attachment_by_id(
-    pool: &SqlitePool,
+    pool: &DbPool,
     attachment_id: &str,
 ) -> anyhow::Result<Option<AttachmentRow>> {
-    let query_future = sqlx::query_as::<_, (String, String, String, String, i64, String, Option<Vec<u8>>, Option<String>, i64)>(
-        "SELECT id, message_id, filename, mime_type, size_bytes, storage_type, inline_data, disk_path, created_at
+    let query_future = sqlx::query_as::<_, (String, String, String, String, i64, String, Option<Vec<u8>>, Option<String>, i64, Option<String>)>(
+        "SELECT id, message_id, filename, mime_type, size_bytes, storage_type, inline_data, disk_path, created_at, derived_text
          FROM chat_attachments
-         WHERE id = ?"
+         WHERE id = $1"
     )
     .bind(attachment_id)
     .fetch_optional(pool);
@@ -782,6 +997,7 @@ pub async fn get_attachment_by_id(
             inline_data,
             disk_path,
             created_at,
+            derived_text,
         )| {
             AttachmentRow {
                 id,
@@ -793,21 +1009,80 @@ pub async fn get_attachment_by_id(
                 inline_data,
                 disk_path,
                 created_at,
+                derived_text,
             }
         },
     ))
 }
 
+/// Store text derived from an attachment after it was saved
+pub async fn set_attachment_derived_text(
+    pool: &DbPool,
+    attachment_id: &str,
+    derived_text: &str,
+) -> anyhow::Result<()> {
+    let query_future = sqlx::query("UPDATE chat_attachments SET derived_text = $1 WHERE id = $2")
+        .bind(derived_text)
+        .bind(attachment_id)
+        .execute(pool);
+
+    db_timeout(query_future).await?;
+
+    Ok(())
+}
+
+/// Attachment metadata and derived text in an agent/user thread, newest first
+pub struct ThreadAttachment {
+    pub message_id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub derived_text: Option<String>,
+}
+
+pub async fn get_thread_attachments(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+    limit: i64,
+) -> anyhow::Result<Vec<ThreadAttachment>> {
+    let query_future = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        "SELECT a.message_id, a.filename, a.mime_type, a.derived_text
+         FROM chat_attachments a
+         JOIN chat_messages m ON m.id = a.message_id
+         WHERE m.agent_id = $1 AND m.user_id = $2
+         ORDER BY a.created_at DESC
+         LIMIT $3",
+    )
+    .bind(agent_id)
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool);
+
+    let rows = db_timeout(query_future).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(message_id, filename, mime_type, derived_text)| ThreadAttachment {
+                message_id,
+                filename,
+                mime_type,
+                derived_text,
+            },
+        )
+        .collect())
+}
+
 /// Helper: get disk paths for attachments belonging to given message IDs
 async fn get_disk_attachment_paths(
-    pool: &SqlitePool,
+    pool: &DbPool,
     message_ids: &[String],
 ) -> anyhow::Result<Vec<String>> {
     if message_ids.is_empty() {
         return Ok(vec![]);
     }
     // Build placeholders for IN clause
-    let placeholders: Vec<&str> = message_ids.iter().map(|_| "?").collect();
+    let placeholders: Vec<String> = (1..=message_ids.len()).map(|i| format!("${}", i)).collect();
     let sql = format!(
         "SELECT disk_path FROM chat_attachments WHERE message_id IN ({}) AND storage_type = 'disk' AND disk_path IS NOT NULL",
         placeholders.join(",")
@@ -823,615 +1098,1776 @@ async fn get_disk_attachment_paths(
     Ok(rows.into_iter().map(|(path,)| path).collect())
 }
 
-// ============================================================
-// MCP Dynamic Server Persistence
-// ============================================================
+// ─── Chat Summaries ───
 
-#[derive(Debug, Clone)]
-pub struct McpServerRecord {
-    pub name: String,
-    pub command: String,
-    pub args: String,
-    pub script_content: Option<String>,
-    pub description: Option<String>,
-    pub created_at: i64,
-    pub is_active: bool,
-    pub env: String, // JSON-serialized HashMap<String, String>
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ChatSummaryRow {
+    pub agent_id: String,
+    pub user_id: String,
+    pub summary: String,
+    pub summarized_until: i64,
+    pub message_count: i64,
+    pub updated_at: i64,
 }
 
-pub async fn save_mcp_server(pool: &SqlitePool, record: &McpServerRecord) -> anyhow::Result<()> {
-    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query(
-            "INSERT INTO mcp_servers \
-             (name, command, args, script_content, description, created_at, is_active, env, default_policy) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'opt-out') \
-             ON CONFLICT(name) DO UPDATE SET \
-               command = excluded.command, \
-               args = excluded.args, \
-               script_content = excluded.script_content, \
-               description = excluded.description, \
-               is_active = excluded.is_active, \
-               env = excluded.env",
-        )
-        .bind(&record.name)
-        .bind(&record.command)
-        .bind(&record.args)
-        .bind(&record.script_content)
-        .bind(&record.description)
-        .bind(record.created_at)
-        .bind(record.is_active)
-        .bind(&record.env)
-        .execute(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to save MCP server: {}", e))?;
-        Ok(())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout saving MCP server"))?
+pub async fn get_chat_summary(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+) -> anyhow::Result<Option<ChatSummaryRow>> {
+    let query_future = sqlx::query_as::<_, ChatSummaryRow>(
+        "SELECT agent_id, user_id, summary, summarized_until, message_count, updated_at \
+         FROM chat_summaries WHERE agent_id = $1 AND user_id = $2",
+    )
+    .bind(agent_id)
+    .bind(user_id)
+    .fetch_optional(pool);
+    db_timeout(query_future).await
 }
 
-pub async fn load_active_mcp_servers(pool: &SqlitePool) -> anyhow::Result<Vec<McpServerRecord>> {
-    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
-        let rows = sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                String,
-                Option<String>,
-                Option<String>,
-                i64,
-                bool,
-                String,
-            ),
-        >(
-            "SELECT name, command, args, script_content, description, created_at, is_active, env \
-             FROM mcp_servers WHERE is_active = 1 ORDER BY created_at ASC",
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to load MCP servers: {}", e))?;
+pub async fn save_chat_summary(pool: &DbPool, row: &ChatSummaryRow) -> anyhow::Result<()> {
+    let query_future = sqlx::query(
+        "INSERT INTO chat_summaries \
+         (agent_id, user_id, summary, summarized_until, message_count, updated_at) \
+         VALUES ($1, $2, $3, $4, $5, $6) \
+         ON CONFLICT(agent_id, user_id) DO UPDATE SET \
+           summary = excluded.summary, \
+           summarized_until = excluded.summarized_until, \
+           message_count = excluded.message_count, \
+           updated_at = excluded.updated_at",
+    )
+    .bind(&row.agent_id)
+    .bind(&row.user_id)
+    .bind(&row.summary)
+    .bind(row.summarized_until)
+    .bind(row.message_count)
+    .bind(row.updated_at)
+    .execute(pool);
+    db_timeout(query_future).await?;
+    Ok(())
+}
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(name, command, args, script_content, description, created_at, is_active, env)| {
-                    McpServerRecord {
-                        name,
-                        command,
-                        args,
-                        script_content,
-                        description,
-                        created_at,
-                        is_active,
-                        env,
-                    }
-                },
-            )
-            .collect())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout loading MCP servers"))?
+/// Number of messages in a thread newer than `after_ts`.
+pub async fn count_chat_messages_after(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+    after_ts: i64,
+) -> anyhow::Result<i64> {
+    let query_future = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM chat_messages WHERE agent_id = $1 AND user_id = $2 AND created_at > $3",
+    )
+    .bind(agent_id)
+    .bind(user_id)
+    .bind(after_ts)
+    .fetch_one(pool);
+    db_timeout(query_future).await
 }
 
-pub async fn deactivate_mcp_server(pool: &SqlitePool, name: &str) -> anyhow::Result<()> {
-    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query("UPDATE mcp_servers SET is_active = 0 WHERE name = ?")
-            .bind(name)
-            .execute(pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to deactivate MCP server: {}", e))?;
-        Ok(())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout deactivating MCP server"))?
+/// Oldest messages of a thread newer than `after_ts`, oldest first.
+pub async fn get_chat_messages_after(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+    after_ts: i64,
+    limit: i64,
+) -> anyhow::Result<Vec<ChatMessageRow>> {
+    let query_future = sqlx::query_as::<_, ChatMessageTuple>(
+        "SELECT id, agent_id, user_id, source, content, metadata, created_at, session_id
+         FROM chat_messages
+         WHERE agent_id = $1 AND user_id = $2 AND created_at > $3
+         ORDER BY created_at ASC
+         LIMIT $4",
+    )
+    .bind(agent_id)
+    .bind(user_id)
+    .bind(after_ts)
+    .bind(limit)
+    .fetch_all(pool);
+
+    Ok(db_timeout(query_future)
+        .await?
+        .into_iter()
+        .map(chat_message_from_tuple)
+        .collect())
 }
 
-// ============================================================
-// MCP Access Control (MCP_SERVER_UI_DESIGN.md §3)
-// ============================================================
+// ─── Chat Sessions ───
 
-/// Access control entry for MCP tool-level permissions.
-/// Maps to `mcp_access_control` table.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccessControlEntry {
-    pub id: Option<i64>,
-    pub entry_type: String, // "capability" | "server_grant" | "tool_grant"
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ChatSessionRow {
+    pub id: String,
     pub agent_id: String,
-    pub server_id: String,
-    pub tool_name: Option<String>,
-    pub permission: String, // "allow" | "deny"
-    pub granted_by: Option<String>,
-    pub granted_at: String,
-    pub expires_at: Option<String>,
-    pub justification: Option<String>,
-    pub metadata: Option<String>,
+    pub user_id: String,
+    pub name: String,
+    /// The session new messages and the agent's context come from. At most
+    /// one per agent/user thread.
+    pub active: bool,
+    pub archived: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
 }
 
-/// Save a single access control entry.
-pub async fn save_access_control_entry(
-    pool: &SqlitePool,
-    entry: &AccessControlEntry,
-) -> anyhow::Result<i64> {
-    let result = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_scalar::<_, i64>(
-            "INSERT INTO mcp_access_control \
-             (entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
-             RETURNING id",
+const CHAT_SESSION_COLUMNS: &str =
+    "id, agent_id, user_id, name, active, archived, created_at, updated_at";
+
+/// Create a session and make it the thread's active one.
+pub async fn create_chat_session(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+    name: &str,
+) -> anyhow::Result<ChatSessionRow> {
+    let now = Utc::now().timestamp_millis();
+    let row = ChatSessionRow {
+        id: format!("session.{}", cloto_shared::ClotoId::new()),
+        agent_id: agent_id.to_string(),
+        user_id: user_id.to_string(),
+        name: name.to_string(),
+        active: true,
+        archived: false,
+        created_at: now,
+        updated_at: now,
+    };
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE chat_sessions SET active = FALSE WHERE agent_id = $1 AND user_id = $2 AND active = TRUE",
         )
-        .bind(&entry.entry_type)
-        .bind(&entry.agent_id)
-        .bind(&entry.server_id)
-        .bind(&entry.tool_name)
-        .bind(&entry.permission)
-        .bind(&entry.granted_by)
-        .bind(&entry.granted_at)
-        .bind(&entry.expires_at)
-        .bind(&entry.justification)
-        .bind(&entry.metadata)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to save access control entry: {}", e))
+        .bind(agent_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "INSERT INTO chat_sessions (id, agent_id, user_id, name, active, archived, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, TRUE, FALSE, $5, $5)",
+        )
+        .bind(&row.id)
+        .bind(agent_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout saving access control entry"))??;
+    .map_err(|_| anyhow::anyhow!("Database operation timed out after {}s", DB_TIMEOUT_SECS))?
+    .map_err(|e| anyhow::anyhow!("Failed to create chat session: {}", e))?;
+    Ok(row)
+}
 
-    Ok(result)
+/// Sessions of a thread, most recently used first.
+pub async fn list_chat_sessions(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+    include_archived: bool,
+) -> anyhow::Result<Vec<ChatSessionRow>> {
+    let sql = format!(
+        "SELECT {} FROM chat_sessions
+         WHERE agent_id = $1 AND user_id = $2 AND (archived = FALSE OR $3)
+         ORDER BY updated_at DESC, id",
+        CHAT_SESSION_COLUMNS
+    );
+    let query_future = sqlx::query_as::<_, ChatSessionRow>(&sql)
+        .bind(agent_id)
+        .bind(user_id)
+        .bind(include_archived)
+        .fetch_all(pool);
+    db_timeout(query_future).await
 }
 
-/// Get all access control entries for a specific MCP server (tree view data).
-pub async fn get_access_entries_for_server(
-    pool: &SqlitePool,
-    server_id: &str,
-) -> anyhow::Result<Vec<AccessControlEntry>> {
-    let rows = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_as::<_, (i64, String, String, String, Option<String>, String, Option<String>, String, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata \
-             FROM mcp_access_control WHERE server_id = ? ORDER BY agent_id, entry_type, tool_name",
-        )
-        .bind(server_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to load access entries: {}", e))
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout loading access entries"))??;
+pub async fn get_chat_session(pool: &DbPool, id: &str) -> anyhow::Result<Option<ChatSessionRow>> {
+    let sql = format!(
+        "SELECT {} FROM chat_sessions WHERE id = $1",
+        CHAT_SESSION_COLUMNS
+    );
+    let query_future = sqlx::query_as::<_, ChatSessionRow>(&sql)
+        .bind(id)
+        .fetch_optional(pool);
+    db_timeout(query_future).await
+}
 
-    Ok(rows
-        .into_iter()
-        .map(
-            |(
-                id,
-                entry_type,
-                agent_id,
-                server_id,
-                tool_name,
-                permission,
-                granted_by,
-                granted_at,
-                expires_at,
-                justification,
-                metadata,
-            )| {
-                AccessControlEntry {
-                    id: Some(id),
-                    entry_type,
-                    agent_id,
-                    server_id,
-                    tool_name,
-                    permission,
-                    granted_by,
-                    granted_at,
-                    expires_at,
-                    justification,
-                    metadata,
-                }
-            },
-        )
-        .collect())
+pub async fn get_active_chat_session(
+    pool: &DbPool,
+    agent_id: &str,
+    user_id: &str,
+) -> anyhow::Result<Option<ChatSessionRow>> {
+    let sql = format!(
+        "SELECT {} FROM chat_sessions WHERE agent_id = $1 AND user_id = $2 AND active = TRUE",
+        CHAT_SESSION_COLUMNS
+    );
+    let query_future = sqlx::query_as::<_, ChatSessionRow>(&sql)
+        .bind(agent_id)
+        .bind(user_id)
+        .fetch_optional(pool);
+    db_timeout(query_future).await
 }
 
-/// Get all access control entries for a specific agent (by-agent view).
-pub async fn get_access_entries_for_agent(
-    pool: &SqlitePool,
+/// The thread's active session, creating a "Default" one if there is none.
+pub async fn ensure_active_chat_session(
+    pool: &DbPool,
     agent_id: &str,
-) -> anyhow::Result<Vec<AccessControlEntry>> {
-    let rows = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_as::<_, (i64, String, String, String, Option<String>, String, Option<String>, String, Option<String>, Option<String>, Option<String>)>(
-            "SELECT id, entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata \
-             FROM mcp_access_control WHERE agent_id = ? ORDER BY server_id, entry_type, tool_name",
+    user_id: &str,
+) -> anyhow::Result<ChatSessionRow> {
+    if let Some(session) = get_active_chat_session(pool, agent_id, user_id).await? {
+        return Ok(session);
+    }
+    match create_chat_session(pool, agent_id, user_id, "Default").await {
+        Ok(session) => Ok(session),
+        // Lost a race with another writer; its session is the active one now
+        Err(e) => get_active_chat_session(pool, agent_id, user_id)
+            .await?
+            .ok_or(e),
+    }
+}
+
+/// Make a session the active one of its thread (un-archiving it).
+/// Returns `false` if the session does not exist.
+pub async fn activate_chat_session(pool: &DbPool, id: &str) -> anyhow::Result<bool> {
+    let now = Utc::now().timestamp_millis();
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let mut tx = pool.begin().await?;
+        let thread: Option<(String, String)> =
+            sqlx::query_as("SELECT agent_id, user_id FROM chat_sessions WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let Some((agent_id, user_id)) = thread else {
+            return Ok(false);
+        };
+        sqlx::query(
+            "UPDATE chat_sessions SET active = FALSE WHERE agent_id = $1 AND user_id = $2 AND active = TRUE",
         )
-        .bind(agent_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to load access entries for agent: {}", e))
+        .bind(&agent_id)
+        .bind(&user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE chat_sessions SET active = TRUE, archived = FALSE, updated_at = $1 WHERE id = $2",
+        )
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout loading access entries for agent"))??;
+    .map_err(|_| anyhow::anyhow!("Database operation timed out after {}s", DB_TIMEOUT_SECS))?
+    .map_err(|e: sqlx::Error| anyhow::anyhow!("Failed to switch chat session: {}", e))
+}
 
-    Ok(rows
+/// Archive a session. An archived session is never active; the thread's next
+/// message starts a new "Default" session unless another one is switched to.
+pub async fn archive_chat_session(pool: &DbPool, id: &str) -> anyhow::Result<bool> {
+    let query_future =
+        sqlx::query("UPDATE chat_sessions SET archived = TRUE, active = FALSE WHERE id = $1")
+            .bind(id)
+            .execute(pool);
+    Ok(db_timeout(query_future).await?.rows_affected() > 0)
+}
+
+/// Messages of one session with cursor-based pagination (newest first).
+pub async fn get_chat_session_messages(
+    pool: &DbPool,
+    session_id: &str,
+    before_ts: Option<i64>,
+    limit: i64,
+) -> anyhow::Result<Vec<ChatMessageRow>> {
+    let query_future = sqlx::query_as::<_, ChatMessageTuple>(
+        "SELECT id, agent_id, user_id, source, content, metadata, created_at, session_id
+         FROM chat_messages
+         WHERE session_id = $1 AND created_at < $2
+         ORDER BY created_at DESC
+         LIMIT $3",
+    )
+    .bind(session_id)
+    .bind(before_ts.unwrap_or(i64::MAX))
+    .bind(limit.min(200))
+    .fetch_all(pool);
+    Ok(db_timeout(query_future)
+        .await?
         .into_iter()
-        .map(
-            |(
-                id,
-                entry_type,
-                agent_id,
-                server_id,
-                tool_name,
-                permission,
-                granted_by,
-                granted_at,
-                expires_at,
-                justification,
-                metadata,
-            )| {
-                AccessControlEntry {
-                    id: Some(id),
-                    entry_type,
-                    agent_id,
-                    server_id,
-                    tool_name,
-                    permission,
-                    granted_by,
-                    granted_at,
-                    expires_at,
-                    justification,
-                    metadata,
-                }
-            },
-        )
+        .map(chat_message_from_tuple)
         .collect())
 }
 
-/// Bulk update access control entries for a server.
-/// Deletes all non-capability entries for the server and inserts the new ones in a transaction.
-pub async fn put_access_entries(
-    pool: &SqlitePool,
-    server_id: &str,
-    entries: &[AccessControlEntry],
+/// Tool-call transcript of the agentic run started by a chat message.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ChatToolHistoryRow {
+    /// The message the run answered
+    pub message_id: String,
+    pub agent_id: String,
+    pub engine_id: String,
+    /// JSON array of OpenAI-style assistant / tool messages, redacted
+    pub history: String,
+    pub created_at: i64,
+}
+
+/// Save the transcript of a run, replacing any earlier one for the message.
+pub async fn save_chat_tool_history(pool: &DbPool, row: &ChatToolHistoryRow) -> anyhow::Result<()> {
+    db_timeout(
+        sqlx::query(
+            "INSERT INTO chat_tool_history (message_id, agent_id, engine_id, history, created_at) \
+             VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT(message_id) DO UPDATE SET engine_id = excluded.engine_id, \
+             history = excluded.history, created_at = excluded.created_at",
+        )
+        .bind(&row.message_id)
+        .bind(&row.agent_id)
+        .bind(&row.engine_id)
+        .bind(&row.history)
+        .bind(row.created_at)
+        .execute(pool),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Transcripts of the given messages of an agent.
+pub async fn get_chat_tool_history(
+    pool: &DbPool,
+    agent_id: &str,
+    message_ids: &[String],
+) -> anyhow::Result<Vec<ChatToolHistoryRow>> {
+    if message_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let placeholders: Vec<String> = (2..=message_ids.len() + 1)
+        .map(|i| format!("${}", i))
+        .collect();
+    let sql = format!(
+        "SELECT message_id, agent_id, engine_id, history, created_at FROM chat_tool_history \
+         WHERE agent_id = $1 AND message_id IN ({})",
+        placeholders.join(",")
+    );
+    let mut query = sqlx::query_as::<_, ChatToolHistoryRow>(&sql).bind(agent_id);
+    for id in message_ids {
+        query = query.bind(id);
+    }
+    db_timeout(query.fetch_all(pool)).await
+}
+
+// ─── Group Chat Rooms ───
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ChatRoomRow {
+    pub id: String,
+    pub name: String,
+    /// `round_robin` or `addressed` (see `managers::rooms::TurnPolicy`).
+    pub turn_policy: String,
+    /// Round-robin cursor into the member list.
+    pub next_turn: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ChatRoomMessageRow {
+    pub id: String,
+    pub room_id: String,
+    pub source: String,
+    /// User ID for `user` messages, agent ID for `agent` messages.
+    pub speaker_id: String,
+    pub speaker_name: String,
+    pub content: String,
+    pub created_at: i64,
+}
+
+/// Create a room with its members, in turn order.
+pub async fn create_chat_room(
+    pool: &DbPool,
+    room: &ChatRoomRow,
+    agent_ids: &[String],
 ) -> anyhow::Result<()> {
     timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        let mut tx = pool.begin().await.map_err(|e| anyhow::anyhow!("Failed to begin transaction: {}", e))?;
-
-        // Delete existing server_grant and tool_grant entries (preserve capability entries)
+        let mut tx = pool.begin().await?;
         sqlx::query(
-            "DELETE FROM mcp_access_control WHERE server_id = ? AND entry_type IN ('server_grant', 'tool_grant')",
+            "INSERT INTO chat_rooms (id, name, turn_policy, next_turn, created_at) VALUES ($1, $2, $3, $4, $5)",
         )
-        .bind(server_id)
+        .bind(&room.id)
+        .bind(&room.name)
+        .bind(&room.turn_policy)
+        .bind(room.next_turn)
+        .bind(room.created_at)
         .execute(&mut *tx)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to delete old access entries: {}", e))?;
-
-        // Insert new entries
-        for entry in entries {
-            if entry.entry_type == "capability" {
-                continue; // Don't overwrite capability entries via bulk update
-            }
-            sqlx::query(
-                "INSERT INTO mcp_access_control \
-                 (entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata) \
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            )
-            .bind(&entry.entry_type)
-            .bind(&entry.agent_id)
-            .bind(&entry.server_id)
-            .bind(&entry.tool_name)
-            .bind(&entry.permission)
-            .bind(&entry.granted_by)
-            .bind(&entry.granted_at)
-            .bind(&entry.expires_at)
-            .bind(&entry.justification)
-            .bind(&entry.metadata)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to insert access entry: {}", e))?;
+        .await?;
+        for (position, agent_id) in agent_ids.iter().enumerate() {
+            #[allow(clippy::cast_possible_wrap)]
+            sqlx::query("INSERT INTO chat_room_members (room_id, agent_id, position) VALUES ($1, $2, $3)")
+                .bind(&room.id)
+                .bind(agent_id)
+                .bind(position as i64)
+                .execute(&mut *tx)
+                .await?;
         }
-
-        tx.commit().await.map_err(|e| anyhow::anyhow!("Failed to commit transaction: {}", e))?;
-        Ok(())
+        tx.commit().await
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout updating access entries"))?
+    .map_err(|_| anyhow::anyhow!("Database operation timed out after {}s", DB_TIMEOUT_SECS))?
+    .map_err(|e| anyhow::anyhow!("Failed to create chat room: {}", e))
 }
 
-/// Resolve tool access for an agent.
-/// Priority: tool_grant > server_grant > default_policy
-pub async fn resolve_tool_access(
-    pool: &SqlitePool,
-    agent_id: &str,
-    server_id: &str,
-    tool_name: &str,
-) -> anyhow::Result<String> {
-    // 1. Check for explicit tool_grant
-    let tool_grant = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_scalar::<_, String>(
-            "SELECT permission FROM mcp_access_control \
-             WHERE agent_id = ? AND server_id = ? AND tool_name = ? AND entry_type = 'tool_grant' \
-             AND (expires_at IS NULL OR expires_at > datetime('now')) \
+pub async fn list_chat_rooms(pool: &DbPool) -> anyhow::Result<Vec<ChatRoomRow>> {
+    let query_future = sqlx::query_as::<_, ChatRoomRow>(
+        "SELECT id, name, turn_policy, next_turn, created_at FROM chat_rooms ORDER BY created_at DESC",
+    )
+    .fetch_all(pool);
+    db_timeout(query_future).await
+}
+
+pub async fn get_chat_room(pool: &DbPool, room_id: &str) -> anyhow::Result<Option<ChatRoomRow>> {
+    let query_future = sqlx::query_as::<_, ChatRoomRow>(
+        "SELECT id, name, turn_policy, next_turn, created_at FROM chat_rooms WHERE id = $1",
+    )
+    .bind(room_id)
+    .fetch_optional(pool);
+    db_timeout(query_future).await
+}
+
+/// Member agent IDs in turn order.
+pub async fn get_chat_room_members(pool: &DbPool, room_id: &str) -> anyhow::Result<Vec<String>> {
+    let query_future = sqlx::query_as::<_, (String,)>(
+        "SELECT agent_id FROM chat_room_members WHERE room_id = $1 ORDER BY position",
+    )
+    .bind(room_id)
+    .fetch_all(pool);
+    Ok(db_timeout(query_future)
+        .await?
+        .into_iter()
+        .map(|(id,)| id)
+        .collect())
+}
+
+/// Claim the current round-robin turn and advance the cursor. Returns the
+/// claimed turn (not yet reduced modulo the member count).
+pub async fn take_chat_room_turn(pool: &DbPool, room_id: &str) -> anyhow::Result<i64> {
+    let query_future = sqlx::query_as::<_, (i64,)>(
+        "UPDATE chat_rooms SET next_turn = next_turn + 1 WHERE id = $1 RETURNING next_turn - 1",
+    )
+    .bind(room_id)
+    .fetch_one(pool);
+    Ok(db_timeout(query_future).await?.0)
+}
+
+/// Delete a room; members and transcript cascade.
+pub async fn delete_chat_room(pool: &DbPool, room_id: &str) -> anyhow::Result<bool> {
+    let query_future = sqlx::query("DELETE FROM chat_rooms WHERE id = $1")
+        .bind(room_id)
+        .execute(pool);
+    Ok(db_timeout(query_future).await?.rows_affected() > 0)
+}
+
+pub async fn save_chat_room_message(pool: &DbPool, msg: &ChatRoomMessageRow) -> anyhow::Result<()> {
+    let query_future = sqlx::query(
+        "INSERT INTO chat_room_messages (id, room_id, source, speaker_id, speaker_name, content, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(&msg.id)
+    .bind(&msg.room_id)
+    .bind(&msg.source)
+    .bind(&msg.speaker_id)
+    .bind(&msg.speaker_name)
+    .bind(&msg.content)
+    .bind(msg.created_at)
+    .execute(pool);
+    db_timeout(query_future).await?;
+    Ok(())
+}
+
+/// Room transcript with cursor-based pagination (newest first).
+pub async fn get_chat_room_messages(
+    pool: &DbPool,
+    room_id: &str,
+    before_ts: Option<i64>,
+    limit: i64,
+) -> anyhow::Result<Vec<ChatRoomMessageRow>> {
+    let query_future = sqlx::query_as::<_, ChatRoomMessageRow>(
+        "SELECT id, room_id, source, speaker_id, speaker_name, content, created_at
+         FROM chat_room_messages
+         WHERE room_id = $1 AND created_at < $2
+         ORDER BY created_at DESC, rowid DESC
+         LIMIT $3",
+    )
+    .bind(room_id)
+    .bind(before_ts.unwrap_or(i64::MAX))
+    .bind(limit.min(200))
+    .fetch_all(pool);
+    db_timeout(query_future).await
+}
+
+// ─── Agent Plugin Bindings ───
+
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct AgentToolRule {
+    /// Filled from the enclosing binding when rules are written.
+    #[serde(default)]
+    pub plugin_id: String,
+    pub tool_name: String,
+    pub permission: String, // "allow" | "deny"
+}
+
+/// A plugin bound to an agent, with its per-tool rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentPluginBinding {
+    pub plugin_id: String,
+    #[serde(default)]
+    pub pos_x: i64,
+    #[serde(default)]
+    pub pos_y: i64,
+    #[serde(default)]
+    pub tools: Vec<AgentToolRule>,
+}
+
+pub async fn get_agent_tool_rules(
+    pool: &DbPool,
+    agent_id: &str,
+) -> anyhow::Result<Vec<AgentToolRule>> {
+    let query_future = sqlx::query_as::<_, AgentToolRule>(
+        "SELECT plugin_id, tool_name, permission FROM agent_plugin_tools \
+         WHERE agent_id = $1 ORDER BY plugin_id, tool_name",
+    )
+    .bind(agent_id)
+    .fetch_all(pool);
+    db_timeout(query_future).await
+}
+
+pub async fn get_agent_plugin_bindings(
+    pool: &DbPool,
+    agent_id: &str,
+) -> anyhow::Result<Vec<AgentPluginBinding>> {
+    let query_future = sqlx::query_as::<_, (String, i64, i64)>(
+        "SELECT plugin_id, pos_x, pos_y FROM agent_plugins \
+         WHERE agent_id = $1 ORDER BY pos_y, pos_x, plugin_id",
+    )
+    .bind(agent_id)
+    .fetch_all(pool);
+    let rows = db_timeout(query_future).await?;
+    let rules = get_agent_tool_rules(pool, agent_id).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(plugin_id, pos_x, pos_y)| AgentPluginBinding {
+            tools: rules
+                .iter()
+                .filter(|r| r.plugin_id == plugin_id)
+                .cloned()
+                .collect(),
+            plugin_id,
+            pos_x,
+            pos_y,
+        })
+        .collect())
+}
+
+/// Replace an agent's plugin bindings and tool rules.
+pub async fn put_agent_plugin_bindings(
+    pool: &DbPool,
+    agent_id: &str,
+    bindings: &[AgentPluginBinding],
+) -> anyhow::Result<()> {
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let mut tx = pool.begin().await?;
+        // Tool rules cascade from agent_plugins
+        sqlx::query("DELETE FROM agent_plugins WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&mut *tx)
+            .await?;
+        for binding in bindings {
+            sqlx::query(
+                "INSERT INTO agent_plugins (agent_id, plugin_id, pos_x, pos_y) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(agent_id)
+            .bind(&binding.plugin_id)
+            .bind(binding.pos_x)
+            .bind(binding.pos_y)
+            .execute(&mut *tx)
+            .await?;
+            for rule in &binding.tools {
+                sqlx::query(
+                    "INSERT INTO agent_plugin_tools (agent_id, plugin_id, tool_name, permission) \
+                     VALUES ($1, $2, $3, $4)",
+                )
+                .bind(agent_id)
+                .bind(&binding.plugin_id)
+                .bind(&rule.tool_name)
+                .bind(&rule.permission)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database operation timed out after {}s", DB_TIMEOUT_SECS))?
+    .map_err(|e| anyhow::anyhow!("Failed to update agent plugins: {}", e))
+}
+
+// ============================================================
+// MCP Dynamic Server Persistence
+// ============================================================
+
+#[derive(Debug, Clone)]
+pub struct McpServerRecord {
+    pub name: String,
+    pub command: String,
+    pub args: String,
+    pub script_content: Option<String>,
+    pub description: Option<String>,
+    pub created_at: i64,
+    pub is_active: bool,
+    pub env: String, // JSON-serialized HashMap<String, String>
+}
+
+pub async fn save_mcp_server(pool: &DbPool, record: &McpServerRecord) -> anyhow::Result<()> {
+    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query(
+            "INSERT INTO mcp_servers \
+             (name, command, args, script_content, description, created_at, is_active, env, default_policy) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'opt-out') \
+             ON CONFLICT(name) DO UPDATE SET \
+               command = excluded.command, \
+               args = excluded.args, \
+               script_content = excluded.script_content, \
+               description = excluded.description, \
+               is_active = excluded.is_active, \
+               env = excluded.env",
+        )
+        .bind(&record.name)
+        .bind(&record.command)
+        .bind(&record.args)
+        .bind(&record.script_content)
+        .bind(&record.description)
+        .bind(record.created_at)
+        .bind(record.is_active)
+        .bind(&record.env)
+        .execute(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to save MCP server: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout saving MCP server"))?
+}
+
+pub async fn load_active_mcp_servers(pool: &DbPool) -> anyhow::Result<Vec<McpServerRecord>> {
+    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let rows = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                i64,
+                bool,
+                String,
+            ),
+        >(
+            "SELECT name, command, args, script_content, description, created_at, is_active, env \
+             FROM mcp_servers WHERE is_active = TRUE ORDER BY created_at ASC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load MCP servers: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(name, command, args, script_content, description, created_at, is_active, env)| {
+                    McpServerRecord {
+                        name,
+                        command,
+                        args,
+                        script_content,
+                        description,
+                        created_at,
+                        is_active,
+                        env,
+                    }
+                },
+            )
+            .collect())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout loading MCP servers"))?
+}
+
+pub async fn deactivate_mcp_server(pool: &DbPool, name: &str) -> anyhow::Result<()> {
+    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query("UPDATE mcp_servers SET is_active = FALSE WHERE name = $1")
+            .bind(name)
+            .execute(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to deactivate MCP server: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout deactivating MCP server"))?
+}
+
+// ============================================================
+// MCP Access Control (MCP_SERVER_UI_DESIGN.md §3)
+// ============================================================
+
+/// Access control entry for MCP tool-level permissions.
+/// Maps to `mcp_access_control` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessControlEntry {
+    pub id: Option<i64>,
+    pub entry_type: String, // "capability" | "server_grant" | "tool_grant"
+    pub agent_id: String,
+    pub server_id: String,
+    pub tool_name: Option<String>,
+    pub permission: String, // "allow" | "deny"
+    pub granted_by: Option<String>,
+    pub granted_at: String,
+    pub expires_at: Option<String>,
+    pub justification: Option<String>,
+    pub metadata: Option<String>,
+}
+
+/// Save a single access control entry.
+pub async fn save_access_control_entry(
+    pool: &DbPool,
+    entry: &AccessControlEntry,
+) -> anyhow::Result<i64> {
+    let result = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO mcp_access_control \
+             (entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+             RETURNING id",
+        )
+        .bind(&entry.entry_type)
+        .bind(&entry.agent_id)
+        .bind(&entry.server_id)
+        .bind(&entry.tool_name)
+        .bind(&entry.permission)
+        .bind(&entry.granted_by)
+        .bind(&entry.granted_at)
+        .bind(&entry.expires_at)
+        .bind(&entry.justification)
+        .bind(&entry.metadata)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to save access control entry: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout saving access control entry"))??;
+
+    Ok(result)
+}
+
+/// Get all access control entries for a specific MCP server (tree view data).
+pub async fn get_access_entries_for_server(
+    pool: &DbPool,
+    server_id: &str,
+) -> anyhow::Result<Vec<AccessControlEntry>> {
+    let rows = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_as::<_, (i64, String, String, String, Option<String>, String, Option<String>, String, Option<String>, Option<String>, Option<String>)>(
+            "SELECT id, entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata \
+             FROM mcp_access_control WHERE server_id = $1 ORDER BY agent_id, entry_type, tool_name",
+        )
+        .bind(server_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load access entries: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout loading access entries"))??;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                entry_type,
+                agent_id,
+                server_id,
+                tool_name,
+                permission,
+                granted_by,
+                granted_at,
+                expires_at,
+                justification,
+                metadata,
+            )| {
+                AccessControlEntry {
+                    id: Some(id),
+                    entry_type,
+                    agent_id,
+                    server_id,
+                    tool_name,
+                    permission,
+                    granted_by,
+                    granted_at,
+                    expires_at,
+                    justification,
+                    metadata,
+                }
+            },
+        )
+        .collect())
+}
+
+/// Get all access control entries for a specific agent (by-agent view).
+pub async fn get_access_entries_for_agent(
+    pool: &DbPool,
+    agent_id: &str,
+) -> anyhow::Result<Vec<AccessControlEntry>> {
+    let rows = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_as::<_, (i64, String, String, String, Option<String>, String, Option<String>, String, Option<String>, Option<String>, Option<String>)>(
+            "SELECT id, entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata \
+             FROM mcp_access_control WHERE agent_id = $1 ORDER BY server_id, entry_type, tool_name",
+        )
+        .bind(agent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load access entries for agent: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout loading access entries for agent"))??;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                entry_type,
+                agent_id,
+                server_id,
+                tool_name,
+                permission,
+                granted_by,
+                granted_at,
+                expires_at,
+                justification,
+                metadata,
+            )| {
+                AccessControlEntry {
+                    id: Some(id),
+                    entry_type,
+                    agent_id,
+                    server_id,
+                    tool_name,
+                    permission,
+                    granted_by,
+                    granted_at,
+                    expires_at,
+                    justification,
+                    metadata,
+                }
+            },
+        )
+        .collect())
+}
+
+/// Bulk update access control entries for a server.
+/// Deletes all non-capability entries for the server and inserts the new ones in a transaction.
+pub async fn put_access_entries(
+    pool: &DbPool,
+    server_id: &str,
+    entries: &[AccessControlEntry],
+) -> anyhow::Result<()> {
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let mut tx = pool.begin().await.map_err(|e| anyhow::anyhow!("Failed to begin transaction: {}", e))?;
+
+        // Delete existing server_grant and tool_grant entries (preserve capability entries)
+        sqlx::query(
+            "DELETE FROM mcp_access_control WHERE server_id = $1 AND entry_type IN ('server_grant', 'tool_grant')",
+        )
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to delete old access entries: {}", e))?;
+
+        // Insert new entries
+        for entry in entries {
+            if entry.entry_type == "capability" {
+                continue; // Don't overwrite capability entries via bulk update
+            }
+            sqlx::query(
+                "INSERT INTO mcp_access_control \
+                 (entry_type, agent_id, server_id, tool_name, permission, granted_by, granted_at, expires_at, justification, metadata) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(&entry.entry_type)
+            .bind(&entry.agent_id)
+            .bind(&entry.server_id)
+            .bind(&entry.tool_name)
+            .bind(&entry.permission)
+            .bind(&entry.granted_by)
+            .bind(&entry.granted_at)
+            .bind(&entry.expires_at)
+            .bind(&entry.justification)
+            .bind(&entry.metadata)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to insert access entry: {}", e))?;
+        }
+
+        tx.commit().await.map_err(|e| anyhow::anyhow!("Failed to commit transaction: {}", e))?;
+        Ok(())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout updating access entries"))?
+}
+
+/// Resolve tool access for an agent.
+/// Priority: tool_grant > server_grant > default_policy
+pub async fn resolve_tool_access(
+    pool: &DbPool,
+    agent_id: &str,
+    server_id: &str,
+    tool_name: &str,
+) -> anyhow::Result<String> {
+    // 1. Check for explicit tool_grant
+    let tool_grant = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_scalar::<_, String>(
+            "SELECT permission FROM mcp_access_control \
+             WHERE agent_id = $1 AND server_id = $2 AND tool_name = $3 AND entry_type = 'tool_grant' \
+             AND (expires_at IS NULL OR expires_at > $4) \
+             LIMIT 1",
+        )
+        .bind(agent_id)
+        .bind(server_id)
+        .bind(tool_name)
+        .bind(sql_now())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to check tool grant: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout checking tool grant"))??;
+
+    if let Some(permission) = tool_grant {
+        return Ok(permission);
+    }
+
+    // 2. Check for server_grant
+    let server_grant = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_scalar::<_, String>(
+            "SELECT permission FROM mcp_access_control \
+             WHERE agent_id = $1 AND server_id = $2 AND entry_type = 'server_grant' AND tool_name IS NULL \
+             AND (expires_at IS NULL OR expires_at > $3) \
              LIMIT 1",
         )
-        .bind(agent_id)
-        .bind(server_id)
-        .bind(tool_name)
-        .fetch_optional(pool)
+        .bind(agent_id)
+        .bind(server_id)
+        .bind(sql_now())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to check server grant: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout checking server grant"))??;
+
+    if let Some(permission) = server_grant {
+        return Ok(permission);
+    }
+
+    // 3. Fall back to server default_policy
+    let policy = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_scalar::<_, String>(
+            "SELECT default_policy FROM mcp_servers WHERE name = $1 LIMIT 1",
+        )
+        .bind(server_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to check default policy: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout checking default policy"))??;
+
+    match policy.as_deref() {
+        Some("opt-out") => Ok("allow".to_string()),
+        _ => Ok("deny".to_string()), // opt-in = deny by default
+    }
+}
+
+/// Get access summary for a server's tools (Summary Bar data).
+/// Returns (tool_name, allowed_count, denied_count, inherited_count).
+pub async fn get_access_summary(
+    pool: &DbPool,
+    server_id: &str,
+) -> anyhow::Result<Vec<(String, i64, i64, i64)>> {
+    // This query counts explicit grants per tool.
+    // "inherited" means agents that have a server_grant but no tool_grant.
+    let rows = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_as::<_, (String, i64, i64)>(
+            "SELECT tool_name, \
+             SUM(CASE WHEN permission = 'allow' THEN 1 ELSE 0 END) as allowed, \
+             SUM(CASE WHEN permission = 'deny' THEN 1 ELSE 0 END) as denied \
+             FROM mcp_access_control \
+             WHERE server_id = $1 AND entry_type = 'tool_grant' AND tool_name IS NOT NULL \
+             GROUP BY tool_name",
+        )
+        .bind(server_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get access summary: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout getting access summary"))??;
+
+    // Count agents with server_grant but no tool_grant (inherited)
+    let server_grant_count = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(DISTINCT agent_id) FROM mcp_access_control \
+             WHERE server_id = $1 AND entry_type = 'server_grant'",
+        )
+        .bind(server_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to count server grants: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout counting server grants"))??;
+
+    Ok(rows
+        .into_iter()
+        .map(|(tool_name, allowed, denied)| {
+            let explicit = allowed + denied;
+            let inherited = (server_grant_count - explicit).max(0);
+            (tool_name, allowed, denied, inherited)
+        })
+        .collect())
+}
+
+/// Get MCP server settings (including default_policy from the extended mcp_servers table).
+pub async fn get_mcp_server_settings(
+    pool: &DbPool,
+    name: &str,
+) -> anyhow::Result<Option<(McpServerRecord, String)>> {
+    let result = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, i64, bool, String, String)>(
+            "SELECT name, command, args, script_content, description, created_at, is_active, default_policy, env \
+             FROM mcp_servers WHERE name = $1",
+        )
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get MCP server settings: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout getting MCP server settings"))??;
+
+    Ok(result.map(
+        |(
+            name,
+            command,
+            args,
+            script_content,
+            description,
+            created_at,
+            is_active,
+            default_policy,
+            env,
+        )| {
+            (
+                McpServerRecord {
+                    name,
+                    command,
+                    args,
+                    script_content,
+                    description,
+                    created_at,
+                    is_active,
+                    env,
+                },
+                default_policy,
+            )
+        },
+    ))
+}
+
+/// Update MCP server default_policy.
+/// Returns the number of rows affected (0 if server not in DB).
+pub async fn update_mcp_server_default_policy(
+    pool: &DbPool,
+    name: &str,
+    default_policy: &str,
+) -> anyhow::Result<u64> {
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let result = sqlx::query("UPDATE mcp_servers SET default_policy = $1 WHERE name = $2")
+            .bind(default_policy)
+            .bind(name)
+            .execute(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to update default policy: {}", e))?;
+
+        Ok(result.rows_affected())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout updating default policy"))?
+}
+
+/// Update MCP server environment variables (JSON-serialized HashMap).
+pub async fn update_mcp_server_env(
+    pool: &DbPool,
+    name: &str,
+    env_json: &str,
+) -> anyhow::Result<u64> {
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let result = sqlx::query("UPDATE mcp_servers SET env = $1 WHERE name = $2")
+            .bind(env_json)
+            .bind(name)
+            .execute(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to update env: {}", e))?;
+
+        Ok(result.rows_affected())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout updating env"))?
+}
+
+/// Get MCP server resource limits (JSON-serialized `ResourceLimits`), if set.
+pub async fn get_mcp_server_resource_limits(
+    pool: &DbPool,
+    name: &str,
+) -> anyhow::Result<Option<String>> {
+    let row: Option<(Option<String>,)> = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query_as("SELECT resource_limits FROM mcp_servers WHERE name = $1")
+            .bind(name)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get resource limits: {}", e))
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout getting resource limits"))??;
+
+    Ok(row.and_then(|(limits,)| limits))
+}
+
+/// Update MCP server resource limits (JSON-serialized `ResourceLimits`).
+/// Returns the number of rows affected (0 if server not in DB).
+pub async fn update_mcp_server_resource_limits(
+    pool: &DbPool,
+    name: &str,
+    limits_json: &str,
+) -> anyhow::Result<u64> {
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let result = sqlx::query("UPDATE mcp_servers SET resource_limits = $1 WHERE name = $2")
+            .bind(limits_json)
+            .bind(name)
+            .execute(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to update resource limits: {}", e))?;
+
+        Ok(result.rows_affected())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout updating resource limits"))?
+}
+
+/// Insert a config-loaded MCP server into the DB so its settings can be persisted.
+pub async fn ensure_mcp_server_in_db(
+    pool: &DbPool,
+    name: &str,
+    command: &str,
+    args: &str,
+    default_policy: &str,
+) -> anyhow::Result<()> {
+    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query(
+            "INSERT INTO mcp_servers (name, command, args, created_at, is_active, default_policy) \
+             VALUES ($1, $2, $3, $4, TRUE, $5) \
+             ON CONFLICT(name) DO UPDATE SET default_policy = excluded.default_policy",
+        )
+        .bind(name)
+        .bind(command)
+        .bind(args)
+        .bind(Utc::now().timestamp())
+        .bind(default_policy)
+        .execute(pool)
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to check tool grant: {}", e))
+        .map_err(|e| anyhow::anyhow!("Failed to upsert MCP server: {}", e))?;
+        Ok(())
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout checking tool grant"))??;
+    .map_err(|_| anyhow::anyhow!("Database timeout upserting MCP server"))?
+}
 
-    if let Some(permission) = tool_grant {
-        return Ok(permission);
-    }
+// ============================================================
+// Revoked API Keys
+// ============================================================
 
-    // 2. Check for server_grant
-    let server_grant = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_scalar::<_, String>(
-            "SELECT permission FROM mcp_access_control \
-             WHERE agent_id = ? AND server_id = ? AND entry_type = 'server_grant' AND tool_name IS NULL \
-             AND (expires_at IS NULL OR expires_at > datetime('now')) \
-             LIMIT 1",
-        )
-        .bind(agent_id)
-        .bind(server_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to check server grant: {}", e))
+/// Compute a deterministic fingerprint of a key for revocation storage.
+/// Uses DefaultHasher with a fixed salt (not crypto-grade, but sufficient
+/// for revocation purposes on a local LAN-only dashboard).
+#[must_use]
+pub fn hash_api_key(key: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::fmt::Write as _;
+    use std::hash::{Hash, Hasher};
+    let mut h = DefaultHasher::new();
+    key.hash(&mut h);
+    key.len().hash(&mut h);
+    b"cloto-revoke-salt-2026".hash(&mut h);
+    let val = h.finish();
+    let mut out = String::new();
+    write!(out, "{:016x}{:016x}", val, val ^ 0xdead_beef_cafe_babe).unwrap();
+    out
+}
+
+pub async fn revoke_api_key(pool: &DbPool, key: &str) -> anyhow::Result<()> {
+    let key_hash = hash_api_key(key);
+    let now = chrono::Utc::now().timestamp_millis();
+    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
+        sqlx::query("INSERT INTO revoked_keys (key_hash, revoked_at) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(&key_hash)
+            .bind(now)
+            .execute(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to revoke API key: {}", e))?;
+        Ok(())
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout checking server grant"))??;
+    .map_err(|_| anyhow::anyhow!("Database timeout revoking API key"))?
+}
 
-    if let Some(permission) = server_grant {
-        return Ok(permission);
-    }
+pub async fn load_revoked_key_hashes(pool: &DbPool) -> anyhow::Result<Vec<String>> {
+    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT key_hash FROM revoked_keys")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load revoked key hashes: {}", e))?;
+        Ok(rows.into_iter().map(|(h,)| h).collect())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Database timeout loading revoked keys"))?
+}
 
-    // 3. Fall back to server default_policy
-    let policy = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_scalar::<_, String>(
-            "SELECT default_policy FROM mcp_servers WHERE name = ? LIMIT 1",
-        )
-        .bind(server_id)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to check default policy: {}", e))
+pub async fn is_api_key_revoked(pool: &DbPool, key: &str) -> anyhow::Result<bool> {
+    let key_hash = hash_api_key(key);
+    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT key_hash FROM revoked_keys WHERE key_hash = $1")
+                .bind(&key_hash)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to check revoked keys: {}", e))?;
+        Ok(row.is_some())
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout checking default policy"))??;
+    .map_err(|_| anyhow::anyhow!("Database timeout checking revoked keys"))?
+}
 
-    match policy.as_deref() {
-        Some("opt-out") => Ok("allow".to_string()),
-        _ => Ok("deny".to_string()), // opt-in = deny by default
+// ============================================================
+// Scoped API Keys
+// ============================================================
+
+/// A key issued via `POST /api/system/keys`. The plaintext is shown once at
+/// creation; only its SHA-256 (`hash_scoped_api_key`) is stored.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ApiKeyRow {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    /// Leading characters of the key, so operators can tell keys apart.
+    pub key_prefix: String,
+    /// `read_only`, `chat` or `admin`
+    pub scope: String,
+    pub created_at: i64,
+    /// Per-key rate limit replacing the chat and management group quotas;
+    /// both set or both `None`.
+    pub rate_per_second: Option<i32>,
+    pub rate_burst: Option<i32>,
+}
+
+impl ApiKeyRow {
+    /// The key's own rate limit, if it has one.
+    #[must_use]
+    pub fn rate_quota(&self) -> Option<crate::config::RateQuota> {
+        Some(crate::config::RateQuota {
+            per_second: u32::try_from(self.rate_per_second?).ok()?,
+            burst: u32::try_from(self.rate_burst?).ok()?,
+        })
     }
 }
 
-/// Get access summary for a server's tools (Summary Bar data).
-/// Returns (tool_name, allowed_count, denied_count, inherited_count).
-pub async fn get_access_summary(
-    pool: &SqlitePool,
-    server_id: &str,
-) -> anyhow::Result<Vec<(String, i64, i64, i64)>> {
-    // This query counts explicit grants per tool.
-    // "inherited" means agents that have a server_grant but no tool_grant.
-    let rows = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_as::<_, (String, i64, i64)>(
-            "SELECT tool_name, \
-             SUM(CASE WHEN permission = 'allow' THEN 1 ELSE 0 END) as allowed, \
-             SUM(CASE WHEN permission = 'deny' THEN 1 ELSE 0 END) as denied \
-             FROM mcp_access_control \
-             WHERE server_id = ? AND entry_type = 'tool_grant' AND tool_name IS NOT NULL \
-             GROUP BY tool_name",
+#[must_use]
+pub fn hash_scoped_api_key(key: &str) -> String {
+    use sha2::{Digest, Sha256};
+    cloto_shared::hex_encode(&Sha256::digest(key.as_bytes()))
+}
+
+pub async fn create_api_key(pool: &DbPool, row: &ApiKeyRow) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO api_keys (id, name, key_hash, key_prefix, scope, created_at, rate_per_second, rate_burst) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(&row.id)
+    .bind(&row.name)
+    .bind(&row.key_hash)
+    .bind(&row.key_prefix)
+    .bind(&row.scope)
+    .bind(row.created_at)
+    .bind(row.rate_per_second)
+    .bind(row.rate_burst)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_api_keys(pool: &DbPool) -> anyhow::Result<Vec<ApiKeyRow>> {
+    let rows = sqlx::query_as::<_, ApiKeyRow>(
+        "SELECT id, name, key_hash, key_prefix, scope, created_at, rate_per_second, rate_burst FROM api_keys ORDER BY created_at, id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Returns the deleted key's hash, or `None` if no key has that ID.
+pub async fn delete_api_key(pool: &DbPool, id: &str) -> anyhow::Result<Option<String>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("DELETE FROM api_keys WHERE id = $1 RETURNING key_hash")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(row.map(|(hash,)| hash))
+}
+
+// ============================================================
+// Dashboard Users
+// ============================================================
+
+/// A dashboard account. Only the Argon2id hash of the password is stored.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct UserRow {
+    pub id: String,
+    pub username: String,
+    #[serde(skip_serializing)]
+    pub password_hash: String,
+    /// `read_only`, `chat` or `admin`
+    pub role: String,
+    pub created_at: i64,
+}
+
+pub async fn create_user(pool: &DbPool, row: &UserRow) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO users (id, username, password_hash, role, created_at) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(&row.id)
+    .bind(&row.username)
+    .bind(&row.password_hash)
+    .bind(&row.role)
+    .bind(row.created_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_users(pool: &DbPool) -> anyhow::Result<Vec<UserRow>> {
+    let rows = sqlx::query_as::<_, UserRow>(
+        "SELECT id, username, password_hash, role, created_at FROM users ORDER BY created_at, id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn get_user_by_username(
+    pool: &DbPool,
+    username: &str,
+) -> anyhow::Result<Option<UserRow>> {
+    let row = sqlx::query_as::<_, UserRow>(
+        "SELECT id, username, password_hash, role, created_at FROM users WHERE username = $1",
+    )
+    .bind(username)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+/// Returns whether a user with that ID existed.
+pub async fn delete_user(pool: &DbPool, id: &str) -> anyhow::Result<bool> {
+    let result = sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// ============================================================
+// Tool Invocation Approvals
+// ============================================================
+
+/// A call to a tool in its server's `approval_required_tools`, awaiting (or
+/// past) an administrator's decision.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ToolInvocationRequestRow {
+    pub id: String,
+    pub server_id: String,
+    pub tool_name: String,
+    /// JSON-encoded tool arguments
+    pub arguments: String,
+    /// `pending`, `approved`, `denied` or `expired`
+    pub status: String,
+    pub created_at: i64,
+    pub decided_at: Option<i64>,
+    pub decided_by: Option<String>,
+}
+
+pub async fn create_tool_invocation_request(
+    pool: &DbPool,
+    row: &ToolInvocationRequestRow,
+) -> anyhow::Result<()> {
+    db_timeout(
+        sqlx::query(
+            "INSERT INTO tool_invocation_requests (id, server_id, tool_name, arguments, status, created_at) VALUES ($1, $2, $3, $4, $5, $6)",
         )
-        .bind(server_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to get access summary: {}", e))
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout getting access summary"))??;
+        .bind(&row.id)
+        .bind(&row.server_id)
+        .bind(&row.tool_name)
+        .bind(&row.arguments)
+        .bind(&row.status)
+        .bind(row.created_at)
+        .execute(pool),
+    )
+    .await?;
+    Ok(())
+}
 
-    // Count agents with server_grant but no tool_grant (inherited)
-    let server_grant_count = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(DISTINCT agent_id) FROM mcp_access_control \
-             WHERE server_id = ? AND entry_type = 'server_grant'",
+pub async fn list_pending_tool_invocation_requests(
+    pool: &DbPool,
+) -> anyhow::Result<Vec<ToolInvocationRequestRow>> {
+    db_timeout(
+        sqlx::query_as::<_, ToolInvocationRequestRow>(
+            "SELECT id, server_id, tool_name, arguments, status, created_at, decided_at, decided_by FROM tool_invocation_requests WHERE status = 'pending' ORDER BY created_at",
         )
-        .bind(server_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to count server grants: {}", e))
-    })
+        .fetch_all(pool),
+    )
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout counting server grants"))??;
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|(tool_name, allowed, denied)| {
-            let explicit = allowed + denied;
-            let inherited = (server_grant_count - explicit).max(0);
-            (tool_name, allowed, denied, inherited)
-        })
-        .collect())
+/// Move a pending request to `status`. Returns `false` if the request does
+/// not exist or was already decided.
+pub async fn decide_tool_invocation_request(
+    pool: &DbPool,
+    id: &str,
+    status: &str,
+    decided_by: &str,
+) -> anyhow::Result<bool> {
+    if !["approved", "denied", "expired"].contains(&status) {
+        return Err(anyhow::anyhow!(
+            "Invalid status value: '{}'. Must be 'approved', 'denied' or 'expired'",
+            status
+        ));
+    }
+    let result = db_timeout(
+        sqlx::query(
+            "UPDATE tool_invocation_requests SET status = $1, decided_at = $2, decided_by = $3 WHERE id = $4 AND status = 'pending'",
+        )
+        .bind(status)
+        .bind(Utc::now().timestamp_millis())
+        .bind(decided_by)
+        .bind(id)
+        .execute(pool),
+    )
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Expire every pending request. Used at startup: the calls that were
+/// waiting on them did not survive the restart.
+pub async fn expire_pending_tool_invocation_requests(pool: &DbPool) -> anyhow::Result<u64> {
+    let result = db_timeout(
+        sqlx::query(
+            "UPDATE tool_invocation_requests SET status = 'expired', decided_at = $1, decided_by = 'system' WHERE status = 'pending'",
+        )
+        .bind(Utc::now().timestamp_millis())
+        .execute(pool),
+    )
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// ============================================================
+// Dead-Letter Events
+// ============================================================
+
+/// An event a plugin failed to handle after its retries.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct DeadLetterEventRow {
+    pub id: String,
+    pub plugin_id: String,
+    /// The event's `type` tag (e.g. `MessageReceived`)
+    pub event_type: String,
+    /// JSON-encoded `ClotoEvent`
+    pub event: String,
+    /// Cascade depth the event was dispatched at
+    pub depth: i32,
+    /// Error from the last attempt
+    pub error: String,
+    pub attempts: i32,
+    pub created_at: i64,
+    pub replayed_at: Option<i64>,
+}
+
+pub async fn create_dead_letter_event(
+    pool: &DbPool,
+    row: &DeadLetterEventRow,
+) -> anyhow::Result<()> {
+    db_timeout(
+        sqlx::query(
+            "INSERT INTO dead_letter_events (id, plugin_id, event_type, event, depth, error, attempts, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&row.id)
+        .bind(&row.plugin_id)
+        .bind(&row.event_type)
+        .bind(&row.event)
+        .bind(row.depth)
+        .bind(&row.error)
+        .bind(row.attempts)
+        .bind(row.created_at)
+        .execute(pool),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Newest first. Replayed events are included unless `pending_only`.
+pub async fn list_dead_letter_events(
+    pool: &DbPool,
+    pending_only: bool,
+    limit: i64,
+) -> anyhow::Result<Vec<DeadLetterEventRow>> {
+    let sql = if pending_only {
+        "SELECT id, plugin_id, event_type, event, depth, error, attempts, created_at, replayed_at FROM dead_letter_events WHERE replayed_at IS NULL ORDER BY created_at DESC LIMIT $1"
+    } else {
+        "SELECT id, plugin_id, event_type, event, depth, error, attempts, created_at, replayed_at FROM dead_letter_events ORDER BY created_at DESC LIMIT $1"
+    };
+    db_timeout(
+        sqlx::query_as::<_, DeadLetterEventRow>(sql)
+            .bind(limit)
+            .fetch_all(pool),
+    )
+    .await
 }
 
-/// Get MCP server settings (including default_policy from the extended mcp_servers table).
-pub async fn get_mcp_server_settings(
-    pool: &SqlitePool,
-    name: &str,
-) -> anyhow::Result<Option<(McpServerRecord, String)>> {
-    let result = timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>, i64, bool, String, String)>(
-            "SELECT name, command, args, script_content, description, created_at, is_active, default_policy, env \
-             FROM mcp_servers WHERE name = ?",
+pub async fn get_dead_letter_event(
+    pool: &DbPool,
+    id: &str,
+) -> anyhow::Result<Option<DeadLetterEventRow>> {
+    db_timeout(
+        sqlx::query_as::<_, DeadLetterEventRow>(
+            "SELECT id, plugin_id, event_type, event, depth, error, attempts, created_at, replayed_at FROM dead_letter_events WHERE id = $1",
         )
-        .bind(name)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to get MCP server settings: {}", e))
-    })
+        .bind(id)
+        .fetch_optional(pool),
+    )
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout getting MCP server settings"))??;
+}
 
-    Ok(result.map(
-        |(
-            name,
-            command,
-            args,
-            script_content,
-            description,
-            created_at,
-            is_active,
-            default_policy,
-            env,
-        )| {
-            (
-                McpServerRecord {
-                    name,
-                    command,
-                    args,
-                    script_content,
-                    description,
-                    created_at,
-                    is_active,
-                    env,
-                },
-                default_policy,
-            )
-        },
-    ))
+/// Record a successful replay. Returns `false` if the event does not exist.
+pub async fn mark_dead_letter_event_replayed(pool: &DbPool, id: &str) -> anyhow::Result<bool> {
+    let result = db_timeout(
+        sqlx::query("UPDATE dead_letter_events SET replayed_at = $1 WHERE id = $2")
+            .bind(Utc::now().timestamp_millis())
+            .bind(id)
+            .execute(pool),
+    )
+    .await?;
+    Ok(result.rows_affected() > 0)
 }
 
-/// Update MCP server default_policy.
-/// Returns the number of rows affected (0 if server not in DB).
-pub async fn update_mcp_server_default_policy(
-    pool: &SqlitePool,
-    name: &str,
-    default_policy: &str,
-) -> anyhow::Result<u64> {
-    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        let result = sqlx::query("UPDATE mcp_servers SET default_policy = ? WHERE name = ?")
-            .bind(default_policy)
-            .bind(name)
-            .execute(pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to update default policy: {}", e))?;
+// ============================================================
+// Tool Invocations
+// ============================================================
 
-        Ok(result.rows_affected())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout updating default policy"))?
+/// One agent tool call, written by `ToolAudit`.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize, Deserialize)]
+pub struct ToolInvocationRow {
+    pub id: String,
+    pub agent_id: String,
+    pub engine_id: String,
+    pub tool_name: String,
+    /// Native plugin or MCP server that owns the tool
+    pub server_id: Option<String>,
+    /// JSON-encoded arguments, redacted and truncated
+    pub arguments: String,
+    /// `SUCCESS`, `FAILURE` or `DENIED`
+    pub status: String,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub trace_id: Option<String>,
+    pub created_at: i64,
 }
 
-/// Update MCP server environment variables (JSON-serialized HashMap).
-pub async fn update_mcp_server_env(
-    pool: &SqlitePool,
-    name: &str,
-    env_json: &str,
-) -> anyhow::Result<u64> {
-    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
-        let result = sqlx::query("UPDATE mcp_servers SET env = ? WHERE name = ?")
-            .bind(env_json)
-            .bind(name)
-            .execute(pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to update env: {}", e))?;
-
-        Ok(result.rows_affected())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout updating env"))?
+/// Filters for `list_tool_invocations`. `None` matches everything.
+#[derive(Debug, Default)]
+pub struct ToolInvocationFilter<'a> {
+    pub agent_id: Option<&'a str>,
+    pub tool_name: Option<&'a str>,
+    pub server_id: Option<&'a str>,
+    pub status: Option<&'a str>,
+    /// Only rows created before this Unix timestamp (ms), for paging
+    pub before: Option<i64>,
 }
 
-/// Insert a config-loaded MCP server into the DB so its settings can be persisted.
-pub async fn ensure_mcp_server_in_db(
-    pool: &SqlitePool,
-    name: &str,
-    command: &str,
-    args: &str,
-    default_policy: &str,
-) -> anyhow::Result<()> {
-    timeout(Duration::from_secs(DB_TIMEOUT_SECS), async {
+pub async fn create_tool_invocation(pool: &DbPool, row: &ToolInvocationRow) -> anyhow::Result<()> {
+    db_timeout(
         sqlx::query(
-            "INSERT INTO mcp_servers (name, command, args, created_at, is_active, default_policy) \
-             VALUES (?, ?, ?, unixepoch(), 1, ?) \
-             ON CONFLICT(name) DO UPDATE SET default_policy = excluded.default_policy",
+            "INSERT INTO tool_invocations (id, agent_id, engine_id, tool_name, server_id, arguments, status, error, duration_ms, trace_id, created_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
         )
-        .bind(name)
-        .bind(command)
-        .bind(args)
-        .bind(default_policy)
-        .execute(pool)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to upsert MCP server: {}", e))?;
-        Ok(())
-    })
+        .bind(&row.id)
+        .bind(&row.agent_id)
+        .bind(&row.engine_id)
+        .bind(&row.tool_name)
+        .bind(&row.server_id)
+        .bind(&row.arguments)
+        .bind(&row.status)
+        .bind(&row.error)
+        .bind(row.duration_ms)
+        .bind(&row.trace_id)
+        .bind(row.created_at)
+        .execute(pool),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Tool invocations matching `filter`, most recent first.
+pub async fn list_tool_invocations(
+    pool: &DbPool,
+    filter: &ToolInvocationFilter<'_>,
+    limit: i64,
+) -> anyhow::Result<Vec<ToolInvocationRow>> {
+    db_timeout(
+        sqlx::query_as::<_, ToolInvocationRow>(
+            "SELECT id, agent_id, engine_id, tool_name, server_id, arguments, status, error, duration_ms, trace_id, created_at \
+             FROM tool_invocations \
+             WHERE ($1 IS NULL OR agent_id = $1) \
+               AND ($2 IS NULL OR tool_name = $2) \
+               AND ($3 IS NULL OR server_id = $3) \
+               AND ($4 IS NULL OR status = $4) \
+               AND ($5 IS NULL OR created_at < $5) \
+             ORDER BY created_at DESC \
+             LIMIT $6",
+        )
+        .bind(filter.agent_id)
+        .bind(filter.tool_name)
+        .bind(filter.server_id)
+        .bind(filter.status)
+        .bind(filter.before)
+        .bind(limit)
+        .fetch_all(pool),
+    )
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout upserting MCP server"))?
 }
 
 // ============================================================
-// Revoked API Keys
+// Event Journal
 // ============================================================
 
-/// Compute a deterministic fingerprint of a key for revocation storage.
-/// Uses DefaultHasher with a fixed salt (not crypto-grade, but sufficient
-/// for revocation purposes on a local LAN-only dashboard).
-#[must_use]
-pub fn hash_api_key(key: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::fmt::Write as _;
-    use std::hash::{Hash, Hasher};
-    let mut h = DefaultHasher::new();
-    key.hash(&mut h);
-    key.len().hash(&mut h);
-    b"cloto-revoke-salt-2026".hash(&mut h);
-    let val = h.finish();
-    let mut out = String::new();
-    write!(out, "{:016x}{:016x}", val, val ^ 0xdead_beef_cafe_babe).unwrap();
-    out
+/// One event as written to the journal.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub trace_id: String,
+    pub event_type: &'static str,
+    /// JSON-encoded `ClotoEvent`, as broadcast over SSE
+    pub event: Arc<str>,
+    /// Event timestamp, Unix ms
+    pub created_at: i64,
 }
 
-pub async fn revoke_api_key(pool: &SqlitePool, key: &str) -> anyhow::Result<()> {
-    let key_hash = hash_api_key(key);
-    let now = chrono::Utc::now().timestamp_millis();
-    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
-        sqlx::query("INSERT OR IGNORE INTO revoked_keys (key_hash, revoked_at) VALUES (?, ?)")
-            .bind(&key_hash)
-            .bind(now)
-            .execute(pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to revoke API key: {}", e))?;
-        Ok(())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout revoking API key"))?
+/// Filters for `list_events` and `count_events`. `None` matches everything.
+#[derive(Debug, Default)]
+pub struct JournalFilter<'a> {
+    pub event_type: Option<&'a str>,
+    pub trace_id: Option<&'a str>,
+    /// Only events at or after this Unix timestamp (ms)
+    pub since: Option<i64>,
+    /// Only events before this Unix timestamp (ms)
+    pub until: Option<i64>,
 }
 
-pub async fn load_revoked_key_hashes(pool: &SqlitePool) -> anyhow::Result<Vec<String>> {
-    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
-        let rows: Vec<(String,)> = sqlx::query_as("SELECT key_hash FROM revoked_keys")
-            .fetch_all(pool)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to load revoked key hashes: {}", e))?;
-        Ok(rows.into_iter().map(|(h,)| h).collect())
+/// Append a batch of events in one transaction.
+pub async fn insert_events(pool: &DbPool, entries: &[JournalEntry]) -> anyhow::Result<()> {
+    db_timeout(async {
+        let mut tx = pool.begin().await?;
+        for entry in entries {
+            sqlx::query(
+                "INSERT INTO events (trace_id, event_type, event, created_at) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&entry.trace_id)
+            .bind(entry.event_type)
+            .bind(&*entry.event)
+            .bind(entry.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
     })
     .await
-    .map_err(|_| anyhow::anyhow!("Database timeout loading revoked keys"))?
 }
 
-pub async fn is_api_key_revoked(pool: &SqlitePool, key: &str) -> anyhow::Result<bool> {
-    let key_hash = hash_api_key(key);
-    tokio::time::timeout(std::time::Duration::from_secs(DB_TIMEOUT_SECS), async {
-        let row: Option<(String,)> =
-            sqlx::query_as("SELECT key_hash FROM revoked_keys WHERE key_hash = ?")
-                .bind(&key_hash)
-                .fetch_optional(pool)
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to check revoked keys: {}", e))?;
-        Ok(row.is_some())
-    })
-    .await
-    .map_err(|_| anyhow::anyhow!("Database timeout checking revoked keys"))?
+/// Number of journaled events matching `filter`.
+pub async fn count_events(pool: &DbPool, filter: &JournalFilter<'_>) -> anyhow::Result<i64> {
+    let (count,): (i64,) = db_timeout(
+        sqlx::query_as(
+            "SELECT COUNT(*) FROM events \
+             WHERE ($1 IS NULL OR event_type = $1) \
+               AND ($2 IS NULL OR trace_id = $2) \
+               AND ($3 IS NULL OR created_at >= $3) \
+               AND ($4 IS NULL OR created_at < $4)",
+        )
+        .bind(filter.event_type)
+        .bind(filter.trace_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_one(pool),
+    )
+    .await?;
+    Ok(count)
+}
+
+/// Event JSON of journaled events matching `filter`, most recent first,
+/// skipping the `offset` most recent.
+pub async fn list_events(
+    pool: &DbPool,
+    filter: &JournalFilter<'_>,
+    limit: i64,
+    offset: i64,
+) -> anyhow::Result<Vec<String>> {
+    let rows: Vec<(String,)> = db_timeout(
+        sqlx::query_as(
+            "SELECT event FROM events \
+             WHERE ($1 IS NULL OR event_type = $1) \
+               AND ($2 IS NULL OR trace_id = $2) \
+               AND ($3 IS NULL OR created_at >= $3) \
+               AND ($4 IS NULL OR created_at < $4) \
+             ORDER BY created_at DESC, id DESC \
+             LIMIT $5 OFFSET $6",
+        )
+        .bind(filter.event_type)
+        .bind(filter.trace_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool),
+    )
+    .await?;
+    Ok(rows.into_iter().map(|(event,)| event).collect())
+}
+
+/// Delete journaled events older than `before` (Unix ms). Returns the number removed.
+pub async fn prune_events(pool: &DbPool, before: i64) -> anyhow::Result<u64> {
+    let result = db_timeout(
+        sqlx::query("DELETE FROM events WHERE created_at < $1")
+            .bind(before)
+            .execute(pool),
+    )
+    .await?;
+    Ok(result.rows_affected())
 }
 
 #[cfg(test)]
@@ -1440,7 +2876,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_audit_log_roundtrip() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
         init_db(&pool, "sqlite::memory:").await.unwrap();
 
         let entry = AuditLogEntry {
@@ -1465,9 +2901,33 @@ mod tests {
         assert_eq!(logs[0].result, "SUCCESS");
     }
 
+    #[tokio::test]
+    async fn test_event_journal_prune() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        init_db(&pool, "sqlite::memory:").await.unwrap();
+
+        let entries: Vec<JournalEntry> = [1_000, 2_000, 3_000]
+            .into_iter()
+            .map(|created_at| JournalEntry {
+                trace_id: "trace-001".to_string(),
+                event_type: "SystemNotification",
+                event: "{}".into(),
+                created_at,
+            })
+            .collect();
+        insert_events(&pool, &entries).await.unwrap();
+
+        assert_eq!(prune_events(&pool, 2_500).await.unwrap(), 2);
+        let filter = JournalFilter {
+            trace_id: Some("trace-001"),
+            ..Default::default()
+        };
+        assert_eq!(count_events(&pool, &filter).await.unwrap(), 1);
+    }
+
     #[tokio::test]
     async fn test_audit_log_ordering() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
         init_db(&pool, "sqlite::memory:").await.unwrap();
 
         // Insert multiple entries
@@ -1497,7 +2957,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_permission_request_lifecycle() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
         init_db(&pool, "sqlite::memory:").await.unwrap();
 
         // Create a permission request
@@ -1535,7 +2995,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_multiple_permission_requests() {
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
         init_db(&pool, "sqlite::memory:").await.unwrap();
 
         // Create multiple requests
@@ -1564,6 +3024,71 @@ mod tests {
         assert_eq!(pending[1].request_id, "req-002");
         assert_eq!(pending[2].request_id, "req-001");
     }
+
+    #[tokio::test]
+    async fn test_cron_job_run_lifecycle() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        init_db(&pool, "sqlite::memory:").await.unwrap();
+
+        let job = CronJobRow {
+            id: "cron.test.1".to_string(),
+            agent_id: "agent.cloto_default".to_string(),
+            name: "test".to_string(),
+            enabled: true,
+            schedule_type: "interval".to_string(),
+            schedule_value: "3600".to_string(),
+            engine_id: None,
+            message: "ping".to_string(),
+            next_run_at: 0,
+            last_run_at: None,
+            last_status: None,
+            last_error: None,
+            max_iterations: Some(8),
+            created_at: String::new(),
+            misfire_policy: "run_once".to_string(),
+            jitter_secs: 0,
+            chain_on: "success".to_string(),
+            weekdays_only: false,
+            skip_dates: String::new(),
+            timezone: None,
+        };
+        create_cron_job(&pool, &job).await.unwrap();
+
+        insert_cron_job_run(&pool, &job.id, "scheduler", "msg-1", 1_000)
+            .await
+            .unwrap();
+        insert_cron_job_run(&pool, &job.id, "manual", "msg-2", 2_000)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            finish_cron_job_run(&pool, "msg-1", "success", Some("msg-1-resp"), None, 1_500)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("cron.test.1")
+        );
+        // Already finished / unknown messages are ignored
+        assert!(
+            finish_cron_job_run(&pool, "msg-1", "error", None, None, 1_600)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            expire_cron_job_runs(&pool, 5_000, 9_000).await.unwrap(),
+            vec!["cron.test.1".to_string()]
+        );
+
+        let runs = list_cron_job_runs(&pool, &job.id, 10).await.unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].message_id, "msg-2");
+        assert_eq!(runs[0].status, "timeout");
+        assert_eq!(runs[0].duration_ms, Some(7_000));
+        assert_eq!(runs[1].status, "success");
+        assert_eq!(runs[1].duration_ms, Some(500));
+        assert_eq!(runs[1].response_message_id.as_deref(), Some("msg-1-resp"));
+    }
 }
 
 // ── Cron Job Scheduler ──
@@ -1584,35 +3109,68 @@ pub struct CronJobRow {
     pub last_error: Option<String>,
     pub max_iterations: Option<i32>,
     pub created_at: String,
+    /// What to do with occurrences missed while the kernel was down:
+    /// `run_once` (default), `skip`, or `run_all`.
+    pub misfire_policy: String,
+    /// Random delay (0..=jitter_secs) added to each scheduled run.
+    pub jitter_secs: i64,
+    /// For `schedule_type = "after"`: which outcome of the upstream job
+    /// (`schedule_value`) triggers this one — `success`, `failure` or `always`.
+    pub chain_on: String,
+    /// Skip occurrences falling on Saturday or Sunday (in the job's timezone).
+    pub weekdays_only: bool,
+    /// Comma-separated `YYYY-MM-DD` dates on which occurrences are skipped,
+    /// in the job's timezone.
+    pub skip_dates: String,
+    /// IANA timezone for cron expressions and calendar modifiers; `None` = UTC.
+    pub timezone: Option<String>,
 }
 
-pub async fn list_cron_jobs(pool: &SqlitePool) -> anyhow::Result<Vec<CronJobRow>> {
+pub async fn list_cron_jobs(pool: &DbPool) -> anyhow::Result<Vec<CronJobRow>> {
     let rows = sqlx::query_as::<_, CronJobRow>(
-        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at FROM cron_jobs ORDER BY created_at DESC"
+        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at, misfire_policy, jitter_secs, chain_on, weekdays_only, skip_dates, timezone FROM cron_jobs ORDER BY created_at DESC"
     ).fetch_all(pool).await?;
     Ok(rows)
 }
 
 pub async fn list_cron_jobs_for_agent(
-    pool: &SqlitePool,
+    pool: &DbPool,
     agent_id: &str,
 ) -> anyhow::Result<Vec<CronJobRow>> {
     let rows = sqlx::query_as::<_, CronJobRow>(
-        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at FROM cron_jobs WHERE agent_id = ? ORDER BY created_at DESC"
+        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at, misfire_policy, jitter_secs, chain_on, weekdays_only, skip_dates, timezone FROM cron_jobs WHERE agent_id = $1 ORDER BY created_at DESC"
     ).bind(agent_id).fetch_all(pool).await?;
     Ok(rows)
 }
 
-pub async fn get_due_cron_jobs(pool: &SqlitePool, now_ms: i64) -> anyhow::Result<Vec<CronJobRow>> {
+pub async fn get_due_cron_jobs(pool: &DbPool, now_ms: i64) -> anyhow::Result<Vec<CronJobRow>> {
     let rows = sqlx::query_as::<_, CronJobRow>(
-        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at FROM cron_jobs WHERE enabled = 1 AND next_run_at <= ? ORDER BY next_run_at ASC"
+        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at, misfire_policy, jitter_secs, chain_on, weekdays_only, skip_dates, timezone FROM cron_jobs WHERE enabled = TRUE AND next_run_at <= $1 ORDER BY next_run_at ASC"
     ).bind(now_ms).fetch_all(pool).await?;
     Ok(rows)
 }
 
-pub async fn create_cron_job(pool: &SqlitePool, job: &CronJobRow) -> anyhow::Result<()> {
+/// Enabled jobs chained after `upstream_job_id` (`schedule_type = "after"`).
+pub async fn list_chained_cron_jobs(
+    pool: &DbPool,
+    upstream_job_id: &str,
+) -> anyhow::Result<Vec<CronJobRow>> {
+    let rows = sqlx::query_as::<_, CronJobRow>(
+        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at, misfire_policy, jitter_secs, chain_on, weekdays_only, skip_dates, timezone FROM cron_jobs WHERE enabled = TRUE AND schedule_type = 'after' AND schedule_value = $1 ORDER BY created_at ASC"
+    ).bind(upstream_job_id).fetch_all(pool).await?;
+    Ok(rows)
+}
+
+pub async fn get_cron_job(pool: &DbPool, id: &str) -> anyhow::Result<Option<CronJobRow>> {
+    let row = sqlx::query_as::<_, CronJobRow>(
+        "SELECT id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, last_run_at, last_status, last_error, max_iterations, created_at, misfire_policy, jitter_secs, chain_on, weekdays_only, skip_dates, timezone FROM cron_jobs WHERE id = $1"
+    ).bind(id).fetch_optional(pool).await?;
+    Ok(row)
+}
+
+pub async fn create_cron_job(pool: &DbPool, job: &CronJobRow) -> anyhow::Result<()> {
     sqlx::query(
-        "INSERT INTO cron_jobs (id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, max_iterations) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        "INSERT INTO cron_jobs (id, agent_id, name, enabled, schedule_type, schedule_value, engine_id, message, next_run_at, max_iterations, misfire_policy, jitter_secs, chain_on, weekdays_only, skip_dates, timezone) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)"
     )
     .bind(&job.id)
     .bind(&job.agent_id)
@@ -1624,13 +3182,19 @@ pub async fn create_cron_job(pool: &SqlitePool, job: &CronJobRow) -> anyhow::Res
     .bind(&job.message)
     .bind(job.next_run_at)
     .bind(job.max_iterations)
+    .bind(&job.misfire_policy)
+    .bind(job.jitter_secs)
+    .bind(&job.chain_on)
+    .bind(job.weekdays_only)
+    .bind(&job.skip_dates)
+    .bind(&job.timezone)
     .execute(pool)
     .await?;
     Ok(())
 }
 
 pub async fn update_cron_job_run(
-    pool: &SqlitePool,
+    pool: &DbPool,
     id: &str,
     last_run_at: i64,
     last_status: &str,
@@ -1639,7 +3203,7 @@ pub async fn update_cron_job_run(
     enabled: bool,
 ) -> anyhow::Result<()> {
     sqlx::query(
-        "UPDATE cron_jobs SET last_run_at = ?, last_status = ?, last_error = ?, next_run_at = ?, enabled = ? WHERE id = ?"
+        "UPDATE cron_jobs SET last_run_at = $1, last_status = $2, last_error = $3, next_run_at = $4, enabled = $5 WHERE id = $6"
     )
     .bind(last_run_at)
     .bind(last_status)
@@ -1652,8 +3216,8 @@ pub async fn update_cron_job_run(
     Ok(())
 }
 
-pub async fn delete_cron_job(pool: &SqlitePool, id: &str) -> anyhow::Result<()> {
-    let result = sqlx::query("DELETE FROM cron_jobs WHERE id = ?")
+pub async fn delete_cron_job(pool: &DbPool, id: &str) -> anyhow::Result<()> {
+    let result = sqlx::query("DELETE FROM cron_jobs WHERE id = $1")
         .bind(id)
         .execute(pool)
         .await?;
@@ -1663,12 +3227,8 @@ pub async fn delete_cron_job(pool: &SqlitePool, id: &str) -> anyhow::Result<()>
     Ok(())
 }
 
-pub async fn set_cron_job_enabled(
-    pool: &SqlitePool,
-    id: &str,
-    enabled: bool,
-) -> anyhow::Result<()> {
-    let result = sqlx::query("UPDATE cron_jobs SET enabled = ? WHERE id = ?")
+pub async fn set_cron_job_enabled(pool: &DbPool, id: &str, enabled: bool) -> anyhow::Result<()> {
+    let result = sqlx::query("UPDATE cron_jobs SET enabled = $1 WHERE id = $2")
         .bind(enabled)
         .bind(id)
         .execute(pool)
@@ -1679,6 +3239,110 @@ pub async fn set_cron_job_enabled(
     Ok(())
 }
 
+// ── Cron Job Run History ──
+
+/// Runs kept per job; older rows are pruned when a new run is recorded.
+const CRON_RUNS_PER_JOB: i64 = 100;
+
+#[derive(Debug, Clone, sqlx::FromRow, serde::Serialize, serde::Deserialize)]
+pub struct CronJobRunRow {
+    pub id: i64,
+    pub job_id: String,
+    pub trigger: String,
+    pub message_id: String,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub status: String,
+    pub response_message_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Record a dispatched run (status `running`) and prune old runs of the job.
+pub async fn insert_cron_job_run(
+    pool: &DbPool,
+    job_id: &str,
+    trigger: &str,
+    message_id: &str,
+    started_at: i64,
+) -> anyhow::Result<i64> {
+    let id = sqlx::query_scalar::<_, i64>(
+        "INSERT INTO cron_job_runs (job_id, trigger, message_id, started_at) VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(job_id)
+    .bind(trigger)
+    .bind(message_id)
+    .bind(started_at)
+    .fetch_one(pool)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM cron_job_runs WHERE job_id = $1 AND id NOT IN (SELECT id FROM cron_job_runs WHERE job_id = $2 ORDER BY started_at DESC, id DESC LIMIT $3)",
+    )
+    .bind(job_id)
+    .bind(job_id)
+    .bind(CRON_RUNS_PER_JOB)
+    .execute(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Complete the running run that dispatched `message_id`.
+/// Returns the run's job ID, or `None` if no running run matches (e.g. not a
+/// cron message).
+pub async fn finish_cron_job_run(
+    pool: &DbPool,
+    message_id: &str,
+    status: &str,
+    response_message_id: Option<&str>,
+    error: Option<&str>,
+    finished_at: i64,
+) -> anyhow::Result<Option<String>> {
+    let job_id = sqlx::query_scalar::<_, String>(
+        "UPDATE cron_job_runs SET status = $1, response_message_id = $2, error = $3, finished_at = $4, duration_ms = $5 - started_at WHERE message_id = $6 AND status = 'running' RETURNING job_id",
+    )
+    .bind(status)
+    .bind(response_message_id)
+    .bind(error)
+    .bind(finished_at)
+    .bind(finished_at)
+    .bind(message_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(job_id)
+}
+
+/// Mark runs still `running` after `started_before` (unix ms) as timed out.
+/// Returns the job ID of each expired run.
+pub async fn expire_cron_job_runs(
+    pool: &DbPool,
+    started_before: i64,
+    now_ms: i64,
+) -> anyhow::Result<Vec<String>> {
+    let job_ids = sqlx::query_scalar::<_, String>(
+        "UPDATE cron_job_runs SET status = 'timeout', finished_at = $1, duration_ms = $2 - started_at, error = 'No response from agent' WHERE status = 'running' AND started_at < $3 RETURNING job_id",
+    )
+    .bind(now_ms)
+    .bind(now_ms)
+    .bind(started_before)
+    .fetch_all(pool)
+    .await?;
+    Ok(job_ids)
+}
+
+/// Most recent runs of a job, newest first.
+pub async fn list_cron_job_runs(
+    pool: &DbPool,
+    job_id: &str,
+    limit: i64,
+) -> anyhow::Result<Vec<CronJobRunRow>> {
+    let rows = sqlx::query_as::<_, CronJobRunRow>(
+        "SELECT id, job_id, trigger, message_id, started_at, finished_at, duration_ms, status, response_message_id, error FROM cron_job_runs WHERE job_id = $1 ORDER BY started_at DESC, id DESC LIMIT $2"
+    ).bind(job_id).bind(limit).fetch_all(pool).await?;
+    Ok(rows)
+}
+
 // ── LLM Provider Registry (MGP §13.4 llm_completion) ──
 
 #[derive(Debug, Clone, sqlx::FromRow, serde::Serialize, serde::Deserialize)]
@@ -1693,26 +3357,22 @@ pub struct LlmProviderRow {
     pub created_at: String,
 }
 
-pub async fn list_llm_providers(pool: &SqlitePool) -> anyhow::Result<Vec<LlmProviderRow>> {
+pub async fn list_llm_providers(pool: &DbPool) -> anyhow::Result<Vec<LlmProviderRow>> {
     let rows = sqlx::query_as::<_, LlmProviderRow>(
         "SELECT id, display_name, api_url, api_key, model_id, timeout_secs, enabled, created_at FROM llm_providers ORDER BY id"
     ).fetch_all(pool).await?;
     Ok(rows)
 }
 
-pub async fn get_llm_provider(pool: &SqlitePool, id: &str) -> anyhow::Result<LlmProviderRow> {
+pub async fn get_llm_provider(pool: &DbPool, id: &str) -> anyhow::Result<LlmProviderRow> {
     let row = sqlx::query_as::<_, LlmProviderRow>(
-        "SELECT id, display_name, api_url, api_key, model_id, timeout_secs, enabled, created_at FROM llm_providers WHERE id = ?"
+        "SELECT id, display_name, api_url, api_key, model_id, timeout_secs, enabled, created_at FROM llm_providers WHERE id = $1"
     ).bind(id).fetch_optional(pool).await?;
     row.ok_or_else(|| anyhow::anyhow!("LLM provider '{}' not found", id))
 }
 
-pub async fn set_llm_provider_key(
-    pool: &SqlitePool,
-    id: &str,
-    api_key: &str,
-) -> anyhow::Result<()> {
-    let result = sqlx::query("UPDATE llm_providers SET api_key = ? WHERE id = ?")
+pub async fn set_llm_provider_key(pool: &DbPool, id: &str, api_key: &str) -> anyhow::Result<()> {
+    let result = sqlx::query("UPDATE llm_providers SET api_key = $1 WHERE id = $2")
         .bind(api_key)
         .bind(id)
         .execute(pool)
@@ -1723,8 +3383,8 @@ pub async fn set_llm_provider_key(
     Ok(())
 }
 
-pub async fn delete_llm_provider_key(pool: &SqlitePool, id: &str) -> anyhow::Result<()> {
-    sqlx::query("UPDATE llm_providers SET api_key = '' WHERE id = ?")
+pub async fn delete_llm_provider_key(pool: &DbPool, id: &str) -> anyhow::Result<()> {
+    sqlx::query("UPDATE llm_providers SET api_key = '' WHERE id = $1")
         .bind(id)
         .execute(pool)
         .await?;
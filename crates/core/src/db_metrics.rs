@@ -0,0 +1,326 @@
+//! SQLite pool and query instrumentation.
+//!
+//! sqlx reports every statement (`sqlx::query`, with `elapsed_secs` and a
+//! short `summary` of the SQL) and every pool checkout
+//! (`sqlx::pool::acquire`, with `aquired_after_secs`) as tracing events.
+//! [`DbMetricsLayer`] folds those into histograms, so query timings are
+//! collected without wrapping each call site. The kernel pool emits both at
+//! TRACE level, which keeps them out of the regular log output.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Histogram bucket upper bounds in milliseconds (plus an implicit `+Inf`).
+pub const BUCKETS_MS: [u64; 11] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// Distinct query summaries tracked; further queries are counted as `other`.
+const MAX_TRACKED_QUERIES: usize = 256;
+
+const QUERY_TARGET: &str = "sqlx::query";
+const ACQUIRE_TARGET: &str = "sqlx::pool::acquire";
+
+/// Lock-free latency histogram with fixed buckets.
+#[derive(Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS_MS.len() + 1],
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketCount {
+    /// Upper bound in milliseconds; `None` is `+Inf`.
+    pub le_ms: Option<u64>,
+    /// Observations at or below `le_ms` (cumulative, like Prometheus).
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum_ms: f64,
+    pub buckets: Vec<BucketCount>,
+}
+
+impl Histogram {
+    pub fn record(&self, elapsed: Duration) {
+        let bucket = BUCKETS_MS
+            .iter()
+            .position(|&le| elapsed <= Duration::from_millis(le))
+            .unwrap_or(BUCKETS_MS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        #[allow(clippy::cast_possible_truncation)]
+        self.sum_us
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                cumulative += bucket.load(Ordering::Relaxed);
+                BucketCount {
+                    le_ms: BUCKETS_MS.get(i).copied(),
+                    count: cumulative,
+                }
+            })
+            .collect();
+        #[allow(clippy::cast_precision_loss)]
+        let sum_ms = self.sum_us.load(Ordering::Relaxed) as f64 / 1000.0;
+        HistogramSnapshot {
+            count: self.count.load(Ordering::Relaxed),
+            sum_ms,
+            buckets,
+        }
+    }
+}
+
+impl HistogramSnapshot {
+    /// Append this histogram in Prometheus text format (seconds).
+    /// `labels` is either empty or `key="value"` pairs without braces.
+    pub fn write_prometheus(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        for bucket in &self.buckets {
+            let le = bucket.le_ms.map_or_else(
+                || "+Inf".to_string(),
+                #[allow(clippy::cast_precision_loss)]
+                |ms| (ms as f64 / 1000.0).to_string(),
+            );
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}{}le=\"{}\"}} {}",
+                name, labels, sep, le, bucket.count
+            );
+        }
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", labels)
+        };
+        let _ = writeln!(out, "{}_sum{} {}", name, labels, self.sum_ms / 1000.0);
+        let _ = writeln!(out, "{}_count{} {}", name, labels, self.count);
+    }
+}
+
+/// Escape a Prometheus label value.
+#[must_use]
+pub fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Point-in-time connection pool occupancy.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PoolStats {
+    pub max_connections: u32,
+    pub open: u32,
+    pub idle: u32,
+    pub in_use: u32,
+}
+
+impl PoolStats {
+    #[must_use]
+    pub fn of(pool: &crate::db::DbPool) -> Self {
+        let open = pool.size();
+        #[allow(clippy::cast_possible_truncation)]
+        let idle = pool.num_idle() as u32;
+        Self {
+            max_connections: pool.options().get_max_connections(),
+            open,
+            idle,
+            in_use: open.saturating_sub(idle),
+        }
+    }
+}
+
+/// Connection acquire wait times and per-query execution times.
+#[derive(Default)]
+pub struct DbMetrics {
+    acquire: Histogram,
+    queries: DashMap<String, Arc<Histogram>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryTiming {
+    /// First words of the SQL statement, as summarized by sqlx.
+    pub query: String,
+    #[serde(flatten)]
+    pub histogram: HistogramSnapshot,
+}
+
+impl DbMetrics {
+    pub fn record_acquire(&self, waited: Duration) {
+        self.acquire.record(waited);
+    }
+
+    pub fn record_query(&self, summary: &str, elapsed: Duration) {
+        let histogram = if let Some(histogram) = self.queries.get(summary) {
+            histogram.clone()
+        } else {
+            let key = if self.queries.len() < MAX_TRACKED_QUERIES {
+                summary
+            } else {
+                "other"
+            };
+            self.queries.entry(key.to_string()).or_default().clone()
+        };
+        histogram.record(elapsed);
+    }
+
+    #[must_use]
+    pub fn acquire_snapshot(&self) -> HistogramSnapshot {
+        self.acquire.snapshot()
+    }
+
+    /// Per-query timings, slowest total time first.
+    #[must_use]
+    pub fn query_snapshots(&self) -> Vec<QueryTiming> {
+        let mut timings: Vec<QueryTiming> = self
+            .queries
+            .iter()
+            .map(|entry| QueryTiming {
+                query: entry.key().clone(),
+                histogram: entry.value().snapshot(),
+            })
+            .collect();
+        timings.sort_by(|a, b| b.histogram.sum_ms.total_cmp(&a.histogram.sum_ms));
+        timings
+    }
+}
+
+/// The process-wide metrics fed by the tracing layer installed at startup.
+pub fn global() -> &'static DbMetrics {
+    static METRICS: OnceLock<DbMetrics> = OnceLock::new();
+    METRICS.get_or_init(DbMetrics::default)
+}
+
+/// Tracing layer that records sqlx statement and acquire events.
+pub struct DbMetricsLayer {
+    metrics: &'static DbMetrics,
+}
+
+impl DbMetricsLayer {
+    #[must_use]
+    pub fn new(metrics: &'static DbMetrics) -> Self {
+        Self { metrics }
+    }
+
+    /// Whether an event or callsite is one this layer consumes.
+    #[must_use]
+    pub fn wants(metadata: &tracing::Metadata<'_>) -> bool {
+        matches!(metadata.target(), QUERY_TARGET | ACQUIRE_TARGET)
+    }
+}
+
+#[derive(Default)]
+struct SqlxFields {
+    summary: Option<String>,
+    elapsed_secs: Option<f64>,
+}
+
+impl Visit for SqlxFields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        // sqlx misspells the acquire field; accept both spellings
+        if matches!(
+            field.name(),
+            "elapsed_secs" | "aquired_after_secs" | "acquired_after_secs"
+        ) {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "summary" {
+            self.summary = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl<S: Subscriber> Layer<S> for DbMetricsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let target = event.metadata().target();
+        if target != QUERY_TARGET && target != ACQUIRE_TARGET {
+            return;
+        }
+        let mut fields = SqlxFields::default();
+        event.record(&mut fields);
+        let Some(elapsed) = fields
+            .elapsed_secs
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        else {
+            return;
+        };
+        if target == ACQUIRE_TARGET {
+            self.metrics.record_acquire(elapsed);
+        } else {
+            let summary = fields.summary.unwrap_or_default();
+            self.metrics
+                .record_query(summary.trim_end_matches(" …"), elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram = Histogram::default();
+        histogram.record(Duration::from_micros(500));
+        histogram.record(Duration::from_millis(30));
+        histogram.record(Duration::from_secs(10));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 3);
+        assert_eq!(snapshot.buckets[0].count, 1); // <= 1ms
+        assert_eq!(snapshot.buckets[3].count, 1); // <= 25ms
+        assert_eq!(snapshot.buckets[4].count, 2); // <= 50ms
+        let inf = snapshot.buckets.last().unwrap();
+        assert_eq!((inf.le_ms, inf.count), (None, 3));
+    }
+
+    #[test]
+    fn test_layer_records_sqlx_events() {
+        let metrics: &'static DbMetrics = Box::leak(Box::default());
+        let subscriber = tracing_subscriber::registry().with(DbMetricsLayer::new(metrics));
+        tracing::subscriber::with_default(subscriber, || {
+            let summary = "SELECT * FROM agents …".to_string();
+            tracing::trace!(target: "sqlx::query", summary, elapsed_secs = 0.02);
+            tracing::trace!(target: "sqlx::pool::acquire", aquired_after_secs = 0.003);
+            tracing::trace!(target: "cloto_core", elapsed_secs = 1.0);
+        });
+
+        let queries = metrics.query_snapshots();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].query, "SELECT * FROM agents");
+        assert_eq!(queries[0].histogram.count, 1);
+        assert_eq!(metrics.acquire_snapshot().count, 1);
+
+        let mut out = String::new();
+        queries[0]
+            .histogram
+            .write_prometheus(&mut out, "cloto_db_query_seconds", "query=\"x\"");
+        assert!(out.contains("cloto_db_query_seconds_bucket{query=\"x\",le=\"0.025\"} 1"));
+        assert!(out.contains("cloto_db_query_seconds_count{query=\"x\"} 1"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Draining shutdown.
+//!
+//! A shutdown request (`POST /api/system/shutdown`, SIGTERM, maintenance)
+//! first puts the kernel into draining mode: mutating API requests are
+//! refused with 503 and no new agentic runs are started. In-flight runs and
+//! the MCP tool calls they make are waited for (up to
+//! `CLOTO_SHUTDOWN_DRAIN_SECS`), pending audit writes are flushed, and only
+//! then are the event loop, the LLM proxy and the listener stopped.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+/// Counts in-flight work and refuses new work once draining has begun.
+#[derive(Default)]
+pub struct DrainTracker {
+    draining: AtomicBool,
+    active: AtomicUsize,
+    idle: Notify,
+}
+
+/// Registration of one unit of in-flight work; released on drop.
+pub struct DrainGuard(Arc<DrainTracker>);
+
+impl Drop for DrainGuard {
+    fn drop(&mut self) {
+        if self.0.active.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl DrainTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting new work.
+    pub fn begin(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    #[must_use]
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Units of work currently registered.
+    #[must_use]
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Register new work; `None` once draining has begun.
+    #[must_use]
+    pub fn enter(self: &Arc<Self>) -> Option<DrainGuard> {
+        let guard = self.track();
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Register work that is part of something already in flight (a tool
+    /// call of a running agentic loop, a pending write) and so must also be
+    /// waited for while draining.
+    #[must_use]
+    pub fn track(self: &Arc<Self>) -> DrainGuard {
+        self.active.fetch_add(1, Ordering::AcqRel);
+        DrainGuard(self.clone())
+    }
+
+    /// Wait until no work is registered. Returns `false` on timeout.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let idle = self.idle.notified();
+                if self.active() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+/// Run the draining phase: refuse new work, wait for in-flight runs and tool
+/// calls, then flush pending audit writes. Returns once it is safe to stop.
+pub async fn drain(tracker: &DrainTracker, timeout: Duration) {
+    tracker.begin();
+    let active = tracker.active();
+    if active > 0 {
+        info!(
+            active,
+            "⏳ Draining: waiting up to {}s for in-flight agentic runs and tool calls",
+            timeout.as_secs()
+        );
+        if !tracker.wait_idle(timeout).await {
+            warn!(
+                remaining = tracker.active(),
+                "Drain timeout reached; abandoning in-flight work"
+            );
+        }
+    }
+    // Audit entries of the runs that just finished are still being written
+    if !crate::db::flush_audit_logs(Duration::from_secs(5)).await {
+        warn!("Timed out flushing pending audit log writes");
+    }
+    info!("✅ Drain complete");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_enter_refused_while_draining() {
+        let tracker = Arc::new(DrainTracker::new());
+        let guard = tracker.enter().expect("accepting work");
+        assert_eq!(tracker.active(), 1);
+        tracker.begin();
+        assert!(tracker.enter().is_none());
+        assert_eq!(tracker.active(), 1);
+        // Work already in flight can still register follow-up work
+        let follow_up = tracker.track();
+        assert_eq!(tracker.active(), 2);
+        drop(follow_up);
+        drop(guard);
+        assert_eq!(tracker.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_waits_for_guards() {
+        let tracker = Arc::new(DrainTracker::new());
+        let guard = tracker.track();
+        assert!(!tracker.wait_idle(Duration::from_millis(20)).await);
+
+        let waiter = {
+            let tracker = tracker.clone();
+            tokio::spawn(async move { tracker.wait_idle(Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(guard);
+        assert!(waiter.await.expect("join"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_returns_immediately_when_idle() {
+        let tracker = DrainTracker::new();
+        assert!(tracker.wait_idle(Duration::ZERO).await);
+    }
+}
@@ -1,22 +1,212 @@
 use crate::managers::{AgentManager, PluginManager, PluginRegistry};
 use cloto_shared::{ClotoEvent, Permission};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc};
-use tracing::{debug, error, info, warn};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tracing::{error, info, warn, Instrument};
+
+/// An event together with its JSON, serialized once when it is published.
+/// Both the history ring buffer and the SSE broadcast carry it, so
+/// `GET /api/history` only concatenates cached JSON and each SSE subscriber
+/// reuses the same string instead of serializing the event again.
+#[derive(Clone, Debug)]
+pub struct SerializedEvent {
+    pub event: Arc<ClotoEvent>,
+    pub json: Arc<str>,
+}
+
+impl From<Arc<ClotoEvent>> for SerializedEvent {
+    fn from(event: Arc<ClotoEvent>) -> Self {
+        let json = serde_json::to_string(&*event).unwrap_or_else(|e| {
+            error!("Failed to serialize event: {}", e);
+            "null".to_string()
+        });
+        Self {
+            event,
+            json: json.into(),
+        }
+    }
+}
+
+impl std::ops::Deref for SerializedEvent {
+    type Target = ClotoEvent;
+
+    fn deref(&self) -> &ClotoEvent {
+        &self.event
+    }
+}
+
+/// An agent's answer to one message, as carried by `ThoughtResponse`.
+#[derive(Debug, Clone)]
+pub struct ThoughtReply {
+    pub agent_id: String,
+    pub engine_id: String,
+    pub content: String,
+}
+
+/// Wait on `events` for the `ThoughtResponse` to `message_id`. Subscribe
+/// before publishing the message, or a fast answer is missed. `None` if the
+/// bus closes first; callers bound the wait with their own timeout.
+pub async fn wait_for_thought_response(
+    events: &mut broadcast::Receiver<SerializedEvent>,
+    message_id: &str,
+) -> Option<ThoughtReply> {
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let cloto_shared::ClotoEventData::ThoughtResponse {
+                    agent_id,
+                    engine_id,
+                    content,
+                    source_message_id,
+                    ..
+                } = &event.data
+                {
+                    if source_message_id == message_id {
+                        return Some(ThoughtReply {
+                            agent_id: agent_id.clone(),
+                            engine_id: engine_id.clone(),
+                            content: content.clone(),
+                        });
+                    }
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Queued journal writes; beyond this events are left out of the journal
+/// (they still reach the ring buffer and SSE).
+const JOURNAL_QUEUE: usize = 4096;
+/// Events written per transaction.
+const JOURNAL_BATCH: usize = 256;
+
+/// Persists recorded events to the `events` table (`CLOTO_EVENT_JOURNAL`).
+/// Writes are queued and batched by a background task so a slow database
+/// never stalls the event loop.
+pub struct EventJournal {
+    pool: crate::db::DbPool,
+    tx: mpsc::Sender<crate::db::JournalEntry>,
+    retention_days: u64,
+}
+
+impl EventJournal {
+    /// Start the writer task. It stops once the journal is dropped and the
+    /// queue is empty.
+    #[must_use]
+    pub fn spawn(pool: crate::db::DbPool, retention_days: u64) -> Self {
+        let (tx, mut rx) = mpsc::channel::<crate::db::JournalEntry>(JOURNAL_QUEUE);
+        let writer_pool = pool.clone();
+        tokio::spawn(async move {
+            let mut batch = Vec::with_capacity(JOURNAL_BATCH);
+            while rx.recv_many(&mut batch, JOURNAL_BATCH).await > 0 {
+                // Shutdown flushes pending audit writes; journal batches count as one
+                let _pending = crate::db::track_audit_write();
+                if let Err(e) = crate::db::insert_events(&writer_pool, &batch).await {
+                    error!(count = batch.len(), "Failed to journal events: {}", e);
+                }
+                batch.clear();
+            }
+        });
+        Self {
+            pool,
+            tx,
+            retention_days,
+        }
+    }
+
+    fn record(&self, entry: &SerializedEvent) {
+        let row = crate::db::JournalEntry {
+            trace_id: entry.trace_id.to_string(),
+            event_type: entry.data.type_name(),
+            event: entry.json.clone(),
+            created_at: entry.timestamp.timestamp_millis(),
+        };
+        if self.tx.try_send(row).is_err() {
+            warn!("Event journal queue full; event not journaled");
+        }
+    }
+
+    /// Delete events older than the retention period.
+    async fn prune(&self) {
+        #[allow(clippy::cast_possible_wrap)]
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(self.retention_days as i64);
+        match crate::db::prune_events(&self.pool, cutoff.timestamp_millis()).await {
+            Ok(0) => {}
+            Ok(removed) => info!("Event journal cleanup: {} events pruned", removed),
+            Err(e) => warn!("Event journal cleanup failed: {}", e),
+        }
+    }
+}
 
 pub struct EventProcessor {
     registry: Arc<PluginRegistry>,
     plugin_manager: Arc<PluginManager>,
     agent_manager: AgentManager,
-    tx_internal: broadcast::Sender<Arc<ClotoEvent>>,
-    history: Arc<tokio::sync::RwLock<VecDeque<Arc<ClotoEvent>>>>,
+    tx_internal: broadcast::Sender<SerializedEvent>,
+    history: Arc<tokio::sync::RwLock<VecDeque<SerializedEvent>>>,
     metrics: Arc<crate::managers::SystemMetrics>,
-    max_history_size: usize,
+    max_history_size: std::sync::atomic::AtomicUsize,
     event_retention_hours: u64, // M-10: Configurable retention period
     consensus: Option<Arc<crate::consensus::ConsensusOrchestrator>>,
     /// Per-plugin rate limiter for InputControl actions (bug-143: Guardrail 1.6)
     action_rate_limiter: Arc<dashmap::DashMap<String, governor::DefaultDirectRateLimiter>>,
+    /// Resolves `ClickElement` into coordinates; without it the action is forwarded as-is
+    element_resolver: Option<Arc<crate::vision::ElementResolver>>,
+    journal: Option<EventJournal>,
+    /// Plugin dispatches in flight. The loop waits for a permit, so a
+    /// backlog pushes back on the event channel.
+    dispatch_permits: Arc<tokio::sync::Semaphore>,
+    /// Messages waiting on their agent's previous one. Bounded separately,
+    /// so one slow agent's backlog cannot hold every dispatch permit.
+    queued_permits: Arc<tokio::sync::Semaphore>,
+    agent_turns: AgentTurns,
+    next_turn: std::sync::atomic::AtomicU64,
+}
+
+/// Upper bound on plugin dispatches running off the event loop at once.
+const MAX_CONCURRENT_DISPATCHES: usize = 64;
+/// Upper bound on messages waiting for their agent's previous message.
+const MAX_QUEUED_MESSAGES: usize = 1024;
+
+/// Per target agent, the number and completion of the last
+/// `MessageReceived` dispatch queued for it
+type AgentTurns = Arc<std::sync::Mutex<HashMap<String, (u64, oneshot::Receiver<()>)>>>;
+
+/// A `MessageReceived`'s place in its target agent's queue.
+struct AgentTurn {
+    turns: AgentTurns,
+    agent_id: String,
+    number: u64,
+    /// The agent's previous message, which must finish first
+    previous: Option<oneshot::Receiver<()>>,
+    /// Dropped with the turn, letting the agent's next message go
+    _done: oneshot::Sender<()>,
+}
+
+impl AgentTurn {
+    async fn wait(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            let _ = previous.await;
+        }
+    }
+}
+
+impl Drop for AgentTurn {
+    fn drop(&mut self) {
+        // The agent's last queued turn clears its entry, so the map only
+        // holds agents with a message in flight
+        if let Ok(mut turns) = self.turns.lock() {
+            if turns
+                .get(&self.agent_id)
+                .is_some_and(|(number, _)| *number == self.number)
+            {
+                turns.remove(&self.agent_id);
+            }
+        }
+    }
 }
 
 impl EventProcessor {
@@ -25,8 +215,8 @@ impl EventProcessor {
         registry: Arc<PluginRegistry>,
         plugin_manager: Arc<PluginManager>,
         agent_manager: AgentManager,
-        tx_internal: broadcast::Sender<Arc<ClotoEvent>>,
-        history: Arc<tokio::sync::RwLock<VecDeque<Arc<ClotoEvent>>>>,
+        tx_internal: broadcast::Sender<SerializedEvent>,
+        history: Arc<tokio::sync::RwLock<VecDeque<SerializedEvent>>>,
         metrics: Arc<crate::managers::SystemMetrics>,
         max_history_size: usize,
         event_retention_hours: u64, // M-10: Configurable retention period
@@ -39,18 +229,49 @@ impl EventProcessor {
             tx_internal,
             history,
             metrics,
-            max_history_size,
+            max_history_size: std::sync::atomic::AtomicUsize::new(max_history_size),
             event_retention_hours,
             consensus,
             action_rate_limiter: Arc::new(dashmap::DashMap::new()),
+            element_resolver: None,
+            journal: None,
+            dispatch_permits: Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_DISPATCHES)),
+            queued_permits: Arc::new(tokio::sync::Semaphore::new(MAX_QUEUED_MESSAGES)),
+            agent_turns: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            next_turn: std::sync::atomic::AtomicU64::new(0),
         }
     }
 
-    async fn record_event(&self, event: Arc<ClotoEvent>) {
+    #[must_use]
+    pub fn with_element_resolver(mut self, resolver: Arc<crate::vision::ElementResolver>) -> Self {
+        self.element_resolver = Some(resolver);
+        self
+    }
+
+    #[must_use]
+    pub fn with_journal(mut self, journal: EventJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Change the event history capacity (config hot reload).
+    /// The history shrinks on the next recorded event.
+    pub fn set_max_history_size(&self, size: usize) {
+        self.max_history_size
+            .store(size, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    async fn record_event(&self, entry: SerializedEvent) {
+        if let Some(journal) = &self.journal {
+            journal.record(&entry);
+        }
+        let max_history_size = self
+            .max_history_size
+            .load(std::sync::atomic::Ordering::Relaxed);
         let mut history = self.history.write().await;
-        history.push_back(event);
+        history.push_back(entry);
         // H-06: Use while loop to handle bursts that exceed capacity
-        while history.len() > self.max_history_size {
+        while history.len() > max_history_size {
             history.pop_front();
         }
     }
@@ -73,44 +294,6 @@ impl EventProcessor {
         });
     }
 
-    /// Spawn the active heartbeat task.
-    /// Every `interval_secs` seconds, updates last_seen for all enabled agents.
-    pub fn spawn_heartbeat_task(
-        agent_manager: AgentManager,
-        interval_secs: u64,
-        shutdown: Arc<tokio::sync::Notify>,
-    ) {
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
-            loop {
-                tokio::select! {
-                    () = shutdown.notified() => {
-                        tracing::info!("Active heartbeat task shutting down");
-                        break;
-                    }
-                    _ = interval.tick() => {
-                        match agent_manager.list_agents().await {
-                            Ok(agents) => {
-                                let enabled_count = agents.iter().filter(|a| a.enabled).count();
-                                for agent in &agents {
-                                    if agent.enabled {
-                                        if let Err(e) = agent_manager.touch_last_seen(&agent.id).await {
-                                            error!(agent_id = %agent.id, error = %e, "Heartbeat: failed to update last_seen");
-                                        }
-                                    }
-                                }
-                                debug!("Heartbeat: pinged {} enabled agents", enabled_count);
-                            }
-                            Err(e) => {
-                                error!("Heartbeat: failed to list agents: {}", e);
-                            }
-                        }
-                    }
-                }
-            }
-        });
-    }
-
     pub async fn cleanup_old_events(&self) {
         const MAX_EVENT_HISTORY: usize = 10_000;
 
@@ -144,9 +327,106 @@ impl EventProcessor {
         }
 
         info!("Event history cleanup: {} events retained", history.len());
+        drop(history);
+
+        if let Some(journal) = &self.journal {
+            journal.prune().await;
+        }
+    }
+
+    /// Resolve a `ClickElement` request on the current screen and re-submit it as
+    /// `MouseMove` + `MouseClick` from the same requester (so the follow-up actions
+    /// pass the same authorization gate). Failures are published as a
+    /// `SystemNotification` carrying a JSON `ClickElementError`.
+    fn spawn_click_resolution(
+        &self,
+        resolver: Arc<crate::vision::ElementResolver>,
+        envelope: &crate::EnvelopedEvent,
+        requester: cloto_shared::ClotoId,
+        label: String,
+        event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+    ) {
+        let tx_internal = self.tx_internal.clone();
+        let trace_id = envelope.event.trace_id;
+        let issuer = envelope.issuer;
+        let depth = envelope.depth + 1;
+        tokio::spawn(async move {
+            let (capture, result) = resolver.resolve_click(&label).await;
+            if let Some(capture) = capture {
+                let _ = tx_internal.send(
+                    Arc::new(ClotoEvent::with_trace(
+                        trace_id,
+                        cloto_shared::ClotoEventData::VisionUpdated(capture),
+                    ))
+                    .into(),
+                );
+            }
+            match result {
+                Ok(actions) => {
+                    info!(trace_id = %trace_id, label = %label, "🎯 ClickElement resolved");
+                    for action in actions {
+                        let follow_up = crate::EnvelopedEvent {
+                            event: Arc::new(ClotoEvent::with_trace(
+                                trace_id,
+                                cloto_shared::ClotoEventData::ActionRequested { requester, action },
+                            )),
+                            issuer,
+                            correlation_id: Some(trace_id),
+                            depth,
+                        };
+                        if event_tx.send(follow_up).await.is_err() {
+                            error!(trace_id = %trace_id, "Event bus closed during ClickElement");
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!(trace_id = %trace_id, "{}", e);
+                    let payload = serde_json::to_string(&e).unwrap_or_else(|_| e.to_string());
+                    let _ = tx_internal.send(
+                        Arc::new(ClotoEvent::with_trace(
+                            trace_id,
+                            cloto_shared::ClotoEventData::SystemNotification(payload),
+                        ))
+                        .into(),
+                    );
+                }
+            }
+        }.in_current_span());
+    }
+
+    /// Queue a `MessageReceived` behind the previous one for the same agent,
+    /// so two agentic loops never run over one history at once. Events
+    /// without a target agent are not queued.
+    fn agent_turn(&self, event: &ClotoEvent) -> Option<AgentTurn> {
+        let cloto_shared::ClotoEventData::MessageReceived(msg) = &event.data else {
+            return None;
+        };
+        let agent_id = msg
+            .metadata
+            .get("target_agent_id")
+            .or(msg.target_agent.as_ref())
+            .filter(|id| !id.is_empty())?
+            .clone();
+        let number = self
+            .next_turn
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let (done, turn) = oneshot::channel();
+        let previous = self
+            .agent_turns
+            .lock()
+            .ok()?
+            .insert(agent_id.clone(), (number, turn))
+            .map(|(_, previous)| previous);
+        Some(AgentTurn {
+            turns: self.agent_turns.clone(),
+            agent_id,
+            number,
+            previous,
+            _done: done,
+        })
     }
 
-    #[allow(clippy::too_many_lines)]
     pub async fn process_loop(
         &self,
         mut event_rx: mpsc::Receiver<crate::EnvelopedEvent>,
@@ -155,205 +435,380 @@ impl EventProcessor {
         info!("🧠 Kernel Event Processor Loop started.");
 
         while let Some(envelope) = event_rx.recv().await {
-            let event = envelope.event.clone();
-            let trace_id = event.trace_id;
+            let span = crate::telemetry::event_span(&envelope);
+            self.process_envelope(envelope, &event_tx)
+                .instrument(span)
+                .await;
+        }
+    }
 
-            // Record event history
-            self.record_event(event.clone()).await;
+    #[allow(clippy::too_many_lines)]
+    async fn process_envelope(
+        &self,
+        envelope: crate::EnvelopedEvent,
+        event_tx: &mpsc::Sender<crate::EnvelopedEvent>,
+    ) {
+        let event = envelope.event.clone();
+        let trace_id = event.trace_id;
 
-            // Increment metrics based on event type
-            if let cloto_shared::ClotoEventData::MessageReceived(_) = &event.data {
-                self.metrics
-                    .total_requests
-                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            }
+        // Serialize once (outside the history lock); history and SSE share the JSON
+        let serialized = SerializedEvent::from(event.clone());
 
-            // 1. 全プラグイン（および内部システムハンドラ）に配信
-            self.registry
-                .dispatch_event(envelope.clone(), &event_tx)
-                .await;
+        // Streamed response text goes to SSE subscribers only; recording or
+        // dispatching every chunk would crowd out the complete ThoughtResponse
+        if let cloto_shared::ClotoEventData::ThoughtResponseChunk { .. } = &event.data {
+            let _ = self.tx_internal.send(serialized);
+            return;
+        }
+
+        // Record event history
+        self.record_event(serialized.clone()).await;
 
-            // 1b. Consensus Orchestrator (kernel-level, replaces core.moderator plugin)
-            if let Some(ref consensus) = self.consensus {
-                if let Some(response_data) = consensus.handle_event(&event).await {
-                    let response_event = Arc::new(ClotoEvent::with_trace(trace_id, response_data));
-                    let response_envelope = crate::EnvelopedEvent {
-                        event: response_event,
-                        issuer: None,
-                        correlation_id: Some(trace_id),
-                        depth: envelope.depth + 1,
+        // Increment metrics based on event type
+        if let cloto_shared::ClotoEventData::MessageReceived(_) = &event.data {
+            self.metrics
+                .total_requests
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+
+        // 1. 全プラグイン（および内部システムハンドラ）に配信
+        // Off the loop, so a slow plugin holds up only its own delivery
+        let mut turn = self.agent_turn(&event);
+        let dispatch_permits = self.dispatch_permits.clone();
+        // A message behind its agent's previous one takes a dispatch permit
+        // only once its turn comes
+        let (permit, queued) = if turn.as_ref().is_some_and(|t| t.previous.is_some()) {
+            let Ok(queued) = self.queued_permits.clone().acquire_owned().await else {
+                return;
+            };
+            (None, Some(queued))
+        } else {
+            let Ok(permit) = dispatch_permits.clone().acquire_owned().await else {
+                return;
+            };
+            (Some(permit), None)
+        };
+        let registry = self.registry.clone();
+        let dispatch_envelope = envelope.clone();
+        let dispatch_tx = event_tx.clone();
+        tokio::spawn(
+            async move {
+                if let Some(turn) = turn.as_mut() {
+                    turn.wait().await;
+                }
+                let permit = if let Some(permit) = permit {
+                    permit
+                } else {
+                    drop(queued);
+                    let Ok(permit) = dispatch_permits.acquire_owned().await else {
+                        return;
                     };
-                    if let Err(e) = event_tx.send(response_envelope).await {
-                        error!("Failed to send consensus response event: {}", e);
-                    }
+                    permit
+                };
+                registry
+                    .dispatch_event(dispatch_envelope, &dispatch_tx)
+                    .await;
+                // Lets the agent's next message go
+                drop(turn);
+                drop(permit);
+            }
+            .in_current_span(),
+        );
+
+        // 1b. Consensus Orchestrator (kernel-level, replaces core.moderator plugin)
+        if let Some(ref consensus) = self.consensus {
+            for response_data in consensus.handle_event(&event).await {
+                let response_event = Arc::new(ClotoEvent::with_trace(trace_id, response_data));
+                let response_envelope = crate::EnvelopedEvent {
+                    event: response_event,
+                    issuer: None,
+                    correlation_id: Some(trace_id),
+                    depth: envelope.depth + 1,
+                };
+                if let Err(e) = event_tx.send(response_envelope).await {
+                    error!("Failed to send consensus response event: {}", e);
                 }
             }
+        }
 
-            // 2. 内部イベント分岐処理
-            match &event.data {
-                cloto_shared::ClotoEventData::ThoughtResponse {
-                    agent_id,
-                    engine_id: _,
-                    content,
-                    source_message_id: _,
-                } => {
-                    info!(trace_id = %trace_id, agent_id = %agent_id, "🧠 Received ThoughtResponse");
-
-                    // Passive heartbeat: agent responded, update last_seen
-                    self.agent_manager.touch_last_seen(agent_id).await.ok();
-
-                    // Broadcast ThoughtResponse to SSE subscribers (dashboard needs this)
-                    let _ = self.tx_internal.send(event.clone());
-
-                    // Also create a MessageReceived for plugin cascade
-                    let msg = cloto_shared::ClotoMessage::new(
-                        cloto_shared::MessageSource::Agent {
-                            id: agent_id.clone(),
-                        },
-                        content.clone(),
+        // 2. 内部イベント分岐処理
+        match &event.data {
+            cloto_shared::ClotoEventData::ThoughtResponse {
+                agent_id,
+                engine_id: _,
+                content,
+                ..
+            } => {
+                info!(trace_id = %trace_id, agent_id = %agent_id, "🧠 Received ThoughtResponse");
+
+                // Passive heartbeat: agent responded, update last_seen
+                self.agent_manager.touch_last_seen(agent_id).await.ok();
+
+                // Broadcast ThoughtResponse to SSE subscribers (dashboard needs this)
+                let _ = self.tx_internal.send(serialized.clone());
+
+                // Also create a MessageReceived for plugin cascade
+                let msg = cloto_shared::ClotoMessage::new(
+                    cloto_shared::MessageSource::Agent {
+                        id: agent_id.clone(),
+                    },
+                    content.clone(),
+                );
+                let msg_received = Arc::new(cloto_shared::ClotoEvent::with_trace(
+                    trace_id,
+                    cloto_shared::ClotoEventData::MessageReceived(msg.clone()),
+                ));
+                let _ = self.tx_internal.send(msg_received.clone().into());
+
+                let system_envelope = crate::EnvelopedEvent {
+                    event: msg_received,
+                    issuer: None,
+                    correlation_id: Some(trace_id),
+                    depth: envelope.depth + 1,
+                };
+                let _ = event_tx.send(system_envelope).await;
+            }
+            cloto_shared::ClotoEventData::AgentMessage {
+                from_agent,
+                to_agent,
+                content,
+            } => {
+                let _ = self.tx_internal.send(serialized.clone());
+                self.route_agent_message(&envelope, from_agent, to_agent, content, event_tx)
+                    .await;
+            }
+            cloto_shared::ClotoEventData::ActionRequested { requester, action } => {
+                // Security Check: Verify that the issuer matches the requester
+                let is_valid_issuer = match &envelope.issuer {
+                    Some(issuer_id) => issuer_id == requester,
+                    None => true, // System/Kernel can act on behalf of anyone
+                };
+
+                if !is_valid_issuer {
+                    error!(
+                        trace_id = %trace_id,
+                        requester_id = %requester,
+                        issuer_id = ?envelope.issuer,
+                        "🚫 FORGERY DETECTED: Plugin attempted to impersonate another ID in ActionRequested"
                     );
-                    let msg_received = Arc::new(cloto_shared::ClotoEvent::with_trace(
-                        trace_id,
-                        cloto_shared::ClotoEventData::MessageReceived(msg.clone()),
-                    ));
-                    let _ = self.tx_internal.send(msg_received.clone());
-
-                    let system_envelope = crate::EnvelopedEvent {
-                        event: msg_received,
-                        issuer: None,
-                        correlation_id: Some(trace_id),
-                        depth: envelope.depth + 1,
-                    };
-                    let _ = event_tx.send(system_envelope).await;
+                    return; // Drop the event
                 }
-                cloto_shared::ClotoEventData::ActionRequested {
-                    requester,
-                    action: _action,
-                } => {
-                    // Security Check: Verify that the issuer matches the requester
-                    let is_valid_issuer = match &envelope.issuer {
-                        Some(issuer_id) => issuer_id == requester,
-                        None => true, // System/Kernel can act on behalf of anyone
-                    };
 
-                    if !is_valid_issuer {
-                        error!(
-                            trace_id = %trace_id,
-                            requester_id = %requester,
-                            issuer_id = ?envelope.issuer,
-                            "🚫 FORGERY DETECTED: Plugin attempted to impersonate another ID in ActionRequested"
-                        );
-                        continue; // Drop the event
+                if self.authorize(requester, Permission::InputControl).await {
+                    if !self.check_action_rate(&requester.to_string()) {
+                        warn!(trace_id = %trace_id, requester_id = %requester, "⚡ InputControl rate limit exceeded");
+                        return;
                     }
-
-                    if self.authorize(requester, Permission::InputControl).await {
-                        if !self.check_action_rate(&requester.to_string()) {
-                            warn!(trace_id = %trace_id, requester_id = %requester, "⚡ InputControl rate limit exceeded");
-                            continue;
-                        }
-                        info!(trace_id = %trace_id, requester_id = %requester, "✅ Action authorized");
-                        let _ = self.tx_internal.send(event.clone());
-                    } else {
-                        error!(
-                            trace_id = %trace_id,
-                            requester_id = %requester,
-                            "🚫 SECURITY VIOLATION: Plugin attempted Action without InputControl permission"
+                    info!(trace_id = %trace_id, requester_id = %requester, "✅ Action authorized");
+                    if let (cloto_shared::HandAction::ClickElement { label }, Some(resolver)) =
+                        (action, &self.element_resolver)
+                    {
+                        self.spawn_click_resolution(
+                            resolver.clone(),
+                            &envelope,
+                            *requester,
+                            label.clone(),
+                            event_tx.clone(),
                         );
+                        return;
                     }
-                }
-                cloto_shared::ClotoEventData::PermissionGranted {
-                    plugin_id,
-                    permission,
-                } => {
-                    info!(
+                    let _ = self.tx_internal.send(serialized.clone());
+                } else {
+                    error!(
                         trace_id = %trace_id,
-                        plugin_id = %plugin_id,
-                        permission = ?permission,
-                        "🔐 Permission GRANTED to plugin"
+                        requester_id = %requester,
+                        "🚫 SECURITY VIOLATION: Plugin attempted Action without InputControl permission"
                     );
+                }
+            }
+            cloto_shared::ClotoEventData::PermissionGranted {
+                plugin_id,
+                permission,
+            } => {
+                info!(
+                    trace_id = %trace_id,
+                    plugin_id = %plugin_id,
+                    permission = ?permission,
+                    "🔐 Permission GRANTED to plugin"
+                );
+
+                // 1. 権限リストの更新 (In-memory)
+                let cloto_id = cloto_shared::ClotoId::from_name(plugin_id);
+                self.registry
+                    .update_effective_permissions(cloto_id, permission.clone())
+                    .await;
 
-                    // 1. 権限リストの更新 (In-memory)
-                    let cloto_id = cloto_shared::ClotoId::from_name(plugin_id);
-                    self.registry
-                        .update_effective_permissions(cloto_id, permission.clone())
-                        .await;
-
-                    // 2. Capability の注入
-                    let plugins = self.registry.plugins.read().await;
-                    if let Some(plugin) = plugins.get(plugin_id) {
-                        if let Some(cap) = self
-                            .plugin_manager
-                            .get_capability_for_permission(permission)
-                        {
-                            let plugin_id = plugin_id.clone(); // Clone for spawn
-                            info!(trace_id = %trace_id, plugin_id = %plugin_id, "💉 Injecting capability");
-                            let plugin = plugin.clone();
-                            tokio::spawn(async move {
+                // 2. Capability の注入
+                let plugins = self.registry.plugins.load();
+                if let Some(plugin) = plugins.get(plugin_id) {
+                    if let Some(cap) = self
+                        .plugin_manager
+                        .get_capability_for_permission(permission)
+                    {
+                        let plugin_id = plugin_id.clone(); // Clone for spawn
+                        info!(trace_id = %trace_id, plugin_id = %plugin_id, "💉 Injecting capability");
+                        let plugin = plugin.clone();
+                        tokio::spawn(
+                            async move {
                                 if let Err(e) = plugin.on_capability_injected(cap).await {
                                     error!(trace_id = %trace_id, plugin_id = %plugin_id, error = %e, "❌ Failed to inject capability");
                                 }
-                            });
-                        }
+                            }
+                            .in_current_span(),
+                        );
                     }
-                    drop(plugins);
-                }
-                cloto_shared::ClotoEventData::ConfigUpdated { .. } => {
-                    let _ = self.tx_internal.send(event);
-                }
-                cloto_shared::ClotoEventData::AgentPowerChanged {
-                    ref agent_id,
-                    enabled,
-                } => {
-                    info!(
-                        trace_id = %trace_id,
-                        agent_id = %agent_id,
-                        enabled = %enabled,
-                        "🔌 Agent power state changed"
-                    );
-                    let _ = self.tx_internal.send(event);
-                }
-                cloto_shared::ClotoEventData::ToolInvoked {
-                    ref agent_id,
-                    ref tool_name,
-                    success,
-                    duration_ms,
-                    iteration,
-                    ..
-                } => {
-                    info!(
-                        trace_id = %trace_id,
-                        agent_id = %agent_id,
-                        tool = %tool_name,
-                        success = success,
-                        duration_ms = duration_ms,
-                        iteration = iteration,
-                        "🔧 Tool invoked"
-                    );
-                    let _ = self.tx_internal.send(event);
-                }
-                cloto_shared::ClotoEventData::AgenticLoopCompleted {
-                    ref agent_id,
-                    total_iterations,
-                    total_tool_calls,
-                    ..
-                } => {
-                    info!(
-                        trace_id = %trace_id,
-                        agent_id = %agent_id,
-                        iterations = total_iterations,
-                        tool_calls = total_tool_calls,
-                        "✅ Agentic loop completed"
-                    );
-                    let _ = self.tx_internal.send(event);
-                }
-                _ => {
-                    // Forward to SSE subscribers
-                    let _ = self.tx_internal.send(event);
                 }
+                drop(plugins);
+            }
+            cloto_shared::ClotoEventData::ConfigUpdated { .. } => {
+                let _ = self.tx_internal.send(serialized);
+            }
+            cloto_shared::ClotoEventData::AgentPowerChanged {
+                ref agent_id,
+                enabled,
+            } => {
+                info!(
+                    trace_id = %trace_id,
+                    agent_id = %agent_id,
+                    enabled = %enabled,
+                    "🔌 Agent power state changed"
+                );
+                let _ = self.tx_internal.send(serialized);
+            }
+            cloto_shared::ClotoEventData::ToolInvoked {
+                ref agent_id,
+                ref tool_name,
+                success,
+                duration_ms,
+                iteration,
+                ..
+            } => {
+                info!(
+                    trace_id = %trace_id,
+                    agent_id = %agent_id,
+                    tool = %tool_name,
+                    success = success,
+                    duration_ms = duration_ms,
+                    iteration = iteration,
+                    "🔧 Tool invoked"
+                );
+                let _ = self.tx_internal.send(serialized);
+            }
+            cloto_shared::ClotoEventData::ToolApprovalRequested {
+                ref request_id,
+                ref server_id,
+                ref tool_name,
+                ..
+            } => {
+                warn!(
+                    trace_id = %trace_id,
+                    request_id = %request_id,
+                    server_id = %server_id,
+                    tool = %tool_name,
+                    "✋ Tool call awaiting approval"
+                );
+                let _ = self.tx_internal.send(serialized);
+            }
+            cloto_shared::ClotoEventData::AgenticLoopCompleted {
+                ref agent_id,
+                total_iterations,
+                total_tool_calls,
+                ..
+            } => {
+                info!(
+                    trace_id = %trace_id,
+                    agent_id = %agent_id,
+                    iterations = total_iterations,
+                    tool_calls = total_tool_calls,
+                    "✅ Agentic loop completed"
+                );
+                let _ = self.tx_internal.send(serialized);
+            }
+            cloto_shared::ClotoEventData::AgenticLoopAborted {
+                ref agent_id,
+                ref reason,
+                total_iterations,
+                total_tool_calls,
+                ..
+            } => {
+                warn!(
+                    trace_id = %trace_id,
+                    agent_id = %agent_id,
+                    reason = %reason,
+                    iterations = total_iterations,
+                    tool_calls = total_tool_calls,
+                    "🔁 Agentic loop aborted"
+                );
+                let _ = self.tx_internal.send(serialized);
+            }
+            _ => {
+                // Forward to SSE subscribers
+                let _ = self.tx_internal.send(serialized);
             }
         }
     }
 
+    /// Deliver an `AgentMessage` to its recipient as a `MessageReceived`
+    /// one depth deeper. The depth travels in the message metadata
+    /// (`event_depth`) so the recipient's `ThoughtResponse` keeps counting
+    /// instead of starting over at zero.
+    async fn route_agent_message(
+        &self,
+        envelope: &crate::EnvelopedEvent,
+        from_agent: &str,
+        to_agent: &str,
+        content: &str,
+        event_tx: &mpsc::Sender<crate::EnvelopedEvent>,
+    ) {
+        let trace_id = envelope.event.trace_id;
+        let depth = envelope.depth + 1;
+        if from_agent == to_agent {
+            warn!(trace_id = %trace_id, agent_id = %from_agent, "🚫 Agent message to itself dropped");
+            return;
+        }
+        if depth >= self.registry.max_event_depth {
+            warn!(
+                trace_id = %trace_id,
+                from = %from_agent,
+                to = %to_agent,
+                depth = depth,
+                "🛑 Agent message dropped: event depth limit reached"
+            );
+            return;
+        }
+        for agent_id in [from_agent, to_agent] {
+            if let Err(e) = self.agent_manager.get_agent_config(agent_id).await {
+                warn!(trace_id = %trace_id, agent_id = %agent_id, error = %e, "🚫 Agent message dropped: unknown agent");
+                return;
+            }
+        }
+
+        info!(trace_id = %trace_id, from = %from_agent, to = %to_agent, depth = depth, "📨 Routing agent message");
+        let mut msg = cloto_shared::ClotoMessage::new(
+            cloto_shared::MessageSource::Agent {
+                id: from_agent.to_string(),
+            },
+            content.to_string(),
+        );
+        msg.target_agent = Some(to_agent.to_string());
+        msg.metadata
+            .insert("target_agent_id".to_string(), to_agent.to_string());
+        msg.metadata
+            .insert("event_depth".to_string(), depth.to_string());
+        let delivery = Arc::new(ClotoEvent::with_trace(
+            trace_id,
+            cloto_shared::ClotoEventData::MessageReceived(msg),
+        ));
+        let _ = event_tx
+            .send(crate::EnvelopedEvent {
+                event: delivery,
+                issuer: None,
+                correlation_id: Some(trace_id),
+                depth,
+            })
+            .await;
+    }
+
     /// Per-plugin rate limiting for InputControl actions (bug-143: Guardrail 1.6).
     /// Returns `true` if the action is within rate limits, `false` if rate-limited.
     fn check_action_rate(&self, requester_id: &str) -> bool {
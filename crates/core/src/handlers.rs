@@ -1,29 +1,44 @@
 pub mod agents;
 pub mod assets;
+pub mod auth;
 pub mod chat;
 pub mod cron;
 pub mod events;
+pub mod keys;
 pub mod llm;
 pub mod mcp;
+pub mod openai;
 pub mod permissions;
+pub mod rooms;
 pub mod system;
 
 // Re-export all handler functions so that existing `handlers::*` paths in lib.rs continue to work.
-pub use agents::{create_agent, delete_agent, get_agents, power_toggle, update_agent};
+pub use agents::{
+    create_agent, delete_agent, get_agent_plugins, get_agents, power_toggle, put_agent_plugins,
+    update_agent,
+};
+pub use auth::{create_user, delete_user, get_session, list_users, login, logout};
 pub use chat::chat_handler;
 pub use cron::{
-    create_cron_job, delete_cron_job, list_cron_jobs, run_cron_job_now, toggle_cron_job,
+    create_cron_job, delete_cron_job, list_cron_job_runs, list_cron_jobs, run_cron_job_now,
+    toggle_cron_job,
 };
-pub use events::post_event_handler;
+pub use events::{list_dead_letter_events, post_event_handler, replay_dead_letter_event};
+pub use keys::{create_api_key, delete_api_key, list_api_keys};
 pub use llm::{delete_llm_provider_key, list_llm_providers, set_llm_provider_key};
 pub use mcp::{
     apply_plugin_settings, create_mcp_server, delete_mcp_server, get_agent_access,
-    get_mcp_server_access, get_mcp_server_settings, get_plugin_config, get_plugin_permissions,
-    get_plugins, get_yolo_mode, grant_permission_handler, list_mcp_servers, put_mcp_server_access,
-    restart_mcp_server, revoke_permission_handler, set_yolo_mode, start_mcp_server,
-    stop_mcp_server, update_mcp_server_settings, update_plugin_config,
+    get_mcp_server_access, get_mcp_server_models, get_mcp_server_settings, get_plugin_config,
+    get_plugin_icon, get_plugin_permissions, get_plugins, get_yolo_mode, grant_permission_handler,
+    list_mcp_servers, put_mcp_server_access, reload_plugin, restart_mcp_server,
+    revoke_permission_handler, set_yolo_mode, start_mcp_server, stop_mcp_server,
+    update_mcp_server_settings, update_plugin_config,
+};
+pub use permissions::{
+    approve_permission, approve_tool_call, deny_permission, deny_tool_call,
+    get_pending_permissions, get_pending_tool_calls,
 };
-pub use permissions::{approve_permission, deny_permission, get_pending_permissions};
+pub use rooms::{create_room, delete_room, get_room_messages, list_rooms, post_room_message};
 
 /// GET /api/system/version
 /// Returns current Cloto version and build target (public, no auth).
@@ -48,43 +63,185 @@ use axum::{
     Json,
 };
 use futures::stream::Stream;
-use std::{convert::Infallible, sync::Arc, time::Duration};
+use std::{collections::HashSet, convert::Infallible, sync::Arc, time::Duration};
 use tracing::{error, info};
 
 use crate::{AppError, AppResult, AppState};
 
-pub(crate) fn check_auth(state: &AppState, headers: &HeaderMap) -> AppResult<()> {
+/// GET /api/system/capabilities — detected hardware (GPU, RAM, CPU, screen, camera)
+pub async fn capabilities_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<crate::platform::HardwareCapabilities>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    let hardware = tokio::task::spawn_blocking(crate::platform::hardware)
+        .await
+        .map_err(|e| AppError::Internal(e.into()))?;
+    Ok(Json(hardware.clone()))
+}
+
+/// GET /api/system/config — effective settings and where each came from
+/// (flag, env, file, default). Secrets are masked.
+pub async fn system_config_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    let config = state
+        .runtime_config
+        .read()
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("runtime config lock poisoned")))?;
+    Ok(Json(serde_json::json!({
+        "config_file": config.config_file,
+        "settings": config.effective_settings(),
+    })))
+}
+
+/// What a key issued via `/api/system/keys` may do. Each scope includes the
+/// ones below it: `admin` ⊃ `chat` ⊃ `read_only`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// GET endpoints: dashboards, agent lists, chat history.
+    ReadOnly,
+    /// Read access plus sending (and clearing) chat messages.
+    Chat,
+    /// Everything, including key management.
+    Admin,
+}
+
+impl ApiKeyScope {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::Chat => "chat",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl std::str::FromStr for ApiKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_only" => Ok(Self::ReadOnly),
+            "chat" => Ok(Self::Chat),
+            "admin" => Ok(Self::Admin),
+            other => Err(format!(
+                "Unknown scope '{}' (expected read_only, chat or admin)",
+                other
+            )),
+        }
+    }
+}
+
+/// The key sent with a request: `X-API-Key`, or an `Authorization: Bearer`
+/// token as sent by OpenAI-compatible clients.
+pub(crate) fn provided_api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("X-API-Key").and_then(|h| h.to_str().ok()) {
+        return Some(key);
+    }
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Whether `provided` is `CLOTO_API_KEY` (constant-time). Read from the
+/// runtime config, so authentication and rate limiting agree on the key.
+pub(crate) fn is_admin_key(state: &AppState, provided: &str) -> bool {
     use subtle::ConstantTimeEq;
-    if let Some(ref required_key) = state.config.admin_api_key {
-        let auth_header = headers.get("X-API-Key").and_then(|h| h.to_str().ok());
+    state
+        .runtime_config
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .admin_api_key
+        .as_ref()
+        .is_some_and(|key| bool::from(provided.as_bytes().ct_eq(key.as_bytes())))
+}
 
-        let matches: bool = match auth_header {
-            Some(provided) => provided.as_bytes().ct_eq(required_key.as_bytes()).into(),
-            None => false,
+/// Require admin access: `CLOTO_API_KEY` or an `admin`-scoped key.
+pub(crate) fn check_auth(state: &AppState, headers: &HeaderMap) -> AppResult<()> {
+    check_scope(state, headers, ApiKeyScope::Admin)
+}
+
+/// Require a key whose scope includes `required`. `CLOTO_API_KEY` always
+/// passes; keys from the `api_keys` table pass when their scope is at least
+/// `required`. Revoked keys are rejected either way. Without a key, a
+/// dashboard session cookie passes when the user's role is at least `required`.
+pub(crate) fn check_scope(
+    state: &AppState,
+    headers: &HeaderMap,
+    required: ApiKeyScope,
+) -> AppResult<()> {
+    let denied = || {
+        AppError::Cloto(cloto_shared::ClotoError::PermissionDenied(
+            cloto_shared::Permission::AdminAccess,
+        ))
+    };
+    let has_scoped_keys = state.api_keys.read().is_ok_and(|keys| !keys.is_empty());
+    let has_users = state.users.read().is_ok_and(|users| !users.is_empty());
+    let has_admin_key = state
+        .runtime_config
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .admin_api_key
+        .is_some();
+    if has_admin_key || has_scoped_keys || has_users {
+        let Some(provided) = provided_api_key(headers) else {
+            return match auth::session_role(state, headers) {
+                Some(role) if role >= required => Ok(()),
+                Some(role) => {
+                    tracing::warn!(
+                        role = role.as_str(),
+                        required = required.as_str(),
+                        "🚫 User role too narrow"
+                    );
+                    Err(denied())
+                }
+                None => Err(denied()),
+            };
         };
-        if !matches {
-            return Err(AppError::Cloto(cloto_shared::ClotoError::PermissionDenied(
-                cloto_shared::Permission::AdminAccess,
-            )));
+
+        let scope = if is_admin_key(state, provided) {
+            Some(ApiKeyScope::Admin)
+        } else {
+            let hash = crate::db::hash_scoped_api_key(provided);
+            state
+                .api_keys
+                .read()
+                .ok()
+                .and_then(|keys| keys.get(&hash).copied())
+        };
+        match scope {
+            Some(scope) if scope >= required => {}
+            Some(scope) => {
+                tracing::warn!(
+                    scope = scope.as_str(),
+                    required = required.as_str(),
+                    "🚫 API key scope too narrow"
+                );
+                return Err(denied());
+            }
+            None => return Err(denied()),
         }
         // Check revocation: reject key even if it matches, if it has been invalidated
-        if let Some(provided) = auth_header {
-            let hash = crate::db::hash_api_key(provided);
-            if let Ok(revoked) = state.revoked_keys.read() {
-                if revoked.contains(&hash) {
-                    tracing::warn!("🚫 Rejected revoked API key");
-                    return Err(AppError::Cloto(cloto_shared::ClotoError::PermissionDenied(
-                        cloto_shared::Permission::AdminAccess,
-                    )));
-                }
+        let hash = crate::db::hash_api_key(provided);
+        if let Ok(revoked) = state.revoked_keys.read() {
+            if revoked.contains(&hash) {
+                tracing::warn!("🚫 Rejected revoked API key");
+                return Err(denied());
             }
         }
     } else {
         // In release builds, require API key to be configured
         if !cfg!(debug_assertions) {
-            return Err(AppError::Cloto(cloto_shared::ClotoError::PermissionDenied(
-                cloto_shared::Permission::AdminAccess,
-            )));
+            return Err(denied());
         }
         // M-09: Warn loudly in debug builds when no API key is set
         tracing::warn!(
@@ -96,7 +253,7 @@ pub(crate) fn check_auth(state: &AppState, headers: &HeaderMap) -> AppResult<()>
 }
 
 pub(crate) fn spawn_admin_audit(
-    pool: sqlx::SqlitePool,
+    pool: crate::db::DbPool,
     event_type: &str,
     target_id: String,
     reason: String,
@@ -130,7 +287,8 @@ pub(crate) fn spawn_admin_audit(
 /// # Behavior
 /// 1. Broadcasts `SystemNotification` shutdown message
 /// 2. Creates `.maintenance` file (atomic write via tmp + rename)
-/// 3. Signals shutdown after 1-second delay (allows response delivery)
+/// 3. Signals shutdown after 1-second delay (allows response delivery);
+///    the kernel then drains in-flight agentic runs before exiting
 ///
 /// Guardian process can detect `.maintenance` file and handle restart logic.
 ///
@@ -159,34 +317,70 @@ pub async fn shutdown_handler(
     tokio::spawn(async move {
         tokio::time::sleep(Duration::from_secs(1)).await;
 
-        // 🚧 Signal maintenance mode (atomic write to prevent symlink attacks)
-        let maint = crate::config::exe_dir().join(".maintenance");
-        let suffix: u64 = rand::random();
-        let maint_tmp = crate::config::exe_dir().join(format!(".maintenance_{:016x}.tmp", suffix));
-        match std::fs::write(&maint_tmp, "active")
-            .and_then(|()| std::fs::rename(&maint_tmp, &maint))
-        {
+        // 🚧 Signal maintenance mode
+        match crate::write_maintenance_marker() {
             Ok(()) => info!("🚧 Maintenance mode engaged."),
             Err(e) => error!("❌ Failed to create .maintenance file: {}", e),
         }
 
         info!("👋 Kernel shutting down gracefully.");
-        shutdown.notify_one();
+        shutdown.notify_waiters();
     });
 
     Ok(Json(serde_json::json!({ "status": "shutting_down" })))
 }
 
+#[derive(serde::Deserialize)]
+pub struct SseQuery {
+    /// Comma-separated event types, e.g. `MessageReceived,ThoughtResponse`.
+    types: Option<String>,
+    /// Only events sent by, to or about this agent.
+    agent_id: Option<String>,
+}
+
+/// Server-side filter for one SSE subscriber. Empty filters match everything.
+struct SseFilter {
+    types: HashSet<String>,
+    agent_id: Option<String>,
+}
+
+impl SseFilter {
+    fn new(query: SseQuery) -> Self {
+        Self {
+            types: query
+                .types
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+            agent_id: query.agent_id.filter(|id| !id.is_empty()),
+        }
+    }
+
+    fn matches(&self, data: &cloto_shared::ClotoEventData) -> bool {
+        (self.types.is_empty() || self.types.contains(data.type_name()))
+            && self
+                .agent_id
+                .as_deref()
+                .is_none_or(|id| data.involves_agent(id))
+    }
+}
+
 /// Server-Sent Events (SSE) stream for real-time event delivery.
 ///
-/// **Route:** `GET /api/events/stream`
+/// **Route:** `GET /api/events[?types=MessageReceived,ThoughtResponse&agent_id=...]`
 ///
 /// # Authentication
 /// No authentication required (subscriber-only).
 ///
 /// # Behavior
 /// 1. Sends initial `handshake` event with data `"connected"`
-/// 2. Streams all events from the broadcast channel as JSON
+/// 2. Streams events from the broadcast channel as JSON (serialized once
+///    when published, not per subscriber). `types` keeps only the listed
+///    event types and `agent_id` only events sent by, to or about that
+///    agent; events not tied to an agent are dropped when it is set.
 /// 3. Sends keep-alive every 15 seconds to prevent connection timeout
 /// 4. Handles lag by warning and continuing (events may be dropped)
 ///
@@ -195,17 +389,17 @@ pub async fn shutdown_handler(
 /// Connection closes when the broadcast channel is closed.
 pub async fn sse_handler(
     State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<SseQuery>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let filter = SseFilter::new(query);
     let mut rx = state.tx.subscribe();
     let stream = async_stream::stream! {
         yield Ok(Event::default().event("handshake").data("connected"));
         loop {
             match rx.recv().await {
-                Ok(event) => {
-                    if let Ok(json) = serde_json::to_string(&event) {
-                        yield Ok(Event::default().data(json));
-                    }
-                }
+                Ok(event) if !filter.matches(&event.data) => {}
+                // Serialized once by the publisher, shared by all subscribers
+                Ok(event) => yield Ok(Event::default().data(&*event.json)),
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
                     tracing::warn!("SSE stream lagged by {} messages", n);
                 }
@@ -222,20 +416,117 @@ pub async fn sse_handler(
     )
 }
 
-/// Get recent event history from the in-memory ring buffer.
+#[derive(serde::Deserialize)]
+pub struct HistoryQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+    /// Unix timestamp (ms); only events at or after it.
+    since: Option<i64>,
+    /// Unix timestamp (ms); only events before it.
+    until: Option<i64>,
+    /// Event type tag, e.g. `ThoughtResponse`.
+    #[serde(rename = "type")]
+    event_type: Option<String>,
+    trace_id: Option<String>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, event: &cloto_shared::ClotoEvent) -> bool {
+        let at = event.timestamp.timestamp_millis();
+        self.since.is_none_or(|since| at >= since)
+            && self.until.is_none_or(|until| at < until)
+            && self
+                .event_type
+                .as_deref()
+                .is_none_or(|t| event.data.type_name() == t)
+            && self
+                .trace_id
+                .as_deref()
+                .is_none_or(|id| event.trace_id.to_string() == id)
+    }
+}
+
+const DEFAULT_HISTORY_PAGE: usize = 200;
+const MAX_HISTORY_PAGE: usize = 1000;
+
+/// Get event history, from the event journal when `CLOTO_EVENT_JOURNAL` is
+/// enabled and from the in-memory ring buffer otherwise.
 ///
-/// **Route:** `GET /api/history`
+/// **Route:** `GET /api/history[?limit=N&offset=M&since=MS&until=MS&type=T&trace_id=ID]`
 ///
 /// # Authentication
 /// No authentication required (read-only).
 ///
 /// # Response
-/// Returns a JSON array of recent events (most recent first),
-/// limited by the configured `event_history_size`.
-pub async fn get_history(State(state): State<Arc<AppState>>) -> AppResult<Json<serde_json::Value>> {
-    let history = state.event_history.read().await;
-    let history_vec: Vec<_> = history.iter().collect();
-    Ok(Json(serde_json::json!(history_vec)))
+/// Returns a JSON array with one page of matching events in chronological
+/// order: the `limit` (default 200, max 1000) events preceding the `offset`
+/// newest ones. `since` (inclusive) and `until` (exclusive) are Unix
+/// timestamps in ms; `type` is the event's `type` tag. `X-Total-Count`
+/// carries the number of matching events. Events are serialized once when
+/// recorded, so a page costs one string copy per event.
+pub async fn get_history(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<HistoryQuery>,
+) -> AppResult<impl axum::response::IntoResponse> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_PAGE)
+        .clamp(1, MAX_HISTORY_PAGE);
+    let offset = query.offset.unwrap_or(0);
+    let (total, page): (usize, Vec<Arc<str>>) = if state.config.event_journal_enabled {
+        let filter = crate::db::JournalFilter {
+            event_type: query.event_type.as_deref(),
+            trace_id: query.trace_id.as_deref(),
+            since: query.since,
+            until: query.until,
+        };
+        let (Ok(sql_limit), Ok(sql_offset)) = (i64::try_from(limit), i64::try_from(offset)) else {
+            return Err(AppError::Validation("offset is out of range".into()));
+        };
+        let total = crate::db::count_events(&state.pool, &filter).await?;
+        let newest_first =
+            crate::db::list_events(&state.pool, &filter, sql_limit, sql_offset).await?;
+        (
+            usize::try_from(total).unwrap_or(0),
+            newest_first.into_iter().rev().map(Arc::from).collect(),
+        )
+    } else {
+        let history = state.event_history.read().await;
+        let matching: Vec<&crate::events::SerializedEvent> = history
+            .iter()
+            .filter(|entry| query.matches(entry))
+            .collect();
+        let end = matching.len().saturating_sub(offset);
+        let start = end.saturating_sub(limit);
+        let page = matching[start..end]
+            .iter()
+            .map(|entry| entry.json.clone())
+            .collect();
+        (matching.len(), page)
+    };
+
+    let mut body = String::with_capacity(page.iter().map(|j| j.len() + 1).sum::<usize>() + 2);
+    body.push('[');
+    for (i, json) in page.iter().enumerate() {
+        if i > 0 {
+            body.push(',');
+        }
+        body.push_str(json);
+    }
+    body.push(']');
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "application/json".to_string(),
+            ),
+            (
+                axum::http::HeaderName::from_static("x-total-count"),
+                total.to_string(),
+            ),
+        ],
+        body,
+    ))
 }
 
 /// Get system metrics and health information.
@@ -251,12 +542,33 @@ pub async fn get_history(State(state): State<Arc<AppState>>) -> AppResult<Json<s
 ///   "total_requests": 42,
 ///   "total_memories": 10,
 ///   "total_episodes": 5,
-///   "event_history": { "current_size": 100, "max_size": 1000, "memory_estimate_bytes": 800 }
+///   "event_history": { "current_size": 100, "max_size": 1000, "memory_estimate_bytes": 800 },
+///   "database": {
+///     "pool": { "max_connections": 10, "open": 3, "idle": 2, "in_use": 1 },
+///     "acquire_wait": { "count": 120, "sum_ms": 4.2, "buckets": [{ "le_ms": 1, "count": 118 }, …] },
+///     "queries": [{ "query": "SELECT * FROM agents", "count": 40, "sum_ms": 12.5, "buckets": […] }]
+///   },
+///   "rate_limits": {
+///     "groups": { "chat": { "buckets": 3, "limited_total": 0 }, … },
+///     "api_keys": [{ "id": "key.…", "group": "chat", "remaining": 37, "idle_secs": 4 }]
+///   }
 /// }
 /// ```
+/// Histogram buckets are cumulative; `queries` is sorted by total time.
+/// Rate limit buckets of IP clients are counted but not listed.
 pub async fn get_metrics(State(state): State<Arc<AppState>>) -> AppResult<Json<serde_json::Value>> {
-    let history_len = state.event_history.read().await.len();
-    let max_size = state.config.event_history_size;
+    let (history_len, history_bytes) = {
+        let history = state.event_history.read().await;
+        let bytes: usize = history
+            .iter()
+            .map(|entry| entry.json.len() + std::mem::size_of::<crate::events::SerializedEvent>())
+            .sum();
+        (history.len(), bytes)
+    };
+    let max_size = state
+        .runtime_config
+        .read()
+        .map_or(state.config.event_history_size, |c| c.event_history_size);
 
     Ok(Json(serde_json::json!({
         "total_requests": state.metrics.total_requests.load(std::sync::atomic::Ordering::Relaxed),
@@ -266,11 +578,229 @@ pub async fn get_metrics(State(state): State<Arc<AppState>>) -> AppResult<Json<s
         "event_history": {
             "current_size": history_len,
             "max_size": max_size,
-            "memory_estimate_bytes": history_len * std::mem::size_of::<std::sync::Arc<cloto_shared::ClotoEvent>>(),
-        }
+            "memory_estimate_bytes": history_bytes,
+        },
+        "database": {
+            "pool": crate::db_metrics::PoolStats::of(&state.pool),
+            "acquire_wait": state.metrics.db.acquire_snapshot(),
+            "queries": state.metrics.db.query_snapshots(),
+        },
+        "rate_limits": state.rate_limiter.snapshot(),
     })))
 }
 
+/// System metrics in Prometheus text exposition format.
+///
+/// **Route:** `GET /api/metrics/prometheus`
+///
+/// # Authentication
+/// No authentication required (read-only).
+///
+/// # Response
+/// Request/memory counters, database pool gauges, and histograms of
+/// connection acquire wait (`cloto_db_acquire_seconds`) and per-query
+/// execution time (`cloto_db_query_seconds{query="…"}`).
+pub async fn get_prometheus_metrics(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    use std::fmt::Write as _;
+    use std::sync::atomic::Ordering;
+
+    let history_len = state.event_history.read().await.len();
+    let pool = crate::db_metrics::PoolStats::of(&state.pool);
+    let mut out = String::new();
+    for (name, kind, value) in [
+        (
+            "cloto_requests_total",
+            "counter",
+            state.metrics.total_requests.load(Ordering::Relaxed),
+        ),
+        (
+            "cloto_memories_total",
+            "counter",
+            state.metrics.total_memories.load(Ordering::Relaxed),
+        ),
+        (
+            "cloto_episodes_total",
+            "counter",
+            state.metrics.total_episodes.load(Ordering::Relaxed),
+        ),
+        ("cloto_event_history_size", "gauge", history_len as u64),
+        (
+            "cloto_db_pool_max_connections",
+            "gauge",
+            u64::from(pool.max_connections),
+        ),
+    ] {
+        let _ = writeln!(out, "# TYPE {} {}\n{} {}", name, kind, name, value);
+    }
+    let _ = writeln!(out, "# TYPE cloto_db_pool_connections gauge");
+    let _ = writeln!(
+        out,
+        "cloto_db_pool_connections{{state=\"idle\"}} {}",
+        pool.idle
+    );
+    let _ = writeln!(
+        out,
+        "cloto_db_pool_connections{{state=\"in_use\"}} {}",
+        pool.in_use
+    );
+
+    let _ = writeln!(out, "# TYPE cloto_db_acquire_seconds histogram");
+    state
+        .metrics
+        .db
+        .acquire_snapshot()
+        .write_prometheus(&mut out, "cloto_db_acquire_seconds", "");
+    let _ = writeln!(out, "# TYPE cloto_db_query_seconds histogram");
+    for timing in state.metrics.db.query_snapshots() {
+        let labels = format!(
+            "query=\"{}\"",
+            crate::db_metrics::escape_label(&timing.query)
+        );
+        timing
+            .histogram
+            .write_prometheus(&mut out, "cloto_db_query_seconds", &labels);
+    }
+
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        out,
+    )
+}
+
+/// Get recent slow requests (slower than `CLOTO_SLOW_REQUEST_MS`).
+///
+/// **Route:** `GET /api/metrics/slow-requests`
+///
+/// # Authentication
+/// No authentication required (read-only).
+///
+/// # Response
+/// ```json
+/// {
+///   "threshold_ms": 2000,
+///   "requests": [
+///     { "method": "POST", "route": "/api/chat", "status": 200, "duration_ms": 3120,
+///       "trace_id": "…", "timestamp": "2026-01-01T00:00:00Z" }
+///   ]
+/// }
+/// ```
+/// Most recent first, at most 100 entries.
+pub async fn get_slow_requests(
+    State(state): State<Arc<AppState>>,
+) -> AppResult<Json<serde_json::Value>> {
+    Ok(Json(serde_json::json!({
+        "threshold_ms": state.slow_requests.threshold().as_millis(),
+        "requests": state.slow_requests.snapshot(),
+    })))
+}
+
+#[derive(serde::Deserialize)]
+pub struct AuditQuery {
+    /// Event type, e.g. `TOOL_EXECUTED`.
+    #[serde(rename = "type")]
+    event_type: Option<String>,
+    /// Actor (for tool calls: the agent).
+    agent_id: Option<String>,
+    /// RFC 3339 timestamp; only older entries are returned.
+    before: Option<String>,
+    limit: Option<i64>,
+}
+
+/// Query the audit log.
+///
+/// **Route:** `GET /api/audit[?type=TOOL_EXECUTED&agent_id=...&before=...&limit=N]`
+///
+/// # Authentication
+/// Requires valid API key in `X-API-Key` header.
+///
+/// # Response
+/// `{ "entries": [AuditLogEntry, ...] }`, most recent first, `limit` defaults
+/// to 100 (max 1000). `TOOL_EXECUTED` entries carry the engine, providing
+/// plugin/server, duration and redacted arguments in `metadata`.
+pub async fn get_audit_logs(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<AuditQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let before = query
+        .before
+        .as_deref()
+        .map(|b| {
+            chrono::DateTime::parse_from_rfc3339(b)
+                .map(|ts| ts.with_timezone(&chrono::Utc).to_rfc3339())
+                .map_err(|_| AppError::Validation("before must be an RFC 3339 timestamp".into()))
+        })
+        .transpose()?;
+    let entries = crate::db::query_audit_logs_filtered(
+        &state.pool,
+        query.event_type.as_deref(),
+        query.agent_id.as_deref(),
+        before.as_deref(),
+        query.limit.unwrap_or(100).clamp(1, 1000),
+    )
+    .await?;
+    Ok(Json(serde_json::json!({ "entries": entries })))
+}
+
+#[derive(serde::Deserialize)]
+pub struct ToolInvocationQuery {
+    agent_id: Option<String>,
+    tool: Option<String>,
+    /// Native plugin or MCP server owning the tool.
+    server: Option<String>,
+    /// `SUCCESS`, `FAILURE` or `DENIED`.
+    status: Option<String>,
+    /// Unix timestamp (ms); only older invocations are returned.
+    before: Option<i64>,
+    limit: Option<i64>,
+}
+
+/// Query recorded agent tool calls.
+///
+/// **Route:** `GET /api/tools/invocations[?agent_id=...&tool=...&server=...&status=...&before=...&limit=N]`
+///
+/// # Authentication
+/// Requires valid API key in `X-API-Key` header.
+///
+/// # Response
+/// `{ "invocations": [ToolInvocationRow, ...] }`, most recent first, `limit`
+/// defaults to 100 (max 1000). `arguments` is redacted and truncated JSON.
+pub async fn get_tool_invocations(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<ToolInvocationQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let status = query.status.map(|s| s.to_uppercase());
+    if let Some(ref status) = status {
+        if !["SUCCESS", "FAILURE", "DENIED"].contains(&status.as_str()) {
+            return Err(AppError::Validation(
+                "status must be SUCCESS, FAILURE or DENIED".into(),
+            ));
+        }
+    }
+    let filter = crate::db::ToolInvocationFilter {
+        agent_id: query.agent_id.as_deref(),
+        tool_name: query.tool.as_deref(),
+        server_id: query.server.as_deref(),
+        status: status.as_deref(),
+        before: query.before,
+    };
+    let invocations = crate::db::list_tool_invocations(
+        &state.pool,
+        &filter,
+        query.limit.unwrap_or(100).clamp(1, 1000),
+    )
+    .await?;
+    Ok(Json(serde_json::json!({ "invocations": invocations })))
+}
+
 /// Get stored agent memories via KS22 MCP server.
 ///
 /// **Route:** `GET /api/memories`
@@ -447,4 +977,44 @@ mod tests {
         let result = check_auth(&state, &headers);
         assert!(result.is_err(), "API key should be case-sensitive");
     }
+
+    #[tokio::test]
+    async fn test_check_scope_hierarchy() {
+        let state = create_test_app_state(Some("test-secret-key".to_string())).await;
+        state.api_keys.write().unwrap().insert(
+            crate::db::hash_scoped_api_key("chat-key"),
+            ApiKeyScope::Chat,
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert("X-API-Key", HeaderValue::from_static("chat-key"));
+
+        assert!(check_scope(&state, &headers, ApiKeyScope::ReadOnly).is_ok());
+        assert!(check_scope(&state, &headers, ApiKeyScope::Chat).is_ok());
+        assert!(check_auth(&state, &headers).is_err());
+
+        headers.insert("X-API-Key", HeaderValue::from_static("test-secret-key"));
+        assert!(check_scope(&state, &headers, ApiKeyScope::Chat).is_ok());
+        assert!(check_auth(&state, &headers).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_metrics_include_pool() {
+        use axum::response::IntoResponse;
+
+        let state = create_test_app_state(None).await;
+        state
+            .metrics
+            .db
+            .record_query("SELECT \"x\"", Duration::from_millis(3));
+
+        let response = get_prometheus_metrics(State(state)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE cloto_requests_total counter"));
+        assert!(text.contains("cloto_db_pool_connections{state=\"in_use\"}"));
+        assert!(text.contains("cloto_db_acquire_seconds_count"));
+        assert!(text.contains("cloto_db_query_seconds_count{query=\"SELECT \\\"x\\\"\"}"));
+    }
 }
@@ -9,7 +9,7 @@ use tracing::error;
 
 use crate::{AppError, AppResult, AppState};
 
-use super::{check_auth, spawn_admin_audit};
+use super::{check_auth, check_scope, spawn_admin_audit, ApiKeyScope};
 
 #[derive(Deserialize)]
 pub struct CreateAgentRequest {
@@ -19,6 +19,7 @@ pub struct CreateAgentRequest {
     pub metadata: Option<HashMap<String, String>>,
     pub required_capabilities: Option<Vec<cloto_shared::CapabilityType>>,
     pub password: Option<String>,
+    pub system_prompt: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -31,6 +32,32 @@ pub struct PowerToggleRequest {
 pub struct UpdateAgentRequest {
     pub default_engine_id: Option<String>,
     pub metadata: HashMap<String, String>,
+    /// Omitted leaves the prompt unchanged; an empty string clears it.
+    pub system_prompt: Option<String>,
+}
+
+/// Longest accepted `system_prompt` template, in bytes.
+const MAX_SYSTEM_PROMPT_LEN: usize = 20_000;
+
+/// Reject a `system_prompt` template that is too long or uses unknown variables.
+fn validate_system_prompt(template: &str) -> AppResult<()> {
+    if template.len() > MAX_SYSTEM_PROMPT_LEN {
+        return Err(AppError::Validation(format!(
+            "System prompt must be at most {MAX_SYSTEM_PROMPT_LEN} characters (got {})",
+            template.len()
+        )));
+    }
+    cloto_shared::llm::validate_system_prompt(template).map_err(AppError::Validation)
+}
+
+/// Reject `heartbeat_*` metadata the heartbeat task could not use.
+fn validate_heartbeat_metadata(metadata: &HashMap<String, String>) -> AppResult<()> {
+    crate::managers::heartbeat::HeartbeatSettings::from_metadata(
+        metadata,
+        std::time::Duration::ZERO,
+    )
+    .map(|_| ())
+    .map_err(|e| AppError::Validation(e.to_string()))
 }
 
 /// List all registered agents.
@@ -67,7 +94,8 @@ pub async fn get_agents(State(state): State<Arc<AppState>>) -> AppResult<Json<se
 ///   "description": "A helpful assistant",
 ///   "default_engine": "engine-id",
 ///   "metadata": { "key": "value" },
-///   "required_capabilities": ["Reasoning", "Memory"]
+///   "required_capabilities": ["Reasoning", "Memory"],
+///   "system_prompt": "You are {agent_name}. Today is {date}."
 /// }
 /// ```
 ///
@@ -76,11 +104,16 @@ pub async fn get_agents(State(state): State<Arc<AppState>>) -> AppResult<Json<se
 /// - **description**: Required, 1-1000 characters (UTF-8 byte length)
 /// - **default_engine**: Required, must reference a valid engine ID
 /// - **metadata**: Optional key-value pairs
-/// - **required_capabilities**: Optional, defaults to `[Reasoning, Memory]`
+/// - **required_capabilities**: Optional, defaults to `[Reasoning, Memory]`.
+///   Each one must be provided by an active plugin or connected MCP server;
+///   the providers are bound to the new agent.
+/// - **system_prompt**: Optional template replacing the built-in system
+///   prompt, at most 20000 bytes. It may use `{agent_name}`, `{agent_id}`,
+///   `{description}`, `{date}` and `{memories}`; other variables are rejected.
 ///
 /// # Response
-/// - **200 OK:** `{ "status": "success", "id": "<generated-agent-id>" }`
-/// - **400 Bad Request:** Validation error (name/description length)
+/// - **200 OK:** `{ "status": "success", "id": "<generated-agent-id>", "bindings": [...] }`
+/// - **400 Bad Request:** Validation error (name/description length, missing capabilities)
 /// - **403 Forbidden:** Invalid or missing API key
 ///
 /// # Errors
@@ -128,6 +161,32 @@ pub async fn create_agent(
         }
     }
 
+    validate_heartbeat_metadata(&metadata)?;
+    let system_prompt = payload.system_prompt.filter(|p| !p.is_empty());
+    if let Some(ref template) = system_prompt {
+        validate_system_prompt(template)?;
+    }
+
+    let required_capabilities = payload.required_capabilities.unwrap_or_else(|| {
+        vec![
+            cloto_shared::CapabilityType::Reasoning,
+            cloto_shared::CapabilityType::Memory,
+        ]
+    });
+    let resolution = state
+        .agent_manager
+        .resolve_capabilities(
+            None,
+            &required_capabilities,
+            &payload.default_engine,
+            &metadata,
+            &state.registry,
+        )
+        .await?;
+    if let Some(message) = resolution.missing_error() {
+        return Err(AppError::Validation(message));
+    }
+
     let agent_id = state
         .agent_manager
         .create_agent(
@@ -135,18 +194,25 @@ pub async fn create_agent(
             &payload.description,
             &payload.default_engine,
             metadata,
-            payload.required_capabilities.unwrap_or_else(|| {
-                vec![
-                    cloto_shared::CapabilityType::Reasoning,
-                    cloto_shared::CapabilityType::Memory,
-                ]
-            }),
+            required_capabilities,
             payload.password.as_deref(),
         )
         .await?;
-    Ok(Json(
-        serde_json::json!({ "status": "success", "id": agent_id }),
-    ))
+    state
+        .agent_manager
+        .bind_capabilities(&agent_id, &resolution.bindings)
+        .await?;
+    if let Some(ref template) = system_prompt {
+        state
+            .agent_manager
+            .set_system_prompt(&agent_id, Some(template))
+            .await?;
+    }
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "id": agent_id,
+        "bindings": resolution.bindings,
+    })))
 }
 
 /// Update an existing agent's settings.
@@ -163,12 +229,17 @@ pub async fn create_agent(
 /// ```json
 /// {
 ///   "default_engine_id": "new-engine-id",
-///   "metadata": { "key": "updated-value" }
+///   "metadata": { "key": "updated-value" },
+///   "system_prompt": "You are {agent_name}. Relevant memories:\n{memories}"
 /// }
 /// ```
 ///
+/// `system_prompt` is validated as for `POST /api/agents`; leaving it out
+/// keeps the current prompt and `""` restores the built-in one.
+///
 /// # Response
 /// - **200 OK:** `{ "status": "success" }`
+/// - **400 Bad Request:** Invalid heartbeat metadata or system prompt
 /// - **403 Forbidden:** Invalid or missing API key
 /// - **404 Not Found:** Agent ID does not exist
 pub async fn update_agent(
@@ -178,10 +249,20 @@ pub async fn update_agent(
     Json(payload): Json<UpdateAgentRequest>,
 ) -> AppResult<Json<serde_json::Value>> {
     check_auth(&state, &headers)?;
+    validate_heartbeat_metadata(&payload.metadata)?;
+    if let Some(ref template) = payload.system_prompt {
+        validate_system_prompt(template)?;
+    }
     state
         .agent_manager
         .update_agent_config(&id, payload.default_engine_id, payload.metadata)
         .await?;
+    if let Some(template) = payload.system_prompt {
+        state
+            .agent_manager
+            .set_system_prompt(&id, Some(template.as_str()).filter(|t| !t.is_empty()))
+            .await?;
+    }
     Ok(Json(serde_json::json!({ "status": "success" })))
 }
 
@@ -246,6 +327,8 @@ pub async fn delete_agent(
 /// **Route:** `POST /api/agents/:id/power`
 ///
 /// If the agent has a power password set, the `password` field is required.
+/// Powering on re-resolves the agent's required capabilities and fails with
+/// 400 listing any that no running plugin or MCP server provides.
 pub async fn power_toggle(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -273,6 +356,27 @@ pub async fn power_toggle(
         }
     }
 
+    if payload.enabled {
+        let (agent, engine_id) = state.agent_manager.get_agent_config(&id).await?;
+        let resolution = state
+            .agent_manager
+            .resolve_capabilities(
+                Some(&id),
+                &agent.required_capabilities,
+                &engine_id,
+                &agent.metadata,
+                &state.registry,
+            )
+            .await?;
+        if let Some(message) = resolution.missing_error() {
+            return Err(AppError::Validation(message));
+        }
+        state
+            .agent_manager
+            .bind_capabilities(&id, &resolution.bindings)
+            .await?;
+    }
+
     state
         .agent_manager
         .set_enabled(&id, payload.enabled)
@@ -310,3 +414,148 @@ pub async fn power_toggle(
         "enabled": payload.enabled
     })))
 }
+
+#[derive(Deserialize)]
+pub struct PutAgentPluginsRequest {
+    pub plugins: Vec<crate::db::AgentPluginBinding>,
+}
+
+/// Upper bounds on a plugin binding update.
+const MAX_AGENT_PLUGINS: usize = 64;
+const MAX_TOOL_RULES_PER_PLUGIN: usize = 256;
+
+fn validate_plugin_bindings(bindings: &mut [crate::db::AgentPluginBinding]) -> AppResult<()> {
+    if bindings.len() > MAX_AGENT_PLUGINS {
+        return Err(AppError::Validation(format!(
+            "At most {} plugins can be bound to an agent (got {})",
+            MAX_AGENT_PLUGINS,
+            bindings.len()
+        )));
+    }
+    let mut seen_plugins = std::collections::HashSet::new();
+    for binding in bindings.iter_mut() {
+        if binding.plugin_id.is_empty() || binding.plugin_id.len() > 200 {
+            return Err(AppError::Validation(
+                "plugin_id must be 1-200 characters".to_string(),
+            ));
+        }
+        if !seen_plugins.insert(binding.plugin_id.clone()) {
+            return Err(AppError::Validation(format!(
+                "Plugin '{}' is listed more than once",
+                binding.plugin_id
+            )));
+        }
+        if binding.tools.len() > MAX_TOOL_RULES_PER_PLUGIN {
+            return Err(AppError::Validation(format!(
+                "At most {} tool rules per plugin (got {} for '{}')",
+                MAX_TOOL_RULES_PER_PLUGIN,
+                binding.tools.len(),
+                binding.plugin_id
+            )));
+        }
+        let mut seen_tools = std::collections::HashSet::new();
+        for rule in &mut binding.tools {
+            if rule.tool_name.is_empty() || rule.tool_name.len() > 200 {
+                return Err(AppError::Validation(
+                    "tool_name must be 1-200 characters".to_string(),
+                ));
+            }
+            if !["allow", "deny"].contains(&rule.permission.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "Invalid permission '{}' for tool '{}'; expected 'allow' or 'deny'",
+                    rule.permission, rule.tool_name
+                )));
+            }
+            if !seen_tools.insert(rule.tool_name.clone()) {
+                return Err(AppError::Validation(format!(
+                    "Tool '{}' has more than one rule for plugin '{}'",
+                    rule.tool_name, binding.plugin_id
+                )));
+            }
+            rule.plugin_id.clone_from(&binding.plugin_id);
+        }
+    }
+    Ok(())
+}
+
+/// List the plugins bound to an agent with their per-tool rules.
+///
+/// **Route:** `GET /api/agents/:id/plugins`
+///
+/// # Response
+/// - **200 OK:** `{ "agent_id": "...", "plugins": [{ "plugin_id", "pos_x", "pos_y", "tools": [{ "tool_name", "permission" }] }] }`
+/// - **404 Not Found:** Agent ID does not exist
+pub async fn get_agent_plugins(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    state
+        .agent_manager
+        .get_agent_config(&id)
+        .await
+        .map_err(|_| AppError::NotFound(format!("Agent '{}' not found", id)))?;
+
+    let plugins = crate::db::get_agent_plugin_bindings(&state.pool, &id).await?;
+    Ok(Json(serde_json::json!({
+        "agent_id": id,
+        "plugins": plugins,
+    })))
+}
+
+/// Replace the plugins bound to an agent and their per-tool rules.
+///
+/// **Route:** `PUT /api/agents/:id/plugins`
+///
+/// A `deny` rule blocks a native plugin tool for this agent. A plugin with
+/// any `allow` rule only exposes the allowed tools. Rules are enforced by
+/// the agentic loop before each tool call; MCP servers keep using the MCP
+/// access control entries.
+///
+/// # Request Body
+/// ```json
+/// { "plugins": [{ "plugin_id": "tool.terminal", "tools": [{ "tool_name": "execute_command", "permission": "deny" }] }] }
+/// ```
+///
+/// # Response
+/// - **200 OK:** `{ "status": "success", "count": <plugins> }`
+/// - **400 Bad Request:** Invalid binding or rule
+/// - **404 Not Found:** Agent ID does not exist
+pub async fn put_agent_plugins(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(mut payload): Json<PutAgentPluginsRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    state
+        .agent_manager
+        .get_agent_config(&id)
+        .await
+        .map_err(|_| AppError::NotFound(format!("Agent '{}' not found", id)))?;
+    validate_plugin_bindings(&mut payload.plugins)?;
+
+    crate::db::put_agent_plugin_bindings(&state.pool, &id, &payload.plugins).await?;
+
+    let rule_count: usize = payload.plugins.iter().map(|b| b.tools.len()).sum();
+    spawn_admin_audit(
+        state.pool.clone(),
+        "AGENT_PLUGINS_UPDATED",
+        id.clone(),
+        format!(
+            "Agent {} bound to {} plugins with {} tool rules",
+            id,
+            payload.plugins.len(),
+            rule_count
+        ),
+        None,
+        None,
+        None,
+    );
+
+    Ok(Json(serde_json::json!({
+        "status": "success",
+        "count": payload.plugins.len(),
+    })))
+}
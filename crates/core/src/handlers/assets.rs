@@ -9,6 +9,12 @@ use rust_embed::RustEmbed;
 #[folder = "../../dashboard/dist/"]
 struct Asset;
 
+/// Whether the dashboard build was embedded into this binary.
+#[must_use]
+pub fn has_dashboard() -> bool {
+    Asset::get("index.html").is_some()
+}
+
 pub async fn static_handler(uri: Uri) -> impl IntoResponse {
     let path = uri.path().trim_start_matches('/');
 
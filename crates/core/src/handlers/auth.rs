@@ -0,0 +1,457 @@
+//! Dashboard user accounts and sign-in (`/api/auth`).
+//!
+//! `POST /api/auth/login` checks a username and password against the `users`
+//! table and sets a `cloto_session` cookie: the user ID and expiry, signed
+//! with HMAC-SHA256 under a key generated at startup (so sessions end when
+//! the kernel restarts). `POST /api/auth/logout` revokes the token until it
+//! would have expired. `check_scope` maps a valid session to the user's
+//! role, which takes the same values as API key scopes. Requests carrying an
+//! API key are authorized by the key alone.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::{check_auth, spawn_admin_audit, ApiKeyScope};
+use crate::managers::AgentManager;
+use crate::{AppError, AppResult, AppState};
+
+pub const SESSION_COOKIE: &str = "cloto_session";
+const SESSION_TTL_SECS: i64 = 12 * 60 * 60;
+const MIN_PASSWORD_LEN: usize = 8;
+const MAX_PASSWORD_LEN: usize = 1024;
+
+/// A user as cached in `AppState::users` for session checks.
+#[derive(Debug, Clone)]
+pub struct DashboardUser {
+    pub username: String,
+    pub role: ApiKeyScope,
+}
+
+/// Key signing session cookies, and the tokens revoked by sign-out.
+pub struct SessionKey {
+    key: ring::hmac::Key,
+    /// Revoked token signatures and their expiry; dropped once expired.
+    revoked: Mutex<HashMap<String, i64>>,
+}
+
+impl SessionKey {
+    #[must_use]
+    pub fn generate() -> Self {
+        use rand::RngCore;
+        let mut secret = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut secret);
+        Self {
+            key: ring::hmac::Key::new(ring::hmac::HMAC_SHA256, &secret),
+            revoked: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `<user_id>.<expires_at>.<signature>`
+    #[must_use]
+    pub fn sign(&self, user_id: &str, expires_at: i64) -> String {
+        let payload = format!("{}.{}", user_id, expires_at);
+        let tag = ring::hmac::sign(&self.key, payload.as_bytes());
+        format!("{}.{}", payload, cloto_shared::hex_encode(tag.as_ref()))
+    }
+
+    /// The user ID of an untampered, unexpired, unrevoked token.
+    #[must_use]
+    pub fn verify(&self, token: &str, now: i64) -> Option<String> {
+        let (user_id, expires_at, signature) = self.check(token)?;
+        if expires_at <= now {
+            return None;
+        }
+        // A poisoned lock fails closed
+        let revoked = self
+            .revoked
+            .lock()
+            .map_or(true, |revoked| revoked.contains_key(signature));
+        (!revoked).then(|| user_id.to_string())
+    }
+
+    /// Reject a still-valid token from now on.
+    pub fn revoke(&self, token: &str, now: i64) {
+        let Some((_, expires_at, signature)) = self.check(token) else {
+            return;
+        };
+        if let Ok(mut revoked) = self.revoked.lock() {
+            revoked.retain(|_, expiry| *expiry > now);
+            if expires_at > now {
+                revoked.insert(signature.to_string(), expires_at);
+            }
+        }
+    }
+
+    /// Split a correctly signed token into user ID, expiry and signature.
+    fn check<'a>(&self, token: &'a str) -> Option<(&'a str, i64, &'a str)> {
+        let (payload, signature) = token.rsplit_once('.')?;
+        let (user_id, expires_at) = payload.rsplit_once('.')?;
+        if !signature.len().is_multiple_of(2) {
+            return None;
+        }
+        let tag = (0..signature.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(signature.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        ring::hmac::verify(&self.key, payload.as_bytes(), &tag).ok()?;
+        Some((user_id, expires_at.parse().ok()?, signature))
+    }
+}
+
+fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == SESSION_COOKIE).then_some(value)
+        })
+}
+
+/// The ID and cached record of the user signed in with this request.
+fn session_user(state: &AppState, headers: &HeaderMap) -> Option<(String, DashboardUser)> {
+    let user_id = state
+        .session_key
+        .verify(session_token(headers)?, chrono::Utc::now().timestamp())?;
+    let user = state.users.read().ok()?.get(&user_id).cloned()?;
+    Some((user_id, user))
+}
+
+/// Role of the user signed in with this request, if the session is valid
+/// and the user still exists.
+pub(crate) fn session_role(state: &AppState, headers: &HeaderMap) -> Option<ApiKeyScope> {
+    session_user(state, headers).map(|(_, user)| user.role)
+}
+
+/// Whether the client reached us over HTTPS, as reported by a TLS-terminating
+/// proxy (the kernel itself only serves plain HTTP).
+fn is_https(headers: &HeaderMap) -> bool {
+    let forwarded_proto = headers
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"));
+    let forwarded = headers
+        .get_all(header::FORWARDED)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split([',', ';']))
+        .any(|pair| pair.trim().eq_ignore_ascii_case("proto=https"));
+    forwarded_proto || forwarded
+}
+
+fn session_cookie(value: &str, max_age: i64, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/api; HttpOnly; SameSite=Strict; Max-Age={}{}",
+        SESSION_COOKIE,
+        value,
+        max_age,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// POST /api/auth/login — check credentials and set the session cookie
+pub async fn login(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<LoginRequest>,
+) -> AppResult<impl IntoResponse> {
+    let user = crate::db::get_user_by_username(&state.pool, &payload.username)
+        .await
+        .map_err(AppError::Internal)?;
+    // Unknown users cost a hash too, so response times don't reveal them
+    let hash = user.as_ref().map(|u| u.password_hash.clone());
+    let password = payload.password;
+    let verified = tokio::task::spawn_blocking(move || {
+        let Some(hash) = hash else {
+            let _ = AgentManager::hash_password(&password);
+            return false;
+        };
+        AgentManager::verify_password(&password, &hash).unwrap_or(false)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.into()))?;
+
+    let user = match user {
+        Some(user) if verified => user,
+        _ => {
+            tracing::warn!(username = %payload.username, "🚫 Dashboard sign-in failed");
+            crate::db::spawn_audit_log(
+                state.pool.clone(),
+                crate::db::AuditLogEntry {
+                    timestamp: chrono::Utc::now(),
+                    event_type: "USER_LOGIN".to_string(),
+                    actor_id: Some(payload.username.clone()),
+                    target_id: None,
+                    permission: None,
+                    result: "DENIED".to_string(),
+                    reason: "Invalid username or password".to_string(),
+                    metadata: None,
+                    trace_id: None,
+                },
+            );
+            return Err(AppError::Cloto(cloto_shared::ClotoError::PermissionDenied(
+                cloto_shared::Permission::AdminAccess,
+            )));
+        }
+    };
+
+    let expires_at = chrono::Utc::now().timestamp() + SESSION_TTL_SECS;
+    let token = state.session_key.sign(&user.id, expires_at);
+    crate::db::spawn_audit_log(
+        state.pool.clone(),
+        crate::db::AuditLogEntry {
+            timestamp: chrono::Utc::now(),
+            event_type: "USER_LOGIN".to_string(),
+            actor_id: Some(user.username.clone()),
+            target_id: Some(user.id.clone()),
+            permission: Some(user.role.clone()),
+            result: "SUCCESS".to_string(),
+            reason: "Dashboard sign-in".to_string(),
+            metadata: None,
+            trace_id: None,
+        },
+    );
+
+    Ok((
+        [(
+            header::SET_COOKIE,
+            session_cookie(&token, SESSION_TTL_SECS, is_https(&headers)),
+        )],
+        Json(serde_json::json!({
+            "id": user.id,
+            "username": user.username,
+            "role": user.role,
+            "expires_at": expires_at,
+        })),
+    ))
+}
+
+/// POST /api/auth/logout — revoke the session and clear its cookie
+pub async fn logout(State(state): State<Arc<AppState>>, headers: HeaderMap) -> impl IntoResponse {
+    if let Some(token) = session_token(&headers) {
+        state
+            .session_key
+            .revoke(token, chrono::Utc::now().timestamp());
+    }
+    (
+        [(
+            header::SET_COOKIE,
+            session_cookie("", 0, is_https(&headers)),
+        )],
+        Json(serde_json::json!({ "status": "signed_out" })),
+    )
+}
+
+/// GET /api/auth/session — the signed-in user
+pub async fn get_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<serde_json::Value>> {
+    let (id, user) = session_user(&state, &headers).ok_or(AppError::Cloto(
+        cloto_shared::ClotoError::PermissionDenied(cloto_shared::Permission::AdminAccess),
+    ))?;
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "username": user.username,
+        "role": user.role,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub role: String,
+}
+
+/// GET /api/auth/users — dashboard accounts (password hashes are never returned)
+pub async fn list_users(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let users = crate::db::list_users(&state.pool)
+        .await
+        .map_err(AppError::Internal)?;
+    Ok(Json(serde_json::json!({ "users": users })))
+}
+
+/// POST /api/auth/users — create a dashboard account
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateUserRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let username = payload.username.trim();
+    if username.is_empty()
+        || username.len() > 64
+        || !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'))
+    {
+        return Err(AppError::Validation(
+            "username must be 1-64 characters of letters, digits, '.', '_', '-' or '@'".into(),
+        ));
+    }
+    if !(MIN_PASSWORD_LEN..=MAX_PASSWORD_LEN).contains(&payload.password.len()) {
+        return Err(AppError::Validation(format!(
+            "password must be {}-{} bytes",
+            MIN_PASSWORD_LEN, MAX_PASSWORD_LEN
+        )));
+    }
+    let role: ApiKeyScope = payload.role.parse().map_err(AppError::Validation)?;
+    if crate::db::get_user_by_username(&state.pool, username)
+        .await
+        .map_err(AppError::Internal)?
+        .is_some()
+    {
+        return Err(AppError::Validation(format!(
+            "User '{}' already exists",
+            username
+        )));
+    }
+
+    let password = payload.password;
+    let password_hash = tokio::task::spawn_blocking(move || AgentManager::hash_password(&password))
+        .await
+        .map_err(|e| AppError::Internal(e.into()))?
+        .map_err(AppError::Internal)?;
+    let row = crate::db::UserRow {
+        id: format!("user.{}", cloto_shared::ClotoId::new()),
+        username: username.to_string(),
+        password_hash,
+        role: role.as_str().to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    crate::db::create_user(&state.pool, &row)
+        .await
+        .map_err(AppError::Internal)?;
+    if let Ok(mut users) = state.users.write() {
+        users.insert(
+            row.id.clone(),
+            DashboardUser {
+                username: row.username.clone(),
+                role,
+            },
+        );
+    }
+
+    spawn_admin_audit(
+        state.pool.clone(),
+        "USER_CREATED",
+        row.id.clone(),
+        format!("Dashboard user '{}' created", row.username),
+        Some(row.role.clone()),
+        None,
+        None,
+    );
+
+    Ok(Json(
+        serde_json::to_value(&row).map_err(|e| AppError::Internal(e.into()))?,
+    ))
+}
+
+/// DELETE /api/auth/users/:id — delete an account, ending its sessions
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    if !crate::db::delete_user(&state.pool, &id)
+        .await
+        .map_err(AppError::Internal)?
+    {
+        return Err(AppError::NotFound(format!("User '{}' not found", id)));
+    }
+    if let Ok(mut users) = state.users.write() {
+        users.remove(&id);
+    }
+
+    spawn_admin_audit(
+        state.pool.clone(),
+        "USER_DELETED",
+        id.clone(),
+        "Dashboard user deleted".to_string(),
+        None,
+        None,
+        None,
+    );
+
+    Ok(Json(serde_json::json!({ "status": "deleted", "id": id })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_token_round_trip() {
+        let key = SessionKey::generate();
+        let token = key.sign("user.abc", 2_000);
+        assert_eq!(key.verify(&token, 1_000).as_deref(), Some("user.abc"));
+        // Expired
+        assert_eq!(key.verify(&token, 2_000), None);
+        // Signed by another kernel
+        assert_eq!(SessionKey::generate().verify(&token, 1_000), None);
+        // Extended expiry
+        let forged = token.replacen(".2000.", ".9999.", 1);
+        assert_eq!(key.verify(&forged, 1_000), None);
+    }
+
+    #[test]
+    fn test_revoked_session_token() {
+        let key = SessionKey::generate();
+        let token = key.sign("user.abc", 2_000);
+        let other = key.sign("user.abc", 3_000);
+        key.revoke(&token, 1_000);
+        assert_eq!(key.verify(&token, 1_000), None);
+        assert_eq!(key.verify(&other, 1_000).as_deref(), Some("user.abc"));
+        // Expired revocations are dropped on the next revoke
+        key.revoke(&other, 2_500);
+        assert_eq!(key.revoked.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_secure_cookie_behind_tls_proxy() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_https(&headers));
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        assert!(is_https(&headers));
+        assert!(session_cookie("t", 60, true).ends_with("; Secure"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::FORWARDED,
+            "for=1.2.3.4;proto=https".parse().unwrap(),
+        );
+        assert!(is_https(&headers));
+    }
+
+    #[test]
+    fn test_session_token_from_cookie_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            "theme=dark; cloto_session=user.a.1.ff".parse().unwrap(),
+        );
+        assert_eq!(session_token(&headers), Some("user.a.1.ff"));
+    }
+}
@@ -10,44 +10,95 @@ use std::sync::Arc;
 use tracing::error;
 
 use crate::db::{self, AttachmentRow, ChatMessageRow};
+use crate::managers::attachments;
 use crate::{AppError, AppResult, AppState};
 
 #[derive(Deserialize)]
 pub struct GetMessagesQuery {
     pub user_id: Option<String>,
+    /// Defaults to the thread's active session.
+    pub session_id: Option<String>,
     pub before: Option<i64>,
     pub limit: Option<i64>,
+    /// Attach the tool history of the agentic run each message started.
+    #[serde(default)]
+    pub include_tools: bool,
+}
+
+/// Resolve an explicitly requested session, checking it belongs to the agent.
+async fn session_for_agent(
+    state: &AppState,
+    agent_id: &str,
+    session_id: &str,
+) -> AppResult<db::ChatSessionRow> {
+    db::get_chat_session(&state.pool, session_id)
+        .await?
+        .filter(|s| s.agent_id == agent_id)
+        .ok_or_else(|| AppError::NotFound(format!("Chat session '{}' not found", session_id)))
 }
 
 /// GET /api/chat/:agent_id/messages
-/// Returns paginated chat messages (newest first)
+/// Returns paginated chat messages of a session (newest first).
+/// With `include_tools=true`, a message that started an agentic run carries
+/// the run's assistant / tool messages as `tool_history`.
 pub async fn get_messages(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Path(agent_id): Path<String>,
     Query(params): Query<GetMessagesQuery>,
 ) -> AppResult<Json<serde_json::Value>> {
-    super::check_auth(&state, &headers)?;
+    super::check_scope(&state, &headers, super::ApiKeyScope::ReadOnly)?;
 
     let user_id = params.user_id.as_deref().unwrap_or("default");
     let limit = params.limit.unwrap_or(50).min(200);
 
-    let messages = db::get_chat_messages(
-        &state.pool,
-        &agent_id,
-        user_id,
-        params.before,
-        limit + 1, // fetch one extra to determine has_more
-    )
-    .await?;
+    let session_id = match params.session_id {
+        Some(ref id) => Some(session_for_agent(&state, &agent_id, id).await?.id),
+        None => db::get_active_chat_session(&state.pool, &agent_id, user_id)
+            .await?
+            .map(|s| s.id),
+    };
+    // fetch one extra to determine has_more
+    let messages = match session_id {
+        Some(ref session_id) => {
+            db::get_chat_session_messages(&state.pool, session_id, params.before, limit + 1).await?
+        }
+        None => {
+            db::get_chat_messages(&state.pool, &agent_id, user_id, params.before, limit + 1).await?
+        }
+    };
 
     #[allow(clippy::cast_possible_wrap)]
     let has_more = messages.len() as i64 > limit;
     let messages: Vec<ChatMessageRow> = messages.into_iter().take(limit as usize).collect();
 
+    let messages: Vec<serde_json::Value> = if params.include_tools {
+        let ids: Vec<String> = messages.iter().map(|m| m.id.clone()).collect();
+        let mut histories: std::collections::HashMap<String, serde_json::Value> =
+            db::get_chat_tool_history(&state.pool, &agent_id, &ids)
+                .await?
+                .into_iter()
+                .filter_map(|row| Some((row.message_id, serde_json::from_str(&row.history).ok()?)))
+                .collect();
+        messages
+            .into_iter()
+            .map(|m| {
+                let history = histories.remove(&m.id);
+                let mut value = serde_json::json!(m);
+                if let Some(history) = history {
+                    value["tool_history"] = history;
+                }
+                value
+            })
+            .collect()
+    } else {
+        messages.into_iter().map(|m| serde_json::json!(m)).collect()
+    };
+
     Ok(Json(serde_json::json!({
         "messages": messages,
         "has_more": has_more,
+        "session_id": session_id,
     })))
 }
 
@@ -57,6 +108,8 @@ pub struct PostMessageRequest {
     pub source: String,
     pub content: serde_json::Value, // ContentBlock[] as opaque JSON
     pub metadata: Option<serde_json::Value>,
+    /// Defaults to the thread's active session.
+    pub session_id: Option<String>,
 }
 
 /// POST /api/chat/:agent_id/messages
@@ -68,7 +121,7 @@ pub async fn post_message(
     Path(agent_id): Path<String>,
     Json(payload): Json<PostMessageRequest>,
 ) -> AppResult<Json<serde_json::Value>> {
-    super::check_auth(&state, &headers)?;
+    super::check_scope(&state, &headers, super::ApiKeyScope::Chat)?;
 
     // Block messages to disabled agents
     let (agent, _) = state
@@ -116,6 +169,10 @@ pub async fn post_message(
         )));
     }
 
+    if let Some(ref session_id) = payload.session_id {
+        session_for_agent(&state, &agent_id, session_id).await?;
+    }
+
     let now = chrono::Utc::now().timestamp_millis();
     let content_str = serde_json::to_string(&payload.content)
         .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize content: {}", e)))?;
@@ -129,84 +186,22 @@ pub async fn post_message(
         content: content_str,
         metadata: metadata_str,
         created_at: now,
+        session_id: payload.session_id,
     };
 
     db::save_chat_message(&state.pool, &msg).await?;
 
+    // A finished exchange may push the thread past the summary threshold
+    if msg.source == "agent" {
+        state
+            .summarizer
+            .spawn_maybe_summarize(&msg.agent_id, &msg.user_id);
+    }
+
     // Process inline attachments from content blocks
     if let Some(blocks) = payload.content.as_array() {
         for block in blocks {
-            if block.get("type").and_then(|t| t.as_str()) == Some("image") {
-                if let Some(url) = block.get("url").and_then(|u| u.as_str()) {
-                    // Handle base64 data URIs as inline attachments
-                    if let Some(data_part) = url.strip_prefix("data:") {
-                        if let Some((mime_info, base64_data)) = data_part.split_once(',') {
-                            let mime_type = mime_info.trim_end_matches(";base64").to_string();
-                            // M-2: Only allow known-safe MIME types
-                            const ALLOWED_MIME_TYPES: &[&str] = &[
-                                "image/png",
-                                "image/jpeg",
-                                "image/jpg",
-                                "image/gif",
-                                "image/webp",
-                                "image/svg+xml",
-                            ];
-                            if !ALLOWED_MIME_TYPES.contains(&mime_type.as_str()) {
-                                tracing::warn!(
-                                    "Rejected attachment with disallowed MIME type: {}",
-                                    mime_type
-                                );
-                                continue;
-                            }
-                            let Ok(decoded) = base64_decode(base64_data) else {
-                                tracing::warn!("Invalid base64 data in attachment, skipping");
-                                continue;
-                            };
-                            {
-                                let att_id = uuid::Uuid::new_v4().to_string();
-                                #[allow(clippy::cast_possible_wrap)]
-                                let size = decoded.len() as i64;
-                                let filename =
-                                    format!("image_{}.{}", &att_id[..8], mime_to_ext(&mime_type));
-
-                                let (storage_type, inline_data, disk_path) = if size <= 65536 {
-                                    // <=64KB: store inline
-                                    ("inline".to_string(), Some(decoded), None)
-                                } else {
-                                    // >64KB: store on disk
-                                    let dir = format!("data/attachments/{}", &msg.id);
-                                    let path = format!("{}/{}", dir, filename);
-                                    if let Err(e) = tokio::fs::create_dir_all(&dir).await {
-                                        error!("Failed to create attachment dir: {}", e);
-                                        continue;
-                                    }
-                                    if let Err(e) = tokio::fs::write(&path, &decoded).await {
-                                        error!("Failed to write attachment file: {}", e);
-                                        continue;
-                                    }
-                                    ("disk".to_string(), None, Some(path))
-                                };
-
-                                let att = AttachmentRow {
-                                    id: att_id,
-                                    message_id: msg.id.clone(),
-                                    filename,
-                                    mime_type,
-                                    size_bytes: size,
-                                    storage_type,
-                                    inline_data,
-                                    disk_path,
-                                    created_at: now,
-                                };
-
-                                if let Err(e) = db::save_attachment(&state.pool, &att).await {
-                                    error!("Failed to save attachment: {}", e);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            store_attachment(&state, &msg.id, block, now).await;
         }
     }
 
@@ -229,7 +224,7 @@ pub async fn delete_messages(
     Path(agent_id): Path<String>,
     Query(params): Query<DeleteMessagesQuery>,
 ) -> AppResult<Json<serde_json::Value>> {
-    super::check_auth(&state, &headers)?;
+    super::check_scope(&state, &headers, super::ApiKeyScope::Chat)?;
 
     let user_id = params.user_id.as_deref().unwrap_or("default");
     let deleted_count = db::delete_chat_messages(&state.pool, &agent_id, user_id).await?;
@@ -239,6 +234,94 @@ pub async fn delete_messages(
     })))
 }
 
+#[derive(Deserialize)]
+pub struct ListSessionsQuery {
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+/// GET /api/chat/:agent_id/sessions
+/// Conversation sessions, most recently used first
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+    Query(params): Query<ListSessionsQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    super::check_scope(&state, &headers, super::ApiKeyScope::ReadOnly)?;
+
+    let user_id = params.user_id.as_deref().unwrap_or("default");
+    let sessions =
+        db::list_chat_sessions(&state.pool, &agent_id, user_id, params.include_archived).await?;
+    Ok(Json(serde_json::json!({ "sessions": sessions })))
+}
+
+#[derive(Deserialize)]
+pub struct CreateSessionRequest {
+    pub name: Option<String>,
+    pub user_id: Option<String>,
+}
+
+/// POST /api/chat/:agent_id/sessions
+/// Start a new session and make it the active one
+pub async fn create_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+    Json(payload): Json<CreateSessionRequest>,
+) -> AppResult<Json<db::ChatSessionRow>> {
+    super::check_scope(&state, &headers, super::ApiKeyScope::Chat)?;
+
+    state
+        .agent_manager
+        .get_agent_config(&agent_id)
+        .await
+        .map_err(|_| AppError::NotFound(format!("Agent '{}' not found", agent_id)))?;
+    let name = payload.name.as_deref().map_or("", str::trim);
+    if name.len() > 100 {
+        return Err(AppError::Validation(
+            "name must be at most 100 characters".into(),
+        ));
+    }
+    let name = if name.is_empty() { "New session" } else { name };
+    let user_id = payload.user_id.as_deref().unwrap_or("default");
+    let session = db::create_chat_session(&state.pool, &agent_id, user_id, name).await?;
+    Ok(Json(session))
+}
+
+/// POST /api/chat/:agent_id/sessions/:session_id/activate
+/// Switch the thread to this session (restoring it if archived)
+pub async fn activate_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((agent_id, session_id)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    super::check_scope(&state, &headers, super::ApiKeyScope::Chat)?;
+
+    session_for_agent(&state, &agent_id, &session_id).await?;
+    db::activate_chat_session(&state.pool, &session_id).await?;
+    Ok(Json(
+        serde_json::json!({ "status": "active", "id": session_id }),
+    ))
+}
+
+/// POST /api/chat/:agent_id/sessions/:session_id/archive
+/// Archive a session; its messages are kept but no longer used as context
+pub async fn archive_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((agent_id, session_id)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    super::check_scope(&state, &headers, super::ApiKeyScope::Chat)?;
+
+    session_for_agent(&state, &agent_id, &session_id).await?;
+    db::archive_chat_session(&state.pool, &session_id).await?;
+    Ok(Json(
+        serde_json::json!({ "status": "archived", "id": session_id }),
+    ))
+}
+
 /// GET /api/chat/attachments/:attachment_id
 /// Serve an attachment file
 pub async fn get_attachment(
@@ -246,7 +329,7 @@ pub async fn get_attachment(
     headers: HeaderMap,
     Path(attachment_id): Path<String>,
 ) -> AppResult<impl IntoResponse> {
-    super::check_auth(&state, &headers)?;
+    super::check_scope(&state, &headers, super::ApiKeyScope::ReadOnly)?;
 
     let att = db::get_attachment_by_id(&state.pool, &attachment_id)
         .await?
@@ -306,7 +389,7 @@ pub async fn chat_handler(
     headers: HeaderMap,
     Json(msg): Json<cloto_shared::ClotoMessage>,
 ) -> AppResult<Json<serde_json::Value>> {
-    super::check_auth(&state, &headers)?;
+    super::check_scope(&state, &headers, super::ApiKeyScope::Chat)?;
     let envelope =
         crate::EnvelopedEvent::system(cloto_shared::ClotoEventData::MessageReceived(msg));
     if let Err(e) = state.event_tx.send(envelope).await {
@@ -320,6 +403,104 @@ pub async fn chat_handler(
 
 // --- Helpers ---
 
+/// Save an `image` or `file` block carrying a base64 data URI as an
+/// attachment of `message_id`, after running it through the processing
+/// pipeline (downscaling, metadata stripping, text extraction).
+async fn store_attachment(
+    state: &Arc<AppState>,
+    message_id: &str,
+    block: &serde_json::Value,
+    now: i64,
+) {
+    let kind = block.get("type").and_then(|t| t.as_str());
+    if !matches!(kind, Some("image" | "file")) {
+        return;
+    }
+    // Handle base64 data URIs as inline attachments
+    let Some(data_part) = block
+        .get("url")
+        .and_then(|u| u.as_str())
+        .and_then(|url| url.strip_prefix("data:"))
+    else {
+        return;
+    };
+    let Some((mime_info, base64_data)) = data_part.split_once(',') else {
+        return;
+    };
+    let mime_type = mime_info.trim_end_matches(";base64").to_string();
+    // M-2: Only allow known-safe MIME types
+    if !attachments::is_allowed_mime_type(&mime_type) {
+        tracing::warn!(
+            "Rejected attachment with disallowed MIME type: {}",
+            mime_type
+        );
+        return;
+    }
+    let Ok(decoded) = base64_decode(base64_data) else {
+        tracing::warn!("Invalid base64 data in attachment, skipping");
+        return;
+    };
+
+    let processor = state.attachments.clone();
+    let processed =
+        match tokio::task::spawn_blocking(move || processor.process(&mime_type, decoded)).await {
+            Ok(processed) => processed,
+            Err(e) => {
+                error!("Attachment processing panicked: {}", e);
+                return;
+            }
+        };
+
+    let att_id = uuid::Uuid::new_v4().to_string();
+    #[allow(clippy::cast_possible_wrap)]
+    let size = processed.data.len() as i64;
+    let ext = mime_to_ext(&processed.mime_type);
+    let filename = block
+        .get("filename")
+        .and_then(|f| f.as_str())
+        .and_then(attachments::sanitize_filename)
+        .unwrap_or_else(|| format!("{}_{}.{}", kind.unwrap_or("file"), &att_id[..8], ext));
+
+    let (storage_type, inline_data, disk_path) = if size <= 65536 {
+        // <=64KB: store inline
+        ("inline".to_string(), Some(processed.data.clone()), None)
+    } else {
+        // >64KB: store on disk under a generated name
+        let dir = format!("data/attachments/{}", message_id);
+        let path = format!("{}/{}.{}", dir, att_id, ext);
+        if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+            error!("Failed to create attachment dir: {}", e);
+            return;
+        }
+        if let Err(e) = tokio::fs::write(&path, &processed.data).await {
+            error!("Failed to write attachment file: {}", e);
+            return;
+        }
+        ("disk".to_string(), None, Some(path))
+    };
+
+    let att = AttachmentRow {
+        id: att_id,
+        message_id: message_id.to_string(),
+        filename,
+        mime_type: processed.mime_type,
+        size_bytes: size,
+        storage_type,
+        inline_data,
+        disk_path,
+        created_at: now,
+        derived_text: processed.derived_text,
+    };
+
+    if let Err(e) = db::save_attachment(&state.pool, &att).await {
+        error!("Failed to save attachment: {}", e);
+        return;
+    }
+    state
+        .attachments
+        .spawn_transcription(att.id, att.mime_type, processed.data);
+}
+
 fn base64_decode(input: &str) -> Result<Vec<u8>, ()> {
     use base64::Engine;
     base64::engine::general_purpose::STANDARD
@@ -334,6 +515,19 @@ fn mime_to_ext(mime: &str) -> &str {
         "image/gif" => "gif",
         "image/webp" => "webp",
         "image/svg+xml" => "svg",
+        "text/plain" => "txt",
+        "text/markdown" => "md",
+        "text/csv" => "csv",
+        "application/json" => "json",
+        "application/pdf" => "pdf",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation" => "pptx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        "audio/mpeg" => "mp3",
+        "audio/wav" => "wav",
+        "audio/ogg" => "ogg",
+        "audio/webm" => "weba",
+        "audio/mp4" => "m4a",
         _ => "bin",
     }
 }
@@ -1,10 +1,11 @@
 use axum::{extract::State, Json};
+use chrono_tz::Tz;
 use std::sync::Arc;
 use tracing::info;
 
 use crate::{AppError, AppResult, AppState};
 
-use super::check_auth;
+use super::{check_auth, check_scope, ApiKeyScope};
 
 /// GET /api/cron/jobs[?agent_id=X]
 pub async fn list_cron_jobs(
@@ -12,7 +13,7 @@ pub async fn list_cron_jobs(
     headers: axum::http::HeaderMap,
     query: axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
     let jobs = if let Some(agent_id) = query.get("agent_id") {
         crate::db::list_cron_jobs_for_agent(&state.pool, agent_id).await
     } else {
@@ -24,6 +25,44 @@ pub async fn list_cron_jobs(
     ))
 }
 
+/// `weekdays_only`, `skip_dates` (array of YYYY-MM-DD) and `skip_ics`
+/// (iCalendar text whose event dates are merged into the skip dates).
+fn parse_calendar_modifiers(payload: &serde_json::Value) -> AppResult<(bool, String)> {
+    let weekdays_only = payload["weekdays_only"].as_bool().unwrap_or(false);
+    let skip_dates: Vec<&str> = match &payload["skip_dates"] {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(dates) => dates
+            .iter()
+            .map(|d| {
+                d.as_str().ok_or_else(|| {
+                    AppError::Validation("skip_dates must be an array of YYYY-MM-DD strings".into())
+                })
+            })
+            .collect::<AppResult<_>>()?,
+        _ => {
+            return Err(AppError::Validation(
+                "skip_dates must be an array of YYYY-MM-DD strings".into(),
+            ))
+        }
+    };
+    let skip_dates =
+        crate::managers::scheduler::build_skip_dates(&skip_dates, payload["skip_ics"].as_str())
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+    Ok((weekdays_only, skip_dates))
+}
+
+/// The optional IANA `timezone` of a new job, and the zone it resolves to
+/// (UTC when unset).
+fn parse_timezone_field(payload: &serde_json::Value) -> AppResult<(Option<&str>, Tz)> {
+    let timezone = payload["timezone"]
+        .as_str()
+        .map(str::trim)
+        .filter(|tz| !tz.is_empty());
+    let tz = crate::managers::scheduler::parse_timezone(timezone)
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+    Ok((timezone, tz))
+}
+
 /// POST /api/cron/jobs
 pub async fn create_cron_job(
     State(state): State<Arc<AppState>>,
@@ -39,7 +78,7 @@ pub async fn create_cron_job(
         .as_str()
         .ok_or_else(|| AppError::Validation("name is required".into()))?;
     let schedule_type = payload["schedule_type"].as_str().ok_or_else(|| {
-        AppError::Validation("schedule_type is required (interval|cron|once)".into())
+        AppError::Validation("schedule_type is required (interval|cron|once|after)".into())
     })?;
     let schedule_value = payload["schedule_value"]
         .as_str()
@@ -48,10 +87,47 @@ pub async fn create_cron_job(
         .as_str()
         .ok_or_else(|| AppError::Validation("message is required".into()))?;
 
+    let misfire_policy = payload["misfire_policy"].as_str().unwrap_or("run_once");
+    misfire_policy
+        .parse::<crate::managers::scheduler::MisfirePolicy>()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+    let jitter_secs = payload["jitter_secs"].as_i64().unwrap_or(0);
+    if !(0..=crate::managers::scheduler::MAX_JITTER_SECS).contains(&jitter_secs) {
+        return Err(AppError::Validation(format!(
+            "jitter_secs must be between 0 and {}",
+            crate::managers::scheduler::MAX_JITTER_SECS
+        )));
+    }
+
+    let (weekdays_only, skip_dates) = parse_calendar_modifiers(&payload)?;
+    let (timezone, tz) = parse_timezone_field(&payload)?;
+
+    let chain_on = payload["chain_on"].as_str().unwrap_or("success");
+    chain_on
+        .parse::<crate::managers::scheduler::ChainOn>()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+    if schedule_type == "after" {
+        // schedule_value names the upstream job
+        let upstream = crate::db::get_cron_job(&state.pool, schedule_value)
+            .await
+            .map_err(AppError::Internal)?;
+        if upstream.is_none() {
+            return Err(AppError::Validation(format!(
+                "Upstream cron job '{}' not found",
+                schedule_value
+            )));
+        }
+    }
+
     // Validate schedule and compute initial next_run_at
     let next_run_at =
-        crate::managers::scheduler::calculate_initial_next_run(schedule_type, schedule_value)
+        crate::managers::scheduler::calculate_initial_next_run(schedule_type, schedule_value, tz)
             .map_err(|e| AppError::Validation(e.to_string()))?;
+    let next_run_at = if schedule_type == "once" {
+        next_run_at
+    } else {
+        crate::managers::scheduler::apply_jitter(next_run_at, jitter_secs)
+    };
 
     let job_id = format!("cron.{}.{}", agent_id, cloto_shared::ClotoId::new());
     let engine_id = payload["engine_id"].as_str().map(String::from);
@@ -72,6 +148,12 @@ pub async fn create_cron_job(
         last_error: None,
         max_iterations: max_iterations.or(Some(8)),
         created_at: String::new(), // set by DB default
+        misfire_policy: misfire_policy.to_string(),
+        jitter_secs,
+        chain_on: chain_on.to_string(),
+        weekdays_only,
+        skip_dates,
+        timezone: timezone.map(String::from),
     };
 
     crate::db::create_cron_job(&state.pool, &job)
@@ -80,9 +162,23 @@ pub async fn create_cron_job(
 
     info!(job_id = %job_id, agent_id = %agent_id, name = %name, "Cron job created");
 
-    Ok(Json(
-        serde_json::json!({ "id": job_id, "next_run_at": next_run_at }),
-    ))
+    // Upcoming occurrences, shown in the job's timezone
+    let next_runs: Vec<String> = crate::managers::scheduler::preview_runs(
+        schedule_type,
+        schedule_value,
+        tz,
+        next_run_at,
+        crate::managers::scheduler::PREVIEW_RUNS,
+    )
+    .iter()
+    .map(chrono::DateTime::to_rfc3339)
+    .collect();
+
+    Ok(Json(serde_json::json!({
+        "id": job_id,
+        "next_run_at": next_run_at,
+        "next_runs": next_runs,
+    })))
 }
 
 /// DELETE /api/cron/jobs/:id
@@ -126,42 +222,45 @@ pub async fn run_cron_job_now(
 ) -> AppResult<Json<serde_json::Value>> {
     check_auth(&state, &headers)?;
 
-    // Fetch the job
-    let jobs = crate::db::list_cron_jobs(&state.pool)
+    let job = crate::db::get_cron_job(&state.pool, &job_id)
         .await
-        .map_err(|e| AppError::Internal(e))?;
-    let job = jobs
-        .into_iter()
-        .find(|j| j.id == job_id)
+        .map_err(AppError::Internal)?
         .ok_or_else(|| AppError::NotFound(format!("Cron job '{}' not found", job_id)))?;
 
-    // Build and dispatch the message immediately
-    let mut metadata = std::collections::HashMap::new();
-    metadata.insert("target_agent_id".into(), job.agent_id.clone());
-    metadata.insert("cron_job_id".into(), job.id.clone());
-    metadata.insert("cron_source".into(), "manual".into());
-    if let Some(ref engine_id) = job.engine_id {
-        metadata.insert("engine_override".into(), engine_id.clone());
-    }
+    let message_id = crate::managers::scheduler::dispatch_job(
+        &state.pool,
+        &state.event_tx,
+        &job,
+        "manual",
+        None,
+    )
+    .await
+    .map_err(AppError::Internal)?;
 
-    let msg = cloto_shared::ClotoMessage {
-        id: cloto_shared::ClotoId::new().to_string(),
-        source: cloto_shared::MessageSource::System,
-        target_agent: Some(job.agent_id.clone()),
-        content: job.message.clone(),
-        timestamp: chrono::Utc::now(),
-        metadata,
-    };
+    info!(job_id = %job_id, "Cron job manually triggered");
+    Ok(Json(
+        serde_json::json!({ "status": "dispatched", "message_id": message_id }),
+    ))
+}
 
-    let envelope =
-        crate::EnvelopedEvent::system(cloto_shared::ClotoEventData::MessageReceived(msg));
+#[derive(serde::Deserialize)]
+pub struct RunsQuery {
+    limit: Option<i64>,
+}
 
-    state
-        .event_tx
-        .send(envelope)
+/// GET /api/cron/jobs/:id/runs[?limit=N] — execution history, newest first
+pub async fn list_cron_job_runs(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    axum::extract::Path(job_id): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<RunsQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let runs = crate::db::list_cron_job_runs(&state.pool, &job_id, limit)
         .await
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to dispatch: {}", e)))?;
-
-    info!(job_id = %job_id, "Cron job manually triggered");
-    Ok(Json(serde_json::json!({ "status": "dispatched" })))
+        .map_err(AppError::Internal)?;
+    Ok(Json(
+        serde_json::json!({ "runs": runs, "count": runs.len() }),
+    ))
 }
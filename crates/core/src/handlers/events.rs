@@ -18,6 +18,7 @@ use super::check_auth;
 /// - `MessageReceived` - Chat messages
 /// - `VisionUpdated` - Vision data updates
 /// - `GazeUpdated` - Gaze tracking data
+/// - `AgentMessage` - Message from one agent to another
 ///
 /// All other event types are rejected with 403 to prevent
 /// injection of system-critical events.
@@ -38,7 +39,8 @@ pub async fn post_event_handler(
         // SystemNotification removed - external callers should not inject system notifications
         cloto_shared::ClotoEventData::MessageReceived(_)
         | cloto_shared::ClotoEventData::VisionUpdated(_)
-        | cloto_shared::ClotoEventData::GazeUpdated(_) => {
+        | cloto_shared::ClotoEventData::GazeUpdated(_)
+        | cloto_shared::ClotoEventData::AgentMessage { .. } => {
             // これらは許可
         }
         _ => {
@@ -61,3 +63,79 @@ pub async fn post_event_handler(
     }
     Ok(Json(serde_json::json!({ "status": "published" })))
 }
+
+#[derive(serde::Deserialize)]
+pub struct DeadLetterQuery {
+    /// Include events that were already replayed.
+    #[serde(default)]
+    all: bool,
+    limit: Option<i64>,
+}
+
+/// Events plugins failed to handle after `CLOTO_EVENT_RETRY_MAX` retries.
+///
+/// **Route:** `GET /api/events/dead-letter[?all=true&limit=N]`
+///
+/// # Authentication
+/// Requires valid API key in `X-API-Key` header (payloads may be sensitive).
+///
+/// # Response
+/// `{ "events": [DeadLetterEventRow, ...] }`, newest first. Replayed events
+/// are left out unless `all=true`; `limit` defaults to 100 (max 1000).
+pub async fn list_dead_letter_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::extract::Query(query): axum::extract::Query<DeadLetterQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let events = crate::db::list_dead_letter_events(
+        &state.pool,
+        !query.all,
+        query.limit.unwrap_or(100).clamp(1, 1000),
+    )
+    .await?;
+    Ok(Json(serde_json::json!({ "events": events })))
+}
+
+/// Redeliver a dead-lettered event to the plugin that failed to handle it.
+///
+/// **Route:** `POST /api/events/dead-letter/:id/replay`
+///
+/// # Response
+/// - **200 OK:** `{ "status": "replayed", "id": ... }`
+/// - **404 Not Found:** No dead-lettered event with this ID
+/// - **500 Internal Server Error:** The plugin failed again (the entry stays
+///   pending) or is no longer loaded
+pub async fn replay_dead_letter_event(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let row = crate::db::get_dead_letter_event(&state.pool, &id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Dead-letter event '{}' not found", id)))?;
+    state
+        .registry
+        .replay_dead_letter(&row, &state.event_tx)
+        .await
+        .map_err(|e| {
+            AppError::Internal(e.context(format!("Replay to '{}' failed", row.plugin_id)))
+        })?;
+    crate::db::mark_dead_letter_event_replayed(&state.pool, &id).await?;
+
+    super::spawn_admin_audit(
+        state.pool.clone(),
+        "DEAD_LETTER_REPLAYED",
+        row.plugin_id.clone(),
+        format!(
+            "Human administrator replayed {} event {}",
+            row.event_type, id
+        ),
+        None,
+        None,
+        None,
+    );
+
+    Ok(Json(serde_json::json!({ "status": "replayed", "id": id })))
+}
@@ -0,0 +1,141 @@
+//! Scoped API key management (`/api/system/keys`).
+//!
+//! `CLOTO_API_KEY` remains the bootstrap admin key; these handlers issue
+//! additional keys limited to `read_only`, `chat` or `admin` scope.
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::{check_auth, spawn_admin_audit, ApiKeyScope};
+use crate::{AppError, AppResult, AppState};
+
+const KEY_PREFIX_LEN: usize = 12;
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scope: String,
+    /// Own rate limit for requests made with the key, replacing the chat
+    /// and management group quotas. Set together with `rate_burst`.
+    pub rate_per_second: Option<u32>,
+    pub rate_burst: Option<u32>,
+}
+
+/// GET /api/system/keys — issued keys (hashes and plaintext are never returned)
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let keys = crate::db::list_api_keys(&state.pool)
+        .await
+        .map_err(AppError::Internal)?;
+    Ok(Json(serde_json::json!({ "keys": keys })))
+}
+
+/// POST /api/system/keys — issue a key. The plaintext `key` is only
+/// included in this response.
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    use rand::Rng;
+
+    check_auth(&state, &headers)?;
+    let name = payload.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::Validation("name must be 1-100 characters".into()));
+    }
+    let scope: ApiKeyScope = payload.scope.parse().map_err(AppError::Validation)?;
+    let (rate_per_second, rate_burst) = match (payload.rate_per_second, payload.rate_burst) {
+        (None, None) => (None, None),
+        (Some(per_second @ 1..=10_000), Some(burst @ 1..=10_000)) => {
+            (i32::try_from(per_second).ok(), i32::try_from(burst).ok())
+        }
+        _ => {
+            return Err(AppError::Validation(
+                "rate_per_second and rate_burst must be set together, each 1-10000".into(),
+            ))
+        }
+    };
+
+    let key = {
+        let mut rng = rand::thread_rng();
+        format!("cloto_{:032x}{:032x}", rng.gen::<u128>(), rng.gen::<u128>())
+    };
+    let row = crate::db::ApiKeyRow {
+        id: format!("key.{}", cloto_shared::ClotoId::new()),
+        name: name.to_string(),
+        key_hash: crate::db::hash_scoped_api_key(&key),
+        key_prefix: key[..KEY_PREFIX_LEN].to_string(),
+        scope: scope.as_str().to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+        rate_per_second,
+        rate_burst,
+    };
+    crate::db::create_api_key(&state.pool, &row)
+        .await
+        .map_err(AppError::Internal)?;
+    if let Ok(mut keys) = state.api_keys.write() {
+        keys.insert(row.key_hash.clone(), scope);
+    }
+    state
+        .rate_limiter
+        .register_key(&row.key_hash, &row.id, row.rate_quota());
+
+    spawn_admin_audit(
+        state.pool.clone(),
+        "API_KEY_CREATED",
+        row.id.clone(),
+        format!("API key '{}' issued", row.name),
+        Some(row.scope.clone()),
+        None,
+        None,
+    );
+
+    Ok(Json(serde_json::json!({
+        "id": row.id,
+        "name": row.name,
+        "scope": row.scope,
+        "key_prefix": row.key_prefix,
+        "created_at": row.created_at,
+        "rate_per_second": row.rate_per_second,
+        "rate_burst": row.rate_burst,
+        "key": key,
+    })))
+}
+
+/// DELETE /api/system/keys/:id — revoke an issued key immediately
+pub async fn delete_api_key(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    let hash = crate::db::delete_api_key(&state.pool, &id)
+        .await
+        .map_err(AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound(format!("API key '{}' not found", id)))?;
+    if let Ok(mut keys) = state.api_keys.write() {
+        keys.remove(&hash);
+    }
+    state.rate_limiter.remove_key(&hash);
+
+    spawn_admin_audit(
+        state.pool.clone(),
+        "API_KEY_DELETED",
+        id.clone(),
+        "API key deleted".to_string(),
+        None,
+        None,
+        None,
+    );
+
+    Ok(Json(serde_json::json!({ "status": "deleted", "id": id })))
+}
@@ -3,14 +3,14 @@ use std::sync::Arc;
 
 use crate::{AppError, AppResult, AppState};
 
-use super::check_auth;
+use super::{check_auth, check_scope, ApiKeyScope};
 
 /// GET /api/llm/providers
 pub async fn list_llm_providers(
     State(state): State<Arc<AppState>>,
     headers: axum::http::HeaderMap,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
     let providers = crate::db::list_llm_providers(&state.pool)
         .await
         .map_err(|e| AppError::Internal(e))?;
@@ -1,15 +1,18 @@
 use axum::{
-    extract::{Path, State},
-    http::HeaderMap,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 use std::{collections::HashMap, sync::Arc};
 use tracing::{error, info};
 
+use crate::managers::mcp_transport::ResourceLimits;
+use crate::managers::PluginManager;
 use crate::{AppError, AppResult, AppState};
 
-use super::{check_auth, spawn_admin_audit};
+use super::{check_auth, check_scope, spawn_admin_audit, ApiKeyScope};
 
 #[derive(Debug, Deserialize)]
 pub struct PluginToggleRequest {
@@ -17,6 +20,13 @@ pub struct PluginToggleRequest {
     pub is_active: bool,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct PluginListQuery {
+    /// Embed `icon_data` in each manifest (off by default; see `get_plugin_icon`)
+    #[serde(default)]
+    pub include_icons: bool,
+}
+
 #[derive(Deserialize)]
 pub struct UpdateConfigPayload {
     pub key: String,
@@ -46,14 +56,122 @@ pub struct RevokePermissionRequest {
 ///
 /// Each entry includes: `id`, `name`, `description`, `version`, `category`,
 /// `tags`, `capabilities`, `is_active`, and `provided_tools`.
-pub async fn get_plugins(State(state): State<Arc<AppState>>) -> AppResult<Json<serde_json::Value>> {
-    let manifests = state
+///
+/// `icon_data` (base64, up to 64KB per plugin) is omitted unless
+/// `?include_icons=true`; fetch icons from `GET /api/plugins/:id/icon` instead.
+pub async fn get_plugins(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PluginListQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    let mut manifests = state
         .plugin_manager
         .list_plugins_with_settings(&state.registry)
         .await?;
+    if !query.include_icons {
+        for manifest in &mut manifests {
+            manifest.icon_data = None;
+        }
+    }
     Ok(Json(serde_json::json!(manifests)))
 }
 
+/// Cache lifetime of plugin icons. Icons only change with the plugin itself,
+/// and the `ETag` lets clients revalidate cheaply after that.
+const ICON_CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// Serve a plugin's icon as an image.
+///
+/// **Route:** `GET /api/plugins/:id/icon`
+///
+/// # Authentication
+/// No authentication required (read-only).
+///
+/// # Response
+/// - **200 OK:** The decoded icon with long-lived `Cache-Control` and an `ETag`
+/// - **304 Not Modified:** `If-None-Match` matches the current icon
+/// - **404 Not Found:** Unknown plugin, or the plugin has no icon
+pub async fn get_plugin_icon(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<axum::response::Response> {
+    let icon_data = state
+        .registry
+        .get_engine(&id)
+        .await
+        .and_then(|plugin| plugin.manifest().icon_data)
+        .ok_or_else(|| AppError::NotFound(format!("No icon for plugin '{}'", id)))?;
+
+    let etag = icon_etag(&icon_data);
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag))
+    {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, ICON_CACHE_CONTROL.to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    let (mime, bytes) = decode_icon(&icon_data).ok_or_else(|| {
+        AppError::Internal(anyhow::anyhow!("Plugin '{}' has malformed icon data", id))
+    })?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, mime),
+            (header::CACHE_CONTROL, ICON_CACHE_CONTROL.to_string()),
+            (header::ETAG, etag),
+            // SVG icons must not run scripts when opened directly
+            (
+                header::CONTENT_SECURITY_POLICY,
+                "default-src 'none'; style-src 'unsafe-inline'".to_string(),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+fn icon_etag(icon_data: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(icon_data.as_bytes());
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    format!("\"{:016x}\"", u64::from_be_bytes(prefix))
+}
+
+/// Decode `icon_data`, either a `data:<mime>;base64,...` URL or bare base64
+/// (PNG or SVG, sniffed from the content).
+fn decode_icon(icon_data: &str) -> Option<(String, Vec<u8>)> {
+    use base64::Engine;
+    let (mime, payload) = match icon_data.strip_prefix("data:") {
+        Some(rest) => {
+            let (mime, payload) = rest.split_once(";base64,")?;
+            (Some(mime.to_string()), payload)
+        }
+        None => (None, icon_data),
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .ok()?;
+    let mime = mime.unwrap_or_else(|| {
+        if bytes.starts_with(b"\x89PNG") {
+            "image/png"
+        } else if bytes.starts_with(b"<svg") || bytes.starts_with(b"<?xml") {
+            "image/svg+xml"
+        } else {
+            "application/octet-stream"
+        }
+        .to_string()
+    });
+    Some((mime, bytes))
+}
+
 /// Get plugin configuration values.
 ///
 /// **Route:** `GET /api/plugins/:id/config`
@@ -63,16 +181,31 @@ pub async fn get_plugins(State(state): State<Arc<AppState>>) -> AppResult<Json<s
 /// Config may contain sensitive values (API keys, tokens).
 ///
 /// # Response
-/// - **200 OK:** JSON object of key-value configuration pairs
+/// - **200 OK:** JSON object of key-value configuration pairs. Keys declared
+///   in the plugin's `config_schema` fall back to their defaults, and
+///   `secret` keys are returned as `"***"`.
 /// - **403 Forbidden:** Invalid or missing API key
 pub async fn get_plugin_config(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
     let config = state.plugin_manager.get_config(&id).await?;
-    Ok(Json(serde_json::json!(config)))
+    let schema = plugin_config_schema(&state, &id).await;
+    Ok(Json(serde_json::json!(PluginManager::present_config(
+        &schema, config
+    ))))
+}
+
+/// The `config_schema` of a loaded plugin (empty if unknown or undeclared).
+async fn plugin_config_schema(state: &AppState, id: &str) -> Vec<cloto_shared::ConfigField> {
+    state
+        .registry
+        .get_engine(id)
+        .await
+        .map(|p| p.manifest().config_schema)
+        .unwrap_or_default()
 }
 
 /// Update a single plugin configuration key-value pair.
@@ -93,6 +226,8 @@ pub async fn get_plugin_config(
 ///
 /// # Response
 /// - **200 OK:** `{ "status": "success" }`
+/// - **400 Bad Request:** Key or value rejected by the plugin's
+///   `config_schema` (unknown key, wrong type, out of range, pattern mismatch)
 /// - **403 Forbidden:** Invalid or missing API key
 pub async fn update_plugin_config(
     State(state): State<Arc<AppState>>,
@@ -101,6 +236,16 @@ pub async fn update_plugin_config(
     Json(payload): Json<UpdateConfigPayload>,
 ) -> AppResult<Json<serde_json::Value>> {
     check_auth(&state, &headers)?;
+    let schema = plugin_config_schema(&state, &id).await;
+    let is_secret = schema
+        .iter()
+        .any(|f| f.key == payload.key && f.field_type == cloto_shared::ConfigFieldType::Secret);
+    // A masked secret sent back unchanged keeps the stored value
+    if is_secret && payload.value == PluginManager::SECRET_MASK {
+        return Ok(Json(serde_json::json!({ "status": "success" })));
+    }
+    PluginManager::validate_config(&schema, &payload.key, &payload.value)
+        .map_err(|e| AppError::Validation(e.to_string()))?;
     state
         .plugin_manager
         .update_config(&id, &payload.key, &payload.value)
@@ -169,6 +314,64 @@ pub async fn apply_plugin_settings(
     Ok(Json(true))
 }
 
+/// Reload a plugin without restarting the kernel.
+///
+/// **Route:** `POST /api/plugins/:id/reload`
+///
+/// # Authentication
+/// Requires valid API key in `X-API-Key` header.
+///
+/// Native plugins are re-instantiated from their registered factory and
+/// `on_plugin_init` runs again with their current permissions. MCP servers
+/// (all external plugins) are restarted, which re-runs the MCP handshake and
+/// picks up changes to the server's code.
+///
+/// # Response
+/// - **200 OK:** `{ "status": "reloaded", "id": ..., "kind": "native" | "mcp" }`
+/// - **400 Bad Request:** The plugin has no factory, or re-initialization failed
+/// - **403 Forbidden:** Invalid or missing API key
+/// - **404 Not Found:** No loaded plugin or MCP server with this ID
+pub async fn reload_plugin(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+
+    let kind =
+        if state.registry.get_engine(&id).await.is_some() {
+            state
+                .plugin_manager
+                .reload_plugin(&state.registry, &id)
+                .await
+                .map_err(|e| AppError::Validation(format!("Failed to reload plugin: {}", e)))?;
+            "native"
+        } else if state.mcp_manager.has_server(&id).await {
+            state.mcp_manager.restart_server(&id).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to restart server: {}", e))
+            })?;
+            "mcp"
+        } else {
+            return Err(AppError::NotFound(format!("Plugin '{}' not found", id)));
+        };
+
+    spawn_admin_audit(
+        state.pool.clone(),
+        "PLUGIN_RELOADED",
+        id.clone(),
+        format!("Plugin reloaded ({})", kind),
+        None,
+        None,
+        None,
+    );
+
+    Ok(Json(serde_json::json!({
+        "status": "reloaded",
+        "id": id,
+        "kind": kind,
+    })))
+}
+
 /// Grant a permission to a plugin.
 ///
 /// **Route:** `POST /api/plugins/:id/permissions`
@@ -182,7 +385,8 @@ pub async fn apply_plugin_settings(
 /// ```
 ///
 /// Valid permissions: `NetworkAccess`, `FileRead`, `FileWrite`,
-/// `ProcessExecution`, `VisionRead`, `AdminAccess`.
+/// `ProcessExecution`, `VisionRead`, `AdminAccess`, `ClipboardAccess`,
+/// `CameraRead`, `ChannelRelay`.
 ///
 /// # Side Effects
 /// - Broadcasts `PermissionGranted` event (triggers capability injection)
@@ -241,7 +445,7 @@ pub async fn get_plugin_permissions(
     headers: HeaderMap,
     Path(id): Path<String>,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
     let perms = state.plugin_manager.get_permissions(&id).await?;
     let list: Vec<String> = perms.iter().map(|p| format!("{:?}", p)).collect();
     Ok(Json(
@@ -417,7 +621,7 @@ pub async fn list_mcp_servers(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
 
     let servers = state.mcp_manager.list_servers().await;
 
@@ -473,7 +677,7 @@ pub async fn get_mcp_server_settings(
     Path(name): Path<String>,
     headers: HeaderMap,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
 
     let settings = crate::db::get_mcp_server_settings(&state.pool, &name)
         .await
@@ -481,6 +685,11 @@ pub async fn get_mcp_server_settings(
 
     // Get in-memory config env (from mcp.toml or runtime) as defaults
     let config_env = state.mcp_manager.get_server_env(&name).await;
+    let resource_limits = state
+        .mcp_manager
+        .get_server_resource_limits(&name)
+        .await
+        .unwrap_or_default();
 
     if let Some((record, default_policy)) = settings {
         // Merge: in-memory config env as base, DB env overrides
@@ -515,6 +724,7 @@ pub async fn get_mcp_server_settings(
             "default_policy": default_policy,
             "config": {},
             "env": masked_env,
+            "resource_limits": resource_limits,
             "auto_restart": false,
             "command": record.command,
             "args": serde_json::from_str::<Vec<String>>(&record.args).unwrap_or_default(),
@@ -548,6 +758,7 @@ pub async fn get_mcp_server_settings(
                 "default_policy": "opt-in",
                 "config": {},
                 "env": masked_env,
+                "resource_limits": resource_limits,
                 "auto_restart": false,
                 "command": server.command,
                 "args": server.args,
@@ -674,6 +885,11 @@ pub async fn update_mcp_server_settings(
         }
     }
 
+    // Handle resource limit updates ({} clears both limits)
+    if let Some(limits_value) = body.get("resource_limits") {
+        update_resource_limits(&state, &name, limits_value).await?;
+    }
+
     spawn_admin_audit(
         state.pool.clone(),
         "MCP_SERVER_SETTINGS_UPDATED",
@@ -690,13 +906,57 @@ pub async fn update_mcp_server_settings(
     })))
 }
 
+/// Validate and persist `resource_limits` from a settings update, then restart the server.
+async fn update_resource_limits(
+    state: &AppState,
+    name: &str,
+    limits_value: &serde_json::Value,
+) -> AppResult<()> {
+    let limits: ResourceLimits = serde_json::from_value(limits_value.clone())
+        .map_err(|e| AppError::Validation(format!("Invalid resource_limits: {}", e)))?;
+    limits
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let servers = state.mcp_manager.list_servers().await;
+    let Some(server) = servers.iter().find(|s| s.id == name) else {
+        return Err(AppError::Validation(format!(
+            "MCP server '{}' not found",
+            name
+        )));
+    };
+    if crate::db::get_mcp_server_settings(&state.pool, name)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("{}", e)))?
+        .is_none()
+    {
+        // Config-loaded server not yet in DB — persist it first
+        let args_json = serde_json::to_string(&server.args).unwrap_or_else(|_| "[]".to_string());
+        crate::db::ensure_mcp_server_in_db(
+            &state.pool,
+            name,
+            &server.command,
+            &args_json,
+            "opt-in",
+        )
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("{}", e)))?;
+    }
+
+    state
+        .mcp_manager
+        .update_server_resource_limits(name, limits)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("{}", e)))
+}
+
 /// GET /api/mcp/servers/:name/access
 pub async fn get_mcp_server_access(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
     headers: HeaderMap,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
 
     let entries = crate::db::get_access_entries_for_server(&state.pool, &name)
         .await
@@ -894,6 +1154,46 @@ pub async fn stop_mcp_server(
     })))
 }
 
+/// GET /api/mcp/servers/:name/models
+/// Models a mind server can use, for servers that provide a `list_models`
+/// tool (e.g. the locally installed models of `mind.ollama`).
+pub async fn get_mcp_server_models(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+
+    if !state
+        .mcp_manager
+        .has_server_tool(&name, "list_models")
+        .await
+    {
+        return Err(AppError::NotFound(format!(
+            "MCP server '{}' is not running or does not list models",
+            name
+        )));
+    }
+    let result = state
+        .mcp_manager
+        .call_server_tool(&name, "list_models", serde_json::json!({}))
+        .await
+        .map_err(AppError::Internal)?;
+    let models = result.json_content().ok_or_else(|| {
+        AppError::Internal(anyhow::anyhow!(
+            "MCP server '{}' returned a non-JSON model list",
+            name
+        ))
+    })?;
+    if let Some(error) = models.get("error").and_then(serde_json::Value::as_str) {
+        return Err(AppError::Cloto(cloto_shared::ClotoError::PluginError {
+            id: name,
+            message: error.to_string(),
+        }));
+    }
+    Ok(Json(models))
+}
+
 // ============================================================
 // YOLO Mode API
 // ============================================================
@@ -903,7 +1203,7 @@ pub async fn get_yolo_mode(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
 ) -> AppResult<Json<serde_json::Value>> {
-    check_auth(&state, &headers)?;
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
     let enabled = state
         .mcp_manager
         .yolo_mode
@@ -0,0 +1,293 @@
+//! OpenAI-compatible gateway (`/v1/chat/completions`, `/v1/models`).
+//!
+//! Lets OpenAI SDK clients and tools such as Open WebUI talk to agents: the
+//! `model` field names the target agent, the last user message is delivered
+//! as a `MessageReceived` event and the agent's `ThoughtResponse` comes back
+//! as the completion.
+
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use cloto_shared::{ClotoEventData, ClotoMessage, MessageSource};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::managers::channel_relay::reply_timeout;
+use crate::{AppError, AppResult, AppState};
+
+use super::{check_scope, ApiKeyScope};
+
+#[derive(Deserialize)]
+pub struct ChatCompletionRequest {
+    /// Target agent id.
+    pub model: String,
+    pub messages: Vec<ChatCompletionMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    /// End-user identifier, used as the message source.
+    pub user: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: String,
+    /// A string, or an array of content parts of which only `text` parts
+    /// are relayed.
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl ChatCompletionMessage {
+    fn text(&self) -> String {
+        match &self.content {
+            Value::String(s) => s.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter(|p| p.get("type").and_then(Value::as_str) == Some("text"))
+                .filter_map(|p| p.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Build the agent message for a completion request. As with channel
+/// adapters, only the last user message is relayed; the earlier turns are
+/// covered by the agent's own chat history.
+fn message_for(req: &ChatCompletionRequest) -> AppResult<ClotoMessage> {
+    let content = req
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .map(ChatCompletionMessage::text)
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| AppError::Validation("No user text message to relay".to_string()))?;
+
+    // `user` is whatever the client says; keep it apart from real user ids
+    let (user_id, user_name) = match req.user {
+        Some(ref user) => (format!("openai:{}", user), user.clone()),
+        None => ("openai".to_string(), "openai".to_string()),
+    };
+    let mut msg = ClotoMessage::new(
+        MessageSource::User {
+            id: user_id,
+            name: user_name,
+        },
+        content,
+    );
+    msg.target_agent = Some(req.model.clone());
+    msg.metadata = HashMap::from([
+        ("target_agent_id".to_string(), req.model.clone()),
+        ("channel".to_string(), "openai".to_string()),
+    ]);
+    Ok(msg)
+}
+
+fn completion_id(message_id: &str) -> String {
+    format!("chatcmpl-{}", message_id)
+}
+
+fn chunk(id: &str, model: &str, created: i64, delta: Value, finish_reason: Option<&str>) -> String {
+    json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+    })
+    .to_string()
+}
+
+/// Create a chat completion by messaging an agent.
+///
+/// **Route:** `POST /v1/chat/completions`
+///
+/// # Authentication
+/// Requires a `chat` scope, via `X-API-Key` or `Authorization: Bearer`.
+///
+/// # Response
+/// An OpenAI `chat.completion` object, or with `"stream": true` a stream of
+/// `chat.completion.chunk` events ending in `data: [DONE]`. Waits up to
+/// `CLOTO_CHANNEL_REPLY_TIMEOUT_SECS` for the agent's answer.
+pub async fn chat_completions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ChatCompletionRequest>,
+) -> AppResult<Response> {
+    check_scope(&state, &headers, ApiKeyScope::Chat)?;
+    let agents = state.agent_manager.list_agents().await?;
+    if !agents.iter().any(|a| a.id == req.model) {
+        return Err(AppError::NotFound(format!(
+            "Agent '{}' not found",
+            req.model
+        )));
+    }
+    let msg = message_for(&req)?;
+    let message_id = msg.id.clone();
+
+    // Subscribe before publishing so a fast answer is not missed
+    let mut events = state.tx.subscribe();
+    state
+        .event_tx
+        .send(crate::EnvelopedEvent::system(
+            ClotoEventData::MessageReceived(msg),
+        ))
+        .await
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("Failed to accept message")))?;
+
+    let id = completion_id(&message_id);
+    let created = chrono::Utc::now().timestamp();
+    let timeout = reply_timeout();
+
+    if req.stream {
+        let model = req.model;
+        let stream = async_stream::stream! {
+            yield Ok::<_, std::convert::Infallible>(Event::default().data(chunk(
+                &id, &model, created, json!({ "role": "assistant", "content": "" }), None,
+            )));
+            let deadline = tokio::time::Instant::now() + timeout;
+            let mut streamed = String::new();
+            loop {
+                let event = match tokio::time::timeout_at(deadline, events.recv()).await {
+                    Ok(Ok(event)) => event,
+                    Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+                    Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => {
+                        tracing::warn!(message_id = %message_id, "OpenAI stream ended without agent response");
+                        yield Ok(Event::default().data(chunk(&id, &model, created, json!({}), Some("length"))));
+                        break;
+                    }
+                };
+                match &event.data {
+                    ClotoEventData::ThoughtResponseChunk { source_message_id, delta, .. }
+                        if *source_message_id == message_id =>
+                    {
+                        streamed.push_str(delta);
+                        yield Ok(Event::default().data(chunk(
+                            &id, &model, created, json!({ "content": delta }), None,
+                        )));
+                    }
+                    ClotoEventData::ThoughtResponse { source_message_id, content, .. }
+                        if *source_message_id == message_id =>
+                    {
+                        // The final response carries the full text; send what
+                        // the chunks did not cover.
+                        let rest = content.strip_prefix(streamed.as_str()).unwrap_or(content);
+                        if !rest.is_empty() {
+                            yield Ok(Event::default().data(chunk(
+                                &id, &model, created, json!({ "content": rest }), None,
+                            )));
+                        }
+                        yield Ok(Event::default().data(chunk(&id, &model, created, json!({}), Some("stop"))));
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            yield Ok(Event::default().data("[DONE]"));
+        };
+        return Ok(Sse::new(stream)
+            .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+            .into_response());
+    }
+
+    let reply = tokio::time::timeout(
+        timeout,
+        crate::events::wait_for_thought_response(&mut events, &message_id),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Timeout(format!(
+            "No agent response within {}s (agent disabled or busy?)",
+            timeout.as_secs()
+        ))
+    })?
+    .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Event bus closed")))?;
+
+    Ok(Json(json!({
+        "id": id,
+        "object": "chat.completion",
+        "created": created,
+        "model": req.model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": reply.content },
+            "finish_reason": "stop",
+        }],
+        "usage": { "prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0 },
+    }))
+    .into_response())
+}
+
+/// List agents as OpenAI models.
+///
+/// **Route:** `GET /v1/models`
+///
+/// # Authentication
+/// Requires a `read_only` scope, via `X-API-Key` or `Authorization: Bearer`.
+pub async fn list_models(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    let agents = state.agent_manager.list_agents().await?;
+    let data: Vec<Value> = agents
+        .into_iter()
+        .filter(|a| a.enabled)
+        .map(|a| {
+            json!({
+                "id": a.id,
+                "object": "model",
+                "created": 0,
+                "owned_by": "cloto",
+                "name": a.name,
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "object": "list", "data": data })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_relays_last_user_text() {
+        let req: ChatCompletionRequest = serde_json::from_value(json!({
+            "model": "agent.karin",
+            "user": "webui-user",
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "First question" },
+                { "role": "assistant", "content": "First answer" },
+                { "role": "user", "content": [
+                    { "type": "text", "text": "What is in" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/a.png" } },
+                    { "type": "text", "text": "this picture?" },
+                ]},
+            ],
+        }))
+        .unwrap();
+        let Ok(msg) = message_for(&req) else {
+            panic!("request with a user message should be relayed");
+        };
+        assert_eq!(msg.content, "What is in\nthis picture?");
+        assert_eq!(msg.target_agent.as_deref(), Some("agent.karin"));
+        assert_eq!(msg.metadata["target_agent_id"], "agent.karin");
+        assert!(
+            matches!(msg.source, MessageSource::User { ref id, .. } if id == "openai:webui-user")
+        );
+        assert!(!req.stream);
+    }
+}
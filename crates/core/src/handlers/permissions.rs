@@ -2,7 +2,7 @@ use axum::{extract::State, http::HeaderMap, Json};
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::{AppResult, AppState};
+use crate::{AppError, AppResult, AppState};
 
 use super::{check_auth, spawn_admin_audit};
 
@@ -107,3 +107,84 @@ pub async fn deny_permission(
         "message": "Permission request denied"
     })))
 }
+
+/// Tool calls waiting for approval (tools listed in an MCP server's
+/// `approval_required_tools`).
+///
+/// **Route:** `GET /api/tool-calls/pending`
+///
+/// # Authentication
+/// Requires valid API key in `X-API-Key` header (arguments may be sensitive).
+pub async fn get_pending_tool_calls(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<Vec<crate::db::ToolInvocationRequestRow>>> {
+    check_auth(&state, &headers)?;
+    let requests = crate::db::list_pending_tool_invocation_requests(&state.pool).await?;
+    Ok(Json(requests))
+}
+
+/// Let a waiting tool call proceed.
+///
+/// **Route:** `POST /api/tool-calls/:request_id/approve`
+///
+/// # Response
+/// - **200 OK:** `{ "status": "approved", "id": ... }`
+/// - **404 Not Found:** No call is waiting on this request (unknown, already
+///   decided, or expired)
+pub async fn approve_tool_call(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::extract::Path(request_id): axum::extract::Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    decide_tool_call(&state, &headers, request_id, true).await
+}
+
+/// Reject a waiting tool call; the agent receives an error result.
+///
+/// **Route:** `POST /api/tool-calls/:request_id/deny`
+pub async fn deny_tool_call(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::extract::Path(request_id): axum::extract::Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    decide_tool_call(&state, &headers, request_id, false).await
+}
+
+async fn decide_tool_call(
+    state: &AppState,
+    headers: &HeaderMap,
+    request_id: String,
+    approve: bool,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(state, headers)?;
+    if !state
+        .mcp_manager
+        .resolve_tool_approval(&request_id, approve, "admin")
+        .await?
+    {
+        return Err(AppError::NotFound(format!(
+            "Tool call '{}' is not awaiting approval",
+            request_id
+        )));
+    }
+
+    let (event_type, status) = if approve {
+        ("TOOL_CALL_APPROVED", "approved")
+    } else {
+        ("TOOL_CALL_DENIED", "denied")
+    };
+    spawn_admin_audit(
+        state.pool.clone(),
+        event_type,
+        request_id.clone(),
+        format!("Human administrator {} tool call", status),
+        None,
+        None,
+        None,
+    );
+
+    Ok(Json(
+        serde_json::json!({ "status": status, "id": request_id }),
+    ))
+}
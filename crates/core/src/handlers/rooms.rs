@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::db::{self, ChatRoomMessageRow, ChatRoomRow};
+use crate::managers::rooms::TurnPolicy;
+use crate::{AppError, AppResult, AppState};
+
+use super::{check_auth, check_scope, ApiKeyScope};
+
+/// Upper bound on a room message, matching what a single chat turn can carry.
+const MAX_ROOM_MESSAGE_CHARS: usize = 32_000;
+
+async fn room_with_members(state: &AppState, room: ChatRoomRow) -> AppResult<serde_json::Value> {
+    let members = db::get_chat_room_members(&state.pool, &room.id).await?;
+    let mut value = serde_json::to_value(room)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize room: {}", e)))?;
+    value["members"] = serde_json::json!(members);
+    Ok(value)
+}
+
+async fn get_room(state: &AppState, room_id: &str) -> AppResult<ChatRoomRow> {
+    db::get_chat_room(&state.pool, room_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Room '{}' not found", room_id)))
+}
+
+/// GET /api/rooms
+pub async fn list_rooms(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    let mut rooms = Vec::new();
+    for room in db::list_chat_rooms(&state.pool).await? {
+        rooms.push(room_with_members(&state, room).await?);
+    }
+    Ok(Json(serde_json::json!({ "rooms": rooms })))
+}
+
+#[derive(Deserialize)]
+pub struct CreateRoomRequest {
+    pub name: String,
+    /// Member agents, in round-robin order.
+    pub agent_ids: Vec<String>,
+    #[serde(default)]
+    pub turn_policy: Option<String>,
+}
+
+/// POST /api/rooms
+pub async fn create_room(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateRoomRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+
+    let name = payload.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::Validation(
+            "name must be 1-100 characters".to_string(),
+        ));
+    }
+    let policy: TurnPolicy = payload
+        .turn_policy
+        .as_deref()
+        .unwrap_or("round_robin")
+        .parse()
+        .map_err(|e: anyhow::Error| AppError::Validation(e.to_string()))?;
+
+    let room = state
+        .rooms
+        .create(name, &payload.agent_ids, policy)
+        .await
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+    Ok(Json(room_with_members(&state, room).await?))
+}
+
+/// DELETE /api/rooms/:id
+pub async fn delete_room(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(room_id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_auth(&state, &headers)?;
+    if !state.rooms.delete(&room_id).await? {
+        return Err(AppError::NotFound(format!("Room '{}' not found", room_id)));
+    }
+    Ok(Json(serde_json::json!({ "status": "deleted" })))
+}
+
+#[derive(Deserialize)]
+pub struct GetRoomMessagesQuery {
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/rooms/:id/messages
+/// Returns the merged transcript, newest first, with per-speaker attribution
+pub async fn get_room_messages(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(room_id): Path<String>,
+    Query(params): Query<GetRoomMessagesQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::ReadOnly)?;
+    get_room(&state, &room_id).await?;
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let messages =
+        db::get_chat_room_messages(&state.pool, &room_id, params.before, limit + 1).await?;
+
+    #[allow(clippy::cast_possible_wrap)]
+    let has_more = messages.len() as i64 > limit;
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let messages: Vec<ChatRoomMessageRow> = messages.into_iter().take(limit as usize).collect();
+
+    Ok(Json(serde_json::json!({
+        "messages": messages,
+        "has_more": has_more,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct PostRoomMessageRequest {
+    pub content: String,
+    pub user_id: Option<String>,
+    pub user_name: Option<String>,
+}
+
+/// POST /api/rooms/:id/messages
+/// Post a user message. Replies are appended to the transcript as the
+/// chosen agents answer; the response lists who will speak.
+pub async fn post_room_message(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(room_id): Path<String>,
+    Json(payload): Json<PostRoomMessageRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    check_scope(&state, &headers, ApiKeyScope::Chat)?;
+    let room = get_room(&state, &room_id).await?;
+
+    let content = payload.content.trim();
+    if content.is_empty() || content.len() > MAX_ROOM_MESSAGE_CHARS {
+        return Err(AppError::Validation(format!(
+            "content must be 1-{} characters",
+            MAX_ROOM_MESSAGE_CHARS
+        )));
+    }
+    let user_id = payload.user_id.as_deref().unwrap_or("default");
+    let user_name = payload.user_name.as_deref().unwrap_or("User");
+
+    let posted = state
+        .rooms
+        .post(&room, user_id, user_name, content)
+        .await
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "id": posted.message.id,
+        "created_at": posted.message.created_at,
+        "speakers": posted.speakers,
+    })))
+}
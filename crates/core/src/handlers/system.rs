@@ -2,9 +2,13 @@ use async_trait::async_trait;
 use chrono::Utc;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
 
-use crate::managers::{AgentManager, McpClientManager, PluginRegistry};
+use crate::managers::loop_guard::{LoopGuard, LoopVerdict};
+use crate::managers::tool_audit::{ToolAudit, ToolExecution, ToolOutcome};
+use crate::managers::{
+    AgentManager, AttachmentProcessor, ConversationSummarizer, McpClientManager, PluginRegistry,
+};
 use cloto_shared::{
     AgentMetadata, ClotoEvent, ClotoEventData, ClotoId, ClotoMessage, Plugin, PluginCast,
     PluginManifest, ThinkResult, ToolCall,
@@ -80,7 +84,14 @@ pub struct SystemHandler {
     metrics: Arc<crate::managers::SystemMetrics>,
     consensus_engines: Vec<String>,
     max_agentic_iterations: u8,
-    tool_execution_timeout_secs: u64,
+    tool_execution_timeout_secs: std::sync::atomic::AtomicU64,
+    summarizer: Option<Arc<ConversationSummarizer>>,
+    tool_audit: Option<Arc<ToolAudit>>,
+    attachments: Option<Arc<AttachmentProcessor>>,
+    /// Chat history store; when set, context comes from the active session.
+    chat_sessions: Option<crate::db::DbPool>,
+    /// Agentic runs in flight; no new ones start once shutdown is draining.
+    drain: Arc<crate::drain::DrainTracker>,
 }
 
 impl SystemHandler {
@@ -104,18 +115,73 @@ impl SystemHandler {
             metrics,
             consensus_engines,
             max_agentic_iterations,
-            tool_execution_timeout_secs,
+            tool_execution_timeout_secs: std::sync::atomic::AtomicU64::new(
+                tool_execution_timeout_secs,
+            ),
+            summarizer: None,
+            tool_audit: None,
+            attachments: None,
+            chat_sessions: None,
+            drain: Arc::new(crate::drain::DrainTracker::new()),
         }
     }
 
+    /// Inject rolling chat summaries into recalled context.
+    #[must_use]
+    pub fn with_summarizer(mut self, summarizer: Arc<ConversationSummarizer>) -> Self {
+        self.summarizer = Some(summarizer);
+        self
+    }
+
+    /// Record every tool call in the audit log.
+    #[must_use]
+    pub fn with_tool_audit(mut self, tool_audit: Arc<ToolAudit>) -> Self {
+        self.tool_audit = Some(tool_audit);
+        self
+    }
+
+    /// Add the text of attachments the user sends or mentions to the context.
+    #[must_use]
+    pub fn with_attachments(mut self, attachments: Arc<AttachmentProcessor>) -> Self {
+        self.attachments = Some(attachments);
+        self
+    }
+
+    /// Build context from the active chat session instead of memory recall.
+    #[must_use]
+    pub fn with_chat_sessions(mut self, pool: crate::db::DbPool) -> Self {
+        self.chat_sessions = Some(pool);
+        self
+    }
+
+    /// Share the kernel's drain tracker so shutdown waits for running loops.
+    #[must_use]
+    pub fn with_drain_tracker(mut self, drain: Arc<crate::drain::DrainTracker>) -> Self {
+        self.drain = drain;
+        self
+    }
+
+    /// Change the per-tool execution timeout (config hot reload).
+    pub fn set_tool_execution_timeout_secs(&self, secs: u64) {
+        self.tool_execution_timeout_secs
+            .store(secs, std::sync::atomic::Ordering::Relaxed);
+    }
+
     #[allow(clippy::too_many_lines)]
-    pub async fn handle_message(&self, msg: ClotoMessage) -> anyhow::Result<()> {
+    pub async fn handle_message(&self, mut msg: ClotoMessage) -> anyhow::Result<()> {
         let target_agent_id = msg
             .metadata
             .get("target_agent_id")
             .cloned()
             .unwrap_or_else(|| self.default_agent_id.clone());
 
+        // Agent messages carry the event depth they were delivered at
+        let depth: u8 = msg
+            .metadata
+            .get("event_depth")
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(0);
+
         // 1. エージェント情報の取得
         let (agent, default_engine_id) = self
             .agent_manager
@@ -134,6 +200,13 @@ impl SystemHandler {
             .await
             .ok();
 
+        // Chat sessions: the active session's history is the whole context
+        let session_context = match self.chat_sessions {
+            Some(ref pool) => self.session_context(pool, &target_agent_id, &msg).await,
+            None => None,
+        };
+        let from_session = session_context.is_some();
+
         // 2. メモリからのコンテキスト取得 (Dual Dispatch: Rust Plugin → MCP Server)
         let memory_plugin = if let Some(preferred_id) = agent.metadata.get("preferred_memory") {
             self.registry.get_engine(preferred_id).await
@@ -170,7 +243,9 @@ impl SystemHandler {
             None
         };
 
-        let context = if let Some(ref plugin) = memory_plugin {
+        let context = if let Some(context) = session_context {
+            context
+        } else if let Some(ref plugin) = memory_plugin {
             if let Some(mem) = plugin.as_memory() {
                 // 🔐 Check MemoryRead permission before recall
                 let manifest = plugin.manifest();
@@ -235,6 +310,37 @@ impl SystemHandler {
             vec![]
         };
 
+        // Summarized chat history stands in for the oldest recalled messages
+        let context = if let (Some(ref summarizer), false) = (&self.summarizer, from_session) {
+            summarizer
+                .apply_to_context(
+                    &target_agent_id,
+                    crate::managers::summarizer::DEFAULT_THREAD_USER,
+                    context,
+                )
+                .await
+        } else {
+            context
+        };
+
+        // Derived text of attachments sent with or referenced by this message.
+        // Attachment refs come from storage, never from the sender.
+        let mut context = context;
+        msg.attachments = Vec::new();
+        if let Some(ref attachments) = self.attachments {
+            msg.attachments = attachments.refs_for(&msg.id).await;
+            context.extend(
+                attachments
+                    .context_for(
+                        &target_agent_id,
+                        crate::managers::summarizer::DEFAULT_THREAD_USER,
+                        &msg.id,
+                        &msg.content,
+                    )
+                    .await,
+            );
+        }
+
         // 3. 【核心】思考要求イベントを発行
         info!(
             target_agent_id = %target_agent_id,
@@ -247,9 +353,17 @@ impl SystemHandler {
 
         if msg.content.to_lowercase().starts_with("consensus:") {
             // 合意形成モード
+            let strategy = match msg.metadata.get("consensus_strategy") {
+                Some(name) => name.parse().unwrap_or_else(|e| {
+                    warn!("{}; falling back to synthesis", e);
+                    cloto_shared::ConsensusStrategy::Synthesis
+                }),
+                None => cloto_shared::ConsensusStrategy::Synthesis,
+            };
             let thought_event_data = cloto_shared::ClotoEventData::ConsensusRequested {
                 task: msg.content.clone(),
                 engine_ids: self.consensus_engines.clone(),
+                strategy,
             };
 
             let envelope = crate::EnvelopedEvent {
@@ -270,7 +384,7 @@ impl SystemHandler {
                 let inner_thought = cloto_shared::ClotoEventData::ThoughtRequested {
                     agent: agent.clone(),
                     engine_id: engine.clone(),
-                    message: msg.clone(),
+                    message: self.message_for_engine(engine, &msg).await,
                     context: context.clone(),
                 };
                 let env = crate::EnvelopedEvent {
@@ -326,17 +440,21 @@ impl SystemHandler {
                             content: content.clone(),
                             timestamp: Utc::now(),
                             metadata: std::collections::HashMap::new(),
+                            attachments: Vec::new(),
                         };
                         let agent_id_clone = agent.id.clone();
-                        tokio::spawn(async move {
-                            if let Some(mem) = plugin_clone.as_memory() {
-                                let _ = tokio::time::timeout(
-                                    std::time::Duration::from_secs(5),
-                                    mem.store(agent_id_clone, agent_resp_msg),
-                                )
-                                .await;
+                        tokio::spawn(
+                            async move {
+                                if let Some(mem) = plugin_clone.as_memory() {
+                                    let _ = tokio::time::timeout(
+                                        std::time::Duration::from_secs(5),
+                                        mem.store(agent_id_clone, agent_resp_msg),
+                                    )
+                                    .await;
+                                }
                             }
-                        });
+                            .in_current_span(),
+                        );
                     } else if let Some((ref mcp, ref server_id)) = mcp_memory {
                         let mcp_clone = mcp.clone();
                         let server_id_clone = server_id.clone();
@@ -347,17 +465,24 @@ impl SystemHandler {
                             "source": { "type": "Agent", "id": agent.id },
                             "timestamp": Utc::now().to_rfc3339(),
                         });
-                        tokio::spawn(async move {
-                            let store_args = serde_json::json!({
-                                "agent_id": agent_id_clone,
-                                "message": resp_msg_json,
-                            });
-                            let _ = tokio::time::timeout(
-                                std::time::Duration::from_secs(5),
-                                mcp_clone.call_server_tool(&server_id_clone, "store", store_args),
-                            )
-                            .await;
-                        });
+                        tokio::spawn(
+                            async move {
+                                let store_args = serde_json::json!({
+                                    "agent_id": agent_id_clone,
+                                    "message": resp_msg_json,
+                                });
+                                let _ = tokio::time::timeout(
+                                    std::time::Duration::from_secs(5),
+                                    mcp_clone.call_server_tool(
+                                        &server_id_clone,
+                                        "store",
+                                        store_args,
+                                    ),
+                                )
+                                .await;
+                            }
+                            .in_current_span(),
+                        );
                     }
 
                     let thought_response = ClotoEventData::ThoughtResponse {
@@ -365,12 +490,13 @@ impl SystemHandler {
                         engine_id: engine_id.clone(),
                         content,
                         source_message_id: msg.id.clone(),
+                        metadata: None,
                     };
                     let envelope = crate::EnvelopedEvent {
                         event: Arc::new(ClotoEvent::with_trace(trace_id, thought_response)),
                         issuer: None,
                         correlation_id: None,
-                        depth: 0,
+                        depth,
                     };
                     if let Err(e) = self.sender.send(envelope).await {
                         error!(
@@ -393,12 +519,13 @@ impl SystemHandler {
                         engine_id: engine_id.clone(),
                         content: format!("[Error] Processing failed: {}", e),
                         source_message_id: msg.id.clone(),
+                        metadata: None,
                     };
                     let envelope = crate::EnvelopedEvent {
                         event: Arc::new(ClotoEvent::with_trace(trace_id, error_response)),
                         issuer: None,
                         correlation_id: None,
-                        depth: 0,
+                        depth,
                     };
                     let _ = self.sender.send(envelope).await;
                 }
@@ -443,7 +570,7 @@ impl SystemHandler {
                                 }
                             }
                         }
-                    });
+                    }.in_current_span());
                 } else {
                     tracing::warn!(
                         plugin_id = %manifest.id,
@@ -467,35 +594,41 @@ impl SystemHandler {
             let ep_server_id = server_id.clone();
             let ep_agent_id = agent_id.clone();
 
-            tokio::spawn(async move {
-                let store_args = serde_json::json!({
-                    "agent_id": agent_id,
-                    "message": msg_json,
-                });
-                match tokio::time::timeout(
-                    std::time::Duration::from_secs(5),
-                    mcp.call_server_tool(&server_id, "store", store_args),
-                )
-                .await
-                {
-                    Ok(Ok(_)) => {
-                        metrics
-                            .total_memories
-                            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    }
-                    Ok(Err(e)) => {
-                        error!(agent_id = %agent_id, error = %e, "❌ MCP memory store failed");
-                    }
-                    Err(_) => {
-                        error!(agent_id = %agent_id, "❌ MCP memory store timed out (5s)");
+            tokio::spawn(
+                async move {
+                    let store_args = serde_json::json!({
+                        "agent_id": agent_id,
+                        "message": msg_json,
+                    });
+                    match tokio::time::timeout(
+                        std::time::Duration::from_secs(5),
+                        mcp.call_server_tool(&server_id, "store", store_args),
+                    )
+                    .await
+                    {
+                        Ok(Ok(_)) => {
+                            metrics
+                                .total_memories
+                                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        }
+                        Ok(Err(e)) => {
+                            error!(agent_id = %agent_id, error = %e, "❌ MCP memory store failed");
+                        }
+                        Err(_) => {
+                            error!(agent_id = %agent_id, "❌ MCP memory store timed out (5s)");
+                        }
                     }
                 }
-            });
+                .in_current_span(),
+            );
 
             // Episode auto-archival check (background, non-blocking)
-            tokio::spawn(async move {
-                Self::maybe_archive_episode(&ep_mcp, &ep_server_id, &ep_agent_id).await;
-            });
+            tokio::spawn(
+                async move {
+                    Self::maybe_archive_episode(&ep_mcp, &ep_server_id, &ep_agent_id).await;
+                }
+                .in_current_span(),
+            );
         }
 
         Ok(())
@@ -533,6 +666,7 @@ impl SystemHandler {
         if engine_plugin.is_none() && mcp_engine.is_none() {
             return Err(anyhow::anyhow!("Engine '{}' not found", engine_id));
         }
+        let message = &self.message_for_engine(engine_id, message).await;
 
         // Determine tool support
         let supports_tools = if let Some(ref plugin) = engine_plugin {
@@ -568,6 +702,21 @@ impl SystemHandler {
                 .collect_tool_schemas_for_agent(agent_plugin_ids, &agent.id)
                 .await
         };
+        // Native plugin tools: drop those the agent's tool rules deny
+        let tool_policy = self.agent_manager.get_tool_policy(&agent.id).await?;
+        let tools: Vec<serde_json::Value> = tools
+            .into_iter()
+            .filter(|t| {
+                let name = t
+                    .get("function")
+                    .and_then(|f| f.get("name"))
+                    .and_then(|n| n.as_str())
+                    .unwrap_or_default();
+                self.registry
+                    .native_tool_owner(name)
+                    .is_none_or(|plugin_id| tool_policy.allows(&plugin_id, name))
+            })
+            .collect();
         if tools.is_empty() {
             return self
                 .engine_think(
@@ -591,6 +740,18 @@ impl SystemHandler {
                     .map(std::string::ToString::to_string)
             })
             .collect();
+        // Tools that declare an `agent_id` parameter always receive the caller's ID
+        let agent_scoped_tools: std::collections::HashSet<&str> = tools
+            .iter()
+            .filter_map(|t| {
+                let function = t.get("function")?;
+                function
+                    .get("parameters")?
+                    .get("properties")?
+                    .get("agent_id")?;
+                function.get("name")?.as_str()
+            })
+            .collect();
 
         info!(
             agent_id = %agent.id,
@@ -602,6 +763,7 @@ impl SystemHandler {
         let mut tool_history: Vec<serde_json::Value> = Vec::new();
         let mut iteration: u8 = 0;
         let mut total_tool_calls: u32 = 0;
+        let mut loop_guard = LoopGuard::new();
         const MAX_TOOL_HISTORY: usize = 100;
 
         loop {
@@ -612,6 +774,7 @@ impl SystemHandler {
                     "⚠️ Agentic loop hit max iterations ({}), forcing text response",
                     self.max_agentic_iterations
                 );
+                self.record_tool_history(agent, engine_id, message, &tool_history);
                 return self
                     .engine_think(
                         engine_plugin.as_ref(),
@@ -639,6 +802,7 @@ impl SystemHandler {
 
             match result {
                 ThinkResult::Final(content) => {
+                    self.record_tool_history(agent, engine_id, message, &tool_history);
                     // Emit loop completion event
                     self.emit_event(
                         trace_id,
@@ -695,10 +859,84 @@ impl SystemHandler {
                     }
                     tool_history.push(assistant_msg);
 
+                    match loop_guard.check(&calls) {
+                        LoopVerdict::Continue => {}
+                        LoopVerdict::Warn(pattern) => {
+                            warn!(
+                                agent_id = %agent.id,
+                                reason = pattern.reason(),
+                                "🔁 Tool call loop detected, correcting engine"
+                            );
+                            for call in &calls {
+                                tool_history.push(serde_json::json!({
+                                    "role": "tool",
+                                    "tool_call_id": call.id,
+                                    "content": "Error: not executed, this repeats earlier calls"
+                                }));
+                            }
+                            tool_history.push(serde_json::json!({
+                                "role": "system",
+                                "content": pattern.correction()
+                            }));
+                            continue;
+                        }
+                        LoopVerdict::Abort(pattern) => {
+                            self.record_tool_history(agent, engine_id, message, &tool_history);
+                            self.emit_event(
+                                trace_id,
+                                ClotoEventData::AgenticLoopAborted {
+                                    agent_id: agent.id.clone(),
+                                    engine_id: engine_id.to_string(),
+                                    reason: pattern.reason().to_string(),
+                                    calls: pattern.calls().to_vec(),
+                                    total_iterations: iteration,
+                                    total_tool_calls,
+                                    source_message_id: message.id.clone(),
+                                },
+                            )
+                            .await;
+                            warn!(
+                                agent_id = %agent.id,
+                                reason = pattern.reason(),
+                                iterations = iteration,
+                                "🔁 Agentic loop aborted"
+                            );
+                            return Ok(pattern.explanation(iteration, total_tool_calls));
+                        }
+                    }
+
                     // Execute each tool call
                     for call in &calls {
                         total_tool_calls += 1;
 
+                        // 🔐 Per-tool rules on the agent's native plugin bindings
+                        if let Some(plugin_id) = self.registry.native_tool_owner(&call.name) {
+                            if !tool_policy.allows(&plugin_id, &call.name) {
+                                warn!(
+                                    agent_id = %agent.id,
+                                    plugin_id = %plugin_id,
+                                    tool = %call.name,
+                                    "🔐 Tool denied by agent plugin rules"
+                                );
+                                tool_history.push(serde_json::json!({
+                                    "role": "tool",
+                                    "tool_call_id": call.id,
+                                    "content": format!("Error: tool '{}' is not allowed for this agent", call.name)
+                                }));
+                                self.audit_tool_call(
+                                    agent,
+                                    engine_id,
+                                    call,
+                                    &call.arguments,
+                                    0,
+                                    ToolOutcome::Denied("Denied by agent tool rules".to_string()),
+                                    trace_id,
+                                )
+                                .await;
+                                continue;
+                            }
+                        }
+
                         // M-04: Pre-validate tool name before execution
                         if !tool_names.contains(&call.name) {
                             warn!(
@@ -710,6 +948,16 @@ impl SystemHandler {
                                 "tool_call_id": call.id,
                                 "content": format!("Error: tool '{}' not found", call.name)
                             }));
+                            self.audit_tool_call(
+                                agent,
+                                engine_id,
+                                call,
+                                &call.arguments,
+                                0,
+                                ToolOutcome::Denied("Tool not available to this agent".to_string()),
+                                trace_id,
+                            )
+                            .await;
                             continue;
                         }
 
@@ -720,7 +968,9 @@ impl SystemHandler {
                         // to access their memory or profile
                         let mut safe_args = call.arguments.clone();
                         if let Some(obj) = safe_args.as_object_mut() {
-                            if obj.contains_key("agent_id") {
+                            if obj.contains_key("agent_id")
+                                || agent_scoped_tools.contains(call.name.as_str())
+                            {
                                 obj.insert(
                                     "agent_id".to_string(),
                                     serde_json::Value::String(agent.id.clone()),
@@ -728,8 +978,18 @@ impl SystemHandler {
                             }
                         }
 
+                        let audited_args = safe_args.clone();
+                        // Time spent waiting for a human to approve the call
+                        // does not count against the tool timeout
+                        let approval_wait = match self.registry.mcp_manager {
+                            Some(ref mcp) => mcp.tool_approval_wait(&call.name).await,
+                            None => None,
+                        };
                         let tool_result = tokio::time::timeout(
-                            Duration::from_secs(self.tool_execution_timeout_secs),
+                            Duration::from_secs(
+                                self.tool_execution_timeout_secs
+                                    .load(std::sync::atomic::Ordering::Relaxed),
+                            ) + approval_wait.unwrap_or_default(),
                             async {
                                 if agent_plugin_ids.is_empty() {
                                     self.registry.execute_tool(&call.name, safe_args).await
@@ -762,6 +1022,22 @@ impl SystemHandler {
                             "  🔧 Tool executed"
                         );
 
+                        let outcome = if success {
+                            ToolOutcome::Success
+                        } else {
+                            ToolOutcome::Failure(content.clone())
+                        };
+                        self.audit_tool_call(
+                            agent,
+                            engine_id,
+                            call,
+                            &audited_args,
+                            duration_ms,
+                            outcome,
+                            trace_id,
+                        )
+                        .await;
+
                         // Emit observability event
                         self.emit_event(
                             trace_id,
@@ -797,7 +1073,41 @@ impl SystemHandler {
 
     // ── Engine Dispatch Helpers (Rust Plugin / MCP Dual Dispatch) ──
 
+    /// `message` as sent to `engine_id`: image attachments carry their
+    /// base64 data when the engine supports vision.
+    async fn message_for_engine(&self, engine_id: &str, message: &ClotoMessage) -> ClotoMessage {
+        let mut message = message.clone();
+        let Some(ref attachments) = self.attachments else {
+            return message;
+        };
+        if !message
+            .attachments
+            .iter()
+            .any(cloto_shared::AttachmentRef::is_image)
+        {
+            return message;
+        }
+        let supports_vision = if let Some(plugin) = self.registry.get_engine(engine_id).await {
+            plugin
+                .as_reasoning()
+                .is_some_and(cloto_shared::ReasoningEngine::supports_vision)
+        } else if let Some(ref mcp) = self.registry.mcp_manager {
+            mcp.engine_supports_vision(engine_id).await
+        } else {
+            false
+        };
+        if supports_vision {
+            message.attachments = attachments.with_image_data(&message.attachments).await;
+        }
+        message
+    }
+
     /// Call engine's think() — routes to either Rust plugin or MCP server.
+    #[tracing::instrument(
+        name = "reasoning.think",
+        skip_all,
+        fields(engine_id = %engine_id, agent_id = %agent.id)
+    )]
     async fn engine_think(
         &self,
         engine_plugin: Option<&Arc<dyn Plugin>>,
@@ -811,9 +1121,12 @@ impl SystemHandler {
             let engine = plugin.as_reasoning().ok_or_else(|| {
                 anyhow::anyhow!("Plugin '{}' is not a ReasoningEngine", engine_id)
             })?;
-            return engine.think(agent, message, context).await;
+            return self
+                .think_streaming(engine, engine_id, agent, message, context)
+                .await;
         }
 
+        // MCP engines stream by emitting `ThoughtResponseChunk` notifications
         if let Some(mcp) = mcp_engine {
             let args = serde_json::json!({
                 "agent": serde_json::to_value(agent)?,
@@ -826,13 +1139,64 @@ impl SystemHandler {
                 }).collect::<Vec<_>>(),
             });
             let result = mcp.call_server_tool(engine_id, "think", args).await?;
-            return Self::extract_mcp_think_content(&result);
+            return result.think_content();
         }
 
         Err(anyhow::anyhow!("Engine '{}' not found", engine_id))
     }
 
+    /// Run `think_streaming()` on a Rust engine, forwarding each chunk as a
+    /// `ThoughtResponseChunk` event while the response is generated.
+    async fn think_streaming(
+        &self,
+        engine: &dyn cloto_shared::ReasoningEngine,
+        engine_id: &str,
+        agent: &AgentMetadata,
+        message: &ClotoMessage,
+        context: Vec<ClotoMessage>,
+    ) -> anyhow::Result<String> {
+        let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::channel::<String>(64);
+        let sender = self.sender.clone();
+        let agent_id = agent.id.clone();
+        let engine_id_owned = engine_id.to_string();
+        let source_message_id = message.id.clone();
+        let forwarder = tokio::spawn(async move {
+            while let Some(delta) = chunk_rx.recv().await {
+                if delta.is_empty() {
+                    continue;
+                }
+                let envelope = crate::EnvelopedEvent {
+                    event: Arc::new(ClotoEvent::new(ClotoEventData::ThoughtResponseChunk {
+                        agent_id: agent_id.clone(),
+                        engine_id: engine_id_owned.clone(),
+                        source_message_id: source_message_id.clone(),
+                        delta,
+                    })),
+                    issuer: None,
+                    correlation_id: None,
+                    depth: 0,
+                };
+                if sender.send(envelope).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let result = engine
+            .think_streaming(agent, message, context, chunk_tx)
+            .await;
+        // The engine dropped its sender; let queued chunks go out before the
+        // caller emits the final ThoughtResponse
+        let _ = forwarder.await;
+        result
+    }
+
     /// Call engine's think_with_tools() — routes to either Rust plugin or MCP server.
+    #[tracing::instrument(
+        name = "reasoning.think",
+        skip_all,
+        fields(engine_id = %engine_id, agent_id = %agent.id, tools = tools.len())
+    )]
     async fn engine_think_with_tools(
         &self,
         engine_plugin: Option<&Arc<dyn Plugin>>,
@@ -875,29 +1239,6 @@ impl SystemHandler {
         Err(anyhow::anyhow!("Engine '{}' not found", engine_id))
     }
 
-    /// Extract text content from MCP think() response.
-    fn extract_mcp_think_content(
-        result: &crate::managers::mcp_protocol::CallToolResult,
-    ) -> anyhow::Result<String> {
-        use crate::managers::mcp_protocol::ToolContent;
-        for content in &result.content {
-            if let ToolContent::Text { text } = content {
-                // Try to parse as JSON (may contain {"type":"final","content":"..."})
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
-                    if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
-                        return Err(anyhow::anyhow!("MCP engine error: {}", error));
-                    }
-                    if let Some(content) = json.get("content").and_then(|c| c.as_str()) {
-                        return Ok(content.to_string());
-                    }
-                }
-                // Fall back to raw text
-                return Ok(text.clone());
-            }
-        }
-        Err(anyhow::anyhow!("MCP engine returned no text content"))
-    }
-
     /// Parse ThinkResult from MCP think_with_tools() response.
     fn parse_mcp_think_result(
         result: &crate::managers::mcp_protocol::CallToolResult,
@@ -1004,6 +1345,7 @@ impl SystemHandler {
                                     content,
                                     timestamp,
                                     metadata: std::collections::HashMap::new(),
+                                    attachments: Vec::new(),
                                 })
                             })
                             .collect();
@@ -1128,6 +1470,132 @@ impl SystemHandler {
         None
     }
 
+    #[allow(clippy::too_many_arguments)]
+    async fn audit_tool_call(
+        &self,
+        agent: &AgentMetadata,
+        engine_id: &str,
+        call: &ToolCall,
+        arguments: &serde_json::Value,
+        duration_ms: u64,
+        outcome: ToolOutcome,
+        trace_id: ClotoId,
+    ) {
+        let Some(ref tool_audit) = self.tool_audit else {
+            return;
+        };
+        let provider = match self.registry.native_tool_owner(&call.name) {
+            Some(plugin_id) => Some(plugin_id),
+            None => match self.registry.mcp_manager {
+                Some(ref mcp) => mcp.get_tool_server_id(&call.name).await,
+                None => None,
+            },
+        };
+        tool_audit.record(ToolExecution {
+            agent_id: &agent.id,
+            engine_id,
+            provider,
+            tool_name: &call.name,
+            arguments,
+            duration_ms,
+            outcome,
+            trace_id,
+        });
+    }
+
+    /// Keep the tool history of a finished agentic run with the message it answered.
+    fn record_tool_history(
+        &self,
+        agent: &AgentMetadata,
+        engine_id: &str,
+        message: &ClotoMessage,
+        tool_history: &[serde_json::Value],
+    ) {
+        if let Some(ref tool_audit) = self.tool_audit {
+            tool_audit.record_history(&agent.id, engine_id, &message.id, tool_history);
+        }
+    }
+
+    /// Recent messages of the session `msg` belongs to (its `session_id`
+    /// metadata, else the agent's active session), oldest first. `None` when
+    /// the agent has no session yet.
+    async fn session_context(
+        &self,
+        pool: &crate::db::DbPool,
+        agent_id: &str,
+        msg: &ClotoMessage,
+    ) -> Option<Vec<ClotoMessage>> {
+        let session = match msg.metadata.get("session_id") {
+            Some(id) => crate::db::get_chat_session(pool, id).await,
+            None => {
+                crate::db::get_active_chat_session(
+                    pool,
+                    agent_id,
+                    crate::managers::summarizer::DEFAULT_THREAD_USER,
+                )
+                .await
+            }
+        };
+        let session = match session {
+            Ok(session) => session.filter(|s| s.agent_id == agent_id)?,
+            Err(e) => {
+                error!(agent_id = %agent_id, error = %e, "❌ Failed to load chat session");
+                return None;
+            }
+        };
+
+        let limit = self.memory_context_limit;
+        #[allow(clippy::cast_possible_wrap)]
+        let rows = match crate::db::get_chat_session_messages(
+            pool,
+            &session.id,
+            None,
+            limit as i64 + 1,
+        )
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(session_id = %session.id, error = %e, "❌ Failed to load session history");
+                return Some(vec![]);
+            }
+        };
+        let mut context: Vec<ClotoMessage> = rows
+            .into_iter()
+            .rev()
+            .filter(|row| row.id != msg.id)
+            .map(|row| ClotoMessage {
+                source: match row.source.as_str() {
+                    "user" => cloto_shared::MessageSource::User {
+                        id: row.user_id.clone(),
+                        name: row.user_id.clone(),
+                    },
+                    "agent" => cloto_shared::MessageSource::Agent {
+                        id: row.agent_id.clone(),
+                    },
+                    _ => cloto_shared::MessageSource::System,
+                },
+                target_agent: None,
+                content: crate::managers::summarizer::message_text(&row),
+                timestamp: chrono::DateTime::from_timestamp_millis(row.created_at)
+                    .unwrap_or_else(Utc::now),
+                metadata: std::collections::HashMap::new(),
+                attachments: Vec::new(),
+                id: row.id,
+            })
+            .collect();
+        // The dashboard saves the user's message before sending it
+        if context.last().is_some_and(|m| {
+            matches!(m.source, cloto_shared::MessageSource::User { .. }) && m.content == msg.content
+        }) {
+            context.pop();
+        }
+        if context.len() > limit {
+            context.drain(..context.len() - limit);
+        }
+        Some(context)
+    }
+
     async fn emit_event(&self, trace_id: ClotoId, data: ClotoEventData) {
         let envelope = crate::EnvelopedEvent {
             event: Arc::new(ClotoEvent::with_trace(trace_id, data)),
@@ -1161,6 +1629,7 @@ impl Plugin for SystemHandler {
             is_active: true,
             is_configured: true,
             required_config_keys: vec![],
+            config_schema: vec![],
             action_icon: None,
             action_target: None,
             icon_data: None,
@@ -1169,6 +1638,7 @@ impl Plugin for SystemHandler {
             required_permissions: vec![],
             provided_capabilities: vec![],
             provided_tools: vec![],
+            subscribes: vec!["MessageReceived".to_string()],
         }
     }
 
@@ -1177,8 +1647,19 @@ impl Plugin for SystemHandler {
         event: &ClotoEvent,
     ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
         if let cloto_shared::ClotoEventData::MessageReceived(msg) = &event.data {
-            // Only trigger thinking for messages from users to prevent agent-agent loops
-            if matches!(msg.source, cloto_shared::MessageSource::User { .. }) {
+            // Only trigger thinking for messages from users, or from agents
+            // when the kernel routed an AgentMessage (`target_agent` set);
+            // other agent output must not trigger another round of thinking
+            let addressed = match msg.source {
+                cloto_shared::MessageSource::User { .. } => true,
+                cloto_shared::MessageSource::Agent { .. } => msg.target_agent.is_some(),
+                cloto_shared::MessageSource::System => false,
+            };
+            if addressed {
+                let Some(_run) = self.drain.enter() else {
+                    warn!(message_id = %msg.id, "Kernel is shutting down; message not processed");
+                    return Ok(None);
+                };
                 let msg = msg.clone();
                 self.handle_message(msg).await?;
             }
@@ -152,6 +152,8 @@ pub async fn install(prefix: PathBuf, service: bool, user: Option<String>) -> an
     if service {
         if cfg!(windows) {
             println!("  As service:       sc.exe start Cloto");
+        } else if cfg!(target_os = "macos") {
+            println!("  As service:       sudo launchctl bootstrap system /Library/LaunchDaemons/com.cloto.system.plist");
         } else {
             println!("  As service:       sudo systemctl start cloto");
         }
@@ -1,16 +1,24 @@
 pub mod capabilities;
+pub mod check;
 pub mod cli;
 pub mod config;
+pub mod config_reload;
 pub mod consensus;
 pub mod db;
+pub mod db_metrics;
+pub mod drain;
 pub mod events;
 pub mod handlers;
 pub mod installer;
 pub mod managers;
 pub mod middleware;
 pub mod platform;
+pub mod secrets;
+pub mod telemetry;
 pub mod test_utils;
 pub mod validation;
+pub mod vision;
+pub mod webhook;
 
 // Re-export audit log and permission request types for external use
 pub use db::{
@@ -18,8 +26,8 @@ pub use db::{
     query_audit_logs, update_permission_request, write_audit_log, AuditLogEntry, PermissionRequest,
 };
 
+use crate::db::DbPool;
 use cloto_shared::ClotoEvent;
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, Notify, RwLock};
@@ -50,22 +58,45 @@ pub struct DynamicRouter {
 }
 
 pub struct AppState {
-    pub tx: broadcast::Sender<Arc<ClotoEvent>>,
+    pub tx: broadcast::Sender<events::SerializedEvent>,
     pub registry: Arc<managers::PluginRegistry>,
     pub event_tx: mpsc::Sender<EnvelopedEvent>,
-    pub pool: SqlitePool,
+    pub pool: DbPool,
     pub agent_manager: managers::AgentManager,
     pub plugin_manager: Arc<managers::PluginManager>,
     pub mcp_manager: Arc<managers::McpClientManager>,
     pub dynamic_router: Arc<DynamicRouter>,
+    /// Configuration as loaded at startup.
     pub config: config::AppConfig,
-    pub event_history: Arc<RwLock<VecDeque<Arc<ClotoEvent>>>>,
+    /// Effective configuration, including settings applied by hot reload.
+    pub runtime_config: Arc<std::sync::RwLock<config::AppConfig>>,
+    pub event_history: Arc<RwLock<VecDeque<events::SerializedEvent>>>,
     pub metrics: Arc<managers::SystemMetrics>,
     pub rate_limiter: Arc<middleware::RateLimiter>,
+    /// Shutdown requested. Starts the drain; the server stops once it ends.
     pub shutdown: Arc<Notify>,
+    /// In-flight agentic runs and tool calls, waited for on shutdown.
+    pub drain: Arc<drain::DrainTracker>,
     /// In-memory cache of revoked API key hashes (SHA-256 fingerprints).
     /// Loaded from DB at startup; updated on POST /api/system/invalidate-key.
     pub revoked_keys: Arc<std::sync::RwLock<std::collections::HashSet<String>>>,
+    /// Scoped API keys by SHA-256 hash. Loaded from DB at startup; updated by
+    /// the /api/system/keys handlers.
+    pub api_keys: Arc<std::sync::RwLock<std::collections::HashMap<String, handlers::ApiKeyScope>>>,
+    /// Dashboard users by ID. Loaded from DB at startup; updated by the
+    /// /api/auth/users handlers.
+    pub users:
+        Arc<std::sync::RwLock<std::collections::HashMap<String, handlers::auth::DashboardUser>>>,
+    /// Signs dashboard session cookies; regenerated on every start.
+    pub session_key: Arc<handlers::auth::SessionKey>,
+    /// Recent requests slower than `config.slow_request_threshold_ms`.
+    pub slow_requests: Arc<middleware::SlowRequestLog>,
+    /// Group chat rooms (several agents in one conversation).
+    pub rooms: Arc<managers::RoomManager>,
+    /// Rolling summaries of long chat threads.
+    pub summarizer: Arc<managers::ConversationSummarizer>,
+    /// Chat upload processing (image sanitizing, text extraction).
+    pub attachments: Arc<managers::AttachmentProcessor>,
 }
 
 pub enum AppError {
@@ -73,6 +104,14 @@ pub enum AppError {
     Internal(anyhow::Error),
     NotFound(String),
     Validation(String),
+    /// Request body over the route's limit (bytes).
+    PayloadTooLarge(usize),
+    /// Load shedding: too many requests in flight. Carries the Retry-After seconds.
+    Overloaded(u64),
+    /// The kernel is draining for shutdown and takes no new work.
+    ShuttingDown,
+    /// Waiting on the agent (or another upstream) ran out of time.
+    Timeout(String),
 }
 
 impl axum::response::IntoResponse for AppError {
@@ -106,6 +145,36 @@ impl axum::response::IntoResponse for AppError {
                 "ValidationError".to_string(),
                 m,
             ),
+            AppError::PayloadTooLarge(limit) => (
+                axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+                "PayloadTooLarge".to_string(),
+                format!("Request body exceeds the {} byte limit", limit),
+            ),
+            AppError::ShuttingDown => (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                "ShuttingDown".to_string(),
+                "The kernel is shutting down".to_string(),
+            ),
+            AppError::Timeout(m) => (
+                axum::http::StatusCode::GATEWAY_TIMEOUT,
+                "Timeout".to_string(),
+                m,
+            ),
+            AppError::Overloaded(retry_after) => {
+                let body = axum::Json(serde_json::json!({
+                    "status": "error",
+                    "error": {
+                        "type": "Overloaded",
+                        "message": "The kernel is busy; retry shortly"
+                    }
+                }));
+                return (
+                    axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                    [(axum::http::header::RETRY_AFTER, retry_after.to_string())],
+                    body,
+                )
+                    .into_response();
+            }
         };
 
         let body = axum::Json(serde_json::json!({
@@ -140,6 +209,115 @@ impl From<sqlx::Error> for AppError {
 
 pub type AppResult<T> = Result<T, AppError>;
 
+// ── Embedding Host Handle ──
+
+/// Handle for an embedding host (the Tauri desktop shell) to talk to a kernel
+/// started with [`run_kernel`] in the same process.
+#[derive(Clone)]
+pub struct KernelHandle {
+    event_tx: mpsc::Sender<EnvelopedEvent>,
+    shutdown: Arc<Notify>,
+}
+
+static KERNEL_HANDLE: std::sync::OnceLock<KernelHandle> = std::sync::OnceLock::new();
+
+/// The running kernel, once [`run_kernel`] has finished initialization.
+#[must_use]
+pub fn kernel_handle() -> Option<KernelHandle> {
+    KERNEL_HANDLE.get().cloned()
+}
+
+impl KernelHandle {
+    /// Maintenance handshake: announce `reason` to subscribers, write the
+    /// `.maintenance` marker and stop the kernel. Used before applying updates.
+    pub async fn enter_maintenance(&self, reason: &str) -> anyhow::Result<()> {
+        let envelope = EnvelopedEvent::system(cloto_shared::ClotoEventData::SystemNotification(
+            reason.to_string(),
+        ));
+        if let Err(e) = self.event_tx.send(envelope).await {
+            tracing::error!("Failed to send maintenance notification event: {}", e);
+        }
+        write_maintenance_marker()?;
+        tracing::info!("🚧 Maintenance mode engaged by host: {}", reason);
+        self.shutdown.notify_waiters();
+        Ok(())
+    }
+}
+
+/// Create the `.maintenance` marker next to the executable.
+/// Atomic write via tmp + rename to prevent symlink attacks.
+pub fn write_maintenance_marker() -> std::io::Result<()> {
+    let maint = config::exe_dir().join(".maintenance");
+    let suffix: u64 = rand::random();
+    let maint_tmp = config::exe_dir().join(format!(".maintenance_{:016x}.tmp", suffix));
+    std::fs::write(&maint_tmp, "active").and_then(|()| std::fs::rename(&maint_tmp, &maint))
+}
+
+/// Headless servers expose the browser dashboard to whoever can reach the
+/// port, so they must not run with the debug auth bypass of a missing key.
+fn check_headless(config: &config::AppConfig) -> anyhow::Result<()> {
+    if config.admin_api_key.is_none() {
+        anyhow::bail!(
+            "Headless mode requires CLOTO_API_KEY (the browser dashboard signs in with it)"
+        );
+    }
+    if !handlers::assets::has_dashboard() {
+        tracing::warn!(
+            "⚠️  No dashboard build embedded (dashboard/dist is empty); only the API is served"
+        );
+    }
+    Ok(())
+}
+
+/// Location of `mcp.toml`: `CLOTO_MCP_CONFIG`, else `data/mcp.toml` next to
+/// the binary. Relative paths that do not exist from the working directory
+/// are resolved against the project root (handles `cargo tauri dev` where
+/// CWD differs from the project root).
+pub(crate) fn mcp_config_path(config: &config::AppConfig) -> String {
+    let config_path = config.mcp_config_path.clone().unwrap_or_else(|| {
+        config::exe_dir()
+            .join("data")
+            .join("mcp.toml")
+            .to_string_lossy()
+            .to_string()
+    });
+    let p = std::path::Path::new(&config_path);
+    if p.is_relative() && !p.exists() {
+        // Walk up from exe_dir to find the workspace root (Cargo.toml)
+        managers::McpClientManager::resolve_project_path(p).unwrap_or(config_path)
+    } else {
+        config_path
+    }
+}
+
+/// Without a desktop shell, service managers stop the kernel with signals.
+fn spawn_signal_handler(shutdown: Arc<Notify>) {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(mut term) => {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => {}
+                        _ = term.recv() => {}
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to install SIGTERM handler: {}", e);
+                    let _ = tokio::signal::ctrl_c().await;
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+        tracing::info!("🛑 Termination signal received");
+        shutdown.notify_waiters();
+    });
+}
+
 /// Kernel 起動用のエントリポイント
 #[allow(clippy::too_many_lines)]
 pub async fn run_kernel() -> anyhow::Result<()> {
@@ -175,6 +353,20 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         tracing::warn!("    Set CLOTO_API_KEY in .env or environment to enable admin operations.");
     }
 
+    if config.headless {
+        check_headless(&config)?;
+    }
+
+    // Probe hardware once up front so MCP servers and the API get cached results
+    let hardware = tokio::task::spawn_blocking(platform::hardware).await?;
+    info!(
+        "🖥️  Hardware: {} ({} cores, {} MB RAM), GPU: {}",
+        hardware.cpu.brand,
+        hardware.cpu.logical_cores,
+        hardware.total_memory_mb,
+        hardware.gpu_backend()
+    );
+
     // 0. Ensure parent directory of DB file exists (for deployed layout)
     if let Some(path_str) = config.database_url.strip_prefix("sqlite:") {
         let db_path = std::path::Path::new(path_str);
@@ -199,10 +391,12 @@ pub async fn run_kernel() -> anyhow::Result<()> {
     }
 
     // 1. データベースの初期化
-    use sqlx::sqlite::SqliteConnectOptions;
-    use std::str::FromStr;
-    let opts = SqliteConnectOptions::from_str(&config.database_url)?.create_if_missing(true);
-    let pool = sqlx::SqlitePool::connect_with(opts).await?;
+    let pool = db::connect(
+        &config.database_url,
+        config.db_pool_size,
+        std::time::Duration::from_secs(config.db_acquire_timeout_secs),
+    )
+    .await?;
     db::init_db(&pool, &config.database_url).await?;
 
     // 2. Plugin Manager Setup
@@ -214,6 +408,7 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         config.max_event_depth,
     )?;
     plugin_manager_obj.shutdown = shutdown.clone();
+    let drain = Arc::new(drain::DrainTracker::new());
 
     // 3. Channel Setup
     let (event_tx, event_rx) = tokio::sync::mpsc::channel::<EnvelopedEvent>(100);
@@ -221,65 +416,110 @@ pub async fn run_kernel() -> anyhow::Result<()> {
     let plugin_manager = Arc::new(plugin_manager_obj);
 
     // 3b. MCP Client Manager (created early so PluginRegistry can reference it)
-    let mcp_manager = Arc::new(managers::McpClientManager::new(
+    let (tx, _rx) = tokio::sync::broadcast::channel(100);
+    let mut mcp_manager_obj = managers::McpClientManager::new(pool.clone(), config.yolo_mode);
+    mcp_manager_obj.set_event_tx(event_tx.clone());
+    mcp_manager_obj.set_event_bus(tx.clone());
+    mcp_manager_obj.set_tool_approval_timeout_secs(config.tool_approval_timeout_secs);
+    mcp_manager_obj.set_drain_tracker(drain.clone());
+    let tool_audit = Arc::new(managers::tool_audit::ToolAudit::new(
         pool.clone(),
-        config.yolo_mode,
+        &config.audit_redact_keys,
     ));
+    mcp_manager_obj.set_tool_audit(tool_audit.clone());
+    match db::expire_pending_tool_invocation_requests(&pool).await {
+        Ok(0) => {}
+        Ok(n) => tracing::info!(
+            "Expired {} tool approval request(s) left over from the last run",
+            n
+        ),
+        Err(e) => tracing::warn!("Failed to expire stale tool approval requests: {}", e),
+    }
+    let mcp_manager = Arc::new(mcp_manager_obj);
 
     // 4. Initialize External Plugins
     let mut registry = plugin_manager.initialize_all().await?;
     registry.set_mcp_manager(mcp_manager.clone());
+    registry.set_event_retry(config.event_retry_max, config.event_retry_backoff_ms);
+    registry.set_dead_letter_pool(pool.clone());
     let registry_arc = Arc::new(registry);
 
     // 5. Managers & Internal Handlers
     let agent_manager = AgentManager::new(pool.clone());
-    let (tx, _rx) = tokio::sync::broadcast::channel(100);
 
+    // Built-in web plugins
+    match webhook::WebhookPlugin::load(&webhook::webhooks_config_path(&config), event_tx.clone()) {
+        Ok(Some(plugin)) => {
+            registry_arc
+                .plugins
+                .write()
+                .await
+                .insert(webhook::PLUGIN_ID.to_string(), Arc::new(plugin));
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!(error = %e, "Failed to load webhooks config"),
+    }
+
+    // Routes of web plugins are served under /api/plugin/*
+    let mut plugin_routes = Router::new();
+    for plugin in registry_arc.plugins.load().values() {
+        if let Some(web) = plugin.as_web() {
+            plugin_routes = web.register_routes(plugin_routes);
+        }
+    }
     let dynamic_router = Arc::new(DynamicRouter {
-        router: tokio::sync::RwLock::new(Router::new()),
+        router: tokio::sync::RwLock::new(plugin_routes),
     });
 
     let metrics = Arc::new(managers::SystemMetrics::new());
     let event_history = Arc::new(tokio::sync::RwLock::new(VecDeque::new()));
 
     // 🔌 System Handler の登録
-    let system_handler = Arc::new(SystemHandler::new(
+    let summarizer = Arc::new(managers::ConversationSummarizer::new(
+        pool.clone(),
         registry_arc.clone(),
         agent_manager.clone(),
-        config.default_agent_id.clone(),
-        event_tx.clone(),
-        config.memory_context_limit,
-        metrics.clone(),
-        config.consensus_engines.clone(),
-        config.max_agentic_iterations,
-        config.tool_execution_timeout_secs,
+        config.summary_threshold,
+    ));
+    let attachments = Arc::new(managers::AttachmentProcessor::new(
+        pool.clone(),
+        mcp_manager.clone(),
+        std::env::var("CLOTO_TRANSCRIBE_SERVER")
+            .ok()
+            .filter(|s| !s.is_empty()),
+        std::env::var("CLOTO_ATTACHMENT_MAX_DIMENSION")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&d| d > 0)
+            .unwrap_or(managers::attachments::DEFAULT_MAX_DIMENSION),
     ));
+    let system_handler = Arc::new(
+        SystemHandler::new(
+            registry_arc.clone(),
+            agent_manager.clone(),
+            config.default_agent_id.clone(),
+            event_tx.clone(),
+            config.memory_context_limit,
+            metrics.clone(),
+            config.consensus_engines.clone(),
+            config.max_agentic_iterations,
+            config.tool_execution_timeout_secs,
+        )
+        .with_summarizer(summarizer.clone())
+        .with_attachments(attachments.clone())
+        .with_chat_sessions(pool.clone())
+        .with_drain_tracker(drain.clone())
+        .with_tool_audit(tool_audit),
+    );
 
     {
         let mut plugins = registry_arc.plugins.write().await;
-        plugins.insert("kernel.system".to_string(), system_handler);
+        plugins.insert("kernel.system".to_string(), system_handler.clone());
     }
 
     // Load MCP servers from config file (mcp.toml)
     {
-        let config_path = config.mcp_config_path.clone().unwrap_or_else(|| {
-            config::exe_dir()
-                .join("data")
-                .join("mcp.toml")
-                .to_string_lossy()
-                .to_string()
-        });
-        // Resolve relative config paths against the project root (handles
-        // cargo tauri dev where CWD differs from project root).
-        let config_path = {
-            let p = std::path::Path::new(&config_path);
-            if p.is_relative() && !p.exists() {
-                // Walk up from exe_dir to find the workspace root (Cargo.toml)
-                managers::McpClientManager::resolve_project_path(p).unwrap_or(config_path)
-            } else {
-                config_path
-            }
-        };
+        let config_path = mcp_config_path(&config);
         if let Err(e) = mcp_manager.load_config_file(&config_path).await {
             tracing::warn!(error = %e, "Failed to load MCP config file");
         }
@@ -291,7 +531,7 @@ pub async fn run_kernel() -> anyhow::Result<()> {
     }
 
     // 5. Rate Limiter & App State
-    let rate_limiter = Arc::new(middleware::RateLimiter::new(10, 20));
+    let rate_limiter = Arc::new(middleware::RateLimiter::from_config(&config.rate_limits));
 
     // Load revoked key hashes into memory
     let revoked_keys = {
@@ -309,6 +549,60 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         Arc::new(std::sync::RwLock::new(set))
     };
 
+    let api_keys = {
+        let mut map = std::collections::HashMap::new();
+        match db::list_api_keys(&pool).await {
+            Ok(rows) => {
+                for row in rows {
+                    match row.scope.parse() {
+                        Ok(scope) => {
+                            rate_limiter.register_key(&row.key_hash, &row.id, row.rate_quota());
+                            map.insert(row.key_hash, scope);
+                        }
+                        Err(e) => tracing::warn!(id = %row.id, error = %e, "Skipping API key"),
+                    }
+                }
+                if !map.is_empty() {
+                    info!(count = map.len(), "🔑 Loaded scoped API keys");
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to load scoped API keys"),
+        }
+        Arc::new(std::sync::RwLock::new(map))
+    };
+
+    let users = {
+        let mut map = std::collections::HashMap::new();
+        match db::list_users(&pool).await {
+            Ok(rows) => {
+                for row in rows {
+                    match row.role.parse() {
+                        Ok(role) => {
+                            map.insert(
+                                row.id,
+                                handlers::auth::DashboardUser {
+                                    username: row.username,
+                                    role,
+                                },
+                            );
+                        }
+                        Err(e) => tracing::warn!(id = %row.id, error = %e, "Skipping user"),
+                    }
+                }
+                if !map.is_empty() {
+                    info!(count = map.len(), "👤 Loaded dashboard users");
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to load dashboard users"),
+        }
+        Arc::new(std::sync::RwLock::new(map))
+    };
+
+    let _ = KERNEL_HANDLE.set(KernelHandle {
+        event_tx: event_tx.clone(),
+        shutdown: shutdown.clone(),
+    });
+
     let app_state = Arc::new(AppState {
         tx: tx.clone(),
         registry: registry_arc.clone(),
@@ -318,12 +612,28 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         plugin_manager: plugin_manager.clone(),
         mcp_manager: mcp_manager.clone(),
         dynamic_router: dynamic_router.clone(),
+        runtime_config: Arc::new(std::sync::RwLock::new(config.clone())),
         config: config.clone(),
         event_history: event_history.clone(),
         metrics: metrics.clone(),
         rate_limiter: rate_limiter.clone(),
         shutdown,
+        drain,
         revoked_keys,
+        api_keys,
+        users,
+        session_key: Arc::new(handlers::auth::SessionKey::generate()),
+        slow_requests: Arc::new(middleware::SlowRequestLog::new(
+            config.slow_request_threshold_ms,
+        )),
+        rooms: Arc::new(managers::RoomManager::new(
+            pool.clone(),
+            agent_manager.clone(),
+            event_tx.clone(),
+            tx.clone(),
+        )),
+        summarizer,
+        attachments,
     });
 
     // 6. Consensus Orchestrator (kernel-level, replaces core.moderator plugin)
@@ -343,7 +653,7 @@ pub async fn run_kernel() -> anyhow::Result<()> {
     let consensus_orchestrator = consensus::ConsensusOrchestrator::new(consensus_config);
 
     // 6a. Event Loop
-    let processor = Arc::new(EventProcessor::new(
+    let mut processor = EventProcessor::new(
         registry_arc.clone(),
         plugin_manager.clone(),
         agent_manager.clone(),
@@ -353,28 +663,84 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         config.event_history_size,
         config.event_retention_hours,
         Some(consensus_orchestrator),
-    ));
+    )
+    .with_element_resolver(Arc::new(vision::ElementResolver::new(
+        mcp_manager.clone(),
+        std::env::var("CLOTO_VISION_SERVER").unwrap_or_else(|_| "vision.screen".to_string()),
+    )));
+    if config.event_journal_enabled {
+        info!(
+            retention_days = config.event_journal_retention_days,
+            "📒 Event journal enabled"
+        );
+        processor = processor.with_journal(events::EventJournal::spawn(
+            pool.clone(),
+            config.event_journal_retention_days,
+        ));
+    }
+    let processor = Arc::new(processor);
 
     // Start event history cleanup task
     processor
         .clone()
         .spawn_cleanup_task(app_state.shutdown.clone());
 
-    // 6a. Active Heartbeat task (ping all enabled agents every 30s)
+    // 6a'. Config hot reload (.env / cloto.toml polling)
+    let cors_origins: config_reload::CorsOrigins =
+        Arc::new(std::sync::RwLock::new(config.cors_origins.clone()));
+    let env_file = config::env_file_path();
+    if config.config_reload_interval_secs == 0
+        || (env_file.is_none() && config.config_file.is_none())
+    {
+        info!("Config hot reload disabled (no config files or interval is 0)");
+    } else {
+        config_reload::spawn_config_watcher(
+            config_reload::ReloadTargets {
+                state: app_state.clone(),
+                processor: processor.clone(),
+                system_handler,
+                cors_origins: cors_origins.clone(),
+            },
+            env_file,
+            config.config_file.clone(),
+            config.config_reload_interval_secs,
+        );
+    }
+
+    // 6a. Active Heartbeat task (per-agent engine pings, default every 30s)
     let heartbeat_interval = std::env::var("HEARTBEAT_INTERVAL_SECS")
         .unwrap_or_else(|_| "30".to_string())
         .parse::<u64>()
         .unwrap_or(30);
-    EventProcessor::spawn_heartbeat_task(
+    managers::HeartbeatMonitor::new(
         agent_manager.clone(),
-        heartbeat_interval,
-        app_state.shutdown.clone(),
-    );
+        registry_arc.clone(),
+        event_tx.clone(),
+        std::time::Duration::from_secs(heartbeat_interval),
+    )
+    .spawn(app_state.shutdown.clone());
+
+    // 6a. Long-term memory compaction (CLOTO_MEMORY_COMPACTION_THRESHOLD)
+    managers::MemoryCompactor::new(
+        mcp_manager.clone(),
+        registry_arc.clone(),
+        agent_manager.clone(),
+        config.memory_compaction_threshold,
+    )
+    .spawn(app_state.shutdown.clone());
 
     // 6b. MCP health monitor — auto-restart dead servers (bug-142)
     Arc::clone(&mcp_manager).spawn_health_monitor(app_state.shutdown.clone());
 
-    // 6c. Cron job scheduler (Layer 2: Autonomous Trigger)
+    // 6c. Cron job scheduler (Layer 2: Autonomous Trigger). The run tracker
+    // also completes manual runs, so it starts either way.
+    managers::scheduler::spawn_run_tracker(
+        pool.clone(),
+        event_tx.clone(),
+        tx.subscribe(),
+        config.cron_check_interval_secs,
+        app_state.shutdown.clone(),
+    );
     if config.cron_enabled {
         managers::scheduler::spawn_cron_task(
             pool.clone(),
@@ -384,16 +750,31 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         );
     }
 
-    // 6d. Internal LLM Proxy (MGP §13.4 — centralized API key management)
-    managers::llm_proxy::spawn_llm_proxy(
-        pool.clone(),
-        config.llm_proxy_port,
-        app_state.shutdown.clone(),
-    );
+    // 6d. Draining shutdown: on a shutdown request, wait for in-flight
+    // agentic runs before stopping what they depend on (event loop, LLM
+    // proxy, listener)
+    let drained = Arc::new(Notify::new());
+    {
+        let state = app_state.clone();
+        let drained = drained.clone();
+        let requested = state.shutdown.clone().notified_owned();
+        tokio::spawn(async move {
+            requested.await;
+            let timeout = state
+                .runtime_config
+                .read()
+                .map_or(state.config.shutdown_drain_secs, |c| c.shutdown_drain_secs);
+            drain::drain(&state.drain, std::time::Duration::from_secs(timeout)).await;
+            drained.notify_waiters();
+        });
+    }
+
+    // 6e. Internal LLM Proxy (MGP §13.4 — centralized API key management)
+    managers::llm_proxy::spawn_llm_proxy(pool.clone(), config.llm_proxy_port, drained.clone());
 
     let event_tx_clone = event_tx.clone();
     let processor_clone = processor.clone();
-    let shutdown_clone = app_state.shutdown.clone();
+    let shutdown_clone = drained.clone();
     tokio::spawn(async move {
         tokio::select! {
             () = shutdown_clone.notified() => {
@@ -423,11 +804,77 @@ pub async fn run_kernel() -> anyhow::Result<()> {
 
     // 7. Web Server
 
-    // Admin endpoints: rate-limited (10 req/s, burst 20)
-    let admin_routes = Router::new()
+    // Admin endpoints: rate-limited per route group (AppConfig::rate_limits)
+    let shutdown_routes = Router::new()
         .route("/system/shutdown", post(handlers::shutdown_handler))
+        .layer(axum::middleware::from_fn_with_state(
+            (app_state.clone(), middleware::RouteGroup::Shutdown),
+            middleware::rate_limit_middleware,
+        ))
+        .layer(axum::extract::DefaultBodyLimit::max(
+            middleware::JSON_BODY_LIMIT,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware::JSON_BODY_LIMIT,
+            middleware::body_limit_middleware,
+        ));
+
+    // M-08: chat endpoints are rate limited separately so management calls
+    // cannot starve them (and vice versa)
+    let chat_routes = Router::new()
+        .route("/chat", post(handlers::chat_handler))
+        // Chat persistence endpoints
+        .route(
+            "/chat/:agent_id/messages",
+            get(handlers::chat::get_messages)
+                .post(handlers::chat::post_message)
+                .delete(handlers::chat::delete_messages),
+        )
+        .route(
+            "/chat/:agent_id/sessions",
+            get(handlers::chat::list_sessions).post(handlers::chat::create_session),
+        )
+        .route(
+            "/chat/:agent_id/sessions/:session_id/activate",
+            post(handlers::chat::activate_session),
+        )
+        .route(
+            "/chat/:agent_id/sessions/:session_id/archive",
+            post(handlers::chat::archive_session),
+        )
+        .route(
+            "/chat/attachments/:attachment_id",
+            get(handlers::chat::get_attachment),
+        )
+        // Group chat rooms
+        .route(
+            "/rooms/:id/messages",
+            get(handlers::get_room_messages).post(handlers::post_room_message),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(middleware::InFlightLimiter::new(
+                "chat",
+                config.max_in_flight_chat,
+            )),
+            middleware::in_flight_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            (app_state.clone(), middleware::RouteGroup::Chat),
+            middleware::rate_limit_middleware,
+        ))
+        // Chat messages carry inline (base64) attachments
+        .layer(axum::extract::DefaultBodyLimit::max(
+            middleware::ATTACHMENT_BODY_LIMIT,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware::ATTACHMENT_BODY_LIMIT,
+            middleware::body_limit_middleware,
+        ));
+
+    let admin_routes = Router::new()
         .route("/plugins/apply", post(handlers::apply_plugin_settings))
         .route("/plugins/:id/config", post(handlers::update_plugin_config))
+        .route("/plugins/:id/reload", post(handlers::reload_plugin))
         .route(
             "/plugins/:id/permissions",
             get(handlers::get_plugin_permissions).delete(handlers::revoke_permission_handler),
@@ -442,7 +889,19 @@ pub async fn run_kernel() -> anyhow::Result<()> {
             post(handlers::update_agent).delete(handlers::delete_agent),
         )
         .route("/agents/:id/power", post(handlers::power_toggle))
+        .route(
+            "/agents/:id/plugins",
+            get(handlers::get_agent_plugins).put(handlers::put_agent_plugins),
+        )
         .route("/events/publish", post(handlers::post_event_handler))
+        .route(
+            "/events/dead-letter",
+            get(handlers::list_dead_letter_events),
+        )
+        .route(
+            "/events/dead-letter/:id/replay",
+            post(handlers::replay_dead_letter_event),
+        )
         // Cron job management (Layer 2: Autonomous Trigger)
         .route(
             "/cron/jobs",
@@ -451,6 +910,13 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         .route("/cron/jobs/:id", delete(handlers::delete_cron_job))
         .route("/cron/jobs/:id/toggle", post(handlers::toggle_cron_job))
         .route("/cron/jobs/:id/run", post(handlers::run_cron_job_now))
+        .route("/cron/jobs/:id/runs", get(handlers::list_cron_job_runs))
+        // Group chat room management
+        .route(
+            "/rooms",
+            get(handlers::list_rooms).post(handlers::create_room),
+        )
+        .route("/rooms/:id", delete(handlers::delete_room))
         // LLM Provider management (MGP §13.4 — centralized key management)
         .route("/llm/providers", get(handlers::list_llm_providers))
         .route(
@@ -462,19 +928,10 @@ pub async fn run_kernel() -> anyhow::Result<()> {
             post(handlers::approve_permission),
         )
         .route("/permissions/:id/deny", post(handlers::deny_permission))
-        // M-08: chat_handler moved here to apply rate limiting
-        .route("/chat", post(handlers::chat_handler))
-        // Chat persistence endpoints
-        .route(
-            "/chat/:agent_id/messages",
-            get(handlers::chat::get_messages)
-                .post(handlers::chat::post_message)
-                .delete(handlers::chat::delete_messages),
-        )
-        .route(
-            "/chat/attachments/:attachment_id",
-            get(handlers::chat::get_attachment),
-        )
+        // Per-call tool approval (human-in-the-loop)
+        .route("/tool-calls/pending", get(handlers::get_pending_tool_calls))
+        .route("/tool-calls/:id/approve", post(handlers::approve_tool_call))
+        .route("/tool-calls/:id/deny", post(handlers::deny_tool_call))
         // MCP dynamic server management
         .route(
             "/mcp/servers",
@@ -500,6 +957,10 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         )
         .route("/mcp/servers/:name/start", post(handlers::start_mcp_server))
         .route("/mcp/servers/:name/stop", post(handlers::stop_mcp_server))
+        .route(
+            "/mcp/servers/:name/models",
+            get(handlers::get_mcp_server_models),
+        )
         // Settings
         .route(
             "/settings/yolo",
@@ -507,22 +968,56 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         )
         // API key invalidation
         .route("/system/invalidate-key", post(handlers::invalidate_api_key))
+        .route(
+            "/system/keys",
+            get(handlers::list_api_keys).post(handlers::create_api_key),
+        )
+        .route("/system/keys/:id", delete(handlers::delete_api_key))
+        // Dashboard accounts; sign-in shares the management rate limit
+        .route(
+            "/auth/users",
+            get(handlers::list_users).post(handlers::create_user),
+        )
+        .route("/auth/users/:id", delete(handlers::delete_user))
+        .route("/auth/login", post(handlers::login))
+        .route("/auth/logout", post(handlers::logout))
+        .route("/audit", get(handlers::get_audit_logs))
+        .route("/tools/invocations", get(handlers::get_tool_invocations))
         .layer(axum::middleware::from_fn_with_state(
-            app_state.clone(),
+            (app_state.clone(), middleware::RouteGroup::Management),
             middleware::rate_limit_middleware,
-        ));
+        ))
+        .layer(axum::extract::DefaultBodyLimit::max(
+            middleware::JSON_BODY_LIMIT,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware::JSON_BODY_LIMIT,
+            middleware::body_limit_middleware,
+        ))
+        .merge(chat_routes)
+        .merge(shutdown_routes);
 
     // Public/read endpoints (no rate limiting)
     let api_routes = Router::new()
         .route("/system/version", get(handlers::version_handler))
         .route("/system/health", get(handlers::health_handler))
-        .route("/events", get(handlers::sse_handler))
+        .route("/system/capabilities", get(handlers::capabilities_handler))
+        .route("/system/config", get(handlers::system_config_handler))
+        .route("/auth/session", get(handlers::get_session))
+        .route(
+            "/events",
+            get(handlers::sse_handler)
+                .layer(axum::middleware::map_response(middleware::skip_access_log)),
+        )
         .route("/history", get(handlers::get_history))
         .route("/metrics", get(handlers::get_metrics))
+        .route("/metrics/slow-requests", get(handlers::get_slow_requests))
+        .route("/metrics/prometheus", get(handlers::get_prometheus_metrics))
         .route("/memories", get(handlers::get_memories))
         .route("/episodes", get(handlers::get_episodes))
         .route("/plugins", get(handlers::get_plugins))
         .route("/plugins/:id/config", get(handlers::get_plugin_config))
+        .route("/plugins/:id/icon", get(handlers::get_plugin_icon))
         .route("/agents", get(handlers::get_agents))
         .route(
             "/permissions/pending",
@@ -533,17 +1028,75 @@ pub async fn run_kernel() -> anyhow::Result<()> {
             "/mcp/access/by-agent/:agent_id",
             get(handlers::get_agent_access),
         )
+        .layer(axum::extract::DefaultBodyLimit::max(
+            middleware::JSON_BODY_LIMIT,
+        ))
         .merge(admin_routes)
-        .layer(axum::extract::DefaultBodyLimit::max(10 * 1024 * 1024)); // 10MB for chat attachments
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(middleware::InFlightLimiter::new(
+                "global",
+                config.max_in_flight_requests,
+            )),
+            middleware::in_flight_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.drain.clone(),
+            middleware::drain_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            middleware::slow_request_middleware,
+        ))
+        .layer(axum::middleware::from_fn(middleware::access_log_middleware));
+
+    // OpenAI-compatible gateway for SDK clients (model = agent id)
+    let openai_routes = Router::new()
+        .route(
+            "/chat/completions",
+            post(handlers::openai::chat_completions),
+        )
+        .route("/models", get(handlers::openai::list_models))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(middleware::InFlightLimiter::new(
+                "openai",
+                config.max_in_flight_chat,
+            )),
+            middleware::in_flight_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.drain.clone(),
+            middleware::drain_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            (app_state.clone(), middleware::RouteGroup::Chat),
+            middleware::rate_limit_middleware,
+        ))
+        // OpenAI clients resend the whole conversation with every request
+        .layer(axum::extract::DefaultBodyLimit::max(
+            middleware::ATTACHMENT_BODY_LIMIT,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware::ATTACHMENT_BODY_LIMIT,
+            middleware::body_limit_middleware,
+        ))
+        .layer(axum::middleware::from_fn(middleware::access_log_middleware));
 
     let app = Router::new()
         .nest("/api", api_routes.with_state(app_state.clone()))
+        .nest("/v1", openai_routes.with_state(app_state.clone()))
         .route("/api/plugin/*path", any(dynamic_proxy_handler))
         .with_state(app_state.clone())
         .fallback(handlers::assets::static_handler)
+        .layer(axum::middleware::from_fn(middleware::trace_middleware))
         .layer(
             CorsLayer::new()
-                .allow_origin(config.cors_origins)
+                .allow_origin(tower_http::cors::AllowOrigin::predicate(
+                    move |origin, _| {
+                        cors_origins
+                            .read()
+                            .is_ok_and(|origins| origins.contains(origin))
+                    },
+                ))
                 .allow_methods([
                     axum::http::Method::GET,
                     axum::http::Method::POST,
@@ -553,6 +1106,7 @@ pub async fn run_kernel() -> anyhow::Result<()> {
                 .allow_headers([
                     axum::http::header::CONTENT_TYPE,
                     axum::http::HeaderName::from_static("x-api-key"),
+                    axum::http::header::AUTHORIZATION,
                 ]),
         );
 
@@ -563,7 +1117,15 @@ pub async fn run_kernel() -> anyhow::Result<()> {
         config.bind_address, config.port
     );
 
-    let shutdown_signal = app_state.shutdown.clone();
+    if config.headless {
+        info!(
+            "🌐 Headless mode: dashboard at http://{}:{}/ (sign in with CLOTO_API_KEY)",
+            config.bind_address, config.port
+        );
+        spawn_signal_handler(app_state.shutdown.clone());
+    }
+
+    let shutdown_signal = drained;
     axum::serve(
         listener,
         app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
@@ -1,5 +1,19 @@
 use clap::Parser;
 
+/// Load `.env` (`CLOTO_ENV_FILE`, else the working directory, else next to the binary)
+fn load_env_file() -> anyhow::Result<()> {
+    if let Ok(path) = std::env::var("CLOTO_ENV_FILE") {
+        dotenvy::from_path(&path)?;
+    } else if dotenvy::dotenv().is_err() {
+        if let Ok(exe) = std::env::current_exe() {
+            if let Some(dir) = exe.parent() {
+                let _ = dotenvy::from_path(dir.join(".env"));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = cloto_core::cli::Cli::parse();
@@ -7,18 +21,24 @@ async fn main() -> anyhow::Result<()> {
     match cli.command {
         None => {
             // Default: load .env and run kernel (backward compatible)
-            if dotenvy::dotenv().is_err() {
-                if let Ok(exe) = std::env::current_exe() {
-                    if let Some(dir) = exe.parent() {
-                        let _ = dotenvy::from_path(dir.join(".env"));
-                    }
-                }
+            cloto_core::config::set_cli_overrides(cli.config_overrides());
+            load_env_file()?;
+            if cli.check {
+                // No log subscriber: stdout carries only the JSON report
+                let report = cloto_core::check::run().await;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                std::process::exit(i32::from(!report.ok));
             }
-            tracing_subscriber::fmt::init();
+            let _telemetry =
+                cloto_core::telemetry::init(cloto_core::config::OtlpConfig::from_env()?.as_ref())?;
             cloto_core::run_kernel().await
         }
         Some(cmd) => {
-            tracing_subscriber::fmt::init();
+            // The secrets file location and passphrase may come from .env
+            if matches!(cmd, cloto_core::cli::Commands::Secrets { .. }) {
+                load_env_file()?;
+            }
+            let _telemetry = cloto_core::telemetry::init(None)?;
             cloto_core::cli::dispatch(cmd).await
         }
     }
@@ -1,8 +1,11 @@
-use sqlx::SqlitePool;
+use crate::db::DbPool;
+use serde::Serialize;
 use std::collections::HashMap;
-use tracing::debug;
+use tracing::{debug, info};
 
-use cloto_shared::AgentMetadata;
+use cloto_shared::{AgentMetadata, CapabilityType};
+
+use super::PluginRegistry;
 
 #[derive(sqlx::FromRow)]
 struct AgentRow {
@@ -12,14 +15,74 @@ struct AgentRow {
     enabled: bool,
     last_seen: i64,
     default_engine_id: String,
-    required_capabilities: sqlx::types::Json<Vec<cloto_shared::CapabilityType>>,
-    metadata: sqlx::types::Json<HashMap<String, String>>,
+    required_capabilities: crate::db::JsonText<Vec<cloto_shared::CapabilityType>>,
+    metadata: crate::db::JsonText<HashMap<String, String>>,
     power_password_hash: Option<String>,
+    system_prompt: Option<String>,
+}
+
+/// A required capability and the plugin or MCP server that provides it.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityBinding {
+    pub capability: CapabilityType,
+    pub plugin_id: String,
+    pub is_mcp: bool,
+}
+
+/// Outcome of matching an agent's required capabilities against what is running.
+#[derive(Debug, Default)]
+pub struct CapabilityResolution {
+    pub bindings: Vec<CapabilityBinding>,
+    pub missing: Vec<CapabilityType>,
+}
+
+impl CapabilityResolution {
+    /// Human-readable error for the capabilities nothing provides, if any.
+    #[must_use]
+    pub fn missing_error(&self) -> Option<String> {
+        if self.missing.is_empty() {
+            return None;
+        }
+        let names: Vec<String> = self.missing.iter().map(|c| format!("{:?}", c)).collect();
+        Some(format!(
+            "Missing capabilities: {} (no active plugin or connected MCP server provides them)",
+            names.join(", ")
+        ))
+    }
+}
+
+/// Per-tool rules for the native plugins bound to an agent.
+///
+/// A `deny` rule blocks its tool. A plugin with any `allow` rule is in
+/// allowlist mode: its unlisted tools are blocked. Plugins without rules
+/// keep all their tools.
+#[derive(Debug, Default)]
+pub struct ToolPolicy {
+    rules: Vec<crate::db::AgentToolRule>,
+}
+
+impl ToolPolicy {
+    #[must_use]
+    pub fn new(rules: Vec<crate::db::AgentToolRule>) -> Self {
+        Self { rules }
+    }
+
+    #[must_use]
+    pub fn allows(&self, plugin_id: &str, tool_name: &str) -> bool {
+        let mut allowlist = false;
+        for rule in self.rules.iter().filter(|r| r.plugin_id == plugin_id) {
+            if rule.tool_name == tool_name {
+                return rule.permission == "allow";
+            }
+            allowlist |= rule.permission == "allow";
+        }
+        !allowlist
+    }
 }
 
 #[derive(Clone)]
 pub struct AgentManager {
-    pool: SqlitePool,
+    pool: DbPool,
 }
 
 impl AgentManager {
@@ -27,7 +90,7 @@ impl AgentManager {
     pub const HEARTBEAT_THRESHOLD_MS: i64 = 90_000;
 
     #[must_use]
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         Self { pool }
     }
 
@@ -47,6 +110,7 @@ impl AgentManager {
             default_engine_id: Some(row.default_engine_id),
             required_capabilities: row.required_capabilities.0,
             metadata: meta,
+            system_prompt: row.system_prompt,
         };
         agent.resolve_status(Self::HEARTBEAT_THRESHOLD_MS);
         agent
@@ -58,7 +122,7 @@ impl AgentManager {
     ) -> anyhow::Result<(AgentMetadata, String)> {
         let row: AgentRow = sqlx::query_as(
             "SELECT id, name, description, enabled, last_seen, default_engine_id, \
-             required_capabilities, metadata, power_password_hash FROM agents WHERE id = ?",
+             required_capabilities, metadata, power_password_hash, system_prompt FROM agents WHERE id = $1",
         )
         .bind(agent_id)
         .fetch_one(&self.pool)
@@ -72,7 +136,7 @@ impl AgentManager {
     pub async fn list_agents(&self) -> anyhow::Result<Vec<AgentMetadata>> {
         let rows: Vec<AgentRow> = sqlx::query_as(
             "SELECT id, name, description, enabled, last_seen, default_engine_id, \
-             required_capabilities, metadata, power_password_hash FROM agents",
+             required_capabilities, metadata, power_password_hash, system_prompt FROM agents",
         )
         .fetch_all(&self.pool)
         .await?;
@@ -117,7 +181,7 @@ impl AgentManager {
         sqlx::query(
             "INSERT INTO agents (id, name, description, default_engine_id, status, \
              enabled, last_seen, metadata, required_capabilities, power_password_hash) \
-             VALUES (?, ?, ?, ?, 'online', 1, ?, ?, ?, ?)",
+             VALUES ($1, $2, $3, $4, 'online', TRUE, $5, $6, $7, $8)",
         )
         .bind(&id_str)
         .bind(name)
@@ -136,7 +200,7 @@ impl AgentManager {
     /// Update the last_seen timestamp for an agent (passive heartbeat).
     pub async fn touch_last_seen(&self, agent_id: &str) -> anyhow::Result<()> {
         let now_ms = chrono::Utc::now().timestamp_millis();
-        sqlx::query("UPDATE agents SET last_seen = ? WHERE id = ?")
+        sqlx::query("UPDATE agents SET last_seen = $1 WHERE id = $2")
             .bind(now_ms)
             .bind(agent_id)
             .execute(&self.pool)
@@ -151,7 +215,7 @@ impl AgentManager {
         } else {
             0
         };
-        sqlx::query("UPDATE agents SET enabled = ?, last_seen = ? WHERE id = ?")
+        sqlx::query("UPDATE agents SET enabled = $1, last_seen = $2 WHERE id = $3")
             .bind(enabled)
             .bind(now_ms)
             .bind(agent_id)
@@ -163,7 +227,7 @@ impl AgentManager {
     /// Get the stored password hash for an agent.
     pub async fn get_password_hash(&self, agent_id: &str) -> anyhow::Result<Option<String>> {
         let row: (Option<String>,) =
-            sqlx::query_as("SELECT power_password_hash FROM agents WHERE id = ?")
+            sqlx::query_as("SELECT power_password_hash FROM agents WHERE id = $1")
                 .bind(agent_id)
                 .fetch_one(&self.pool)
                 .await?;
@@ -195,6 +259,12 @@ impl AgentManager {
             .is_ok())
     }
 
+    /// Load the agent's per-tool rules for native plugins.
+    pub async fn get_tool_policy(&self, agent_id: &str) -> anyhow::Result<ToolPolicy> {
+        let rules = crate::db::get_agent_tool_rules(&self.pool, agent_id).await?;
+        Ok(ToolPolicy::new(rules))
+    }
+
     /// Return the set of MCP server IDs this agent has access to (server_grant + allow).
     pub async fn get_granted_server_ids(&self, agent_id: &str) -> anyhow::Result<Vec<String>> {
         let entries = crate::db::get_access_entries_for_agent(&self.pool, agent_id).await?;
@@ -208,12 +278,16 @@ impl AgentManager {
     /// Delete an agent and all associated data (chat messages, attachments via cascade).
     pub async fn delete_agent(&self, agent_id: &str) -> anyhow::Result<()> {
         // chat_attachments cascade from chat_messages (ON DELETE CASCADE in schema)
-        sqlx::query("DELETE FROM chat_messages WHERE agent_id = ?")
+        sqlx::query("DELETE FROM chat_messages WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM chat_tool_history WHERE agent_id = $1")
             .bind(agent_id)
             .execute(&self.pool)
             .await?;
 
-        let result = sqlx::query("DELETE FROM agents WHERE id = ?")
+        let result = sqlx::query("DELETE FROM agents WHERE id = $1")
             .bind(agent_id)
             .execute(&self.pool)
             .await?;
@@ -232,14 +306,14 @@ impl AgentManager {
     ) -> anyhow::Result<()> {
         let metadata_json = serde_json::to_string(&metadata)?;
         if let Some(engine_id) = default_engine_id {
-            sqlx::query("UPDATE agents SET metadata = ?, default_engine_id = ? WHERE id = ?")
+            sqlx::query("UPDATE agents SET metadata = $1, default_engine_id = $2 WHERE id = $3")
                 .bind(metadata_json)
                 .bind(engine_id)
                 .bind(agent_id)
                 .execute(&self.pool)
                 .await?;
         } else {
-            sqlx::query("UPDATE agents SET metadata = ? WHERE id = ?")
+            sqlx::query("UPDATE agents SET metadata = $1 WHERE id = $2")
                 .bind(metadata_json)
                 .bind(agent_id)
                 .execute(&self.pool)
@@ -247,4 +321,177 @@ impl AgentManager {
         }
         Ok(())
     }
+
+    /// Set or clear (`None`) the agent's system prompt template.
+    pub async fn set_system_prompt(
+        &self,
+        agent_id: &str,
+        system_prompt: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let result = sqlx::query("UPDATE agents SET system_prompt = $1 WHERE id = $2")
+            .bind(system_prompt)
+            .bind(agent_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(cloto_shared::ClotoError::AgentNotFound(agent_id.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Match required capabilities to active providers.
+    ///
+    /// The agent's default engine is preferred for `Reasoning` and its
+    /// `preferred_memory` for `Memory`; otherwise the first Rust plugin
+    /// declaring the capability wins, then the first connected MCP server.
+    /// Servers the agent has an explicit `server_grant` deny for are skipped.
+    pub async fn resolve_capabilities(
+        &self,
+        agent_id: Option<&str>,
+        capabilities: &[CapabilityType],
+        default_engine: &str,
+        metadata: &HashMap<String, String>,
+        registry: &PluginRegistry,
+    ) -> anyhow::Result<CapabilityResolution> {
+        let denied: Vec<String> = match agent_id {
+            Some(id) => crate::db::get_access_entries_for_agent(&self.pool, id)
+                .await?
+                .into_iter()
+                .filter(|e| e.entry_type == "server_grant" && e.permission == "deny")
+                .map(|e| e.server_id)
+                .collect(),
+            None => Vec::new(),
+        };
+        let plugins = registry.list_plugins().await;
+
+        let mut resolution = CapabilityResolution::default();
+        for capability in capabilities {
+            if resolution
+                .bindings
+                .iter()
+                .any(|b| &b.capability == capability)
+                || resolution.missing.contains(capability)
+            {
+                continue;
+            }
+            let mut rust: Vec<&str> = plugins
+                .iter()
+                .filter(|m| m.is_active && m.provided_capabilities.contains(capability))
+                .map(|m| m.id.as_str())
+                .collect();
+            rust.sort_unstable();
+            let mcp: Vec<String> = match registry.mcp_manager {
+                Some(ref mcp) => mcp
+                    .capability_providers(capability)
+                    .await
+                    .into_iter()
+                    .filter(|id| !denied.contains(id))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            let preferred = match capability {
+                CapabilityType::Reasoning => Some(default_engine),
+                CapabilityType::Memory => metadata.get("preferred_memory").map(String::as_str),
+                _ => None,
+            };
+            let binding = preferred
+                .filter(|id| rust.contains(id) || mcp.iter().any(|m| m == id))
+                .map(|id| (id.to_string(), !rust.contains(&id)))
+                .or_else(|| rust.first().map(|id| ((*id).to_string(), false)))
+                .or_else(|| mcp.first().map(|id| (id.clone(), true)));
+
+            match binding {
+                Some((plugin_id, is_mcp)) => resolution.bindings.push(CapabilityBinding {
+                    capability: capability.clone(),
+                    plugin_id,
+                    is_mcp,
+                }),
+                None => resolution.missing.push(capability.clone()),
+            }
+        }
+        Ok(resolution)
+    }
+
+    /// Record capability providers in `agent_plugins`. MCP servers are also
+    /// granted to the agent unless a `server_grant` entry already exists.
+    pub async fn bind_capabilities(
+        &self,
+        agent_id: &str,
+        bindings: &[CapabilityBinding],
+    ) -> anyhow::Result<()> {
+        let granted: Vec<String> = crate::db::get_access_entries_for_agent(&self.pool, agent_id)
+            .await?
+            .into_iter()
+            .filter(|e| e.entry_type == "server_grant")
+            .map(|e| e.server_id)
+            .collect();
+
+        for (pos, binding) in (0_i64..).zip(bindings) {
+            sqlx::query(
+                "INSERT INTO agent_plugins (agent_id, plugin_id, pos_x, pos_y) \
+                 VALUES ($1, $2, $3, 0) ON CONFLICT DO NOTHING",
+            )
+            .bind(agent_id)
+            .bind(&binding.plugin_id)
+            .bind(pos)
+            .execute(&self.pool)
+            .await?;
+
+            if binding.is_mcp && !granted.contains(&binding.plugin_id) {
+                let entry = crate::db::AccessControlEntry {
+                    id: None,
+                    entry_type: "server_grant".to_string(),
+                    agent_id: agent_id.to_string(),
+                    server_id: binding.plugin_id.clone(),
+                    tool_name: None,
+                    permission: "allow".to_string(),
+                    granted_by: Some("capability_binding".to_string()),
+                    granted_at: chrono::Utc::now().to_rfc3339(),
+                    expires_at: None,
+                    justification: Some(format!("Provides {:?}", binding.capability)),
+                    metadata: None,
+                };
+                crate::db::save_access_control_entry(&self.pool, &entry).await?;
+            }
+            info!(
+                agent_id = %agent_id,
+                plugin_id = %binding.plugin_id,
+                capability = ?binding.capability,
+                "🔗 Bound capability provider"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(plugin_id: &str, tool_name: &str, permission: &str) -> crate::db::AgentToolRule {
+        crate::db::AgentToolRule {
+            plugin_id: plugin_id.to_string(),
+            tool_name: tool_name.to_string(),
+            permission: permission.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tool_policy_deny_and_allowlist() {
+        let policy = ToolPolicy::new(vec![
+            rule("tool.terminal", "execute_command", "deny"),
+            rule("vision.screen", "capture_screen", "allow"),
+        ]);
+
+        // Deny rule blocks only its tool
+        assert!(!policy.allows("tool.terminal", "execute_command"));
+        assert!(policy.allows("tool.terminal", "list_dir"));
+        // An allow rule switches the plugin to allowlist mode
+        assert!(policy.allows("vision.screen", "capture_screen"));
+        assert!(!policy.allows("vision.screen", "analyze_image"));
+        // No rules: everything is allowed
+        assert!(policy.allows("python.bridge", "run"));
+        assert!(ToolPolicy::default().allows("tool.terminal", "execute_command"));
+    }
 }
@@ -0,0 +1,837 @@
+//! Processing of chat uploads.
+//!
+//! Before an attachment is stored, images are downscaled to
+//! `CLOTO_ATTACHMENT_MAX_DIMENSION` and stripped of EXIF/text metadata, and
+//! documents (plain text, PDF, DOCX/PPTX/XLSX) have their text extracted.
+//! Audio is handed to the MCP server named by `CLOTO_TRANSCRIBE_SERVER`
+//! (tool `transcribe`) in the background. The derived text is stored with the
+//! attachment and added to an agent's context when the user sends the file
+//! or mentions it by name. Engines that support vision also receive the
+//! images sent with a message, base64-encoded in `ClotoMessage::attachments`.
+
+use super::McpClientManager;
+use crate::db;
+use crate::db::DbPool;
+use cloto_shared::{AttachmentRef, ClotoMessage, MessageSource};
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+pub const DEFAULT_MAX_DIMENSION: u32 = 2048;
+/// Derived text kept per attachment.
+const MAX_DERIVED_CHARS: usize = 100_000;
+/// Decompressed bytes read from a single PDF stream or archive entry.
+const MAX_INFLATED_BYTES: u64 = 16 * 1024 * 1024;
+/// Derived text injected into an agent's context per attachment.
+const MAX_CONTEXT_CHARS: usize = 8_000;
+/// Thread attachments checked for a mention by name.
+const MENTION_SCAN_LIMIT: i64 = 50;
+/// Images per message sent to a vision engine.
+const MAX_VISION_IMAGES: usize = 4;
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_mins(5);
+
+const DOCX: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+const PPTX: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+const XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// MIME types accepted for upload besides images.
+pub const DOCUMENT_MIME_TYPES: &[&str] = &[
+    "text/plain",
+    "text/markdown",
+    "text/csv",
+    "application/json",
+    "application/pdf",
+    DOCX,
+    PPTX,
+    XLSX,
+    "audio/mpeg",
+    "audio/wav",
+    "audio/ogg",
+    "audio/webm",
+    "audio/mp4",
+];
+
+pub struct ProcessedAttachment {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+    pub derived_text: Option<String>,
+}
+
+pub struct AttachmentProcessor {
+    pool: DbPool,
+    mcp: Arc<McpClientManager>,
+    transcribe_server: Option<String>,
+    max_dimension: u32,
+}
+
+impl AttachmentProcessor {
+    #[must_use]
+    pub fn new(
+        pool: DbPool,
+        mcp: Arc<McpClientManager>,
+        transcribe_server: Option<String>,
+        max_dimension: u32,
+    ) -> Self {
+        Self {
+            pool,
+            mcp,
+            transcribe_server,
+            max_dimension,
+        }
+    }
+
+    /// Sanitize and derive text from an upload. CPU-bound; call from a
+    /// blocking task.
+    #[must_use]
+    pub fn process(&self, mime_type: &str, data: Vec<u8>) -> ProcessedAttachment {
+        if mime_type.starts_with("image/") && mime_type != "image/svg+xml" {
+            return match self.process_image(mime_type, &data) {
+                Ok(Some((data, mime_type))) => ProcessedAttachment {
+                    data,
+                    mime_type,
+                    derived_text: None,
+                },
+                Ok(None) => ProcessedAttachment {
+                    data,
+                    mime_type: mime_type.to_string(),
+                    derived_text: None,
+                },
+                Err(e) => {
+                    warn!(mime_type = %mime_type, error = %e, "Image processing failed, storing as uploaded");
+                    ProcessedAttachment {
+                        data,
+                        mime_type: mime_type.to_string(),
+                        derived_text: None,
+                    }
+                }
+            };
+        }
+
+        let derived_text = match extract_text(mime_type, &data) {
+            Ok(text) => text.map(|t| truncate_chars(t.trim(), MAX_DERIVED_CHARS)),
+            Err(e) => {
+                warn!(mime_type = %mime_type, error = %e, "Text extraction failed");
+                None
+            }
+        };
+        ProcessedAttachment {
+            data,
+            mime_type: mime_type.to_string(),
+            derived_text: derived_text.filter(|t| !t.is_empty()),
+        }
+    }
+
+    /// Downscale oversized images and drop metadata. `None` keeps the upload.
+    fn process_image(
+        &self,
+        mime_type: &str,
+        data: &[u8],
+    ) -> anyhow::Result<Option<(Vec<u8>, String)>> {
+        let reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+        let Some(format) = reader.format() else {
+            return Ok(None);
+        };
+        let mut decoder = reader.into_decoder()?;
+        let (width, height) = decoder.dimensions();
+        let orientation = decoder.orientation()?;
+        let oversized = width > self.max_dimension || height > self.max_dimension;
+        let rotated = orientation != image::metadata::Orientation::NoTransforms;
+
+        if !oversized && !rotated {
+            // Metadata can be dropped without re-encoding
+            let stripped = match format {
+                ImageFormat::Jpeg => strip_jpeg_metadata(data),
+                ImageFormat::Png => strip_png_metadata(data),
+                _ => None,
+            };
+            return Ok(stripped.map(|d| (d, mime_type.to_string())));
+        }
+
+        let mut img = DynamicImage::from_decoder(decoder)?;
+        img.apply_orientation(orientation);
+        if oversized {
+            img = img.resize(
+                self.max_dimension,
+                self.max_dimension,
+                image::imageops::FilterType::Triangle,
+            );
+        }
+        let mut out = Cursor::new(Vec::new());
+        let mime_type = if format == ImageFormat::Jpeg {
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, 85);
+            img.to_rgb8().write_with_encoder(encoder)?;
+            "image/jpeg"
+        } else {
+            img.write_to(&mut out, ImageFormat::Png)?;
+            "image/png"
+        };
+        info!(
+            from = %format!("{}x{}", width, height),
+            to = %format!("{}x{}", img.width(), img.height()),
+            "🖼️ Attachment image re-encoded"
+        );
+        Ok(Some((out.into_inner(), mime_type.to_string())))
+    }
+
+    /// Transcribe an audio attachment in the background, if a server is set.
+    pub fn spawn_transcription(
+        self: &Arc<Self>,
+        attachment_id: String,
+        mime_type: String,
+        data: Vec<u8>,
+    ) {
+        let Some(server_id) = self.transcribe_server.clone() else {
+            return;
+        };
+        if !mime_type.starts_with("audio/") {
+            return;
+        }
+        let mcp = self.mcp.clone();
+        let pool = self.pool.clone();
+        tokio::spawn(async move {
+            use base64::Engine;
+            let args = serde_json::json!({
+                "data": base64::engine::general_purpose::STANDARD.encode(&data),
+                "mime_type": mime_type,
+            });
+            let result = tokio::time::timeout(
+                TRANSCRIBE_TIMEOUT,
+                mcp.call_server_tool(&server_id, "transcribe", args),
+            )
+            .await;
+            let text = match result {
+                Ok(Ok(result)) if result.is_error != Some(true) => result
+                    .content
+                    .iter()
+                    .filter_map(|c| match c {
+                        super::mcp_protocol::ToolContent::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Ok(Ok(_)) => {
+                    error!(server_id = %server_id, "❌ Transcription server returned an error");
+                    return;
+                }
+                Ok(Err(e)) => {
+                    error!(server_id = %server_id, error = %e, "❌ Transcription failed");
+                    return;
+                }
+                Err(_) => {
+                    error!(server_id = %server_id, "⏱️ Transcription timed out");
+                    return;
+                }
+            };
+            let text = truncate_chars(text.trim(), MAX_DERIVED_CHARS);
+            if text.is_empty() {
+                return;
+            }
+            if let Err(e) = db::set_attachment_derived_text(&pool, &attachment_id, &text).await {
+                error!(attachment_id = %attachment_id, error = %e, "Failed to save transcript");
+            }
+        });
+    }
+
+    /// System messages carrying the derived text of attachments sent with
+    /// `message_id` or mentioned by filename in `content`.
+    pub async fn context_for(
+        &self,
+        agent_id: &str,
+        user_id: &str,
+        message_id: &str,
+        content: &str,
+    ) -> Vec<ClotoMessage> {
+        let attachments =
+            match db::get_thread_attachments(&self.pool, agent_id, user_id, MENTION_SCAN_LIMIT)
+                .await
+            {
+                Ok(attachments) => attachments,
+                Err(e) => {
+                    error!(agent_id = %agent_id, error = %e, "Failed to load chat attachments");
+                    return vec![];
+                }
+            };
+        let content = content.to_lowercase();
+        attachments
+            .into_iter()
+            .filter(|a| a.message_id == message_id || content.contains(&a.filename.to_lowercase()))
+            .filter_map(|a| {
+                let text = a.derived_text?;
+                Some(ClotoMessage::new(
+                    MessageSource::System,
+                    format!(
+                        "Contents of attached file \"{}\" ({}):\n{}",
+                        a.filename,
+                        a.mime_type,
+                        truncate_chars(&text, MAX_CONTEXT_CHARS)
+                    ),
+                ))
+            })
+            .collect()
+    }
+
+    /// References to the attachments stored with `message_id`, without data.
+    pub async fn refs_for(&self, message_id: &str) -> Vec<AttachmentRef> {
+        match db::get_attachments_for_message(&self.pool, message_id).await {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|a| AttachmentRef {
+                    id: a.id,
+                    filename: a.filename,
+                    mime_type: a.mime_type,
+                    data: None,
+                })
+                .collect(),
+            Err(e) => {
+                error!(message_id = %message_id, error = %e, "Failed to load message attachments");
+                vec![]
+            }
+        }
+    }
+
+    /// `attachments` with the base64 content of the first
+    /// `MAX_VISION_IMAGES` images filled in, for a vision-capable engine.
+    pub async fn with_image_data(&self, attachments: &[AttachmentRef]) -> Vec<AttachmentRef> {
+        use base64::Engine;
+        let mut out = Vec::with_capacity(attachments.len());
+        let mut images = 0;
+        for attachment in attachments {
+            let mut attachment = attachment.clone();
+            attachment.data = None;
+            if attachment.is_image() && images < MAX_VISION_IMAGES {
+                match self.read(&attachment.id).await {
+                    Ok(data) => {
+                        attachment.data =
+                            Some(base64::engine::general_purpose::STANDARD.encode(data));
+                        images += 1;
+                    }
+                    Err(e) => {
+                        warn!(attachment_id = %attachment.id, error = %e, "Failed to read image attachment");
+                    }
+                }
+            }
+            out.push(attachment);
+        }
+        out
+    }
+
+    async fn read(&self, attachment_id: &str) -> anyhow::Result<Vec<u8>> {
+        let att = db::get_attachment_by_id(&self.pool, attachment_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("attachment not found"))?;
+        match (att.storage_type.as_str(), att.inline_data, att.disk_path) {
+            ("inline", Some(data), _) => Ok(data),
+            ("disk", _, Some(path)) => Ok(tokio::fs::read(&path).await?),
+            (storage_type, _, _) => Err(anyhow::anyhow!(
+                "no data for storage type '{}'",
+                storage_type
+            )),
+        }
+    }
+}
+
+/// Whether an upload of this MIME type is accepted.
+#[must_use]
+pub fn is_allowed_mime_type(mime_type: &str) -> bool {
+    const IMAGE_MIME_TYPES: &[&str] = &[
+        "image/png",
+        "image/jpeg",
+        "image/jpg",
+        "image/gif",
+        "image/webp",
+        "image/svg+xml",
+    ];
+    IMAGE_MIME_TYPES.contains(&mime_type) || DOCUMENT_MIME_TYPES.contains(&mime_type)
+}
+
+/// Display name safe for storage and `Content-Disposition`.
+#[must_use]
+pub fn sanitize_filename(name: &str) -> Option<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let clean: String = base
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '"' | '<' | '>' | ':' | '|' | '?' | '*'))
+        .take(200)
+        .collect();
+    let clean = clean.trim().trim_start_matches('.').to_string();
+    (!clean.is_empty()).then_some(clean)
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        let truncated: String = text.chars().take(max_chars).collect();
+        format!("{}…", truncated)
+    } else {
+        text.to_string()
+    }
+}
+
+// ── Metadata stripping ──
+
+/// Copy of a JPEG without APP1 (EXIF/XMP) and APP13 (IPTC) segments.
+fn strip_jpeg_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = data[pos + 1];
+        // Start of scan: the rest is image data
+        if marker == 0xDA {
+            out.extend_from_slice(&data[pos..]);
+            return Some(out);
+        }
+        let len = usize::from(u16::from_be_bytes([data[pos + 2], data[pos + 3]]));
+        let end = pos + 2 + len;
+        if len < 2 || end > data.len() {
+            return None;
+        }
+        if marker != 0xE1 && marker != 0xED {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+    None
+}
+
+/// Copy of a PNG without eXIf, tEXt, zTXt, iTXt and tIME chunks.
+fn strip_png_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    if !data.starts_with(SIGNATURE) {
+        return None;
+    }
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(SIGNATURE);
+    let mut pos = SIGNATURE.len();
+    while pos + 12 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as usize;
+        let end = pos.checked_add(12)?.checked_add(len)?;
+        if end > data.len() {
+            return None;
+        }
+        let kind = &data[pos + 4..pos + 8];
+        if !matches!(kind, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        if kind == b"IEND" {
+            return Some(out);
+        }
+        pos = end;
+    }
+    None
+}
+
+// ── Text extraction ──
+
+fn extract_text(mime_type: &str, data: &[u8]) -> anyhow::Result<Option<String>> {
+    Ok(match mime_type {
+        "text/plain" | "text/markdown" | "text/csv" | "application/json" => {
+            Some(String::from_utf8_lossy(data).into_owned())
+        }
+        "application/pdf" => Some(extract_pdf_text(data)),
+        DOCX => Some(extract_ooxml_text(
+            data,
+            |name| name == "word/document.xml",
+            "w:t",
+            "w:p",
+        )?),
+        PPTX => Some(extract_ooxml_text(
+            data,
+            |name| {
+                name.starts_with("ppt/slides/slide")
+                    && std::path::Path::new(name)
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"))
+            },
+            "a:t",
+            "a:p",
+        )?),
+        XLSX => Some(extract_ooxml_text(
+            data,
+            |name| name == "xl/sharedStrings.xml",
+            "t",
+            "si",
+        )?),
+        _ => None,
+    })
+}
+
+/// Text of the `text_tag` elements in the matching entries of an Office
+/// archive, one line per `paragraph_tag`.
+fn extract_ooxml_text(
+    data: &[u8],
+    wanted: impl Fn(&str) -> bool,
+    text_tag: &str,
+    paragraph_tag: &str,
+) -> anyhow::Result<String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|n| wanted(n))
+        .map(str::to_string)
+        .collect();
+    // slide2.xml before slide10.xml
+    names.sort_by_key(|n| (n.len(), n.clone()));
+
+    let mut text = String::new();
+    for name in names {
+        let mut xml = String::new();
+        archive
+            .by_name(&name)?
+            .take(MAX_INFLATED_BYTES)
+            .read_to_string(&mut xml)?;
+        xml_text(&xml, text_tag, paragraph_tag, &mut text);
+        if text.len() > MAX_DERIVED_CHARS * 4 {
+            break;
+        }
+    }
+    Ok(text)
+}
+
+fn xml_text(xml: &str, text_tag: &str, paragraph_tag: &str, out: &mut String) {
+    let mut rest = xml;
+    let mut in_text = false;
+    while let Some(start) = rest.find('<') {
+        if in_text {
+            out.push_str(&xml_unescape(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        let name = tag
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        if name == text_tag {
+            in_text = !tag.ends_with('/');
+        } else if name.strip_prefix('/') == Some(text_tag) {
+            in_text = false;
+        } else if name.strip_prefix('/') == Some(paragraph_tag) {
+            out.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Best-effort text of a PDF: literal strings shown by the text operators of
+/// uncompressed or Flate-compressed content streams. Text in fonts with
+/// custom encodings (hex strings) is not recovered.
+fn extract_pdf_text(data: &[u8]) -> String {
+    let mut text = String::new();
+    let mut pos = 0;
+    while let Some(found) = find(&data[pos..], b"stream") {
+        let keyword = pos + found;
+        let mut start = keyword + b"stream".len();
+        pos = start;
+        // Skip "endstream" and the dictionary that precedes a real stream
+        if keyword >= 3 && &data[keyword - 3..keyword] == b"end" {
+            continue;
+        }
+        if data.get(start) == Some(&b'\r') {
+            start += 1;
+        }
+        if data.get(start) == Some(&b'\n') {
+            start += 1;
+        }
+        let Some(len) = find(&data[start..], b"endstream") else {
+            break;
+        };
+        let body = &data[start..start + len];
+        pos = start + len;
+
+        let dict_start = data[..keyword]
+            .windows(2)
+            .rposition(|w| w == b"<<")
+            .unwrap_or(keyword);
+        let dict = &data[dict_start..keyword];
+        let content = if find(dict, b"/FlateDecode").is_some() {
+            let mut inflated = Vec::new();
+            if flate2::read::ZlibDecoder::new(body)
+                .take(MAX_INFLATED_BYTES)
+                .read_to_end(&mut inflated)
+                .is_err()
+                && inflated.is_empty()
+            {
+                continue;
+            }
+            inflated
+        } else if find(dict, b"/Filter").is_some() {
+            continue;
+        } else {
+            body.to_vec()
+        };
+        pdf_content_text(&content, &mut text);
+        if text.len() > MAX_DERIVED_CHARS * 4 {
+            break;
+        }
+    }
+    text
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Append the text shown by `Tj`, `TJ`, `'` and `"` in a content stream.
+fn pdf_content_text(content: &[u8], out: &mut String) {
+    let mut pending = String::new();
+    let mut i = 0;
+    while i < content.len() {
+        match content[i] {
+            b'(' => {
+                let (s, next) = pdf_literal_string(content, i + 1);
+                pending.push_str(&s);
+                i = next;
+            }
+            b'%' => {
+                while i < content.len() && content[i] != b'\n' && content[i] != b'\r' {
+                    i += 1;
+                }
+            }
+            b'-' if pending.ends_with(|c: char| c != ' ') => {
+                // Large negative kerning in a TJ array separates words
+                let end = content[i + 1..]
+                    .iter()
+                    .position(|b| !b.is_ascii_digit() && *b != b'.')
+                    .map_or(content.len(), |p| i + 1 + p);
+                if std::str::from_utf8(&content[i + 1..end])
+                    .ok()
+                    .and_then(|n| n.parse::<f32>().ok())
+                    .is_some_and(|n| n > 200.0)
+                {
+                    pending.push(' ');
+                }
+                i = end;
+            }
+            c if c.is_ascii_alphabetic() || c == b'\'' || c == b'"' || c == b'*' => {
+                let end = content[i..]
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphabetic() || matches!(b, b'\'' | b'"' | b'*')))
+                    .map_or(content.len(), |p| i + p);
+                match &content[i..end] {
+                    b"Tj" | b"TJ" => out.push_str(&std::mem::take(&mut pending)),
+                    b"'" | b"\"" => {
+                        out.push('\n');
+                        out.push_str(&std::mem::take(&mut pending));
+                    }
+                    b"Td" | b"TD" | b"T*" | b"ET" => {
+                        if !out.is_empty() && !out.ends_with('\n') {
+                            out.push('\n');
+                        }
+                    }
+                    _ => pending.clear(),
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// Decode a PDF literal string starting after its `(`; returns the text and
+/// the index after the closing `)`.
+fn pdf_literal_string(content: &[u8], mut i: usize) -> (String, usize) {
+    let mut bytes = Vec::new();
+    let mut depth = 0;
+    while i < content.len() {
+        let b = content[i];
+        i += 1;
+        match b {
+            b'\\' if i < content.len() => {
+                let e = content[i];
+                i += 1;
+                match e {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    // Backspace, form feed and line continuations are dropped
+                    b'b' | b'f' | b'\r' | b'\n' => {}
+                    b'0'..=b'7' => {
+                        let mut value = u32::from(e - b'0');
+                        for _ in 0..2 {
+                            match content.get(i) {
+                                Some(d @ b'0'..=b'7') => {
+                                    value = value * 8 + u32::from(d - b'0');
+                                    i += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push((value & 0xFF) as u8);
+                    }
+                    other => bytes.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(b);
+            }
+            b')' if depth == 0 => break,
+            b')' => {
+                depth -= 1;
+                bytes.push(b);
+            }
+            _ => bytes.push(b),
+        }
+    }
+    // PDFDocEncoding is Latin-1 for printable text
+    (bytes.iter().map(|&b| char::from(b)).collect(), i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    async fn processor() -> AttachmentProcessor {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        let mcp = Arc::new(McpClientManager::new(pool.clone(), false));
+        AttachmentProcessor::new(pool, mcp, None, 64)
+    }
+
+    #[tokio::test]
+    async fn test_oversized_image_downscaled() {
+        let img = DynamicImage::new_rgb8(200, 100);
+        let mut png = Cursor::new(Vec::new());
+        img.write_to(&mut png, ImageFormat::Png).unwrap();
+
+        let processed = processor().await.process("image/png", png.into_inner());
+        assert_eq!(processed.mime_type, "image/png");
+        let out = image::load_from_memory(&processed.data).unwrap();
+        assert_eq!((out.width(), out.height()), (64, 32));
+    }
+
+    #[test]
+    fn test_jpeg_exif_stripped() {
+        let mut jpeg = vec![0xFF, 0xD8];
+        // APP1 with an EXIF payload
+        jpeg.extend_from_slice(&[0xFF, 0xE1, 0x00, 0x08]);
+        jpeg.extend_from_slice(b"Exif\0\0");
+        // DQT stays
+        jpeg.extend_from_slice(&[0xFF, 0xDB, 0x00, 0x03, 0x01]);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]);
+
+        let stripped = strip_jpeg_metadata(&jpeg).unwrap();
+        assert!(find(&stripped, b"Exif").is_none());
+        assert!(find(&stripped, &[0xFF, 0xDB]).is_some());
+        assert!(stripped.ends_with(&[0x12, 0x34, 0xFF, 0xD9]));
+    }
+
+    #[test]
+    fn test_pdf_text_extracted() {
+        let content = b"BT /F1 12 Tf 72 712 Td (Quarterly report) Tj 0 -14 Td [(Rev) -20 (enue) -300 (up)] TJ ET";
+        let mut compressed =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        compressed.write_all(content).unwrap();
+        let compressed = compressed.finish().unwrap();
+
+        let mut pdf =
+            b"%PDF-1.4\n4 0 obj\n<< /Length 99 /Filter /FlateDecode >>\nstream\n".to_vec();
+        pdf.extend_from_slice(&compressed);
+        pdf.extend_from_slice(b"\nendstream\nendobj\n%%EOF");
+
+        assert_eq!(extract_pdf_text(&pdf), "Quarterly report\nRevenue up\n");
+    }
+
+    #[test]
+    fn test_docx_text_extracted() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(
+            "word/document.xml",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(
+            br#"<w:document><w:body><w:p><w:r><w:t>Fish &amp; chips</w:t></w:r></w:p><w:p><w:r><w:t xml:space="preserve">Second </w:t></w:r><w:r><w:t>line</w:t></w:r></w:p></w:body></w:document>"#,
+        )
+        .unwrap();
+        let docx = zip.finish().unwrap().into_inner();
+
+        let text = extract_text(DOCX, &docx).unwrap().unwrap();
+        assert_eq!(text, "Fish & chips\nSecond line\n");
+    }
+
+    #[tokio::test]
+    async fn test_image_data_loaded_for_vision() {
+        let processor = processor().await;
+        db::init_db(&processor.pool, "sqlite::memory:")
+            .await
+            .unwrap();
+        db::save_chat_message(
+            &processor.pool,
+            &db::ChatMessageRow {
+                id: "m".into(),
+                agent_id: "agent.cloto_default".into(),
+                user_id: "u".into(),
+                source: "user".into(),
+                content: "[]".into(),
+                metadata: None,
+                created_at: 1_000,
+                session_id: None,
+            },
+        )
+        .await
+        .unwrap();
+        for (id, filename, mime_type) in [
+            ("a", "photo.png", "image/png"),
+            ("b", "notes.txt", "text/plain"),
+        ] {
+            db::save_attachment(
+                &processor.pool,
+                &db::AttachmentRow {
+                    id: id.into(),
+                    message_id: "m".into(),
+                    filename: filename.into(),
+                    mime_type: mime_type.into(),
+                    size_bytes: 3,
+                    storage_type: "inline".into(),
+                    inline_data: Some(b"abc".to_vec()),
+                    disk_path: None,
+                    created_at: 1_000,
+                    derived_text: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut refs = processor.refs_for("m").await;
+        refs.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(refs.len(), 2);
+        assert!(refs.iter().all(|a| a.data.is_none()));
+
+        let loaded = processor.with_image_data(&refs).await;
+        assert_eq!(loaded[0].data.as_deref(), Some("YWJj"));
+        assert_eq!(loaded[1].data, None);
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(
+            sanitize_filename("../../etc/\"passwd\"").as_deref(),
+            Some("passwd")
+        );
+        assert_eq!(
+            sanitize_filename("C:\\Users\\me\\notes.txt").as_deref(),
+            Some("notes.txt")
+        );
+        assert_eq!(sanitize_filename("..."), None);
+    }
+}
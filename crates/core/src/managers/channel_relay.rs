@@ -0,0 +1,272 @@
+//! Channel relay — external chat services talking to agents through MCP servers.
+//!
+//! Adapter servers (Slack, Telegram, ...) hold the `ChannelRelay` permission and
+//! forward each inbound chat message as an MCP `sampling/createMessage` request.
+//! The kernel publishes it as `MessageReceived` for the bound agent and answers
+//! the request with the agent's `ThoughtResponse`, so adapters need no reply
+//! routing of their own.
+//!
+//! Request `metadata` (all optional):
+//! - `agent_id`: target agent (default: `DEFAULT_AGENT_ID`)
+//! - `user_id` / `user_name`: sender on the external service
+//! - `conversation`: opaque key of the channel/thread, stored in the message metadata
+//! - `audio_ref` / `audio_mime`: a voice note saved by the adapter, passed through
+//!   as message metadata for speech-to-text (the text is then a placeholder or caption)
+
+use crate::events::SerializedEvent;
+use cloto_shared::{ClotoEvent, ClotoEventData, ClotoMessage, MessageSource};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+pub const SAMPLING_METHOD: &str = "sampling/createMessage";
+
+const DEFAULT_REPLY_TIMEOUT_SECS: u64 = 180;
+
+/// JSON-RPC error codes returned to the server.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Deserialize)]
+struct SamplingParams {
+    messages: Vec<SamplingMessage>,
+    #[serde(default)]
+    metadata: RelayMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct SamplingMessage {
+    role: String,
+    content: SamplingContent,
+}
+
+#[derive(Debug, Deserialize)]
+struct SamplingContent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RelayMetadata {
+    agent_id: Option<String>,
+    user_id: Option<String>,
+    user_name: Option<String>,
+    conversation: Option<String>,
+    audio_ref: Option<String>,
+    audio_mime: Option<String>,
+}
+
+/// How long to wait for an agent's answer (`CLOTO_CHANNEL_REPLY_TIMEOUT_SECS`).
+/// Group chat rooms use the same limit per speaker.
+pub(crate) fn reply_timeout() -> Duration {
+    let secs = std::env::var("CLOTO_CHANNEL_REPLY_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&s| s > 0)
+        .unwrap_or(DEFAULT_REPLY_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Relays sampling requests of one adapter server to agents.
+#[derive(Clone)]
+pub(crate) struct ChannelRelay {
+    server_id: String,
+    event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+    events: broadcast::Sender<SerializedEvent>,
+    reply_timeout: Duration,
+}
+
+impl ChannelRelay {
+    pub(crate) fn new(
+        server_id: String,
+        event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+        events: broadcast::Sender<SerializedEvent>,
+    ) -> Self {
+        Self {
+            server_id,
+            event_tx,
+            events,
+            reply_timeout: reply_timeout(),
+        }
+    }
+
+    /// Build the agent message for a sampling request. The last user text
+    /// message is the one relayed; earlier ones are the adapter's own context
+    /// and are covered by the agent's memory instead.
+    fn message(&self, params: Value) -> Result<ClotoMessage, (i64, String)> {
+        let params: SamplingParams = serde_json::from_value(params)
+            .map_err(|e| (INVALID_PARAMS, format!("Invalid sampling request: {}", e)))?;
+        let content = params
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user" && m.content.kind == "text")
+            .and_then(|m| m.content.text.clone())
+            .filter(|t| !t.trim().is_empty())
+            .ok_or((INVALID_PARAMS, "No user text message to relay".to_string()))?;
+
+        let meta = params.metadata;
+        let user_id = meta.user_id.unwrap_or_else(|| "unknown".to_string());
+        let mut msg = ClotoMessage::new(
+            MessageSource::User {
+                id: format!("{}:{}", self.server_id, user_id),
+                name: meta.user_name.unwrap_or_else(|| user_id.clone()),
+            },
+            content,
+        );
+        let mut metadata = HashMap::from([
+            ("channel".to_string(), self.server_id.clone()),
+            ("channel_user_id".to_string(), user_id),
+        ]);
+        if let Some(agent_id) = meta.agent_id {
+            msg.target_agent = Some(agent_id.clone());
+            metadata.insert("target_agent_id".to_string(), agent_id);
+        }
+        if let Some(conversation) = meta.conversation {
+            metadata.insert("channel_conversation".to_string(), conversation);
+        }
+        if let Some(audio_ref) = meta.audio_ref {
+            metadata.insert("audio_ref".to_string(), audio_ref);
+            if let Some(mime) = meta.audio_mime {
+                metadata.insert("audio_mime".to_string(), mime);
+            }
+        }
+        msg.metadata = metadata;
+        Ok(msg)
+    }
+
+    /// Publish the message and wait for the agent's answer. Returns an MCP
+    /// `CreateMessageResult`.
+    pub(crate) async fn handle(&self, params: Value) -> Result<Value, (i64, String)> {
+        let msg = self.message(params)?;
+        let message_id = msg.id.clone();
+
+        // Subscribe before publishing so a fast answer is not missed
+        let mut events = self.events.subscribe();
+        let envelope = crate::EnvelopedEvent {
+            event: Arc::new(ClotoEvent::new(ClotoEventData::MessageReceived(msg))),
+            issuer: Some(cloto_shared::ClotoId::from_name(&self.server_id)),
+            correlation_id: None,
+            depth: 0,
+        };
+        self.event_tx
+            .send(envelope)
+            .await
+            .map_err(|_| (INTERNAL_ERROR, "Event bus closed".to_string()))?;
+
+        let reply = tokio::time::timeout(
+            self.reply_timeout,
+            crate::events::wait_for_thought_response(&mut events, &message_id),
+        )
+        .await
+        .map_err(|_| {
+            (
+                INTERNAL_ERROR,
+                format!(
+                    "No agent response within {}s (agent disabled or busy?)",
+                    self.reply_timeout.as_secs()
+                ),
+            )
+        })?
+        .ok_or((INTERNAL_ERROR, "Event bus closed".to_string()))?;
+
+        Ok(serde_json::json!({
+            "role": "assistant",
+            "content": { "type": "text", "text": reply.content },
+            "model": format!("{}/{}", reply.agent_id, reply.engine_id),
+            "stopReason": "endTurn",
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay() -> (ChannelRelay, mpsc::Receiver<crate::EnvelopedEvent>) {
+        let (event_tx, event_rx) = mpsc::channel(4);
+        let (events, _) = broadcast::channel(4);
+        (
+            ChannelRelay::new("adapter.test".to_string(), event_tx, events),
+            event_rx,
+        )
+    }
+
+    #[test]
+    fn test_message_from_sampling_request() {
+        let (relay, _rx) = relay();
+        let msg = relay
+            .message(serde_json::json!({
+                "messages": [
+                    { "role": "user", "content": { "type": "text", "text": "earlier" } },
+                    { "role": "assistant", "content": { "type": "text", "text": "reply" } },
+                    { "role": "user", "content": { "type": "text", "text": "hello" } }
+                ],
+                "maxTokens": 1000,
+                "metadata": {
+                    "agent_id": "agent.a",
+                    "user_id": "U1",
+                    "conversation": "C1:123",
+                    "audio_ref": "/tmp/voice.ogg",
+                    "audio_mime": "audio/ogg"
+                }
+            }))
+            .unwrap();
+        assert_eq!(msg.content, "hello");
+        assert_eq!(msg.metadata["target_agent_id"], "agent.a");
+        assert_eq!(msg.metadata["channel_conversation"], "C1:123");
+        assert_eq!(msg.metadata["audio_ref"], "/tmp/voice.ogg");
+        assert_eq!(msg.metadata["audio_mime"], "audio/ogg");
+        assert!(
+            matches!(msg.source, MessageSource::User { ref id, .. } if id == "adapter.test:U1")
+        );
+
+        let err = relay
+            .message(serde_json::json!({ "messages": [] }))
+            .unwrap_err();
+        assert_eq!(err.0, INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_handle_returns_agent_response() {
+        let (relay, mut rx) = relay();
+        let events = relay.events.clone();
+        let task = tokio::spawn({
+            let relay = relay.clone();
+            async move {
+                relay
+                    .handle(serde_json::json!({
+                        "messages": [{ "role": "user", "content": { "type": "text", "text": "hi" } }]
+                    }))
+                    .await
+            }
+        });
+
+        let envelope = rx.recv().await.unwrap();
+        let ClotoEventData::MessageReceived(ref msg) = envelope.event.data else {
+            panic!("expected MessageReceived");
+        };
+        events
+            .send(
+                Arc::new(ClotoEvent::new(ClotoEventData::ThoughtResponse {
+                    agent_id: "agent.a".to_string(),
+                    engine_id: "mind.test".to_string(),
+                    content: "hello back".to_string(),
+                    source_message_id: msg.id.clone(),
+                    metadata: None,
+                }))
+                .into(),
+            )
+            .unwrap();
+
+        let result = task.await.unwrap().unwrap();
+        assert_eq!(result["content"]["text"], "hello back");
+        assert_eq!(result["model"], "agent.a/mind.test");
+    }
+}
@@ -0,0 +1,419 @@
+//! Active heartbeat — pings each enabled agent's engine on its own schedule.
+//!
+//! Per-agent settings live in the agent metadata (all optional):
+//! - `heartbeat_interval_secs`: ping interval (default: `HEARTBEAT_INTERVAL_SECS`)
+//! - `heartbeat_prompt`: text sent to the engine's `think`; without it the ping
+//!   only checks that the engine plugin is loaded or its MCP server connected
+//! - `heartbeat_quiet_hours`: `HH:MM-HH:MM` in server local time, may wrap
+//!   midnight; the agent is not pinged inside the window
+//!
+//! A successful ping refreshes `last_seen`. When an engine stops answering,
+//! `AgentOffline` is emitted once; `AgentOnline` follows when it recovers.
+
+use super::{AgentManager, PluginRegistry};
+use crate::EnvelopedEvent;
+use chrono::NaiveTime;
+use cloto_shared::{AgentMetadata, ClotoEventData, ClotoMessage, MessageSource};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+/// How often due agents are looked for.
+const TICK: Duration = Duration::from_secs(5);
+/// Bounds for `heartbeat_interval_secs`.
+const MIN_INTERVAL_SECS: u64 = 5;
+const MAX_INTERVAL_SECS: u64 = 86_400;
+/// A prompt ping that takes longer than this counts as no answer.
+const PING_TIMEOUT: Duration = Duration::from_mins(1);
+const MAX_PROMPT_CHARS: usize = 2000;
+
+/// Daily window in which an agent is left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    #[must_use]
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("expected HH:MM-HH:MM, got '{}'", s))?;
+        let parse = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .map_err(|_| anyhow::anyhow!("invalid time '{}' in quiet hours", t.trim()))
+        };
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start == end {
+            anyhow::bail!("quiet hours start and end must differ");
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Heartbeat settings of one agent, read from its metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeartbeatSettings {
+    pub interval: Duration,
+    pub prompt: Option<String>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl HeartbeatSettings {
+    #[must_use]
+    pub fn defaults(interval: Duration) -> Self {
+        Self {
+            interval,
+            prompt: None,
+            quiet_hours: None,
+        }
+    }
+
+    /// Parse the `heartbeat_*` metadata keys.
+    pub fn from_metadata(
+        metadata: &HashMap<String, String>,
+        default_interval: Duration,
+    ) -> anyhow::Result<Self> {
+        let interval = match metadata.get("heartbeat_interval_secs") {
+            Some(v) => {
+                let secs: u64 = v
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("heartbeat_interval_secs must be a number"))?;
+                if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&secs) {
+                    anyhow::bail!(
+                        "heartbeat_interval_secs must be {}-{}",
+                        MIN_INTERVAL_SECS,
+                        MAX_INTERVAL_SECS
+                    );
+                }
+                Duration::from_secs(secs)
+            }
+            None => default_interval,
+        };
+        let prompt = match metadata.get("heartbeat_prompt").map(|p| p.trim()) {
+            Some(p) if p.chars().count() > MAX_PROMPT_CHARS => {
+                anyhow::bail!(
+                    "heartbeat_prompt must be at most {} characters",
+                    MAX_PROMPT_CHARS
+                )
+            }
+            Some("") | None => None,
+            Some(p) => Some(p.to_string()),
+        };
+        let quiet_hours = metadata
+            .get("heartbeat_quiet_hours")
+            .filter(|q| !q.trim().is_empty())
+            .map(|q| q.parse())
+            .transpose()?;
+        Ok(Self {
+            interval,
+            prompt,
+            quiet_hours,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Beat {
+    last_ping: Option<Instant>,
+    /// `None` until the first ping result.
+    online: Option<bool>,
+}
+
+pub struct HeartbeatMonitor {
+    agent_manager: AgentManager,
+    registry: Arc<PluginRegistry>,
+    event_tx: mpsc::Sender<EnvelopedEvent>,
+    default_interval: Duration,
+    beats: HashMap<String, Beat>,
+}
+
+impl HeartbeatMonitor {
+    #[must_use]
+    pub fn new(
+        agent_manager: AgentManager,
+        registry: Arc<PluginRegistry>,
+        event_tx: mpsc::Sender<EnvelopedEvent>,
+        default_interval: Duration,
+    ) -> Self {
+        Self {
+            agent_manager,
+            registry,
+            event_tx,
+            default_interval,
+            beats: HashMap::new(),
+        }
+    }
+
+    pub fn spawn(mut self, shutdown: Arc<tokio::sync::Notify>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK);
+            loop {
+                tokio::select! {
+                    () = shutdown.notified() => {
+                        info!("Active heartbeat task shutting down");
+                        break;
+                    }
+                    _ = interval.tick() => self.tick().await,
+                }
+            }
+        });
+    }
+
+    /// Ping every enabled agent whose interval has elapsed.
+    pub async fn tick(&mut self) {
+        let agents = match self.agent_manager.list_agents().await {
+            Ok(agents) => agents,
+            Err(e) => {
+                error!("Heartbeat: failed to list agents: {}", e);
+                return;
+            }
+        };
+        // Powered-off and deleted agents start over when they come back
+        self.beats
+            .retain(|id, _| agents.iter().any(|a| a.enabled && &a.id == id));
+
+        let now = Instant::now();
+        let local_time = chrono::Local::now().time();
+        let mut due = Vec::new();
+        for agent in agents.into_iter().filter(|a| a.enabled) {
+            let settings = match HeartbeatSettings::from_metadata(
+                &agent.metadata,
+                self.default_interval,
+            ) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    debug!(agent_id = %agent.id, error = %e, "Heartbeat: invalid settings, using defaults");
+                    HeartbeatSettings::defaults(self.default_interval)
+                }
+            };
+            if settings.quiet_hours.is_some_and(|q| q.contains(local_time)) {
+                continue;
+            }
+            let beat = self.beats.entry(agent.id.clone()).or_default();
+            if beat
+                .last_ping
+                .is_some_and(|last| now.duration_since(last) < settings.interval)
+            {
+                continue;
+            }
+            beat.last_ping = Some(now);
+            due.push((agent, settings.prompt));
+        }
+
+        let results = futures::future::join_all(
+            due.iter()
+                .map(|(agent, prompt)| self.ping(agent, prompt.as_deref())),
+        )
+        .await;
+        for ((agent, _), result) in due.iter().zip(results) {
+            self.record(agent, result).await;
+        }
+    }
+
+    async fn ping(&self, agent: &AgentMetadata, prompt: Option<&str>) -> Result<(), String> {
+        let engine_id = agent.default_engine_id.as_deref().unwrap_or_default();
+
+        if let Some(plugin) = self.registry.get_engine(engine_id).await {
+            let Some(prompt) = prompt else {
+                return Ok(());
+            };
+            let engine = plugin
+                .as_reasoning()
+                .ok_or_else(|| format!("plugin '{}' is not a reasoning engine", engine_id))?;
+            let message = ClotoMessage::new(MessageSource::System, prompt.to_string());
+            return match tokio::time::timeout(PING_TIMEOUT, engine.think(agent, &message, vec![]))
+                .await
+            {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err("heartbeat prompt timed out".to_string()),
+            };
+        }
+
+        let Some(ref mcp) = self.registry.mcp_manager else {
+            return Err(format!("engine '{}' not found", engine_id));
+        };
+        if !mcp.is_server_connected(engine_id).await {
+            return Err(format!("engine '{}' is not connected", engine_id));
+        }
+        let Some(prompt) = prompt else {
+            return Ok(());
+        };
+        let message = ClotoMessage::new(MessageSource::System, prompt.to_string());
+        let args = serde_json::json!({
+            "agent": agent,
+            "message": message,
+            "context": [],
+        });
+        match tokio::time::timeout(PING_TIMEOUT, mcp.call_server_tool(engine_id, "think", args))
+            .await
+        {
+            Ok(Ok(result)) if result.is_error != Some(true) => Ok(()),
+            Ok(Ok(_)) => Err("engine returned an error".to_string()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("heartbeat prompt timed out".to_string()),
+        }
+    }
+
+    async fn record(&mut self, agent: &AgentMetadata, result: Result<(), String>) {
+        let online = result.is_ok();
+        let Some(beat) = self.beats.get_mut(&agent.id) else {
+            return;
+        };
+        // The first result only reports an engine that is already down
+        let was_online = beat.online.replace(online).unwrap_or(true);
+        let engine_id = agent.default_engine_id.clone().unwrap_or_default();
+
+        let event = match result {
+            Ok(()) => {
+                if let Err(e) = self.agent_manager.touch_last_seen(&agent.id).await {
+                    error!(agent_id = %agent.id, error = %e, "Heartbeat: failed to update last_seen");
+                }
+                if was_online {
+                    return;
+                }
+                info!(agent_id = %agent.id, engine_id = %engine_id, "💓 Agent engine is responding again");
+                ClotoEventData::AgentOnline {
+                    agent_id: agent.id.clone(),
+                    engine_id,
+                }
+            }
+            Err(reason) => {
+                if !was_online {
+                    return;
+                }
+                warn!(agent_id = %agent.id, engine_id = %engine_id, reason = %reason, "💔 Agent engine stopped responding");
+                ClotoEventData::AgentOffline {
+                    agent_id: agent.id.clone(),
+                    engine_id,
+                    reason,
+                }
+            }
+        };
+        if let Err(e) = self.event_tx.send(EnvelopedEvent::system(event)).await {
+            error!("Heartbeat: failed to send agent status event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let night: QuietHours = "22:00-07:00".parse().unwrap();
+        assert!(night.contains(at(23, 30)));
+        assert!(night.contains(at(6, 59)));
+        assert!(!night.contains(at(7, 0)));
+        assert!(!night.contains(at(12, 0)));
+
+        let lunch: QuietHours = "12:00-13:00".parse().unwrap();
+        assert!(lunch.contains(at(12, 30)));
+        assert!(!lunch.contains(at(13, 0)));
+
+        assert!("12:00".parse::<QuietHours>().is_err());
+        assert!("25:00-07:00".parse::<QuietHours>().is_err());
+        assert!("07:00-07:00".parse::<QuietHours>().is_err());
+    }
+
+    #[test]
+    fn test_settings_from_metadata() {
+        let default = Duration::from_secs(30);
+        assert_eq!(
+            HeartbeatSettings::from_metadata(&HashMap::new(), default).unwrap(),
+            HeartbeatSettings::defaults(default)
+        );
+
+        let metadata: HashMap<String, String> = [
+            ("heartbeat_interval_secs", "300"),
+            ("heartbeat_prompt", " ping "),
+            ("heartbeat_quiet_hours", "22:00-07:00"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let settings = HeartbeatSettings::from_metadata(&metadata, default).unwrap();
+        assert_eq!(settings.interval, Duration::from_mins(5));
+        assert_eq!(settings.prompt.as_deref(), Some("ping"));
+        assert!(settings.quiet_hours.is_some());
+
+        let too_fast: HashMap<String, String> =
+            [("heartbeat_interval_secs".to_string(), "1".to_string())].into();
+        assert!(HeartbeatSettings::from_metadata(&too_fast, default).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_offline_and_online_events_on_transitions() {
+        let pool = crate::db::DbPool::connect("sqlite::memory:").await.unwrap();
+        crate::db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let agent_manager = AgentManager::new(pool);
+        let agent_id = agent_manager
+            .create_agent(
+                "Beat",
+                "Heartbeat test",
+                "mind.missing",
+                HashMap::new(),
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        let (agent, _) = agent_manager.get_agent_config(&agent_id).await.unwrap();
+
+        let (event_tx, mut event_rx) = mpsc::channel(8);
+        let mut monitor = HeartbeatMonitor::new(
+            agent_manager,
+            Arc::new(PluginRegistry::new(5, 10)),
+            event_tx,
+            Duration::from_secs(30),
+        );
+        let offline_events = |rx: &mut mpsc::Receiver<EnvelopedEvent>| {
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .filter(|e| {
+                    matches!(
+                        e.event.data,
+                        ClotoEventData::AgentOffline { ref agent_id, .. } if agent_id == &agent.id
+                    )
+                })
+                .count()
+        };
+
+        // No engine is loaded: the first tick reports the agent offline, once
+        monitor.tick().await;
+        assert_eq!(offline_events(&mut event_rx), 1);
+        monitor.beats.get_mut(&agent.id).unwrap().last_ping = None;
+        monitor.tick().await;
+        assert_eq!(offline_events(&mut event_rx), 0);
+
+        // Recovery is announced
+        monitor.record(&agent, Ok(())).await;
+        let event = event_rx.try_recv().expect("online event");
+        assert!(matches!(
+            event.event.data,
+            ClotoEventData::AgentOnline { .. }
+        ));
+    }
+}
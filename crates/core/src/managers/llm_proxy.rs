@@ -3,27 +3,33 @@
 //! Mind MCP servers call this proxy instead of LLM provider APIs directly.
 //! The proxy adds the appropriate Authorization header from the `llm_providers` table.
 //! This ensures API keys are never exposed to MCP server subprocesses.
+//!
+//! Request bodies are forwarded as-is, so providers with their own wire format
+//! (Gemini's `generateContent`) work as long as the server speaks it. An
+//! `api_url` may contain a `{model}` placeholder for APIs that take the model
+//! in the path.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::db::DbPool;
 use axum::{
+    body::Body,
     extract::State,
     http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::post,
     Json, Router,
 };
 use serde_json::Value;
-use sqlx::SqlitePool;
 use tokio::sync::Notify;
 use tracing::{debug, error, info, warn};
 
 use crate::db;
 
 struct ProxyState {
-    pool: SqlitePool,
+    pool: DbPool,
     http_client: reqwest::Client,
 }
 
@@ -32,7 +38,7 @@ struct ProxyState {
 /// Mind MCP servers send requests to this proxy with an `X-LLM-Provider` header
 /// indicating which provider to route to. The proxy looks up the API key from
 /// the database and forwards the request with proper authentication.
-pub fn spawn_llm_proxy(pool: SqlitePool, port: u16, shutdown: Arc<Notify>) {
+pub fn spawn_llm_proxy(pool: DbPool, port: u16, shutdown: Arc<Notify>) {
     let state = Arc::new(ProxyState {
         pool,
         http_client: reqwest::Client::builder()
@@ -67,11 +73,12 @@ pub fn spawn_llm_proxy(pool: SqlitePool, port: u16, shutdown: Arc<Notify>) {
     });
 }
 
+#[allow(clippy::too_many_lines)]
 async fn proxy_handler(
     State(state): State<Arc<ProxyState>>,
     headers: HeaderMap,
     Json(body): Json<Value>,
-) -> impl IntoResponse {
+) -> Response {
     // Determine provider from header or body
     let provider_id = headers
         .get("X-LLM-Provider")
@@ -91,7 +98,8 @@ async fn proxy_handler(
                 Json(serde_json::json!({
                     "error": { "message": "Missing X-LLM-Provider header or 'provider' field" }
                 })),
-            );
+            )
+                .into_response();
         }
     };
 
@@ -104,7 +112,8 @@ async fn proxy_handler(
                 Json(serde_json::json!({
                     "error": { "message": format!("Provider '{}' not found: {}", provider_id, e) }
                 })),
-            );
+            )
+                .into_response();
         }
     };
 
@@ -114,7 +123,8 @@ async fn proxy_handler(
             Json(serde_json::json!({
                 "error": { "message": format!("Provider '{}' is disabled", provider_id) }
             })),
-        );
+        )
+            .into_response();
     }
 
     // Strip the 'provider' field from body before forwarding
@@ -123,21 +133,33 @@ async fn proxy_handler(
         obj.remove("provider");
     }
 
+    let url = match resolve_upstream_url(&provider.api_url, &provider.model_id, &mut forward_body) {
+        Ok(url) => url,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": { "message": message } })),
+            )
+                .into_response();
+        }
+    };
+
     // Build the forwarded request
     let mut req = state
         .http_client
-        .post(&provider.api_url)
+        .post(&url)
         .header("Content-Type", "application/json")
         .timeout(Duration::from_secs(provider.timeout_secs as u64));
 
     // Add API key if configured
     if !provider.api_key.is_empty() {
-        req = req.header("Authorization", format!("Bearer {}", provider.api_key));
+        let (name, value) = api_key_header(&url, &provider.api_key);
+        req = req.header(name, value);
     }
 
     debug!(
         provider = %provider_id,
-        url = %provider.api_url,
+        url = %url,
         "Proxying LLM request"
     );
 
@@ -145,10 +167,24 @@ async fn proxy_handler(
     match req.json(&forward_body).send().await {
         Ok(response) => {
             let status = response.status();
+            // Streamed completions (`"stream": true`) are relayed as they arrive
+            if status.is_success() && forward_body.get("stream") == Some(&Value::Bool(true)) {
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("text/event-stream")
+                    .to_string();
+                return (
+                    [(axum::http::header::CONTENT_TYPE, content_type)],
+                    Body::from_stream(response.bytes_stream()),
+                )
+                    .into_response();
+            }
             match response.json::<Value>().await {
                 Ok(resp_body) => {
                     if status.is_success() {
-                        (StatusCode::OK, Json(resp_body))
+                        (StatusCode::OK, Json(resp_body)).into_response()
                     } else {
                         warn!(
                             provider = %provider_id,
@@ -160,6 +196,7 @@ async fn proxy_handler(
                                 .unwrap_or(StatusCode::BAD_GATEWAY),
                             Json(resp_body),
                         )
+                            .into_response()
                     }
                 }
                 Err(e) => {
@@ -169,7 +206,7 @@ async fn proxy_handler(
                         Json(serde_json::json!({
                             "error": { "message": format!("Failed to parse provider response: {}", e) }
                         })),
-                    )
+                    ).into_response()
                 }
             }
         }
@@ -180,7 +217,101 @@ async fn proxy_handler(
                 Json(serde_json::json!({
                     "error": { "message": format!("Failed to reach provider '{}': {}", provider_id, e) }
                 })),
-            )
+            ).into_response()
         }
     }
 }
+
+/// Fill a `{model}` placeholder in `api_url` from the request's `model` field
+/// (removed from the body, which such APIs reject) or the provider default.
+fn resolve_upstream_url(
+    api_url: &str,
+    default_model: &str,
+    body: &mut Value,
+) -> Result<String, String> {
+    if !api_url.contains("{model}") {
+        return Ok(api_url.to_string());
+    }
+    let model = body
+        .as_object_mut()
+        .and_then(|obj| obj.remove("model"))
+        .and_then(|m| m.as_str().map(String::from))
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| default_model.to_string());
+    if model.is_empty()
+        || !model
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!("Invalid model name '{}'", model));
+    }
+    Ok(api_url.replace("{model}", &model))
+}
+
+/// Header carrying the provider API key. Google's Generative Language API
+/// takes the key in `x-goog-api-key`; everything else gets a bearer token.
+fn api_key_header(url: &str, api_key: &str) -> (&'static str, String) {
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .unwrap_or_default();
+    if host == "generativelanguage.googleapis.com" {
+        ("x-goog-api-key", api_key.to_string())
+    } else {
+        ("Authorization", format!("Bearer {}", api_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEMINI_URL: &str =
+        "https://generativelanguage.googleapis.com/v1beta/models/{model}:generateContent";
+
+    #[test]
+    fn test_model_placeholder_uses_request_model() {
+        let mut body = serde_json::json!({ "model": "gemini-2.5-pro", "contents": [] });
+        let url = resolve_upstream_url(GEMINI_URL, "gemini-2.0-flash", &mut body).unwrap();
+        assert_eq!(
+            url,
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:generateContent"
+        );
+        assert!(body.get("model").is_none());
+
+        let mut body = serde_json::json!({ "contents": [] });
+        let url = resolve_upstream_url(GEMINI_URL, "gemini-2.0-flash", &mut body).unwrap();
+        assert!(url.contains("/models/gemini-2.0-flash:generateContent"));
+
+        let mut body = serde_json::json!({ "model": "../../v1/files?x=" });
+        assert!(resolve_upstream_url(GEMINI_URL, "gemini-2.0-flash", &mut body).is_err());
+    }
+
+    #[test]
+    fn test_urls_without_placeholder_keep_model_in_body() {
+        let mut body = serde_json::json!({ "model": "deepseek-chat" });
+        let url = resolve_upstream_url(
+            "https://api.deepseek.com/chat/completions",
+            "deepseek-chat",
+            &mut body,
+        )
+        .unwrap();
+        assert_eq!(url, "https://api.deepseek.com/chat/completions");
+        assert_eq!(body["model"], "deepseek-chat");
+    }
+
+    #[test]
+    fn test_api_key_header_per_provider() {
+        assert_eq!(
+            api_key_header(
+                "https://generativelanguage.googleapis.com/v1beta/models/m:generateContent",
+                "k"
+            ),
+            ("x-goog-api-key", "k".to_string())
+        );
+        assert_eq!(
+            api_key_header("https://api.deepseek.com/chat/completions", "k"),
+            ("Authorization", "Bearer k".to_string())
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Loop detection for the agentic tool-use loop.
+//!
+//! Each round of tool calls the engine requests is compared with the rounds
+//! before it in the same turn. Asking for exactly the previous round again is
+//! a repeat; a sequence such as A, B, A, B (period 2 or 3) is an oscillation.
+//! The first detection earns the engine a corrective message instead of the
+//! tool results; the second ends the turn with an explanation for the user.
+
+use cloto_shared::ToolCall;
+
+/// Longest cycle recognised as an oscillation.
+const MAX_CYCLE: usize = 3;
+/// Characters of the arguments shown when describing a call.
+const MAX_ARGS_CHARS: usize = 120;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopPattern {
+    /// The same round of calls was requested twice in a row.
+    Repeated { calls: Vec<String> },
+    /// The engine keeps cycling through the same rounds of calls.
+    Oscillating { cycle: Vec<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopVerdict {
+    Continue,
+    /// Skip this round and tell the engine to stop repeating itself.
+    Warn(LoopPattern),
+    /// Give up on the turn.
+    Abort(LoopPattern),
+}
+
+#[derive(Debug, Default)]
+pub struct LoopGuard {
+    rounds: Vec<Vec<String>>,
+    strikes: u8,
+}
+
+impl LoopGuard {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a round of tool calls and judge it against the earlier rounds.
+    pub fn check(&mut self, calls: &[ToolCall]) -> LoopVerdict {
+        let mut round: Vec<String> = calls.iter().map(describe_call).collect();
+        round.sort();
+        self.rounds.push(round);
+
+        let Some(pattern) = self.detect() else {
+            return LoopVerdict::Continue;
+        };
+        self.strikes += 1;
+        if self.strikes == 1 {
+            LoopVerdict::Warn(pattern)
+        } else {
+            LoopVerdict::Abort(pattern)
+        }
+    }
+
+    fn detect(&self) -> Option<LoopPattern> {
+        let n = self.rounds.len();
+        if n >= 2 && self.rounds[n - 1] == self.rounds[n - 2] {
+            return Some(LoopPattern::Repeated {
+                calls: self.rounds[n - 1].clone(),
+            });
+        }
+        (2..=MAX_CYCLE)
+            .filter(|p| n >= 2 * p)
+            .find(|&p| self.rounds[n - p..] == self.rounds[n - 2 * p..n - p])
+            .map(|p| LoopPattern::Oscillating {
+                cycle: self.rounds[n - p..]
+                    .iter()
+                    .map(|round| round.join(" + "))
+                    .collect(),
+            })
+    }
+}
+
+impl LoopPattern {
+    /// Machine-readable reason carried by `AgenticLoopAborted`.
+    #[must_use]
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Repeated { .. } => "repeated_tool_calls",
+            Self::Oscillating { .. } => "oscillating_tool_calls",
+        }
+    }
+
+    /// The calls involved, for events and logs.
+    #[must_use]
+    pub fn calls(&self) -> &[String] {
+        match self {
+            Self::Repeated { calls } => calls,
+            Self::Oscillating { cycle } => cycle,
+        }
+    }
+
+    /// System message telling the engine to change course.
+    #[must_use]
+    pub fn correction(&self) -> String {
+        match self {
+            Self::Repeated { calls } => format!(
+                "You already ran this: {}. The results are above and did not change. \
+                 Do not repeat the same calls; use those results or answer the user.",
+                calls.join(", ")
+            ),
+            Self::Oscillating { cycle } => format!(
+                "You are cycling through the same tool calls ({}) without making progress. \
+                 Stop repeating them; use the results above or answer the user.",
+                cycle.join(" → ")
+            ),
+        }
+    }
+
+    /// Explanation shown in the chat when the turn is abandoned.
+    #[must_use]
+    pub fn explanation(&self, iterations: u8, tool_calls: u32) -> String {
+        let what = match self {
+            Self::Repeated { .. } => "kept repeating the same tool calls",
+            Self::Oscillating { .. } => "kept cycling through the same tool calls",
+        };
+        let calls: Vec<String> = self.calls().iter().map(|c| format!("- `{}`", c)).collect();
+        format!(
+            "⚠️ I stopped working on this request because I {} and was not making progress.\n\n\
+             Calls involved:\n{}\n\n\
+             Stopped after {} iterations and {} tool calls. \
+             Rephrasing the request or adding more specific instructions may help.",
+            what,
+            calls.join("\n"),
+            iterations,
+            tool_calls
+        )
+    }
+}
+
+fn describe_call(call: &ToolCall) -> String {
+    let args = call.arguments.to_string();
+    if args.chars().count() > MAX_ARGS_CHARS {
+        let truncated: String = args.chars().take(MAX_ARGS_CHARS).collect();
+        format!("{}({}…)", call.name, truncated)
+    } else {
+        format!("{}({})", call.name, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, query: &str) -> ToolCall {
+        ToolCall {
+            id: format!("call_{}", name),
+            name: name.to_string(),
+            arguments: serde_json::json!({ "query": query }),
+        }
+    }
+
+    #[test]
+    fn test_repeated_round_warns_then_aborts() {
+        let mut guard = LoopGuard::new();
+        assert_eq!(
+            guard.check(&[call("search", "rust")]),
+            LoopVerdict::Continue
+        );
+        let LoopVerdict::Warn(pattern) = guard.check(&[call("search", "rust")]) else {
+            panic!("expected a warning");
+        };
+        assert_eq!(pattern.reason(), "repeated_tool_calls");
+        assert!(pattern.correction().contains(r#"search({"query":"rust"})"#));
+        assert!(matches!(
+            guard.check(&[call("search", "rust")]),
+            LoopVerdict::Abort(LoopPattern::Repeated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_progress_is_not_a_loop() {
+        let mut guard = LoopGuard::new();
+        assert_eq!(
+            guard.check(&[call("search", "rust")]),
+            LoopVerdict::Continue
+        );
+        assert_eq!(
+            guard.check(&[call("search", "tokio")]),
+            LoopVerdict::Continue
+        );
+        assert_eq!(
+            guard.check(&[call("fetch", "a"), call("fetch", "b")]),
+            LoopVerdict::Continue
+        );
+        // Same calls in another order are the same round
+        assert!(matches!(
+            guard.check(&[call("fetch", "b"), call("fetch", "a")]),
+            LoopVerdict::Warn(LoopPattern::Repeated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_oscillation_detected() {
+        let mut guard = LoopGuard::new();
+        for (name, query) in [("read", "x"), ("write", "x"), ("read", "x")] {
+            assert_eq!(guard.check(&[call(name, query)]), LoopVerdict::Continue);
+        }
+        let LoopVerdict::Warn(LoopPattern::Oscillating { cycle }) =
+            guard.check(&[call("write", "x")])
+        else {
+            panic!("expected an oscillation");
+        };
+        assert_eq!(cycle.len(), 2);
+        let explanation = LoopPattern::Oscillating { cycle }.explanation(4, 4);
+        assert!(explanation.contains("4 iterations and 4 tool calls"));
+    }
+}
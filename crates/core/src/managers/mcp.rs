@@ -1,24 +1,205 @@
+use super::mcp_manifest;
 use super::mcp_protocol::{
     CallToolParams, CallToolResult, ClientCapabilities, ClientInfo, ClotoHandshakeParams,
     ClotoHandshakeResult, InitializeParams, JsonRpcRequest, JsonRpcResponse, ListToolsResult,
     McpConfigFile, McpServerConfig, McpTool, ToolContent,
 };
-use super::mcp_transport::{self, StdioTransport};
+use super::mcp_transport::{self, ResourceLimits, Transport};
+use super::mcp_venv;
+use crate::db::DbPool;
 use anyhow::{Context, Result};
+use futures::StreamExt;
 use serde_json::Value;
-use sqlx::SqlitePool;
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, oneshot, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
+// ============================================================
+// NotificationSink — server → kernel notifications
+// ============================================================
+
+/// Notification carrying a `ColorVisionData` payload, published as `VisionUpdated`.
+pub const VISION_UPDATED_NOTIFICATION: &str = "notifications/cloto.vision_updated";
+
+/// Notification carrying a serialized `ClotoEventData` (`{"type": ..., "data": ...}`),
+/// used by servers that stream events (e.g. gaze tracking) instead of answering tool calls.
+pub const EMIT_NOTIFICATION: &str = "notifications/cloto.emit";
+
+const DEFAULT_EVENT_RATE_PER_SEC: u32 = 30;
+
+/// Forwards notifications sent by an MCP server onto the kernel event bus.
+#[derive(Clone)]
+pub(crate) struct NotificationSink {
+    server_id: String,
+    event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+    /// The server declared (and was granted) `VisionRead` or `CameraRead`.
+    vision: bool,
+    /// Shared by all instances of a server
+    rate_limiter: Arc<governor::DefaultDirectRateLimiter>,
+    /// Set when the server declared (and was granted) `ChannelRelay`.
+    relay: Option<super::channel_relay::ChannelRelay>,
+}
+
+impl NotificationSink {
+    fn new(
+        server_id: String,
+        event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+        vision: bool,
+        relay: Option<super::channel_relay::ChannelRelay>,
+    ) -> Self {
+        use governor::{Quota, RateLimiter};
+        use std::num::NonZeroU32;
+
+        let rate = std::env::var("CLOTO_MCP_EVENT_RATE_PER_SEC")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .and_then(NonZeroU32::new)
+            .unwrap_or(NonZeroU32::new(DEFAULT_EVENT_RATE_PER_SEC).unwrap());
+        Self {
+            server_id,
+            event_tx,
+            vision,
+            rate_limiter: Arc::new(RateLimiter::direct(Quota::per_second(rate))),
+            relay,
+        }
+    }
+
+    /// Answer a request sent by the server. Only `sampling/createMessage`
+    /// from `ChannelRelay` servers is supported; it is handled in the
+    /// background because the agent may take a while to respond.
+    fn handle_request(
+        &self,
+        id: Value,
+        method: &str,
+        params: Option<Value>,
+        reply: mpsc::Sender<String>,
+    ) {
+        use super::channel_relay::{METHOD_NOT_FOUND, SAMPLING_METHOD};
+        use super::mcp_protocol::JsonRpcError;
+
+        let relay = self.relay.clone().filter(|_| method == SAMPLING_METHOD);
+        let limited = relay.is_some() && self.rate_limiter.check().is_err();
+        let server_id = self.server_id.clone();
+        let method = method.to_string();
+        tokio::spawn(async move {
+            let outcome = match relay {
+                _ if limited => Err((
+                    super::channel_relay::INTERNAL_ERROR,
+                    "Rate limit exceeded".to_string(),
+                )),
+                Some(relay) => relay.handle(params.unwrap_or(Value::Null)).await,
+                None => Err((
+                    METHOD_NOT_FOUND,
+                    format!("Method not supported: {}", method),
+                )),
+            };
+            let response = match outcome {
+                Ok(result) => JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id: Some(id),
+                    result: Some(result),
+                    error: None,
+                },
+                Err((code, message)) => {
+                    debug!(server_id = %server_id, "{} request failed: {}", method, message);
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: Some(id),
+                        result: None,
+                        error: Some(JsonRpcError {
+                            code,
+                            message,
+                            data: None,
+                        }),
+                    }
+                }
+            };
+            if let Ok(line) = serde_json::to_string(&response) {
+                let _ = reply.send(line).await;
+            }
+        });
+    }
+
+    async fn dispatch(&self, method: &str, params: Option<Value>) {
+        let params = params.unwrap_or(Value::Null);
+        let data = match method {
+            VISION_UPDATED_NOTIFICATION => {
+                serde_json::from_value(params).map(cloto_shared::ClotoEventData::VisionUpdated)
+            }
+            EMIT_NOTIFICATION => serde_json::from_value(params),
+            other => {
+                debug!(server_id = %self.server_id, "Ignoring notification: {}", other);
+                return;
+            }
+        };
+        match data {
+            Ok(mut data) => {
+                // Engines stream their own replies only
+                if let cloto_shared::ClotoEventData::ThoughtResponseChunk {
+                    ref mut engine_id,
+                    ..
+                } = data
+                {
+                    engine_id.clone_from(&self.server_id);
+                }
+                self.publish(data).await;
+            }
+            Err(e) => warn!(server_id = %self.server_id, "Malformed {} payload: {}", method, e),
+        }
+    }
+
+    /// Servers may only publish observation events; anything that asks the
+    /// kernel to act (messages, actions, permissions) must go through tools.
+    fn is_allowed(&self, data: &cloto_shared::ClotoEventData) -> bool {
+        use cloto_shared::ClotoEventData;
+        match data {
+            ClotoEventData::VisionUpdated(_) => self.vision,
+            ClotoEventData::GazeUpdated(_)
+            | ClotoEventData::SystemNotification(_)
+            | ClotoEventData::ThoughtResponseChunk { .. } => true,
+            _ => false,
+        }
+    }
+
+    async fn publish(&self, data: cloto_shared::ClotoEventData) {
+        if !self.is_allowed(&data) {
+            warn!(
+                server_id = %self.server_id,
+                "Dropping event not permitted from MCP servers: {}",
+                serde_json::to_value(&data)
+                    .ok()
+                    .and_then(|v| v.get("type").and_then(serde_json::Value::as_str).map(str::to_string))
+                    .unwrap_or_default()
+            );
+            return;
+        }
+        if self.rate_limiter.check().is_err() {
+            debug!(server_id = %self.server_id, "Event rate limit exceeded, dropping event");
+            return;
+        }
+        let envelope = crate::EnvelopedEvent {
+            event: Arc::new(cloto_shared::ClotoEvent::new(data)),
+            issuer: Some(cloto_shared::ClotoId::from_name(&self.server_id)),
+            correlation_id: None,
+            depth: 0,
+        };
+        if self.event_tx.send(envelope).await.is_err() {
+            debug!(
+                "Event bus closed, dropping event from [MCP] {}",
+                self.server_id
+            );
+        }
+    }
+}
+
 // ============================================================
 // McpClient — JSON-RPC client for a single MCP server
 // ============================================================
 
 pub struct McpClient {
-    transport: Arc<Mutex<StdioTransport>>,
+    transport: Arc<Mutex<Box<dyn Transport>>>,
     /// Cloned sender for lock-free request dispatch.
     /// The response loop holds `transport` Mutex during recv(); sending through
     /// this channel avoids the deadlock where call() would block on the same Mutex.
@@ -26,6 +207,19 @@ pub struct McpClient {
     pending_requests: Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value>>>>>,
     next_id: Arc<AtomicI64>,
     response_task: Option<tokio::task::JoinHandle<()>>,
+    /// Requests currently awaiting a response (used for instance routing)
+    in_flight: Arc<AtomicUsize>,
+    requests_total: Arc<AtomicU64>,
+    errors_total: Arc<AtomicU64>,
+}
+
+/// Decrements the in-flight counter when a request finishes (or is dropped).
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl Drop for McpClient {
@@ -40,12 +234,12 @@ impl McpClient {
     const MAX_PENDING_REQUESTS: usize = 100;
     const REQUEST_TIMEOUT_SECS: u64 = 120;
 
-    pub async fn connect(
-        command: &str,
-        args: &[String],
+    pub(crate) async fn connect(
+        config: &McpServerConfig,
         env: &HashMap<String, String>,
+        sink: Option<NotificationSink>,
     ) -> Result<Self> {
-        let transport = StdioTransport::start(command, args, env).await?;
+        let transport = mcp_transport::connect(config, env).await?;
         let sender = transport.sender();
         let mut client = Self {
             transport: Arc::new(Mutex::new(transport)),
@@ -53,17 +247,21 @@ impl McpClient {
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
             next_id: Arc::new(AtomicI64::new(1)),
             response_task: None,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            requests_total: Arc::new(AtomicU64::new(0)),
+            errors_total: Arc::new(AtomicU64::new(0)),
         };
 
-        client.start_response_loop();
+        client.start_response_loop(sink);
         client.initialize().await?;
 
         Ok(client)
     }
 
-    fn start_response_loop(&mut self) {
+    fn start_response_loop(&mut self, sink: Option<NotificationSink>) {
         let transport = self.transport.clone();
         let pending = self.pending_requests.clone();
+        let reply = self.sender.clone();
 
         let handle = tokio::spawn(async move {
             loop {
@@ -73,6 +271,24 @@ impl McpClient {
                 };
 
                 if let Some(line) = msg_opt {
+                    // Notifications carry a method but no id; server requests carry both
+                    if let Ok(request) = serde_json::from_str::<JsonRpcRequest>(&line) {
+                        match (request.id, &sink) {
+                            (None, Some(sink)) => {
+                                sink.dispatch(&request.method, request.params).await;
+                            }
+                            (Some(id), Some(sink)) => {
+                                sink.handle_request(
+                                    id,
+                                    &request.method,
+                                    request.params,
+                                    reply.clone(),
+                                );
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
                     if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(&line) {
                         if let Some(id_val) = response.id {
                             if let Some(id) = id_val.as_i64() {
@@ -127,6 +343,17 @@ impl McpClient {
     }
 
     async fn call(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        let _guard = InFlightGuard(self.in_flight.clone());
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        let result = self.send_request(method, params).await;
+        if result.is_err() {
+            self.errors_total.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value> {
         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
 
         let request = JsonRpcRequest::new(id, method, params);
@@ -166,7 +393,9 @@ impl McpClient {
     async fn initialize(&self) -> Result<()> {
         let params = InitializeParams {
             protocol_version: "2024-11-05".to_string(),
-            capabilities: ClientCapabilities {},
+            capabilities: ClientCapabilities {
+                sampling: Some(serde_json::json!({})),
+            },
             client_info: ClientInfo {
                 name: "CLOTO-KERNEL".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -221,6 +450,7 @@ impl McpClient {
     pub async fn cloto_handshake(&self) -> Result<Option<ClotoHandshakeResult>> {
         let params = ClotoHandshakeParams {
             kernel_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: mcp_manifest::CLOTO_PROTOCOL_VERSION,
         };
 
         match self
@@ -245,6 +475,32 @@ impl McpClient {
     pub fn is_alive(&self) -> bool {
         !self.sender.is_closed()
     }
+
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn health(&self, index: usize) -> InstanceHealth {
+        InstanceHealth {
+            index,
+            alive: self.is_alive(),
+            in_flight: self.in_flight(),
+            requests_total: self.requests_total.load(Ordering::Relaxed),
+            errors_total: self.errors_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Per-process health of a (possibly multi-instance) MCP server.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InstanceHealth {
+    pub index: usize,
+    pub alive: bool,
+    pub in_flight: usize,
+    pub requests_total: u64,
+    pub errors_total: u64,
 }
 
 // ============================================================
@@ -256,12 +512,40 @@ pub struct McpServerHandle {
     pub id: String,
     pub config: McpServerConfig,
     pub client: Option<Arc<McpClient>>,
+    /// Additional instances when `config.instances > 1` (the primary `client`
+    /// is used for discovery and notifications)
+    pub replicas: Vec<Arc<McpClient>>,
     pub tools: Vec<McpTool>,
     pub handshake: Option<ClotoHandshakeResult>,
     pub status: ServerStatus,
     pub source: ServerSource,
 }
 
+impl McpServerHandle {
+    /// Pick the live instance with the fewest in-flight requests, falling back
+    /// to the primary so callers still get its error when everything is down.
+    #[must_use]
+    pub fn route(&self) -> Option<Arc<McpClient>> {
+        self.client
+            .iter()
+            .chain(self.replicas.iter())
+            .filter(|c| c.is_alive())
+            .min_by_key(|c| c.in_flight())
+            .or(self.client.as_ref())
+            .cloned()
+    }
+
+    #[must_use]
+    pub fn instance_health(&self) -> Vec<InstanceHealth> {
+        self.client
+            .iter()
+            .chain(self.replicas.iter())
+            .enumerate()
+            .map(|(i, c)| c.health(i))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ServerSource {
@@ -297,15 +581,73 @@ pub struct McpServerInfo {
     pub tools: Vec<String>,
     pub is_cloto_sdk: bool,
     pub source: ServerSource,
+    /// Per-instance health (empty when not running)
+    pub instances: Vec<InstanceHealth>,
 }
 
 // ============================================================
 // McpClientManager — kernel-level MCP server orchestrator
 // ============================================================
 
+/// Upper bound for `instances` in a server config.
+const MAX_INSTANCES: usize = 16;
+
+/// Servers from `mcp.toml` connected at the same time during startup.
+const MAX_PARALLEL_STARTUPS: usize = 8;
+
+const DEFAULT_STARTUP_TIMEOUT_SECS: u64 = 60;
+
+/// Per-server startup budget; servers with `python_requirements` also get the
+/// venv install timeout.
+fn startup_timeout() -> std::time::Duration {
+    std::time::Duration::from_secs(
+        std::env::var("CLOTO_MCP_STARTUP_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&s| s > 0)
+            .unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECS),
+    )
+}
+
+/// Group servers into startup waves: a server is started in the wave after
+/// the last server it `depends_on`. Unknown dependencies are ignored and
+/// servers caught in a dependency cycle start together in the last wave.
+fn startup_waves(configs: Vec<McpServerConfig>) -> Vec<Vec<McpServerConfig>> {
+    let ids: HashSet<String> = configs.iter().map(|c| c.id.clone()).collect();
+    for config in &configs {
+        for dep in config.depends_on.iter().filter(|d| !ids.contains(*d)) {
+            warn!(id = %config.id, dependency = %dep, "Unknown MCP server dependency ignored");
+        }
+    }
+
+    let mut started = HashSet::new();
+    let mut pending = configs;
+    let mut waves = Vec::new();
+    while !pending.is_empty() {
+        let (ready, blocked): (Vec<_>, Vec<_>) = pending.into_iter().partition(|c| {
+            c.depends_on
+                .iter()
+                .all(|d| started.contains(d) || !ids.contains(d))
+        });
+        if ready.is_empty() {
+            let cycle: Vec<&str> = blocked.iter().map(|c| c.id.as_str()).collect();
+            warn!(
+                "MCP server dependency cycle among [{}]; starting them together",
+                cycle.join(", ")
+            );
+            waves.push(blocked);
+            break;
+        }
+        started.extend(ready.iter().map(|c| c.id.clone()));
+        waves.push(ready);
+        pending = blocked;
+    }
+    waves
+}
+
 pub struct McpClientManager {
     servers: RwLock<HashMap<String, McpServerHandle>>,
-    pool: SqlitePool,
+    pool: DbPool,
     /// Tool name → server ID index for fast routing
     tool_index: RwLock<HashMap<String, String>>,
     /// YOLO mode: auto-approve all MCP server permissions (ARCHITECTURE.md §5.7).
@@ -313,18 +655,110 @@ pub struct McpClientManager {
     pub yolo_mode: Arc<AtomicBool>,
     /// Preserved configs from stopped servers, enabling restart for config-loaded servers
     stopped_configs: RwLock<HashMap<String, (McpServerConfig, ServerSource)>>,
+    /// Event bus for notifications sent by servers (e.g. vision updates)
+    event_tx: Option<mpsc::Sender<crate::EnvelopedEvent>>,
+    /// Processed events, watched for agent replies to relayed channel messages
+    events: Option<broadcast::Sender<crate::events::SerializedEvent>>,
+    /// Tool calls blocked on an administrator's decision, by request ID
+    tool_approvals: ToolApprovalWaiters,
+    tool_approval_timeout_secs: AtomicU64,
+    /// Redacts the arguments of calls waiting for approval before they are
+    /// stored or broadcast
+    tool_audit: Option<Arc<super::tool_audit::ToolAudit>>,
+    /// In-flight tool calls, waited for by a draining shutdown
+    drain: Arc<crate::drain::DrainTracker>,
+}
+
+type ToolApprovalWaiters = Arc<std::sync::Mutex<HashMap<String, oneshot::Sender<bool>>>>;
+
+const DEFAULT_TOOL_APPROVAL_TIMEOUT_SECS: u64 = 300;
+
+/// Held while a tool call waits for approval. If the wait ends without a
+/// decision (timeout, or the caller gave up), the request is marked expired.
+struct PendingToolApproval {
+    id: String,
+    pool: DbPool,
+    waiters: ToolApprovalWaiters,
+}
+
+impl Drop for PendingToolApproval {
+    fn drop(&mut self) {
+        let undecided = self
+            .waiters
+            .lock()
+            .is_ok_and(|mut waiters| waiters.remove(&self.id).is_some());
+        if undecided {
+            let (pool, id) = (self.pool.clone(), std::mem::take(&mut self.id));
+            tokio::spawn(async move {
+                if let Err(e) =
+                    crate::db::decide_tool_invocation_request(&pool, &id, "expired", "system").await
+                {
+                    warn!(request_id = %id, "Failed to expire tool approval request: {}", e);
+                }
+            });
+        }
+    }
 }
 
 impl McpClientManager {
     #[must_use]
-    pub fn new(pool: SqlitePool, yolo_mode: bool) -> Self {
+    pub fn new(pool: DbPool, yolo_mode: bool) -> Self {
         Self {
             servers: RwLock::new(HashMap::new()),
             pool,
             tool_index: RwLock::new(HashMap::new()),
             yolo_mode: Arc::new(AtomicBool::new(yolo_mode)),
             stopped_configs: RwLock::new(HashMap::new()),
+            event_tx: None,
+            events: None,
+            tool_approvals: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            tool_approval_timeout_secs: AtomicU64::new(DEFAULT_TOOL_APPROVAL_TIMEOUT_SECS),
+            tool_audit: None,
+            drain: Arc::new(crate::drain::DrainTracker::new()),
+        }
+    }
+
+    pub fn set_tool_approval_timeout_secs(&self, secs: u64) {
+        self.tool_approval_timeout_secs
+            .store(secs, Ordering::Relaxed);
+    }
+
+    /// Whether calls to `tool_name` currently wait for an administrator's
+    /// approval (listed in its server's `approval_required_tools`, YOLO off).
+    /// Returns the longest such a call may wait.
+    pub async fn tool_approval_wait(&self, tool_name: &str) -> Option<std::time::Duration> {
+        if self.yolo_mode.load(Ordering::Relaxed) {
+            return None;
         }
+        let server_id = self.tool_index.read().await.get(tool_name).cloned()?;
+        let servers = self.servers.read().await;
+        servers
+            .get(&server_id)?
+            .config
+            .approval_required_tools
+            .iter()
+            .any(|t| t == tool_name)
+            .then(|| {
+                std::time::Duration::from_secs(
+                    self.tool_approval_timeout_secs.load(Ordering::Relaxed),
+                )
+            })
+    }
+
+    pub fn set_event_tx(&mut self, tx: mpsc::Sender<crate::EnvelopedEvent>) {
+        self.event_tx = Some(tx);
+    }
+
+    pub fn set_event_bus(&mut self, events: broadcast::Sender<crate::events::SerializedEvent>) {
+        self.events = Some(events);
+    }
+
+    pub fn set_tool_audit(&mut self, tool_audit: Arc<super::tool_audit::ToolAudit>) {
+        self.tool_audit = Some(tool_audit);
+    }
+
+    pub fn set_drain_tracker(&mut self, drain: Arc<crate::drain::DrainTracker>) {
+        self.drain = drain;
     }
 
     /// Load server configs from mcp.toml file (if exists) and connect.
@@ -364,7 +798,7 @@ impl McpClientManager {
             base_dir.display()
         );
 
-        let mut failed = 0usize;
+        let mut configs = Vec::with_capacity(total);
         for mut server_config in config.servers {
             // Resolve relative paths in args against the base directory
             server_config.args = server_config
@@ -381,11 +815,27 @@ impl McpClientManager {
                     arg
                 })
                 .collect();
+            configs.push(server_config);
+        }
 
-            if let Err(e) = self
-                .connect_server(server_config.clone(), ServerSource::Config)
-                .await
-            {
+        // Independent servers start concurrently; each wave waits for the
+        // servers the next one depends on.
+        let startup_timeout = startup_timeout();
+        let mut failed = 0usize;
+        for wave in startup_waves(configs) {
+            let results: Vec<(McpServerConfig, Result<()>)> = futures::stream::iter(wave)
+                .map(|server_config| async move {
+                    let result = self
+                        .start_config_server(server_config.clone(), startup_timeout)
+                        .await;
+                    (server_config, result)
+                })
+                .buffer_unordered(MAX_PARALLEL_STARTUPS)
+                .collect()
+                .await;
+
+            for (server_config, result) in results {
+                let Err(e) = result else { continue };
                 failed += 1;
                 warn!(
                     id = %server_config.id,
@@ -400,6 +850,7 @@ impl McpClientManager {
                         id: server_config.id.clone(),
                         config: server_config,
                         client: None,
+                        replicas: Vec::new(),
                         tools: Vec::new(),
                         handshake: None,
                         status: ServerStatus::Error(e.to_string()),
@@ -421,6 +872,29 @@ impl McpClientManager {
         Ok(())
     }
 
+    /// Connect one server from `mcp.toml`, giving up after `startup_timeout`.
+    async fn start_config_server(
+        &self,
+        config: McpServerConfig,
+        startup_timeout: std::time::Duration,
+    ) -> Result<()> {
+        let timeout = if config.python_requirements.is_empty() {
+            startup_timeout
+        } else {
+            startup_timeout + mcp_venv::install_timeout()
+        };
+        let id = config.id.clone();
+        match tokio::time::timeout(timeout, self.connect_server(config, ServerSource::Config)).await
+        {
+            Ok(result) => result.map(|_| ()),
+            Err(_) => Err(anyhow::anyhow!(
+                "MCP server '{}' did not start within {}s",
+                id,
+                timeout.as_secs()
+            )),
+        }
+    }
+
     /// Restore persisted MCP servers from the database.
     pub async fn restore_from_db(&self) -> Result<()> {
         let records = crate::db::load_active_mcp_servers(&self.pool).await?;
@@ -440,9 +914,16 @@ impl McpClientManager {
                 args,
                 env: db_env,
                 transport: "stdio".to_string(),
+                url: None,
+                headers: HashMap::new(),
                 auto_restart: true,
                 required_permissions: Vec::new(),
                 tool_validators: HashMap::new(),
+                python_requirements: Vec::new(),
+                instances: 1,
+                depends_on: Vec::new(),
+                approval_required_tools: Vec::new(),
+                resource_limits: ResourceLimits::default(),
             };
 
             // Regenerate script file if needed
@@ -479,6 +960,7 @@ impl McpClientManager {
                         id: config.id.clone(),
                         config,
                         client: None,
+                        replicas: Vec::new(),
                         tools: Vec::new(),
                         handshake: None,
                         status: ServerStatus::Error(e.to_string()),
@@ -490,17 +972,164 @@ impl McpClientManager {
         Ok(())
     }
 
+    /// Ensure the managed venv for a server declaring `python_requirements` is
+    /// installed and return its launch environment.
+    ///
+    /// Installing a new or changed requirement set needs an approved
+    /// `PackageInstall` request bound to that exact set (auto-approved in YOLO
+    /// mode); until then the server is blocked with a pending request.
+    async fn prepare_python_env(
+        &self,
+        config: &McpServerConfig,
+    ) -> Result<HashMap<String, String>> {
+        let id = &config.id;
+        let reqs = &config.python_requirements;
+        for req in reqs {
+            mcp_venv::validate_requirement(req).with_context(|| {
+                format!("MCP server '{}' has an invalid python requirement", id)
+            })?;
+        }
+
+        if !mcp_venv::is_installed(id, reqs).await {
+            let perm = mcp_venv::PACKAGE_INSTALL_PERMISSION;
+            let request_id = format!(
+                "mcp-{}-{}-{}",
+                id,
+                perm,
+                mcp_venv::requirements_fingerprint(reqs)
+            );
+            let packages = mcp_venv::normalize_requirements(reqs).join(", ");
+            let approved = crate::db::is_permission_request_approved(&self.pool, &request_id)
+                .await
+                .unwrap_or(false);
+
+            if !approved {
+                let yolo = self.yolo_mode.load(Ordering::Relaxed);
+                let request = crate::db::PermissionRequest {
+                    request_id: request_id.clone(),
+                    created_at: chrono::Utc::now(),
+                    plugin_id: id.clone(),
+                    permission_type: perm.to_string(),
+                    target_resource: Some(packages.clone()),
+                    justification: if yolo {
+                        format!(
+                            "MCP server '{}' installs Python packages (auto-approved: YOLO mode)",
+                            id
+                        )
+                    } else {
+                        format!(
+                            "MCP server '{}' needs Python packages installed into its venv: {}",
+                            id, packages
+                        )
+                    },
+                    status: if yolo { "approved" } else { "pending" }.to_string(),
+                    approved_by: yolo.then(|| "YOLO".to_string()),
+                    approved_at: yolo.then(chrono::Utc::now),
+                    expires_at: None,
+                    metadata: Some(serde_json::json!({
+                        "source": "mcp_python_env",
+                        "requirements": mcp_venv::normalize_requirements(reqs),
+                    })),
+                };
+                if let Err(e) = crate::db::create_permission_request(&self.pool, request).await {
+                    debug!("Package install request note for [MCP] {}: {}", id, e);
+                }
+                if !yolo {
+                    return Err(anyhow::anyhow!(
+                        "MCP server '{}' blocked: package installation pending approval \
+                         (request '{}': {}). Approve via dashboard or API, then retry.",
+                        id,
+                        request_id,
+                        packages
+                    ));
+                }
+            }
+
+            mcp_venv::install(id, &config.command, reqs)
+                .await
+                .with_context(|| format!("Failed to prepare Python environment for '{}'", id))?;
+        }
+
+        let mut env = config.env.clone();
+        env.extend(mcp_venv::activation_env(id));
+        Ok(env)
+    }
+
+    fn notification_sink(&self, config: &McpServerConfig) -> Option<NotificationSink> {
+        self.event_tx.clone().map(|event_tx| {
+            let relay = self
+                .events
+                .clone()
+                .filter(|_| {
+                    config
+                        .required_permissions
+                        .iter()
+                        .any(|p| p == "ChannelRelay")
+                })
+                .map(|events| {
+                    super::channel_relay::ChannelRelay::new(
+                        config.id.clone(),
+                        event_tx.clone(),
+                        events,
+                    )
+                });
+            NotificationSink::new(
+                config.id.clone(),
+                event_tx,
+                config
+                    .required_permissions
+                    .iter()
+                    .any(|p| p == "VisionRead" || p == "CameraRead"),
+                relay,
+            )
+        })
+    }
+
+    /// Start the additional instances of a multi-instance server. Instances that
+    /// fail to start are logged and skipped; the server runs with fewer.
+    async fn spawn_replicas(
+        config: &McpServerConfig,
+        env: &HashMap<String, String>,
+        sink: Option<&NotificationSink>,
+    ) -> Vec<Arc<McpClient>> {
+        let count = config.instances.clamp(1, MAX_INSTANCES) - 1;
+        let mut replicas = Vec::with_capacity(count);
+        for i in 1..=count {
+            match McpClient::connect(config, env, sink.cloned()).await {
+                Ok(c) => replicas.push(Arc::new(c)),
+                Err(e) => warn!(
+                    "Failed to start instance {} of [MCP] {}: {}",
+                    i, config.id, e
+                ),
+            }
+        }
+        if count > 0 {
+            info!(
+                "[MCP] {} running {} instance(s)",
+                config.id,
+                replicas.len() + 1
+            );
+        }
+        replicas
+    }
+
     /// Connect to an MCP server with retry logic.
     #[allow(clippy::too_many_lines)]
     pub async fn connect_server(
         &self,
-        config: McpServerConfig,
+        mut config: McpServerConfig,
         source: ServerSource,
     ) -> Result<Vec<String>> {
         let id = config.id.clone();
 
-        // Validate command against whitelist
-        mcp_transport::validate_command(&config.command)?;
+        // Validate command against whitelist (or the remote URL)
+        mcp_transport::validate_server_config(&config)?;
+
+        // Limits saved via the settings API override mcp.toml
+        if let Some(limits) = self.persisted_resource_limits(&id).await {
+            config.resource_limits = limits;
+        }
+        config.resource_limits.validate()?;
 
         // Check for duplicate — allow retry if server is in Error/Disconnected state
         {
@@ -600,17 +1229,35 @@ impl McpClientManager {
             }
         }
 
-        info!(
-            "Connecting to MCP server [{}]: {} {:?}",
-            id, config.command, config.args
-        );
+        let mut launch_env = crate::platform::hardware_env();
+        launch_env.extend(if config.python_requirements.is_empty() {
+            config.env.clone()
+        } else {
+            self.prepare_python_env(&config).await?
+        });
+
+        if config.transport == "stdio" {
+            info!(
+                "Connecting to MCP server [{}]: {} {:?}",
+                id, config.command, config.args
+            );
+        } else {
+            info!(
+                "Connecting to MCP server [{}] over {}: {}",
+                id,
+                config.transport,
+                config.url.as_deref().unwrap_or_default()
+            );
+        }
+
+        let sink = self.notification_sink(&config);
 
         // Retry with exponential backoff (3 attempts)
         let client = {
             let mut result: Option<McpClient> = None;
             let mut last_err = None;
             for attempt in 1..=3u32 {
-                match McpClient::connect(&config.command, &config.args, &config.env).await {
+                match McpClient::connect(&config, &launch_env, sink.clone()).await {
                     Ok(c) => {
                         result = Some(c);
                         break;
@@ -673,6 +1320,25 @@ impl McpClientManager {
             }
         };
 
+        // Cloto SDK servers must present a valid manifest; plain MCP servers
+        // only get warnings for malformed tool schemas.
+        if let Some(ref h) = handshake {
+            let errors = mcp_manifest::validate_handshake(h, &id, &tools);
+            if !errors.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "MCP server '{}' rejected: invalid manifest:\n  - {}",
+                    id,
+                    errors.join("\n  - ")
+                ));
+            }
+        } else {
+            for problem in mcp_manifest::validate_tools(&tools) {
+                warn!("[MCP] {}: {}", id, problem);
+            }
+        }
+
+        let replicas = Self::spawn_replicas(&config, &launch_env, sink.as_ref()).await;
+
         let tool_names: Vec<String> = tools.iter().map(|t| t.name.clone()).collect();
         let client_arc = Arc::new(client);
 
@@ -680,6 +1346,7 @@ impl McpClientManager {
             id: id.clone(),
             config,
             client: Some(client_arc),
+            replicas,
             tools: tools.clone(),
             handshake,
             status: ServerStatus::Connected,
@@ -752,6 +1419,7 @@ impl McpClientManager {
                 tools: h.tools.iter().map(|t| t.name.clone()).collect(),
                 is_cloto_sdk: h.handshake.is_some(),
                 source: h.source,
+                instances: h.instance_health(),
             })
             .collect();
 
@@ -767,6 +1435,7 @@ impl McpClientManager {
                     tools: Vec::new(),
                     is_cloto_sdk: false,
                     source: *source,
+                    instances: Vec::new(),
                 });
             }
         }
@@ -784,12 +1453,55 @@ impl McpClientManager {
             .collect()
     }
 
+    /// Connected servers that provide a capability, sorted by ID.
+    /// `mind.*` servers reason and servers with `store`+`recall` tools are
+    /// memory; anything else must declare the capability in its handshake.
+    pub async fn capability_providers(
+        &self,
+        capability: &cloto_shared::CapabilityType,
+    ) -> Vec<String> {
+        use cloto_shared::CapabilityType;
+
+        let name = format!("{:?}", capability);
+        let servers = self.servers.read().await;
+        let mut ids: Vec<String> = servers
+            .iter()
+            .filter(|(_, h)| h.status == ServerStatus::Connected)
+            .filter(|(id, h)| {
+                let has_tool = |tool: &str| h.tools.iter().any(|t| t.name == tool);
+                let declared = h.handshake.as_ref().is_some_and(|hs| {
+                    hs.capabilities
+                        .iter()
+                        .any(|c| c.eq_ignore_ascii_case(&name))
+                });
+                declared
+                    || match capability {
+                        CapabilityType::Reasoning => id.starts_with("mind."),
+                        CapabilityType::Memory => has_tool("store") && has_tool("recall"),
+                        CapabilityType::Tool => !h.tools.is_empty(),
+                        _ => false,
+                    }
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
     /// Check if a server with the given ID is registered.
     pub async fn has_server(&self, id: &str) -> bool {
         let servers = self.servers.read().await;
         servers.contains_key(id)
     }
 
+    /// Check if a server is registered and currently connected.
+    pub async fn is_server_connected(&self, id: &str) -> bool {
+        let servers = self.servers.read().await;
+        servers
+            .get(id)
+            .is_some_and(|h| h.status == ServerStatus::Connected)
+    }
+
     /// Check if a specific server has a tool with the given name.
     pub async fn has_server_tool(&self, server_id: &str, tool_name: &str) -> bool {
         let servers = self.servers.read().await;
@@ -798,6 +1510,20 @@ impl McpClientManager {
             .is_some_and(|h| h.tools.iter().any(|t| t.name == tool_name))
     }
 
+    /// Whether a `mind.*` server accepts image data: its `think` tool
+    /// declares `message.attachments` in the input schema.
+    pub async fn engine_supports_vision(&self, server_id: &str) -> bool {
+        let servers = self.servers.read().await;
+        servers.get(server_id).is_some_and(|h| {
+            h.tools.iter().any(|t| {
+                t.name == "think"
+                    && t.input_schema
+                        .pointer("/properties/message/properties/attachments")
+                        .is_some()
+            })
+        })
+    }
+
     // ============================================================
     // Tool Routing (used by PluginRegistry in Phase 1+)
     // ============================================================
@@ -971,7 +1697,9 @@ impl McpClientManager {
     /// Execute a tool by name, routing to the correct MCP server.
     /// Handles kernel-native tools (create_mcp_server) internally.
     /// Applies kernel-side validation (A) before forwarding to the MCP server.
+    #[tracing::instrument(name = "mcp.tool_call", skip(self, args), fields(tool = %tool_name))]
     pub async fn execute_tool(&self, tool_name: &str, args: Value) -> Result<Value> {
+        let _in_flight = self.drain.track();
         // Kernel-native tool: create_mcp_server
         if tool_name == "create_mcp_server" {
             return self.execute_create_mcp_server(args).await;
@@ -985,16 +1713,24 @@ impl McpClientManager {
                 .ok_or_else(|| anyhow::anyhow!("MCP tool '{}' not found", tool_name))?
         };
 
-        let (client, tool_validators) = {
+        let (client, tool_validators, needs_approval) = {
             let servers = self.servers.read().await;
             let handle = servers
                 .get(&server_id)
                 .ok_or_else(|| anyhow::anyhow!("MCP server '{}' not found", server_id))?;
             let client = handle
-                .client
-                .clone()
+                .route()
                 .ok_or_else(|| anyhow::anyhow!("MCP server '{}' not connected", server_id))?;
-            (client, handle.config.tool_validators.clone())
+            let needs_approval = handle
+                .config
+                .approval_required_tools
+                .iter()
+                .any(|t| t == tool_name);
+            (
+                client,
+                handle.config.tool_validators.clone(),
+                needs_approval,
+            )
         };
 
         // ──── Kernel-side Validation (A): Validate tool arguments before forwarding ────
@@ -1002,6 +1738,12 @@ impl McpClientManager {
             validate_tool_arguments(validator_name, tool_name, &args)?;
         }
 
+        // ──── Per-call approval (human-in-the-loop) ────
+        if needs_approval && !self.yolo_mode.load(Ordering::Relaxed) {
+            self.await_tool_approval(&server_id, tool_name, &args)
+                .await?;
+        }
+
         let result = client.call_tool(tool_name, args).await?;
 
         // Convert CallToolResult to a simple JSON value
@@ -1039,21 +1781,130 @@ impl McpClientManager {
         }
     }
 
+    /// Record a pending `tool_invocation_requests` row, announce it with a
+    /// `ToolApprovalRequested` event and wait for `resolve_tool_approval`.
+    async fn await_tool_approval(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        args: &Value,
+    ) -> Result<()> {
+        // The request is listed and broadcast to dashboards; keep secrets out
+        let args = match self.tool_audit {
+            Some(ref tool_audit) => tool_audit.redact(args),
+            None => args.clone(),
+        };
+        let row = crate::db::ToolInvocationRequestRow {
+            id: format!("toolcall.{}", cloto_shared::ClotoId::new()),
+            server_id: server_id.to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: args.to_string(),
+            status: "pending".to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            decided_at: None,
+            decided_by: None,
+        };
+        crate::db::create_tool_invocation_request(&self.pool, &row)
+            .await
+            .context("Failed to record tool approval request")?;
+
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut waiters) = self.tool_approvals.lock() {
+            waiters.insert(row.id.clone(), tx);
+        }
+        let _pending = PendingToolApproval {
+            id: row.id.clone(),
+            pool: self.pool.clone(),
+            waiters: self.tool_approvals.clone(),
+        };
+
+        info!(
+            request_id = %row.id,
+            server = %server_id,
+            tool = %tool_name,
+            "✋ Tool call waiting for approval"
+        );
+        if let Some(ref event_tx) = self.event_tx {
+            let event = crate::EnvelopedEvent::system(
+                cloto_shared::ClotoEventData::ToolApprovalRequested {
+                    request_id: row.id.clone(),
+                    server_id: server_id.to_string(),
+                    tool_name: tool_name.to_string(),
+                    arguments: args,
+                },
+            );
+            let _ = event_tx.send(event).await;
+        }
+
+        // The approval timeout, not the plugin event timeout of the agent
+        // run this call belongs to, bounds the wait
+        let wait_secs = self.tool_approval_timeout_secs.load(Ordering::Relaxed);
+        let decision = super::without_event_timeout(tokio::time::timeout(
+            std::time::Duration::from_secs(wait_secs),
+            rx,
+        ))
+        .await;
+        match decision {
+            Ok(Ok(true)) => Ok(()),
+            Ok(Ok(false)) => Err(anyhow::anyhow!(
+                "Tool call '{}' was denied by an administrator (request '{}')",
+                tool_name,
+                row.id
+            )),
+            _ => Err(anyhow::anyhow!(
+                "Tool call '{}' was not approved within {}s (request '{}')",
+                tool_name,
+                wait_secs,
+                row.id
+            )),
+        }
+    }
+
+    /// Approve or deny a tool call waiting in `execute_tool`. Returns `false`
+    /// if no call is waiting on `request_id` (unknown, decided or expired).
+    pub async fn resolve_tool_approval(
+        &self,
+        request_id: &str,
+        approve: bool,
+        decided_by: &str,
+    ) -> Result<bool> {
+        let waiter = self
+            .tool_approvals
+            .lock()
+            .ok()
+            .and_then(|mut waiters| waiters.remove(request_id));
+        let Some(waiter) = waiter else {
+            return Ok(false);
+        };
+        let status = if approve { "approved" } else { "denied" };
+        if !crate::db::decide_tool_invocation_request(&self.pool, request_id, status, decided_by)
+            .await?
+        {
+            return Ok(false);
+        }
+        Ok(waiter.send(approve).is_ok())
+    }
+
     /// Execute a tool on a specific server by server ID and tool name.
+    #[tracing::instrument(
+        name = "mcp.tool_call",
+        skip(self, args),
+        fields(server = %server_id, tool = %tool_name)
+    )]
     pub async fn call_server_tool(
         &self,
         server_id: &str,
         tool_name: &str,
         args: Value,
     ) -> Result<CallToolResult> {
+        let _in_flight = self.drain.track();
         let client = {
             let servers = self.servers.read().await;
             let handle = servers
                 .get(server_id)
                 .ok_or_else(|| anyhow::anyhow!("MCP server '{}' not found", server_id))?;
             handle
-                .client
-                .clone()
+                .route()
                 .ok_or_else(|| anyhow::anyhow!("MCP server '{}' not connected", server_id))?
         };
 
@@ -1133,9 +1984,16 @@ impl McpClientManager {
             args: args.clone(),
             env: HashMap::new(),
             transport: "stdio".to_string(),
+            url: None,
+            headers: HashMap::new(),
             auto_restart: true,
             required_permissions: Vec::new(),
             tool_validators: HashMap::new(),
+            python_requirements: Vec::new(),
+            instances: 1,
+            depends_on: Vec::new(),
+            approval_required_tools: Vec::new(),
+            resource_limits: ResourceLimits::default(),
         };
 
         let tool_names = self.connect_server(config, ServerSource::Dynamic).await?;
@@ -1245,9 +2103,16 @@ impl McpClientManager {
             args,
             env: HashMap::new(),
             transport: "stdio".to_string(),
+            url: None,
+            headers: HashMap::new(),
             auto_restart: true,
             required_permissions: Vec::new(),
             tool_validators: HashMap::new(),
+            python_requirements: Vec::new(),
+            instances: 1,
+            depends_on: Vec::new(),
+            approval_required_tools: Vec::new(),
+            resource_limits: ResourceLimits::default(),
         };
 
         self.connect_server(config, ServerSource::Dynamic).await
@@ -1287,8 +2152,53 @@ impl McpClientManager {
         Ok(())
     }
 
+    /// Get a server's in-memory resource limits (from config or settings).
+    pub async fn get_server_resource_limits(&self, id: &str) -> Option<ResourceLimits> {
+        let servers = self.servers.read().await;
+        servers.get(id).map(|h| h.config.resource_limits)
+    }
+
+    /// Update a server's resource limits, persist to DB, and restart.
+    pub async fn update_server_resource_limits(
+        &self,
+        id: &str,
+        limits: ResourceLimits,
+    ) -> Result<()> {
+        limits.validate()?;
+        let limits_json = serde_json::to_string(&limits)?;
+        crate::db::update_mcp_server_resource_limits(&self.pool, id, &limits_json).await?;
+
+        {
+            let mut servers = self.servers.write().await;
+            if let Some(handle) = servers.get_mut(id) {
+                handle.config.resource_limits = limits;
+            }
+        }
+
+        // Restart so the new limits apply to a fresh process
+        let _ = self.restart_server(id).await;
+        Ok(())
+    }
+
+    async fn persisted_resource_limits(&self, id: &str) -> Option<ResourceLimits> {
+        match crate::db::get_mcp_server_resource_limits(&self.pool, id).await {
+            Ok(Some(json)) => match serde_json::from_str(&json) {
+                Ok(limits) => Some(limits),
+                Err(e) => {
+                    warn!(server = %id, error = %e, "Ignoring malformed stored resource limits");
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                debug!(server = %id, error = %e, "Could not load stored resource limits");
+                None
+            }
+        }
+    }
+
     /// Get a reference to the database pool (for access control queries).
-    pub fn pool(&self) -> &SqlitePool {
+    pub fn pool(&self) -> &DbPool {
         &self.pool
     }
 
@@ -1357,6 +2267,56 @@ impl McpClientManager {
         });
     }
 
+    /// Replace dead extra instances of auto-restart servers whose primary is
+    /// still alive (a dead primary restarts the whole server instead).
+    async fn respawn_dead_replicas(&self) {
+        let targets: Vec<(McpServerConfig, Vec<usize>)> = {
+            let servers = self.servers.read().await;
+            servers
+                .values()
+                .filter(|h| {
+                    h.config.auto_restart && h.client.as_ref().is_some_and(|c| c.is_alive())
+                })
+                .filter_map(|h| {
+                    let dead: Vec<usize> = h
+                        .replicas
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, c)| !c.is_alive())
+                        .map(|(i, _)| i)
+                        .collect();
+                    (!dead.is_empty()).then(|| (h.config.clone(), dead))
+                })
+                .collect()
+        };
+
+        for (config, dead) in targets {
+            let mut env = crate::platform::hardware_env();
+            env.extend(config.env.clone());
+            if !config.python_requirements.is_empty() {
+                env.extend(mcp_venv::activation_env(&config.id));
+            }
+            let sink = self.notification_sink(&config);
+            for index in dead {
+                warn!(server_id = %config.id, instance = index + 1, "MCP instance died, respawning");
+                match McpClient::connect(&config, &env, sink.clone()).await {
+                    Ok(client) => {
+                        let mut servers = self.servers.write().await;
+                        if let Some(slot) = servers
+                            .get_mut(&config.id)
+                            .and_then(|h| h.replicas.get_mut(index))
+                        {
+                            *slot = Arc::new(client);
+                        }
+                    }
+                    Err(e) => {
+                        error!(server_id = %config.id, instance = index + 1, error = %e, "MCP instance respawn failed");
+                    }
+                }
+            }
+        }
+    }
+
     /// Scan all registered MCP servers and restart any that have died
     /// (process exited / channel closed) if their config has `auto_restart: true`.
     async fn check_and_restart_dead_servers(&self) {
@@ -1381,6 +2341,8 @@ impl McpClientManager {
                 .collect()
         };
 
+        self.respawn_dead_replicas().await;
+
         for server_id in dead_servers {
             warn!(server_id = %server_id, "MCP server died, attempting auto-restart");
             match self.restart_server(&server_id).await {
@@ -1433,6 +2395,12 @@ const SANDBOX_BLOCKED_PATTERNS: &[&str] = &[
     "doas ",
     "/bin/rm -rf",
     "/usr/bin/rm -rf",
+    // Windows hosts (the terminal server adds interpreter-specific checks)
+    "diskpart",
+    "bcdedit",
+    "vssadmin",
+    "format-volume",
+    "-encodedcommand",
 ];
 
 /// Blocked shell metacharacters for the "sandbox" validator.
@@ -1741,3 +2709,279 @@ if __name__ == "__main__":
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(id: &str, depends_on: &[&str]) -> McpServerConfig {
+        McpServerConfig {
+            id: id.to_string(),
+            command: "python".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            transport: "stdio".to_string(),
+            url: None,
+            headers: HashMap::new(),
+            auto_restart: false,
+            required_permissions: Vec::new(),
+            tool_validators: HashMap::new(),
+            python_requirements: Vec::new(),
+            instances: 1,
+            depends_on: depends_on.iter().map(ToString::to_string).collect(),
+            approval_required_tools: Vec::new(),
+            resource_limits: ResourceLimits::default(),
+        }
+    }
+
+    fn ids(waves: &[Vec<McpServerConfig>]) -> Vec<Vec<&str>> {
+        waves
+            .iter()
+            .map(|wave| wave.iter().map(|c| c.id.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_startup_waves_follow_dependencies() {
+        let waves = startup_waves(vec![
+            config("tool.c", &["tool.b"]),
+            config("tool.a", &[]),
+            config("tool.b", &["tool.a", "tool.missing"]),
+            config("tool.d", &[]),
+        ]);
+        assert_eq!(
+            ids(&waves),
+            vec![vec!["tool.a", "tool.d"], vec!["tool.b"], vec!["tool.c"]]
+        );
+    }
+
+    #[test]
+    fn test_startup_waves_start_cycles_last() {
+        let waves = startup_waves(vec![
+            config("tool.a", &["tool.b"]),
+            config("tool.b", &["tool.a"]),
+            config("tool.c", &[]),
+        ]);
+        assert_eq!(ids(&waves), vec![vec!["tool.c"], vec!["tool.a", "tool.b"]]);
+    }
+
+    async fn pending_request_id(manager: &McpClientManager) -> String {
+        for _ in 0..100 {
+            let pending = crate::db::list_pending_tool_invocation_requests(&manager.pool)
+                .await
+                .unwrap();
+            if let Some(row) = pending.into_iter().next() {
+                return row.id;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("no pending tool approval request");
+    }
+
+    #[tokio::test]
+    async fn test_tool_approval_waits_for_decision() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        crate::db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let (event_tx, mut event_rx) = mpsc::channel(8);
+        let mut manager = McpClientManager::new(pool.clone(), false);
+        manager.set_event_tx(event_tx);
+        let manager = Arc::new(manager);
+        let args = serde_json::json!({ "command": "rm -rf build" });
+
+        // Approved: the call proceeds
+        let waiting = tokio::spawn({
+            let manager = manager.clone();
+            let args = args.clone();
+            async move {
+                manager
+                    .await_tool_approval("tool.terminal", "execute_command", &args)
+                    .await
+            }
+        });
+        let id = pending_request_id(&manager).await;
+        let event = event_rx.recv().await.unwrap();
+        assert!(matches!(
+            &event.event.data,
+            cloto_shared::ClotoEventData::ToolApprovalRequested { request_id, arguments, .. }
+                if *request_id == id && *arguments == args
+        ));
+        assert!(manager
+            .resolve_tool_approval(&id, true, "admin")
+            .await
+            .unwrap());
+        waiting.await.unwrap().unwrap();
+        assert!(!manager
+            .resolve_tool_approval(&id, false, "admin")
+            .await
+            .unwrap());
+
+        // Denied: the call fails
+        let waiting = tokio::spawn({
+            let manager = manager.clone();
+            let args = args.clone();
+            async move {
+                manager
+                    .await_tool_approval("tool.terminal", "execute_command", &args)
+                    .await
+            }
+        });
+        let id = pending_request_id(&manager).await;
+        assert!(manager
+            .resolve_tool_approval(&id, false, "admin")
+            .await
+            .unwrap());
+        let err = waiting.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("denied"), "{}", err);
+
+        // No decision in time: the request expires
+        manager.set_tool_approval_timeout_secs(1);
+        let err = manager
+            .await_tool_approval("tool.terminal", "execute_command", &args)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not approved"), "{}", err);
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(crate::db::list_pending_tool_invocation_requests(&pool)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tool_approval_request_redacts_arguments() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        crate::db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let (event_tx, mut event_rx) = mpsc::channel(8);
+        let mut manager = McpClientManager::new(pool.clone(), false);
+        manager.set_event_tx(event_tx);
+        manager.set_tool_audit(Arc::new(super::super::tool_audit::ToolAudit::new(
+            pool.clone(),
+            &["password".to_string()],
+        )));
+        let manager = Arc::new(manager);
+        let args = serde_json::json!({ "user": "deploy", "password": "hunter2" });
+
+        let waiting = tokio::spawn({
+            let manager = manager.clone();
+            async move {
+                manager
+                    .await_tool_approval("tool.terminal", "login", &args)
+                    .await
+            }
+        });
+        let id = pending_request_id(&manager).await;
+        let pending = crate::db::list_pending_tool_invocation_requests(&pool)
+            .await
+            .unwrap();
+        assert!(!pending[0].arguments.contains("hunter2"));
+        let event = event_rx.recv().await.unwrap();
+        let cloto_shared::ClotoEventData::ToolApprovalRequested { arguments, .. } =
+            &event.event.data
+        else {
+            panic!("expected ToolApprovalRequested");
+        };
+        assert_eq!(arguments["user"], "deploy");
+        assert_ne!(arguments["password"], "hunter2");
+        assert!(!arguments.to_string().contains("hunter2"));
+
+        manager
+            .resolve_tool_approval(&id, true, "admin")
+            .await
+            .unwrap();
+        waiting.await.unwrap().unwrap();
+    }
+
+    /// Stand-in for an agent run: makes a call that needs approval.
+    struct ApprovingAgent(Arc<McpClientManager>);
+
+    impl cloto_shared::PluginCast for ApprovingAgent {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl cloto_shared::Plugin for ApprovingAgent {
+        fn manifest(&self) -> cloto_shared::PluginManifest {
+            cloto_shared::PluginManifest {
+                id: "agent.approving".to_string(),
+                name: "Approving".to_string(),
+                description: String::new(),
+                version: "1.0".to_string(),
+                category: cloto_shared::PluginCategory::Agent,
+                service_type: cloto_shared::ServiceType::Reasoning,
+                tags: vec![],
+                is_active: true,
+                is_configured: true,
+                required_config_keys: vec![],
+                config_schema: vec![],
+                action_icon: None,
+                action_target: None,
+                icon_data: None,
+                magic_seal: 0x5645_5253,
+                sdk_version: "1.0".to_string(),
+                required_permissions: vec![],
+                provided_capabilities: vec![],
+                provided_tools: vec![],
+                subscribes: vec![],
+            }
+        }
+
+        async fn on_event(
+            &self,
+            _event: &cloto_shared::ClotoEvent,
+        ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+            let args = serde_json::json!({ "command": "make release" });
+            self.0
+                .await_tool_approval("tool.terminal", "execute_command", &args)
+                .await?;
+            Ok(Some(cloto_shared::ClotoEventData::SystemNotification(
+                "approved".to_string(),
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_approval_outlasts_plugin_event_timeout() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        crate::db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let (approval_tx, _approval_rx) = mpsc::channel(8);
+        let mut manager = McpClientManager::new(pool, false);
+        manager.set_event_tx(approval_tx);
+        let manager = Arc::new(manager);
+
+        // PLUGIN_EVENT_TIMEOUT_SECS = 1
+        let registry = Arc::new(crate::managers::PluginRegistry::new(1, 5));
+        registry.plugins.write().await.insert(
+            "agent.approving".to_string(),
+            Arc::new(ApprovingAgent(manager.clone())) as Arc<dyn cloto_shared::Plugin>,
+        );
+        let (event_tx, mut event_rx) = mpsc::channel(8);
+        let dispatch = tokio::spawn({
+            let registry = registry.clone();
+            async move {
+                let envelope = crate::EnvelopedEvent::system(
+                    cloto_shared::ClotoEventData::SystemNotification("go".to_string()),
+                );
+                registry.dispatch_event(envelope, &event_tx).await;
+            }
+        });
+
+        let id = pending_request_id(&manager).await;
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        assert!(manager
+            .resolve_tool_approval(&id, true, "admin")
+            .await
+            .unwrap());
+        dispatch.await.unwrap();
+
+        let returned = tokio::time::timeout(std::time::Duration::from_secs(2), event_rx.recv())
+            .await
+            .expect("run finished after approval")
+            .unwrap();
+        assert!(matches!(
+            &returned.event.data,
+            cloto_shared::ClotoEventData::SystemNotification(text) if text == "approved"
+        ));
+    }
+}
@@ -0,0 +1,617 @@
+//! Remote MCP servers over HTTP.
+//!
+//! `transport = "http"` speaks Streamable HTTP: every message is POSTed to
+//! the server URL, which answers with JSON or an SSE stream, and a GET stream
+//! carries server-initiated messages once the session is initialized.
+//! `transport = "sse"` speaks the older HTTP+SSE transport: a GET stream
+//! announces a POST endpoint and then carries every server message.
+
+use super::mcp_transport::Transport;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::{StatusCode, Url};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+use tracing::{debug, error, info, warn};
+
+/// Session header assigned by Streamable HTTP servers on initialization.
+const SESSION_HEADER: &str = "mcp-session-id";
+
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// How long an `sse` server has to announce its POST endpoint.
+const ENDPOINT_TIMEOUT_SECS: u64 = 30;
+
+pub struct HttpTransport {
+    request_tx: mpsc::Sender<String>,
+    response_rx: mpsc::Receiver<String>,
+    writer_task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        self.writer_task.abort();
+    }
+}
+
+/// Aborts a background task when the task owning it ends or is aborted.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    fn sender(&self) -> mpsc::Sender<String> {
+        self.request_tx.clone()
+    }
+
+    async fn recv(&mut self) -> Option<String> {
+        self.response_rx.recv().await
+    }
+
+    /// The connection is usable until the session expires or, for `sse`,
+    /// the event stream ends.
+    fn is_alive(&mut self) -> bool {
+        !self.request_tx.is_closed()
+    }
+}
+
+impl HttpTransport {
+    /// Connect to a Streamable HTTP server. Nothing is sent until the client
+    /// initializes, so connection errors surface on the first request.
+    pub fn start_streamable(url: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        info!("Connecting to MCP server over Streamable HTTP: {}", url);
+        let client = build_client(headers)?;
+        let url = Url::parse(url).context("invalid MCP server url")?;
+
+        let (req_tx, mut req_rx) = mpsc::channel::<String>(100);
+        let (res_tx, res_rx) = mpsc::channel::<String>(100);
+        let session: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+        let closed = Arc::new(Notify::new());
+
+        let writer_task = tokio::spawn(async move {
+            let mut listener: Option<AbortOnDrop> = None;
+            loop {
+                let msg = tokio::select! {
+                    msg = req_rx.recv() => match msg {
+                        Some(msg) => msg,
+                        None => break,
+                    },
+                    () = closed.notified() => break,
+                };
+                // Server-initiated messages may follow initialization
+                if listener.is_none() && is_initialized_notification(&msg) {
+                    listener = Some(AbortOnDrop(tokio::spawn(listen_streamable(
+                        client.clone(),
+                        url.clone(),
+                        session.clone(),
+                        res_tx.clone(),
+                    ))));
+                }
+                // One task per message so a slow tool call does not hold up others
+                tokio::spawn(post_streamable(
+                    client.clone(),
+                    url.clone(),
+                    session.clone(),
+                    msg,
+                    res_tx.clone(),
+                    closed.clone(),
+                ));
+            }
+        });
+
+        Ok(Self {
+            request_tx: req_tx,
+            response_rx: res_rx,
+            writer_task,
+        })
+    }
+
+    /// Connect to an HTTP+SSE server: open its event stream and wait for
+    /// the endpoint that messages are POSTed to.
+    pub async fn start_sse(url: &str, headers: &HashMap<String, String>) -> Result<Self> {
+        info!("Connecting to MCP server over HTTP+SSE: {}", url);
+        let client = build_client(headers)?;
+        let url = Url::parse(url).context("invalid MCP server url")?;
+
+        let response = client
+            .get(url.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .context("Failed to open MCP event stream")?;
+        if !response.status().is_success() {
+            bail!("MCP event stream returned HTTP {}", response.status());
+        }
+        let mut events = Box::pin(sse_events(response));
+
+        let endpoint = tokio::time::timeout(Duration::from_secs(ENDPOINT_TIMEOUT_SECS), async {
+            while let Some(event) = events.next().await {
+                if event.event == "endpoint" {
+                    return Some(event.data);
+                }
+            }
+            None
+        })
+        .await
+        .context("MCP server did not announce its endpoint in time")?
+        .context("MCP event stream closed before announcing its endpoint")?;
+        let endpoint = url
+            .join(endpoint.trim())
+            .context("MCP server announced an invalid endpoint")?;
+        debug!("MCP HTTP+SSE endpoint: {}", endpoint);
+
+        let (req_tx, mut req_rx) = mpsc::channel::<String>(100);
+        let (res_tx, res_rx) = mpsc::channel::<String>(100);
+        let closed = Arc::new(Notify::new());
+
+        // Reader Task (event stream)
+        let reader_closed = closed.clone();
+        let reader_tx = res_tx.clone();
+        let reader = AbortOnDrop(tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                if matches!(event.event.as_str(), "" | "message")
+                    && reader_tx.send(event.data).await.is_err()
+                {
+                    return;
+                }
+            }
+            warn!("MCP event stream closed.");
+            reader_closed.notify_one();
+        }));
+
+        // Writer Task
+        let writer_task = tokio::spawn(async move {
+            let _reader = reader;
+            loop {
+                let msg = tokio::select! {
+                    msg = req_rx.recv() => match msg {
+                        Some(msg) => msg,
+                        None => break,
+                    },
+                    () = closed.notified() => break,
+                };
+                let client = client.clone();
+                let endpoint = endpoint.clone();
+                let res_tx = res_tx.clone();
+                // Replies arrive on the event stream; the POST only acknowledges
+                tokio::spawn(async move {
+                    let result = client
+                        .post(endpoint)
+                        .header(CONTENT_TYPE, "application/json")
+                        .body(msg.clone())
+                        .send()
+                        .await;
+                    match result {
+                        Ok(resp) if resp.status().is_success() => {}
+                        Ok(resp) => {
+                            fail_request(&msg, &format!("HTTP {}", resp.status()), &res_tx).await;
+                        }
+                        Err(e) => fail_request(&msg, &e.to_string(), &res_tx).await,
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            request_tx: req_tx,
+            response_rx: res_rx,
+            writer_task,
+        })
+    }
+}
+
+/// HTTP client sending `headers` (e.g. `Authorization`) with every request.
+fn build_client(headers: &HashMap<String, String>) -> Result<reqwest::Client> {
+    let mut default_headers = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("invalid header name '{}'", name))?;
+        let mut value = HeaderValue::from_str(value)
+            .with_context(|| format!("invalid value for header '{}'", name))?;
+        value.set_sensitive(true);
+        default_headers.insert(name, value);
+    }
+    Ok(reqwest::Client::builder()
+        .default_headers(default_headers)
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .build()?)
+}
+
+/// POST one message to a Streamable HTTP server and forward its reply.
+async fn post_streamable(
+    client: reqwest::Client,
+    url: Url,
+    session: Arc<Mutex<Option<String>>>,
+    msg: String,
+    res_tx: mpsc::Sender<String>,
+    closed: Arc<Notify>,
+) {
+    let session_id = session.lock().ok().and_then(|s| s.clone());
+    let mut request = client
+        .post(url)
+        .header(ACCEPT, "application/json, text/event-stream")
+        .header(CONTENT_TYPE, "application/json")
+        .body(msg.clone());
+    if let Some(id) = &session_id {
+        request = request.header(SESSION_HEADER, id);
+    }
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            fail_request(&msg, &e.to_string(), &res_tx).await;
+            return;
+        }
+    };
+
+    // Stored before the reply is forwarded, so the client's next message
+    // (notifications/initialized) already carries the session
+    if let Some(id) = response
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        if let Ok(mut session) = session.lock() {
+            *session = Some(id.to_string());
+        }
+    }
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND && session_id.is_some() {
+        error!("MCP session expired; closing connection");
+        fail_request(&msg, "MCP session expired", &res_tx).await;
+        closed.notify_one();
+        return;
+    }
+    if !status.is_success() {
+        fail_request(&msg, &format!("HTTP {}", status), &res_tx).await;
+        return;
+    }
+    // 202 Accepted: notifications and responses to server requests
+    if status == StatusCode::ACCEPTED {
+        return;
+    }
+
+    let is_stream = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if is_stream {
+        let mut events = Box::pin(sse_events(response));
+        while let Some(event) = events.next().await {
+            if matches!(event.event.as_str(), "" | "message")
+                && res_tx.send(event.data).await.is_err()
+            {
+                return;
+            }
+        }
+    } else {
+        match response.text().await {
+            Ok(body) => forward_json(&body, &res_tx).await,
+            Err(e) => fail_request(&msg, &e.to_string(), &res_tx).await,
+        }
+    }
+}
+
+/// Receive server-initiated messages on the session's GET stream. Servers
+/// that do not offer one answer 405; a stream the server ends is reopened.
+async fn listen_streamable(
+    client: reqwest::Client,
+    url: Url,
+    session: Arc<Mutex<Option<String>>>,
+    res_tx: mpsc::Sender<String>,
+) {
+    loop {
+        let mut request = client.get(url.clone()).header(ACCEPT, "text/event-stream");
+        if let Some(id) = session.lock().ok().and_then(|s| s.clone()) {
+            request = request.header(SESSION_HEADER, id);
+        }
+        let response = match request.send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!(
+                    "MCP server offers no event stream (HTTP {})",
+                    response.status()
+                );
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to open MCP event stream: {}", e);
+                return;
+            }
+        };
+        let mut events = Box::pin(sse_events(response));
+        while let Some(event) = events.next().await {
+            if matches!(event.event.as_str(), "" | "message")
+                && res_tx.send(event.data).await.is_err()
+            {
+                return;
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// Forward a JSON reply body; batches are split into single messages.
+async fn forward_json(body: &str, res_tx: &mpsc::Sender<String>) {
+    let messages = match serde_json::from_str::<Value>(body) {
+        Ok(Value::Array(batch)) => batch.iter().map(Value::to_string).collect(),
+        Ok(_) => vec![body.to_string()],
+        Err(e) => {
+            warn!("MCP server sent a non-JSON reply: {}", e);
+            return;
+        }
+    };
+    for message in messages {
+        if res_tx.send(message).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Answer a request that could not be delivered with a JSON-RPC error, so
+/// the caller fails now instead of waiting for its timeout.
+async fn fail_request(msg: &str, reason: &str, res_tx: &mpsc::Sender<String>) {
+    let Ok(value) = serde_json::from_str::<Value>(msg) else {
+        return;
+    };
+    // Notifications and replies to server requests have nobody waiting
+    let (Some(id), Some(_)) = (value.get("id"), value.get("method")) else {
+        warn!("Failed to deliver MCP message: {}", reason);
+        return;
+    };
+    let error = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32000, "message": format!("MCP HTTP transport: {}", reason) },
+    });
+    if res_tx.send(error.to_string()).await.is_err() {
+        debug!("MCP response channel closed");
+    }
+}
+
+fn is_initialized_notification(msg: &str) -> bool {
+    serde_json::from_str::<Value>(msg)
+        .is_ok_and(|v| v.get("method").and_then(Value::as_str) == Some("notifications/initialized"))
+}
+
+/// One Server-Sent Event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SseEvent {
+    /// Event type; empty when the server did not name one.
+    event: String,
+    data: String,
+}
+
+/// Incremental `text/event-stream` parser fed with raw body chunks.
+#[derive(Default)]
+struct SseParser {
+    buf: Vec<u8>,
+    event: String,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buf.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buf.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            let line = String::from_utf8_lossy(&line);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: std::mem::take(&mut self.event),
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                self.event.clear();
+                continue;
+            }
+            if line.starts_with(':') {
+                continue; // comment / keep-alive
+            }
+            let (field, value) = line.split_once(':').unwrap_or((&line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = value.to_string(),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+fn sse_events(response: reqwest::Response) -> impl Stream<Item = SseEvent> {
+    async_stream::stream! {
+        let mut parser = SseParser::default();
+        let mut body = response.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            let Ok(chunk) = chunk else {
+                break;
+            };
+            for event in parser.push(&chunk) {
+                yield event;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::mcp::McpClient;
+    use crate::managers::mcp_protocol::McpServerConfig;
+    use axum::extract::State;
+    use axum::http::HeaderMap as AxumHeaderMap;
+    use axum::response::sse::{Event, Sse};
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::{get, post};
+    use axum::Router;
+    use serde_json::json;
+
+    fn remote_config(transport: &str, url: String) -> McpServerConfig {
+        serde_json::from_value(json!({
+            "id": "remote.test",
+            "transport": transport,
+            "url": url,
+            "headers": { "Authorization": "Bearer test-token" },
+        }))
+        .unwrap()
+    }
+
+    async fn serve(app: Router) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    /// Reply to the requests `McpClient::connect` and `list_tools` make.
+    fn reply(request: &Value) -> Option<Value> {
+        let result = match request["method"].as_str()? {
+            "initialize" => json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "serverInfo": { "name": "test", "version": "1" },
+            }),
+            "tools/list" => {
+                json!({ "tools": [{ "name": "echo", "inputSchema": { "type": "object" } }] })
+            }
+            _ => return None,
+        };
+        Some(json!({ "jsonrpc": "2.0", "id": request.get("id")?, "result": result }))
+    }
+
+    #[test]
+    fn test_sse_parser_split_chunks_and_multiline_data() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b": keep-alive\n\nevent: endp").is_empty());
+        let events = parser.push(b"oint\r\ndata: /messages\r\n\r\ndata: a\ndata: b\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".into(),
+                    data: "/messages".into()
+                },
+                SseEvent {
+                    event: String::new(),
+                    data: "a\nb".into()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_session_and_sse_reply() {
+        async fn handle(headers: AxumHeaderMap, body: String) -> Response {
+            assert_eq!(headers["authorization"], "Bearer test-token");
+            let request: Value = serde_json::from_str(&body).unwrap();
+            let Some(response) = reply(&request) else {
+                return axum::http::StatusCode::ACCEPTED.into_response();
+            };
+            if request["method"] == "initialize" {
+                return ([(SESSION_HEADER, "session-1")], axum::Json(response)).into_response();
+            }
+            // Later requests must carry the session and get an SSE reply
+            assert_eq!(headers[SESSION_HEADER], "session-1");
+            let event = Event::default().event("message").data(response.to_string());
+            Sse::new(futures::stream::iter([Ok::<_, std::convert::Infallible>(
+                event,
+            )]))
+            .into_response()
+        }
+        let app = Router::new().route(
+            "/mcp",
+            post(handle).get(|| async { axum::http::StatusCode::METHOD_NOT_ALLOWED }),
+        );
+        let addr = serve(app).await;
+
+        let config = remote_config("http", format!("http://{}/mcp", addr));
+        let client = McpClient::connect(&config, &HashMap::new(), None)
+            .await
+            .unwrap();
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(tools.tools[0].name, "echo");
+        assert!(client.is_alive());
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_unreachable_fails_fast() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let config = remote_config("http", format!("http://{}/mcp", addr));
+        let err = tokio::time::timeout(
+            Duration::from_secs(10),
+            McpClient::connect(&config, &HashMap::new(), None),
+        )
+        .await
+        .expect("connection error instead of request timeout")
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("MCP HTTP transport"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_sse_transport() {
+        type Outbox = Arc<tokio::sync::Mutex<Option<mpsc::Receiver<String>>>>;
+        let (out_tx, out_rx) = mpsc::channel::<String>(16);
+        let outbox: Outbox = Arc::new(tokio::sync::Mutex::new(Some(out_rx)));
+
+        let app = Router::new()
+            .route(
+                "/sse",
+                get(|State((_, outbox)): State<(mpsc::Sender<String>, Outbox)>| async move {
+                    let mut rx = outbox.lock().await.take().unwrap();
+                    let stream = async_stream::stream! {
+                        yield Ok::<_, std::convert::Infallible>(
+                            Event::default().event("endpoint").data("/messages?session=1"),
+                        );
+                        while let Some(msg) = rx.recv().await {
+                            yield Ok(Event::default().event("message").data(msg));
+                        }
+                    };
+                    Sse::new(stream)
+                }),
+            )
+            .route(
+                "/messages",
+                post(
+                    |State((tx, _)): State<(mpsc::Sender<String>, Outbox)>, body: String| async move {
+                        let request: Value = serde_json::from_str(&body).unwrap();
+                        if let Some(response) = reply(&request) {
+                            tx.send(response.to_string()).await.unwrap();
+                        }
+                        axum::http::StatusCode::ACCEPTED
+                    },
+                ),
+            )
+            .with_state((out_tx, outbox));
+        let addr = serve(app).await;
+
+        let config = remote_config("sse", format!("http://{}/sse", addr));
+        let client = McpClient::connect(&config, &HashMap::new(), None)
+            .await
+            .unwrap();
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(tools.tools.len(), 1);
+    }
+}
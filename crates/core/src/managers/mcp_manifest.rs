@@ -0,0 +1,217 @@
+//! Validation of `cloto/handshake` manifests and tool schemas.
+//!
+//! Servers that answer the handshake are Cloto SDK servers and are held to
+//! the manifest contract: a compatible protocol version, a server ID matching
+//! the configuration, known permission names and well-formed tool schemas.
+//! All problems are collected so a server author can fix them in one pass.
+
+use super::mcp_protocol::{ClotoHandshakeResult, McpTool};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Handshake protocol version spoken by this kernel.
+pub const CLOTO_PROTOCOL_VERSION: u32 = 1;
+/// Oldest server protocol version the kernel still accepts.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+fn is_valid_tool_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_TOOL_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Check tool definitions from `tools/list` against the MCP tool schema shape.
+#[must_use]
+pub fn validate_tools(tools: &[McpTool]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for tool in tools {
+        let name = &tool.name;
+        if !is_valid_tool_name(name) {
+            errors.push(format!(
+                "tool '{}': name must be 1-{} characters of [A-Za-z0-9_.-]",
+                name, MAX_TOOL_NAME_LEN
+            ));
+        }
+        if !seen.insert(name.as_str()) {
+            errors.push(format!("tool '{}': declared more than once", name));
+        }
+
+        let Some(schema) = tool.input_schema.as_object() else {
+            errors.push(format!(
+                "tool '{}': inputSchema must be a JSON object",
+                name
+            ));
+            continue;
+        };
+        if schema.get("type").and_then(Value::as_str) != Some("object") {
+            errors.push(format!(
+                "tool '{}': inputSchema.type must be \"object\"",
+                name
+            ));
+        }
+        let properties = match schema.get("properties") {
+            None => None,
+            Some(Value::Object(props)) => Some(props),
+            Some(_) => {
+                errors.push(format!(
+                    "tool '{}': inputSchema.properties must be an object",
+                    name
+                ));
+                None
+            }
+        };
+        match schema.get("required") {
+            None => {}
+            Some(Value::Array(required)) => {
+                for field in required {
+                    match field.as_str() {
+                        Some(field) if properties.is_some_and(|p| p.contains_key(field)) => {}
+                        Some(field) => errors.push(format!(
+                            "tool '{}': required field '{}' is not defined in properties",
+                            name, field
+                        )),
+                        None => errors.push(format!(
+                            "tool '{}': inputSchema.required must contain only strings",
+                            name
+                        )),
+                    }
+                }
+            }
+            Some(_) => errors.push(format!(
+                "tool '{}': inputSchema.required must be an array",
+                name
+            )),
+        }
+    }
+
+    errors
+}
+
+/// Check a handshake manifest against the server config and its tool list.
+#[must_use]
+pub fn validate_handshake(
+    handshake: &ClotoHandshakeResult,
+    config_id: &str,
+    tools: &[McpTool],
+) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    match handshake.protocol_version {
+        None => errors.push(format!(
+            "manifest is missing protocolVersion (kernel speaks {}); upgrade the Cloto MCP SDK",
+            CLOTO_PROTOCOL_VERSION
+        )),
+        Some(v) if v > CLOTO_PROTOCOL_VERSION => errors.push(format!(
+            "server protocol version {} is newer than this kernel supports ({}); upgrade ClotoCore",
+            v, CLOTO_PROTOCOL_VERSION
+        )),
+        Some(v) if v < MIN_PROTOCOL_VERSION => errors.push(format!(
+            "server protocol version {} is no longer supported (minimum {}); upgrade the Cloto MCP SDK",
+            v, MIN_PROTOCOL_VERSION
+        )),
+        Some(_) => {}
+    }
+
+    if handshake.server_id != config_id {
+        errors.push(format!(
+            "manifest serverId '{}' does not match configured id '{}'",
+            handshake.server_id, config_id
+        ));
+    }
+
+    for perm in &handshake.required_permissions {
+        if serde_json::from_value::<cloto_shared::Permission>(Value::String(perm.clone())).is_err()
+        {
+            errors.push(format!(
+                "unknown permission '{}' in requiredPermissions",
+                perm
+            ));
+        }
+    }
+
+    let listed: HashSet<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+    for tool in &handshake.tools {
+        if !listed.contains(tool.as_str()) {
+            errors.push(format!(
+                "manifest declares tool '{}' but tools/list does not provide it",
+                tool
+            ));
+        }
+    }
+
+    errors.extend(validate_tools(tools));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool(name: &str, schema: Value) -> McpTool {
+        McpTool {
+            name: name.to_string(),
+            description: None,
+            input_schema: schema,
+        }
+    }
+
+    fn handshake(version: Option<u32>) -> ClotoHandshakeResult {
+        ClotoHandshakeResult {
+            server_id: "tool.demo".to_string(),
+            version: Some("0.1.0".to_string()),
+            protocol_version: version,
+            capabilities: vec![],
+            tools: vec!["run".to_string()],
+            required_permissions: vec!["NetworkAccess".to_string()],
+            seal: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_manifest_passes() {
+        let tools = vec![tool(
+            "run",
+            json!({"type": "object", "properties": {"cmd": {"type": "string"}}, "required": ["cmd"]}),
+        )];
+        assert!(validate_handshake(&handshake(Some(1)), "tool.demo", &tools).is_empty());
+    }
+
+    #[test]
+    fn test_incompatible_protocol_version_rejected() {
+        let tools = vec![tool("run", json!({"type": "object"}))];
+        let errors = validate_handshake(&handshake(Some(99)), "tool.demo", &tools);
+        assert!(errors.iter().any(|e| e.contains("upgrade ClotoCore")));
+        let errors = validate_handshake(&handshake(None), "tool.demo", &tools);
+        assert!(errors.iter().any(|e| e.contains("protocolVersion")));
+    }
+
+    #[test]
+    fn test_manifest_mismatches_reported() {
+        let mut h = handshake(Some(1));
+        h.required_permissions.push("RootAccess".to_string());
+        h.tools.push("missing".to_string());
+        let errors =
+            validate_handshake(&h, "tool.other", &[tool("run", json!({"type": "object"}))]);
+        assert_eq!(errors.len(), 3, "{errors:?}");
+    }
+
+    #[test]
+    fn test_tool_schema_shape() {
+        let errors = validate_tools(&[
+            tool("bad name", json!({"type": "object"})),
+            tool("no_type", json!({"properties": {}})),
+            tool("bad_required", json!({"type": "object", "required": ["x"]})),
+            tool("not_object", json!("string")),
+            tool("dup", json!({"type": "object"})),
+            tool("dup", json!({"type": "object"})),
+        ]);
+        assert_eq!(errors.len(), 5, "{errors:?}");
+    }
+}
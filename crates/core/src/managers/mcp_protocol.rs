@@ -68,7 +68,12 @@ pub struct InitializeParams {
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ClientCapabilities {}
+pub struct ClientCapabilities {
+    /// Servers with `ChannelRelay` may send `sampling/createMessage`
+    /// to relay chat messages to agents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<Value>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -106,6 +111,38 @@ pub struct CallToolResult {
     pub is_error: Option<bool>,
 }
 
+impl CallToolResult {
+    /// Extract the reply text from an engine's `think()` response.
+    pub fn think_content(&self) -> anyhow::Result<String> {
+        for content in &self.content {
+            if let ToolContent::Text { text } = content {
+                // Try to parse as JSON (may contain {"type":"final","content":"..."})
+                if let Ok(json) = serde_json::from_str::<Value>(text) {
+                    if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
+                        return Err(anyhow::anyhow!("MCP engine error: {}", error));
+                    }
+                    if let Some(content) = json.get("content").and_then(|c| c.as_str()) {
+                        return Ok(content.to_string());
+                    }
+                }
+                // Fall back to raw text
+                return Ok(text.clone());
+            }
+        }
+        Err(anyhow::anyhow!("MCP engine returned no text content"))
+    }
+
+    /// The first text content parsed as JSON, for tools that answer with a
+    /// JSON document (e.g. `list_models`).
+    #[must_use]
+    pub fn json_content(&self) -> Option<Value> {
+        self.content.iter().find_map(|c| match c {
+            ToolContent::Text { text } => serde_json::from_str(text).ok(),
+            _ => None,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ToolContent {
@@ -126,6 +163,7 @@ pub enum ToolContent {
 #[serde(rename_all = "camelCase")]
 pub struct ClotoHandshakeParams {
     pub kernel_version: String,
+    pub protocol_version: u32,
 }
 
 /// Response from cloto/handshake
@@ -134,8 +172,13 @@ pub struct ClotoHandshakeParams {
 pub struct ClotoHandshakeResult {
     pub server_id: String,
     pub version: Option<String>,
+    /// Handshake protocol version the server was built for
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
     pub capabilities: Vec<String>,
     pub tools: Vec<String>,
+    #[serde(default)]
+    pub required_permissions: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seal: Option<String>,
 }
@@ -144,13 +187,23 @@ pub struct ClotoHandshakeResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerConfig {
     pub id: String,
+    /// Server executable for the `stdio` transport (unused for remote servers).
+    #[serde(default)]
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
     #[serde(default)]
     pub env: std::collections::HashMap<String, String>,
+    /// `stdio` (local process), `http` (Streamable HTTP) or `sse` (HTTP+SSE).
     #[serde(default = "default_transport")]
     pub transport: String,
+    /// Endpoint of a remote server for the `http` and `sse` transports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Headers sent with every request to a remote server (e.g.
+    /// `Authorization`); values accept `${VAR}` and secret references.
+    #[serde(default)]
+    pub headers: std::collections::HashMap<String, String>,
     #[serde(default)]
     pub auto_restart: bool,
     /// Required permissions for this MCP server (Permission gate: D).
@@ -161,12 +214,36 @@ pub struct McpServerConfig {
     /// Maps tool name → validator name (e.g., "execute_command" → "sandbox").
     #[serde(default)]
     pub tool_validators: std::collections::HashMap<String, String>,
+    /// Python packages installed into a managed per-server venv before launch
+    /// (PEP 508 requirements; installation requires `PackageInstall` approval).
+    #[serde(default)]
+    pub python_requirements: Vec<String>,
+    /// Number of server processes to run; tool calls are routed to the least
+    /// busy live instance so one long call does not serialize the others.
+    #[serde(default = "default_instances")]
+    pub instances: usize,
+    /// IDs of servers in the same config file that must finish starting
+    /// (successfully or not) before this one is started.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Tools whose every call waits for an administrator's approval
+    /// (`POST /api/tool-calls/:id/approve`) unless YOLO mode is on.
+    #[serde(default)]
+    pub approval_required_tools: Vec<String>,
+    /// CPU/memory limits for each server process. Limits saved via the
+    /// settings API (`mcp_servers.resource_limits`) take precedence.
+    #[serde(default)]
+    pub resource_limits: super::mcp_transport::ResourceLimits,
 }
 
 fn default_transport() -> String {
     "stdio".to_string()
 }
 
+fn default_instances() -> usize {
+    1
+}
+
 /// Top-level config structure for mcp.toml
 #[derive(Debug, Deserialize)]
 pub struct McpConfigFile {
@@ -1,4 +1,7 @@
+use super::mcp_protocol::McpServerConfig;
 use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -29,24 +32,248 @@ pub fn validate_command(command: &str) -> Result<String> {
     Ok(command.to_string())
 }
 
+/// Check that `config` can be connected: a whitelisted command for `stdio`,
+/// an http(s) URL for the `http` (Streamable HTTP) and `sse` (HTTP+SSE)
+/// transports.
+pub fn validate_server_config(config: &McpServerConfig) -> Result<()> {
+    match config.transport.as_str() {
+        "stdio" => validate_command(&config.command).map(|_| ()),
+        "http" | "sse" => {
+            let Some(url) = config.url.as_deref() else {
+                bail!("transport '{}' requires a 'url'", config.transport);
+            };
+            let parsed = reqwest::Url::parse(url).context("invalid 'url'")?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                bail!("'url' must be http or https, got '{}'", parsed.scheme());
+            }
+            Ok(())
+        }
+        other => bail!(
+            "unsupported transport '{}' (expected 'stdio', 'http' or 'sse')",
+            other
+        ),
+    }
+}
+
+/// A connection to an MCP server exchanging JSON-RPC messages as strings.
+///
+/// Outgoing messages go through the channel returned by `sender`, so a
+/// client can send while its response loop is blocked in `recv`.
+#[async_trait]
+pub trait Transport: Send {
+    /// Get a clone of the request sender for lock-free sending.
+    fn sender(&self) -> mpsc::Sender<String>;
+
+    /// Next message from the server; `None` once the connection is closed.
+    async fn recv(&mut self) -> Option<String>;
+
+    /// Whether the server (process or connection) is still usable.
+    fn is_alive(&mut self) -> bool;
+}
+
+/// Open the transport named by `config.transport`. `env` is the launch
+/// environment of stdio servers; remote servers ignore it.
+pub(crate) async fn connect(
+    config: &McpServerConfig,
+    env: &HashMap<String, String>,
+) -> Result<Box<dyn Transport>> {
+    validate_server_config(config)?;
+    match config.transport.as_str() {
+        "http" | "sse" => {
+            let headers = config
+                .headers
+                .iter()
+                .map(|(k, v)| (k.clone(), resolve_env_value(v)))
+                .collect();
+            let url = config.url.as_deref().unwrap_or_default();
+            let transport = if config.transport == "sse" {
+                super::mcp_http::HttpTransport::start_sse(url, &headers).await?
+            } else {
+                super::mcp_http::HttpTransport::start_streamable(url, &headers)?
+            };
+            Ok(Box::new(transport))
+        }
+        _ => Ok(Box::new(
+            StdioTransport::start(&config.command, &config.args, env, config.resource_limits)
+                .await?,
+        )),
+    }
+}
+
+/// OS resource limits applied to an MCP server process when it is spawned,
+/// so a runaway server cannot exhaust the host. Unset fields are unlimited.
+///
+/// Unix uses `setrlimit` (`RLIMIT_AS`, `RLIMIT_CPU`) and Windows a Job
+/// Object, which also kills the process tree when the server is dropped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Memory cap in MiB (address space on Unix, committed memory on
+    /// Windows). Node.js reserves a large address space, so allow several
+    /// GiB for `npx`/`node` servers on Unix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// Total CPU time in seconds. A server exceeding it is killed, and
+    /// restarted if `auto_restart` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_secs: Option<u64>,
+}
+
+impl ResourceLimits {
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        self.memory_mb.is_none() && self.cpu_secs.is_none()
+    }
+
+    /// Reject zero limits, which would stop the server from starting at all.
+    pub fn validate(&self) -> Result<()> {
+        if self.memory_mb == Some(0) || self.cpu_secs == Some(0) {
+            bail!("resource limits must be positive (omit a limit to leave it unset)");
+        }
+        Ok(())
+    }
+}
+
+/// Install a `pre_exec` hook that applies `limits` with `setrlimit` in the child.
+#[cfg(unix)]
+fn apply_rlimits(limits: ResourceLimits, cmd: &mut Command) {
+    let memory = limits.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+    let cpu = limits.cpu_secs;
+    if memory.is_none() && cpu.is_none() {
+        return;
+    }
+    // SAFETY: the closure runs in the forked child before exec and only
+    // calls setrlimit, which is async-signal-safe.
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(bytes) = memory {
+                set_rlimit(libc::RLIMIT_AS, bytes, bytes)?;
+            }
+            if let Some(secs) = cpu {
+                // SIGXCPU at the soft limit, SIGKILL a second later
+                set_rlimit(libc::RLIMIT_CPU, secs, secs.saturating_add(1))?;
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type RlimitResource = libc::c_int;
+
+#[cfg(unix)]
+#[allow(clippy::useless_conversion)] // rlim_t is not u64 on every platform
+fn set_rlimit(resource: RlimitResource, soft: u64, hard: u64) -> std::io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: soft.try_into().unwrap_or(libc::RLIM_INFINITY),
+        rlim_max: hard.try_into().unwrap_or(libc::RLIM_INFINITY),
+    };
+    // SAFETY: `limit` is a valid rlimit for the duration of the call.
+    if unsafe { libc::setrlimit(resource, std::ptr::from_ref(&limit)) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Job Object holding a server process; closing it kills the process tree.
+#[cfg(windows)]
+struct JobObject(windows_sys::Win32::Foundation::HANDLE);
+
+#[cfg(windows)]
+// SAFETY: the handle is owned by this value and only closed on drop.
+unsafe impl Send for JobObject {}
+#[cfg(windows)]
+// SAFETY: the handle is not used after creation except by Drop.
+unsafe impl Sync for JobObject {}
+
+#[cfg(windows)]
+impl JobObject {
+    fn assign(child: &Child, limits: ResourceLimits) -> Result<Self> {
+        use windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+            JOB_OBJECT_LIMIT_PROCESS_TIME,
+        };
+
+        let process = child
+            .raw_handle()
+            .context("MCP server process has already exited")?;
+        // SAFETY: plain Win32 calls; every pointer passed is valid for the
+        // duration of its call and the job handle is owned by the result.
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                bail!(
+                    "CreateJobObjectW failed: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            let job = Self(handle);
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            if let Some(mb) = limits.memory_mb {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+                info.ProcessMemoryLimit =
+                    usize::try_from(mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX);
+            }
+            if let Some(secs) = limits.cpu_secs {
+                info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_PROCESS_TIME;
+                // 100-nanosecond units
+                info.BasicLimitInformation.PerProcessUserTimeLimit =
+                    i64::try_from(secs.saturating_mul(10_000_000)).unwrap_or(i64::MAX);
+            }
+            if SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                std::ptr::from_ref(&info).cast(),
+                u32::try_from(std::mem::size_of_val(&info)).unwrap_or(u32::MAX),
+            ) == 0
+            {
+                bail!(
+                    "SetInformationJobObject failed: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            if AssignProcessToJobObject(job.0, process as _) == 0 {
+                bail!(
+                    "AssignProcessToJobObject failed: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            Ok(job)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for JobObject {
+    fn drop(&mut self) {
+        // SAFETY: the handle was returned by CreateJobObjectW and is closed once.
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
 pub struct StdioTransport {
     child: Child,
     request_tx: mpsc::Sender<String>,
     response_rx: mpsc::Receiver<String>,
+    #[cfg(windows)]
+    _job: Option<JobObject>,
 }
 
 impl StdioTransport {
-    /// Get a clone of the request sender for lock-free sending.
-    #[must_use]
-    pub fn sender(&self) -> mpsc::Sender<String> {
-        self.request_tx.clone()
-    }
-
-    /// Start a new MCP server process with environment variable injection.
+    /// Start a new MCP server process with environment variable injection,
+    /// under `limits`.
     pub async fn start(
         command: &str,
         args: &[String],
         env: &HashMap<String, String>,
+        limits: ResourceLimits,
     ) -> Result<Self> {
         info!("Starting MCP Server: {} {:?}", command, args);
 
@@ -59,16 +286,38 @@ impl StdioTransport {
             .stderr(Stdio::piped())
             .kill_on_drop(true);
 
+        // Inherited variables holding secret references (e.g. provider keys
+        // in .env) are passed resolved
+        for (key, value) in std::env::vars() {
+            if crate::secrets::is_reference(&value) && !env.contains_key(&key) {
+                cmd.env(key, resolve_env_value(&value));
+            }
+        }
+
         // Inject environment variables (with shell variable expansion)
         for (key, value) in env {
             let resolved = resolve_env_value(value);
             cmd.env(key, resolved);
         }
 
+        #[cfg(unix)]
+        apply_rlimits(limits, &mut cmd);
+
         let mut child = cmd
             .spawn()
             .context(format!("Failed to spawn MCP server: {}", command))?;
 
+        #[cfg(windows)]
+        let job = if limits.is_unlimited() {
+            None
+        } else {
+            Some(JobObject::assign(&child, limits)?)
+        };
+        #[cfg(not(any(unix, windows)))]
+        if !limits.is_unlimited() {
+            warn!("Resource limits are not supported on this platform; ignoring them");
+        }
+
         let stdin = child.stdin.take().context("Failed to open stdin")?;
         let stdout = child.stdout.take().context("Failed to open stdout")?;
         let stderr = child.stderr.take().context("Failed to open stderr")?;
@@ -116,6 +365,8 @@ impl StdioTransport {
             child,
             request_tx: req_tx,
             response_rx: res_rx,
+            #[cfg(windows)]
+            _job: job,
         })
     }
 
@@ -125,24 +376,40 @@ impl StdioTransport {
             .await
             .context("Failed to send message to transport task")
     }
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    fn sender(&self) -> mpsc::Sender<String> {
+        self.request_tx.clone()
+    }
 
-    pub async fn recv(&mut self) -> Option<String> {
+    async fn recv(&mut self) -> Option<String> {
         self.response_rx.recv().await
     }
 
     /// Check if the child process is still running.
-    pub fn is_alive(&mut self) -> bool {
+    fn is_alive(&mut self) -> bool {
         matches!(self.child.try_wait(), Ok(None))
     }
 }
 
 /// Resolve `${ENV_VAR}` references in a value string to actual environment variables.
-fn resolve_env_value(value: &str) -> String {
-    if let Some(var_name) = value.strip_prefix("${").and_then(|s| s.strip_suffix('}')) {
+/// Secret references (`keychain:` / `secret:`), literal or in the variable,
+/// are resolved too; unresolvable ones become empty like missing variables.
+pub(crate) fn resolve_env_value(value: &str) -> String {
+    let value = if let Some(var_name) = value.strip_prefix("${").and_then(|s| s.strip_suffix('}')) {
         std::env::var(var_name).unwrap_or_default()
     } else {
         value.to_string()
+    };
+    if !crate::secrets::is_reference(&value) {
+        return value;
     }
+    crate::secrets::resolve(&value).unwrap_or_else(|e| {
+        warn!("Failed to resolve secret for MCP server env: {:#}", e);
+        String::new()
+    })
 }
 
 #[cfg(test)]
@@ -175,6 +442,34 @@ mod tests {
         assert!(validate_command("C:\\Windows\\node").is_err());
     }
 
+    #[test]
+    fn test_validate_server_config_remote() {
+        let config = |value: serde_json::Value| -> McpServerConfig {
+            serde_json::from_value(value).unwrap()
+        };
+        assert!(validate_server_config(&config(serde_json::json!({
+            "id": "a", "transport": "http", "url": "https://mcp.example.com/mcp"
+        })))
+        .is_ok());
+        // Remote servers need an http(s) url and no command
+        assert!(validate_server_config(&config(serde_json::json!({
+            "id": "a", "transport": "sse"
+        })))
+        .is_err());
+        assert!(validate_server_config(&config(serde_json::json!({
+            "id": "a", "transport": "http", "url": "file:///etc/passwd"
+        })))
+        .is_err());
+        assert!(validate_server_config(&config(serde_json::json!({
+            "id": "a", "transport": "websocket", "url": "ws://localhost"
+        })))
+        .is_err());
+        assert!(validate_server_config(&config(serde_json::json!({
+            "id": "a", "command": "bash"
+        })))
+        .is_err());
+    }
+
     #[test]
     fn test_resolve_env_value_passthrough() {
         assert_eq!(resolve_env_value("hello"), "hello");
@@ -192,4 +487,53 @@ mod tests {
     fn test_resolve_env_value_missing() {
         assert_eq!(resolve_env_value("${NONEXISTENT_CLOTO_VAR_12345}"), "");
     }
+
+    #[test]
+    fn test_resource_limits_serde_and_validate() {
+        let limits: ResourceLimits = serde_json::from_str(r#"{"memory_mb":512}"#).unwrap();
+        assert_eq!(limits.memory_mb, Some(512));
+        assert_eq!(limits.cpu_secs, None);
+        assert!(!limits.is_unlimited());
+        assert!(limits.validate().is_ok());
+        assert_eq!(
+            serde_json::to_string(&limits).unwrap(),
+            r#"{"memory_mb":512}"#
+        );
+
+        let empty: ResourceLimits = serde_json::from_str("{}").unwrap();
+        assert!(empty.is_unlimited());
+        assert!(empty.validate().is_ok());
+
+        let zero = ResourceLimits {
+            memory_mb: None,
+            cpu_secs: Some(0),
+        };
+        assert!(zero.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_resource_limits_applied_to_child() {
+        let limits = ResourceLimits {
+            memory_mb: Some(1024),
+            cpu_secs: Some(30),
+        };
+        let args = vec![
+            "-c".to_string(),
+            "import resource; print(resource.getrlimit(resource.RLIMIT_AS)[0], \
+             resource.getrlimit(resource.RLIMIT_CPU)[0])"
+                .to_string(),
+        ];
+        let Ok(mut transport) =
+            StdioTransport::start("python3", &args, &HashMap::new(), limits).await
+        else {
+            eprintln!("python3 not available; skipping");
+            return;
+        };
+        let line = tokio::time::timeout(std::time::Duration::from_secs(10), transport.recv())
+            .await
+            .expect("child output")
+            .expect("child line");
+        assert_eq!(line.trim(), format!("{} 30", 1024u64 * 1024 * 1024));
+    }
 }
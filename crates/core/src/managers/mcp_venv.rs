@@ -0,0 +1,240 @@
+//! Managed Python virtual environments for MCP servers.
+//!
+//! Servers that declare `python_requirements` in mcp.toml get their own venv
+//! under `CLOTO_MCP_VENV_DIR` (default `data/mcp-venvs/<server_id>`). Packages
+//! are installed only after an administrator approved the exact requirement
+//! set (`PackageInstall` permission request). The installed set is recorded
+//! in the venv, so restarts reuse it without re-approval or reinstalling.
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::info;
+
+/// Permission request type gating package installation.
+pub const PACKAGE_INSTALL_PERMISSION: &str = "PackageInstall";
+
+/// File inside the venv recording the installed requirement set.
+const MARKER_FILE: &str = "cloto-requirements.txt";
+
+const DEFAULT_INSTALL_TIMEOUT_SECS: u64 = 600;
+
+fn venv_root() -> PathBuf {
+    std::env::var("CLOTO_MCP_VENV_DIR")
+        .map_or_else(|_| PathBuf::from("data/mcp-venvs"), PathBuf::from)
+}
+
+pub(crate) fn install_timeout() -> Duration {
+    Duration::from_secs(
+        std::env::var("CLOTO_MCP_VENV_INSTALL_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_INSTALL_TIMEOUT_SECS),
+    )
+}
+
+#[must_use]
+pub fn venv_dir(server_id: &str) -> PathBuf {
+    venv_root().join(server_id)
+}
+
+fn bin_dir(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts")
+    } else {
+        venv.join("bin")
+    }
+}
+
+/// Validate a single PEP 508 requirement.
+///
+/// Only index packages are allowed: pip options (`-r`, `--index-url`), direct
+/// URLs (`name @ https://...`) and local paths are rejected so an approved
+/// requirement list cannot pull code from an arbitrary location.
+pub fn validate_requirement(req: &str) -> Result<()> {
+    let req = req.trim();
+    if req.is_empty() {
+        bail!("Empty requirement");
+    }
+    if req.starts_with('-') {
+        bail!("pip options are not allowed in requirements: '{}'", req);
+    }
+    if req.contains('@') || req.contains('/') || req.contains('\\') || req.contains("://") {
+        bail!(
+            "Only index packages are allowed (no URLs or paths): '{}'",
+            req
+        );
+    }
+    if !req
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+    {
+        bail!("Requirement must start with a package name: '{}'", req);
+    }
+    let allowed = |c: char| c.is_ascii_alphanumeric() || " ._-[],<>=!~;'\"*".contains(c);
+    if let Some(bad) = req.chars().find(|c| !allowed(*c)) {
+        bail!("Invalid character '{}' in requirement '{}'", bad, req);
+    }
+    Ok(())
+}
+
+/// Canonical form of a requirement set: trimmed, deduplicated, sorted.
+#[must_use]
+pub fn normalize_requirements(reqs: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = reqs.iter().map(|r| r.trim().to_string()).collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Short fingerprint of a requirement set, used in permission request IDs so
+/// that approval is bound to the exact list.
+#[must_use]
+pub fn requirements_fingerprint(reqs: &[String]) -> String {
+    let digest = Sha256::digest(normalize_requirements(reqs).join("\n").as_bytes());
+    format!("{:x}", digest)[..12].to_string()
+}
+
+/// Whether the venv already has exactly this requirement set installed.
+pub async fn is_installed(server_id: &str, reqs: &[String]) -> bool {
+    let marker = venv_dir(server_id).join(MARKER_FILE);
+    match tokio::fs::read_to_string(&marker).await {
+        Ok(content) => content == normalize_requirements(reqs).join("\n"),
+        Err(_) => false,
+    }
+}
+
+async fn run(cmd: &mut Command, what: &str) -> Result<()> {
+    let output = tokio::time::timeout(install_timeout(), cmd.kill_on_drop(true).output())
+        .await
+        .map_err(|_| anyhow::anyhow!("{} timed out", what))?
+        .with_context(|| format!("Failed to run {}", what))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: String = stderr
+            .lines()
+            .rev()
+            .take(10)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!("{} failed ({}):\n{}", what, output.status, tail);
+    }
+    Ok(())
+}
+
+/// Create the venv (if needed) with `base_python` and install `reqs` into it.
+/// Callers must have checked approval first.
+pub async fn install(server_id: &str, base_python: &str, reqs: &[String]) -> Result<()> {
+    for req in reqs {
+        validate_requirement(req)?;
+    }
+    let venv = venv_dir(server_id);
+    let python = bin_dir(&venv).join(if cfg!(windows) {
+        "python.exe"
+    } else {
+        "python"
+    });
+
+    if !python.exists() {
+        info!(
+            "Creating venv for [MCP] {} at {}",
+            server_id,
+            venv.display()
+        );
+        tokio::fs::create_dir_all(venv_root()).await?;
+        run(
+            Command::new(base_python).arg("-m").arg("venv").arg(&venv),
+            "venv creation",
+        )
+        .await?;
+    }
+
+    let normalized = normalize_requirements(reqs);
+    info!(
+        "Installing {} package(s) for [MCP] {}: {}",
+        normalized.len(),
+        server_id,
+        normalized.join(", ")
+    );
+    run(
+        Command::new(&python)
+            .args([
+                "-m",
+                "pip",
+                "install",
+                "--disable-pip-version-check",
+                "--no-input",
+                "--",
+            ])
+            .args(&normalized),
+        "pip install",
+    )
+    .await?;
+
+    tokio::fs::write(venv.join(MARKER_FILE), normalized.join("\n")).await?;
+    Ok(())
+}
+
+/// Environment overrides that make a bare `python` command resolve to the venv.
+///
+/// The command itself stays on the transport whitelist; the venv's bin
+/// directory is prepended to `PATH` for the child process instead.
+#[must_use]
+pub fn activation_env(server_id: &str) -> HashMap<String, String> {
+    let venv = venv_dir(server_id);
+    let venv = std::fs::canonicalize(&venv).unwrap_or(venv);
+    let mut paths = vec![bin_dir(&venv)];
+    if let Some(existing) = std::env::var_os("PATH") {
+        paths.extend(std::env::split_paths(&existing));
+    }
+    let path = std::env::join_paths(paths)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    HashMap::from([
+        ("PATH".to_string(), path),
+        (
+            "VIRTUAL_ENV".to_string(),
+            venv.to_string_lossy().into_owned(),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_requirement() {
+        assert!(validate_requirement("mss>=9.0.0").is_ok());
+        assert!(validate_requirement("uiautomation>=2.0; sys_platform == 'win32'").is_ok());
+        assert!(validate_requirement("requests[socks]==2.31.*").is_ok());
+        assert!(validate_requirement("-r requirements.txt").is_err());
+        assert!(validate_requirement("--index-url=http://evil").is_err());
+        assert!(validate_requirement("pkg @ https://example.com/pkg.whl").is_err());
+        assert!(validate_requirement("./local/pkg").is_err());
+        assert!(validate_requirement("pkg; rm -rf $HOME").is_err());
+        assert!(validate_requirement("").is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order_and_duplicates() {
+        let a = vec!["mss>=9".to_string(), "Pillow".to_string()];
+        let b = vec![
+            " Pillow".to_string(),
+            "mss>=9".to_string(),
+            "Pillow".to_string(),
+        ];
+        assert_eq!(requirements_fingerprint(&a), requirements_fingerprint(&b));
+        assert_ne!(
+            requirements_fingerprint(&a),
+            requirements_fingerprint(&["mss>=10".to_string()])
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Periodic compaction of long-term memories.
+//!
+//! Every `COMPACTION_INTERVAL`, each agent holding more than
+//! `CLOTO_MEMORY_COMPACTION_THRESHOLD` memories in the MCP memory server has
+//! its oldest ones folded into a summary entry written by the agent's own
+//! engine, leaving the newest half of the threshold verbatim. Summary entries
+//! are recalled like any other memory and are folded again by later runs, so
+//! long-term facts survive while recall stays within `MEMORY_CONTEXT_LIMIT`.
+//!
+//! Requires a memory server providing `list_memories` (with `oldest_first`)
+//! and `compact_memories`, such as `memory.ks22`.
+
+use super::{AgentManager, McpClientManager, PluginRegistry};
+use cloto_shared::{AgentMetadata, ClotoMessage, MessageSource};
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+const COMPACTION_INTERVAL: Duration = Duration::from_hours(1);
+/// Memories folded into a summary per engine call (`list_memories` caps at 500).
+const MAX_FOLD_BATCH: usize = 200;
+/// Per-memory cap on the text handed to the engine.
+const MAX_MEMORY_CHARS: usize = 2000;
+const SUMMARY_TIMEOUT: Duration = Duration::from_mins(2);
+const TOOL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A memory as listed by the memory server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StoredMemory {
+    pub id: i64,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub source: serde_json::Value,
+}
+
+impl StoredMemory {
+    fn speaker<'a>(&self, agent: &'a AgentMetadata) -> &'a str {
+        let field = |key: &str| self.source.get(key).and_then(|v| v.as_str());
+        match (field("type"), field("kind")) {
+            (Some("User"), _) => "User",
+            (Some("Agent"), _) => agent.name.as_str(),
+            (_, Some("summary")) => "Summary",
+            _ => "System",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct MemoryPage {
+    #[serde(default)]
+    memories: Vec<StoredMemory>,
+    #[serde(default)]
+    total: usize,
+}
+
+pub struct MemoryCompactor {
+    mcp: Arc<McpClientManager>,
+    registry: Arc<PluginRegistry>,
+    agent_manager: AgentManager,
+    /// Memories an agent may hold before compaction; 0 disables it.
+    threshold: usize,
+}
+
+impl MemoryCompactor {
+    #[must_use]
+    pub fn new(
+        mcp: Arc<McpClientManager>,
+        registry: Arc<PluginRegistry>,
+        agent_manager: AgentManager,
+        threshold: usize,
+    ) -> Self {
+        Self {
+            mcp,
+            registry,
+            agent_manager,
+            threshold,
+        }
+    }
+
+    pub fn spawn(self, shutdown: Arc<tokio::sync::Notify>) {
+        if self.threshold == 0 {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(COMPACTION_INTERVAL);
+            // The first tick fires immediately; leave startup to the servers
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    () = shutdown.notified() => {
+                        info!("Memory compaction task shutting down");
+                        break;
+                    }
+                    _ = interval.tick() => self.compact_all().await,
+                }
+            }
+        });
+    }
+
+    /// Compact the memories of every enabled agent that is over the threshold.
+    pub async fn compact_all(&self) {
+        let Some(server_id) = self.mcp.find_memory_server().await else {
+            return;
+        };
+        if self
+            .mcp
+            .get_tool_server_id("compact_memories")
+            .await
+            .as_deref()
+            != Some(server_id.as_str())
+        {
+            return;
+        }
+        let agents = match self.agent_manager.list_agents().await {
+            Ok(agents) => agents,
+            Err(e) => {
+                error!("Memory compaction: failed to list agents: {}", e);
+                return;
+            }
+        };
+        for agent in agents.into_iter().filter(|a| a.enabled) {
+            if let Err(e) = self.compact_agent(&server_id, &agent).await {
+                error!(agent_id = %agent.id, error = %e, "❌ Memory compaction failed");
+            }
+        }
+    }
+
+    /// Fold the agent's oldest memories into a summary if it is over the
+    /// threshold. Returns the number of memories folded.
+    pub async fn compact_agent(
+        &self,
+        server_id: &str,
+        agent: &AgentMetadata,
+    ) -> anyhow::Result<usize> {
+        let page: MemoryPage = self
+            .call_json(
+                server_id,
+                "list_memories",
+                serde_json::json!({
+                    "agent_id": agent.id,
+                    "limit": MAX_FOLD_BATCH,
+                    "oldest_first": true,
+                }),
+            )
+            .await?;
+        let batch = fold_batch(page.memories, page.total, self.threshold);
+        if batch.len() < 2 {
+            return Ok(0);
+        }
+
+        let engine_id = self.agent_manager.get_agent_config(&agent.id).await?.1;
+        let request = ClotoMessage::new(MessageSource::System, render_prompt(agent, &batch));
+        let summary = tokio::time::timeout(
+            SUMMARY_TIMEOUT,
+            self.registry.think(&engine_id, agent, &request, vec![]),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("engine '{}' timed out", engine_id))??;
+        let summary = summary.trim();
+        if summary.is_empty() {
+            anyhow::bail!("engine '{}' returned an empty summary", engine_id);
+        }
+
+        let ids: Vec<i64> = batch.iter().map(|m| m.id).collect();
+        let result: serde_json::Value = self
+            .call_json(
+                server_id,
+                "compact_memories",
+                serde_json::json!({
+                    "agent_id": agent.id,
+                    "memory_ids": ids,
+                    "summary": summary,
+                }),
+            )
+            .await?;
+        let folded = result
+            .get("compacted")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or_default();
+        info!(
+            agent_id = %agent.id,
+            folded,
+            total = page.total,
+            "🗜️ Memories compacted into summary"
+        );
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(folded as usize)
+    }
+
+    async fn call_json<T: serde::de::DeserializeOwned>(
+        &self,
+        server_id: &str,
+        tool: &str,
+        args: serde_json::Value,
+    ) -> anyhow::Result<T> {
+        let result = tokio::time::timeout(
+            TOOL_TIMEOUT,
+            self.mcp.call_server_tool(server_id, tool, args),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("'{}' on '{}' timed out", tool, server_id))??;
+        let json = result
+            .json_content()
+            .ok_or_else(|| anyhow::anyhow!("'{}' returned no JSON", tool))?;
+        if let Some(error) = json.get("error").and_then(|e| e.as_str()) {
+            anyhow::bail!("'{}' failed: {}", tool, error);
+        }
+        Ok(serde_json::from_value(json)?)
+    }
+}
+
+/// The oldest memories to fold when `total` is over `threshold`: enough to
+/// bring the agent down to half the threshold, at most `MAX_FOLD_BATCH`.
+/// `oldest` is the start of the agent's memories, oldest first.
+#[must_use]
+pub fn fold_batch(
+    mut oldest: Vec<StoredMemory>,
+    total: usize,
+    threshold: usize,
+) -> Vec<StoredMemory> {
+    if threshold == 0 || total <= threshold {
+        return Vec::new();
+    }
+    // Folded memories are replaced by one summary entry
+    let fold = (total - threshold / 2 + 1).min(MAX_FOLD_BATCH);
+    oldest.truncate(fold);
+    oldest
+}
+
+fn render_prompt(agent: &AgentMetadata, memories: &[StoredMemory]) -> String {
+    let mut prompt = format!(
+        "Condense these long-term memories into one summary. Keep facts about the user, \
+         decisions, preferences, commitments and open questions; drop small talk and \
+         anything already superseded. Earlier summaries are marked [Summary]. Write it \
+         from your perspective as {}, under 400 words, and reply with the summary only.\n\n",
+        agent.name
+    );
+    for memory in memories {
+        let text = if memory.content.chars().count() > MAX_MEMORY_CHARS {
+            let truncated: String = memory.content.chars().take(MAX_MEMORY_CHARS).collect();
+            format!("{}…", truncated)
+        } else {
+            memory.content.clone()
+        };
+        let _ = writeln!(prompt, "[{}] {}", memory.speaker(agent), text);
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memories(n: i64) -> Vec<StoredMemory> {
+        (1..=n)
+            .map(|id| StoredMemory {
+                id,
+                content: format!("memory {}", id),
+                source: serde_json::json!({ "type": "User", "id": "u", "name": "U" }),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fold_batch_brings_agent_to_half_threshold() {
+        // Under or at the threshold: nothing to do
+        assert!(fold_batch(memories(10), 10, 10).is_empty());
+        assert!(fold_batch(memories(10), 100, 0).is_empty());
+
+        // 30 memories, threshold 20: fold 21 into one summary, leaving 10
+        let batch = fold_batch(memories(30), 30, 20);
+        assert_eq!(batch.len(), 21);
+        assert_eq!(batch.first().map(|m| m.id), Some(1));
+
+        // Capped per run
+        let batch = fold_batch(
+            memories(i64::try_from(MAX_FOLD_BATCH).unwrap()),
+            10_000,
+            100,
+        );
+        assert_eq!(batch.len(), MAX_FOLD_BATCH);
+    }
+
+    #[test]
+    fn test_prompt_marks_earlier_summaries() {
+        let agent = AgentMetadata {
+            id: "agent.k".to_string(),
+            name: "Karin".to_string(),
+            description: String::new(),
+            enabled: true,
+            last_seen: 0,
+            status: String::new(),
+            default_engine_id: None,
+            required_capabilities: vec![],
+            metadata: std::collections::HashMap::new(),
+            system_prompt: None,
+        };
+        let batch = vec![
+            StoredMemory {
+                id: 1,
+                content: "User lives in Osaka".to_string(),
+                source: serde_json::json!({ "type": "System", "kind": "summary" }),
+            },
+            StoredMemory {
+                id: 2,
+                content: "I like tea".to_string(),
+                source: serde_json::json!({ "type": "User", "id": "u", "name": "U" }),
+            },
+        ];
+        let prompt = render_prompt(&agent, &batch);
+        assert!(prompt.contains("as Karin"));
+        assert!(prompt.contains("[Summary] User lives in Osaka\n[User] I like tea\n"));
+    }
+}
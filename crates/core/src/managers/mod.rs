@@ -1,13 +1,32 @@
 mod agents;
+pub mod attachments;
+pub mod channel_relay;
+pub mod heartbeat;
 pub mod llm_proxy;
+pub mod loop_guard;
 pub mod mcp;
+pub mod mcp_http;
+pub mod mcp_manifest;
 pub mod mcp_protocol;
 pub mod mcp_transport;
+pub mod mcp_venv;
+pub mod memory_compactor;
 mod plugin;
 mod registry;
+pub mod rooms;
 pub mod scheduler;
+pub mod summarizer;
+pub mod tool_audit;
 
-pub use agents::AgentManager;
+pub use agents::{AgentManager, CapabilityBinding, CapabilityResolution, ToolPolicy};
+pub use attachments::AttachmentProcessor;
+pub use heartbeat::HeartbeatMonitor;
 pub use mcp::McpClientManager;
+pub use memory_compactor::MemoryCompactor;
 pub use plugin::PluginManager;
-pub use registry::{PluginRegistry, PluginSetting, SystemMetrics};
+pub use registry::{
+    without_event_timeout, PluginFactory, PluginMap, PluginRegistry, PluginSetting, SystemMetrics,
+    DEFAULT_EVENT_RETRY_BACKOFF_MS, DEFAULT_EVENT_RETRY_MAX,
+};
+pub use rooms::RoomManager;
+pub use summarizer::ConversationSummarizer;
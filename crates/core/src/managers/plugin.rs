@@ -1,14 +1,14 @@
-use sqlx::SqlitePool;
+use crate::db::DbPool;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::info;
 
 use super::registry::{PluginRegistry, PluginSetting};
 use crate::capabilities::SafeHttpClient;
-use cloto_shared::Permission;
+use cloto_shared::{ConfigField, ConfigFieldType, Permission};
 
 pub struct PluginManager {
-    pub pool: SqlitePool,
+    pub pool: DbPool,
     http_client: Arc<SafeHttpClient>,
     event_timeout_secs: u64,
     max_event_depth: u8,
@@ -18,8 +18,12 @@ pub struct PluginManager {
 }
 
 impl PluginManager {
+    /// Placeholder returned instead of `secret` config values. Sending it
+    /// back unchanged keeps the stored value.
+    pub const SECRET_MASK: &'static str = "***";
+
     pub fn new(
-        pool: SqlitePool,
+        pool: DbPool,
         allowed_hosts: Vec<String>,
         event_timeout_secs: u64,
         max_event_depth: u8,
@@ -46,6 +50,86 @@ impl PluginManager {
         Ok(registry)
     }
 
+    /// Replace a loaded plugin with a fresh instance from its registered
+    /// factory and run `on_plugin_init` on it, without restarting the kernel.
+    ///
+    /// Calls already in flight finish on the old instance, which is dropped
+    /// once the last registry snapshot holding it is released.
+    pub async fn reload_plugin(
+        &self,
+        registry: &PluginRegistry,
+        plugin_id: &str,
+    ) -> anyhow::Result<cloto_shared::PluginManifest> {
+        let factory = registry.factory(plugin_id).await.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Plugin '{}' has no registered factory and cannot be reloaded",
+                plugin_id
+            )
+        })?;
+        let plugin = factory()?;
+        let manifest = plugin.manifest();
+        if manifest.id != plugin_id {
+            return Err(anyhow::anyhow!(
+                "Factory for '{}' built plugin '{}'",
+                plugin_id,
+                manifest.id
+            ));
+        }
+
+        let permissions = self.get_permissions(plugin_id).await?;
+        let network = permissions
+            .contains(&Permission::NetworkAccess)
+            .then(|| self.http_client.clone() as Arc<dyn cloto_shared::NetworkCapability>);
+        let context = cloto_shared::PluginRuntimeContext {
+            effective_permissions: permissions.clone(),
+            store: Arc::new(crate::db::ScopedDataStore::new(
+                Arc::new(crate::db::SqliteDataStore::new(self.pool.clone())),
+                plugin_id.to_string(),
+            )),
+            event_tx: self.plugin_event_sender(plugin_id),
+        };
+        plugin.on_plugin_init(context, network).await?;
+
+        registry
+            .effective_permissions
+            .write()
+            .await
+            .insert(cloto_shared::ClotoId::from_name(plugin_id), permissions);
+        registry
+            .plugins
+            .write()
+            .await
+            .insert(plugin_id.to_string(), plugin);
+        info!(plugin_id = %plugin_id, "🔄 Plugin reloaded");
+        Ok(manifest)
+    }
+
+    /// Channel for events a plugin emits on its own, forwarded to the event
+    /// bus with the plugin as issuer. Closes when the plugin drops its sender.
+    fn plugin_event_sender(
+        &self,
+        plugin_id: &str,
+    ) -> tokio::sync::mpsc::Sender<cloto_shared::ClotoEventData> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        if let Some(event_tx) = self.event_tx.clone() {
+            let issuer = cloto_shared::ClotoId::from_name(plugin_id);
+            tokio::spawn(async move {
+                while let Some(data) = rx.recv().await {
+                    let envelope = crate::EnvelopedEvent {
+                        event: Arc::new(cloto_shared::ClotoEvent::new(data)),
+                        issuer: Some(issuer),
+                        correlation_id: None,
+                        depth: 0,
+                    };
+                    if event_tx.send(envelope).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        tx
+    }
+
     /// L5: Get a clone of the shared SafeHttpClient Arc for runtime host addition.
     #[must_use]
     pub fn http_client(&self) -> Arc<SafeHttpClient> {
@@ -81,13 +165,17 @@ impl PluginManager {
                     std::sync::Arc::new(crate::capabilities::AllowedProcessCapability::new(vec![])),
                 ))
             }
+            // Only available when a desktop host registered a clipboard
+            Permission::ClipboardAccess => {
+                crate::capabilities::clipboard().map(cloto_shared::PluginCapability::Clipboard)
+            }
             _ => None,
         }
     }
 
     pub async fn get_config(&self, plugin_id: &str) -> anyhow::Result<HashMap<String, String>> {
         let rows: Vec<(String, String)> = sqlx::query_as(
-            "SELECT config_key, config_value FROM plugin_configs WHERE plugin_id = ? LIMIT 100",
+            "SELECT config_key, config_value FROM plugin_configs WHERE plugin_id = $1 LIMIT 100",
         )
         .bind(plugin_id)
         .fetch_all(&self.pool)
@@ -101,14 +189,93 @@ impl PluginManager {
         key: &str,
         value: &str,
     ) -> anyhow::Result<()> {
-        sqlx::query("INSERT OR REPLACE INTO plugin_configs (plugin_id, config_key, config_value) VALUES (?, ?, ?)")
-            .bind(plugin_id)
-            .bind(key)
-            .bind(value)
-            .execute(&self.pool).await?;
+        sqlx::query(
+            "INSERT INTO plugin_configs (plugin_id, config_key, config_value) VALUES ($1, $2, $3) \
+             ON CONFLICT(plugin_id, config_key) DO UPDATE SET config_value = excluded.config_value",
+        )
+        .bind(plugin_id)
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
+    /// Check `key = value` against a plugin's config schema. Plugins without
+    /// a schema accept any key.
+    pub fn validate_config(schema: &[ConfigField], key: &str, value: &str) -> anyhow::Result<()> {
+        if schema.is_empty() {
+            return Ok(());
+        }
+        let field = schema
+            .iter()
+            .find(|f| f.key == key)
+            .ok_or_else(|| anyhow::anyhow!("Unknown config key '{}'", key))?;
+        if value.is_empty() {
+            if field.required {
+                anyhow::bail!("'{}' is required", key);
+            }
+            return Ok(());
+        }
+        match &field.field_type {
+            ConfigFieldType::String | ConfigFieldType::Secret => {}
+            ConfigFieldType::Int { min, max } => {
+                let n: i64 = value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("'{}' must be an integer", key))?;
+                if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+                    anyhow::bail!(
+                        "'{}' must be between {} and {}",
+                        key,
+                        min.map_or("-∞".to_string(), |v| v.to_string()),
+                        max.map_or("∞".to_string(), |v| v.to_string())
+                    );
+                }
+            }
+            ConfigFieldType::Bool => {
+                if value != "true" && value != "false" {
+                    anyhow::bail!("'{}' must be true or false", key);
+                }
+            }
+            ConfigFieldType::Enum { options } => {
+                if !options.iter().any(|o| o == value) {
+                    anyhow::bail!("'{}' must be one of: {}", key, options.join(", "));
+                }
+            }
+        }
+        if let Some(ref pattern) = field.pattern {
+            let re = regex::Regex::new(&format!("^(?:{})$", pattern))
+                .map_err(|e| anyhow::anyhow!("Invalid pattern for '{}': {}", key, e))?;
+            if !re.is_match(value) {
+                anyhow::bail!("'{}' does not match the pattern {}", key, pattern);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stored config over the schema defaults, with `secret` values masked.
+    #[must_use]
+    pub fn present_config(
+        schema: &[ConfigField],
+        mut config: HashMap<String, String>,
+    ) -> HashMap<String, String> {
+        for field in schema {
+            if let Some(ref default) = field.default {
+                config
+                    .entry(field.key.clone())
+                    .or_insert_with(|| default.clone());
+            }
+            if field.field_type == ConfigFieldType::Secret {
+                if let Some(value) = config.get_mut(&field.key) {
+                    if !value.is_empty() {
+                        *value = Self::SECRET_MASK.to_string();
+                    }
+                }
+            }
+        }
+        config
+    }
+
     pub async fn list_plugins_with_settings(
         &self,
         registry: &PluginRegistry,
@@ -136,7 +303,7 @@ impl PluginManager {
     pub async fn apply_settings(&self, settings: Vec<(String, bool)>) -> anyhow::Result<()> {
         let mut tx = self.pool.begin().await?;
         for (id, active) in settings {
-            sqlx::query("UPDATE plugin_settings SET is_active = ? WHERE plugin_id = ?")
+            sqlx::query("UPDATE plugin_settings SET is_active = $1 WHERE plugin_id = $2")
                 .bind(active)
                 .bind(id)
                 .execute(&mut *tx)
@@ -151,8 +318,8 @@ impl PluginManager {
         &self,
         plugin_id: &str,
     ) -> anyhow::Result<Vec<cloto_shared::Permission>> {
-        let row: Option<(sqlx::types::Json<Vec<cloto_shared::Permission>>,)> =
-            sqlx::query_as("SELECT allowed_permissions FROM plugin_settings WHERE plugin_id = ?")
+        let row: Option<(crate::db::JsonText<Vec<cloto_shared::Permission>>,)> =
+            sqlx::query_as("SELECT allowed_permissions FROM plugin_settings WHERE plugin_id = $1")
                 .bind(plugin_id)
                 .fetch_optional(&self.pool)
                 .await?;
@@ -178,7 +345,7 @@ impl PluginManager {
             ));
         }
         let updated = serde_json::to_string(&perms)?;
-        sqlx::query("UPDATE plugin_settings SET allowed_permissions = ? WHERE plugin_id = ?")
+        sqlx::query("UPDATE plugin_settings SET allowed_permissions = $1 WHERE plugin_id = $2")
             .bind(&updated)
             .bind(plugin_id)
             .execute(&self.pool)
@@ -198,24 +365,116 @@ impl PluginManager {
         plugin_id: &str,
         permission: cloto_shared::Permission,
     ) -> anyhow::Result<()> {
-        // H-08: Single atomic SQL statement to prevent TOCTOU race in permission grant
-        let perm_json = serde_json::to_string(&permission)?;
-        sqlx::query(
-            "UPDATE plugin_settings SET allowed_permissions = json_insert(
-                allowed_permissions,
-                '$[' || json_array_length(allowed_permissions) || ']',
-                json(?)
-            ) WHERE plugin_id = ?
-            AND NOT EXISTS (
-                SELECT 1 FROM json_each(allowed_permissions)
-                WHERE value = json_extract(json(?), '$')
-            )",
-        )
-        .bind(&perm_json)
-        .bind(plugin_id)
-        .bind(&perm_json)
-        .execute(&self.pool)
-        .await?;
-        Ok(())
+        // H-08: Compare-and-swap on the stored list so concurrent grants
+        // (possibly from other kernels sharing the database) cannot lose
+        // each other's writes.
+        loop {
+            let current: Option<String> = sqlx::query_scalar(
+                "SELECT COALESCE(allowed_permissions, '[]') FROM plugin_settings WHERE plugin_id = $1",
+            )
+            .bind(plugin_id)
+            .fetch_optional(&self.pool)
+            .await?;
+            let Some(current) = current else {
+                return Ok(());
+            };
+            let mut perms: Vec<cloto_shared::Permission> =
+                serde_json::from_str(&current).unwrap_or_default();
+            if perms.contains(&permission) {
+                return Ok(());
+            }
+            perms.push(permission.clone());
+            let updated = sqlx::query(
+                "UPDATE plugin_settings SET allowed_permissions = $1 \
+                 WHERE plugin_id = $2 AND COALESCE(allowed_permissions, '[]') = $3",
+            )
+            .bind(serde_json::to_string(&perms)?)
+            .bind(plugin_id)
+            .bind(&current)
+            .execute(&self.pool)
+            .await?;
+            if updated.rows_affected() > 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Vec<ConfigField> {
+        vec![
+            ConfigField::new("api_key", ConfigFieldType::Secret).required(),
+            ConfigField::new(
+                "max_tokens",
+                ConfigFieldType::Int {
+                    min: Some(1),
+                    max: Some(8192),
+                },
+            )
+            .with_default("1024"),
+            ConfigField::new("stream", ConfigFieldType::Bool),
+            ConfigField::new(
+                "mode",
+                ConfigFieldType::Enum {
+                    options: vec!["fast".into(), "precise".into()],
+                },
+            ),
+            ConfigField::new("region", ConfigFieldType::String).with_pattern("[a-z]+-[0-9]"),
+        ]
+    }
+
+    #[test]
+    fn test_validate_config_accepts_valid_values() {
+        let schema = schema();
+        for (key, value) in [
+            ("api_key", "sk-123"),
+            ("max_tokens", "4096"),
+            ("stream", "true"),
+            ("mode", "precise"),
+            ("region", "us-1"),
+            ("max_tokens", ""),
+        ] {
+            assert!(
+                PluginManager::validate_config(&schema, key, value).is_ok(),
+                "{key}={value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_config_rejects_invalid_values() {
+        let schema = schema();
+        for (key, value) in [
+            ("unknown", "x"),
+            ("api_key", ""),
+            ("max_tokens", "abc"),
+            ("max_tokens", "0"),
+            ("max_tokens", "9000"),
+            ("stream", "yes"),
+            ("mode", "slow"),
+            ("region", "us-1x"),
+        ] {
+            assert!(
+                PluginManager::validate_config(&schema, key, value).is_err(),
+                "{key}={value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_config_without_schema_accepts_anything() {
+        assert!(PluginManager::validate_config(&[], "anything", "goes").is_ok());
+    }
+
+    #[test]
+    fn test_present_config_fills_defaults_and_masks_secrets() {
+        let config = HashMap::from([("api_key".to_string(), "sk-123".to_string())]);
+        let presented = PluginManager::present_config(&schema(), config);
+        assert_eq!(presented["api_key"], PluginManager::SECRET_MASK);
+        assert_eq!(presented["max_tokens"], "1024");
+        assert!(!presented.contains_key("stream"));
     }
 }
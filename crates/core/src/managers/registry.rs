@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn, Instrument};
 
 use cloto_shared::{ClotoId, Permission, Plugin, PluginManifest};
 
@@ -8,23 +8,284 @@ use cloto_shared::{ClotoId, Permission, Plugin, PluginManifest};
 pub struct PluginSetting {
     pub plugin_id: String,
     pub is_active: bool,
-    pub allowed_permissions: sqlx::types::Json<Vec<Permission>>,
+    pub allowed_permissions: crate::db::JsonText<Vec<Permission>>,
+}
+
+type PluginTable = HashMap<String, Arc<dyn Plugin>>;
+
+pub const DEFAULT_EVENT_RETRY_MAX: u32 = 2;
+pub const DEFAULT_EVENT_RETRY_BACKOFF_MS: u64 = 500;
+
+/// Outcome of one `on_event` call: `Err` when it timed out.
+type DeliveryResult = Result<anyhow::Result<Option<cloto_shared::ClotoEventData>>, TimedOut>;
+
+/// `on_event` ran past its `plugin_event_timeout_secs`.
+#[derive(Debug)]
+struct TimedOut;
+
+tokio::task_local! {
+    /// Timeout of the `on_event` call running on this task.
+    static EVENT_DEADLINE: Arc<EventDeadline>;
+}
+
+/// Deadline of one `on_event` call. Time spent in [`without_event_timeout`]
+/// pushes it back.
+struct EventDeadline {
+    state: std::sync::Mutex<DeadlineState>,
+    changed: tokio::sync::Notify,
+}
+
+struct DeadlineState {
+    deadline: tokio::time::Instant,
+    /// Open `without_event_timeout` sections
+    paused: usize,
+    /// When the outermost open section began
+    paused_at: tokio::time::Instant,
+}
+
+impl EventDeadline {
+    fn new(timeout: std::time::Duration) -> Self {
+        let now = tokio::time::Instant::now();
+        Self {
+            state: std::sync::Mutex::new(DeadlineState {
+                deadline: now + timeout,
+                paused: 0,
+                paused_at: now,
+            }),
+            changed: tokio::sync::Notify::new(),
+        }
+    }
+
+    fn pause(&self) -> PausedDeadline<'_> {
+        if let Ok(mut state) = self.state.lock() {
+            if state.paused == 0 {
+                state.paused_at = tokio::time::Instant::now();
+            }
+            state.paused += 1;
+        }
+        self.changed.notify_waiters();
+        PausedDeadline(self)
+    }
+
+    /// The deadline, or `None` while paused.
+    fn current(&self) -> Result<Option<tokio::time::Instant>, ()> {
+        let state = self.state.lock().map_err(|_| ())?;
+        Ok((state.paused == 0).then_some(state.deadline))
+    }
+
+    /// Resolves once the deadline passes while not paused.
+    async fn expired(&self) {
+        loop {
+            // Registered before reading the state, so no change is missed
+            let changed = self.changed.notified();
+            let Ok(deadline) = self.current() else {
+                return;
+            };
+            match deadline {
+                Some(deadline) if deadline <= tokio::time::Instant::now() => return,
+                Some(deadline) => {
+                    tokio::select! {
+                        () = tokio::time::sleep_until(deadline) => {}
+                        () = changed => {}
+                    }
+                }
+                None => changed.await,
+            }
+        }
+    }
+}
+
+struct PausedDeadline<'a>(&'a EventDeadline);
+
+impl Drop for PausedDeadline<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.0.state.lock() {
+            state.paused = state.paused.saturating_sub(1);
+            if state.paused == 0 {
+                let paused_for = state.paused_at.elapsed();
+                state.deadline += paused_for;
+            }
+        }
+        self.0.changed.notify_waiters();
+    }
+}
+
+/// Run `fut` without the time it takes counting against the plugin event
+/// timeout of the `on_event` call it runs in, e.g. while a person decides
+/// on a tool call. Elsewhere this is plain `fut.await`.
+pub async fn without_event_timeout<F: std::future::Future>(fut: F) -> F::Output {
+    let deadline = EVENT_DEADLINE.try_with(Arc::clone).ok();
+    let _paused = deadline.as_deref().map(EventDeadline::pause);
+    fut.await
+}
+
+/// Builds a fresh instance of a plugin. Registering one makes the plugin
+/// reloadable at runtime (`PluginManager::reload_plugin`).
+pub type PluginFactory = Arc<dyn Fn() -> anyhow::Result<Arc<dyn Plugin>> + Send + Sync>;
+
+/// Event types each plugin subscribes to, for plugins that declare any.
+type SubscriptionTable = HashMap<String, Vec<String>>;
+
+/// Copy-on-write plugin table.
+///
+/// Every event dispatch and HTTP listing reads the table, while plugins are
+/// only added or removed at startup and on enable/disable. Readers take an
+/// `Arc` snapshot (the lock is held just for the refcount increment and never
+/// across an await); writers edit a private copy that is published when the
+/// write guard drops.
+pub struct PluginMap {
+    current: std::sync::RwLock<Arc<PluginTable>>,
+    /// `PluginManifest::subscribes` of the current table, collected on
+    /// publish so dispatch doesn't build a manifest per event.
+    subscriptions: std::sync::RwLock<Arc<SubscriptionTable>>,
+    writer: tokio::sync::Mutex<()>,
+}
+
+impl Default for PluginMap {
+    fn default() -> Self {
+        Self {
+            current: std::sync::RwLock::new(Arc::new(HashMap::new())),
+            subscriptions: std::sync::RwLock::new(Arc::new(HashMap::new())),
+            writer: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+impl PluginMap {
+    /// Current snapshot. Later writes do not affect it.
+    #[must_use]
+    pub fn load(&self) -> Arc<PluginTable> {
+        self.current
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Whether the plugin `id` takes events of `event_type`. Plugins that
+    /// declare no subscriptions take every event.
+    #[must_use]
+    pub fn subscribes_to(&self, id: &str, event_type: &str) -> bool {
+        self.subscriptions
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(id)
+            .is_none_or(|types| types.iter().any(|t| t == event_type))
+    }
+
+    /// Names in the plugin `id`'s `subscribes` list that are not event types.
+    /// The plugin never receives anything under them.
+    #[must_use]
+    pub fn unknown_subscriptions(&self, id: &str) -> Vec<String> {
+        self.subscriptions
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(id)
+            .map(|types| unknown_event_types(types))
+            .unwrap_or_default()
+    }
+
+    /// Start a copy-on-write update. Writers are serialized; the changes
+    /// become visible to readers when the guard is dropped.
+    pub async fn write(&self) -> PluginMapWriteGuard<'_> {
+        let lock = self.writer.lock().await;
+        PluginMapWriteGuard {
+            map: self,
+            next: (*self.load()).clone(),
+            _lock: lock,
+        }
+    }
+}
+
+pub struct PluginMapWriteGuard<'a> {
+    map: &'a PluginMap,
+    next: PluginTable,
+    _lock: tokio::sync::MutexGuard<'a, ()>,
+}
+
+impl std::ops::Deref for PluginMapWriteGuard<'_> {
+    type Target = PluginTable;
+
+    fn deref(&self) -> &PluginTable {
+        &self.next
+    }
+}
+
+impl std::ops::DerefMut for PluginMapWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut PluginTable {
+        &mut self.next
+    }
+}
+
+impl Drop for PluginMapWriteGuard<'_> {
+    fn drop(&mut self) {
+        let next = Arc::new(std::mem::take(&mut self.next));
+        let subscriptions: SubscriptionTable = next
+            .iter()
+            .filter_map(|(id, plugin)| {
+                let subscribes = plugin.manifest().subscribes;
+                (!subscribes.is_empty()).then(|| (id.clone(), subscribes))
+            })
+            .collect();
+        let mut current = self
+            .map
+            .subscriptions
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Only plugins added or changed by this write, so each typo is reported once
+        for (id, types) in &subscriptions {
+            if current.get(id) == Some(types) {
+                continue;
+            }
+            let unknown = unknown_event_types(types);
+            if !unknown.is_empty() {
+                warn!(
+                    plugin_id = %id,
+                    unknown = ?unknown,
+                    "Plugin subscribes to unknown event types; it will never receive them"
+                );
+            }
+        }
+        *current = Arc::new(subscriptions);
+        drop(current);
+        *self
+            .map
+            .current
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = next;
+    }
+}
+
+fn unknown_event_types(types: &[String]) -> Vec<String> {
+    types
+        .iter()
+        .filter(|t| !cloto_shared::ClotoEventData::TYPE_NAMES.contains(&t.as_str()))
+        .cloned()
+        .collect()
 }
 
 pub struct PluginRegistry {
-    pub plugins: tokio::sync::RwLock<HashMap<String, Arc<dyn Plugin>>>,
+    pub plugins: PluginMap,
     pub effective_permissions: tokio::sync::RwLock<HashMap<ClotoId, Vec<Permission>>>,
-    pub event_timeout_secs: u64,
+    pub event_timeout_secs: std::sync::atomic::AtomicU64,
     pub max_event_depth: u8,
     pub event_semaphore: Arc<tokio::sync::Semaphore>,
     /// MCP Client Manager for dual dispatch (Rust plugins + MCP servers)
     pub mcp_manager: Option<Arc<super::McpClientManager>>,
+    /// Retries of a failed `on_event` before the event is dead-lettered
+    pub event_retry_max: std::sync::atomic::AtomicU32,
+    /// Delay before the first retry; doubles on each further one
+    pub event_retry_backoff_ms: std::sync::atomic::AtomicU64,
+    /// Where events that exhaust their retries are kept; dropped if unset
+    dead_letter_pool: Option<crate::db::DbPool>,
+    factories: tokio::sync::RwLock<HashMap<String, PluginFactory>>,
 }
 
 pub struct SystemMetrics {
     pub total_requests: std::sync::atomic::AtomicU64,
     pub total_memories: std::sync::atomic::AtomicU64,
     pub total_episodes: std::sync::atomic::AtomicU64,
+    /// Connection acquire and query timings (see `db_metrics`).
+    pub db: &'static crate::db_metrics::DbMetrics,
 }
 
 impl Default for SystemMetrics {
@@ -33,6 +294,7 @@ impl Default for SystemMetrics {
             total_requests: std::sync::atomic::AtomicU64::new(0),
             total_memories: std::sync::atomic::AtomicU64::new(0),
             total_episodes: std::sync::atomic::AtomicU64::new(0),
+            db: crate::db_metrics::global(),
         }
     }
 }
@@ -48,12 +310,18 @@ impl PluginRegistry {
     #[must_use]
     pub fn new(event_timeout_secs: u64, max_event_depth: u8) -> Self {
         Self {
-            plugins: tokio::sync::RwLock::new(HashMap::new()),
+            plugins: PluginMap::default(),
             effective_permissions: tokio::sync::RwLock::new(HashMap::new()),
-            event_timeout_secs,
+            event_timeout_secs: std::sync::atomic::AtomicU64::new(event_timeout_secs),
             max_event_depth,
             event_semaphore: Arc::new(tokio::sync::Semaphore::new(50)),
             mcp_manager: None,
+            event_retry_max: std::sync::atomic::AtomicU32::new(DEFAULT_EVENT_RETRY_MAX),
+            event_retry_backoff_ms: std::sync::atomic::AtomicU64::new(
+                DEFAULT_EVENT_RETRY_BACKOFF_MS,
+            ),
+            dead_letter_pool: None,
+            factories: tokio::sync::RwLock::new(HashMap::new()),
         }
     }
 
@@ -62,6 +330,19 @@ impl PluginRegistry {
         self.mcp_manager = Some(mcp_manager);
     }
 
+    /// Persist events that fail every retry to `dead_letter_events`.
+    pub fn set_dead_letter_pool(&mut self, pool: crate::db::DbPool) {
+        self.dead_letter_pool = Some(pool);
+    }
+
+    /// Change the retry policy for failed event handling (config hot reload).
+    pub fn set_event_retry(&self, max_retries: u32, backoff_ms: u64) {
+        self.event_retry_max
+            .store(max_retries, std::sync::atomic::Ordering::Relaxed);
+        self.event_retry_backoff_ms
+            .store(backoff_ms, std::sync::atomic::Ordering::Relaxed);
+    }
+
     pub async fn update_effective_permissions(&self, plugin_id: ClotoId, permission: Permission) {
         let mut perms_lock = self.effective_permissions.write().await;
         let perms = perms_lock.entry(plugin_id).or_default();
@@ -70,18 +351,27 @@ impl PluginRegistry {
         }
     }
 
+    /// Register the factory for plugin `id` so it can be reloaded.
+    pub async fn register_factory(&self, id: &str, factory: PluginFactory) {
+        self.factories.write().await.insert(id.to_string(), factory);
+    }
+
+    pub async fn factory(&self, id: &str) -> Option<PluginFactory> {
+        self.factories.read().await.get(id).cloned()
+    }
+
     pub async fn list_plugins(&self) -> Vec<PluginManifest> {
-        let plugins = self.plugins.read().await;
+        let plugins = self.plugins.load();
         plugins.values().map(|p| p.manifest()).collect()
     }
 
     pub async fn get_engine(&self, id: &str) -> Option<Arc<dyn Plugin>> {
-        let plugins = self.plugins.read().await;
+        let plugins = self.plugins.load();
         plugins.get(id).cloned()
     }
 
     pub async fn find_memory(&self) -> Option<Arc<dyn Plugin>> {
-        let plugins = self.plugins.read().await;
+        let plugins = self.plugins.load();
         for plugin in plugins.values() {
             if plugin.as_memory().is_some() {
                 return Some(plugin.clone());
@@ -90,10 +380,54 @@ impl PluginRegistry {
         None
     }
 
+    /// One-shot `think()` on an engine, without tools.
+    /// Dual Dispatch: Rust reasoning plugin first, then an MCP server with that ID.
+    pub async fn think(
+        &self,
+        engine_id: &str,
+        agent: &cloto_shared::AgentMetadata,
+        message: &cloto_shared::ClotoMessage,
+        context: Vec<cloto_shared::ClotoMessage>,
+    ) -> anyhow::Result<String> {
+        if let Some(plugin) = self.get_engine(engine_id).await {
+            let engine = plugin.as_reasoning().ok_or_else(|| {
+                anyhow::anyhow!("Plugin '{}' is not a ReasoningEngine", engine_id)
+            })?;
+            return engine.think(agent, message, context).await;
+        }
+        if let Some(ref mcp) = self.mcp_manager {
+            if mcp.has_server(engine_id).await {
+                let args = serde_json::json!({
+                    "agent": agent,
+                    "message": message,
+                    "context": context.iter().map(|m| {
+                        serde_json::json!({
+                            "source": m.source,
+                            "content": m.content,
+                        })
+                    }).collect::<Vec<_>>(),
+                });
+                let result = mcp.call_server_tool(engine_id, "think", args).await?;
+                return result.think_content();
+            }
+        }
+        Err(anyhow::anyhow!("Engine '{}' not found", engine_id))
+    }
+
+    /// ID of the Rust plugin that provides a tool, if any.
+    #[must_use]
+    pub fn native_tool_owner(&self, tool_name: &str) -> Option<String> {
+        let plugins = self.plugins.load();
+        plugins.iter().find_map(|(id, p)| {
+            let tool = p.as_tool()?;
+            (tool.name() == tool_name).then(|| id.clone())
+        })
+    }
+
     /// Collect tool schemas from all active Tool plugins + MCP servers (OpenAI function calling format).
     pub async fn collect_tool_schemas(&self) -> Vec<serde_json::Value> {
         let mut schemas: Vec<serde_json::Value> = {
-            let plugins = self.plugins.read().await;
+            let plugins = self.plugins.load();
             plugins
                 .values()
                 .filter_map(|p| {
@@ -124,7 +458,7 @@ impl PluginRegistry {
         allowed_plugin_ids: &[String],
     ) -> Vec<serde_json::Value> {
         let mut schemas: Vec<serde_json::Value> = {
-            let plugins = self.plugins.read().await;
+            let plugins = self.plugins.load();
             plugins
                 .iter()
                 .filter_map(|(id, p)| {
@@ -153,8 +487,6 @@ impl PluginRegistry {
     }
 
     /// Execute a tool by name with the given arguments.
-    /// H-01: Drops the read lock before calling tool.execute() to avoid blocking
-    /// plugin registration during long-running tool execution.
     /// Dual Dispatch: tries Rust plugins first, then falls back to MCP servers.
     pub async fn execute_tool(
         &self,
@@ -163,7 +495,7 @@ impl PluginRegistry {
     ) -> anyhow::Result<serde_json::Value> {
         // 1. Try Rust plugins first
         let tool_plugin = {
-            let plugins = self.plugins.read().await;
+            let plugins = self.plugins.load();
             plugins.values().find_map(|p| {
                 let tool = p.as_tool()?;
                 if tool.name() == tool_name {
@@ -172,7 +504,7 @@ impl PluginRegistry {
                     None
                 }
             })
-        }; // read lock dropped here
+        };
         if let Some(plugin) = tool_plugin {
             if let Some(tool) = plugin.as_tool() {
                 return tool.execute(args).await;
@@ -197,7 +529,7 @@ impl PluginRegistry {
     ) -> anyhow::Result<serde_json::Value> {
         // 1. Try Rust plugins first
         let tool_plugin = {
-            let plugins = self.plugins.read().await;
+            let plugins = self.plugins.load();
             plugins.iter().find_map(|(id, p)| {
                 if !allowed_plugin_ids.contains(id) {
                     return None;
@@ -209,7 +541,7 @@ impl PluginRegistry {
                     None
                 }
             })
-        }; // read lock dropped here
+        };
         if let Some(plugin) = tool_plugin {
             if let Some(tool) = plugin.as_tool() {
                 return tool.execute(args).await;
@@ -246,7 +578,7 @@ impl PluginRegistry {
         agent_id: &str,
     ) -> Vec<serde_json::Value> {
         let mut schemas: Vec<serde_json::Value> = {
-            let plugins = self.plugins.read().await;
+            let plugins = self.plugins.load();
             plugins
                 .iter()
                 .filter_map(|(id, p)| {
@@ -286,7 +618,7 @@ impl PluginRegistry {
     ) -> anyhow::Result<serde_json::Value> {
         // 1. Try Rust plugins first (same gate as execute_tool_for)
         let tool_plugin = {
-            let plugins = self.plugins.read().await;
+            let plugins = self.plugins.load();
             plugins.iter().find_map(|(id, p)| {
                 if !allowed_plugin_ids.contains(id) {
                     return None;
@@ -298,7 +630,7 @@ impl PluginRegistry {
                     None
                 }
             })
-        }; // read lock dropped here
+        };
         if let Some(plugin) = tool_plugin {
             if let Some(tool) = plugin.as_tool() {
                 return tool.execute(args).await;
@@ -336,6 +668,10 @@ impl PluginRegistry {
     }
 
     /// 全てのアクティブなプラグインにイベントを配信する
+    ///
+    /// Plugins run concurrently, at most `event_semaphore` at a time, each
+    /// under its own `plugin_event_timeout_secs`. Events they return are
+    /// re-dispatched in plugin ID order once every plugin has finished.
     pub async fn dispatch_event(
         &self,
         envelope: crate::EnvelopedEvent,
@@ -355,77 +691,246 @@ impl PluginRegistry {
             return;
         }
 
-        let plugins = self.plugins.read().await;
+        let plugins = self.plugins.load();
+        let event_type = event.data.type_name();
 
         use futures::stream::{FuturesUnordered, StreamExt};
-        use futures::FutureExt;
         let mut futures = FuturesUnordered::new();
 
         for (id, plugin) in plugins.iter() {
+            if !self.plugins.subscribes_to(id, event_type) {
+                continue;
+            }
             let plugin = plugin.clone();
             let event = event.clone();
             let id = id.clone();
-            let timeout_duration = std::time::Duration::from_secs(self.event_timeout_secs);
+            let span = tracing::info_span!(
+                "plugin.on_event",
+                plugin_id = %id,
+                trace_id = %event.trace_id,
+            );
+            let timeout_duration = self.event_timeout();
+            let max_retries = self
+                .event_retry_max
+                .load(std::sync::atomic::Ordering::Relaxed);
+            let backoff_ms = self
+                .event_retry_backoff_ms
+                .load(std::sync::atomic::Ordering::Relaxed);
             let semaphore = self.event_semaphore.clone();
 
-            futures.push(tokio::spawn(async move {
-                let Ok(_permit) = semaphore.acquire().await else {
-                    tracing::warn!("Semaphore closed during shutdown, skipping plugin {}", id);
-                    return (id, Ok(Ok(None)));
-                };
-                // Catch panics to prevent semaphore permit leaks
-                let result = tokio::time::timeout(timeout_duration, async {
-                    match std::panic::AssertUnwindSafe(plugin.on_event(&event))
-                        .catch_unwind()
-                        .await
-                    {
-                        Ok(r) => r,
-                        Err(_) => Err(anyhow::anyhow!("Plugin panicked during on_event")),
-                    }
-                })
-                .await;
-                // _permit dropped here automatically (even on panic path above)
-                (id, result)
-            }));
+            futures.push(tokio::spawn(
+                async move {
+                    let (result, attempts) = deliver_with_retry(
+                        &id,
+                        &plugin,
+                        &event,
+                        timeout_duration,
+                        (max_retries, backoff_ms),
+                        &semaphore,
+                    )
+                    .await;
+                    (id, result, attempts)
+                }
+                .instrument(span),
+            ));
         }
 
-        // ロックを早めに解放
-        drop(plugins);
-
-        // 完了した順に結果を処理
+        // Gather every outcome first; completion order depends on timing
+        let mut outcomes = Vec::with_capacity(futures.len());
         while let Some(join_result) = futures.next().await {
-            let (id, timeout_result) = match join_result {
-                Ok(pair) => pair,
-                Err(e) => {
-                    error!("🔥 Plugin task PANICKED or was cancelled: {}", e);
-                    continue;
-                }
-            };
+            match join_result {
+                Ok(outcome) => outcomes.push(outcome),
+                Err(e) => error!("🔥 Plugin task PANICKED or was cancelled: {}", e),
+            }
+        }
+        // ...then handle them in plugin ID order, so cascaded events reach
+        // the bus in the same order for the same set of plugins
+        outcomes.sort_by(|a, b| a.0.cmp(&b.0));
 
+        let mut returned = Vec::new();
+        for (id, timeout_result, attempts) in outcomes {
             match timeout_result {
-                Ok(Ok(Some(new_event_data))) => {
-                    let tx = event_tx.clone();
-                    let id_clone = id.clone();
-                    let trace_id = event.trace_id;
-                    let semaphore = self.event_semaphore.clone();
-                    tokio::spawn(redispatch_plugin_event(
-                        tx,
-                        id_clone,
-                        trace_id,
-                        new_event_data,
-                        current_depth,
-                        semaphore,
-                    ));
-                }
+                Ok(Ok(Some(new_event_data))) => returned.push((id, new_event_data)),
                 Ok(Ok(None)) => {}
                 Ok(Err(e)) => {
                     error!("🔌 Plugin {} on_event error: {}", id, e);
+                    self.dead_letter(&id, &event, current_depth, e.to_string(), attempts);
                 }
                 Err(_) => {
                     error!("⏱️ Plugin {} timed out during event processing", id);
+                    self.dead_letter(
+                        &id,
+                        &event,
+                        current_depth,
+                        format!("Timed out after {}s", self.event_timeout().as_secs()),
+                        attempts,
+                    );
                 }
             }
         }
+
+        if !returned.is_empty() {
+            let tx = event_tx.clone();
+            let trace_id = event.trace_id;
+            let semaphore = self.event_semaphore.clone();
+            tokio::spawn(async move {
+                for (id, new_event_data) in returned {
+                    redispatch_plugin_event(
+                        tx.clone(),
+                        id,
+                        trace_id,
+                        new_event_data,
+                        current_depth,
+                        semaphore.clone(),
+                    )
+                    .await;
+                }
+            });
+        }
+    }
+
+    fn event_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.event_timeout_secs
+                .load(std::sync::atomic::Ordering::Relaxed),
+        )
+    }
+
+    /// Keep an event the plugin failed to handle so it can be replayed.
+    fn dead_letter(
+        &self,
+        plugin_id: &str,
+        event: &cloto_shared::ClotoEvent,
+        depth: u8,
+        error: String,
+        attempts: u32,
+    ) {
+        let Some(pool) = self.dead_letter_pool.clone() else {
+            return;
+        };
+        let json = match serde_json::to_value(event) {
+            Ok(json) => json,
+            Err(e) => {
+                error!("Failed to serialize dead-letter event: {}", e);
+                return;
+            }
+        };
+        let row = crate::db::DeadLetterEventRow {
+            id: format!("deadletter.{}", ClotoId::new()),
+            plugin_id: plugin_id.to_string(),
+            event_type: json["type"].as_str().unwrap_or_default().to_string(),
+            event: json.to_string(),
+            depth: i32::from(depth),
+            error,
+            attempts: i32::try_from(attempts).unwrap_or(i32::MAX),
+            created_at: chrono::Utc::now().timestamp_millis(),
+            replayed_at: None,
+        };
+        tokio::spawn(async move {
+            if let Err(e) = crate::db::create_dead_letter_event(&pool, &row).await {
+                error!("Failed to record dead-letter event: {}", e);
+            }
+        });
+    }
+
+    /// Redeliver a dead-lettered event to the plugin that failed it. An event
+    /// the plugin emits in response is dispatched as usual.
+    pub async fn replay_dead_letter(
+        &self,
+        row: &crate::db::DeadLetterEventRow,
+        event_tx: &tokio::sync::mpsc::Sender<crate::EnvelopedEvent>,
+    ) -> anyhow::Result<()> {
+        let event: cloto_shared::ClotoEvent = serde_json::from_str(&row.event)?;
+        let plugin = self
+            .plugins
+            .load()
+            .get(&row.plugin_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Plugin '{}' is not loaded", row.plugin_id))?;
+        let result = {
+            let _permit = self.event_semaphore.acquire().await?;
+            deliver(&plugin, &event, self.event_timeout()).await
+        };
+        let response = result.map_err(|_| {
+            anyhow::anyhow!("Timed out after {}s", self.event_timeout().as_secs())
+        })??;
+        if let Some(new_event_data) = response {
+            tokio::spawn(redispatch_plugin_event(
+                event_tx.clone(),
+                row.plugin_id.clone(),
+                event.trace_id,
+                new_event_data,
+                u8::try_from(row.depth).unwrap_or(self.max_event_depth),
+                self.event_semaphore.clone(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Deliver an event, retrying errors (not timeouts, which already took the
+/// full budget) with exponential backoff. Returns the last result and the
+/// number of attempts made.
+async fn deliver_with_retry(
+    plugin_id: &str,
+    plugin: &Arc<dyn Plugin>,
+    event: &cloto_shared::ClotoEvent,
+    timeout: std::time::Duration,
+    (max_retries, backoff_ms): (u32, u64),
+    semaphore: &tokio::sync::Semaphore,
+) -> (DeliveryResult, u32) {
+    let mut attempt = 0;
+    loop {
+        let Ok(permit) = semaphore.acquire().await else {
+            tracing::warn!(
+                "Semaphore closed during shutdown, skipping plugin {}",
+                plugin_id
+            );
+            return (Ok(Ok(None)), attempt);
+        };
+        let result = deliver(plugin, event, timeout).await;
+        // Release the permit while backing off
+        drop(permit);
+        attempt += 1;
+        match result {
+            Ok(Err(e)) if attempt <= max_retries => {
+                let delay = backoff_ms.saturating_mul(1 << (attempt - 1).min(16));
+                tracing::warn!(
+                    "🔁 Plugin {} on_event failed (attempt {}), retrying in {}ms: {}",
+                    plugin_id,
+                    attempt,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            }
+            result => return (result, attempt),
+        }
+    }
+}
+
+/// Call the plugin's `on_event` once, with a timeout (paused by
+/// [`without_event_timeout`]). Panics are caught and reported as errors so
+/// the caller's semaphore permit is never leaked.
+async fn deliver(
+    plugin: &Arc<dyn Plugin>,
+    event: &cloto_shared::ClotoEvent,
+    timeout: std::time::Duration,
+) -> DeliveryResult {
+    use futures::FutureExt;
+    let deadline = Arc::new(EventDeadline::new(timeout));
+    let call = EVENT_DEADLINE.scope(deadline.clone(), async {
+        match std::panic::AssertUnwindSafe(plugin.on_event(event))
+            .catch_unwind()
+            .await
+        {
+            Ok(r) => r,
+            Err(_) => Err(anyhow::anyhow!("Plugin panicked during on_event")),
+        }
+    });
+    tokio::select! {
+        result = call => Ok(result),
+        () = deadline.expired() => Err(TimedOut),
     }
 }
 
@@ -0,0 +1,532 @@
+//! Group chat rooms — several agents and the user in one conversation.
+//!
+//! A user message posted to a room is appended to the room transcript, then
+//! the room's turn policy picks the members that answer it:
+//! - `round_robin`: the next member in turn order
+//! - `addressed`: the members named in the message (`@id`, `@name` or the
+//!   plain name), in the order they are named; round-robin if nobody is named
+//!
+//! Each speaker receives a `MessageReceived` carrying the recent transcript,
+//! so it sees what the user and the other agents said, including replies given
+//! earlier in the same turn. Answers are appended to the transcript with the
+//! agent as speaker. A room works through one message at a time.
+
+use crate::db::DbPool;
+use crate::db::{self, ChatRoomMessageRow, ChatRoomRow};
+use crate::events::SerializedEvent;
+use cloto_shared::{AgentMetadata, ClotoEvent, ClotoEventData, ClotoMessage, MessageSource};
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tracing::{info, warn};
+
+use super::AgentManager;
+
+pub const MIN_MEMBERS: usize = 2;
+pub const MAX_MEMBERS: usize = 16;
+
+/// Transcript messages handed to a speaker as context.
+const TRANSCRIPT_CONTEXT: i64 = 30;
+
+/// How a room decides who answers a user message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TurnPolicy {
+    RoundRobin,
+    Addressed,
+}
+
+impl TurnPolicy {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RoundRobin => "round_robin",
+            Self::Addressed => "addressed",
+        }
+    }
+}
+
+impl FromStr for TurnPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "round_robin" => Ok(Self::RoundRobin),
+            "addressed" => Ok(Self::Addressed),
+            other => Err(anyhow::anyhow!(
+                "Unknown turn_policy '{}': must be 'round_robin' or 'addressed'",
+                other
+            )),
+        }
+    }
+}
+
+/// Position of the first mention of `needle` in `haystack` as a whole word.
+/// Both are expected in lowercase.
+fn mention_position(haystack: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    haystack.match_indices(needle).find_map(|(start, _)| {
+        let end = start + needle.len();
+        let before_ok = haystack[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_word(c));
+        let after_ok = haystack[end..].chars().next().is_none_or(|c| !is_word(c));
+        (before_ok && after_ok).then_some(start)
+    })
+}
+
+/// Members named in `text`, in the order they are first named.
+#[must_use]
+pub fn addressed_members<'a>(members: &'a [AgentMetadata], text: &str) -> Vec<&'a AgentMetadata> {
+    let text = text.to_lowercase();
+    let mut named: Vec<(usize, &AgentMetadata)> = members
+        .iter()
+        .filter_map(|agent| {
+            let id = agent.id.to_lowercase();
+            let name = agent.name.to_lowercase();
+            [format!("@{}", id), format!("@{}", name), name]
+                .iter()
+                .filter_map(|needle| mention_position(&text, needle))
+                .min()
+                .map(|pos| (pos, agent))
+        })
+        .collect();
+    named.sort_by_key(|(pos, _)| *pos);
+    named.into_iter().map(|(_, agent)| agent).collect()
+}
+
+/// The first enabled member at or after `turn` in turn order.
+#[must_use]
+pub fn round_robin_member(members: &[AgentMetadata], turn: i64) -> Option<&AgentMetadata> {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    let start = turn.rem_euclid(members.len().max(1) as i64) as usize;
+    members
+        .iter()
+        .cycle()
+        .skip(start)
+        .take(members.len())
+        .find(|agent| agent.enabled)
+}
+
+/// The message a speaker receives: who is in the room, then the transcript.
+fn render_prompt(
+    room: &ChatRoomRow,
+    members: &[AgentMetadata],
+    speaker: &AgentMetadata,
+    transcript: &[ChatRoomMessageRow],
+) -> String {
+    let roster: Vec<String> = members
+        .iter()
+        .map(|agent| format!("{} ({})", agent.name, agent.id))
+        .collect();
+    let mut prompt = format!(
+        "[Group chat \"{}\" with the user and {}. You are {}. Reply to the latest message; \
+         do not speak for the others.]\n",
+        room.name,
+        roster.join(", "),
+        speaker.name
+    );
+    for msg in transcript {
+        let speaker_name = if msg.source == "user" {
+            format!("{} (user)", msg.speaker_name)
+        } else {
+            msg.speaker_name.clone()
+        };
+        let _ = writeln!(prompt, "{}: {}", speaker_name, msg.content);
+    }
+    prompt
+}
+
+/// A posted user message and the members chosen to answer it.
+#[derive(Debug, Clone)]
+pub struct PostedMessage {
+    pub message: ChatRoomMessageRow,
+    pub speakers: Vec<String>,
+}
+
+pub struct RoomManager {
+    pool: DbPool,
+    agent_manager: AgentManager,
+    event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+    events: broadcast::Sender<SerializedEvent>,
+    reply_timeout: Duration,
+    /// Serializes turns per room, so the transcript each speaker sees is complete.
+    floors: DashMap<String, Arc<Mutex<()>>>,
+}
+
+impl RoomManager {
+    #[must_use]
+    pub fn new(
+        pool: DbPool,
+        agent_manager: AgentManager,
+        event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+        events: broadcast::Sender<SerializedEvent>,
+    ) -> Self {
+        Self {
+            pool,
+            agent_manager,
+            event_tx,
+            events,
+            reply_timeout: super::channel_relay::reply_timeout(),
+            floors: DashMap::new(),
+        }
+    }
+
+    /// Create a room. Members answer in the order given.
+    pub async fn create(
+        &self,
+        name: &str,
+        agent_ids: &[String],
+        policy: TurnPolicy,
+    ) -> anyhow::Result<ChatRoomRow> {
+        let mut members: Vec<String> = Vec::with_capacity(agent_ids.len());
+        for id in agent_ids {
+            if !members.contains(id) {
+                members.push(id.clone());
+            }
+        }
+        if !(MIN_MEMBERS..=MAX_MEMBERS).contains(&members.len()) {
+            anyhow::bail!(
+                "A room needs between {} and {} distinct agents",
+                MIN_MEMBERS,
+                MAX_MEMBERS
+            );
+        }
+        for id in &members {
+            if self.agent_manager.get_agent_config(id).await.is_err() {
+                anyhow::bail!("Agent '{}' not found", id);
+            }
+        }
+
+        let room = ChatRoomRow {
+            id: format!("room.{}", cloto_shared::ClotoId::new()),
+            name: name.to_string(),
+            turn_policy: policy.as_str().to_string(),
+            next_turn: 0,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        db::create_chat_room(&self.pool, &room, &members).await?;
+        info!(room_id = %room.id, members = members.len(), "💬 Chat room created");
+        Ok(room)
+    }
+
+    pub async fn delete(&self, room_id: &str) -> anyhow::Result<bool> {
+        self.floors.remove(room_id);
+        db::delete_chat_room(&self.pool, room_id).await
+    }
+
+    async fn members(&self, room_id: &str) -> anyhow::Result<Vec<AgentMetadata>> {
+        let mut members = Vec::new();
+        for id in db::get_chat_room_members(&self.pool, room_id).await? {
+            let (agent, _) = self.agent_manager.get_agent_config(&id).await?;
+            members.push(agent);
+        }
+        Ok(members)
+    }
+
+    /// Append a user message to the room and start the members' turn in the
+    /// background. Returns once the speakers are chosen.
+    pub async fn post(
+        self: &Arc<Self>,
+        room: &ChatRoomRow,
+        user_id: &str,
+        user_name: &str,
+        content: &str,
+    ) -> anyhow::Result<PostedMessage> {
+        let members = self.members(&room.id).await?;
+        let policy: TurnPolicy = room.turn_policy.parse()?;
+
+        let mut speakers: Vec<AgentMetadata> = if policy == TurnPolicy::Addressed {
+            addressed_members(&members, content)
+                .into_iter()
+                .filter(|agent| agent.enabled)
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if speakers.is_empty() {
+            let turn = db::take_chat_room_turn(&self.pool, &room.id).await?;
+            let agent = round_robin_member(&members, turn)
+                .ok_or_else(|| anyhow::anyhow!("Every agent in this room is powered off"))?;
+            speakers.push(agent.clone());
+        }
+
+        let message = ChatRoomMessageRow {
+            id: cloto_shared::ClotoId::new().to_string(),
+            room_id: room.id.clone(),
+            source: "user".to_string(),
+            speaker_id: user_id.to_string(),
+            speaker_name: user_name.to_string(),
+            content: content.to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        db::save_chat_room_message(&self.pool, &message).await?;
+
+        let posted = PostedMessage {
+            message,
+            speakers: speakers.iter().map(|agent| agent.id.clone()).collect(),
+        };
+        let manager = self.clone();
+        let room = room.clone();
+        let user = (user_id.to_string(), user_name.to_string());
+        tokio::spawn(async move {
+            manager.run_turn(&room, &members, &speakers, &user).await;
+        });
+        Ok(posted)
+    }
+
+    /// Let each speaker answer in order, appending every answer to the transcript.
+    async fn run_turn(
+        &self,
+        room: &ChatRoomRow,
+        members: &[AgentMetadata],
+        speakers: &[AgentMetadata],
+        (user_id, user_name): &(String, String),
+    ) {
+        let floor = self.floors.entry(room.id.clone()).or_default().clone();
+        let _floor = floor.lock().await;
+
+        for speaker in speakers {
+            let (source, content) = match self.ask(room, members, speaker, user_id, user_name).await
+            {
+                Ok(content) => ("agent", content),
+                Err(e) => {
+                    warn!(room_id = %room.id, agent_id = %speaker.id, error = %e, "Room speaker did not answer");
+                    ("system", format!("{} did not answer: {}", speaker.name, e))
+                }
+            };
+            let reply = ChatRoomMessageRow {
+                id: cloto_shared::ClotoId::new().to_string(),
+                room_id: room.id.clone(),
+                source: source.to_string(),
+                speaker_id: speaker.id.clone(),
+                speaker_name: speaker.name.clone(),
+                content,
+                created_at: chrono::Utc::now().timestamp_millis(),
+            };
+            if let Err(e) = db::save_chat_room_message(&self.pool, &reply).await {
+                warn!(room_id = %room.id, error = %e, "Failed to save room message");
+            }
+        }
+    }
+
+    /// Send the transcript to one speaker and wait for its answer.
+    async fn ask(
+        &self,
+        room: &ChatRoomRow,
+        members: &[AgentMetadata],
+        speaker: &AgentMetadata,
+        user_id: &str,
+        user_name: &str,
+    ) -> anyhow::Result<String> {
+        let mut transcript =
+            db::get_chat_room_messages(&self.pool, &room.id, None, TRANSCRIPT_CONTEXT).await?;
+        transcript.reverse();
+
+        let mut msg = ClotoMessage::new(
+            MessageSource::User {
+                id: user_id.to_string(),
+                name: user_name.to_string(),
+            },
+            render_prompt(room, members, speaker, &transcript),
+        );
+        msg.target_agent = Some(speaker.id.clone());
+        msg.metadata = HashMap::from([
+            ("target_agent_id".to_string(), speaker.id.clone()),
+            ("room_id".to_string(), room.id.clone()),
+        ]);
+        let message_id = msg.id.clone();
+
+        // Subscribe before publishing so a fast answer is not missed
+        let mut events = self.events.subscribe();
+        self.event_tx
+            .send(crate::EnvelopedEvent {
+                event: Arc::new(ClotoEvent::new(ClotoEventData::MessageReceived(msg))),
+                issuer: None,
+                correlation_id: None,
+                depth: 0,
+            })
+            .await
+            .map_err(|_| anyhow::anyhow!("Event bus closed"))?;
+
+        let reply = tokio::time::timeout(
+            self.reply_timeout,
+            crate::events::wait_for_thought_response(&mut events, &message_id),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("no response within {}s", self.reply_timeout.as_secs()))?
+        .ok_or_else(|| anyhow::anyhow!("Event bus closed"))?;
+        Ok(reply.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(id: &str, name: &str, enabled: bool) -> AgentMetadata {
+        AgentMetadata {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            enabled,
+            last_seen: 0,
+            status: "online".to_string(),
+            default_engine_id: None,
+            required_capabilities: vec![],
+            metadata: HashMap::new(),
+            system_prompt: None,
+        }
+    }
+
+    #[test]
+    fn test_addressed_members_in_mention_order() {
+        let members = vec![
+            agent("agent.alice", "Alice", true),
+            agent("agent.bob", "Bob", true),
+            agent("agent.carol", "Carol", true),
+        ];
+        let ids = |text: &str| -> Vec<String> {
+            addressed_members(&members, text)
+                .into_iter()
+                .map(|a| a.id.clone())
+                .collect()
+        };
+
+        assert_eq!(ids("bob, then @agent.alice?"), ["agent.bob", "agent.alice"]);
+        assert_eq!(ids("@Carol what do you think"), ["agent.carol"]);
+        // Whole words only
+        assert!(ids("bobby and alicexyz").is_empty());
+        assert!(ids("nobody named").is_empty());
+    }
+
+    #[test]
+    fn test_round_robin_skips_powered_off_members() {
+        let members = vec![
+            agent("agent.a", "A", true),
+            agent("agent.b", "B", false),
+            agent("agent.c", "C", true),
+        ];
+        assert_eq!(round_robin_member(&members, 0).unwrap().id, "agent.a");
+        assert_eq!(round_robin_member(&members, 1).unwrap().id, "agent.c");
+        assert_eq!(round_robin_member(&members, 5).unwrap().id, "agent.c");
+        assert_eq!(round_robin_member(&members, 3).unwrap().id, "agent.a");
+
+        let off = vec![agent("agent.b", "B", false)];
+        assert!(round_robin_member(&off, 0).is_none());
+    }
+
+    #[test]
+    fn test_turn_policy_parse() {
+        assert_eq!(
+            "addressed".parse::<TurnPolicy>().unwrap(),
+            TurnPolicy::Addressed
+        );
+        assert!("loudest".parse::<TurnPolicy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_addressed_turn_builds_merged_transcript() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        crate::db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        for (id, name) in [("agent.alice", "Alice"), ("agent.bob", "Bob")] {
+            sqlx::query(
+                "INSERT INTO agents (id, name, description, status, default_engine_id, metadata) \
+                 VALUES ($1, $2, '', 'online', 'mind.test', '{}')",
+            )
+            .bind(id)
+            .bind(name)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let (event_tx, mut event_rx) = mpsc::channel(4);
+        let (events, _) = broadcast::channel(16);
+        let rooms = Arc::new(RoomManager::new(
+            pool.clone(),
+            AgentManager::new(pool.clone()),
+            event_tx,
+            events.clone(),
+        ));
+        let room = rooms
+            .create(
+                "Planning",
+                &["agent.alice".to_string(), "agent.bob".to_string()],
+                TurnPolicy::Addressed,
+            )
+            .await
+            .unwrap();
+
+        let posted = rooms
+            .post(&room, "user-1", "Ann", "@bob then alice: status?")
+            .await
+            .unwrap();
+        assert_eq!(posted.speakers, ["agent.bob", "agent.alice"]);
+
+        // Each speaker is asked in turn and sees the replies before it
+        for (agent_id, answer) in [("agent.bob", "on track"), ("agent.alice", "agreed")] {
+            let envelope = event_rx.recv().await.unwrap();
+            let ClotoEventData::MessageReceived(ref msg) = envelope.event.data else {
+                panic!("expected MessageReceived");
+            };
+            assert_eq!(msg.metadata["target_agent_id"], agent_id);
+            assert_eq!(msg.metadata["room_id"], room.id);
+            assert!(msg.content.contains("Ann (user): @bob then alice: status?"));
+            if agent_id == "agent.alice" {
+                assert!(msg.content.contains("Bob: on track"));
+            }
+            events
+                .send(
+                    Arc::new(ClotoEvent::new(ClotoEventData::ThoughtResponse {
+                        agent_id: agent_id.to_string(),
+                        engine_id: "mind.test".to_string(),
+                        content: answer.to_string(),
+                        source_message_id: msg.id.clone(),
+                        metadata: None,
+                    }))
+                    .into(),
+                )
+                .unwrap();
+        }
+
+        let transcript = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let messages = db::get_chat_room_messages(&pool, &room.id, None, 10)
+                    .await
+                    .unwrap();
+                if messages.len() == 3 {
+                    return messages;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let speakers: Vec<(&str, &str)> = transcript
+            .iter()
+            .rev()
+            .map(|m| (m.source.as_str(), m.speaker_id.as_str()))
+            .collect();
+        assert_eq!(
+            speakers,
+            [
+                ("user", "user-1"),
+                ("agent", "agent.bob"),
+                ("agent", "agent.alice")
+            ]
+        );
+    }
+}
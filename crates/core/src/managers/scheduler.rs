@@ -3,23 +3,109 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use chrono::Utc;
-use sqlx::SqlitePool;
-use tokio::sync::{mpsc, Notify};
+use crate::db::DbPool;
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use tokio::sync::{broadcast, mpsc, Notify};
 use tracing::{debug, error, info, warn};
 
+use cloto_shared::schedule::{parse_cron, MIN_INTERVAL_SECS};
 use cloto_shared::{ClotoEvent, ClotoEventData, ClotoId, ClotoMessage, MessageSource};
 
 use crate::db::{self, CronJobRow};
+use crate::events::SerializedEvent;
 use crate::EnvelopedEvent;
 
+/// Runs without an agent response after this long are marked `timeout`.
+const RUN_TIMEOUT_MS: i64 = 30 * 60 * 1000;
+
+/// Prefix of the ThoughtResponse content sent when the agentic loop fails (H-04).
+const ERROR_RESPONSE_PREFIX: &str = "[Error]";
+
+/// Upper bound on catch-up runs dispatched for one job under `run_all`.
+const MAX_CATCH_UP_RUNS: usize = 10;
+
+/// Upper bound on per-job start jitter.
+pub const MAX_JITTER_SECS: i64 = 3600;
+
+/// Occurrences listed in a schedule preview.
+pub const PREVIEW_RUNS: usize = 5;
+
+/// What to do with occurrences missed while the kernel was down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MisfirePolicy {
+    /// Run once, then resume the normal schedule (default).
+    RunOnce,
+    /// Drop missed occurrences and wait for the next one.
+    Skip,
+    /// Run every missed occurrence (capped at `MAX_CATCH_UP_RUNS`).
+    RunAll,
+}
+
+impl FromStr for MisfirePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "run_once" => Ok(Self::RunOnce),
+            "skip" => Ok(Self::Skip),
+            "run_all" => Ok(Self::RunAll),
+            other => Err(anyhow::anyhow!(
+                "Unknown misfire_policy '{}': must be 'run_once', 'skip', or 'run_all'",
+                other
+            )),
+        }
+    }
+}
+
+/// Upper bound on the upstream output appended to a chained job's message.
+const MAX_UPSTREAM_OUTPUT_CHARS: usize = 8000;
+
+/// Upper bound on the number of skip dates stored per job.
+pub const MAX_SKIP_DATES: usize = 1000;
+
+/// Which outcome of the upstream job triggers a chained (`after`) job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainOn {
+    Success,
+    Failure,
+    Always,
+}
+
+impl ChainOn {
+    fn matches(self, succeeded: bool) -> bool {
+        match self {
+            Self::Success => succeeded,
+            Self::Failure => !succeeded,
+            Self::Always => true,
+        }
+    }
+}
+
+impl FromStr for ChainOn {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "success" => Ok(Self::Success),
+            "failure" => Ok(Self::Failure),
+            "always" => Ok(Self::Always),
+            other => Err(anyhow::anyhow!(
+                "Unknown chain_on '{}': must be 'success', 'failure', or 'always'",
+                other
+            )),
+        }
+    }
+}
+
 /// Spawn the cron scheduler background task.
 ///
 /// Every `check_interval_secs` seconds, queries `cron_jobs` for due jobs
 /// and dispatches them as `MessageReceived` events through the existing
-/// agentic loop pipeline.
+/// agentic loop pipeline. The runs it records are completed by
+/// [`spawn_run_tracker`].
 pub fn spawn_cron_task(
-    pool: SqlitePool,
+    pool: DbPool,
     event_tx: mpsc::Sender<EnvelopedEvent>,
     check_interval_secs: u64,
     shutdown: Arc<Notify>,
@@ -38,7 +124,7 @@ pub fn spawn_cron_task(
                     break;
                 }
                 _ = interval.tick() => {
-                    if let Err(e) = tick(&pool, &event_tx).await {
+                    if let Err(e) = tick(&pool, &event_tx, check_interval_secs).await {
                         error!("Cron scheduler tick error: {}", e);
                     }
                 }
@@ -47,7 +133,303 @@ pub fn spawn_cron_task(
     });
 }
 
-async fn tick(pool: &SqlitePool, event_tx: &mpsc::Sender<EnvelopedEvent>) -> anyhow::Result<()> {
+/// Complete `cron_job_runs` rows when the agent answers the dispatched
+/// message, and every `check_interval_secs` expire those left unanswered,
+/// triggering jobs chained after either. Started even with the scheduler
+/// disabled, since manual runs are recorded too.
+pub fn spawn_run_tracker(
+    pool: DbPool,
+    event_tx: mpsc::Sender<EnvelopedEvent>,
+    mut events: broadcast::Receiver<SerializedEvent>,
+    check_interval_secs: u64,
+    shutdown: Arc<Notify>,
+) {
+    tokio::spawn(async move {
+        let mut sweep = tokio::time::interval(Duration::from_secs(check_interval_secs));
+        loop {
+            let event = tokio::select! {
+                () = shutdown.notified() => break,
+                _ = sweep.tick() => {
+                    if let Err(e) = expire_runs(&pool, &event_tx).await {
+                        error!("Cron run tracker: failed to expire runs: {}", e);
+                    }
+                    continue;
+                }
+                received = events.recv() => match received {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Cron run tracker lagged, {} events skipped", n);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            let ClotoEventData::ThoughtResponse {
+                ref agent_id,
+                ref content,
+                ref source_message_id,
+                ..
+            } = event.data
+            else {
+                continue;
+            };
+
+            let succeeded = !content.starts_with(ERROR_RESPONSE_PREFIX);
+            let (status, error) = if succeeded {
+                ("success", None)
+            } else {
+                ("error", Some(content.as_str()))
+            };
+            let response_id = format!("{}-resp", source_message_id);
+            let result = db::finish_cron_job_run(
+                &pool,
+                source_message_id,
+                status,
+                Some(&response_id),
+                error,
+                Utc::now().timestamp_millis(),
+            )
+            .await;
+            match result {
+                Ok(Some(job_id)) => {
+                    save_chat_history(
+                        &pool,
+                        &response_id,
+                        agent_id,
+                        "agent",
+                        content,
+                        serde_json::json!({ "cron_job_id": job_id, "cron_status": status }),
+                    )
+                    .await;
+                    trigger_chained_jobs(&pool, &event_tx, &job_id, succeeded, Some(content)).await;
+                }
+                Ok(None) => {}
+                Err(e) => error!("Cron run tracker: failed to record completion: {}", e),
+            }
+        }
+    });
+}
+
+/// Persist a cron message into the agent's chat history (user `default`, the
+/// dashboard's user) so autonomous runs can be reviewed alongside regular
+/// conversations. Best-effort: failures are logged, never propagated.
+async fn save_chat_history(
+    pool: &DbPool,
+    id: &str,
+    agent_id: &str,
+    source: &str,
+    text: &str,
+    metadata: serde_json::Value,
+) {
+    let msg = db::ChatMessageRow {
+        id: id.to_string(),
+        agent_id: agent_id.to_string(),
+        user_id: "default".to_string(),
+        source: source.to_string(),
+        content: serde_json::json!([{ "type": "text", "text": text }]).to_string(),
+        metadata: Some(metadata.to_string()),
+        created_at: Utc::now().timestamp_millis(),
+        session_id: None,
+    };
+    if let Err(e) = db::save_chat_message(pool, &msg).await {
+        warn!(message_id = %id, "Failed to save cron message to chat history: {}", e);
+    }
+}
+
+/// Dispatch the enabled jobs chained after `upstream_job_id` whose `chain_on`
+/// matches the upstream outcome. `output` (the agent's reply or error) is
+/// passed along in the chained job's message.
+///
+/// Chains cannot form cycles: a job can only be chained after a job that
+/// already exists, and jobs are immutable once created.
+async fn trigger_chained_jobs(
+    pool: &DbPool,
+    event_tx: &mpsc::Sender<EnvelopedEvent>,
+    upstream_job_id: &str,
+    succeeded: bool,
+    output: Option<&str>,
+) {
+    let chained = match db::list_chained_cron_jobs(pool, upstream_job_id).await {
+        Ok(chained) => chained,
+        Err(e) => {
+            error!(
+                "Cron scheduler: failed to load jobs chained after '{}': {}",
+                upstream_job_id, e
+            );
+            return;
+        }
+    };
+
+    for job in chained {
+        let chain_on = job.chain_on.parse::<ChainOn>().unwrap_or(ChainOn::Success);
+        if !chain_on.matches(succeeded) {
+            continue;
+        }
+        let now_ms = Utc::now().timestamp_millis();
+        if calendar_blocks(&job, now_ms) {
+            db::update_cron_job_run(
+                pool,
+                &job.id,
+                now_ms,
+                "skipped",
+                None,
+                job.next_run_at,
+                job.enabled,
+            )
+            .await
+            .ok();
+            continue;
+        }
+        let (status, error) = match dispatch_job(pool, event_tx, &job, "chain", output).await {
+            Ok(_) => {
+                info!(
+                    job_id = %job.id,
+                    upstream = %upstream_job_id,
+                    "Chained cron job dispatched"
+                );
+                ("success", None)
+            }
+            Err(e) => {
+                error!(
+                    "Cron scheduler: failed to dispatch chained job '{}': {}",
+                    job.id, e
+                );
+                ("error", Some(e.to_string()))
+            }
+        };
+        db::update_cron_job_run(
+            pool,
+            &job.id,
+            now_ms,
+            status,
+            error.as_deref(),
+            job.next_run_at,
+            job.enabled,
+        )
+        .await
+        .ok();
+    }
+}
+
+/// Dispatch a job's message to its agent and record the run.
+/// `trigger` is `scheduler`, `manual` or `chain`; for chained runs
+/// `upstream_output` is appended to the job's message. Returns the dispatched
+/// message ID.
+pub async fn dispatch_job(
+    pool: &DbPool,
+    event_tx: &mpsc::Sender<EnvelopedEvent>,
+    job: &CronJobRow,
+    trigger: &str,
+    upstream_output: Option<&str>,
+) -> anyhow::Result<String> {
+    // Build a synthetic ClotoMessage to feed into the existing agentic loop
+    let mut metadata = HashMap::new();
+    metadata.insert("target_agent_id".into(), job.agent_id.clone());
+    metadata.insert("cron_job_id".into(), job.id.clone());
+    metadata.insert("cron_source".into(), trigger.into());
+    if let Some(ref engine_id) = job.engine_id {
+        metadata.insert("engine_override".into(), engine_id.clone());
+    }
+    if let Some(max_iter) = job.max_iterations {
+        metadata.insert("max_iterations_override".into(), max_iter.to_string());
+    }
+
+    let msg = ClotoMessage {
+        id: ClotoId::new().to_string(),
+        source: MessageSource::System,
+        target_agent: Some(job.agent_id.clone()),
+        content: chained_message(job, upstream_output),
+        timestamp: Utc::now(),
+        metadata,
+        attachments: Vec::new(),
+    };
+    let message_id = msg.id.clone();
+
+    // Record before dispatch so a fast response cannot race the insert
+    db::insert_cron_job_run(
+        pool,
+        &job.id,
+        trigger,
+        &message_id,
+        Utc::now().timestamp_millis(),
+    )
+    .await?;
+    save_chat_history(
+        pool,
+        &message_id,
+        &job.agent_id,
+        "system",
+        &msg.content,
+        serde_json::json!({
+            "cron_job_id": job.id,
+            "cron_job_name": job.name,
+            "cron_trigger": trigger,
+        }),
+    )
+    .await;
+
+    let envelope = EnvelopedEvent {
+        event: Arc::new(ClotoEvent::new(ClotoEventData::MessageReceived(msg))),
+        issuer: None,
+        correlation_id: None,
+        depth: 0,
+    };
+
+    if let Err(e) = event_tx.send(envelope).await {
+        let error = e.to_string();
+        db::finish_cron_job_run(
+            pool,
+            &message_id,
+            "error",
+            None,
+            Some(&error),
+            Utc::now().timestamp_millis(),
+        )
+        .await
+        .ok();
+        return Err(anyhow::anyhow!("failed to dispatch: {}", error));
+    }
+    Ok(message_id)
+}
+
+/// The job's message, followed by the upstream job's output for chained runs.
+fn chained_message(job: &CronJobRow, upstream_output: Option<&str>) -> String {
+    match upstream_output {
+        Some(output) if job.schedule_type == "after" => {
+            let mut preview: String = output.chars().take(MAX_UPSTREAM_OUTPUT_CHARS).collect();
+            if output.chars().count() > MAX_UPSTREAM_OUTPUT_CHARS {
+                preview.push('…');
+            }
+            format!(
+                "{}\n\n---\nOutput of the previous step ({}):\n{}",
+                job.message, job.schedule_value, preview
+            )
+        }
+        _ => job.message.clone(),
+    }
+}
+
+/// Fail runs that got no response within `RUN_TIMEOUT_MS`.
+async fn expire_runs(pool: &DbPool, event_tx: &mpsc::Sender<EnvelopedEvent>) -> anyhow::Result<()> {
+    let now_ms = Utc::now().timestamp_millis();
+    let expired = db::expire_cron_job_runs(pool, now_ms - RUN_TIMEOUT_MS, now_ms).await?;
+    if !expired.is_empty() {
+        warn!(
+            "Cron scheduler: {} run(s) timed out without a response",
+            expired.len()
+        );
+    }
+    for job_id in &expired {
+        trigger_chained_jobs(pool, event_tx, job_id, false, None).await;
+    }
+    Ok(())
+}
+
+async fn tick(
+    pool: &DbPool,
+    event_tx: &mpsc::Sender<EnvelopedEvent>,
+    check_interval_secs: u64,
+) -> anyhow::Result<()> {
     let now_ms = Utc::now().timestamp_millis();
     let due_jobs = db::get_due_cron_jobs(pool, now_ms).await?;
 
@@ -57,36 +439,60 @@ async fn tick(pool: &SqlitePool, event_tx: &mpsc::Sender<EnvelopedEvent>) -> any
 
     debug!("Cron scheduler: {} due job(s)", due_jobs.len());
 
+    // A job overdue by more than two ticks was missed (kernel down or stalled)
+    let grace_ms = i64::try_from(check_interval_secs.saturating_mul(2000)).unwrap_or(i64::MAX);
+
     for job in &due_jobs {
-        // Build a synthetic ClotoMessage to feed into the existing agentic loop
-        let mut metadata = HashMap::new();
-        metadata.insert("target_agent_id".into(), job.agent_id.clone());
-        metadata.insert("cron_job_id".into(), job.id.clone());
-        metadata.insert("cron_source".into(), "scheduler".into());
-        if let Some(ref engine_id) = job.engine_id {
-            metadata.insert("engine_override".into(), engine_id.clone());
-        }
-        if let Some(max_iter) = job.max_iterations {
-            metadata.insert("max_iterations_override".into(), max_iter.to_string());
-        }
-
-        let msg = ClotoMessage {
-            id: ClotoId::new().to_string(),
-            source: MessageSource::System,
-            target_agent: Some(job.agent_id.clone()),
-            content: job.message.clone(),
-            timestamp: Utc::now(),
-            metadata,
+        let runs = if calendar_blocks(job, now_ms) {
+            debug!(job_id = %job.id, "Cron job skipped by calendar modifier");
+            0
+        } else if now_ms - job.next_run_at <= grace_ms {
+            1
+        } else {
+            let policy = job
+                .misfire_policy
+                .parse::<MisfirePolicy>()
+                .unwrap_or(MisfirePolicy::RunOnce);
+            let runs = match policy {
+                MisfirePolicy::RunOnce => 1,
+                MisfirePolicy::Skip => 0,
+                MisfirePolicy::RunAll => missed_occurrences(job, now_ms).min(MAX_CATCH_UP_RUNS),
+            };
+            info!(
+                job_id = %job.id,
+                policy = ?policy,
+                overdue_secs = (now_ms - job.next_run_at) / 1000,
+                runs,
+                "Cron job misfired"
+            );
+            runs
         };
 
-        let envelope = EnvelopedEvent {
-            event: Arc::new(ClotoEvent::new(ClotoEventData::MessageReceived(msg))),
-            issuer: None,
-            correlation_id: None,
-            depth: 0,
-        };
+        let (next_run, still_enabled) = calculate_next_run(job, now_ms);
+
+        if runs == 0 {
+            db::update_cron_job_run(
+                pool,
+                &job.id,
+                now_ms,
+                "skipped",
+                None,
+                next_run,
+                still_enabled,
+            )
+            .await
+            .ok();
+            continue;
+        }
 
-        if let Err(e) = event_tx.send(envelope).await {
+        let mut dispatch_error = None;
+        for _ in 0..runs {
+            if let Err(e) = dispatch_job(pool, event_tx, job, "scheduler", None).await {
+                dispatch_error = Some(e);
+                break;
+            }
+        }
+        if let Some(e) = dispatch_error {
             error!("Cron scheduler: failed to dispatch job '{}': {}", job.id, e);
             db::update_cron_job_run(
                 pool,
@@ -109,8 +515,6 @@ async fn tick(pool: &SqlitePool, event_tx: &mpsc::Sender<EnvelopedEvent>) -> any
             "Cron job dispatched"
         );
 
-        // Calculate next run and update job status
-        let (next_run, still_enabled) = calculate_next_run(job, now_ms);
         db::update_cron_job_run(
             pool,
             &job.id,
@@ -127,9 +531,173 @@ async fn tick(pool: &SqlitePool, event_tx: &mpsc::Sender<EnvelopedEvent>) -> any
     Ok(())
 }
 
-/// Calculate the next run time for a cron job.
+/// Parse a job's `timezone` (IANA name, e.g. `Asia/Tokyo`); `None` is UTC.
+pub fn parse_timezone(timezone: Option<&str>) -> anyhow::Result<Tz> {
+    match timezone.map(str::trim).filter(|tz| !tz.is_empty()) {
+        None => Ok(Tz::UTC),
+        Some(name) => name.parse::<Tz>().map_err(|_| {
+            anyhow::anyhow!(
+                "Unknown timezone '{}': expected an IANA name such as Asia/Tokyo",
+                name
+            )
+        }),
+    }
+}
+
+fn job_timezone(job: &CronJobRow) -> Tz {
+    parse_timezone(job.timezone.as_deref()).unwrap_or_else(|e| {
+        warn!(job_id = %job.id, error = %e, "Invalid cron job timezone, using UTC");
+        Tz::UTC
+    })
+}
+
+/// The next `count` occurrences of a schedule after now, in `timezone`.
+/// Interval and one-shot schedules yield their next run only.
+#[must_use]
+pub fn preview_runs(
+    schedule_type: &str,
+    schedule_value: &str,
+    timezone: Tz,
+    first_run_ms: i64,
+    count: usize,
+) -> Vec<chrono::DateTime<Tz>> {
+    let at =
+        |ms: i64| chrono::DateTime::from_timestamp_millis(ms).map(|t| t.with_timezone(&timezone));
+    match schedule_type {
+        "cron" => parse_cron(schedule_value)
+            .map(|schedule| schedule.upcoming(timezone).take(count).collect())
+            .unwrap_or_default(),
+        "interval" => {
+            let interval_ms = schedule_value.parse::<i64>().unwrap_or(3600) * 1000;
+            (0..count)
+                .scan(first_run_ms, |run_ms, _| {
+                    let current = *run_ms;
+                    *run_ms += interval_ms;
+                    Some(current)
+                })
+                .filter_map(at)
+                .collect()
+        }
+        "once" => at(first_run_ms).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether the job's calendar modifiers (weekdays only, skip dates) block
+/// an occurrence at `at_ms`. Days are evaluated in the job's timezone.
+fn calendar_blocks(job: &CronJobRow, at_ms: i64) -> bool {
+    let Some(at) = chrono::DateTime::from_timestamp_millis(at_ms) else {
+        return false;
+    };
+    let date = at.with_timezone(&job_timezone(job)).date_naive();
+    if job.weekdays_only && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        return true;
+    }
+    let date = date.format("%Y-%m-%d").to_string();
+    job.skip_dates.split(',').any(|d| d.trim() == date)
+}
+
+/// Dates of the events in an iCalendar document (e.g. a holiday calendar
+/// export), taken from each `DTSTART` property.
+#[must_use]
+pub fn ics_dates(ics: &str) -> Vec<NaiveDate> {
+    ics.lines()
+        .filter(|line| line.starts_with("DTSTART"))
+        .filter_map(|line| {
+            // DTSTART;VALUE=DATE:20261225 or DTSTART:20261225T090000Z
+            let value = line.rsplit(':').next()?.trim();
+            NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+        })
+        .collect()
+}
+
+/// Validate `YYYY-MM-DD` skip dates and merge them with the dates of an
+/// optional iCalendar document into the stored (sorted, comma-separated) form.
+pub fn build_skip_dates(dates: &[&str], ics: Option<&str>) -> anyhow::Result<String> {
+    let mut parsed = dates
+        .iter()
+        .map(|d| {
+            NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid skip date '{}': expected YYYY-MM-DD", d))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if let Some(ics) = ics {
+        let from_ics = ics_dates(ics);
+        if from_ics.is_empty() {
+            return Err(anyhow::anyhow!("skip_ics contains no DTSTART dates"));
+        }
+        parsed.extend(from_ics);
+    }
+    parsed.sort_unstable();
+    parsed.dedup();
+    if parsed.len() > MAX_SKIP_DATES {
+        return Err(anyhow::anyhow!(
+            "Too many skip dates: {} (max {})",
+            parsed.len(),
+            MAX_SKIP_DATES
+        ));
+    }
+    Ok(parsed
+        .iter()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// Number of occurrences between `job.next_run_at` and `now_ms` (inclusive),
+/// capped at `MAX_CATCH_UP_RUNS`.
+fn missed_occurrences(job: &CronJobRow, now_ms: i64) -> usize {
+    if now_ms < job.next_run_at {
+        return 0;
+    }
+    match job.schedule_type.as_str() {
+        "interval" => {
+            let interval_ms = job
+                .schedule_value
+                .parse::<i64>()
+                .unwrap_or(3600)
+                .max(1)
+                .saturating_mul(1000);
+            usize::try_from((now_ms - job.next_run_at) / interval_ms + 1)
+                .unwrap_or(MAX_CATCH_UP_RUNS)
+                .min(MAX_CATCH_UP_RUNS)
+        }
+        "cron" => {
+            let (Ok(schedule), Some(from)) = (
+                parse_cron(&job.schedule_value),
+                chrono::DateTime::from_timestamp_millis(job.next_run_at),
+            ) else {
+                return 1;
+            };
+            1 + schedule
+                .after(&from.with_timezone(&job_timezone(job)))
+                .take_while(|t| t.timestamp_millis() <= now_ms)
+                .take(MAX_CATCH_UP_RUNS - 1)
+                .count()
+        }
+        _ => 1,
+    }
+}
+
+/// Delay `next_ms` by a random 0..=`jitter_secs` seconds.
+#[must_use]
+pub fn apply_jitter(next_ms: i64, jitter_secs: i64) -> i64 {
+    use rand::Rng;
+    if jitter_secs <= 0 || next_ms == i64::MAX {
+        return next_ms;
+    }
+    let jitter_ms = jitter_secs.min(MAX_JITTER_SECS) * 1000;
+    next_ms.saturating_add(rand::thread_rng().gen_range(0..=jitter_ms))
+}
+
+/// Calculate the next run time for a cron job, including its jitter.
 /// Returns (next_run_at_ms, enabled).
 fn calculate_next_run(job: &CronJobRow, now_ms: i64) -> (i64, bool) {
+    let (next, enabled) = calculate_scheduled_run(job, now_ms);
+    (apply_jitter(next, job.jitter_secs), enabled)
+}
+
+fn calculate_scheduled_run(job: &CronJobRow, now_ms: i64) -> (i64, bool) {
     match job.schedule_type.as_str() {
         "interval" => {
             let interval_secs: u64 = job.schedule_value.parse().unwrap_or(3600);
@@ -140,14 +708,17 @@ fn calculate_next_run(job: &CronJobRow, now_ms: i64) -> (i64, bool) {
             // One-shot: disable after execution
             (i64::MAX, false)
         }
-        "cron" => match cron::Schedule::from_str(&job.schedule_value) {
-            Ok(schedule) => match schedule.upcoming(Utc).next() {
-                Some(next_time) => (next_time.timestamp_millis(), true),
-                None => {
+        // Chained: only ever triggered by the upstream job
+        "after" => (i64::MAX, true),
+        "cron" => match parse_cron(&job.schedule_value) {
+            Ok(schedule) => {
+                if let Some(next_time) = schedule.upcoming(job_timezone(job)).next() {
+                    (next_time.timestamp_millis(), true)
+                } else {
                     warn!(job_id = %job.id, "Cron expression has no future occurrences");
                     (i64::MAX, false)
                 }
-            },
+            }
             Err(e) => {
                 error!(job_id = %job.id, error = %e, "Invalid cron expression: {}", job.schedule_value);
                 (i64::MAX, false)
@@ -164,6 +735,7 @@ fn calculate_next_run(job: &CronJobRow, now_ms: i64) -> (i64, bool) {
 pub fn calculate_initial_next_run(
     schedule_type: &str,
     schedule_value: &str,
+    timezone: Tz,
 ) -> anyhow::Result<i64> {
     let now_ms = Utc::now().timestamp_millis();
     match schedule_type {
@@ -171,8 +743,11 @@ pub fn calculate_initial_next_run(
             let interval_secs: u64 = schedule_value
                 .parse()
                 .map_err(|_| anyhow::anyhow!("Invalid interval: must be seconds (integer)"))?;
-            if interval_secs < 60 {
-                return Err(anyhow::anyhow!("Minimum interval is 60 seconds"));
+            if interval_secs < MIN_INTERVAL_SECS {
+                return Err(anyhow::anyhow!(
+                    "Minimum interval is {} seconds",
+                    MIN_INTERVAL_SECS
+                ));
             }
             Ok(now_ms + (interval_secs as i64 * 1000))
         }
@@ -186,15 +761,223 @@ pub fn calculate_initial_next_run(
             Ok(target_ms)
         }
         "cron" => {
-            let schedule = cron::Schedule::from_str(schedule_value)
-                .map_err(|e| anyhow::anyhow!("Invalid cron expression: {}", e))?;
-            match schedule.upcoming(Utc).next() {
+            let schedule = parse_cron(schedule_value)?;
+            match schedule.upcoming(timezone).next() {
                 Some(next) => Ok(next.timestamp_millis()),
                 None => Err(anyhow::anyhow!("Cron expression has no future occurrences")),
             }
         }
+        // Never due; dispatched when the upstream job finishes
+        "after" => Ok(i64::MAX),
         _ => Err(anyhow::anyhow!(
-            "Unknown schedule_type: must be 'interval', 'cron', or 'once'"
+            "Unknown schedule_type: must be 'interval', 'cron', 'once', or 'after'"
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(schedule_type: &str, schedule_value: &str, next_run_at: i64) -> CronJobRow {
+        CronJobRow {
+            id: "cron.test".to_string(),
+            agent_id: "agent.test".to_string(),
+            name: "test".to_string(),
+            enabled: true,
+            schedule_type: schedule_type.to_string(),
+            schedule_value: schedule_value.to_string(),
+            engine_id: None,
+            message: "ping".to_string(),
+            next_run_at,
+            last_run_at: None,
+            last_status: None,
+            last_error: None,
+            max_iterations: None,
+            created_at: String::new(),
+            misfire_policy: "run_all".to_string(),
+            jitter_secs: 0,
+            chain_on: "success".to_string(),
+            weekdays_only: false,
+            skip_dates: String::new(),
+            timezone: None,
+        }
+    }
+
+    #[test]
+    fn test_missed_interval_occurrences() {
+        let hourly = job("interval", "3600", 0);
+        assert_eq!(missed_occurrences(&hourly, 0), 1);
+        assert_eq!(missed_occurrences(&hourly, 2 * 3_600_000 + 5), 3);
+        assert_eq!(
+            missed_occurrences(&hourly, 100 * 3_600_000),
+            MAX_CATCH_UP_RUNS
+        );
+    }
+
+    #[test]
+    fn test_missed_cron_occurrences() {
+        // Every minute at second 0; due at 00:00, now 00:03:30 → 00:00..00:03
+        let every_minute = job("cron", "0 * * * * *", 0);
+        assert_eq!(missed_occurrences(&every_minute, 210_000), 4);
+    }
+
+    #[test]
+    fn test_crontab_expression_in_timezone() {
+        use cloto_shared::schedule::crontab_days_of_week;
+        assert_eq!(crontab_days_of_week("1-5"), "MON-FRI");
+        assert_eq!(crontab_days_of_week("0,6/2,*"), "SUN,SAT/2,*");
+        assert!(parse_cron("0 9 * * * * * *").is_err());
+
+        // Every weekday at 09:00 JST, from Saturday 2026-03-07 12:00 UTC:
+        // Monday 09:00 JST is Monday 00:00 UTC
+        let tz = parse_timezone(Some("Asia/Tokyo")).unwrap();
+        let from = chrono::DateTime::from_timestamp_millis(1_772_884_800_000)
+            .unwrap()
+            .with_timezone(&tz);
+        let runs: Vec<String> = parse_cron("0 9 * * 1-5")
+            .unwrap()
+            .after(&from)
+            .take(2)
+            .map(|t| t.with_timezone(&Utc).to_rfc3339())
+            .collect();
+        assert_eq!(
+            runs,
+            ["2026-03-09T00:00:00+00:00", "2026-03-10T00:00:00+00:00"]
+        );
+
+        assert_eq!(parse_timezone(None).unwrap(), Tz::UTC);
+        assert!(parse_timezone(Some("JST+9")).is_err());
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        assert_eq!(apply_jitter(1_000, 0), 1_000);
+        assert_eq!(apply_jitter(i64::MAX, 60), i64::MAX);
+        for _ in 0..100 {
+            let next = apply_jitter(1_000, 5);
+            assert!((1_000..=6_000).contains(&next));
+        }
+    }
+
+    #[test]
+    fn test_chain_on_matching() {
+        assert!(ChainOn::Success.matches(true));
+        assert!(!ChainOn::Success.matches(false));
+        assert!(ChainOn::Failure.matches(false));
+        assert!(ChainOn::Always.matches(false));
+        assert!("sometimes".parse::<ChainOn>().is_err());
+    }
+
+    #[test]
+    fn test_chained_message_includes_upstream_output() {
+        let summarize = job("after", "cron.collect", i64::MAX);
+        assert_eq!(chained_message(&summarize, None), "ping");
+        assert_eq!(
+            chained_message(&summarize, Some("42 items")),
+            "ping\n\n---\nOutput of the previous step (cron.collect):\n42 items"
+        );
+        // Output is only forwarded to chained jobs
+        let hourly = job("interval", "3600", 0);
+        assert_eq!(chained_message(&hourly, Some("42 items")), "ping");
+    }
+
+    #[test]
+    fn test_calendar_modifiers() {
+        // 2026-03-07 is a Saturday, 2026-03-09 a Monday (noon UTC)
+        let saturday = 1_772_884_800_000;
+        let monday = saturday + 2 * 86_400_000;
+        let mut daily = job("interval", "86400", 0);
+        assert!(!calendar_blocks(&daily, saturday));
+
+        daily.weekdays_only = true;
+        assert!(calendar_blocks(&daily, saturday));
+        assert!(!calendar_blocks(&daily, monday));
+
+        daily.skip_dates = "2026-03-01,2026-03-09".to_string();
+        assert!(calendar_blocks(&daily, monday));
+
+        // Sunday 20:00 UTC is already Monday in Tokyo
+        daily.skip_dates.clear();
+        let sunday_evening = monday - 16 * 3_600_000;
+        assert!(calendar_blocks(&daily, sunday_evening));
+        daily.timezone = Some("Asia/Tokyo".to_string());
+        assert!(!calendar_blocks(&daily, sunday_evening));
+    }
+
+    #[test]
+    fn test_build_skip_dates_from_ics() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261225\r\nSUMMARY:Christmas\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20260101T000000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            build_skip_dates(&["2026-12-25", "2026-05-05"], Some(ics)).unwrap(),
+            "2026-01-01,2026-05-05,2026-12-25"
+        );
+        assert!(build_skip_dates(&["12/25/2026"], None).is_err());
+        assert!(build_skip_dates(&[], Some("BEGIN:VCALENDAR")).is_err());
+    }
+
+    #[test]
+    fn test_misfire_policy_parsing() {
+        assert_eq!(
+            "skip".parse::<MisfirePolicy>().unwrap(),
+            MisfirePolicy::Skip
+        );
+        assert!("later".parse::<MisfirePolicy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_tracker_finishes_manual_runs() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let job = CronJobRow {
+            agent_id: "agent.cloto_default".to_string(),
+            ..job("interval", "3600", i64::MAX)
+        };
+        db::create_cron_job(&pool, &job).await.unwrap();
+        // Left over from before the kernel started: expired on the first sweep
+        db::insert_cron_job_run(&pool, &job.id, "manual", "msg-stale", 1_000)
+            .await
+            .unwrap();
+
+        // No scheduler task: only the tracker runs
+        let (event_tx, _event_rx) = mpsc::channel(8);
+        let (events, _) = broadcast::channel(8);
+        let shutdown = Arc::new(Notify::new());
+        spawn_run_tracker(
+            pool.clone(),
+            event_tx.clone(),
+            events.subscribe(),
+            60,
+            shutdown.clone(),
+        );
+        let message_id = dispatch_job(&pool, &event_tx, &job, "manual", None)
+            .await
+            .unwrap();
+        let _ = events.send(SerializedEvent::from(Arc::new(ClotoEvent::new(
+            ClotoEventData::ThoughtResponse {
+                agent_id: job.agent_id.clone(),
+                engine_id: "mind.test".to_string(),
+                content: "pong".to_string(),
+                source_message_id: message_id.clone(),
+                metadata: None,
+            },
+        ))));
+
+        let mut statuses = Vec::new();
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            statuses = db::list_cron_job_runs(&pool, &job.id, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|run| (run.message_id, run.status))
+                .collect();
+            if statuses.iter().all(|(_, status)| status != "running") {
+                break;
+            }
+        }
+        shutdown.notify_waiters();
+        assert!(statuses.contains(&(message_id, "success".to_string())));
+        assert!(statuses.contains(&("msg-stale".to_string(), "timeout".to_string())));
+    }
+}
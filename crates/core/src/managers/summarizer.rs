@@ -0,0 +1,368 @@
+//! Rolling summaries of long chat threads.
+//!
+//! Once a thread holds more than `CLOTO_SUMMARY_THRESHOLD` messages past its
+//! summary, the oldest of them are folded into the summary by the agent's own
+//! engine, leaving the newest half of the threshold verbatim. The summary is
+//! injected into the agent's context in place of the messages it covers.
+
+use super::{AgentManager, PluginRegistry};
+use crate::db::DbPool;
+use crate::db::{self, ChatMessageRow, ChatSummaryRow};
+use cloto_shared::{AgentMetadata, ClotoMessage, MessageSource};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// The thread the dashboard persists chat messages under.
+pub const DEFAULT_THREAD_USER: &str = "default";
+
+/// Messages folded into the summary per engine call.
+const MAX_FOLD_BATCH: i64 = 200;
+/// Per-message cap on the text handed to the engine.
+const MAX_MESSAGE_CHARS: usize = 2000;
+const SUMMARY_TIMEOUT: Duration = Duration::from_mins(2);
+
+pub struct ConversationSummarizer {
+    pool: DbPool,
+    registry: Arc<PluginRegistry>,
+    agent_manager: AgentManager,
+    /// Unsummarized messages a thread may hold; 0 disables summarization.
+    threshold: usize,
+    /// Threads with a summary being generated.
+    in_flight: Mutex<HashSet<(String, String)>>,
+}
+
+impl ConversationSummarizer {
+    #[must_use]
+    pub fn new(
+        pool: DbPool,
+        registry: Arc<PluginRegistry>,
+        agent_manager: AgentManager,
+        threshold: usize,
+    ) -> Self {
+        Self {
+            pool,
+            registry,
+            agent_manager,
+            threshold,
+            in_flight: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Replace the context messages a thread summary covers with the summary.
+    pub async fn apply_to_context(
+        &self,
+        agent_id: &str,
+        user_id: &str,
+        mut context: Vec<ClotoMessage>,
+    ) -> Vec<ClotoMessage> {
+        let summary = match db::get_chat_summary(&self.pool, agent_id, user_id).await {
+            Ok(Some(summary)) => summary,
+            Ok(None) => return context,
+            Err(e) => {
+                error!(agent_id = %agent_id, error = %e, "Failed to load chat summary");
+                return context;
+            }
+        };
+        context.retain(|m| m.timestamp.timestamp_millis() > summary.summarized_until);
+        let mut message = ClotoMessage::new(
+            MessageSource::System,
+            format!(
+                "Summary of the earlier conversation ({} messages):\n{}",
+                summary.message_count, summary.summary
+            ),
+        );
+        if let Some(ts) = chrono::DateTime::from_timestamp_millis(summary.summarized_until) {
+            message.timestamp = ts;
+        }
+        context.insert(0, message);
+        context
+    }
+
+    /// Check a thread after a new message and fold old messages in the background.
+    pub fn spawn_maybe_summarize(self: &Arc<Self>, agent_id: &str, user_id: &str) {
+        if self.threshold == 0 {
+            return;
+        }
+        let key = (agent_id.to_string(), user_id.to_string());
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            if !in_flight.insert(key.clone()) {
+                return;
+            }
+        }
+        let this = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = this.maybe_summarize(&key.0, &key.1).await {
+                error!(agent_id = %key.0, error = %e, "❌ Conversation summarization failed");
+            }
+            if let Ok(mut in_flight) = this.in_flight.lock() {
+                in_flight.remove(&key);
+            }
+        });
+    }
+
+    /// Fold the oldest unsummarized messages into the summary if the thread is
+    /// over the threshold. Returns whether a summary was written.
+    pub async fn maybe_summarize(&self, agent_id: &str, user_id: &str) -> anyhow::Result<bool> {
+        let previous = db::get_chat_summary(&self.pool, agent_id, user_id).await?;
+        let after = previous.as_ref().map_or(0, |s| s.summarized_until);
+        let pending = db::count_chat_messages_after(&self.pool, agent_id, user_id, after).await?;
+        #[allow(clippy::cast_possible_wrap)]
+        let threshold = self.threshold as i64;
+        if pending <= threshold {
+            return Ok(false);
+        }
+
+        let fold = (pending - threshold / 2).min(MAX_FOLD_BATCH);
+        let mut messages =
+            db::get_chat_messages_after(&self.pool, agent_id, user_id, after, fold + 1).await?;
+        // Keep messages sharing a timestamp together so none is skipped later
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        if let Some(next) = messages.get(fold as usize).map(|m| m.created_at) {
+            messages.truncate(fold as usize);
+            let whole = messages.iter().filter(|m| m.created_at != next).count();
+            if whole > 0 {
+                messages.truncate(whole);
+            }
+        }
+        let Some(last) = messages.last().map(|m| m.created_at) else {
+            return Ok(false);
+        };
+
+        let (agent, engine_id) = self.agent_manager.get_agent_config(agent_id).await?;
+        let prompt = render_prompt(
+            &agent,
+            previous.as_ref().map(|s| s.summary.as_str()),
+            &messages,
+        );
+        let request = ClotoMessage::new(MessageSource::System, prompt);
+        let summary = tokio::time::timeout(
+            SUMMARY_TIMEOUT,
+            self.registry.think(&engine_id, &agent, &request, vec![]),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("engine '{}' timed out", engine_id))??;
+        let summary = summary.trim();
+        if summary.is_empty() {
+            anyhow::bail!("engine '{}' returned an empty summary", engine_id);
+        }
+
+        #[allow(clippy::cast_possible_wrap)]
+        let message_count =
+            previous.as_ref().map_or(0, |s| s.message_count) + messages.len() as i64;
+        db::save_chat_summary(
+            &self.pool,
+            &ChatSummaryRow {
+                agent_id: agent_id.to_string(),
+                user_id: user_id.to_string(),
+                summary: summary.to_string(),
+                summarized_until: last,
+                message_count,
+                updated_at: chrono::Utc::now().timestamp_millis(),
+            },
+        )
+        .await?;
+        info!(
+            agent_id = %agent_id,
+            folded = messages.len(),
+            total = message_count,
+            "📝 Conversation summary updated"
+        );
+        Ok(true)
+    }
+}
+
+/// Plain text of a stored message (`content` is a JSON ContentBlock array).
+pub(crate) fn message_text(message: &ChatMessageRow) -> String {
+    let blocks: Vec<serde_json::Value> = serde_json::from_str(&message.content).unwrap_or_default();
+    let text: Vec<&str> = blocks
+        .iter()
+        .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+        .collect();
+    let text = text.join("\n");
+    if text.chars().count() > MAX_MESSAGE_CHARS {
+        let truncated: String = text.chars().take(MAX_MESSAGE_CHARS).collect();
+        format!("{}…", truncated)
+    } else {
+        text
+    }
+}
+
+fn render_prompt(
+    agent: &AgentMetadata,
+    previous: Option<&str>,
+    messages: &[ChatMessageRow],
+) -> String {
+    let mut prompt = format!(
+        "Update the running summary of your conversation with the user. Keep facts, \
+         decisions, user preferences and open questions; drop small talk. Write it from \
+         your perspective as {}, under 300 words, and reply with the summary only.\n",
+        agent.name
+    );
+    if let Some(previous) = previous {
+        let _ = write!(prompt, "\nCurrent summary:\n{}\n", previous);
+    }
+    prompt.push_str("\nMessages to fold in:\n");
+    for message in messages {
+        let speaker = match message.source.as_str() {
+            "user" => "User",
+            "agent" => agent.name.as_str(),
+            _ => "System",
+        };
+        let _ = writeln!(prompt, "[{}] {}", speaker, message_text(message));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use cloto_shared::{
+        ClotoEvent, ClotoEventData, Plugin, PluginCast, PluginManifest, ReasoningEngine,
+    };
+
+    /// Engine that answers with the number of prompt lines it was given.
+    struct CountingEngine;
+
+    impl PluginCast for CountingEngine {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+        fn as_reasoning(&self) -> Option<&dyn ReasoningEngine> {
+            Some(self)
+        }
+    }
+
+    #[async_trait]
+    impl Plugin for CountingEngine {
+        fn manifest(&self) -> PluginManifest {
+            PluginManifest {
+                id: "mind.count".to_string(),
+                name: "Count".to_string(),
+                description: String::new(),
+                version: "1.0".to_string(),
+                category: cloto_shared::PluginCategory::Agent,
+                service_type: cloto_shared::ServiceType::Reasoning,
+                tags: vec![],
+                is_active: true,
+                is_configured: true,
+                required_config_keys: vec![],
+                config_schema: vec![],
+                action_icon: None,
+                action_target: None,
+                icon_data: None,
+                magic_seal: 0x5645_5253,
+                sdk_version: "1.0".to_string(),
+                required_permissions: vec![],
+                provided_capabilities: vec![],
+                provided_tools: vec![],
+                subscribes: vec![],
+            }
+        }
+
+        async fn on_event(&self, _event: &ClotoEvent) -> anyhow::Result<Option<ClotoEventData>> {
+            Ok(None)
+        }
+    }
+
+    #[async_trait]
+    impl ReasoningEngine for CountingEngine {
+        fn name(&self) -> &'static str {
+            "count"
+        }
+
+        async fn think(
+            &self,
+            _agent: &AgentMetadata,
+            message: &ClotoMessage,
+            _context: Vec<ClotoMessage>,
+        ) -> anyhow::Result<String> {
+            let folded = message
+                .content
+                .lines()
+                .filter(|l| l.starts_with('['))
+                .count();
+            Ok(format!("summary of {} messages", folded))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_thread_is_folded_into_summary() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let agent_manager = AgentManager::new(pool.clone());
+        let agent_id = agent_manager
+            .create_agent(
+                "Sum",
+                "Summarizes",
+                "mind.count",
+                std::collections::HashMap::new(),
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        for i in 0..12 {
+            db::save_chat_message(
+                &pool,
+                &ChatMessageRow {
+                    id: format!("m{}", i),
+                    agent_id: agent_id.clone(),
+                    user_id: DEFAULT_THREAD_USER.to_string(),
+                    source: if i % 2 == 0 { "user" } else { "agent" }.to_string(),
+                    content: format!(r#"[{{"type":"text","text":"message {}"}}]"#, i),
+                    metadata: None,
+                    created_at: 1_000 + i,
+                    session_id: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let registry = PluginRegistry::new(5, 10);
+        registry
+            .plugins
+            .write()
+            .await
+            .insert("mind.count".to_string(), Arc::new(CountingEngine));
+        let summarizer =
+            ConversationSummarizer::new(pool.clone(), Arc::new(registry), agent_manager, 8);
+
+        // 12 pending > 8: fold all but the newest 4
+        assert!(summarizer
+            .maybe_summarize(&agent_id, DEFAULT_THREAD_USER)
+            .await
+            .unwrap());
+        let summary = db::get_chat_summary(&pool, &agent_id, DEFAULT_THREAD_USER)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.summary, "summary of 8 messages");
+        assert_eq!(
+            (summary.message_count, summary.summarized_until),
+            (8, 1_007)
+        );
+
+        // Under the threshold now: nothing to do
+        assert!(!summarizer
+            .maybe_summarize(&agent_id, DEFAULT_THREAD_USER)
+            .await
+            .unwrap());
+
+        // Covered context is replaced by the summary
+        let mut old = ClotoMessage::new(MessageSource::System, "message 1".to_string());
+        old.timestamp = chrono::DateTime::from_timestamp_millis(1_001).unwrap();
+        let mut recent = ClotoMessage::new(MessageSource::System, "message 10".to_string());
+        recent.timestamp = chrono::DateTime::from_timestamp_millis(1_010).unwrap();
+        let context = summarizer
+            .apply_to_context(&agent_id, DEFAULT_THREAD_USER, vec![old, recent])
+            .await;
+        assert_eq!(context.len(), 2);
+        assert!(context[0].content.contains("summary of 8 messages"));
+        assert_eq!(context[1].content, "message 10");
+    }
+}
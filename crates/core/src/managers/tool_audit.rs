@@ -0,0 +1,367 @@
+//! Audit trail of agent tool calls.
+//!
+//! Every tool call the agentic loop makes (or refuses) is written to the
+//! audit log as a `TOOL_EXECUTED` entry and to the `tool_invocations` table
+//! (`GET /api/tools/invocations`). Arguments are stored with values under
+//! sensitive keys (`CLOTO_AUDIT_REDACT_KEYS`) replaced and long strings
+//! truncated.
+//!
+//! The transcript of each agentic run (the assistant / tool messages passed to
+//! `think_with_tools`) is kept per chat message in `chat_tool_history`, with
+//! the same redaction applied to call arguments and long results cut short.
+
+use crate::db::DbPool;
+use crate::db::{self, AuditLogEntry, ChatToolHistoryRow, ToolInvocationRow};
+use cloto_shared::ClotoId;
+use serde_json::Value;
+
+pub const TOOL_EXECUTED: &str = "TOOL_EXECUTED";
+
+const REDACTED: &str = "[REDACTED]";
+/// Longest string argument kept verbatim.
+const MAX_STRING_CHARS: usize = 256;
+/// Longest serialized argument object kept as JSON.
+const MAX_ARGUMENTS_CHARS: usize = 4096;
+/// Longest message content kept in a run transcript.
+const MAX_TRANSCRIPT_CONTENT_CHARS: usize = 4096;
+
+pub enum ToolOutcome {
+    Success,
+    Failure(String),
+    /// Refused before execution (agent tool rules, unknown tool).
+    Denied(String),
+}
+
+pub struct ToolExecution<'a> {
+    pub agent_id: &'a str,
+    pub engine_id: &'a str,
+    /// Native plugin or MCP server that owns the tool.
+    pub provider: Option<String>,
+    pub tool_name: &'a str,
+    pub arguments: &'a Value,
+    pub duration_ms: u64,
+    pub outcome: ToolOutcome,
+    pub trace_id: ClotoId,
+}
+
+pub struct ToolAudit {
+    pool: DbPool,
+    /// Lowercase key fragments whose values are never stored.
+    redact_keys: Vec<String>,
+}
+
+impl ToolAudit {
+    #[must_use]
+    pub fn new(pool: DbPool, redact_keys: &[String]) -> Self {
+        Self {
+            pool,
+            redact_keys: redact_keys.iter().map(|k| k.to_lowercase()).collect(),
+        }
+    }
+
+    /// Write a `TOOL_EXECUTED` entry and a tool invocation row in the background.
+    pub fn record(&self, execution: ToolExecution<'_>) {
+        let (result, error) = match execution.outcome {
+            ToolOutcome::Success => ("SUCCESS", None),
+            ToolOutcome::Failure(error) => ("FAILURE", Some(error)),
+            ToolOutcome::Denied(reason) => ("DENIED", Some(reason)),
+        };
+        let arguments = self.redact(execution.arguments);
+        let invocation = ToolInvocationRow {
+            id: format!("invocation.{}", ClotoId::new()),
+            agent_id: execution.agent_id.to_string(),
+            engine_id: execution.engine_id.to_string(),
+            tool_name: execution.tool_name.to_string(),
+            server_id: execution.provider.clone(),
+            arguments: arguments.to_string(),
+            status: result.to_string(),
+            error: error.clone(),
+            duration_ms: i64::try_from(execution.duration_ms).unwrap_or(i64::MAX),
+            trace_id: Some(execution.trace_id.to_string()),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        let pool = self.pool.clone();
+        let pending = db::track_audit_write();
+        tokio::spawn(async move {
+            let _pending = pending;
+            if let Err(e) = db::create_tool_invocation(&pool, &invocation).await {
+                tracing::error!(tool = %invocation.tool_name, "Failed to record tool invocation: {}", e);
+            }
+        });
+
+        let reason = error.unwrap_or_else(|| format!("Executed in {}ms", execution.duration_ms));
+        db::spawn_audit_log(
+            self.pool.clone(),
+            AuditLogEntry {
+                timestamp: chrono::Utc::now(),
+                event_type: TOOL_EXECUTED.to_string(),
+                actor_id: Some(execution.agent_id.to_string()),
+                target_id: Some(execution.tool_name.to_string()),
+                permission: None,
+                result: result.to_string(),
+                reason,
+                metadata: Some(serde_json::json!({
+                    "engine_id": execution.engine_id,
+                    "provider": execution.provider,
+                    "duration_ms": execution.duration_ms,
+                    "arguments": arguments,
+                })),
+                trace_id: Some(execution.trace_id.to_string()),
+            },
+        );
+    }
+
+    /// Save the tool history of the run that answered `message_id` in the background.
+    pub fn record_history(
+        &self,
+        agent_id: &str,
+        engine_id: &str,
+        message_id: &str,
+        history: &[Value],
+    ) {
+        if history.is_empty() {
+            return;
+        }
+        let history: Vec<Value> = history.iter().map(|m| self.redact_message(m)).collect();
+        let row = ChatToolHistoryRow {
+            message_id: message_id.to_string(),
+            agent_id: agent_id.to_string(),
+            engine_id: engine_id.to_string(),
+            history: Value::Array(history).to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        let pool = self.pool.clone();
+        let pending = db::track_audit_write();
+        tokio::spawn(async move {
+            let _pending = pending;
+            if let Err(e) = db::save_chat_tool_history(&pool, &row).await {
+                tracing::error!(message_id = %row.message_id, "Failed to save tool history: {}", e);
+            }
+        });
+    }
+
+    /// Copy of a tool history message with call arguments redacted and long
+    /// content truncated.
+    fn redact_message(&self, message: &Value) -> Value {
+        let mut message = message.clone();
+        if let Some(Value::String(content)) = message.get_mut("content") {
+            if content.chars().count() > MAX_TRANSCRIPT_CONTENT_CHARS {
+                *content = truncate(content, MAX_TRANSCRIPT_CONTENT_CHARS);
+            }
+        }
+        if let Some(Value::Array(calls)) = message.get_mut("tool_calls") {
+            for call in calls {
+                let Some(arguments) = call.pointer_mut("/function/arguments") else {
+                    continue;
+                };
+                // Arguments travel as a JSON-encoded string
+                let parsed = match &*arguments {
+                    Value::String(s) => serde_json::from_str(s).unwrap_or(Value::String(s.clone())),
+                    other => other.clone(),
+                };
+                *arguments = Value::String(self.redact(&parsed).to_string());
+            }
+        }
+        message
+    }
+
+    /// Copy of `arguments` fit for the audit log.
+    #[must_use]
+    pub fn redact(&self, arguments: &Value) -> Value {
+        let redacted = self.redact_value(arguments);
+        let serialized = redacted.to_string();
+        if serialized.chars().count() > MAX_ARGUMENTS_CHARS {
+            Value::String(truncate(&serialized, MAX_ARGUMENTS_CHARS))
+        } else {
+            redacted
+        }
+    }
+
+    fn redact_value(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, v)| {
+                        let key_lower = key.to_lowercase();
+                        let v = if self.redact_keys.iter().any(|k| key_lower.contains(k)) {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.redact_value(v)
+                        };
+                        (key.clone(), v)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.redact_value(v)).collect())
+            }
+            Value::String(s) if s.chars().count() > MAX_STRING_CHARS => {
+                Value::String(truncate(s, MAX_STRING_CHARS))
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    let truncated: String = s.chars().take(max_chars).collect();
+    format!("{}…", truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn audit() -> ToolAudit {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        ToolAudit::new(pool, &["password".to_string(), "Token".to_string()])
+    }
+
+    #[tokio::test]
+    async fn test_redacts_sensitive_keys_recursively() {
+        let audit = audit().await;
+        let args = serde_json::json!({
+            "url": "https://example.com",
+            "db_password": "hunter2",
+            "headers": [{ "X-Auth-Token": "abc", "Accept": "text/html" }],
+        });
+        assert_eq!(
+            audit.redact(&args),
+            serde_json::json!({
+                "url": "https://example.com",
+                "db_password": REDACTED,
+                "headers": [{ "X-Auth-Token": REDACTED, "Accept": "text/html" }],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_writes_tool_invocation() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let audit = ToolAudit::new(pool.clone(), &["password".to_string()]);
+
+        for (agent_id, outcome) in [
+            ("agent.a", ToolOutcome::Success),
+            ("agent.a", ToolOutcome::Failure("boom".to_string())),
+            ("agent.b", ToolOutcome::Denied("not allowed".to_string())),
+        ] {
+            audit.record(ToolExecution {
+                agent_id,
+                engine_id: "mind.test",
+                provider: Some("tool.terminal".to_string()),
+                tool_name: "execute_command",
+                arguments: &serde_json::json!({ "command": "ls", "password": "x" }),
+                duration_ms: 12,
+                outcome,
+                trace_id: ClotoId::new(),
+            });
+        }
+
+        let filter = db::ToolInvocationFilter {
+            agent_id: Some("agent.a"),
+            ..Default::default()
+        };
+        let mut rows = Vec::new();
+        for _ in 0..50 {
+            rows = db::list_tool_invocations(&pool, &filter, 10).await.unwrap();
+            if rows.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(rows.len(), 2);
+        let failed = rows.iter().find(|r| r.status == "FAILURE").unwrap();
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+        assert_eq!(failed.server_id.as_deref(), Some("tool.terminal"));
+        assert_eq!(failed.duration_ms, 12);
+        let args: Value = serde_json::from_str(&failed.arguments).unwrap();
+        assert_eq!(args["password"], REDACTED);
+        assert!(rows
+            .iter()
+            .any(|r| r.status == "SUCCESS" && r.error.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_truncates_long_arguments() {
+        let audit = audit().await;
+        let long = "x".repeat(MAX_STRING_CHARS + 10);
+        let redacted = audit.redact(&serde_json::json!({ "code": long }));
+        assert_eq!(
+            redacted["code"].as_str().unwrap().chars().count(),
+            MAX_STRING_CHARS + 1
+        );
+
+        let many: Vec<String> = (0..100).map(|_| "y".repeat(100)).collect();
+        let redacted = audit.redact(&serde_json::json!({ "lines": many }));
+        assert!(redacted.is_string());
+        assert_eq!(
+            redacted.as_str().unwrap().chars().count(),
+            MAX_ARGUMENTS_CHARS + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_history_redacts_transcript() {
+        let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+        db::init_db(&pool, "sqlite::memory:").await.unwrap();
+        let audit = ToolAudit::new(pool.clone(), &["password".to_string()]);
+
+        audit.record_history(
+            "agent.a",
+            "mind.test",
+            "msg-1",
+            &[
+                serde_json::json!({
+                    "role": "assistant",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "login",
+                            "arguments": r#"{"user":"me","password":"hunter2"}"#
+                        }
+                    }]
+                }),
+                serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": "call_1",
+                    "content": "z".repeat(MAX_TRANSCRIPT_CONTENT_CHARS + 10)
+                }),
+            ],
+        );
+
+        let ids = vec!["msg-1".to_string()];
+        let mut rows = Vec::new();
+        for _ in 0..50 {
+            rows = db::get_chat_tool_history(&pool, "agent.a", &ids)
+                .await
+                .unwrap();
+            if !rows.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].engine_id, "mind.test");
+        let history: Vec<Value> = serde_json::from_str(&rows[0].history).unwrap();
+        let arguments: Value = serde_json::from_str(
+            history[0]["tool_calls"][0]["function"]["arguments"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(arguments["user"], "me");
+        assert_eq!(arguments["password"], REDACTED);
+        assert_eq!(
+            history[1]["content"].as_str().unwrap().chars().count(),
+            MAX_TRANSCRIPT_CONTENT_CHARS + 1
+        );
+
+        // Other agents cannot read it
+        assert!(db::get_chat_tool_history(&pool, "agent.b", &ids)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,58 +1,269 @@
 use axum::{
     extract::{ConnectInfo, Request, State},
-    http::StatusCode,
+    http::{HeaderValue, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use dashmap::DashMap;
 use governor::{
-    clock::DefaultClock,
+    clock::{Clock, DefaultClock},
+    middleware::StateInformationMiddleware,
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter as GovernorRateLimiter,
 };
+use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-pub type IpLimiter = GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+pub type IpLimiter =
+    GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock, StateInformationMiddleware>;
 
-/// IP-based rate limiter using token bucket algorithm (via governor)
-pub struct RateLimiter {
+/// Route groups with independent rate limit quotas, so heavy chat traffic
+/// does not starve plugin management (and vice versa).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteGroup {
+    Chat,
+    Management,
+    Shutdown,
+}
+
+impl RouteGroup {
+    const ALL: [Self; 3] = [Self::Chat, Self::Management, Self::Shutdown];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Management => "management",
+            Self::Shutdown => "shutdown",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Whose bucket a request draws from: the API key it authenticated with,
+/// or its IP when it sent no key we know.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateClient {
+    Ip(IpAddr),
+    /// API key ID (`key.<id>`, or `admin` for `CLOTO_API_KEY`)
+    Key(String),
+}
+
+/// Rate limit outcome for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    /// Allowed, with this many requests left in the burst.
+    Allowed { remaining: u32 },
+    /// Limited; the next request is allowed after this long.
+    Limited { retry_after: Duration },
+}
+
+fn to_quota(per_second: u32, burst: u32) -> Quota {
+    // M-03: Prevent panic on zero values by falling back to 1
+    let per_second = NonZeroU32::new(per_second).unwrap_or(NonZeroU32::MIN);
+    let burst = NonZeroU32::new(burst).unwrap_or(NonZeroU32::MIN);
+    Quota::per_second(per_second).allow_burst(burst)
+}
+
+/// A scoped API key known to the limiter.
+struct KeyLimit {
+    id: String,
+    /// Replaces the chat and management quotas; the shutdown quota always
+    /// applies.
+    quota: Option<Quota>,
+}
+
+struct Bucket {
+    limiter: Arc<IpLimiter>,
     // M-04: Store last-seen timestamp alongside limiter for side-effect-free cleanup
-    limiters: DashMap<IpAddr, (Arc<IpLimiter>, std::time::Instant)>,
-    quota: Quota,
+    last_seen: std::time::Instant,
+    /// Burst capacity left after the last request.
+    remaining: u32,
+}
+
+/// Token bucket rate limiter (via governor). Each (route group, client)
+/// pair has its own bucket, where the client is the request's API key or,
+/// without one, its IP.
+pub struct RateLimiter {
+    limiters: DashMap<(RouteGroup, RateClient), Bucket>,
+    quotas: std::sync::RwLock<GroupQuotas>,
+    /// Scoped API keys by hash (`db::hash_scoped_api_key`).
+    keys: std::sync::RwLock<HashMap<String, KeyLimit>>,
+    /// Requests rejected, by route group.
+    limited: [AtomicU64; 3],
+}
+
+#[derive(Clone, Copy)]
+struct GroupQuotas {
+    chat: Quota,
+    management: Quota,
+    shutdown: Quota,
+}
+
+impl GroupQuotas {
+    fn from_config(limits: &crate::config::RateLimitConfig) -> Self {
+        Self {
+            chat: to_quota(limits.chat.per_second, limits.chat.burst),
+            management: to_quota(limits.management.per_second, limits.management.burst),
+            shutdown: to_quota(limits.shutdown.per_second, limits.shutdown.burst),
+        }
+    }
 }
 
 impl RateLimiter {
-    /// Create a new rate limiter.
+    fn with_quotas(quotas: GroupQuotas) -> Self {
+        Self {
+            limiters: DashMap::new(),
+            quotas: std::sync::RwLock::new(quotas),
+            keys: std::sync::RwLock::new(HashMap::new()),
+            limited: Default::default(),
+        }
+    }
+
+    /// Create a new rate limiter with the same quota for every route group.
     /// - `per_second`: token replenish rate per second
     /// - `burst`: maximum burst capacity
     #[must_use]
     pub fn new(per_second: u32, burst: u32) -> Self {
-        // M-03: Prevent panic on zero values by falling back to 1
-        let per_second = NonZeroU32::new(per_second).unwrap_or(NonZeroU32::MIN);
-        let burst = NonZeroU32::new(burst).unwrap_or(NonZeroU32::MIN);
-        let quota = Quota::per_second(per_second).allow_burst(burst);
+        let quota = to_quota(per_second, burst);
+        Self::with_quotas(GroupQuotas {
+            chat: quota,
+            management: quota,
+            shutdown: quota,
+        })
+    }
 
-        Self {
-            limiters: DashMap::new(),
-            quota,
+    /// Create a rate limiter with per-group quotas from `AppConfig`.
+    #[must_use]
+    pub fn from_config(limits: &crate::config::RateLimitConfig) -> Self {
+        Self::with_quotas(GroupQuotas::from_config(limits))
+    }
+
+    /// Replace the per-group quotas (config hot reload). Existing buckets are
+    /// dropped so every client starts over under the new quota.
+    pub fn update(&self, limits: &crate::config::RateLimitConfig) {
+        if let Ok(mut quotas) = self.quotas.write() {
+            *quotas = GroupQuotas::from_config(limits);
+        }
+        self.limiters.clear();
+    }
+
+    /// Give requests made with a scoped key (by hash) their own buckets,
+    /// optionally with a custom quota.
+    pub fn register_key(&self, key_hash: &str, id: &str, quota: Option<crate::config::RateQuota>) {
+        if let Ok(mut keys) = self.keys.write() {
+            keys.insert(
+                key_hash.to_string(),
+                KeyLimit {
+                    id: id.to_string(),
+                    quota: quota.map(|q| to_quota(q.per_second, q.burst)),
+                },
+            );
+        }
+        self.forget_buckets(id);
+    }
+
+    /// Forget a deleted key; its requests fall back to per-IP buckets.
+    pub fn remove_key(&self, key_hash: &str) {
+        let removed = self.keys.write().ok().and_then(|mut k| k.remove(key_hash));
+        if let Some(key) = removed {
+            self.forget_buckets(&key.id);
+        }
+    }
+
+    fn forget_buckets(&self, id: &str) {
+        self.limiters
+            .retain(|(_, client), _| !matches!(client, RateClient::Key(k) if k == id));
+    }
+
+    /// The client for a request made with `key_hash` from `ip`: the key when
+    /// it is registered, otherwise the IP.
+    #[must_use]
+    pub fn client_for(&self, key_hash: Option<&str>, ip: IpAddr) -> RateClient {
+        key_hash
+            .and_then(|hash| {
+                let keys = self.keys.read().ok()?;
+                keys.get(hash).map(|key| RateClient::Key(key.id.clone()))
+            })
+            .unwrap_or(RateClient::Ip(ip))
+    }
+
+    fn quota(&self, group: RouteGroup, client: &RateClient) -> Quota {
+        if let (RateClient::Key(id), RouteGroup::Chat | RouteGroup::Management) = (client, group) {
+            let custom = self.keys.read().ok().and_then(|keys| {
+                keys.values()
+                    .find(|key| &key.id == id)
+                    .and_then(|key| key.quota)
+            });
+            if let Some(quota) = custom {
+                return quota;
+            }
+        }
+        let quotas = match self.quotas.read() {
+            Ok(q) => *q,
+            Err(poisoned) => *poisoned.into_inner(),
+        };
+        match group {
+            RouteGroup::Chat => quotas.chat,
+            RouteGroup::Management => quotas.management,
+            RouteGroup::Shutdown => quotas.shutdown,
         }
     }
 
-    /// Check if the given IP is allowed to proceed.
+    /// Check if the given IP is allowed to call a management route.
     /// Returns `true` if allowed, `false` if rate-limited.
     #[must_use]
     pub fn check(&self, ip: IpAddr) -> bool {
-        let mut entry = self.limiters.entry(ip).or_insert_with(|| {
-            (
-                Arc::new(GovernorRateLimiter::direct(self.quota)),
-                std::time::Instant::now(),
-            )
-        });
+        self.check_group(RouteGroup::Management, ip)
+    }
+
+    /// Check if the given IP is allowed to call a route in `group`.
+    /// Returns `true` if allowed, `false` if rate-limited.
+    #[must_use]
+    pub fn check_group(&self, group: RouteGroup, ip: IpAddr) -> bool {
+        matches!(
+            self.check_client(group, &RateClient::Ip(ip)),
+            RateDecision::Allowed { .. }
+        )
+    }
+
+    /// Take a token from the client's bucket for `group`.
+    pub fn check_client(&self, group: RouteGroup, client: &RateClient) -> RateDecision {
+        let mut entry = self
+            .limiters
+            .entry((group, client.clone()))
+            .or_insert_with(|| Bucket {
+                limiter: Arc::new(
+                    GovernorRateLimiter::direct(self.quota(group, client))
+                        .with_middleware::<StateInformationMiddleware>(),
+                ),
+                last_seen: std::time::Instant::now(),
+                remaining: 0,
+            });
         // Bug #11: Update timestamp BEFORE check to prevent race condition
-        entry.1 = std::time::Instant::now();
-        entry.0.check().is_ok()
+        entry.last_seen = std::time::Instant::now();
+        match entry.limiter.check() {
+            Ok(snapshot) => {
+                entry.remaining = snapshot.remaining_burst_capacity();
+                RateDecision::Allowed {
+                    remaining: entry.remaining,
+                }
+            }
+            Err(not_until) => {
+                entry.remaining = 0;
+                self.limited[group.index()].fetch_add(1, Ordering::Relaxed);
+                RateDecision::Limited {
+                    retry_after: not_until.wait_time_from(DefaultClock::default().now()),
+                }
+            }
+        }
     }
 
     /// Remove idle entries to prevent memory growth.
@@ -60,28 +271,409 @@ impl RateLimiter {
     pub fn cleanup(&self) {
         let idle_threshold = std::time::Duration::from_secs(600); // 10 minutes
         self.limiters
-            .retain(|_, (_, last_seen)| last_seen.elapsed() < idle_threshold);
+            .retain(|_, bucket| bucket.last_seen.elapsed() < idle_threshold);
     }
 
-    /// Number of tracked IPs (useful for metrics)
+    /// Number of tracked (route group, client) buckets (useful for metrics)
     #[must_use]
     pub fn tracked_ips(&self) -> usize {
         self.limiters.len()
     }
+
+    /// Bucket counts and rejections per route group, and the remaining burst
+    /// of each API key's buckets (IPs are not listed).
+    #[must_use]
+    pub fn snapshot(&self) -> serde_json::Value {
+        let mut groups = serde_json::Map::new();
+        for group in RouteGroup::ALL {
+            groups.insert(
+                group.as_str().to_string(),
+                serde_json::json!({
+                    "buckets": self.limiters.iter().filter(|e| e.key().0 == group).count(),
+                    "limited_total": self.limited[group.index()].load(Ordering::Relaxed),
+                }),
+            );
+        }
+        let mut api_keys: Vec<serde_json::Value> = self
+            .limiters
+            .iter()
+            .filter_map(|entry| match &entry.key().1 {
+                RateClient::Key(id) => Some(serde_json::json!({
+                    "id": id,
+                    "group": entry.key().0.as_str(),
+                    "remaining": entry.value().remaining,
+                    "idle_secs": entry.value().last_seen.elapsed().as_secs(),
+                })),
+                RateClient::Ip(_) => None,
+            })
+            .collect();
+        api_keys.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+        serde_json::json!({ "groups": groups, "api_keys": api_keys })
+    }
 }
 
-/// Axum middleware: rejects requests with 429 when rate limit is exceeded.
+/// Axum middleware: rejects requests with 429 when the route group's rate
+/// limit is exceeded. Layered per group with `(state, group)` as its state.
+///
+/// Requests with a known API key draw from that key's bucket, others from
+/// their IP's. Responses carry `X-RateLimit-Remaining`; 429s add
+/// `Retry-After`.
 pub async fn rate_limit_middleware(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State((state, group)): State<(Arc<crate::AppState>, RouteGroup)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = rate_client(&state, request.headers(), addr.ip());
+    match state.rate_limiter.check_client(group, &client) {
+        RateDecision::Allowed { remaining } => {
+            let mut response = next.run(request).await;
+            response
+                .headers_mut()
+                .insert("X-RateLimit-Remaining", HeaderValue::from(remaining));
+            response
+        }
+        RateDecision::Limited { retry_after } => {
+            tracing::warn!(client = ?client, group = ?group, "Rate limit exceeded");
+            let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [
+                    ("Retry-After", retry_after_secs.to_string()),
+                    ("X-RateLimit-Remaining", "0".to_string()),
+                ],
+            )
+                .into_response()
+        }
+    }
+}
+
+/// `CLOTO_API_KEY` draws from the `admin` bucket, a registered scoped key
+/// from its own; anything else is limited by IP.
+fn rate_client(state: &crate::AppState, headers: &axum::http::HeaderMap, ip: IpAddr) -> RateClient {
+    let Some(provided) = crate::handlers::provided_api_key(headers) else {
+        return RateClient::Ip(ip);
+    };
+    if crate::handlers::is_admin_key(state, provided) {
+        return RateClient::Key("admin".to_string());
+    }
+    let hash = crate::db::hash_scoped_api_key(provided);
+    state.rate_limiter.client_for(Some(&hash), ip)
+}
+
+/// Seconds clients are asked to wait (`Retry-After`) when load is shed.
+const OVERLOAD_RETRY_AFTER_SECS: u64 = 1;
+
+/// Caps concurrent requests through a route (group). Requests over the cap
+/// are rejected immediately with 503 instead of queueing on the event bus.
+pub struct InFlightLimiter {
+    name: &'static str,
+    max: usize,
+    permits: Arc<tokio::sync::Semaphore>,
+}
+
+impl InFlightLimiter {
+    #[must_use]
+    pub fn new(name: &'static str, max: usize) -> Self {
+        Self {
+            name,
+            max,
+            permits: Arc::new(tokio::sync::Semaphore::new(max)),
+        }
+    }
+
+    /// Requests currently holding a permit.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.max - self.permits.available_permits()
+    }
+}
+
+/// Axum middleware: sheds load with 503 + `Retry-After` when the limiter is saturated.
+pub async fn in_flight_middleware(
+    State(limiter): State<Arc<InFlightLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Ok(_permit) = limiter.permits.clone().try_acquire_owned() else {
+        tracing::warn!(
+            limiter = limiter.name,
+            max = limiter.max,
+            path = %request.uri().path(),
+            "⚠️ In-flight limit reached, shedding request"
+        );
+        return crate::AppError::Overloaded(OVERLOAD_RETRY_AFTER_SECS).into_response();
+    };
+    next.run(request).await
+}
+
+/// Axum middleware: while shutdown is draining, refuses requests that would
+/// start new work (anything but GET/HEAD) with 503. Reads, including the SSE
+/// stream, keep working so clients can watch in-flight runs finish.
+pub async fn drain_middleware(
+    State(drain): State<Arc<crate::drain::DrainTracker>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let read_only = matches!(
+        *request.method(),
+        axum::http::Method::GET | axum::http::Method::HEAD
+    );
+    if drain.is_draining() && !read_only {
+        return crate::AppError::ShuttingDown.into_response();
+    }
+    next.run(request).await
+}
+
+/// Per-request trace ID, inserted into request extensions by [`trace_middleware`].
+#[derive(Debug, Clone, Copy)]
+pub struct RequestTraceId(pub cloto_shared::ClotoId);
+
+/// Axum middleware: assigns a [`RequestTraceId`] and wraps the request in an
+/// `http.request` span (exported over OTLP when enabled, see `telemetry.rs`).
+pub async fn trace_middleware(mut request: Request, next: Next) -> Response {
+    use tracing::Instrument;
+
+    let trace_id = cloto_shared::ClotoId::new_trace_id();
+    request.extensions_mut().insert(RequestTraceId(trace_id));
+    let span = tracing::info_span!(
+        "http.request",
+        otel.kind = "server",
+        trace_id = %trace_id,
+        http.method = %request.method(),
+        // Path only: query strings may carry tokens
+        http.target = %request.uri().path(),
+        http.status_code = tracing::field::Empty,
+    );
+    crate::telemetry::link_trace_id(&span, trace_id);
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.status_code", response.status().as_u16());
+    response
+}
+
+/// Number of slow requests kept in [`SlowRequestLog`].
+pub const SLOW_REQUEST_LOG_CAPACITY: usize = 100;
+
+/// A request that took longer than the configured threshold.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SlowRequest {
+    pub method: String,
+    /// Matched route pattern (e.g. `/api/chat/:agent_id/messages`).
+    pub route: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    pub trace_id: Option<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Ring buffer of the most recent slow requests (`GET /api/metrics/slow-requests`).
+pub struct SlowRequestLog {
+    threshold_ms: std::sync::atomic::AtomicU64,
+    entries: std::sync::Mutex<std::collections::VecDeque<SlowRequest>>,
+}
+
+impl SlowRequestLog {
+    #[must_use]
+    pub fn new(threshold_ms: u64) -> Self {
+        Self {
+            threshold_ms: std::sync::atomic::AtomicU64::new(threshold_ms),
+            entries: std::sync::Mutex::new(std::collections::VecDeque::with_capacity(
+                SLOW_REQUEST_LOG_CAPACITY,
+            )),
+        }
+    }
+
+    #[must_use]
+    pub fn threshold(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.threshold_ms.load(std::sync::atomic::Ordering::Relaxed),
+        )
+    }
+
+    /// Change the slow-request threshold (config hot reload).
+    pub fn set_threshold_ms(&self, threshold_ms: u64) {
+        self.threshold_ms
+            .store(threshold_ms, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Record `entry`, evicting the oldest one when full.
+    pub fn record(&self, entry: SlowRequest) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.len() >= SLOW_REQUEST_LOG_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Recorded slow requests, most recent first.
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<SlowRequest> {
+        self.entries
+            .lock()
+            .map(|e| e.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Axum middleware: records requests slower than
+/// `AppConfig::slow_request_threshold_ms` into `AppState::slow_requests`.
+pub async fn slow_request_middleware(
     State(state): State<Arc<crate::AppState>>,
     request: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    if !state.rate_limiter.check(addr.ip()) {
-        tracing::warn!(ip = %addr.ip(), "Rate limit exceeded");
-        return Err(StatusCode::TOO_MANY_REQUESTS);
+) -> Response {
+    let started = std::time::Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map_or_else(
+            || request.uri().path().to_string(),
+            |p| p.as_str().to_string(),
+        );
+    let trace_id = request
+        .extensions()
+        .get::<RequestTraceId>()
+        .map(|t| t.0.to_string());
+
+    let response = next.run(request).await;
+
+    let elapsed = started.elapsed();
+    if elapsed >= state.slow_requests.threshold() {
+        let duration_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        tracing::warn!(
+            method = %method,
+            route = %route,
+            duration_ms,
+            trace_id = ?trace_id,
+            "🐢 Slow request"
+        );
+        state.slow_requests.record(SlowRequest {
+            method,
+            route,
+            status: response.status().as_u16(),
+            duration_ms,
+            trace_id,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+    response
+}
+
+/// Body limit for JSON admin/management endpoints.
+pub const JSON_BODY_LIMIT: usize = 64 * 1024;
+/// Body limit for chat routes, whose messages may embed base64 attachments
+/// (or, on `/v1/chat/completions`, the full conversation history).
+pub const ATTACHMENT_BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+/// Axum middleware: enforces a per-route body limit (the state) with a
+/// structured `AppError::PayloadTooLarge` response. Declared lengths are
+/// rejected up front; streamed bodies that overrun the paired
+/// `DefaultBodyLimit` have the extractor's plain-text 413 rewritten.
+pub async fn body_limit_middleware(
+    State(limit): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let declared = request
+        .headers()
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared.is_some_and(|len| len > limit) {
+        return crate::AppError::PayloadTooLarge(limit).into_response();
     }
-    Ok(next.run(request).await)
+
+    let response = next.run(request).await;
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return crate::AppError::PayloadTooLarge(limit).into_response();
+    }
+    response
+}
+
+/// Response marker set by routes that opt out of access logging
+/// (long-lived streams such as SSE would otherwise log once per connection
+/// teardown with a meaningless latency).
+#[derive(Clone, Copy)]
+pub struct SkipAccessLog;
+
+/// Route-level `map_response` hook that opts the route out of [`access_log_middleware`].
+pub async fn skip_access_log(mut response: Response) -> Response {
+    response.extensions_mut().insert(SkipAccessLog);
+    response
+}
+
+/// Short, non-reversible identifier for an API key, so log lines can tell
+/// keys apart without exposing them.
+#[must_use]
+pub fn api_key_fingerprint(key: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = format!("{:x}", Sha256::digest(key.as_bytes()));
+    digest[..8].to_string()
+}
+
+/// Axum middleware: one structured `access_log` event per API request with
+/// method, path, status, latency, API-key fingerprint and body sizes.
+pub async fn access_log_middleware(request: Request, next: Next) -> Response {
+    use axum::body::HttpBody;
+    use axum::extract::OriginalUri;
+
+    let started = std::time::Instant::now();
+    let method = request.method().clone();
+    // Nested routers see the stripped path; log the one the client sent
+    let path = request.extensions().get::<OriginalUri>().map_or_else(
+        || request.uri().path().to_string(),
+        |u| u.path().to_string(),
+    );
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let key_fingerprint = request
+        .headers()
+        .get("X-API-Key")
+        .and_then(|h| h.to_str().ok())
+        .map(api_key_fingerprint);
+    let request_bytes = request.body().size_hint().exact();
+
+    let response = next.run(request).await;
+    if response.extensions().get::<SkipAccessLog>().is_some() {
+        return response;
+    }
+
+    let status = response.status().as_u16();
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    let response_bytes = response.body().size_hint().exact();
+    if response.status().is_server_error() {
+        tracing::warn!(
+            target: "access_log",
+            method = %method,
+            path = %path,
+            status,
+            latency_ms,
+            client_ip = ?client_ip,
+            api_key = ?key_fingerprint,
+            request_bytes = ?request_bytes,
+            response_bytes = ?response_bytes,
+            "HTTP request failed"
+        );
+    } else {
+        tracing::info!(
+            target: "access_log",
+            method = %method,
+            path = %path,
+            status,
+            latency_ms,
+            client_ip = ?client_ip,
+            api_key = ?key_fingerprint,
+            request_bytes = ?request_bytes,
+            response_bytes = ?response_bytes,
+            "HTTP request"
+        );
+    }
+    response
 }
 
 #[cfg(test)]
@@ -162,4 +754,286 @@ mod tests {
         let _ = limiter.check(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)));
         assert_eq!(limiter.tracked_ips(), 2);
     }
+
+    #[test]
+    fn test_api_key_fingerprint_is_stable_and_short() {
+        let a = api_key_fingerprint("secret-key-a");
+        assert_eq!(a.len(), 8);
+        assert_eq!(a, api_key_fingerprint("secret-key-a"));
+        assert_ne!(a, api_key_fingerprint("secret-key-b"));
+        assert!(!a.contains("secret"));
+    }
+
+    #[test]
+    fn test_route_groups_are_independent() {
+        let limits = crate::config::RateLimitConfig {
+            chat: crate::config::RateQuota {
+                per_second: 1,
+                burst: 5,
+            },
+            management: crate::config::RateQuota {
+                per_second: 1,
+                burst: 2,
+            },
+            shutdown: crate::config::RateQuota {
+                per_second: 1,
+                burst: 1,
+            },
+        };
+        let limiter = RateLimiter::from_config(&limits);
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        for _ in 0..2 {
+            assert!(limiter.check_group(RouteGroup::Management, ip));
+        }
+        assert!(!limiter.check_group(RouteGroup::Management, ip));
+
+        // Chat has its own, larger bucket
+        for _ in 0..5 {
+            assert!(limiter.check_group(RouteGroup::Chat, ip));
+        }
+        assert!(!limiter.check_group(RouteGroup::Chat, ip));
+
+        assert!(limiter.check_group(RouteGroup::Shutdown, ip));
+        assert!(!limiter.check_group(RouteGroup::Shutdown, ip));
+    }
+
+    #[test]
+    fn test_api_keys_get_their_own_buckets() {
+        let limiter = RateLimiter::new(1, 2);
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 9));
+        limiter.register_key(
+            "hash-a",
+            "key.a",
+            Some(crate::config::RateQuota {
+                per_second: 1,
+                burst: 4,
+            }),
+        );
+
+        // Unknown keys fall back to the IP
+        assert_eq!(limiter.client_for(Some("hash-x"), ip), RateClient::Ip(ip));
+        let key = limiter.client_for(Some("hash-a"), ip);
+        assert_eq!(key, RateClient::Key("key.a".to_string()));
+
+        // Exhausting the IP bucket leaves the key's custom quota untouched
+        for _ in 0..2 {
+            assert!(limiter.check(ip));
+        }
+        assert!(!limiter.check(ip));
+        for remaining in (0..4).rev() {
+            assert_eq!(
+                limiter.check_client(RouteGroup::Management, &key),
+                RateDecision::Allowed { remaining }
+            );
+        }
+        let RateDecision::Limited { retry_after } =
+            limiter.check_client(RouteGroup::Management, &key)
+        else {
+            panic!("key should be limited after its burst");
+        };
+        assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(1));
+
+        // The shutdown quota is never overridden
+        for _ in 0..2 {
+            assert!(matches!(
+                limiter.check_client(RouteGroup::Shutdown, &key),
+                RateDecision::Allowed { .. }
+            ));
+        }
+        assert!(matches!(
+            limiter.check_client(RouteGroup::Shutdown, &key),
+            RateDecision::Limited { .. }
+        ));
+
+        let snapshot = limiter.snapshot();
+        assert_eq!(snapshot["groups"]["management"]["limited_total"], 2);
+        assert_eq!(snapshot["api_keys"].as_array().map(Vec::len), Some(2));
+
+        limiter.remove_key("hash-a");
+        assert_eq!(limiter.client_for(Some("hash-a"), ip), RateClient::Ip(ip));
+        assert_eq!(limiter.snapshot()["api_keys"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_admin_key_read_from_runtime_config() {
+        let state = crate::test_utils::create_test_app_state(Some("old-key".into())).await;
+        state.runtime_config.write().unwrap().admin_api_key = Some("new-key".into());
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let headers = |key: &str| {
+            let mut headers = axum::http::HeaderMap::new();
+            headers.insert("X-API-Key", key.parse().unwrap());
+            headers
+        };
+
+        assert_eq!(
+            rate_client(&state, &headers("new-key"), ip),
+            RateClient::Key("admin".to_string())
+        );
+        assert_eq!(
+            rate_client(&state, &headers("old-key"), ip),
+            RateClient::Ip(ip)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers() {
+        use tower::ServiceExt;
+
+        let state = crate::test_utils::create_test_app_state(None).await;
+        state.rate_limiter.update(&crate::config::RateLimitConfig {
+            management: crate::config::RateQuota {
+                per_second: 1,
+                burst: 2,
+            },
+            ..crate::config::RateLimitConfig::default()
+        });
+        let app = axum::Router::new()
+            .route("/", axum::routing::get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                (state, RouteGroup::Management),
+                rate_limit_middleware,
+            ))
+            .layer(axum::extract::connect_info::MockConnectInfo(
+                SocketAddr::from(([127, 0, 0, 1], 4000)),
+            ));
+
+        let mut remaining = Vec::new();
+        for _ in 0..3 {
+            let response = app
+                .clone()
+                .oneshot(
+                    axum::http::Request::get("/")
+                        .body(axum::body::Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            remaining.push((
+                response.status(),
+                response.headers()["X-RateLimit-Remaining"].clone(),
+                response.headers().get("Retry-After").cloned(),
+            ));
+        }
+        assert_eq!(remaining[0].0, StatusCode::OK);
+        assert_eq!(remaining[0].1, "1");
+        assert_eq!(remaining[1].1, "0");
+        assert_eq!(remaining[2].0, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(remaining[2].1, "0");
+        assert_eq!(
+            remaining[2].2.as_ref().map(|v| v.to_str().unwrap()),
+            Some("1")
+        );
+    }
+
+    async fn post_with_limit(body: &str, declare_length: bool) -> (StatusCode, serde_json::Value) {
+        use tower::ServiceExt;
+
+        let app = axum::Router::new()
+            .route(
+                "/",
+                axum::routing::post(|axum::Json(v): axum::Json<serde_json::Value>| async move {
+                    axum::Json(v)
+                }),
+            )
+            .layer(axum::extract::DefaultBodyLimit::max(16))
+            .layer(axum::middleware::from_fn_with_state(
+                16usize,
+                body_limit_middleware,
+            ));
+
+        let mut builder = axum::http::Request::post("/").header("content-type", "application/json");
+        if declare_length {
+            builder = builder.header("content-length", body.len());
+        }
+        let response = app
+            .oneshot(
+                builder
+                    .body(axum::body::Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_returns_structured_413() {
+        let oversized = format!("{{\"text\":\"{}\"}}", "x".repeat(64));
+
+        for declare_length in [true, false] {
+            let (status, body) = post_with_limit(&oversized, declare_length).await;
+            assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+            assert_eq!(body["error"]["type"], "PayloadTooLarge");
+        }
+
+        let (status, _) = post_with_limit("{\"a\":1}", true).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_slow_request_log_is_bounded() {
+        let log = SlowRequestLog::new(500);
+        for i in 0..(SLOW_REQUEST_LOG_CAPACITY + 5) {
+            log.record(SlowRequest {
+                method: "GET".to_string(),
+                route: format!("/api/r{}", i),
+                status: 200,
+                duration_ms: 600,
+                trace_id: None,
+                timestamp: chrono::Utc::now(),
+            });
+        }
+        let entries = log.snapshot();
+        assert_eq!(entries.len(), SLOW_REQUEST_LOG_CAPACITY);
+        // Most recent first; the five oldest were evicted
+        assert_eq!(
+            entries[0].route,
+            format!("/api/r{}", SLOW_REQUEST_LOG_CAPACITY + 4)
+        );
+        assert_eq!(entries.last().unwrap().route, "/api/r5");
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_limit_sheds_with_retry_after() {
+        use tower::ServiceExt;
+
+        let release = Arc::new(tokio::sync::Notify::new());
+        let limiter = Arc::new(InFlightLimiter::new("test", 1));
+        let handler_release = release.clone();
+        let app = axum::Router::new()
+            .route(
+                "/",
+                axum::routing::get(move || {
+                    let release = handler_release.clone();
+                    async move { release.notified().await }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                limiter.clone(),
+                in_flight_middleware,
+            ));
+
+        let request = || {
+            axum::http::Request::get("/")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let first = tokio::spawn(app.clone().oneshot(request()));
+        while limiter.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let shed = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(shed.headers()["retry-after"], "1");
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+        assert_eq!(limiter.in_flight(), 0);
+    }
 }
@@ -1,11 +1,24 @@
 //! Platform-specific service management, permissions, and binary update operations.
 //! Each platform module exposes the same public interface, selected at compile time via #[cfg].
 
+mod hardware;
+pub use hardware::{hardware, hardware_env, CpuInfo, GpuInfo, HardwareCapabilities};
+
 #[cfg(unix)]
 mod linux;
 #[cfg(unix)]
 pub use linux::*;
 
+#[cfg(all(unix, not(target_os = "macos")))]
+mod systemd;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub use systemd::*;
+
+#[cfg(target_os = "macos")]
+mod launchd;
+#[cfg(target_os = "macos")]
+pub use launchd::*;
+
 #[cfg(windows)]
 mod windows;
 #[cfg(windows)]
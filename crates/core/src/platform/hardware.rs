@@ -0,0 +1,233 @@
+//! Hardware capability detection.
+//!
+//! Detected once (lazily, on first use) and cached for the lifetime of the
+//! process. The result is reported via `GET /api/system/capabilities` and
+//! passed to MCP servers as `CLOTO_HW_*` environment variables so they can
+//! pick sensible defaults (GPU offload, capture backends, model sizes).
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuInfo {
+    /// Compute backend: `"cuda"` or `"metal"`.
+    pub backend: &'static str,
+    pub name: String,
+    pub memory_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuInfo {
+    pub brand: String,
+    pub arch: &'static str,
+    pub logical_cores: usize,
+    pub physical_cores: Option<usize>,
+    /// SIMD extensions relevant to local inference (e.g. `avx2`, `neon`).
+    pub features: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HardwareCapabilities {
+    pub os: &'static str,
+    pub cpu: CpuInfo,
+    pub total_memory_mb: u64,
+    pub gpus: Vec<GpuInfo>,
+    /// Whether a display is available. `None` when it cannot be determined.
+    pub screen: Option<bool>,
+    /// Whether a camera device is present. `None` when it cannot be determined.
+    pub camera: Option<bool>,
+}
+
+impl HardwareCapabilities {
+    /// Preferred GPU backend, or `"none"`.
+    #[must_use]
+    pub fn gpu_backend(&self) -> &'static str {
+        self.gpus.first().map_or("none", |g| g.backend)
+    }
+}
+
+static HARDWARE: OnceLock<HardwareCapabilities> = OnceLock::new();
+
+/// Detected hardware capabilities. The first call probes the system and may
+/// block briefly (it runs `nvidia-smi`); call it from a blocking context.
+pub fn hardware() -> &'static HardwareCapabilities {
+    HARDWARE.get_or_init(detect)
+}
+
+fn detect() -> HardwareCapabilities {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    sys.refresh_cpu_all();
+
+    let logical_cores = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+    HardwareCapabilities {
+        os: std::env::consts::OS,
+        cpu: CpuInfo {
+            brand: sys
+                .cpus()
+                .first()
+                .map(|c| c.brand().trim().to_string())
+                .unwrap_or_default(),
+            arch: std::env::consts::ARCH,
+            logical_cores,
+            physical_cores: sys.physical_core_count(),
+            features: cpu_features(),
+        },
+        total_memory_mb: sys.total_memory() / (1024 * 1024),
+        gpus: detect_gpus(),
+        screen: detect_screen(),
+        camera: detect_camera(),
+    }
+}
+
+#[allow(clippy::vec_init_then_push)]
+fn cpu_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    #[cfg(target_arch = "x86_64")]
+    {
+        for (name, present) in [
+            ("sse4.2", std::arch::is_x86_feature_detected!("sse4.2")),
+            ("avx", std::arch::is_x86_feature_detected!("avx")),
+            ("avx2", std::arch::is_x86_feature_detected!("avx2")),
+            ("fma", std::arch::is_x86_feature_detected!("fma")),
+            ("f16c", std::arch::is_x86_feature_detected!("f16c")),
+            ("avx512f", std::arch::is_x86_feature_detected!("avx512f")),
+        ] {
+            if present {
+                features.push(name);
+            }
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        features.push("neon");
+    }
+    features
+}
+
+fn detect_gpus() -> Vec<GpuInfo> {
+    let mut gpus = detect_cuda();
+    if cfg!(target_os = "macos") {
+        gpus.push(GpuInfo {
+            backend: "metal",
+            name: if cfg!(target_arch = "aarch64") {
+                "Apple Silicon GPU".to_string()
+            } else {
+                "Metal GPU".to_string()
+            },
+            // Apple Silicon GPUs share system memory
+            memory_mb: None,
+        });
+    }
+    gpus
+}
+
+fn detect_cuda() -> Vec<GpuInfo> {
+    let Ok(output) = Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_nvidia_smi(output: &str) -> Vec<GpuInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, memory) = line.rsplit_once(',')?;
+            let name = name.trim();
+            (!name.is_empty()).then(|| GpuInfo {
+                backend: "cuda",
+                name: name.to_string(),
+                memory_mb: memory.trim().parse().ok(),
+            })
+        })
+        .collect()
+}
+
+fn detect_screen() -> Option<bool> {
+    if cfg!(any(target_os = "windows", target_os = "macos")) {
+        // Desktop OSes always have a session display unless run as a service;
+        // that case is not observable without platform APIs.
+        return None;
+    }
+    Some(std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some())
+}
+
+fn detect_camera() -> Option<bool> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let entries = std::fs::read_dir("/dev").ok()?;
+    Some(entries.flatten().any(|e| {
+        e.file_name()
+            .to_str()
+            .is_some_and(|n| n.starts_with("video"))
+    }))
+}
+
+/// `CLOTO_HW_*` variables passed to MCP server processes.
+/// Unknown values are omitted rather than guessed.
+#[must_use]
+pub fn hardware_env() -> HashMap<String, String> {
+    let hw = hardware();
+    let mut env = HashMap::from([
+        ("CLOTO_HW_GPU".to_string(), hw.gpu_backend().to_string()),
+        (
+            "CLOTO_HW_RAM_MB".to_string(),
+            hw.total_memory_mb.to_string(),
+        ),
+        (
+            "CLOTO_HW_CPU_CORES".to_string(),
+            hw.cpu.logical_cores.to_string(),
+        ),
+        (
+            "CLOTO_HW_CPU_FEATURES".to_string(),
+            hw.cpu.features.join(","),
+        ),
+    ]);
+    if let Some(vram) = hw.gpus.first().and_then(|g| g.memory_mb) {
+        env.insert("CLOTO_HW_GPU_MEMORY_MB".to_string(), vram.to_string());
+    }
+    if let Some(screen) = hw.screen {
+        env.insert("CLOTO_HW_SCREEN".to_string(), u8::from(screen).to_string());
+    }
+    if let Some(camera) = hw.camera {
+        env.insert("CLOTO_HW_CAMERA".to_string(), u8::from(camera).to_string());
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let gpus = parse_nvidia_smi("NVIDIA GeForce RTX 4090, 24564\nTesla T4, [N/A]\n\n");
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
+        assert_eq!(gpus[0].memory_mb, Some(24564));
+        assert_eq!(gpus[1].memory_mb, None);
+    }
+
+    #[test]
+    fn test_hardware_env_reports_known_values() {
+        let env = hardware_env();
+        assert!(env["CLOTO_HW_CPU_CORES"].parse::<usize>().unwrap() >= 1);
+        assert!(matches!(
+            env["CLOTO_HW_GPU"].as_str(),
+            "cuda" | "metal" | "none"
+        ));
+    }
+}
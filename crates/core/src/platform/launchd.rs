@@ -0,0 +1,145 @@
+use anyhow::{bail, Context};
+use std::path::Path;
+use std::process::Command;
+use tracing::info;
+
+const SERVICE_LABEL: &str = "com.cloto.system";
+const SERVICE_FILE: &str = "/Library/LaunchDaemons/com.cloto.system.plist";
+
+/// Escape a value for inclusion in a plist `<string>` element
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Generate launchd property list content.
+///
+/// launchd has no EnvironmentFile equivalent; the kernel loads `.env` from
+/// its working directory, so WorkingDirectory points at the prefix.
+fn service_plist(prefix: &Path, user: Option<&str>) -> String {
+    let exec_start = prefix.join("cloto_system");
+    let log_file = prefix.join("logs").join("cloto.log");
+    let user_entry = user.map_or_else(String::new, |u| {
+        format!(
+            "    <key>UserName</key>\n    <string>{}</string>\n",
+            xml_escape(u)
+        )
+    });
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exec_start}</string>
+        <string>--headless</string>
+    </array>
+    <key>WorkingDirectory</key>
+    <string>{prefix}</string>
+{user_entry}    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ThrottleInterval</key>
+    <integer>5</integer>
+    <key>StandardOutPath</key>
+    <string>{log_file}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_file}</string>
+</dict>
+</plist>
+"#,
+        label = SERVICE_LABEL,
+        exec_start = xml_escape(&exec_start.display().to_string()),
+        prefix = xml_escape(&prefix.display().to_string()),
+        user_entry = user_entry,
+        log_file = xml_escape(&log_file.display().to_string()),
+    )
+}
+
+/// Register Cloto as a launchd daemon
+pub fn install_service(prefix: &Path, user: Option<&str>) -> anyhow::Result<()> {
+    let log_dir = prefix.join("logs");
+    std::fs::create_dir_all(&log_dir)
+        .with_context(|| format!("Failed to create {}", log_dir.display()))?;
+
+    let plist = service_plist(prefix, user);
+    info!("📝 Writing launchd daemon to {}", SERVICE_FILE);
+
+    // Write plist (requires root)
+    std::fs::write(SERVICE_FILE, &plist)
+        .context("Failed to write launchd plist (are you running as root?)")?;
+
+    // Unload a previously loaded definition so the new plist takes effect on start
+    let _ = stop_service();
+
+    info!("✅ Service registered: {}", SERVICE_LABEL);
+    info!(
+        "   Start with: sudo launchctl bootstrap system {}",
+        SERVICE_FILE
+    );
+    info!(
+        "   Status:     sudo launchctl print system/{}",
+        SERVICE_LABEL
+    );
+    info!(
+        "   Logs:       tail -f {}",
+        log_dir.join("cloto.log").display()
+    );
+    Ok(())
+}
+
+/// Remove Cloto launchd daemon
+pub fn uninstall_service() -> anyhow::Result<()> {
+    // Unload if loaded (ignore errors)
+    let _ = stop_service();
+
+    if Path::new(SERVICE_FILE).exists() {
+        std::fs::remove_file(SERVICE_FILE).context("Failed to remove launchd plist")?;
+        info!("✅ Service removed: {}", SERVICE_LABEL);
+    } else {
+        info!("ℹ️  Service file not found, nothing to remove");
+    }
+    Ok(())
+}
+
+/// Load the daemon; RunAtLoad starts it immediately
+pub fn start_service() -> anyhow::Result<()> {
+    run_launchctl(&["bootstrap", "system", SERVICE_FILE])
+}
+
+/// Unload the daemon so KeepAlive does not restart it
+pub fn stop_service() -> anyhow::Result<()> {
+    run_launchctl(&["bootout", &format!("system/{}", SERVICE_LABEL)])
+}
+
+pub fn service_status() -> anyhow::Result<String> {
+    let output = Command::new("launchctl")
+        .args(["print", &format!("system/{}", SERVICE_LABEL)])
+        .output()
+        .context("Failed to run launchctl")?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn run_launchctl(args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("launchctl")
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to run: launchctl {}", args.join(" ")))?;
+    if !status.success() {
+        bail!(
+            "launchctl {} failed with exit code {:?}",
+            args.join(" "),
+            status.code()
+        );
+    }
+    Ok(())
+}
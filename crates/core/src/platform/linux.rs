@@ -1,106 +1,7 @@
-use anyhow::{bail, Context};
+use anyhow::Context;
 use std::path::Path;
-use std::process::Command;
 use tracing::info;
 
-const SERVICE_NAME: &str = "cloto";
-const SERVICE_FILE: &str = "/etc/systemd/system/cloto.service";
-
-/// Generate systemd service unit file content
-fn service_unit(prefix: &Path, user: &str) -> String {
-    let exec_start = prefix.join("cloto_system");
-    format!(
-        r"[Unit]
-Description=Cloto System
-After=network.target
-
-[Service]
-Type=simple
-User={user}
-WorkingDirectory={prefix}
-ExecStart={exec_start}
-Restart=on-failure
-RestartSec=5
-EnvironmentFile={prefix}/.env
-
-[Install]
-WantedBy=multi-user.target
-",
-        user = user,
-        prefix = prefix.display(),
-        exec_start = exec_start.display(),
-    )
-}
-
-/// Register Cloto as a systemd service
-pub fn install_service(prefix: &Path, user: Option<&str>) -> anyhow::Result<()> {
-    let user = user.unwrap_or("root");
-
-    let unit = service_unit(prefix, user);
-    info!("📝 Writing systemd service to {}", SERVICE_FILE);
-
-    // Write service file (requires root)
-    std::fs::write(SERVICE_FILE, &unit)
-        .context("Failed to write systemd service file (are you running as root?)")?;
-
-    // Reload systemd and enable
-    run_systemctl(&["daemon-reload"])?;
-    run_systemctl(&["enable", SERVICE_NAME])?;
-
-    info!("✅ Service registered: {}", SERVICE_NAME);
-    info!("   Start with: sudo systemctl start {}", SERVICE_NAME);
-    info!("   Status:     sudo systemctl status {}", SERVICE_NAME);
-    info!("   Logs:       journalctl -u {} -f", SERVICE_NAME);
-    Ok(())
-}
-
-/// Remove Cloto systemd service
-pub fn uninstall_service() -> anyhow::Result<()> {
-    // Stop if running (ignore errors)
-    let _ = run_systemctl(&["stop", SERVICE_NAME]);
-    let _ = run_systemctl(&["disable", SERVICE_NAME]);
-
-    if Path::new(SERVICE_FILE).exists() {
-        std::fs::remove_file(SERVICE_FILE).context("Failed to remove service file")?;
-        run_systemctl(&["daemon-reload"])?;
-        info!("✅ Service removed: {}", SERVICE_NAME);
-    } else {
-        info!("ℹ️  Service file not found, nothing to remove");
-    }
-    Ok(())
-}
-
-pub fn start_service() -> anyhow::Result<()> {
-    run_systemctl(&["start", SERVICE_NAME])
-}
-
-pub fn stop_service() -> anyhow::Result<()> {
-    run_systemctl(&["stop", SERVICE_NAME])
-}
-
-pub fn service_status() -> anyhow::Result<String> {
-    let output = Command::new("systemctl")
-        .args(["status", SERVICE_NAME])
-        .output()
-        .context("Failed to run systemctl")?;
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-}
-
-fn run_systemctl(args: &[&str]) -> anyhow::Result<()> {
-    let status = Command::new("systemctl")
-        .args(args)
-        .status()
-        .with_context(|| format!("Failed to run: systemctl {}", args.join(" ")))?;
-    if !status.success() {
-        bail!(
-            "systemctl {} failed with exit code {:?}",
-            args.join(" "),
-            status.code()
-        );
-    }
-    Ok(())
-}
-
 /// Set executable permission on a file (chmod 0o755)
 pub fn set_executable_permission(path: &Path) -> anyhow::Result<()> {
     use std::os::unix::fs::PermissionsExt;
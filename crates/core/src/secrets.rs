@@ -0,0 +1,284 @@
+//! Secret references in configuration values.
+//!
+//! Any value in `.env`, `cloto.toml` or an MCP server `env` table may be a
+//! reference instead of the secret itself, so backups of the config
+//! directory do not leak provider keys:
+//!
+//! - `keychain:<service>/<account>` — OS keychain entry (macOS Keychain via
+//!   `security`, Linux Secret Service via `secret-tool`).
+//! - `secret:<name>` — entry of the encrypted secrets file
+//!   (`CLOTO_SECRETS_FILE`, default `data/secrets.toml` next to the binary),
+//!   managed with `cloto_system secrets`. Entries are encrypted with
+//!   ChaCha20-Poly1305 under a key derived (Argon2id) from
+//!   `CLOTO_SECRETS_PASSPHRASE`, which may itself be a `keychain:` reference.
+
+use anyhow::{bail, Context};
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+const KEYCHAIN_PREFIX: &str = "keychain:";
+const SECRET_PREFIX: &str = "secret:";
+
+/// Whether `value` is a secret reference rather than a literal.
+#[must_use]
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(KEYCHAIN_PREFIX) || value.starts_with(SECRET_PREFIX)
+}
+
+/// Resolve a secret reference; literal values are returned unchanged.
+pub fn resolve(value: &str) -> anyhow::Result<String> {
+    if let Some(entry) = value.strip_prefix(KEYCHAIN_PREFIX) {
+        keychain_get(entry)
+    } else if let Some(name) = value.strip_prefix(SECRET_PREFIX) {
+        SecretsFile::load(&secrets_file_path())?.get(name, &passphrase()?)
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+// ── OS keychain ──
+
+fn split_entry(entry: &str) -> anyhow::Result<(&str, &str)> {
+    entry
+        .split_once('/')
+        .filter(|(service, account)| !service.is_empty() && !account.is_empty())
+        .with_context(|| {
+            format!(
+                "Invalid keychain reference '{}': expected <service>/<account>",
+                entry
+            )
+        })
+}
+
+fn keychain_get(entry: &str) -> anyhow::Result<String> {
+    let (service, account) = split_entry(entry)?;
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args(["find-generic-password", "-s", service, "-a", account, "-w"])
+            .output()
+            .context("Failed to run security")?
+    } else if cfg!(unix) {
+        Command::new("secret-tool")
+            .args(["lookup", "service", service, "account", account])
+            .output()
+            .context("Failed to run secret-tool (install libsecret-tools)")?
+    } else {
+        bail!("keychain: references are not supported on this platform; use secret: instead");
+    };
+    if !output.status.success() {
+        bail!("Keychain entry {}/{} not found", service, account);
+    }
+    let value = String::from_utf8(output.stdout).context("Keychain entry is not UTF-8")?;
+    Ok(value.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// ── Encrypted secrets file ──
+
+/// `CLOTO_SECRETS_FILE`, else `data/secrets.toml` next to the binary.
+#[must_use]
+pub fn secrets_file_path() -> PathBuf {
+    std::env::var("CLOTO_SECRETS_FILE").map_or_else(
+        |_| crate::config::exe_dir().join("data").join("secrets.toml"),
+        PathBuf::from,
+    )
+}
+
+/// `CLOTO_SECRETS_PASSPHRASE`, resolving a `keychain:` reference.
+pub fn passphrase() -> anyhow::Result<String> {
+    let value = std::env::var("CLOTO_SECRETS_PASSPHRASE")
+        .context("CLOTO_SECRETS_PASSPHRASE is required to use secret: references")?;
+    if value.starts_with(SECRET_PREFIX) {
+        bail!("CLOTO_SECRETS_PASSPHRASE cannot be a secret: reference");
+    }
+    let value = resolve(&value)?;
+    if value.is_empty() {
+        bail!("CLOTO_SECRETS_PASSPHRASE is empty");
+    }
+    Ok(value)
+}
+
+/// On-disk format: a base64 salt and base64 `nonce || ciphertext` entries.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct SecretsFile {
+    salt: String,
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+/// Derived keys by (passphrase, salt) digest; Argon2 is deliberately slow.
+static KEY_CACHE: Mutex<Option<([u8; 32], [u8; 32])>> = Mutex::new(None);
+
+impl SecretsFile {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read secrets file {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid secrets file {}", path.display()))
+    }
+
+    /// Load `path`, or start an empty file with a fresh salt.
+    pub fn load_or_new(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            return Self::load(path);
+        }
+        let mut salt = [0u8; 16];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut salt);
+        Ok(Self {
+            salt: base64::engine::general_purpose::STANDARD.encode(salt),
+            secrets: BTreeMap::new(),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = format!(
+            "# Encrypted with CLOTO_SECRETS_PASSPHRASE. Manage with `cloto_system secrets`.\n{}",
+            toml::to_string(self)?
+        );
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write secrets file {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.secrets.keys().map(String::as_str).collect()
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.secrets.remove(name).is_some()
+    }
+
+    fn key(&self, passphrase: &str) -> anyhow::Result<LessSafeKey> {
+        use sha2::Digest;
+        let salt = base64::engine::general_purpose::STANDARD
+            .decode(&self.salt)
+            .context("Invalid secrets file salt")?;
+        let digest: [u8; 32] = sha2::Sha256::new()
+            .chain_update(passphrase.as_bytes())
+            .chain_update(&salt)
+            .finalize()
+            .into();
+
+        let mut cache = KEY_CACHE
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let key = match *cache {
+            Some((cached, key)) if cached == digest => key,
+            _ => {
+                let mut key = [0u8; 32];
+                argon2::Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+                    .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+                *cache = Some((digest, key));
+                key
+            }
+        };
+        let unbound = UnboundKey::new(&CHACHA20_POLY1305, &key)
+            .map_err(|_| anyhow::anyhow!("Invalid secrets key"))?;
+        Ok(LessSafeKey::new(unbound))
+    }
+
+    /// Decrypt entry `name`. The name is authenticated, so entries cannot be
+    /// swapped between names.
+    pub fn get(&self, name: &str, passphrase: &str) -> anyhow::Result<String> {
+        let encoded = self
+            .secrets
+            .get(name)
+            .with_context(|| format!("Secret '{}' not found in secrets file", name))?;
+        let mut data = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .with_context(|| format!("Secret '{}' is corrupt", name))?;
+        if data.len() < NONCE_LEN {
+            bail!("Secret '{}' is corrupt", name);
+        }
+        let mut ciphertext = data.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&data)
+            .map_err(|_| anyhow::anyhow!("Secret '{}' is corrupt", name))?;
+        let plaintext = self
+            .key(passphrase)?
+            .open_in_place(nonce, Aad::from(name.as_bytes()), &mut ciphertext)
+            .map_err(|_| {
+                anyhow::anyhow!("Failed to decrypt secret '{}' (wrong passphrase?)", name)
+            })?;
+        String::from_utf8(plaintext.to_vec()).context("Secret is not UTF-8")
+    }
+
+    /// Encrypt and store `value` as entry `name`.
+    pub fn set(&mut self, name: &str, value: &str, passphrase: &str) -> anyhow::Result<()> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            bail!("Invalid secret name '{}'", name);
+        }
+        // Every existing entry must decrypt with this passphrase, otherwise
+        // the file would end up with entries under different keys.
+        for existing in self.secrets.keys() {
+            self.get(existing, passphrase)?;
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut nonce);
+        let mut data = value.as_bytes().to_vec();
+        self.key(passphrase)?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(name.as_bytes()),
+                &mut data,
+            )
+            .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&data);
+        self.secrets.insert(
+            name.to_string(),
+            base64::engine::general_purpose::STANDARD.encode(out),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cloto-secrets-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("secrets.toml");
+
+        let mut file = SecretsFile::load_or_new(&path).unwrap();
+        file.set("deepseek", "sk-test-123", "passphrase").unwrap();
+        file.save(&path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-test-123"));
+
+        let file = SecretsFile::load(&path).unwrap();
+        assert_eq!(file.get("deepseek", "passphrase").unwrap(), "sk-test-123");
+        assert!(file.get("deepseek", "wrong").is_err());
+        assert!(file.get("missing", "passphrase").is_err());
+
+        // Entries are bound to their name
+        let mut swapped = SecretsFile::load(&path).unwrap();
+        let value = swapped.secrets["deepseek"].clone();
+        swapped.secrets.insert("other".to_string(), value);
+        assert!(swapped.get("other", "passphrase").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_literal_values_pass_through() {
+        assert!(!is_reference("sk-plain"));
+        assert_eq!(resolve("sk-plain").unwrap(), "sk-plain");
+        assert!(split_entry("cloto").is_err());
+        assert!(split_entry("cloto/deepseek").is_ok());
+    }
+}
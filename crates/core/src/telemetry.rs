@@ -0,0 +1,204 @@
+//! Tracing subscriber setup and optional OpenTelemetry (OTLP) trace export.
+//!
+//! With `CLOTO_OTLP_ENDPOINT` unset this is the plain `fmt` subscriber the
+//! kernel always used. When set, kernel spans (`http.request`,
+//! `event.process`, `plugin.on_event`, `reasoning.think`, `mcp.tool_call`)
+//! are additionally exported over OTLP/HTTP. Event spans are parented on the
+//! event's `trace_id`, so one agent turn shows up as a single trace in
+//! Jaeger/Tempo regardless of how many events it cascades through; the
+//! envelope's `correlation_id`, `issuer` and `depth` are recorded as span
+//! attributes to tell the hops apart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::Context;
+use cloto_shared::ClotoId;
+use opentelemetry::trace::{
+    SpanContext, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider,
+};
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::trace::{IdGenerator, RandomIdGenerator, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer;
+
+use crate::config::OtlpConfig;
+use crate::db_metrics::DbMetricsLayer;
+
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static OTLP_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Flushes pending spans on drop. Keep it alive for the lifetime of `main`.
+pub struct TelemetryGuard {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("OpenTelemetry shutdown failed: {}", e);
+            }
+        }
+    }
+}
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+}
+
+/// Install the global tracing subscriber (`RUST_LOG` filter, default `info`),
+/// adding an OTLP export layer when `otlp` is configured.
+///
+/// The `RUST_LOG` filter applies per layer: the database metrics layer
+/// always receives sqlx's TRACE-level query and acquire events.
+pub fn init(otlp: Option<&OtlpConfig>) -> anyhow::Result<TelemetryGuard> {
+    let registry = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(env_filter()))
+        .with(
+            DbMetricsLayer::new(crate::db_metrics::global())
+                .with_filter(filter_fn(DbMetricsLayer::wants)),
+        );
+
+    let Some(otlp) = otlp else {
+        registry
+            .try_init()
+            .context("Failed to install tracing subscriber")?;
+        return Ok(TelemetryGuard { provider: None });
+    };
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(otlp.endpoint.clone())
+        .with_timeout(EXPORT_TIMEOUT)
+        .with_headers(otlp.headers.clone())
+        .build()
+        .context("Failed to build OTLP span exporter")?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(otlp.service_name.clone())
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer("cloto-kernel");
+
+    registry
+        .with(
+            tracing_opentelemetry::layer()
+                .with_tracer(tracer)
+                .with_filter(env_filter()),
+        )
+        .try_init()
+        .context("Failed to install tracing subscriber")?;
+    OTLP_ENABLED.store(true, Ordering::Relaxed);
+    tracing::info!(endpoint = %otlp.endpoint, "📡 OpenTelemetry trace export enabled");
+
+    Ok(TelemetryGuard {
+        provider: Some(provider),
+    })
+}
+
+/// Build the `event.process` span for one envelope, parented on its
+/// `trace_id`. Background work spawned while handling the event should
+/// `.instrument(Span::current())` so its spans stay in the same trace.
+pub fn event_span(envelope: &crate::EnvelopedEvent) -> tracing::Span {
+    let trace_id = envelope.event.trace_id;
+    let span = tracing::info_span!(
+        "event.process",
+        trace_id = %trace_id,
+        depth = envelope.depth,
+        correlation_id = tracing::field::Empty,
+        issuer = tracing::field::Empty,
+    );
+    if let Some(correlation_id) = envelope.correlation_id {
+        span.record("correlation_id", tracing::field::display(correlation_id));
+    }
+    if let Some(issuer) = envelope.issuer {
+        span.record("issuer", tracing::field::display(issuer));
+    }
+    link_trace_id(&span, trace_id);
+    span
+}
+
+/// Parent `span` on the kernel `trace_id`, so every span created for events
+/// sharing that ID lands in the same OpenTelemetry trace. No-op when OTLP
+/// export is disabled.
+pub fn link_trace_id(span: &tracing::Span, trace_id: ClotoId) {
+    if !OTLP_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let parent = SpanContext::new(
+        TraceId::from_bytes(*trace_id.as_bytes()),
+        // Synthetic remote parent: a fresh span ID per link
+        RandomIdGenerator::default().new_span_id(),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    span.set_parent(opentelemetry::Context::new().with_remote_span_context(parent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_event_span_records_envelope_fields() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        );
+
+        let parent = ClotoId::new();
+        let issuer = ClotoId::from_name("tool.example");
+        let envelope = crate::EnvelopedEvent {
+            event: std::sync::Arc::new(cloto_shared::ClotoEvent::with_trace(
+                parent,
+                cloto_shared::ClotoEventData::SystemNotification("hi".into()),
+            )),
+            issuer: Some(issuer),
+            correlation_id: Some(parent),
+            depth: 2,
+        };
+        tracing::subscriber::with_default(subscriber, || {
+            let _entered = event_span(&envelope).entered();
+            tracing::info!("inside");
+        });
+
+        let out = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(out.contains("event.process{"), "{}", out);
+        assert!(out.contains("depth=2"), "{}", out);
+        assert!(
+            out.contains(&format!("correlation_id={}", parent)),
+            "{}",
+            out
+        );
+        assert!(out.contains(&format!("issuer={}", issuer)), "{}", out);
+    }
+}
@@ -1,13 +1,13 @@
 use crate::config::AppConfig;
+use crate::db::DbPool;
 use crate::managers::{AgentManager, PluginManager, PluginRegistry, SystemMetrics};
 use crate::DynamicRouter;
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, Notify, RwLock};
 
 pub async fn create_test_app_state(admin_api_key: Option<String>) -> Arc<crate::AppState> {
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     crate::db::init_db(&pool, "sqlite::memory:").await.unwrap();
 
     let (event_tx, _event_rx) = mpsc::channel(100);
@@ -28,6 +28,9 @@ pub async fn create_test_app_state(admin_api_key: Option<String>) -> Arc<crate::
     config.admin_api_key = admin_api_key;
 
     let rate_limiter = Arc::new(crate::middleware::RateLimiter::new(10, 20));
+    let slow_requests = Arc::new(crate::middleware::SlowRequestLog::new(
+        config.slow_request_threshold_ms,
+    ));
 
     let shutdown = Arc::new(Notify::new());
     let mcp_manager = Arc::new(crate::managers::McpClientManager::new(
@@ -35,6 +38,25 @@ pub async fn create_test_app_state(admin_api_key: Option<String>) -> Arc<crate::
         false, // yolo_mode disabled in tests
     ));
 
+    let rooms = Arc::new(crate::managers::RoomManager::new(
+        pool.clone(),
+        agent_manager.clone(),
+        event_tx.clone(),
+        tx.clone(),
+    ));
+    let summarizer = Arc::new(crate::managers::ConversationSummarizer::new(
+        pool.clone(),
+        registry.clone(),
+        agent_manager.clone(),
+        config.summary_threshold,
+    ));
+    let attachments = Arc::new(crate::managers::AttachmentProcessor::new(
+        pool.clone(),
+        mcp_manager.clone(),
+        None,
+        crate::managers::attachments::DEFAULT_MAX_DIMENSION,
+    ));
+
     Arc::new(crate::AppState {
         tx,
         registry,
@@ -44,11 +66,20 @@ pub async fn create_test_app_state(admin_api_key: Option<String>) -> Arc<crate::
         plugin_manager,
         mcp_manager,
         dynamic_router,
+        runtime_config: Arc::new(std::sync::RwLock::new(config.clone())),
         config,
         event_history,
         metrics,
         rate_limiter,
         shutdown,
+        drain: Arc::new(crate::drain::DrainTracker::new()),
         revoked_keys: Arc::new(std::sync::RwLock::new(std::collections::HashSet::new())),
+        api_keys: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+        users: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
+        session_key: Arc::new(crate::handlers::auth::SessionKey::generate()),
+        slow_requests,
+        rooms,
+        summarizer,
+        attachments,
     })
 }
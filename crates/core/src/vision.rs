@@ -0,0 +1,334 @@
+//! Element Resolver — kernel-side coordination for `HandAction::ClickElement`.
+//!
+//! Input actuators only understand coordinates. When a plugin requests a click
+//! on a labelled element, the kernel captures the screen through the vision
+//! MCP server, matches the label against the detected elements and translates
+//! the request into `MouseMove` + `MouseClick`.
+
+use cloto_shared::{ColorVisionData, DetectedElement, HandAction};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::managers::mcp_protocol::ToolContent;
+use crate::managers::McpClientManager;
+
+/// Minimum similarity for a detected element to be treated as the target.
+const MATCH_THRESHOLD: f32 = 0.8;
+/// Number of near-matches reported when resolution fails.
+const MAX_NEAR_MATCHES: usize = 5;
+/// Candidates scoring below this are not worth reporting as near-matches.
+const NEAR_MATCH_FLOOR: f32 = 0.3;
+
+// ============================================================
+// Errors
+// ============================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NearMatch {
+    pub label: String,
+    pub bounds: (i32, i32, i32, i32),
+    pub score: f32,
+}
+
+/// Structured failure of a `ClickElement` request.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClickElementError {
+    pub error: &'static str,
+    pub label: String,
+    pub reason: String,
+    pub near_matches: Vec<NearMatch>,
+}
+
+impl ClickElementError {
+    fn capture_failed(label: &str, reason: impl Into<String>) -> Self {
+        Self {
+            error: "click_element_failed",
+            label: label.to_string(),
+            reason: reason.into(),
+            near_matches: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for ClickElementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ClickElement '{}' failed: {}", self.label, self.reason)
+    }
+}
+
+impl std::error::Error for ClickElementError {}
+
+// ============================================================
+// Matching
+// ============================================================
+
+fn normalize(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Similarity of a requested label and an element label in `[0, 1]`.
+///
+/// Exact (case/whitespace-insensitive) matches score 1.0; containment scores
+/// by how much of the longer label is covered; anything else falls back to
+/// normalized edit distance so typos and OCR noise still rank close.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn label_similarity(wanted: &str, candidate: &str) -> f32 {
+    let wanted = normalize(wanted);
+    let candidate = normalize(candidate);
+    if wanted.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    if wanted == candidate {
+        return 1.0;
+    }
+    let a: Vec<char> = wanted.chars().collect();
+    let b: Vec<char> = candidate.chars().collect();
+    let (shorter, longer) = if a.len() <= b.len() {
+        (a.len(), b.len())
+    } else {
+        (b.len(), a.len())
+    };
+    if candidate.contains(&wanted) || wanted.contains(&candidate) {
+        return 0.5 + 0.5 * (shorter as f32 / longer as f32);
+    }
+    1.0 - levenshtein(&a, &b) as f32 / longer as f32
+}
+
+/// Elements reported by the accessibility API have exact bounds and win ties
+/// against OCR text of the same label.
+fn is_accessible(element: &DetectedElement) -> bool {
+    element
+        .attributes
+        .get("source")
+        .is_some_and(|s| s == "accessibility")
+}
+
+/// Pick the element best matching `label`, or fail listing the closest ones.
+///
+/// # Errors
+/// Returns `ClickElementError` when no element reaches `MATCH_THRESHOLD`.
+pub fn resolve_element<'a>(
+    label: &str,
+    elements: &'a [DetectedElement],
+) -> Result<&'a DetectedElement, ClickElementError> {
+    let mut scored: Vec<(f32, &DetectedElement)> = elements
+        .iter()
+        .filter(|e| e.bounds.2 > 0 && e.bounds.3 > 0)
+        .map(|e| (label_similarity(label, &e.label), e))
+        .collect();
+    scored.sort_by(|(sa, ea), (sb, eb)| {
+        sb.total_cmp(sa)
+            .then_with(|| is_accessible(eb).cmp(&is_accessible(ea)))
+            .then_with(|| eb.confidence.total_cmp(&ea.confidence))
+    });
+
+    match scored.first() {
+        Some((score, element)) if *score >= MATCH_THRESHOLD => Ok(element),
+        _ => Err(ClickElementError {
+            error: "click_element_failed",
+            label: label.to_string(),
+            reason: format!(
+                "No element matching '{}' among {} detected elements",
+                label,
+                elements.len()
+            ),
+            near_matches: scored
+                .iter()
+                .filter(|(score, _)| *score >= NEAR_MATCH_FLOOR)
+                .take(MAX_NEAR_MATCHES)
+                .map(|(score, e)| NearMatch {
+                    label: e.label.clone(),
+                    bounds: e.bounds,
+                    score: (score * 100.0).round() / 100.0,
+                })
+                .collect(),
+        }),
+    }
+}
+
+/// Translate a resolved element into the primitive actions that click its center.
+#[must_use]
+pub fn click_actions(element: &DetectedElement) -> [HandAction; 2] {
+    let (x, y, w, h) = element.bounds;
+    [
+        HandAction::MouseMove {
+            x: x + w / 2,
+            y: y + h / 2,
+        },
+        HandAction::MouseClick {
+            button: "left".to_string(),
+        },
+    ]
+}
+
+// ============================================================
+// ElementResolver
+// ============================================================
+
+/// Captures the screen through a vision MCP server (`list_ui_elements`, then
+/// `read_screen` for OCR text) and resolves labels against the result.
+pub struct ElementResolver {
+    mcp: Arc<McpClientManager>,
+    server_id: String,
+}
+
+impl ElementResolver {
+    #[must_use]
+    pub fn new(mcp: Arc<McpClientManager>, server_id: String) -> Self {
+        Self { mcp, server_id }
+    }
+
+    async fn call_capture_tool(&self, tool: &str, args: Value) -> anyhow::Result<ColorVisionData> {
+        let result = self
+            .mcp
+            .call_server_tool(&self.server_id, tool, args)
+            .await?;
+        let text = result
+            .content
+            .iter()
+            .find_map(|c| match c {
+                ToolContent::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("{} returned no text content", tool))?;
+        let value: Value = serde_json::from_str(text)?;
+        if let Some(err) = value.get("error").and_then(Value::as_str) {
+            anyhow::bail!("{}: {}", tool, err);
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Capture the current screen and return everything the vision server detected.
+    ///
+    /// # Errors
+    /// Fails only when neither the accessibility tree nor OCR produced a result.
+    pub async fn capture(&self) -> anyhow::Result<ColorVisionData> {
+        let accessible = self
+            .call_capture_tool("list_ui_elements", serde_json::json!({}))
+            .await;
+        let ocr = self
+            .call_capture_tool("read_screen", serde_json::json!({}))
+            .await;
+        match (accessible, ocr) {
+            (Ok(mut a11y), Ok(ocr)) => {
+                a11y.detected_elements.extend(ocr.detected_elements);
+                a11y.image_ref = ocr.image_ref;
+                Ok(a11y)
+            }
+            (Ok(data), Err(e)) | (Err(e), Ok(data)) => {
+                debug!(server = %self.server_id, "Partial capture: {}", e);
+                Ok(data)
+            }
+            (Err(a11y), Err(ocr)) => Err(anyhow::anyhow!("{}; {}", a11y, ocr)),
+        }
+    }
+
+    /// Resolve `label` on the current screen into click actions.
+    ///
+    /// Returns the capture alongside the result so callers can publish it.
+    pub async fn resolve_click(
+        &self,
+        label: &str,
+    ) -> (
+        Option<ColorVisionData>,
+        Result<[HandAction; 2], ClickElementError>,
+    ) {
+        let capture = match self.capture().await {
+            Ok(capture) => capture,
+            Err(e) => {
+                return (
+                    None,
+                    Err(ClickElementError::capture_failed(
+                        label,
+                        format!("Screen capture via '{}' failed: {}", self.server_id, e),
+                    )),
+                )
+            }
+        };
+        let result = resolve_element(label, &capture.detected_elements).map(click_actions);
+        (Some(capture), result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn element(label: &str, bounds: (i32, i32, i32, i32), source: &str) -> DetectedElement {
+        DetectedElement {
+            label: label.to_string(),
+            bounds,
+            confidence: 0.9,
+            attributes: HashMap::from([("source".to_string(), source.to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_similarity_ranks_exact_over_partial() {
+        assert!((label_similarity("Save", "  save ") - 1.0).abs() < f32::EPSILON);
+        assert!(label_similarity("Save", "Save As...") < 1.0);
+        assert!(label_similarity("Save", "Save As...") > label_similarity("Save", "Cancel"));
+        assert!(label_similarity("Sumbit", "Submit") >= 0.6);
+        assert!(label_similarity("", "Submit").abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_resolve_prefers_accessibility_on_tie() {
+        let elements = vec![
+            element("OK", (0, 0, 50, 20), "ocr"),
+            element("OK", (100, 100, 60, 30), "accessibility"),
+        ];
+        let found = resolve_element("ok", &elements).unwrap();
+        assert_eq!(found.bounds, (100, 100, 60, 30));
+    }
+
+    #[test]
+    fn test_resolve_failure_lists_near_matches() {
+        let elements = vec![
+            element("Save As...", (0, 0, 80, 20), "accessibility"),
+            element("Settings", (0, 40, 80, 20), "accessibility"),
+            element("Quit", (0, 80, 80, 20), "accessibility"),
+        ];
+        let err = resolve_element("Save", &elements).unwrap_err();
+        assert_eq!(err.label, "Save");
+        assert_eq!(err.near_matches[0].label, "Save As...");
+        assert!(err.near_matches.iter().all(|m| m.label != "Quit"));
+    }
+
+    #[test]
+    fn test_resolve_skips_zero_sized_elements() {
+        let elements = vec![element("OK", (10, 10, 0, 0), "accessibility")];
+        assert!(resolve_element("OK", &elements).is_err());
+    }
+
+    #[test]
+    fn test_click_actions_target_center() {
+        let actions = click_actions(&element("OK", (100, 200, 40, 20), "ocr"));
+        assert!(matches!(
+            actions[0],
+            HandAction::MouseMove { x: 120, y: 210 }
+        ));
+        assert!(matches!(actions[1], HandAction::MouseClick { ref button } if button == "left"));
+    }
+}
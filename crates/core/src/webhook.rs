@@ -0,0 +1,505 @@
+//! `adapter.webhook` — inbound webhooks that drive agents.
+//!
+//! A built-in `WebPlugin` serving `POST /api/plugin/webhook/:hook_id` for the
+//! hooks in `webhooks.toml` (`CLOTO_WEBHOOKS_CONFIG`, default
+//! `data/webhooks.toml` next to the binary). Every request must carry a valid
+//! HMAC-SHA256 signature of the raw body. The JSON payload is rendered through
+//! the hook's template into a `MessageReceived` for an agent or a
+//! `SystemNotification`, so GitHub, Stripe or home-automation events can
+//! trigger agents.
+//!
+//! Templates substitute `{{path.to.field}}` from the payload (array indices
+//! are path segments, e.g. `{{commits.0.message}}`), `{{headers.<name>}}` from
+//! the request headers and `{{hook_id}}`. Missing values render empty.
+//!
+//! ```toml
+//! [[hooks]]
+//! id = "github"
+//! secret = "${GITHUB_WEBHOOK_SECRET}"
+//! signature = "github"
+//! agent_id = "agent.cloto_default"
+//! template = "GitHub {{headers.x-github-event}} on {{repository.full_name}}: {{action}}"
+//! ```
+
+use axum::body::Bytes;
+use axum::extract::Path;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Json;
+use cloto_shared::{
+    ClotoEvent, ClotoEventData, ClotoId, ClotoMessage, MessageSource, Plugin, PluginCast,
+    PluginManifest, WebPlugin,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+pub const PLUGIN_ID: &str = "adapter.webhook";
+
+/// Maximum age of a Stripe signature timestamp.
+const STRIPE_TOLERANCE_SECS: i64 = 300;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WebhooksFile {
+    #[serde(default)]
+    hooks: Vec<HookConfig>,
+}
+
+/// How the request signature is transported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// Hex digest in `signature_header`, after an optional `signature_prefix`.
+    #[default]
+    Hmac,
+    /// `X-Hub-Signature-256: sha256=<hex>`.
+    Github,
+    /// `Stripe-Signature: t=<unix>,v1=<hex>` over `"<t>.<body>"`.
+    Stripe,
+}
+
+/// What a webhook request turns into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    /// `MessageReceived` for `agent_id` (default agent when unset).
+    #[default]
+    Message,
+    /// `SystemNotification`.
+    Notification,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HookConfig {
+    pub id: String,
+    /// HMAC key. `${VAR}` and secret references are resolved.
+    pub secret: String,
+    #[serde(default)]
+    pub signature: SignatureScheme,
+    #[serde(default = "default_signature_header")]
+    pub signature_header: String,
+    #[serde(default)]
+    pub signature_prefix: String,
+    #[serde(default)]
+    pub kind: HookKind,
+    pub agent_id: Option<String>,
+    pub template: String,
+    /// Extra message metadata, each value a template.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+fn default_signature_header() -> String {
+    "X-Signature-256".to_string()
+}
+
+struct Hook {
+    config: HookConfig,
+    key: ring::hmac::Key,
+}
+
+/// Location of `webhooks.toml`: `CLOTO_WEBHOOKS_CONFIG`, else
+/// `data/webhooks.toml` next to the binary.
+#[must_use]
+pub fn webhooks_config_path(config: &crate::config::AppConfig) -> std::path::PathBuf {
+    config.webhooks_config_path.as_ref().map_or_else(
+        || crate::config::exe_dir().join("data").join("webhooks.toml"),
+        std::path::PathBuf::from,
+    )
+}
+
+struct Hooks {
+    hooks: HashMap<String, Hook>,
+    event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+}
+
+pub struct WebhookPlugin {
+    inner: Arc<Hooks>,
+}
+
+impl WebhookPlugin {
+    /// Load the hooks file. Returns `None` when it does not exist.
+    pub fn load(
+        path: &std::path::Path,
+        event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+    ) -> anyhow::Result<Option<Self>> {
+        use anyhow::Context;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let file: WebhooksFile =
+            toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+        let plugin = Self::new(file.hooks, event_tx)?;
+        info!(
+            hooks = plugin.inner.hooks.len(),
+            "🪝 Webhooks loaded from {}",
+            path.display()
+        );
+        Ok(Some(plugin))
+    }
+
+    pub fn new(
+        hooks: Vec<HookConfig>,
+        event_tx: mpsc::Sender<crate::EnvelopedEvent>,
+    ) -> anyhow::Result<Self> {
+        let mut map = HashMap::new();
+        for config in hooks {
+            if config.id.is_empty()
+                || !config
+                    .id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                anyhow::bail!(
+                    "Invalid webhook id '{}': use letters, digits, '_' and '-'",
+                    config.id
+                );
+            }
+            let secret = crate::managers::mcp_transport::resolve_env_value(&config.secret);
+            if secret.is_empty() {
+                anyhow::bail!("Webhook '{}' has an empty secret", config.id);
+            }
+            let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_bytes());
+            let id = config.id.clone();
+            if map.insert(id.clone(), Hook { config, key }).is_some() {
+                anyhow::bail!("Duplicate webhook id '{}'", id);
+            }
+        }
+        Ok(Self {
+            inner: Arc::new(Hooks {
+                hooks: map,
+                event_tx,
+            }),
+        })
+    }
+}
+
+impl Hooks {
+    async fn handle(&self, hook_id: &str, headers: &HeaderMap, body: &[u8]) -> Response {
+        let Some(hook) = self.hooks.get(hook_id) else {
+            return error(StatusCode::NOT_FOUND, "Unknown webhook");
+        };
+        if let Err(reason) = verify(hook, headers, body, chrono::Utc::now().timestamp()) {
+            warn!(hook = hook_id, "Rejected webhook request: {}", reason);
+            return error(StatusCode::UNAUTHORIZED, "Invalid signature");
+        }
+        let payload: Value = match serde_json::from_slice(body) {
+            Ok(v) => v,
+            Err(e) => return error(StatusCode::BAD_REQUEST, &format!("Invalid JSON: {}", e)),
+        };
+
+        let event = event_for(&hook.config, &payload, headers);
+        let envelope = crate::EnvelopedEvent {
+            event: Arc::new(ClotoEvent::new(event)),
+            issuer: Some(ClotoId::from_name(PLUGIN_ID)),
+            correlation_id: None,
+            depth: 0,
+        };
+        if self.event_tx.send(envelope).await.is_err() {
+            return error(StatusCode::SERVICE_UNAVAILABLE, "Event bus closed");
+        }
+        (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "status": "accepted" })),
+        )
+            .into_response()
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn verify(hook: &Hook, headers: &HeaderMap, body: &[u8], now: i64) -> Result<(), &'static str> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .ok_or("missing signature header")
+    };
+    let check = |signed: &[u8], hex: &str| {
+        let tag = decode_hex(hex.trim()).ok_or("malformed signature")?;
+        ring::hmac::verify(&hook.key, signed, &tag).map_err(|_| "signature mismatch")
+    };
+
+    match hook.config.signature {
+        SignatureScheme::Hmac => {
+            let value = header(&hook.config.signature_header)?;
+            let hex = value
+                .strip_prefix(hook.config.signature_prefix.as_str())
+                .ok_or("malformed signature")?;
+            check(body, hex)
+        }
+        SignatureScheme::Github => {
+            let value = header("X-Hub-Signature-256")?;
+            check(
+                body,
+                value.strip_prefix("sha256=").ok_or("malformed signature")?,
+            )
+        }
+        SignatureScheme::Stripe => {
+            let value = header("Stripe-Signature")?;
+            let mut timestamp = None;
+            let mut signatures = Vec::new();
+            for part in value.split(',') {
+                match part.split_once('=') {
+                    Some(("t", t)) => timestamp = Some(t),
+                    Some(("v1", sig)) => signatures.push(sig),
+                    _ => {}
+                }
+            }
+            let timestamp = timestamp.ok_or("malformed signature")?;
+            let t: i64 = timestamp.parse().map_err(|_| "malformed signature")?;
+            if (now - t).abs() > STRIPE_TOLERANCE_SECS {
+                return Err("timestamp outside tolerance");
+            }
+            let mut signed = format!("{}.", timestamp).into_bytes();
+            signed.extend_from_slice(body);
+            if signatures.iter().any(|sig| check(&signed, sig).is_ok()) {
+                Ok(())
+            } else {
+                Err("signature mismatch")
+            }
+        }
+    }
+}
+
+fn lookup<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(payload, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Render a `{{...}}` template against the payload and request headers.
+fn render(template: &str, hook_id: &str, payload: &Value, headers: &HeaderMap) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let key = rest[start + 2..start + end].trim();
+        if key == "hook_id" {
+            out.push_str(hook_id);
+        } else if let Some(name) = key.strip_prefix("headers.") {
+            if let Some(value) = headers.get(name).and_then(|v| v.to_str().ok()) {
+                out.push_str(value);
+            }
+        } else {
+            match lookup(payload, key) {
+                Some(Value::String(s)) => out.push_str(s),
+                Some(Value::Null) | None => {}
+                Some(other) => out.push_str(&other.to_string()),
+            }
+        }
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn event_for(hook: &HookConfig, payload: &Value, headers: &HeaderMap) -> ClotoEventData {
+    let text = render(&hook.template, &hook.id, payload, headers);
+    match hook.kind {
+        HookKind::Notification => ClotoEventData::SystemNotification(text),
+        HookKind::Message => {
+            let mut msg = ClotoMessage::new(
+                MessageSource::User {
+                    id: format!("webhook:{}", hook.id),
+                    name: hook.id.clone(),
+                },
+                text,
+            );
+            let mut metadata: HashMap<String, String> = hook
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.clone(), render(v, &hook.id, payload, headers)))
+                .collect();
+            metadata.insert("channel".to_string(), PLUGIN_ID.to_string());
+            metadata.insert("webhook_id".to_string(), hook.id.clone());
+            if let Some(agent_id) = &hook.agent_id {
+                msg.target_agent = Some(agent_id.clone());
+                metadata.insert("target_agent_id".to_string(), agent_id.clone());
+            }
+            msg.metadata = metadata;
+            ClotoEventData::MessageReceived(msg)
+        }
+    }
+}
+
+impl PluginCast for WebhookPlugin {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_web(&self) -> Option<&dyn WebPlugin> {
+        Some(self)
+    }
+}
+
+#[async_trait::async_trait]
+impl Plugin for WebhookPlugin {
+    fn manifest(&self) -> PluginManifest {
+        let mut hook_ids: Vec<String> = self.inner.hooks.keys().cloned().collect();
+        hook_ids.sort();
+        PluginManifest {
+            id: PLUGIN_ID.to_string(),
+            name: "Webhooks".to_string(),
+            description: "Inbound HMAC-signed webhooks that trigger agents".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            category: cloto_shared::PluginCategory::System,
+            service_type: cloto_shared::ServiceType::Communication,
+            tags: hook_ids,
+            is_active: true,
+            is_configured: true,
+            required_config_keys: vec![],
+            config_schema: vec![],
+            action_icon: None,
+            action_target: None,
+            icon_data: None,
+            magic_seal: 0,
+            sdk_version: "internal".to_string(),
+            required_permissions: vec![],
+            provided_capabilities: vec![],
+            provided_tools: vec![],
+            subscribes: vec![],
+        }
+    }
+}
+
+impl WebPlugin for WebhookPlugin {
+    fn register_routes(
+        &self,
+        router: axum::Router<Arc<dyn Any + Send + Sync>>,
+    ) -> axum::Router<Arc<dyn Any + Send + Sync>> {
+        let hooks = self.inner.clone();
+        router.route(
+            "/api/plugin/webhook/:hook_id",
+            post(
+                move |Path(hook_id): Path<String>, headers: HeaderMap, body: Bytes| {
+                    let hooks = hooks.clone();
+                    async move { hooks.handle(&hook_id, &headers, &body).await }
+                },
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(signature: SignatureScheme) -> HookConfig {
+        HookConfig {
+            id: "github".to_string(),
+            secret: "s3cret".to_string(),
+            signature,
+            signature_header: default_signature_header(),
+            signature_prefix: String::new(),
+            kind: HookKind::Message,
+            agent_id: Some("agent.a".to_string()),
+            template: "{{headers.x-github-event}} on {{repository.full_name}}: {{commits.0.id}}{{missing}}".to_string(),
+            metadata: BTreeMap::from([("repo".to_string(), "{{repository.full_name}}".to_string())]),
+        }
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, b"s3cret");
+        cloto_shared::hex_encode(ring::hmac::sign(&key, body).as_ref())
+    }
+
+    #[test]
+    fn test_verify_signatures() {
+        let (tx, _rx) = mpsc::channel(1);
+        let plugin = WebhookPlugin::new(
+            vec![hook(SignatureScheme::Github), {
+                let mut h = hook(SignatureScheme::Stripe);
+                h.id = "stripe".to_string();
+                h
+            }],
+            tx,
+        )
+        .unwrap();
+        let body = br#"{"action":"opened"}"#;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Hub-Signature-256",
+            format!("sha256={}", sign(body)).parse().unwrap(),
+        );
+        let github = &plugin.inner.hooks["github"];
+        assert!(verify(github, &headers, body, 0).is_ok());
+        assert!(verify(github, &headers, b"tampered", 0).is_err());
+        assert!(verify(github, &HeaderMap::new(), body, 0).is_err());
+
+        let stripe = &plugin.inner.hooks["stripe"];
+        let mut signed = b"1700000000.".to_vec();
+        signed.extend_from_slice(body);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Stripe-Signature",
+            format!("t=1700000000,v1=deadbeef,v1={}", sign(&signed))
+                .parse()
+                .unwrap(),
+        );
+        assert!(verify(stripe, &headers, body, 1_700_000_100).is_ok());
+        assert_eq!(
+            verify(stripe, &headers, body, 1_700_001_000),
+            Err("timestamp outside tolerance")
+        );
+    }
+
+    #[test]
+    fn test_render_message_event() {
+        let payload = serde_json::json!({
+            "repository": { "full_name": "cloto/core" },
+            "commits": [{ "id": "abc123" }]
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert("X-GitHub-Event", "push".parse().unwrap());
+
+        let ClotoEventData::MessageReceived(msg) =
+            event_for(&hook(SignatureScheme::Github), &payload, &headers)
+        else {
+            panic!("expected MessageReceived");
+        };
+        assert_eq!(msg.content, "push on cloto/core: abc123");
+        assert_eq!(msg.target_agent.as_deref(), Some("agent.a"));
+        assert_eq!(msg.metadata["repo"], "cloto/core");
+        assert_eq!(msg.metadata["webhook_id"], "github");
+
+        assert_eq!(
+            render(
+                "{{a}} {{unterminated",
+                "h",
+                &serde_json::json!({"a": 1}),
+                &headers
+            ),
+            "1 {{unterminated"
+        );
+    }
+}
@@ -46,6 +46,7 @@ fn base_manifest(id: ClotoId, name: &str) -> PluginManifest {
         is_active: true,
         is_configured: true,
         required_config_keys: vec![],
+        config_schema: vec![],
         action_icon: None,
         action_target: None,
         icon_data: None,
@@ -54,6 +55,7 @@ fn base_manifest(id: ClotoId, name: &str) -> PluginManifest {
         required_permissions: vec![],
         provided_capabilities: vec![],
         provided_tools: vec![],
+        subscribes: vec![],
     }
 }
 
@@ -93,3 +95,38 @@ pub fn create_panicking_plugin(id: ClotoId) -> Arc<MockPlugin> {
         response: None,
     })
 }
+
+/// Mock plugin that only subscribes to the given event types.
+#[allow(dead_code)]
+pub fn create_subscribed_plugin(
+    id: ClotoId,
+    subscribes: &[&str],
+) -> (Arc<MockPlugin>, Arc<Mutex<Vec<ClotoEvent>>>) {
+    let received_events = Arc::new(Mutex::new(Vec::new()));
+    let mut manifest = base_manifest(id, "SubscribedPlugin");
+    manifest.subscribes = subscribes.iter().map(ToString::to_string).collect();
+    let plugin = Arc::new(MockPlugin {
+        manifest,
+        received_events: received_events.clone(),
+        should_panic: false,
+        response_delay: Duration::ZERO,
+        response: None,
+    });
+    (plugin, received_events)
+}
+
+/// Mock plugin that waits `delay`, then returns `response` as a new event.
+#[allow(dead_code)]
+pub fn create_responding_plugin(
+    id: ClotoId,
+    delay: Duration,
+    response: ClotoEventData,
+) -> Arc<MockPlugin> {
+    Arc::new(MockPlugin {
+        manifest: base_manifest(id, "RespondingPlugin"),
+        received_events: Arc::new(Mutex::new(Vec::new())),
+        should_panic: false,
+        response_delay: delay,
+        response: Some(response),
+    })
+}
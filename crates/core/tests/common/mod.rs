@@ -2,3 +2,7 @@ pub mod mock_plugin;
 pub use mock_plugin::create_mock_plugin;
 #[allow(unused_imports)]
 pub use mock_plugin::create_panicking_plugin;
+#[allow(unused_imports)]
+pub use mock_plugin::create_responding_plugin;
+#[allow(unused_imports)]
+pub use mock_plugin::create_subscribed_plugin;
@@ -117,3 +117,260 @@ async fn test_dispatch_with_no_plugins_is_safe() {
     // Should complete immediately with no plugins registered
     registry.dispatch_event(envelope, &event_tx).await;
 }
+
+#[tokio::test]
+async fn test_dispatch_skips_unsubscribed_plugins() {
+    use common::{create_mock_plugin, create_subscribed_plugin};
+
+    let registry = PluginRegistry::new(2, 10);
+    let (filtered, filtered_received) =
+        create_subscribed_plugin(ClotoId::new(), &["ConfigUpdated"]);
+    let (unfiltered, unfiltered_received) = create_mock_plugin(ClotoId::new());
+    {
+        let mut plugins = registry.plugins.write().await;
+        plugins.insert("filtered".into(), filtered as Arc<dyn cloto_shared::Plugin>);
+        plugins.insert(
+            "unfiltered".into(),
+            unfiltered as Arc<dyn cloto_shared::Plugin>,
+        );
+    }
+
+    let (event_tx, _event_rx) = tokio::sync::mpsc::channel::<EnvelopedEvent>(10);
+    for data in [
+        cloto_shared::ClotoEventData::SystemNotification("ignored".into()),
+        cloto_shared::ClotoEventData::ConfigUpdated {
+            plugin_id: "filtered".into(),
+            config: std::collections::HashMap::new(),
+        },
+    ] {
+        let envelope = EnvelopedEvent {
+            event: Arc::new(cloto_shared::ClotoEvent::new(data)),
+            issuer: None,
+            correlation_id: None,
+            depth: 0,
+        };
+        registry.dispatch_event(envelope, &event_tx).await;
+    }
+
+    let filtered_received = filtered_received.lock().await;
+    assert_eq!(filtered_received.len(), 1);
+    assert_eq!(filtered_received[0].data.type_name(), "ConfigUpdated");
+    assert_eq!(unfiltered_received.lock().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_misspelled_subscription_is_reported() {
+    use common::create_subscribed_plugin;
+
+    let registry = PluginRegistry::new(2, 10);
+    let (typo, _received) =
+        create_subscribed_plugin(ClotoId::new(), &["ConfigUpdated", "MesageReceived"]);
+    registry
+        .plugins
+        .write()
+        .await
+        .insert("typo".into(), typo as Arc<dyn cloto_shared::Plugin>);
+
+    assert_eq!(
+        registry.plugins.unknown_subscriptions("typo"),
+        vec!["MesageReceived".to_string()]
+    );
+    assert!(registry.plugins.unknown_subscriptions("missing").is_empty());
+}
+
+#[tokio::test]
+async fn test_returned_events_follow_plugin_id_order() {
+    use cloto_shared::ClotoEventData;
+    use common::create_responding_plugin;
+    use std::time::Duration;
+
+    let registry = PluginRegistry::new(1, 5);
+    let notification = |text: &str| ClotoEventData::SystemNotification(text.into());
+    {
+        let mut plugins = registry.plugins.write().await;
+        // Never finishes within the 1s timeout
+        plugins.insert(
+            "a_stalled".into(),
+            create_responding_plugin(ClotoId::new(), Duration::from_secs(30), notification("a"))
+                as Arc<dyn cloto_shared::Plugin>,
+        );
+        // Finishes after "c", but its event must still come first
+        plugins.insert(
+            "b_slow".into(),
+            create_responding_plugin(
+                ClotoId::new(),
+                Duration::from_millis(300),
+                notification("b"),
+            ) as Arc<dyn cloto_shared::Plugin>,
+        );
+        plugins.insert(
+            "c_fast".into(),
+            create_responding_plugin(ClotoId::new(), Duration::ZERO, notification("c"))
+                as Arc<dyn cloto_shared::Plugin>,
+        );
+    }
+
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel::<EnvelopedEvent>(10);
+    let envelope = EnvelopedEvent {
+        event: Arc::new(cloto_shared::ClotoEvent::new(notification("ping"))),
+        issuer: None,
+        correlation_id: None,
+        depth: 0,
+    };
+    let started = std::time::Instant::now();
+    registry.dispatch_event(envelope, &event_tx).await;
+    // Bounded by the per-plugin timeout, not the stalled plugin's 30s
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let mut texts = Vec::new();
+    for _ in 0..2 {
+        let envelope = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+            .await
+            .expect("returned event not re-dispatched")
+            .unwrap();
+        assert_eq!(envelope.depth, 1);
+        match &envelope.event.data {
+            ClotoEventData::SystemNotification(text) => texts.push(text.clone()),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+    assert_eq!(texts, ["b", "c"]);
+    assert!(event_rx.try_recv().is_err());
+}
+
+/// Logs the start and end of each message it handles; messages starting
+/// with "slow" take 300ms.
+struct TurnLogPlugin {
+    log: Arc<tokio::sync::Mutex<Vec<String>>>,
+}
+
+impl cloto_shared::PluginCast for TurnLogPlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl cloto_shared::Plugin for TurnLogPlugin {
+    fn manifest(&self) -> cloto_shared::PluginManifest {
+        cloto_shared::PluginManifest {
+            id: "plugin.turn_log".to_string(),
+            name: "TurnLog".to_string(),
+            description: String::new(),
+            version: "1.0".to_string(),
+            category: cloto_shared::PluginCategory::Agent,
+            service_type: cloto_shared::ServiceType::Reasoning,
+            tags: vec![],
+            is_active: true,
+            is_configured: true,
+            required_config_keys: vec![],
+            config_schema: vec![],
+            action_icon: None,
+            action_target: None,
+            icon_data: None,
+            magic_seal: 0x5645_5253,
+            sdk_version: "1.0".to_string(),
+            required_permissions: vec![],
+            provided_capabilities: vec![],
+            provided_tools: vec![],
+            subscribes: vec!["MessageReceived".to_string()],
+        }
+    }
+
+    async fn on_event(
+        &self,
+        event: &cloto_shared::ClotoEvent,
+    ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+        if let cloto_shared::ClotoEventData::MessageReceived(msg) = &event.data {
+            self.log.lock().await.push(format!("start {}", msg.content));
+            if msg.content.starts_with("slow") {
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            }
+            self.log.lock().await.push(format!("end {}", msg.content));
+        }
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn test_messages_to_one_agent_are_handled_in_order() {
+    use cloto_core::events::{EventProcessor, SerializedEvent};
+    use cloto_core::managers::{AgentManager, PluginManager};
+
+    let pool = cloto_core::db::DbPool::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    cloto_core::db::init_db(&pool, "sqlite::memory:")
+        .await
+        .unwrap();
+    let registry = Arc::new(PluginRegistry::new(5, 10));
+    let log = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+    registry.plugins.write().await.insert(
+        "plugin.turn_log".into(),
+        Arc::new(TurnLogPlugin { log: log.clone() }) as Arc<dyn cloto_shared::Plugin>,
+    );
+    let (tx_broadcast, _rx_broadcast) = tokio::sync::broadcast::channel::<SerializedEvent>(100);
+    let processor = EventProcessor::new(
+        registry,
+        Arc::new(PluginManager::new(pool.clone(), vec![], 5, 10).unwrap()),
+        AgentManager::new(pool),
+        tx_broadcast,
+        Arc::new(tokio::sync::RwLock::new(std::collections::VecDeque::new())),
+        Arc::new(cloto_core::managers::SystemMetrics::new()),
+        100,
+        24,
+        None,
+    );
+    let (event_tx, event_rx) = tokio::sync::mpsc::channel::<EnvelopedEvent>(16);
+    let loop_tx = event_tx.clone();
+    tokio::spawn(async move { processor.process_loop(event_rx, loop_tx).await });
+
+    for (agent, content) in [
+        ("agent.a", "slow first"),
+        ("agent.a", "second"),
+        ("agent.b", "other agent"),
+        ("", "slow untargeted"),
+        ("", "untargeted"),
+    ] {
+        let mut msg = cloto_shared::ClotoMessage::new(
+            cloto_shared::MessageSource::User {
+                id: "user".into(),
+                name: "User".into(),
+            },
+            content.to_string(),
+        );
+        if !agent.is_empty() {
+            msg.metadata
+                .insert("target_agent_id".into(), agent.to_string());
+        }
+        event_tx
+            .send(EnvelopedEvent::system(
+                cloto_shared::ClotoEventData::MessageReceived(msg),
+            ))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+
+    let log = log.lock().await.clone();
+    let position = |entry: &str| log.iter().position(|e| e == entry).expect(entry);
+    assert_eq!(log.len(), 10, "{:?}", log);
+    // agent.a's second message waits for its first...
+    assert!(
+        position("end slow first") < position("start second"),
+        "{:?}",
+        log
+    );
+    // ...without holding up agent.b
+    assert!(
+        position("end other agent") < position("end slow first"),
+        "{:?}",
+        log
+    );
+    // Messages with no target agent are not queued behind each other
+    assert!(
+        position("end untargeted") < position("end slow untargeted"),
+        "{:?}",
+        log
+    );
+}
@@ -38,6 +38,7 @@ async fn test_user_message_to_response_flow() {
         content: "Hello, agent!".to_string(),
         timestamp: chrono::Utc::now(),
         metadata: std::collections::HashMap::new(),
+        attachments: Vec::new(),
     };
 
     let event = Arc::new(ClotoEvent::new(ClotoEventData::MessageReceived(
@@ -45,7 +46,7 @@ async fn test_user_message_to_response_flow() {
     )));
 
     // Send event to broadcast channel
-    state.tx.send(event.clone()).unwrap();
+    state.tx.send(event.clone().into()).unwrap();
 
     // Receive the event (should be the same we sent)
     let received = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx.recv())
@@ -68,7 +69,7 @@ async fn test_permission_grant_flow() {
     let state = create_test_app_state().await;
 
     // Insert a pending permission request
-    sqlx::query("INSERT INTO permission_requests (request_id, plugin_id, permission_type, justification, status, created_at) VALUES (?, ?, ?, ?, ?, ?)")
+    sqlx::query("INSERT INTO permission_requests (request_id, plugin_id, permission_type, justification, status, created_at) VALUES ($1, $2, $3, $4, $5, $6)")
         .bind("req-123")
         .bind("test.plugin")
         .bind("NetworkAccess")
@@ -93,7 +94,7 @@ async fn test_permission_grant_flow() {
         permission: cloto_shared::Permission::NetworkAccess,
     }));
 
-    state.tx.send(event.clone()).unwrap();
+    state.tx.send(event.clone().into()).unwrap();
 
     // Receive the event
     let received = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx.recv())
@@ -115,7 +116,7 @@ async fn test_permission_grant_flow() {
 
     // Verify database was updated
     let status: String =
-        sqlx::query_scalar("SELECT status FROM permission_requests WHERE request_id = ?")
+        sqlx::query_scalar("SELECT status FROM permission_requests WHERE request_id = $1")
             .bind("req-123")
             .fetch_one(&state.pool)
             .await
@@ -130,7 +131,7 @@ async fn test_config_update_flow() {
 
     // Insert initial config
     sqlx::query(
-        "INSERT INTO plugin_configs (plugin_id, config_key, config_value) VALUES (?, ?, ?)",
+        "INSERT INTO plugin_configs (plugin_id, config_key, config_value) VALUES ($1, $2, $3)",
     )
     .bind("test.plugin")
     .bind("api_key")
@@ -158,7 +159,7 @@ async fn test_config_update_flow() {
         config,
     }));
 
-    state.tx.send(event.clone()).unwrap();
+    state.tx.send(event.clone().into()).unwrap();
 
     // Receive the event
     let received = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx.recv())
@@ -177,7 +178,7 @@ async fn test_config_update_flow() {
 
     // Verify database was updated
     let value: String = sqlx::query_scalar(
-        "SELECT config_value FROM plugin_configs WHERE plugin_id = ? AND config_key = ?",
+        "SELECT config_value FROM plugin_configs WHERE plugin_id = $1 AND config_key = $2",
     )
     .bind("test.plugin")
     .bind("api_key")
@@ -226,7 +227,7 @@ async fn test_agent_creation_with_memory_context() {
 
     // Verify the agent was stored in the database
     let stored_agent: (String, String) =
-        sqlx::query_as("SELECT name, description FROM agents WHERE name = ?")
+        sqlx::query_as("SELECT name, description FROM agents WHERE name = $1")
             .bind("Memory Test Agent")
             .fetch_one(&state.pool)
             .await
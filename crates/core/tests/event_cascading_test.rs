@@ -1,10 +1,10 @@
+use cloto_core::db::DbPool;
 use cloto_core::{
-    events::EventProcessor,
+    events::{EventProcessor, SerializedEvent},
     managers::{AgentManager, PluginManager, PluginRegistry},
     EnvelopedEvent,
 };
 use cloto_shared::{ClotoEvent, Plugin, PluginCast, PluginManifest, ServiceType};
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
@@ -36,6 +36,7 @@ impl Plugin for PingPlugin {
             is_active: true,
             is_configured: true,
             required_config_keys: vec![],
+            config_schema: vec![],
             action_icon: None,
             action_target: None,
             icon_data: None,
@@ -44,6 +45,7 @@ impl Plugin for PingPlugin {
             required_permissions: vec![],
             provided_capabilities: vec![],
             provided_tools: vec![],
+            subscribes: vec![],
         }
     }
 
@@ -68,7 +70,7 @@ impl Plugin for PingPlugin {
 
 #[tokio::test]
 async fn test_event_cascading_protection() {
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
@@ -98,7 +100,7 @@ async fn test_event_cascading_protection() {
         );
     }
 
-    let (tx_broadcast, mut rx_broadcast) = broadcast::channel::<Arc<ClotoEvent>>(1000);
+    let (tx_broadcast, mut rx_broadcast) = broadcast::channel::<SerializedEvent>(1000);
     let (tx_internal, rx_internal) = mpsc::channel::<EnvelopedEvent>(1000);
 
     let metrics = Arc::new(cloto_core::managers::SystemMetrics::new());
@@ -162,3 +164,125 @@ async fn test_event_cascading_protection() {
         count
     );
 }
+
+/// Answers every delivered agent message by messaging the sender back,
+/// like two agents that keep replying to each other.
+struct ReplyPlugin;
+impl PluginCast for ReplyPlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+#[async_trait::async_trait]
+impl Plugin for ReplyPlugin {
+    fn manifest(&self) -> PluginManifest {
+        let mut manifest = PingPlugin {
+            id: "plugin.reply".to_string(),
+            target_id: String::new(),
+        }
+        .manifest();
+        manifest.name = "Reply".to_string();
+        manifest
+    }
+
+    async fn on_event(
+        &self,
+        event: &ClotoEvent,
+    ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+        if let cloto_shared::ClotoEventData::MessageReceived(msg) = &event.data {
+            if let (cloto_shared::MessageSource::Agent { id }, Some(to)) =
+                (&msg.source, &msg.target_agent)
+            {
+                return Ok(Some(cloto_shared::ClotoEventData::AgentMessage {
+                    from_agent: to.clone(),
+                    to_agent: id.clone(),
+                    content: format!("re: {}", msg.content),
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn test_agent_message_routing_stops_at_depth_limit() {
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+    cloto_core::db::init_db(&pool, "sqlite::memory:")
+        .await
+        .unwrap();
+
+    let plugin_manager = Arc::new(PluginManager::new(pool.clone(), vec![], 1, 10).unwrap());
+    let agent_manager = AgentManager::new(pool.clone());
+    let helper = agent_manager
+        .create_agent(
+            "Helper",
+            "second agent",
+            "mind.deepseek",
+            std::collections::HashMap::new(),
+            vec![],
+            None,
+        )
+        .await
+        .unwrap();
+    let registry = Arc::new(PluginRegistry::new(1, 6));
+    registry
+        .plugins
+        .write()
+        .await
+        .insert("plugin.reply".to_string(), Arc::new(ReplyPlugin));
+
+    let (tx_broadcast, mut rx_broadcast) = broadcast::channel::<SerializedEvent>(1000);
+    let (tx_internal, rx_internal) = mpsc::channel::<EnvelopedEvent>(1000);
+    let processor = EventProcessor::new(
+        registry.clone(),
+        plugin_manager,
+        agent_manager,
+        tx_broadcast,
+        Arc::new(tokio::sync::RwLock::new(VecDeque::new())),
+        Arc::new(cloto_core::managers::SystemMetrics::new()),
+        1000,
+        24,
+        None,
+    );
+    let tx_internal_for_loop = tx_internal.clone();
+    tokio::spawn(async move {
+        processor
+            .process_loop(rx_internal, tx_internal_for_loop)
+            .await;
+    });
+
+    tx_internal
+        .send(EnvelopedEvent::system(
+            cloto_shared::ClotoEventData::AgentMessage {
+                from_agent: "agent.cloto_default".to_string(),
+                to_agent: helper.clone(),
+                content: "hello".to_string(),
+            },
+        ))
+        .await
+        .unwrap();
+
+    let mut deliveries = Vec::new();
+    let timeout = tokio::time::sleep(std::time::Duration::from_secs(2));
+    tokio::pin!(timeout);
+    loop {
+        tokio::select! {
+            () = &mut timeout => break,
+            Ok(event) = rx_broadcast.recv() => {
+                let value: serde_json::Value = serde_json::from_str(&event.json).unwrap();
+                if value["type"] == "MessageReceived" {
+                    deliveries.push(value["data"].clone());
+                }
+            }
+        }
+    }
+
+    // Each hop costs two depth levels (delivery + reply), so a limit of 6
+    // lets the exchange go back and forth a few times and then stop
+    assert!(deliveries.len() >= 2, "{:?}", deliveries);
+    assert!(deliveries.len() < 6, "{:?}", deliveries);
+    assert_eq!(deliveries[0]["target_agent"], helper.as_str());
+    assert_eq!(deliveries[0]["content"], "hello");
+    assert_eq!(deliveries[1]["target_agent"], "agent.cloto_default");
+    assert_eq!(deliveries[1]["content"], "re: hello");
+}
@@ -0,0 +1,131 @@
+use cloto_core::db::DbPool;
+use cloto_core::managers::PluginRegistry;
+use cloto_core::EnvelopedEvent;
+use cloto_shared::{ClotoEvent, ClotoEventData, Plugin, PluginCast, PluginManifest, ServiceType};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Fails its first `failures` events, then handles everything.
+struct FlakyPlugin {
+    id: String,
+    failures: u32,
+    calls: AtomicU32,
+}
+impl PluginCast for FlakyPlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+#[async_trait::async_trait]
+impl Plugin for FlakyPlugin {
+    fn manifest(&self) -> PluginManifest {
+        PluginManifest {
+            id: self.id.clone(),
+            name: "Flaky".to_string(),
+            description: String::new(),
+            version: "1.0".to_string(),
+            category: cloto_shared::PluginCategory::Tool,
+            service_type: ServiceType::Reasoning,
+            tags: vec![],
+            is_active: true,
+            is_configured: true,
+            required_config_keys: vec![],
+            config_schema: vec![],
+            action_icon: None,
+            action_target: None,
+            icon_data: None,
+            magic_seal: 0x5645_5253,
+            sdk_version: "1.0".to_string(),
+            required_permissions: vec![],
+            provided_capabilities: vec![],
+            provided_tools: vec![],
+            subscribes: vec![],
+        }
+    }
+
+    async fn on_event(&self, _event: &ClotoEvent) -> anyhow::Result<Option<ClotoEventData>> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if call <= self.failures {
+            anyhow::bail!("transient failure {}", call);
+        }
+        Ok(None)
+    }
+}
+
+fn flaky(id: &str, failures: u32) -> Arc<FlakyPlugin> {
+    Arc::new(FlakyPlugin {
+        id: id.to_string(),
+        failures,
+        calls: AtomicU32::new(0),
+    })
+}
+
+#[tokio::test]
+async fn test_failed_events_are_retried_then_dead_lettered() {
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+    cloto_core::db::init_db(&pool, "sqlite::memory:")
+        .await
+        .unwrap();
+
+    let mut registry = PluginRegistry::new(5, 10);
+    registry.set_event_retry(2, 1);
+    registry.set_dead_letter_pool(pool.clone());
+    let registry = Arc::new(registry);
+
+    // Recovers on the second retry; fails every attempt
+    let recovers = flaky("plugin.recovers", 2);
+    let broken = flaky("plugin.broken", 4);
+    {
+        let mut plugins = registry.plugins.write().await;
+        plugins.insert("plugin.recovers".to_string(), recovers.clone());
+        plugins.insert("plugin.broken".to_string(), broken.clone());
+    }
+
+    let (event_tx, _event_rx) = mpsc::channel::<EnvelopedEvent>(10);
+    registry
+        .dispatch_event(
+            EnvelopedEvent::system(ClotoEventData::SystemNotification("hello".to_string())),
+            &event_tx,
+        )
+        .await;
+    assert_eq!(recovers.calls.load(Ordering::SeqCst), 3);
+    assert_eq!(broken.calls.load(Ordering::SeqCst), 3);
+
+    // The dead letter is written in the background
+    let mut dead = Vec::new();
+    for _ in 0..50 {
+        dead = cloto_core::db::list_dead_letter_events(&pool, true, 10)
+            .await
+            .unwrap();
+        if !dead.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(dead.len(), 1);
+    let row = &dead[0];
+    assert_eq!(row.plugin_id, "plugin.broken");
+    assert_eq!(row.event_type, "SystemNotification");
+    assert_eq!(row.attempts, 3);
+    assert!(row.error.contains("transient failure 3"));
+
+    // Replay fails while the plugin still fails, then succeeds
+    assert!(registry.replay_dead_letter(row, &event_tx).await.is_err());
+    assert!(registry.replay_dead_letter(row, &event_tx).await.is_ok());
+    assert_eq!(broken.calls.load(Ordering::SeqCst), 5);
+
+    assert!(
+        cloto_core::db::mark_dead_letter_event_replayed(&pool, &row.id)
+            .await
+            .unwrap()
+    );
+    assert!(cloto_core::db::list_dead_letter_events(&pool, true, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    let all = cloto_core::db::list_dead_letter_events(&pool, false, 10)
+        .await
+        .unwrap();
+    assert!(all[0].replayed_at.is_some());
+}
@@ -1,7 +1,7 @@
-use cloto_core::events::EventProcessor;
+use cloto_core::db::DbPool;
+use cloto_core::events::{EventProcessor, SerializedEvent};
 use cloto_core::managers::{AgentManager, PluginManager, PluginRegistry, SystemMetrics};
 use cloto_shared::{ClotoEvent, ClotoEventData};
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
@@ -9,8 +9,8 @@ use tokio::sync::{broadcast, RwLock};
 /// Helper to create EventProcessor for testing
 async fn create_test_processor(
     max_history_size: usize,
-) -> (Arc<EventProcessor>, Arc<RwLock<VecDeque<Arc<ClotoEvent>>>>) {
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+) -> (Arc<EventProcessor>, Arc<RwLock<VecDeque<SerializedEvent>>>) {
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
@@ -48,7 +48,7 @@ async fn test_event_history_size_limit() {
             let event = Arc::new(ClotoEvent::new(ClotoEventData::SystemNotification(
                 format!("Event {}", i),
             )));
-            hist.push_back(event);
+            hist.push_back(event.into());
 
             // Apply size limit
             if hist.len() > 1000 {
@@ -89,7 +89,7 @@ async fn test_time_based_cleanup() {
                 i
             )));
             event.timestamp = old_time; // Set to old timestamp
-            hist.push_back(Arc::new(event));
+            hist.push_back(Arc::new(event).into());
         }
 
         // Add 10 recent events (1 hour ago)
@@ -100,7 +100,7 @@ async fn test_time_based_cleanup() {
                 i
             )));
             event.timestamp = recent_time;
-            hist.push_back(Arc::new(event));
+            hist.push_back(Arc::new(event).into());
         }
     }
 
@@ -145,7 +145,7 @@ async fn test_configurable_history_size() {
             let event = Arc::new(ClotoEvent::new(ClotoEventData::SystemNotification(
                 format!("Event {}", i),
             )));
-            hist.push_back(event);
+            hist.push_back(event.into());
 
             // Apply size limit (500)
             if hist.len() > 500 {
@@ -180,7 +180,7 @@ async fn test_cleanup_task_integration() {
             let mut event =
                 ClotoEvent::new(ClotoEventData::SystemNotification(format!("Old {}", i)));
             event.timestamp = old_time;
-            hist.push_back(Arc::new(event));
+            hist.push_back(Arc::new(event).into());
         }
     }
 
@@ -16,26 +16,165 @@ fn create_test_router(state: Arc<AppState>) -> axum::Router {
     let admin_routes = axum::Router::new()
         .route("/agents", post(handlers::create_agent))
         .route("/agents/:id", post(handlers::update_agent))
+        .route(
+            "/agents/:id/plugins",
+            get(handlers::get_agent_plugins).put(handlers::put_agent_plugins),
+        )
         .route("/plugins/:id/config", post(handlers::update_plugin_config))
+        .route("/plugins/:id/reload", post(handlers::reload_plugin))
         .route(
             "/permissions/:id/approve",
             post(handlers::approve_permission),
-        );
+        )
+        .route("/audit", get(handlers::get_audit_logs))
+        .route("/tools/invocations", get(handlers::get_tool_invocations))
+        .route(
+            "/mcp/servers/:name/models",
+            get(handlers::get_mcp_server_models),
+        )
+        .route("/tool-calls/pending", get(handlers::get_pending_tool_calls))
+        .route("/tool-calls/:id/approve", post(handlers::approve_tool_call))
+        .route("/tool-calls/:id/deny", post(handlers::deny_tool_call))
+        .route(
+            "/events/dead-letter",
+            get(handlers::list_dead_letter_events),
+        )
+        .route(
+            "/events/dead-letter/:id/replay",
+            post(handlers::replay_dead_letter_event),
+        )
+        .route(
+            "/system/keys",
+            get(handlers::list_api_keys).post(handlers::create_api_key),
+        )
+        .route(
+            "/system/keys/:id",
+            axum::routing::delete(handlers::delete_api_key),
+        )
+        .route(
+            "/auth/users",
+            get(handlers::list_users).post(handlers::create_user),
+        )
+        .route(
+            "/auth/users/:id",
+            axum::routing::delete(handlers::delete_user),
+        )
+        .route("/auth/login", post(handlers::login))
+        .route("/auth/logout", post(handlers::logout));
 
     let api_routes = axum::Router::new()
         .route("/chat", post(handlers::chat_handler))
+        .route(
+            "/chat/:agent_id/messages",
+            get(handlers::chat::get_messages).post(handlers::chat::post_message),
+        )
+        .route(
+            "/chat/:agent_id/sessions",
+            get(handlers::chat::list_sessions).post(handlers::chat::create_session),
+        )
+        .route(
+            "/chat/:agent_id/sessions/:session_id/activate",
+            post(handlers::chat::activate_session),
+        )
+        .route(
+            "/chat/:agent_id/sessions/:session_id/archive",
+            post(handlers::chat::archive_session),
+        )
         .route("/agents", get(handlers::get_agents))
+        .route("/plugins", get(handlers::get_plugins))
         .route("/plugins/:id/config", get(handlers::get_plugin_config))
-        .merge(admin_routes)
-        .with_state(state);
+        .route("/plugins/:id/icon", get(handlers::get_plugin_icon))
+        .route("/history", get(handlers::get_history))
+        .route("/auth/session", get(handlers::get_session))
+        .merge(admin_routes);
+
+    let openai_routes = axum::Router::new()
+        .route(
+            "/chat/completions",
+            post(handlers::openai::chat_completions),
+        )
+        .route("/models", get(handlers::openai::list_models))
+        .with_state(state.clone());
+
+    axum::Router::new()
+        .nest("/api", api_routes.with_state(state))
+        .nest("/v1", openai_routes)
+}
+
+/// Rust plugin that only advertises capabilities, for binding tests.
+struct CapabilityPlugin {
+    id: &'static str,
+    capabilities: Vec<cloto_shared::CapabilityType>,
+}
+
+impl cloto_shared::PluginCast for CapabilityPlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl cloto_shared::Plugin for CapabilityPlugin {
+    fn manifest(&self) -> cloto_shared::PluginManifest {
+        cloto_shared::PluginManifest {
+            id: self.id.to_string(),
+            name: self.id.to_string(),
+            description: String::new(),
+            version: "1.0".to_string(),
+            category: cloto_shared::PluginCategory::Agent,
+            service_type: cloto_shared::ServiceType::Reasoning,
+            tags: vec![],
+            is_active: true,
+            is_configured: true,
+            required_config_keys: vec![],
+            config_schema: vec![],
+            action_icon: None,
+            action_target: None,
+            icon_data: None,
+            magic_seal: 0x5645_5253,
+            sdk_version: "1.0".to_string(),
+            required_permissions: vec![],
+            provided_capabilities: self.capabilities.clone(),
+            provided_tools: vec![],
+            subscribes: vec![],
+        }
+    }
 
-    axum::Router::new().nest("/api", api_routes)
+    async fn on_event(
+        &self,
+        _event: &cloto_shared::ClotoEvent,
+    ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+        Ok(None)
+    }
+}
+
+async fn add_capability_plugin(
+    state: &AppState,
+    id: &'static str,
+    capabilities: Vec<cloto_shared::CapabilityType>,
+) {
+    state.registry.plugins.write().await.insert(
+        id.to_string(),
+        Arc::new(CapabilityPlugin { id, capabilities }),
+    );
 }
 
 #[tokio::test]
 async fn test_create_agent_success() {
     let state = create_test_app_state(Some("test-key".to_string())).await;
-    let app = create_test_router(state);
+    add_capability_plugin(
+        &state,
+        "mind.deepseek",
+        vec![cloto_shared::CapabilityType::Reasoning],
+    )
+    .await;
+    add_capability_plugin(
+        &state,
+        "memory.test",
+        vec![cloto_shared::CapabilityType::Memory],
+    )
+    .await;
+    let app = create_test_router(state.clone());
 
     let payload = json!({
         "name": "Test Agent",
@@ -59,6 +198,69 @@ async fn test_create_agent_success() {
         .expect("send request");
 
     assert_eq!(response.status(), StatusCode::OK);
+
+    // The default [Reasoning, Memory] capabilities are bound to their providers
+    let bound: Vec<(String,)> = sqlx::query_as(
+        "SELECT plugin_id FROM agent_plugins WHERE agent_id = 'agent.test_agent' ORDER BY pos_x",
+    )
+    .fetch_all(&state.pool)
+    .await
+    .expect("query bindings");
+    let bound: Vec<&str> = bound.iter().map(|r| r.0.as_str()).collect();
+    assert_eq!(bound, ["mind.deepseek", "memory.test"]);
+}
+
+#[tokio::test]
+async fn test_create_agent_missing_capabilities() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    add_capability_plugin(
+        &state,
+        "mind.deepseek",
+        vec![cloto_shared::CapabilityType::Reasoning],
+    )
+    .await;
+    let app = create_test_router(state.clone());
+
+    let payload = json!({
+        "name": "Seeing Agent",
+        "description": "Needs eyes",
+        "default_engine": "mind.deepseek",
+        "required_capabilities": ["Reasoning", "Memory", "Vision"]
+    });
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/agents")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("X-API-Key", "test-key")
+                .body(Body::from(
+                    serde_json::to_string(&payload).expect("serialize JSON"),
+                ))
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body = String::from_utf8_lossy(&body);
+    assert!(
+        body.contains("Memory, Vision"),
+        "unexpected error: {}",
+        body
+    );
+
+    // Nothing is created when binding fails
+    let count: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM agents WHERE id = 'agent.seeing_agent'")
+            .fetch_one(&state.pool)
+            .await
+            .expect("count agents");
+    assert_eq!(count.0, 0);
 }
 
 #[tokio::test]
@@ -89,13 +291,94 @@ async fn test_create_agent_invalid_payload() {
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+#[tokio::test]
+async fn test_update_agent_system_prompt() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let agent_id = state
+        .agent_manager
+        .create_agent(
+            "Persona",
+            "Has a custom prompt",
+            "mind.deepseek",
+            std::collections::HashMap::new(),
+            vec![],
+            None,
+        )
+        .await
+        .expect("create agent");
+    let app = create_test_router(state.clone());
+    let uri = format!("/api/agents/{}", agent_id);
+
+    let update = |system_prompt: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("X-API-Key", "test-key")
+            .body(Body::from(
+                json!({ "metadata": {}, "system_prompt": system_prompt }).to_string(),
+            ))
+            .expect("build request")
+    };
+
+    // Unknown template variables are rejected
+    let response = app
+        .clone()
+        .oneshot(update("You are {agent_name}, born {birthday}."))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let template = "You are {agent_name} ({agent_id}). Today is {date}.\n{memories}";
+    let response = app
+        .clone()
+        .oneshot(update(template))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let (agent, _) = state
+        .agent_manager
+        .get_agent_config(&agent_id)
+        .await
+        .expect("get agent");
+    assert_eq!(agent.system_prompt.as_deref(), Some(template));
+
+    let rendered = cloto_shared::llm::build_chat_messages(
+        &agent,
+        &cloto_shared::ClotoMessage::new(
+            cloto_shared::MessageSource::User {
+                id: "user".to_string(),
+                name: "User".to_string(),
+            },
+            "hi".to_string(),
+        ),
+        &[],
+    );
+    let system = rendered[0]["content"].as_str().expect("system prompt");
+    assert!(
+        system.starts_with("You are Persona (agent.persona). Today is "),
+        "unexpected prompt: {}",
+        system
+    );
+
+    // An empty prompt restores the built-in one
+    let response = app.oneshot(update("")).await.expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let (agent, _) = state
+        .agent_manager
+        .get_agent_config(&agent_id)
+        .await
+        .expect("get agent");
+    assert_eq!(agent.system_prompt, None);
+}
+
 #[tokio::test]
 async fn test_update_plugin_config_success() {
     let state = create_test_app_state(Some("test-key".to_string())).await;
 
     // Insert a test plugin config first
     sqlx::query(
-        "INSERT INTO plugin_configs (plugin_id, config_key, config_value) VALUES (?, ?, ?)",
+        "INSERT INTO plugin_configs (plugin_id, config_key, config_value) VALUES ($1, $2, $3)",
     )
     .bind("test.plugin")
     .bind("api_key")
@@ -158,12 +441,109 @@ async fn test_update_plugin_config_nonexistent_plugin() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+/// Plugin declaring a typed config schema.
+struct SchemaPlugin;
+
+impl cloto_shared::PluginCast for SchemaPlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl cloto_shared::Plugin for SchemaPlugin {
+    fn manifest(&self) -> cloto_shared::PluginManifest {
+        use cloto_shared::{ConfigField, ConfigFieldType};
+        let mut manifest = CapabilityPlugin {
+            id: "test.schema",
+            capabilities: vec![],
+        }
+        .manifest();
+        manifest.config_schema = vec![
+            ConfigField::new("api_key", ConfigFieldType::Secret).required(),
+            ConfigField::new(
+                "max_tokens",
+                ConfigFieldType::Int {
+                    min: Some(1),
+                    max: Some(8192),
+                },
+            )
+            .with_default("1024"),
+        ];
+        manifest
+    }
+
+    async fn on_event(
+        &self,
+        _event: &cloto_shared::ClotoEvent,
+    ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn test_plugin_config_schema_validation() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    state
+        .registry
+        .plugins
+        .write()
+        .await
+        .insert("test.schema".to_string(), Arc::new(SchemaPlugin));
+    let app = create_test_router(state);
+
+    let post = |key: &str, value: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/plugins/test.schema/config")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("X-API-Key", "test-key")
+            .body(Body::from(
+                json!({ "key": key, "value": value }).to_string(),
+            ))
+            .expect("build request")
+    };
+
+    for (key, value, status) in [
+        ("max_tokens", "99999", StatusCode::BAD_REQUEST),
+        ("unknown", "x", StatusCode::BAD_REQUEST),
+        ("api_key", "", StatusCode::BAD_REQUEST),
+        ("api_key", "sk-secret", StatusCode::OK),
+        ("api_key", "***", StatusCode::OK),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(post(key, value))
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), status, "{key}={value}");
+    }
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/plugins/test.schema/config")
+                .header("X-API-Key", "test-key")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let config: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert_eq!(config["api_key"], "***");
+    assert_eq!(config["max_tokens"], "1024");
+}
+
 #[tokio::test]
 async fn test_chat_handler_routes_to_agent() {
     let state = create_test_app_state(None).await;
 
     // Create a test agent first
-    sqlx::query("INSERT INTO agents (id, name, description, status, default_engine_id, metadata) VALUES (?, ?, ?, ?, ?, ?)")
+    sqlx::query("INSERT INTO agents (id, name, description, status, default_engine_id, metadata) VALUES ($1, $2, $3, $4, $5, $6)")
         .bind("agent.test")
         .bind("Test Agent")
         .bind("Test")
@@ -247,7 +627,7 @@ async fn test_grant_permission_success() {
     let state = create_test_app_state(Some("test-key".to_string())).await;
 
     // Insert a pending permission request
-    sqlx::query("INSERT INTO permission_requests (request_id, plugin_id, permission_type, justification, status, created_at) VALUES (?, ?, ?, ?, ?, ?)")
+    sqlx::query("INSERT INTO permission_requests (request_id, plugin_id, permission_type, justification, status, created_at) VALUES ($1, $2, $3, $4, $5, $6)")
         .bind("req-123")
         .bind("test.plugin")
         .bind("NetworkAccess")
@@ -281,3 +661,1357 @@ async fn test_grant_permission_success() {
 
     assert_eq!(response.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn test_history_pagination() {
+    let state = create_test_app_state(None).await;
+    {
+        let mut history = state.event_history.write().await;
+        for i in 0..5 {
+            let event = cloto_shared::ClotoEvent::new(
+                cloto_shared::ClotoEventData::SystemNotification(format!("Event {}", i)),
+            );
+            history.push_back(Arc::new(event).into());
+        }
+    }
+    let app = create_test_router(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/history?limit=2&offset=1")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "5");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let events: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("parse JSON");
+    let messages: Vec<_> = events.iter().map(|e| e["data"].as_str().unwrap()).collect();
+    assert_eq!(messages, vec!["Event 2", "Event 3"]);
+}
+
+#[tokio::test]
+async fn test_history_filters() {
+    let state = create_test_app_state(None).await;
+    let trace = cloto_shared::ClotoId::new();
+    {
+        let mut history = state.event_history.write().await;
+        for i in 0..3 {
+            let event = cloto_shared::ClotoEvent::new(
+                cloto_shared::ClotoEventData::SystemNotification(format!("Event {}", i)),
+            );
+            history.push_back(Arc::new(event).into());
+        }
+        let traced = cloto_shared::ClotoEvent::with_trace(
+            trace,
+            cloto_shared::ClotoEventData::SystemNotification("Traced".to_string()),
+        );
+        history.push_back(Arc::new(traced).into());
+    }
+    let app = create_test_router(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/history?type=SystemNotification&trace_id={}",
+                    trace
+                ))
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "1");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let events: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("parse JSON");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["data"], "Traced");
+}
+
+#[tokio::test]
+async fn test_history_from_event_journal() {
+    let mut state = create_test_app_state(None).await;
+    Arc::get_mut(&mut state)
+        .expect("unshared state")
+        .config
+        .event_journal_enabled = true;
+
+    // Journaled events outlive the (empty) ring buffer
+    let entries: Vec<_> = (0..5)
+        .map(|i| {
+            let event = cloto_shared::ClotoEvent::new(
+                cloto_shared::ClotoEventData::SystemNotification(format!("Event {}", i)),
+            );
+            cloto_core::db::JournalEntry {
+                trace_id: event.trace_id.to_string(),
+                event_type: event.data.type_name(),
+                event: serde_json::to_string(&event).unwrap().into(),
+                created_at: 1_000 * i64::from(i),
+            }
+        })
+        .collect();
+    cloto_core::db::insert_events(&state.pool, &entries)
+        .await
+        .expect("journal events");
+    let app = create_test_router(state);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/history?since=1000&until=4000&limit=2")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["x-total-count"], "3");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let events: Vec<serde_json::Value> = serde_json::from_slice(&body).expect("parse JSON");
+    let messages: Vec<_> = events.iter().map(|e| e["data"].as_str().unwrap()).collect();
+    assert_eq!(messages, vec!["Event 2", "Event 3"]);
+
+    // An offset SQL can't take is rejected rather than wrapped
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/history?offset={}", u64::MAX))
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+struct IconPlugin;
+
+impl cloto_shared::PluginCast for IconPlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl cloto_shared::Plugin for IconPlugin {
+    fn manifest(&self) -> cloto_shared::PluginManifest {
+        cloto_shared::PluginManifest {
+            id: "tool.icon".to_string(),
+            name: "Icon".to_string(),
+            description: String::new(),
+            version: "1.0".to_string(),
+            category: cloto_shared::PluginCategory::Tool,
+            service_type: cloto_shared::ServiceType::Skill,
+            tags: vec![],
+            is_active: true,
+            is_configured: true,
+            required_config_keys: vec![],
+            config_schema: vec![],
+            action_icon: None,
+            action_target: None,
+            // "<svg/>"
+            icon_data: Some("data:image/svg+xml;base64,PHN2Zy8+".to_string()),
+            magic_seal: 0x5645_5253,
+            sdk_version: "1.0".to_string(),
+            required_permissions: vec![],
+            provided_capabilities: vec![],
+            provided_tools: vec![],
+            subscribes: vec![],
+        }
+    }
+
+    async fn on_event(
+        &self,
+        _event: &cloto_shared::ClotoEvent,
+    ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+        Ok(None)
+    }
+}
+
+#[tokio::test]
+async fn test_plugin_icons_served_separately() {
+    let state = create_test_app_state(None).await;
+    state
+        .registry
+        .plugins
+        .write()
+        .await
+        .insert("tool.icon".to_string(), Arc::new(IconPlugin));
+
+    let get = |uri: &str, etag: Option<&str>| {
+        let mut request = Request::builder().method("GET").uri(uri);
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        request.body(Body::empty()).expect("build request")
+    };
+
+    // Icons are stripped from the listing by default
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/plugins", None))
+        .await
+        .expect("send request");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let plugins: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert_eq!(plugins[0]["id"], "tool.icon");
+    assert!(plugins[0]["icon_data"].is_null());
+
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/plugins?include_icons=true", None))
+        .await
+        .expect("send request");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let plugins: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert!(plugins[0]["icon_data"].is_string());
+
+    // The icon endpoint decodes the data URL and is cacheable
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/plugins/tool.icon/icon", None))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+    assert!(response.headers()[header::CACHE_CONTROL]
+        .to_str()
+        .unwrap()
+        .contains("max-age"));
+    let etag = response.headers()[header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    assert_eq!(&body[..], b"<svg/>");
+
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/plugins/tool.icon/icon", Some(&etag)))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = create_test_router(state)
+        .oneshot(get("/api/plugins/tool.missing/icon", None))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_chat_messages_include_tool_history() {
+    let state = create_test_app_state(None).await;
+    let agent_id = state.config.default_agent_id.clone();
+    for (id, source, created_at) in [("m1", "user", 1_000), ("m1-resp", "agent", 2_000)] {
+        cloto_core::db::save_chat_message(
+            &state.pool,
+            &cloto_core::db::ChatMessageRow {
+                id: id.to_string(),
+                agent_id: agent_id.clone(),
+                user_id: "default".to_string(),
+                source: source.to_string(),
+                content: "[]".to_string(),
+                metadata: None,
+                created_at,
+                session_id: None,
+            },
+        )
+        .await
+        .expect("save message");
+    }
+    let history = json!([
+        {
+            "role": "assistant",
+            "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": { "name": "web_search", "arguments": "{\"q\":\"rust\"}" }
+            }]
+        },
+        { "role": "tool", "tool_call_id": "call_1", "content": "results" }
+    ]);
+    cloto_core::db::save_chat_tool_history(
+        &state.pool,
+        &cloto_core::db::ChatToolHistoryRow {
+            message_id: "m1".to_string(),
+            agent_id: agent_id.clone(),
+            engine_id: "mind.test".to_string(),
+            history: history.to_string(),
+            created_at: 1_500,
+        },
+    )
+    .await
+    .expect("save tool history");
+    let app = create_test_router(state);
+
+    let get = |query: &str| {
+        Request::builder()
+            .uri(format!("/api/chat/{}/messages{}", agent_id, query))
+            .body(Body::empty())
+            .expect("build request")
+    };
+
+    let response = app.clone().oneshot(get("")).await.expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    let messages = body["messages"].as_array().expect("messages");
+    assert_eq!(messages.len(), 2);
+    assert!(messages.iter().all(|m| m.get("tool_history").is_none()));
+
+    let response = app
+        .oneshot(get("?include_tools=true"))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    let messages = body["messages"].as_array().expect("messages");
+    // Newest first: the reply has no run of its own
+    assert_eq!(messages[0]["id"], "m1-resp");
+    assert!(messages[0].get("tool_history").is_none());
+    assert_eq!(messages[1]["id"], "m1");
+    assert_eq!(messages[1]["tool_history"], history);
+}
+
+#[tokio::test]
+async fn test_agent_plugin_tool_rules_roundtrip() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let agent_id = state
+        .agent_manager
+        .create_agent(
+            "Ruled",
+            "Has tool rules",
+            "mind.deepseek",
+            std::collections::HashMap::new(),
+            vec![],
+            None,
+        )
+        .await
+        .expect("create agent");
+    let uri = format!("/api/agents/{}/plugins", agent_id);
+
+    let put = |payload: serde_json::Value| {
+        Request::builder()
+            .method("PUT")
+            .uri(&uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("X-API-Key", "test-key")
+            .body(Body::from(payload.to_string()))
+            .expect("build request")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(put(json!({
+            "plugins": [{
+                "plugin_id": "tool.terminal",
+                "tools": [{ "tool_name": "execute_command", "permission": "deny" }]
+            }]
+        })))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = create_test_router(state.clone())
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(&uri)
+                .header("X-API-Key", "test-key")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert_eq!(body["plugins"][0]["plugin_id"], "tool.terminal");
+    assert_eq!(
+        body["plugins"][0]["tools"][0]["tool_name"],
+        "execute_command"
+    );
+
+    let policy = state
+        .agent_manager
+        .get_tool_policy(&agent_id)
+        .await
+        .expect("load policy");
+    assert!(!policy.allows("tool.terminal", "execute_command"));
+
+    // Unknown permission values are rejected
+    let response = create_test_router(state.clone())
+        .oneshot(put(json!({
+            "plugins": [{
+                "plugin_id": "tool.terminal",
+                "tools": [{ "tool_name": "execute_command", "permission": "maybe" }]
+            }]
+        })))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_audit_log_filtered_by_type() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    for (event_type, actor) in [
+        ("TOOL_EXECUTED", "agent.a"),
+        ("TOOL_EXECUTED", "agent.b"),
+        ("PERMISSION_GRANTED", "admin"),
+    ] {
+        cloto_core::write_audit_log(
+            &state.pool,
+            cloto_core::AuditLogEntry {
+                timestamp: chrono::Utc::now(),
+                event_type: event_type.to_string(),
+                actor_id: Some(actor.to_string()),
+                target_id: Some("web_fetch".to_string()),
+                permission: None,
+                result: "SUCCESS".to_string(),
+                reason: "test".to_string(),
+                metadata: Some(json!({ "arguments": { "url": "https://example.com" } })),
+                trace_id: None,
+            },
+        )
+        .await
+        .expect("write audit log");
+    }
+
+    let get = |uri: &str, key: Option<&str>| {
+        let mut builder = Request::builder().method("GET").uri(uri);
+        if let Some(key) = key {
+            builder = builder.header("X-API-Key", key);
+        }
+        builder.body(Body::empty()).expect("build request")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/audit?type=TOOL_EXECUTED", None))
+        .await
+        .expect("send request");
+    assert!(!response.status().is_success());
+
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/audit?type=TOOL_EXECUTED", Some("test-key")))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    let entries = body["entries"].as_array().expect("entries");
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e["event_type"] == "TOOL_EXECUTED"));
+
+    let response = create_test_router(state)
+        .oneshot(get(
+            "/api/audit?type=TOOL_EXECUTED&agent_id=agent.b",
+            Some("test-key"),
+        ))
+        .await
+        .expect("send request");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert_eq!(body["entries"].as_array().expect("entries").len(), 1);
+    assert_eq!(body["entries"][0]["actor_id"], "agent.b");
+}
+
+#[tokio::test]
+async fn test_tool_invocations_filtered_by_agent() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    for (i, (agent, status)) in [
+        ("agent.a", "SUCCESS"),
+        ("agent.a", "FAILURE"),
+        ("agent.b", "SUCCESS"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        cloto_core::db::create_tool_invocation(
+            &state.pool,
+            &cloto_core::db::ToolInvocationRow {
+                id: format!("invocation.{}", i),
+                agent_id: agent.to_string(),
+                engine_id: "mind.test".to_string(),
+                tool_name: "web_fetch".to_string(),
+                server_id: Some("tool.web".to_string()),
+                arguments: json!({ "url": "https://example.com" }).to_string(),
+                status: status.to_string(),
+                error: (status == "FAILURE").then(|| "timeout".to_string()),
+                duration_ms: 40,
+                trace_id: None,
+                created_at: 1_000 + i64::try_from(i).unwrap(),
+            },
+        )
+        .await
+        .expect("write tool invocation");
+    }
+
+    let get = |uri: &str, key: Option<&str>| {
+        let mut builder = Request::builder().method("GET").uri(uri);
+        if let Some(key) = key {
+            builder = builder.header("X-API-Key", key);
+        }
+        builder.body(Body::empty()).expect("build request")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(get("/api/tools/invocations?agent_id=agent.a", None))
+        .await
+        .expect("send request");
+    assert!(!response.status().is_success());
+
+    let response = create_test_router(state.clone())
+        .oneshot(get(
+            "/api/tools/invocations?agent_id=agent.a",
+            Some("test-key"),
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    let invocations = body["invocations"].as_array().expect("invocations");
+    assert_eq!(invocations.len(), 2);
+    // Most recent first
+    assert_eq!(invocations[0]["status"], "FAILURE");
+    assert_eq!(invocations[0]["error"], "timeout");
+    assert!(invocations.iter().all(|i| i["agent_id"] == "agent.a"));
+
+    let response = create_test_router(state.clone())
+        .oneshot(get(
+            "/api/tools/invocations?status=success",
+            Some("test-key"),
+        ))
+        .await
+        .expect("send request");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let body: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert_eq!(
+        body["invocations"].as_array().expect("invocations").len(),
+        2
+    );
+
+    let response = create_test_router(state)
+        .oneshot(get("/api/tools/invocations?status=bogus", Some("test-key")))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+/// Plugin that records `on_plugin_init` calls, for reload tests.
+struct ReloadablePlugin {
+    generation: usize,
+    inits: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl cloto_shared::PluginCast for ReloadablePlugin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl cloto_shared::Plugin for ReloadablePlugin {
+    fn manifest(&self) -> cloto_shared::PluginManifest {
+        let mut manifest = CapabilityPlugin {
+            id: "test.reload",
+            capabilities: vec![],
+        }
+        .manifest();
+        manifest.description = format!("generation {}", self.generation);
+        manifest
+    }
+
+    async fn on_plugin_init(
+        &self,
+        context: cloto_shared::PluginRuntimeContext,
+        _network: Option<Arc<dyn cloto_shared::NetworkCapability>>,
+    ) -> anyhow::Result<()> {
+        context
+            .store
+            .set_json("test.reload", "generation", json!(self.generation))
+            .await?;
+        self.inits.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_reload_plugin() {
+    use cloto_shared::PluginDataStore;
+
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let inits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let generation = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let factory: cloto_core::managers::PluginFactory = {
+        let inits = inits.clone();
+        Arc::new(move || {
+            Ok(Arc::new(ReloadablePlugin {
+                generation: generation.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+                inits: inits.clone(),
+            }) as Arc<dyn cloto_shared::Plugin>)
+        })
+    };
+    state
+        .registry
+        .plugins
+        .write()
+        .await
+        .insert("test.reload".to_string(), factory().expect("build plugin"));
+    state
+        .registry
+        .register_factory("test.reload", factory)
+        .await;
+    add_capability_plugin(&state, "test.static", vec![]).await;
+
+    let reload = |id: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/plugins/{}/reload", id))
+            .header("X-API-Key", "test-key")
+            .body(Body::empty())
+            .expect("build request")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(reload("test.reload"))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(inits.load(std::sync::atomic::Ordering::SeqCst), 1);
+    let plugin = state
+        .registry
+        .get_engine("test.reload")
+        .await
+        .expect("plugin");
+    assert_eq!(plugin.manifest().description, "generation 1");
+    let stored = cloto_core::db::SqliteDataStore::new(state.pool.clone())
+        .get_json("test.reload", "generation")
+        .await
+        .expect("read store");
+    assert_eq!(stored, Some(json!(1)));
+
+    // No factory registered
+    let response = create_test_router(state.clone())
+        .oneshot(reload("test.static"))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = create_test_router(state)
+        .oneshot(reload("nonexistent"))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_scoped_api_keys() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let request = |method: &str, uri: &str, key: &str, body: Option<serde_json::Value>| {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("X-API-Key", key);
+        match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("build request")
+    };
+    let read_json = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        serde_json::from_slice::<serde_json::Value>(&body).expect("parse JSON")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(request(
+            "POST",
+            "/api/system/keys",
+            "test-key",
+            Some(json!({ "name": "dashboard", "scope": "superuser" })),
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = create_test_router(state.clone())
+        .oneshot(request(
+            "POST",
+            "/api/system/keys",
+            "test-key",
+            Some(json!({ "name": "dashboard", "scope": "read_only" })),
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let created = read_json(response).await;
+    let key = created["key"].as_str().expect("key").to_string();
+    let id = created["id"].as_str().expect("id").to_string();
+    assert!(key.starts_with(created["key_prefix"].as_str().expect("prefix")));
+
+    // Read-only key: GET endpoints work, admin endpoints do not
+    let plugins_uri = "/api/agents/agent.cloto_default/plugins";
+    let response = create_test_router(state.clone())
+        .oneshot(request("GET", plugins_uri, &key, None))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    for uri in ["/api/audit", "/api/system/keys"] {
+        let response = create_test_router(state.clone())
+            .oneshot(request("GET", uri, &key, None))
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", uri);
+    }
+
+    let response = create_test_router(state.clone())
+        .oneshot(request("GET", "/api/system/keys", "test-key", None))
+        .await
+        .expect("send request");
+    let listed = read_json(response).await;
+    let keys = listed["keys"].as_array().expect("keys");
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0]["scope"], "read_only");
+    assert!(keys[0].get("key_hash").is_none());
+    assert!(keys[0].get("key").is_none());
+
+    let response = create_test_router(state.clone())
+        .oneshot(request(
+            "DELETE",
+            &format!("/api/system/keys/{}", id),
+            "test-key",
+            None,
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = create_test_router(state)
+        .oneshot(request("GET", plugins_uri, &key, None))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+fn create_user_request(body: &serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/api/auth/users")
+        .header("X-API-Key", "test-key")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("build request")
+}
+
+fn login_request(password: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/api/auth/login")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "username": "viewer", "password": password }).to_string(),
+        ))
+        .expect("build request")
+}
+
+fn cookie_request(method: &str, uri: &str, cookie: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::COOKIE, cookie)
+        .body(Body::empty())
+        .expect("build request")
+}
+
+/// Create the read_only user "viewer" and sign in; returns its ID and the
+/// session cookie.
+async fn sign_in_viewer(state: &Arc<AppState>) -> (String, String) {
+    let response = create_test_router(state.clone())
+        .oneshot(create_user_request(&json!({
+            "username": "viewer",
+            "password": "correct horse",
+            "role": "read_only",
+        })))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let created: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    assert!(created.get("password_hash").is_none());
+    let user_id = created["id"].as_str().expect("id").to_string();
+
+    let response = create_test_router(state.clone())
+        .oneshot(login_request("correct horse"))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let set_cookie = response
+        .headers()
+        .get(header::SET_COOKIE)
+        .and_then(|v| v.to_str().ok())
+        .expect("session cookie");
+    assert!(set_cookie.contains("HttpOnly"));
+    let cookie = set_cookie.split(';').next().unwrap().to_string();
+    (user_id, cookie)
+}
+
+#[tokio::test]
+async fn test_dashboard_user_login() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+
+    let response = create_test_router(state.clone())
+        .oneshot(create_user_request(&json!({
+            "username": "viewer",
+            "password": "short",
+            "role": "read_only",
+        })))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    sign_in_viewer(&state).await;
+
+    let response = create_test_router(state)
+        .oneshot(login_request("wrong password"))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(response.headers().get(header::SET_COOKIE).is_none());
+}
+
+#[tokio::test]
+async fn test_dashboard_user_session() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let (user_id, cookie) = sign_in_viewer(&state).await;
+
+    // The session carries the read_only role: reads pass, admin routes do not
+    let plugins_uri = "/api/agents/agent.cloto_default/plugins";
+    for (uri, expected) in [
+        (plugins_uri, StatusCode::OK),
+        ("/api/audit", StatusCode::FORBIDDEN),
+        ("/api/auth/session", StatusCode::OK),
+    ] {
+        let response = create_test_router(state.clone())
+            .oneshot(cookie_request("GET", uri, &cookie))
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), expected, "{}", uri);
+    }
+
+    // Tampered cookies are rejected
+    let tampered = format!("{}0", cookie);
+    let response = create_test_router(state.clone())
+        .oneshot(cookie_request("GET", plugins_uri, &tampered))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Deleting the user ends their sessions
+    let response = create_test_router(state.clone())
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/auth/users/{}", user_id))
+                .header("X-API-Key", "test-key")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = create_test_router(state)
+        .oneshot(cookie_request("GET", plugins_uri, &cookie))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_dashboard_user_logout() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let (_, cookie) = sign_in_viewer(&state).await;
+
+    let response = create_test_router(state.clone())
+        .oneshot(cookie_request("POST", "/api/auth/logout", &cookie))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let cleared = response
+        .headers()
+        .get(header::SET_COOKIE)
+        .and_then(|v| v.to_str().ok())
+        .expect("cleared cookie");
+    assert!(cleared.starts_with("cloto_session=;"));
+    assert!(cleared.contains("Max-Age=0"));
+
+    // A copy of the signed-out token no longer works
+    let response = create_test_router(state)
+        .oneshot(cookie_request("GET", "/api/auth/session", &cookie))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+const CHAT_BASE: &str = "/api/chat/agent.cloto_default";
+
+fn chat_request(method: &str, uri: &str, body: Option<serde_json::Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("X-API-Key", "test-key");
+    match body {
+        Some(body) => builder
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => builder.body(Body::empty()),
+    }
+    .expect("build request")
+}
+
+fn post_text_request(id: &str, text: &str, session_id: Option<&str>) -> Request<Body> {
+    chat_request(
+        "POST",
+        &format!("{}/messages", CHAT_BASE),
+        Some(json!({
+            "id": id,
+            "source": "user",
+            "content": [{ "type": "text", "text": text }],
+            "session_id": session_id,
+        })),
+    )
+}
+
+async fn send_chat(state: &Arc<AppState>, req: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let response = create_test_router(state.clone())
+        .oneshot(req)
+        .await
+        .expect("send request");
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    (
+        status,
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+    )
+}
+
+/// Post a first message, opening the default session, then create a second
+/// one. Returns both session IDs.
+async fn open_two_sessions(state: &Arc<AppState>) -> (String, String) {
+    let (status, _) = send_chat(state, post_text_request("msg-1", "first", None)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, listed) = send_chat(
+        state,
+        chat_request("GET", &format!("{}/sessions", CHAT_BASE), None),
+    )
+    .await;
+    let first_id = listed["sessions"][0]["id"]
+        .as_str()
+        .expect("id")
+        .to_string();
+
+    let (status, created) = send_chat(
+        state,
+        chat_request(
+            "POST",
+            &format!("{}/sessions", CHAT_BASE),
+            Some(json!({ "name": "Trip planning" })),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["name"], "Trip planning");
+    assert_eq!(created["active"], true);
+    let second_id = created["id"].as_str().expect("id").to_string();
+    (first_id, second_id)
+}
+
+#[tokio::test]
+async fn test_chat_session_create() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+
+    // The first message lazily opens a default session
+    let (status, _) = send_chat(&state, post_text_request("msg-1", "first", None)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, listed) = send_chat(
+        &state,
+        chat_request("GET", &format!("{}/sessions", CHAT_BASE), None),
+    )
+    .await;
+    let sessions = listed["sessions"].as_array().expect("sessions");
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["active"], true);
+    let first_id = sessions[0]["id"].as_str().expect("id").to_string();
+
+    // A new session becomes active and starts with an empty history
+    let (status, created) = send_chat(
+        &state,
+        chat_request(
+            "POST",
+            &format!("{}/sessions", CHAT_BASE),
+            Some(json!({ "name": "Trip planning" })),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["active"], true);
+    let second_id = created["id"].as_str().expect("id").to_string();
+
+    send_chat(&state, post_text_request("msg-2", "second", None)).await;
+    for (query, session_id, message_id) in [
+        (String::new(), &second_id, "msg-2"),
+        (format!("?session_id={}", first_id), &first_id, "msg-1"),
+    ] {
+        let (_, page) = send_chat(
+            &state,
+            chat_request("GET", &format!("{}/messages{}", CHAT_BASE, query), None),
+        )
+        .await;
+        assert_eq!(page["session_id"], session_id.as_str());
+        let messages = page["messages"].as_array().expect("messages");
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["id"], message_id);
+    }
+
+    let (status, _) = send_chat(
+        &state,
+        post_text_request("msg-3", "stray", Some("session.missing")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_chat_session_list() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let (first_id, second_id) = open_two_sessions(&state).await;
+
+    // Switching back makes the first session current again
+    let (status, _) = send_chat(
+        &state,
+        chat_request(
+            "POST",
+            &format!("{}/sessions/{}/activate", CHAT_BASE, first_id),
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, listed) = send_chat(
+        &state,
+        chat_request("GET", &format!("{}/sessions", CHAT_BASE), None),
+    )
+    .await;
+    let sessions = listed["sessions"].as_array().expect("sessions");
+    assert_eq!(sessions.len(), 2);
+    for session in sessions {
+        assert_eq!(session["active"], session["id"] == first_id.as_str());
+    }
+    let (_, page) = send_chat(
+        &state,
+        chat_request("GET", &format!("{}/messages", CHAT_BASE), None),
+    )
+    .await;
+    assert_eq!(page["session_id"], first_id.as_str());
+
+    // Sessions are scoped to their agent
+    let (status, _) = send_chat(
+        &state,
+        chat_request(
+            "POST",
+            &format!("/api/chat/agent.other/sessions/{}/activate", second_id),
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_chat_session_archive() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let (_, second_id) = open_two_sessions(&state).await;
+
+    // Archived sessions are hidden unless requested
+    let (status, _) = send_chat(
+        &state,
+        chat_request(
+            "POST",
+            &format!("{}/sessions/{}/archive", CHAT_BASE, second_id),
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, listed) = send_chat(
+        &state,
+        chat_request("GET", &format!("{}/sessions", CHAT_BASE), None),
+    )
+    .await;
+    assert_eq!(listed["sessions"].as_array().expect("sessions").len(), 1);
+    let (_, listed) = send_chat(
+        &state,
+        chat_request(
+            "GET",
+            &format!("{}/sessions?include_archived=true", CHAT_BASE),
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(listed["sessions"].as_array().expect("sessions").len(), 2);
+}
+
+#[tokio::test]
+async fn test_tool_call_approval_endpoints() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let request = |method: &str, uri: &str, key: Option<&str>| {
+        let builder = Request::builder().method(method).uri(uri);
+        match key {
+            Some(key) => builder.header("X-API-Key", key),
+            None => builder,
+        }
+        .body(Body::empty())
+        .expect("build request")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(request("GET", "/api/tool-calls/pending", None))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = create_test_router(state.clone())
+        .oneshot(request("GET", "/api/tool-calls/pending", Some("test-key")))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    assert_eq!(body.as_ref(), b"[]");
+
+    // Nothing is waiting on an unknown request
+    for action in ["approve", "deny"] {
+        let response = create_test_router(state.clone())
+            .oneshot(request(
+                "POST",
+                &format!("/api/tool-calls/toolcall.unknown/{}", action),
+                Some("test-key"),
+            ))
+            .await
+            .expect("send request");
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", action);
+    }
+}
+
+#[tokio::test]
+async fn test_dead_letter_listing_and_replay() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let request = |method: &str, uri: &str, key: Option<&str>| {
+        let builder = Request::builder().method(method).uri(uri);
+        match key {
+            Some(key) => builder.header("X-API-Key", key),
+            None => builder,
+        }
+        .body(Body::empty())
+        .expect("build request")
+    };
+
+    let response = create_test_router(state.clone())
+        .oneshot(request("GET", "/api/events/dead-letter", None))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    cloto_core::db::create_dead_letter_event(
+        &state.pool,
+        &cloto_core::db::DeadLetterEventRow {
+            id: "deadletter.1".to_string(),
+            plugin_id: "plugin.gone".to_string(),
+            event_type: "SystemNotification".to_string(),
+            event: serde_json::to_string(&cloto_shared::ClotoEvent::new(
+                cloto_shared::ClotoEventData::SystemNotification("hi".to_string()),
+            ))
+            .expect("serialize event"),
+            depth: 0,
+            error: "boom".to_string(),
+            attempts: 3,
+            created_at: 1,
+            replayed_at: None,
+        },
+    )
+    .await
+    .expect("insert dead letter");
+
+    let response = create_test_router(state.clone())
+        .oneshot(request("GET", "/api/events/dead-letter", Some("test-key")))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = serde_json::from_slice(
+        &axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body"),
+    )
+    .expect("json body");
+    assert_eq!(body["events"][0]["id"], "deadletter.1");
+    assert_eq!(body["events"][0]["plugin_id"], "plugin.gone");
+
+    let response = create_test_router(state.clone())
+        .oneshot(request(
+            "POST",
+            "/api/events/dead-letter/deadletter.unknown/replay",
+            Some("test-key"),
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The plugin is not loaded, so the entry stays pending
+    let response = create_test_router(state.clone())
+        .oneshot(request(
+            "POST",
+            "/api/events/dead-letter/deadletter.1/replay",
+            Some("test-key"),
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let pending = cloto_core::db::list_dead_letter_events(&state.pool, true, 10)
+        .await
+        .expect("list dead letters");
+    assert_eq!(pending.len(), 1);
+}
+
+#[tokio::test]
+async fn test_mcp_server_models_requires_list_models_tool() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let response = create_test_router(state)
+        .oneshot(
+            Request::builder()
+                .uri("/api/mcp/servers/mind.ollama/models")
+                .header("X-API-Key", "test-key")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_openai_gateway_accepts_bearer_keys() {
+    let state = create_test_app_state(Some("test-key".to_string())).await;
+    let agent_id = state
+        .agent_manager
+        .create_agent(
+            "Open WebUI",
+            "Test",
+            "mind.deepseek",
+            std::collections::HashMap::new(),
+            vec![],
+            None,
+        )
+        .await
+        .expect("create agent");
+    let completion = |auth: Option<&str>, body: serde_json::Value| {
+        let builder = Request::builder()
+            .method("POST")
+            .uri("/v1/chat/completions")
+            .header(header::CONTENT_TYPE, "application/json");
+        match auth {
+            Some(auth) => builder.header(header::AUTHORIZATION, auth),
+            None => builder,
+        }
+        .body(Body::from(body.to_string()))
+        .expect("build request")
+    };
+    let hello = |model: &str| json!({ "model": model, "messages": [{ "role": "user", "content": "Hello" }] });
+
+    let response = create_test_router(state.clone())
+        .oneshot(completion(None, hello(&agent_id)))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = create_test_router(state.clone())
+        .oneshot(completion(Some("Bearer test-key"), hello("agent.missing")))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = create_test_router(state.clone())
+        .oneshot(
+            Request::builder()
+                .uri("/v1/models")
+                .header(header::AUTHORIZATION, "Bearer test-key")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let models: serde_json::Value = serde_json::from_slice(&body).expect("parse JSON");
+    let model = models["data"]
+        .as_array()
+        .expect("model list")
+        .iter()
+        .find(|m| m["id"] == agent_id.as_str())
+        .expect("agent listed as model");
+    assert_eq!(model["object"], "model");
+
+    // Only a system prompt: nothing to relay to the agent
+    let response = create_test_router(state)
+        .oneshot(completion(
+            Some("Bearer test-key"),
+            json!({
+                "model": agent_id,
+                "messages": [{ "role": "system", "content": "Be brief." }],
+            }),
+        ))
+        .await
+        .expect("send request");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -1,6 +1,6 @@
 mod common;
 
-use sqlx::SqlitePool;
+use cloto_core::db::DbPool;
 use std::sync::Arc;
 
 #[tokio::test]
@@ -13,7 +13,7 @@ async fn test_permission_logic_unit() {
 
 #[tokio::test]
 async fn test_capability_injection_logic() {
-    let _pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let _pool = DbPool::connect("sqlite::memory:").await.unwrap();
 
     let perms_ok: Vec<cloto_shared::Permission> = vec![cloto_shared::Permission::NetworkAccess];
     let client = Arc::new(cloto_core::capabilities::SafeHttpClient::new(vec![]).unwrap());
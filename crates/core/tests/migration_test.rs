@@ -1,10 +1,10 @@
 //! Database migration and initialization tests.
 //! Tests that DB init is idempotent and creates required tables.
 
-use sqlx::SqlitePool;
+use cloto_core::db::DbPool;
 
-async fn fresh_pool() -> SqlitePool {
-    SqlitePool::connect("sqlite::memory:").await.unwrap()
+async fn fresh_pool() -> DbPool {
+    DbPool::connect("sqlite::memory:").await.unwrap()
 }
 
 #[tokio::test]
@@ -1,10 +1,10 @@
+use cloto_core::db::DbPool;
 use cloto_core::events::EventProcessor;
 use cloto_core::managers::{AgentManager, PluginManager, PluginRegistry};
 use cloto_shared::{
     ClotoEvent, ClotoId, Permission, Plugin, PluginCapability, PluginCast, PluginManifest,
     ServiceType,
 };
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
@@ -43,6 +43,7 @@ impl Plugin for MockPlugin {
             is_active: true,
             is_configured: true,
             required_config_keys: vec![],
+            config_schema: vec![],
             action_icon: None,
             action_target: None,
             icon_data: None,
@@ -51,6 +52,7 @@ impl Plugin for MockPlugin {
             required_permissions: vec![],
             provided_capabilities: vec![],
             provided_tools: vec![],
+            subscribes: vec![],
         }
     }
 
@@ -73,7 +75,7 @@ impl Plugin for MockPlugin {
 #[tokio::test]
 async fn test_dynamic_permission_elevation_flow() {
     // 1. Setup Kernel Components
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
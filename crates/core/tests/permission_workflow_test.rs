@@ -106,16 +106,16 @@ async fn test_plugin_registry_find_memory_empty_returns_none() {
 
 #[tokio::test]
 async fn test_db_permission_grant_roundtrip() {
-    use sqlx::SqlitePool;
+    use cloto_core::db::DbPool;
 
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
 
     // Insert a plugin setting
     sqlx::query(
-        "INSERT INTO plugin_settings (plugin_id, is_active, allowed_permissions) VALUES (?, 1, '[]')"
+        "INSERT INTO plugin_settings (plugin_id, is_active, allowed_permissions) VALUES ($1, 1, '[]')"
     )
     .bind("test.plugin")
     .execute(&pool)
@@ -136,7 +136,7 @@ async fn test_db_permission_grant_roundtrip() {
 
     // Read back and verify
     let row: (String,) =
-        sqlx::query_as("SELECT allowed_permissions FROM plugin_settings WHERE plugin_id = ?")
+        sqlx::query_as("SELECT allowed_permissions FROM plugin_settings WHERE plugin_id = $1")
             .bind("test.plugin")
             .fetch_one(&pool)
             .await
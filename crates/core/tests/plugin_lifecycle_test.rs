@@ -74,6 +74,7 @@ async fn test_invalid_magic_seal_rejected() {
                 is_active: true,
                 is_configured: true,
                 required_config_keys: vec![],
+                config_schema: vec![],
                 action_icon: None,
                 action_target: None,
                 icon_data: None,
@@ -82,6 +83,7 @@ async fn test_invalid_magic_seal_rejected() {
                 required_permissions: vec![],
                 provided_capabilities: vec![],
                 provided_tools: vec![],
+                subscribes: vec![],
             }
         }
         async fn on_event(
@@ -104,10 +106,31 @@ async fn test_invalid_magic_seal_rejected() {
 #[tokio::test]
 async fn test_plugin_registry_empty_on_creation() {
     let registry = PluginRegistry::new(5, 10);
-    let plugins = registry.plugins.read().await;
+    let plugins = registry.plugins.load();
     assert!(plugins.is_empty(), "New registry must start empty");
 }
 
+#[tokio::test]
+async fn test_plugin_registry_snapshots_are_isolated() {
+    use common::create_mock_plugin;
+
+    let registry = PluginRegistry::new(5, 10);
+    let before = registry.plugins.load();
+    {
+        let mut plugins = registry.plugins.write().await;
+        plugins.insert(
+            "normal".into(),
+            create_mock_plugin(ClotoId::new()).0 as Arc<dyn cloto_shared::Plugin>,
+        );
+        assert!(
+            registry.plugins.load().is_empty(),
+            "Writes must not be visible before the guard drops"
+        );
+    }
+    assert!(before.is_empty(), "Existing snapshots must not change");
+    assert!(registry.plugins.load().contains_key("normal"));
+}
+
 #[tokio::test]
 async fn test_cascading_depth_limit_enforced() {
     use common::create_mock_plugin;
@@ -0,0 +1,548 @@
+//! PostgreSQL backend tests (`--features postgres`).
+//!
+//! Each test creates a scratch database on the server named by
+//! `CLOTO_TEST_POSTGRES_URL` (e.g. `postgres://postgres@localhost/postgres`)
+//! and runs the kernel's migrations and queries against it. Tests are skipped
+//! when the variable is unset.
+#![cfg(feature = "postgres")]
+
+use cloto_core::db::{self, DbPool};
+use cloto_shared::PluginDataStore;
+use sqlx::postgres::PgConnectOptions;
+use sqlx::{ConnectOptions, Connection};
+use std::str::FromStr;
+
+async fn fresh_pool() -> Option<DbPool> {
+    let Ok(url) = std::env::var("CLOTO_TEST_POSTGRES_URL") else {
+        eprintln!("CLOTO_TEST_POSTGRES_URL not set; skipping");
+        return None;
+    };
+    let admin = PgConnectOptions::from_str(&url).unwrap();
+    let name = format!("cloto_test_{}", uuid::Uuid::new_v4().simple());
+    let mut conn = admin.connect().await.unwrap();
+    sqlx::query(&format!("CREATE DATABASE {}", name))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    conn.close().await.unwrap();
+
+    let pool = DbPool::connect_with(admin.database(&name)).await.unwrap();
+    db::init_db(&pool, &url).await.unwrap();
+    Some(pool)
+}
+
+#[tokio::test]
+async fn test_init_is_idempotent_and_seeds_default_agent() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    db::init_db(&pool, "postgres://other").await.unwrap();
+
+    let agents = cloto_core::managers::AgentManager::new(pool.clone())
+        .list_agents()
+        .await
+        .unwrap();
+    assert!(agents.iter().any(|a| a.id == "agent.cloto_default"));
+    assert_eq!(db::list_llm_providers(&pool).await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn test_plugin_data_store() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    let store = db::SqliteDataStore::new(pool);
+    let value = serde_json::json!({"n": 1});
+    store.set_json("p", "k", value.clone()).await.unwrap();
+    store.set_json("p", "k", value.clone()).await.unwrap();
+    assert_eq!(store.get_json("p", "k").await.unwrap(), Some(value));
+
+    assert_eq!(store.increment_counter("p", "c").await.unwrap(), 1);
+    assert_eq!(store.increment_counter("p", "c").await.unwrap(), 2);
+}
+
+/// Saves a user message `m0` and an agent reply `m1` for user `u`.
+async fn save_exchange(pool: &DbPool) {
+    for (i, source) in (0_i64..).zip(["user", "agent"]) {
+        db::save_chat_message(
+            pool,
+            &db::ChatMessageRow {
+                id: format!("m{}", i),
+                agent_id: "agent.cloto_default".into(),
+                user_id: "u".into(),
+                source: source.into(),
+                content: "[]".into(),
+                metadata: None,
+                created_at: 1_000 + i,
+                session_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_chat_history() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    save_exchange(&pool).await;
+
+    let history = db::get_chat_messages(&pool, "agent.cloto_default", "u", None, 10)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    let default_session = history[0].session_id.clone().expect("session assigned");
+    let session = db::create_chat_session(&pool, "agent.cloto_default", "u", "Second")
+        .await
+        .unwrap();
+    let active = db::get_active_chat_session(&pool, "agent.cloto_default", "u")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(active.id, session.id);
+    assert!(db::get_chat_session_messages(&pool, &session.id, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db::archive_chat_session(&pool, &session.id).await.unwrap());
+    assert!(db::activate_chat_session(&pool, &default_session)
+        .await
+        .unwrap());
+    let sessions = db::list_chat_sessions(&pool, "agent.cloto_default", "u", false)
+        .await
+        .unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].active);
+
+    // Saving a run's tool history again replaces it
+    for history in ["[]", r#"[{"role":"tool"}]"#] {
+        db::save_chat_tool_history(
+            &pool,
+            &db::ChatToolHistoryRow {
+                message_id: "m0".into(),
+                agent_id: "agent.cloto_default".into(),
+                engine_id: "mind.test".into(),
+                history: history.into(),
+                created_at: 1_000,
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let ids = vec!["m0".to_string(), "m1".to_string()];
+    let tool_history = db::get_chat_tool_history(&pool, "agent.cloto_default", &ids)
+        .await
+        .unwrap();
+    assert_eq!(tool_history.len(), 1);
+    assert_eq!(tool_history[0].history, r#"[{"role":"tool"}]"#);
+
+    db::delete_chat_messages(&pool, "agent.cloto_default", "u")
+        .await
+        .unwrap();
+    assert!(
+        db::get_chat_tool_history(&pool, "agent.cloto_default", &ids)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_chat_attachments() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    save_exchange(&pool).await;
+    db::save_attachment(
+        &pool,
+        &db::AttachmentRow {
+            id: "a".into(),
+            message_id: "m0".into(),
+            filename: "notes.txt".into(),
+            mime_type: "text/plain".into(),
+            size_bytes: 3,
+            storage_type: "inline".into(),
+            inline_data: Some(b"abc".to_vec()),
+            disk_path: None,
+            created_at: 1_000,
+            derived_text: None,
+        },
+    )
+    .await
+    .unwrap();
+    db::set_attachment_derived_text(&pool, "a", "abc")
+        .await
+        .unwrap();
+
+    let att = db::get_attachment_by_id(&pool, "a").await.unwrap().unwrap();
+    assert_eq!(att.inline_data.as_deref(), Some(&b"abc"[..]));
+    assert_eq!(att.derived_text.as_deref(), Some("abc"));
+
+    db::delete_chat_messages(&pool, "agent.cloto_default", "u")
+        .await
+        .unwrap();
+    assert!(db::get_attachment_by_id(&pool, "a")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_access_control_and_revoked_keys() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    db::ensure_mcp_server_in_db(&pool, "tool.x", "python", "[]", "opt-in")
+        .await
+        .unwrap();
+    let entry = db::AccessControlEntry {
+        id: None,
+        entry_type: "tool_grant".into(),
+        agent_id: "agent.cloto_default".into(),
+        server_id: "tool.x".into(),
+        tool_name: Some("run".into()),
+        permission: "allow".into(),
+        granted_by: None,
+        granted_at: chrono::Utc::now().to_rfc3339(),
+        expires_at: None,
+        justification: None,
+        metadata: None,
+    };
+    assert!(db::save_access_control_entry(&pool, &entry).await.unwrap() > 0);
+    let access = db::resolve_tool_access(&pool, "agent.cloto_default", "tool.x", "run")
+        .await
+        .unwrap();
+    assert_eq!(access, "allow");
+    let denied = db::resolve_tool_access(&pool, "agent.cloto_default", "tool.x", "other")
+        .await
+        .unwrap();
+    assert_eq!(denied, "deny");
+
+    db::revoke_api_key(&pool, "k").await.unwrap();
+    db::revoke_api_key(&pool, "k").await.unwrap();
+    assert!(db::is_api_key_revoked(&pool, "k").await.unwrap());
+
+    let key = db::ApiKeyRow {
+        id: "key.1".into(),
+        name: "viewer".into(),
+        key_hash: db::hash_scoped_api_key("cloto_abc"),
+        key_prefix: "cloto_abc".into(),
+        scope: "read_only".into(),
+        created_at: 1,
+        rate_per_second: Some(5),
+        rate_burst: Some(10),
+    };
+    db::create_api_key(&pool, &key).await.unwrap();
+    let keys = db::list_api_keys(&pool).await.unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(
+        (keys[0].rate_per_second, keys[0].rate_burst),
+        (Some(5), Some(10))
+    );
+    assert_eq!(
+        db::delete_api_key(&pool, "key.1").await.unwrap(),
+        Some(key.key_hash)
+    );
+    assert!(db::delete_api_key(&pool, "key.1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_cron_jobs_and_runs() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    let job = db::CronJobRow {
+        id: "job".into(),
+        agent_id: "agent.cloto_default".into(),
+        name: "tick".into(),
+        enabled: true,
+        schedule_type: "interval".into(),
+        schedule_value: "60".into(),
+        engine_id: None,
+        message: "hi".into(),
+        next_run_at: 0,
+        last_run_at: None,
+        last_status: None,
+        last_error: None,
+        max_iterations: Some(8),
+        created_at: String::new(),
+        misfire_policy: "run_once".into(),
+        jitter_secs: 0,
+        chain_on: "success".into(),
+        weekdays_only: false,
+        skip_dates: String::new(),
+        timezone: Some("Asia/Tokyo".into()),
+    };
+    db::create_cron_job(&pool, &job).await.unwrap();
+    let due = db::get_due_cron_jobs(&pool, 1).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].timezone.as_deref(), Some("Asia/Tokyo"));
+
+    let run_id = db::insert_cron_job_run(&pool, "job", "manual", "msg", 10)
+        .await
+        .unwrap();
+    assert!(run_id > 0);
+    let finished = db::finish_cron_job_run(&pool, "msg", "success", None, None, 25)
+        .await
+        .unwrap();
+    assert_eq!(finished.as_deref(), Some("job"));
+    let runs = db::list_cron_job_runs(&pool, "job", 10).await.unwrap();
+    assert_eq!(runs[0].duration_ms, Some(15));
+
+    db::set_cron_job_enabled(&pool, "job", false).await.unwrap();
+    assert!(db::get_due_cron_jobs(&pool, 1).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_plugin_permission_grants() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    let manager = cloto_core::managers::PluginManager::new(pool, vec![], 30, 5).unwrap();
+    manager
+        .grant_permission("mind.deepseek", cloto_shared::Permission::FileRead)
+        .await
+        .unwrap();
+    manager
+        .grant_permission("mind.deepseek", cloto_shared::Permission::FileRead)
+        .await
+        .unwrap();
+    let perms = manager.get_permissions("mind.deepseek").await.unwrap();
+    assert_eq!(
+        perms,
+        vec![
+            cloto_shared::Permission::NetworkAccess,
+            cloto_shared::Permission::FileRead
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_rooms_audit_and_permission_requests() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    let room = db::ChatRoomRow {
+        id: "r".into(),
+        name: "Room".into(),
+        turn_policy: "round_robin".into(),
+        next_turn: 0,
+        created_at: 1,
+    };
+    db::create_chat_room(&pool, &room, &["agent.cloto_default".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(db::take_chat_room_turn(&pool, "r").await.unwrap(), 0);
+    assert_eq!(db::take_chat_room_turn(&pool, "r").await.unwrap(), 1);
+    for id in ["first", "second"] {
+        db::save_chat_room_message(
+            &pool,
+            &db::ChatRoomMessageRow {
+                id: id.into(),
+                room_id: "r".into(),
+                source: "user".into(),
+                speaker_id: "u".into(),
+                speaker_name: "U".into(),
+                content: id.into(),
+                created_at: 5,
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let transcript = db::get_chat_room_messages(&pool, "r", None, 10)
+        .await
+        .unwrap();
+    let ids: Vec<&str> = transcript.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, ["second", "first"]);
+
+    db::write_audit_log(
+        &pool,
+        db::AuditLogEntry {
+            timestamp: chrono::Utc::now(),
+            event_type: "TOOL_EXECUTED".into(),
+            actor_id: Some("agent.cloto_default".into()),
+            target_id: None,
+            permission: None,
+            result: "SUCCESS".into(),
+            reason: String::new(),
+            metadata: None,
+            trace_id: None,
+        },
+    )
+    .await
+    .unwrap();
+    let logs = db::query_audit_logs_filtered(&pool, Some("TOOL_EXECUTED"), None, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+
+    db::create_permission_request(
+        &pool,
+        db::PermissionRequest {
+            request_id: "req".into(),
+            created_at: chrono::Utc::now(),
+            plugin_id: "tool.x".into(),
+            permission_type: "NetworkAccess".into(),
+            target_resource: None,
+            justification: "test".into(),
+            status: "pending".into(),
+            approved_by: None,
+            approved_at: None,
+            expires_at: None,
+            metadata: None,
+        },
+    )
+    .await
+    .unwrap();
+    db::update_permission_request(&pool, "req", "approved", "admin")
+        .await
+        .unwrap();
+    assert!(db::is_permission_approved(&pool, "tool.x", "NetworkAccess")
+        .await
+        .unwrap());
+    assert!(db::get_access_summary(&pool, "tool.x")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_tool_invocation_requests() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    for id in ["t1", "t2"] {
+        db::create_tool_invocation_request(
+            &pool,
+            &db::ToolInvocationRequestRow {
+                id: id.into(),
+                server_id: "tool.terminal".into(),
+                tool_name: "execute_command".into(),
+                arguments: "{}".into(),
+                status: "pending".into(),
+                created_at: 1_000,
+                decided_at: None,
+                decided_by: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(
+        db::list_pending_tool_invocation_requests(&pool)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(
+        db::decide_tool_invocation_request(&pool, "t1", "approved", "admin")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !db::decide_tool_invocation_request(&pool, "t1", "denied", "admin")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        db::expire_pending_tool_invocation_requests(&pool)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(db::list_pending_tool_invocation_requests(&pool)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_tool_invocations() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    for (id, agent, status) in [
+        ("c1", "agent.a", "SUCCESS"),
+        ("c2", "agent.a", "FAILURE"),
+        ("c3", "agent.b", "SUCCESS"),
+    ] {
+        db::create_tool_invocation(
+            &pool,
+            &db::ToolInvocationRow {
+                id: id.into(),
+                agent_id: agent.into(),
+                engine_id: "mind.test".into(),
+                tool_name: "execute_command".into(),
+                server_id: Some("tool.terminal".into()),
+                arguments: "{}".into(),
+                status: status.into(),
+                error: None,
+                duration_ms: 5,
+                trace_id: None,
+                created_at: 1_000,
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let all = db::list_tool_invocations(&pool, &db::ToolInvocationFilter::default(), 10)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 3);
+    let filter = db::ToolInvocationFilter {
+        agent_id: Some("agent.a"),
+        status: Some("SUCCESS"),
+        before: Some(2_000),
+        ..Default::default()
+    };
+    let rows = db::list_tool_invocations(&pool, &filter, 10).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, "c1");
+}
+
+#[tokio::test]
+async fn test_event_journal() {
+    let Some(pool) = fresh_pool().await else {
+        return;
+    };
+    let entries: Vec<db::JournalEntry> = [
+        ("t1", "MessageReceived", 1_000),
+        ("t1", "ThoughtResponse", 2_000),
+        ("t2", "MessageReceived", 3_000),
+    ]
+    .into_iter()
+    .map(|(trace_id, event_type, created_at)| db::JournalEntry {
+        trace_id: trace_id.into(),
+        event_type,
+        event: format!("{{\"at\":{}}}", created_at).into(),
+        created_at,
+    })
+    .collect();
+    db::insert_events(&pool, &entries).await.unwrap();
+
+    let filter = db::JournalFilter {
+        event_type: Some("MessageReceived"),
+        ..Default::default()
+    };
+    assert_eq!(db::count_events(&pool, &filter).await.unwrap(), 2);
+    let newest_first = db::list_events(&pool, &filter, 10, 0).await.unwrap();
+    assert_eq!(newest_first, vec!["{\"at\":3000}", "{\"at\":1000}"]);
+    let filter = db::JournalFilter {
+        trace_id: Some("t1"),
+        since: Some(1_500),
+        until: Some(5_000),
+        ..Default::default()
+    };
+    assert_eq!(
+        db::list_events(&pool, &filter, 10, 0).await.unwrap().len(),
+        1
+    );
+
+    assert_eq!(db::prune_events(&pool, 2_500).await.unwrap(), 2);
+    let remaining = db::JournalFilter::default();
+    assert_eq!(db::count_events(&pool, &remaining).await.unwrap(), 1);
+}
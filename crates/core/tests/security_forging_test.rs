@@ -1,11 +1,11 @@
+use cloto_core::db::DbPool;
 use cloto_core::{
-    events::EventProcessor,
+    events::{EventProcessor, SerializedEvent},
     managers::{AgentManager, PluginManager, PluginRegistry},
 };
 use cloto_shared::{
     ClotoEvent, ClotoId, HandAction, Permission, Plugin, PluginCast, PluginManifest, ServiceType,
 };
-use sqlx::SqlitePool;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
@@ -35,6 +35,7 @@ impl Plugin for AdminPlugin {
             is_active: true,
             is_configured: true,
             required_config_keys: vec![],
+            config_schema: vec![],
             action_icon: None,
             action_target: None,
             icon_data: None,
@@ -43,6 +44,7 @@ impl Plugin for AdminPlugin {
             required_permissions: vec![Permission::InputControl],
             provided_capabilities: vec![],
             provided_tools: vec![],
+            subscribes: vec![],
         }
     }
 }
@@ -71,6 +73,7 @@ impl Plugin for MaliciousPlugin {
             is_active: true,
             is_configured: true,
             required_config_keys: vec![],
+            config_schema: vec![],
             action_icon: None,
             action_target: None,
             icon_data: None,
@@ -79,6 +82,7 @@ impl Plugin for MaliciousPlugin {
             required_permissions: vec![], // 権限なし！
             provided_capabilities: vec![],
             provided_tools: vec![],
+            subscribes: vec![],
         }
     }
 
@@ -107,7 +111,7 @@ impl Plugin for MaliciousPlugin {
 #[tokio::test]
 async fn test_vulnerability_event_forging() {
     // 1. Setup DB & Managers
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
@@ -140,7 +144,7 @@ async fn test_vulnerability_event_forging() {
     // Maliceには権限を与えない
 
     // 5. Setup Event Loop
-    let (tx_broadcast, mut rx_broadcast) = broadcast::channel::<Arc<ClotoEvent>>(100);
+    let (tx_broadcast, mut rx_broadcast) = broadcast::channel::<SerializedEvent>(100);
     let (tx_internal, rx_internal) = mpsc::channel::<cloto_core::EnvelopedEvent>(100);
 
     let metrics = Arc::new(cloto_core::managers::SystemMetrics::new());
@@ -66,7 +66,7 @@ async fn test_sse_handler_streams_events() {
         let event = Arc::new(ClotoEvent::new(ClotoEventData::SystemNotification(
             "Test message".to_string(),
         )));
-        let _ = tx.send(event);
+        let _ = tx.send(event.into());
     });
 
     let app = create_test_router(state);
@@ -124,7 +124,7 @@ async fn test_sse_handler_handles_lagged_receiver() {
             let event = Arc::new(ClotoEvent::new(ClotoEventData::SystemNotification(
                 format!("Message {}", i),
             )));
-            let _ = tx.send(event);
+            let _ = tx.send(event.into());
         }
     });
 
@@ -165,3 +165,108 @@ async fn test_sse_handler_handles_lagged_receiver() {
 
     // Test passes if we didn't panic
 }
+
+#[tokio::test]
+async fn test_sse_handler_sends_published_json() {
+    let state = create_test_app_state().await;
+
+    // The handler must forward the JSON serialized at publish time verbatim
+    let tx = state.tx.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let event = cloto_core::events::SerializedEvent {
+            event: Arc::new(ClotoEvent::new(ClotoEventData::SystemNotification(
+                "ignored".to_string(),
+            ))),
+            json: r#"{"cached":true}"#.into(),
+        };
+        let _ = tx.send(event);
+    });
+
+    let app = create_test_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/events")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let mut stream = response.into_body().into_data_stream();
+
+    // Handshake
+    let _ = tokio::time::timeout(tokio::time::Duration::from_secs(1), stream.next())
+        .await
+        .expect("Timeout waiting for handshake");
+
+    let chunk = tokio::time::timeout(tokio::time::Duration::from_secs(2), stream.next())
+        .await
+        .expect("Timeout waiting for event")
+        .expect("Stream ended unexpectedly")
+        .expect("Error reading stream");
+    let data = String::from_utf8(chunk.to_vec()).unwrap();
+    assert!(data.contains(r#"data: {"cached":true}"#));
+    assert!(!data.contains("ignored"));
+}
+
+#[tokio::test]
+async fn test_sse_handler_filters_by_type_and_agent() {
+    let state = create_test_app_state().await;
+
+    let tx = state.tx.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let response = |agent_id: &str| ClotoEventData::ThoughtResponse {
+            agent_id: agent_id.to_string(),
+            engine_id: "mind.test".to_string(),
+            content: format!("reply from {}", agent_id),
+            source_message_id: "msg-1".to_string(),
+            metadata: None,
+        };
+        for data in [
+            ClotoEventData::GazeUpdated(cloto_shared::GazeData {
+                x: 1,
+                y: 2,
+                confidence: 0.9,
+                fixated: false,
+            }),
+            response("agent.b"),
+            ClotoEventData::AgentPowerChanged {
+                agent_id: "agent.a".to_string(),
+                enabled: true,
+            },
+            response("agent.a"),
+        ] {
+            let _ = tx.send(Arc::new(ClotoEvent::new(data)).into());
+        }
+    });
+
+    let app = create_test_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/events?types=ThoughtResponse,%20MessageReceived&agent_id=agent.a")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let mut stream = response.into_body().into_data_stream();
+
+    // Handshake
+    let _ = tokio::time::timeout(tokio::time::Duration::from_secs(1), stream.next())
+        .await
+        .expect("Timeout waiting for handshake");
+
+    // Gaze, other agents' replies and unlisted types are dropped server-side
+    let chunk = tokio::time::timeout(tokio::time::Duration::from_secs(2), stream.next())
+        .await
+        .expect("Timeout waiting for event")
+        .expect("Stream ended unexpectedly")
+        .expect("Error reading stream");
+    let data = String::from_utf8(chunk.to_vec()).unwrap();
+    assert!(data.contains("reply from agent.a"), "{}", data);
+}
@@ -1,19 +1,19 @@
+use cloto_core::db::DbPool;
 use cloto_core::handlers::system::SystemHandler;
 use cloto_core::managers::{AgentManager, PluginRegistry};
 use cloto_shared::{ClotoEvent, ClotoMessage, MessageSource, Plugin};
-use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
 #[tokio::test]
 async fn test_system_handler_loop_prevention() {
-    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
     cloto_core::db::init_db(&pool, "sqlite::memory:")
         .await
         .unwrap();
 
     let agent_id = "agent.test";
-    sqlx::query("INSERT INTO agents (id, name, description, status, default_engine_id, required_capabilities, metadata, enabled) VALUES (?, 'Test Agent', 'Desc', 'online', 'engine.test', '[\"Reasoning\", \"Memory\"]', '{}', 1)")
+    sqlx::query("INSERT INTO agents (id, name, description, status, default_engine_id, required_capabilities, metadata, enabled) VALUES ($1, 'Test Agent', 'Desc', 'online', 'engine.test', '[\"Reasoning\", \"Memory\"]', '{}', 1)")
         .bind(agent_id)
         .execute(&pool).await.unwrap();
 
@@ -73,3 +73,205 @@ async fn test_system_handler_loop_prevention() {
         "Should NOT have received any event for agent message"
     );
 }
+
+/// Engine that generates its reply in two chunks.
+struct StreamingEngine;
+
+impl cloto_shared::PluginCast for StreamingEngine {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_reasoning(&self) -> Option<&dyn cloto_shared::ReasoningEngine> {
+        Some(self)
+    }
+}
+
+#[async_trait::async_trait]
+impl Plugin for StreamingEngine {
+    fn manifest(&self) -> cloto_shared::PluginManifest {
+        cloto_shared::PluginManifest {
+            id: "mind.stream".to_string(),
+            name: "Stream".to_string(),
+            description: String::new(),
+            version: "1.0".to_string(),
+            category: cloto_shared::PluginCategory::Agent,
+            service_type: cloto_shared::ServiceType::Reasoning,
+            tags: vec![],
+            is_active: true,
+            is_configured: true,
+            required_config_keys: vec![],
+            config_schema: vec![],
+            action_icon: None,
+            action_target: None,
+            icon_data: None,
+            magic_seal: 0x5645_5253,
+            sdk_version: "1.0".to_string(),
+            required_permissions: vec![],
+            provided_capabilities: vec![],
+            provided_tools: vec![],
+            subscribes: vec![],
+        }
+    }
+
+    async fn on_event(
+        &self,
+        _event: &ClotoEvent,
+    ) -> anyhow::Result<Option<cloto_shared::ClotoEventData>> {
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl cloto_shared::ReasoningEngine for StreamingEngine {
+    fn name(&self) -> &'static str {
+        "stream"
+    }
+
+    async fn think(
+        &self,
+        _agent: &cloto_shared::AgentMetadata,
+        _message: &ClotoMessage,
+        _context: Vec<ClotoMessage>,
+    ) -> anyhow::Result<String> {
+        Ok("Hello".to_string())
+    }
+
+    async fn think_streaming(
+        &self,
+        _agent: &cloto_shared::AgentMetadata,
+        _message: &ClotoMessage,
+        _context: Vec<ClotoMessage>,
+        chunks: mpsc::Sender<String>,
+    ) -> anyhow::Result<String> {
+        chunks.send("Hel".to_string()).await?;
+        chunks.send("lo".to_string()).await?;
+        Ok("Hello".to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_streamed_chunks_precede_thought_response() {
+    use cloto_shared::ClotoEventData;
+
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+    cloto_core::db::init_db(&pool, "sqlite::memory:")
+        .await
+        .unwrap();
+    let agent_id = "agent.stream";
+    sqlx::query("INSERT INTO agents (id, name, description, status, default_engine_id, required_capabilities, metadata, enabled) VALUES ($1, 'Streamer', 'Desc', 'online', 'mind.stream', '[\"Reasoning\"]', '{}', 1)")
+        .bind(agent_id)
+        .execute(&pool).await.unwrap();
+
+    let registry = Arc::new(PluginRegistry::new(5, 10));
+    registry
+        .plugins
+        .write()
+        .await
+        .insert("mind.stream".to_string(), Arc::new(StreamingEngine));
+    let (event_tx, mut event_rx) = mpsc::channel(10);
+    let handler = SystemHandler::new(
+        registry,
+        AgentManager::new(pool),
+        agent_id.to_string(),
+        event_tx,
+        10,
+        Arc::new(cloto_core::managers::SystemMetrics::new()),
+        vec![],
+        16,
+        30,
+    );
+
+    let msg = ClotoMessage::new(
+        MessageSource::User {
+            id: "user1".into(),
+            name: "User".into(),
+        },
+        "Hi".into(),
+    );
+    let msg_id = msg.id.clone();
+    handler.handle_message(msg).await.unwrap();
+
+    let mut deltas = Vec::new();
+    let mut response = None;
+    while let Ok(envelope) = event_rx.try_recv() {
+        match &envelope.event.data {
+            ClotoEventData::ThoughtResponseChunk {
+                delta,
+                source_message_id,
+                engine_id,
+                ..
+            } => {
+                assert!(response.is_none(), "chunk after ThoughtResponse");
+                assert_eq!(source_message_id, &msg_id);
+                assert_eq!(engine_id, "mind.stream");
+                deltas.push(delta.clone());
+            }
+            ClotoEventData::ThoughtResponse { content, .. } => response = Some(content.clone()),
+            _ => {}
+        }
+    }
+    assert_eq!(deltas, vec!["Hel", "lo"]);
+    assert_eq!(response.as_deref(), Some("Hello"));
+}
+
+#[tokio::test]
+async fn test_no_new_runs_while_draining() {
+    use cloto_shared::ClotoEventData;
+
+    let pool = DbPool::connect("sqlite::memory:").await.unwrap();
+    cloto_core::db::init_db(&pool, "sqlite::memory:")
+        .await
+        .unwrap();
+    let agent_id = "agent.stream";
+    sqlx::query("INSERT INTO agents (id, name, description, status, default_engine_id, required_capabilities, metadata, enabled) VALUES ($1, 'Streamer', 'Desc', 'online', 'mind.stream', '[\"Reasoning\"]', '{}', 1)")
+        .bind(agent_id)
+        .execute(&pool).await.unwrap();
+
+    let registry = Arc::new(PluginRegistry::new(5, 10));
+    registry
+        .plugins
+        .write()
+        .await
+        .insert("mind.stream".to_string(), Arc::new(StreamingEngine));
+    let (event_tx, mut event_rx) = mpsc::channel(10);
+    let drain = Arc::new(cloto_core::drain::DrainTracker::new());
+    let handler = SystemHandler::new(
+        registry,
+        AgentManager::new(pool),
+        agent_id.to_string(),
+        event_tx,
+        10,
+        Arc::new(cloto_core::managers::SystemMetrics::new()),
+        vec![],
+        16,
+        30,
+    )
+    .with_drain_tracker(drain.clone());
+
+    let user_event = || {
+        ClotoEvent::new(ClotoEventData::MessageReceived(ClotoMessage::new(
+            MessageSource::User {
+                id: "user1".into(),
+                name: "User".into(),
+            },
+            "Hi".into(),
+        )))
+    };
+    let responses = |rx: &mut mpsc::Receiver<cloto_core::EnvelopedEvent>| {
+        let mut count = 0;
+        while let Ok(envelope) = rx.try_recv() {
+            if matches!(envelope.event.data, ClotoEventData::ThoughtResponse { .. }) {
+                count += 1;
+            }
+        }
+        count
+    };
+
+    handler.on_event(&user_event()).await.unwrap();
+    assert_eq!(responses(&mut event_rx), 1);
+    assert_eq!(drain.active(), 0, "finished runs release the tracker");
+
+    drain.begin();
+    handler.on_event(&user_event()).await.unwrap();
+    assert_eq!(responses(&mut event_rx), 0, "no run starts while draining");
+}
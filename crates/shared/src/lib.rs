@@ -7,6 +7,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 pub mod llm;
+pub mod schedule;
 
 // Legacy re-exports removed (cloto_macros, inventory) — all plugins are now MCP servers.
 
@@ -14,6 +15,18 @@ pub mod llm;
 /// M-14: Plugins should reference this instead of their own CARGO_PKG_VERSION
 pub const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Lowercase hex encoding, as used for signatures and key hashes.
+#[must_use]
+pub fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write as _;
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut out, b| {
+            let _ = write!(out, "{:02x}", b);
+            out
+        })
+}
+
 /// Clotoプラットフォーム内での一意の識別子（Agent, Plugin, Session等）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -51,6 +64,12 @@ impl ClotoId {
         let namespace = Uuid::NAMESPACE_DNS;
         Self(Uuid::new_v5(&namespace, name.as_bytes()))
     }
+
+    /// Raw 128-bit value (used as the OpenTelemetry trace ID).
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        self.0.as_bytes()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -82,6 +101,12 @@ pub enum Permission {
     MemoryRead,
     MemoryWrite,
     AdminAccess,
+    /// System clipboard read/write (desktop shell only)
+    ClipboardAccess,
+    /// Webcam frame capture
+    CameraRead,
+    /// Relay messages between an external chat service and agents (channel adapters)
+    ChannelRelay,
 }
 
 impl std::fmt::Display for Permission {
@@ -239,12 +264,24 @@ pub trait ProcessCapability: Send + Sync {
     async fn execute(&self, cmd: &str, args: &[String]) -> anyhow::Result<(String, String, i32)>;
 }
 
+/// System clipboard capability (HAL).
+/// Only injected when ClipboardAccess is granted and a host provides a
+/// clipboard (the desktop shell); headless kernels have no implementation.
+#[async_trait::async_trait]
+pub trait ClipboardCapability: Send + Sync {
+    /// Read the current clipboard contents as text.
+    async fn read_text(&self) -> anyhow::Result<String>;
+    /// Replace the clipboard contents with text.
+    async fn write_text(&self, text: &str) -> anyhow::Result<()>;
+}
+
 /// 実行時に注入される具体的な能力のラッパー
 #[derive(Clone)]
 pub enum PluginCapability {
     Network(Arc<dyn NetworkCapability>),
     File(Arc<dyn FileCapability>),
     Process(Arc<dyn ProcessCapability>),
+    Clipboard(Arc<dyn ClipboardCapability>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -267,6 +304,90 @@ pub enum PluginCategory {
     Other,
 }
 
+/// Value type of a plugin configuration key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigFieldType {
+    String,
+    Int {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<i64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<i64>,
+    },
+    Bool,
+    /// A string never returned in clear by the config API.
+    Secret,
+    Enum {
+        options: Vec<String>,
+    },
+}
+
+/// One key of a plugin's configuration schema.
+///
+/// ```
+/// use cloto_shared::{ConfigField, ConfigFieldType};
+///
+/// let field = ConfigField::new("region", ConfigFieldType::String)
+///     .describe("Deployment region")
+///     .with_default("eu-west-1")
+///     .with_pattern("[a-z]+-[a-z]+-[0-9]");
+/// assert!(!field.required);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigField {
+    pub key: String,
+    #[serde(flatten)]
+    pub field_type: ConfigFieldType,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Regular expression the whole value must match (string and secret keys).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl ConfigField {
+    #[must_use]
+    pub fn new(key: &str, field_type: ConfigFieldType) -> Self {
+        Self {
+            key: key.to_string(),
+            field_type,
+            description: String::new(),
+            default: None,
+            required: false,
+            pattern: None,
+        }
+    }
+
+    #[must_use]
+    pub fn describe(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    #[must_use]
+    pub fn with_default(mut self, default: &str) -> Self {
+        self.default = Some(default.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    #[must_use]
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginManifest {
     pub id: String,
@@ -279,6 +400,10 @@ pub struct PluginManifest {
     pub is_active: bool,
     pub is_configured: bool,
     pub required_config_keys: Vec<String>,
+    /// Typed description of the plugin's configuration keys. When non-empty,
+    /// `PUT /api/plugins/:id/config` rejects unknown keys and invalid values.
+    #[serde(default)]
+    pub config_schema: Vec<ConfigField>,
     pub action_icon: Option<String>,
     pub action_target: Option<String>,
     pub icon_data: Option<String>,
@@ -287,6 +412,11 @@ pub struct PluginManifest {
     pub required_permissions: Vec<Permission>,
     pub provided_capabilities: Vec<CapabilityType>,
     pub provided_tools: Vec<String>,
+    /// Event types (`ClotoEventData::type_name`, e.g. `ThoughtRequested`)
+    /// passed to `on_event`. Empty subscribes to every event. Names that are
+    /// not event types are logged as a warning when the plugin is registered.
+    #[serde(default)]
+    pub subscribes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -305,6 +435,29 @@ pub struct ClotoMessage {
     pub content: String,
     pub timestamp: DateTime<Utc>,
     pub metadata: HashMap<String, String>,
+    /// Files uploaded with the message (`data/attachments`), filled in by
+    /// the kernel before the message reaches a reasoning engine.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<AttachmentRef>,
+}
+
+/// A chat attachment as passed to reasoning engines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttachmentRef {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    /// Base64 file content. Only set for images sent to engines that
+    /// support vision (`ReasoningEngine::supports_vision`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+impl AttachmentRef {
+    #[must_use]
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/") && self.mime_type != "image/svg+xml"
+    }
 }
 
 impl ClotoMessage {
@@ -317,6 +470,7 @@ impl ClotoMessage {
             content,
             timestamp: Utc::now(),
             metadata: HashMap::new(),
+            attachments: Vec::new(),
         }
     }
 }
@@ -473,6 +627,12 @@ pub trait ReasoningEngine: Plugin {
         false
     }
 
+    /// Whether this engine accepts images. The kernel then fills in the
+    /// base64 `data` of image attachments on the message. Default: false.
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
     /// Think with tool support. Default delegates to think() as Final.
     async fn think_with_tools(
         &self,
@@ -485,6 +645,21 @@ pub trait ReasoningEngine: Plugin {
         let content = self.think(agent, message, context).await?;
         Ok(ThinkResult::Final(content))
     }
+
+    /// Think while sending the response text to `chunks` as it is generated.
+    /// Returns the complete response. Default delegates to think() and sends
+    /// it as a single chunk.
+    async fn think_streaming(
+        &self,
+        agent: &AgentMetadata,
+        message: &ClotoMessage,
+        context: Vec<ClotoMessage>,
+        chunks: tokio::sync::mpsc::Sender<String>,
+    ) -> anyhow::Result<String> {
+        let content = self.think(agent, message, context).await?;
+        let _ = chunks.send(content.clone()).await;
+        Ok(content)
+    }
 }
 
 #[async_trait]
@@ -499,6 +674,79 @@ pub trait MemoryProvider: Plugin {
     ) -> anyhow::Result<Vec<ClotoMessage>>;
 }
 
+/// How a consensus session turns the engines' answers into one response.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusStrategy {
+    /// A synthesizer engine merges the answers into one.
+    #[default]
+    Synthesis,
+    /// The answer given (near-)verbatim by the most engines wins.
+    MajorityVote,
+    /// Every engine ranks all answers; the winner is found by instant runoff.
+    RankedChoice,
+    /// A judge engine picks the best answer. `None` uses the synthesizer.
+    BestOfN { judge: Option<String> },
+    /// Engines revise their answers after reading each other's for
+    /// `rounds` rounds in total, then the final answers are synthesized.
+    Debate { rounds: u8 },
+}
+
+impl ConsensusStrategy {
+    /// Debate rounds used when none are given.
+    pub const DEFAULT_DEBATE_ROUNDS: u8 = 2;
+    /// Upper bound on debate rounds.
+    pub const MAX_DEBATE_ROUNDS: u8 = 5;
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Synthesis => "synthesis",
+            Self::MajorityVote => "majority_vote",
+            Self::RankedChoice => "ranked_choice",
+            Self::BestOfN { .. } => "best_of_n",
+            Self::Debate { .. } => "debate",
+        }
+    }
+}
+
+/// Parses `synthesis`, `majority_vote`, `ranked_choice`, `best_of_n` or
+/// `best_of_n:<judge engine>`, and `debate` or `debate:<rounds>`.
+impl std::str::FromStr for ConsensusStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.trim().split_once(':') {
+            Some((name, arg)) => (name, Some(arg.trim()).filter(|a| !a.is_empty())),
+            None => (s.trim(), None),
+        };
+        match (name, arg) {
+            ("synthesis", None) => Ok(Self::Synthesis),
+            ("majority_vote", None) => Ok(Self::MajorityVote),
+            ("ranked_choice", None) => Ok(Self::RankedChoice),
+            ("best_of_n", judge) => Ok(Self::BestOfN {
+                judge: judge.map(String::from),
+            }),
+            ("debate", None) => Ok(Self::Debate {
+                rounds: Self::DEFAULT_DEBATE_ROUNDS,
+            }),
+            ("debate", Some(rounds)) => match rounds.parse::<u8>() {
+                Ok(rounds) if (2..=Self::MAX_DEBATE_ROUNDS).contains(&rounds) => {
+                    Ok(Self::Debate { rounds })
+                }
+                _ => Err(format!(
+                    "debate rounds must be between 2 and {}",
+                    Self::MAX_DEBATE_ROUNDS
+                )),
+            },
+            _ => Err(format!(
+                "Unknown consensus strategy '{}' (synthesis, majority_vote, ranked_choice, best_of_n, debate)",
+                s.trim()
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClotoEvent {
     pub trace_id: ClotoId,
@@ -541,11 +789,44 @@ pub enum ClotoEventData {
         engine_id: String,
         content: String,
         source_message_id: String,
+        /// Details of how the response was produced, e.g. the per-engine
+        /// results of a consensus session.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        metadata: Option<serde_json::Value>,
+    },
+    /// Partial response text while an engine is still generating. Only
+    /// forwarded to SSE subscribers; the complete text follows as `ThoughtResponse`.
+    ThoughtResponseChunk {
+        agent_id: String,
+        /// Set by the kernel for chunks emitted by MCP engine servers.
+        #[serde(default)]
+        engine_id: String,
+        source_message_id: String,
+        /// Text generated since the previous chunk.
+        delta: String,
+    },
+    /// One agent addressing another. The kernel delivers `content` to
+    /// `to_agent` as a `MessageReceived` from `from_agent`, one event depth
+    /// deeper, so agent-to-agent chains stop at `MAX_EVENT_DEPTH`.
+    AgentMessage {
+        from_agent: String,
+        to_agent: String,
+        content: String,
+    },
+    /// A call to a tool listed in its MCP server's `approval_required_tools`
+    /// is waiting for `POST /api/tool-calls/:request_id/approve` (or `deny`).
+    ToolApprovalRequested {
+        request_id: String,
+        server_id: String,
+        tool_name: String,
+        arguments: serde_json::Value,
     },
     /// 複数プラグインによる合意形成の開始 (Prototype)
     ConsensusRequested {
         task: String,
         engine_ids: Vec<String>,
+        #[serde(default)]
+        strategy: ConsensusStrategy,
     },
     /// 各プラグインからの合意形成用提案 (Prototype)
     ConsensusProposal {
@@ -578,6 +859,17 @@ pub enum ClotoEventData {
         agent_id: String,
         enabled: bool,
     },
+    /// Heartbeat: the agent's engine answered again after being offline
+    AgentOnline {
+        agent_id: String,
+        engine_id: String,
+    },
+    /// Heartbeat: the agent's engine stopped answering pings
+    AgentOffline {
+        agent_id: String,
+        engine_id: String,
+        reason: String,
+    },
     // ── Agentic Loop Events ──
     /// A tool was invoked during an agentic loop iteration (observability).
     ToolInvoked {
@@ -597,6 +889,113 @@ pub enum ClotoEventData {
         total_tool_calls: u32,
         source_message_id: String,
     },
+    /// An agentic loop was abandoned because the engine kept repeating or
+    /// cycling through the same tool calls.
+    AgenticLoopAborted {
+        agent_id: String,
+        engine_id: String,
+        /// `repeated_tool_calls` or `oscillating_tool_calls`
+        reason: String,
+        calls: Vec<String>,
+        total_iterations: u8,
+        total_tool_calls: u32,
+        source_message_id: String,
+    },
+}
+
+impl ClotoEventData {
+    /// Every `type_name`, for checking event types given by name (e.g.
+    /// `PluginManifest::subscribes`). New variants go here too.
+    pub const TYPE_NAMES: &'static [&'static str] = &[
+        "MessageReceived",
+        "VisionUpdated",
+        "GazeUpdated",
+        "ActionRequested",
+        "SystemNotification",
+        "ThoughtRequested",
+        "ThoughtResponse",
+        "ThoughtResponseChunk",
+        "AgentMessage",
+        "ToolApprovalRequested",
+        "ConsensusRequested",
+        "ConsensusProposal",
+        "ConfigUpdated",
+        "PermissionRequested",
+        "PermissionGranted",
+        "ManifestUpdated",
+        "AgentPowerChanged",
+        "AgentOnline",
+        "AgentOffline",
+        "ToolInvoked",
+        "AgenticLoopCompleted",
+        "AgenticLoopAborted",
+    ];
+
+    /// The `type` tag this variant serializes with (e.g. `MessageReceived`).
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::MessageReceived(_) => "MessageReceived",
+            Self::VisionUpdated(_) => "VisionUpdated",
+            Self::GazeUpdated(_) => "GazeUpdated",
+            Self::ActionRequested { .. } => "ActionRequested",
+            Self::SystemNotification(_) => "SystemNotification",
+            Self::ThoughtRequested { .. } => "ThoughtRequested",
+            Self::ThoughtResponse { .. } => "ThoughtResponse",
+            Self::ThoughtResponseChunk { .. } => "ThoughtResponseChunk",
+            Self::AgentMessage { .. } => "AgentMessage",
+            Self::ToolApprovalRequested { .. } => "ToolApprovalRequested",
+            Self::ConsensusRequested { .. } => "ConsensusRequested",
+            Self::ConsensusProposal { .. } => "ConsensusProposal",
+            Self::ConfigUpdated { .. } => "ConfigUpdated",
+            Self::PermissionRequested { .. } => "PermissionRequested",
+            Self::PermissionGranted { .. } => "PermissionGranted",
+            Self::ManifestUpdated { .. } => "ManifestUpdated",
+            Self::AgentPowerChanged { .. } => "AgentPowerChanged",
+            Self::AgentOnline { .. } => "AgentOnline",
+            Self::AgentOffline { .. } => "AgentOffline",
+            Self::ToolInvoked { .. } => "ToolInvoked",
+            Self::AgenticLoopCompleted { .. } => "AgenticLoopCompleted",
+            Self::AgenticLoopAborted { .. } => "AgenticLoopAborted",
+        }
+    }
+
+    /// Whether the event concerns `agent_id`: sent by or to it, or about it.
+    /// Events not tied to an agent (gaze, plugin config, ...) never match.
+    #[must_use]
+    pub fn involves_agent(&self, agent_id: &str) -> bool {
+        match self {
+            Self::MessageReceived(msg) => {
+                msg.target_agent.as_deref() == Some(agent_id)
+                    || matches!(&msg.source, MessageSource::Agent { id } if id == agent_id)
+            }
+            Self::ThoughtRequested { agent, .. } => agent.id == agent_id,
+            Self::AgentMessage {
+                from_agent,
+                to_agent,
+                ..
+            } => from_agent == agent_id || to_agent == agent_id,
+            Self::ThoughtResponse { agent_id: id, .. }
+            | Self::ThoughtResponseChunk { agent_id: id, .. }
+            | Self::AgentPowerChanged { agent_id: id, .. }
+            | Self::AgentOnline { agent_id: id, .. }
+            | Self::AgentOffline { agent_id: id, .. }
+            | Self::ToolInvoked { agent_id: id, .. }
+            | Self::AgenticLoopCompleted { agent_id: id, .. }
+            | Self::AgenticLoopAborted { agent_id: id, .. } => id == agent_id,
+            Self::VisionUpdated(_)
+            | Self::GazeUpdated(_)
+            | Self::ActionRequested { .. }
+            | Self::SystemNotification(_)
+            | Self::ToolApprovalRequested { .. }
+            | Self::ConsensusRequested { .. }
+            | Self::ConsensusProposal { .. }
+            | Self::ConfigUpdated { .. }
+            | Self::PermissionRequested { .. }
+            | Self::PermissionGranted { .. }
+            | Self::ManifestUpdated { .. } => false,
+        }
+    }
 }
 
 impl ClotoEvent {
@@ -630,6 +1029,10 @@ pub struct AgentMetadata {
     pub default_engine_id: Option<String>,
     pub required_capabilities: Vec<CapabilityType>,
     pub metadata: HashMap<String, String>,
+    /// System prompt template replacing the built-in one; see
+    /// [`llm::render_system_prompt`] for the variables it may use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
 }
 
 impl AgentMetadata {
@@ -11,7 +11,13 @@ use std::collections::HashMap;
 /// Automatically injects platform context (identity, privacy, capabilities)
 /// so agents self-identify as Cloto agents without requiring manual description setup.
 /// The user-supplied `description` serves as role/persona definition layered on top.
-fn build_system_prompt(agent: &AgentMetadata) -> String {
+///
+/// An agent's own `system_prompt` template, when set, replaces all of this.
+fn build_system_prompt(agent: &AgentMetadata, context: &[ClotoMessage]) -> String {
+    if let Some(template) = agent.system_prompt.as_deref().filter(|t| !t.is_empty()) {
+        return render_system_prompt(template, agent, context);
+    }
+
     let has_memory = agent
         .metadata
         .get("preferred_memory")
@@ -36,6 +42,67 @@ fn build_system_prompt(agent: &AgentMetadata) -> String {
     )
 }
 
+/// Variables a `system_prompt` template may reference as `{name}`.
+pub const SYSTEM_PROMPT_VARIABLES: &[&str] =
+    &["agent_name", "agent_id", "description", "date", "memories"];
+
+/// `{name}` placeholders in a template, as byte ranges including the braces.
+fn placeholders(template: &str) -> impl Iterator<Item = (usize, usize, &str)> {
+    template.match_indices('{').filter_map(move |(start, _)| {
+        let rest = &template[start + 1..];
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))?;
+        (len > 0 && rest[len..].starts_with('}')).then(|| (start, start + len + 2, &rest[..len]))
+    })
+}
+
+/// Check that a `system_prompt` template only uses known variables.
+///
+/// # Errors
+/// Names the first unknown `{variable}`.
+pub fn validate_system_prompt(template: &str) -> Result<(), String> {
+    match placeholders(template).find(|(_, _, name)| !SYSTEM_PROMPT_VARIABLES.contains(name)) {
+        Some((_, _, name)) => Err(format!(
+            "Unknown system prompt variable {{{name}}}; expected one of: {}",
+            SYSTEM_PROMPT_VARIABLES.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Fill in a `system_prompt` template.
+///
+/// `{agent_name}`, `{agent_id}` and `{description}` come from the agent,
+/// `{date}` is today's UTC date and `{memories}` lists the recalled context,
+/// one `- content` line per message. Unknown placeholders are left as-is.
+#[must_use]
+pub fn render_system_prompt(
+    template: &str,
+    agent: &AgentMetadata,
+    context: &[ClotoMessage],
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut last = 0;
+    for (start, end, name) in placeholders(template) {
+        let value = match name {
+            "agent_name" => agent.name.clone(),
+            "agent_id" => agent.id.clone(),
+            "description" => agent.description.clone(),
+            "date" => chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            "memories" => context
+                .iter()
+                .map(|m| format!("- {}", m.content))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => continue,
+        };
+        out.push_str(&template[last..start]);
+        out.push_str(&value);
+        last = end;
+    }
+    out.push_str(&template[last..]);
+    out
+}
+
 /// Build the standard OpenAI-compatible messages array.
 ///
 /// Returns `[system_message, ...context_messages, user_message]`.
@@ -50,7 +117,7 @@ pub fn build_chat_messages(
 
     messages.push(serde_json::json!({
         "role": "system",
-        "content": build_system_prompt(agent)
+        "content": build_system_prompt(agent, context)
     }));
 
     for msg in context {
@@ -62,10 +129,33 @@ pub fn build_chat_messages(
         messages.push(serde_json::json!({ "role": role, "content": msg.content }));
     }
 
-    messages.push(serde_json::json!({ "role": "user", "content": message.content }));
+    messages.push(serde_json::json!({ "role": "user", "content": user_content(message) }));
     messages
 }
 
+/// Content of the user message: plain text, or text and `image_url` parts
+/// when image attachments carry data.
+fn user_content(message: &ClotoMessage) -> serde_json::Value {
+    let images: Vec<serde_json::Value> = message
+        .attachments
+        .iter()
+        .filter(|a| a.is_image())
+        .filter_map(|a| {
+            let data = a.data.as_ref()?;
+            Some(serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": format!("data:{};base64,{}", a.mime_type, data) },
+            }))
+        })
+        .collect();
+    if images.is_empty() {
+        return serde_json::json!(message.content);
+    }
+    let mut parts = vec![serde_json::json!({ "type": "text", "text": message.content })];
+    parts.extend(images);
+    serde_json::Value::Array(parts)
+}
+
 /// Build an `HttpRequest` for an OpenAI-compatible chat completions endpoint.
 ///
 /// When `tools` is `Some` and non-empty, the `"tools"` field is included in the body.
@@ -0,0 +1,95 @@
+//! Cron job schedule parsing shared by the kernel scheduler and the CLI, so
+//! a schedule the CLI accepts is one the kernel accepts.
+
+use std::str::FromStr;
+
+/// Shortest allowed `interval` schedule.
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Parse a cron expression. Five fields are standard crontab syntax
+/// (`min hour dom mon dow`, day of week 0-7 with 0 and 7 = Sunday); six or
+/// seven fields add leading seconds (and trailing years) and number days of
+/// week 1-7 from Sunday. Day names (`MON-FRI`) work in either form.
+pub fn parse_cron(expression: &str) -> anyhow::Result<cron::Schedule> {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let expression = if fields.len() == 5 {
+        format!(
+            "0 {} {} {} {} {}",
+            fields[0],
+            fields[1],
+            fields[2],
+            fields[3],
+            crontab_days_of_week(fields[4])
+        )
+    } else {
+        expression.to_string()
+    };
+    cron::Schedule::from_str(&expression)
+        .map_err(|e| anyhow::anyhow!("Invalid cron expression: {}", e))
+}
+
+/// Rewrite numeric crontab days of week (0-7, Sunday = 0 or 7) as day names.
+#[must_use]
+pub fn crontab_days_of_week(field: &str) -> String {
+    const NAMES: [&str; 8] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+    let name = |atom: &str| match atom.parse::<usize>() {
+        Ok(n) if n < NAMES.len() => NAMES[n].to_string(),
+        _ => atom.to_string(),
+    };
+    field
+        .split(',')
+        .map(|part| {
+            // The step after '/' is a count, not a day
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (part, None),
+            };
+            let mut out = range.split('-').map(name).collect::<Vec<_>>().join("-");
+            if let Some(step) = step {
+                out.push('/');
+                out.push_str(step);
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Check a `schedule_type` / `schedule_value` pair without computing its
+/// next run. `after` only checks that an upstream job id was given; whether
+/// that job exists is up to the kernel.
+pub fn validate_schedule(schedule_type: &str, schedule_value: &str) -> anyhow::Result<()> {
+    match schedule_type {
+        "interval" => {
+            let interval_secs: u64 = schedule_value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid interval: must be seconds (integer)"))?;
+            if interval_secs < MIN_INTERVAL_SECS {
+                anyhow::bail!("Minimum interval is {} seconds", MIN_INTERVAL_SECS);
+            }
+        }
+        "once" => {
+            let dt = chrono::DateTime::parse_from_rfc3339(schedule_value)
+                .map_err(|e| anyhow::anyhow!("Invalid ISO 8601 datetime: {}", e))?;
+            if dt <= chrono::Utc::now() {
+                anyhow::bail!("Scheduled time must be in the future");
+            }
+        }
+        "cron" => {
+            if parse_cron(schedule_value)?
+                .upcoming(chrono::Utc)
+                .next()
+                .is_none()
+            {
+                anyhow::bail!("Cron expression has no future occurrences");
+            }
+        }
+        "after" => {
+            if schedule_value.trim().is_empty() {
+                anyhow::bail!("'after' schedule requires the upstream job id");
+            }
+        }
+        _ => anyhow::bail!("Unknown schedule_type: must be 'interval', 'cron', 'once', or 'after'"),
+    }
+    Ok(())
+}
@@ -0,0 +1,283 @@
+//! Screen capture commands.
+//!
+//! Captures are returned as base64-encoded PNGs together with the geometry of
+//! the source display, so vision consumers can map image coordinates back to
+//! desktop coordinates (multi-monitor layouts, HiDPI scaling).
+
+use base64::Engine;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+use xcap::{Monitor, Window};
+
+/// Window label of the interactive region selector overlay.
+pub const REGION_SELECTOR_LABEL: &str = "region-selector";
+
+/// Geometry and identity of a display, as reported by the OS.
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    pub id: u32,
+    pub name: String,
+    /// Top-left corner in the virtual desktop coordinate space.
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    pub rotation: f32,
+    pub is_primary: bool,
+}
+
+impl From<&Monitor> for MonitorInfo {
+    fn from(m: &Monitor) -> Self {
+        Self {
+            id: m.id(),
+            name: m.name().to_string(),
+            x: m.x(),
+            y: m.y(),
+            width: m.width(),
+            height: m.height(),
+            scale_factor: m.scale_factor(),
+            rotation: m.rotation(),
+            is_primary: m.is_primary(),
+        }
+    }
+}
+
+/// Result of a capture: the PNG image plus the display it came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenCapture {
+    /// Base64-encoded PNG.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub monitor: MonitorInfo,
+}
+
+/// A top-level application window that can be captured.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowInfo {
+    pub id: u32,
+    pub title: String,
+    pub app_name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub is_minimized: bool,
+}
+
+impl From<&Window> for WindowInfo {
+    fn from(w: &Window) -> Self {
+        Self {
+            id: w.id(),
+            title: w.title().to_string(),
+            app_name: w.app_name().to_string(),
+            x: w.x(),
+            y: w.y(),
+            width: w.width(),
+            height: w.height(),
+            is_minimized: w.is_minimized(),
+        }
+    }
+}
+
+/// Result of a window capture.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowCapture {
+    /// Base64-encoded PNG.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub window: WindowInfo,
+}
+
+/// Result of a region capture. `x`/`y`/`region_width`/`region_height` echo the
+/// requested rectangle in desktop coordinates after clamping to the display.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionCapture {
+    /// Base64-encoded PNG.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub region_width: u32,
+    pub region_height: u32,
+    pub monitor: MonitorInfo,
+}
+
+fn all_monitors() -> Result<Vec<Monitor>, String> {
+    Monitor::all().map_err(|e| format!("Failed to enumerate monitors: {}", e))
+}
+
+/// Resolve a monitor by ID, or the primary monitor (falling back to the first
+/// one) when no ID is given.
+fn find_monitor(monitor_id: Option<u32>) -> Result<Monitor, String> {
+    let monitors = all_monitors()?;
+    match monitor_id {
+        Some(id) => monitors
+            .into_iter()
+            .find(|m| m.id() == id)
+            .ok_or_else(|| format!("Monitor not found: {}", id)),
+        None => {
+            let fallback = monitors.first().cloned();
+            monitors
+                .into_iter()
+                .find(Monitor::is_primary)
+                .or(fallback)
+                .ok_or_else(|| "No monitor found".to_string())
+        }
+    }
+}
+
+pub(crate) fn encode_png(image: &image::RgbaImage) -> Result<String, String> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut buf, image::ImageFormat::Png)
+        .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(buf.into_inner()))
+}
+
+/// List connected displays with their geometry.
+#[tauri::command]
+pub fn list_monitors() -> Result<Vec<MonitorInfo>, String> {
+    Ok(all_monitors()?.iter().map(MonitorInfo::from).collect())
+}
+
+/// Capture a display. Defaults to the primary monitor when `monitor_id` is omitted.
+#[tauri::command]
+pub fn capture_screen(monitor_id: Option<u32>) -> Result<ScreenCapture, String> {
+    let monitor = find_monitor(monitor_id)?;
+    let image = monitor
+        .capture_image()
+        .map_err(|e| format!("Screen capture failed: {}", e))?;
+
+    Ok(ScreenCapture {
+        image: encode_png(&image)?,
+        width: image.width(),
+        height: image.height(),
+        monitor: MonitorInfo::from(&monitor),
+    })
+}
+
+/// Capture a rectangle given in desktop coordinates.
+///
+/// The region is resolved against the display containing its top-left corner
+/// and clamped to that display; regions spanning several monitors are cut at
+/// the monitor edge.
+#[tauri::command]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap
+)]
+pub fn capture_region(x: i32, y: i32, width: u32, height: u32) -> Result<RegionCapture, String> {
+    if width == 0 || height == 0 {
+        return Err("Region width and height must be non-zero".to_string());
+    }
+    let monitor =
+        Monitor::from_point(x, y).map_err(|e| format!("No monitor at ({}, {}): {}", x, y, e))?;
+
+    // Clamp the logical rectangle to the monitor bounds
+    let left = (x - monitor.x()).max(0) as u32;
+    let top = (y - monitor.y()).max(0) as u32;
+    let width = width.min(monitor.width().saturating_sub(left));
+    let height = height.min(monitor.height().saturating_sub(top));
+    if width == 0 || height == 0 {
+        return Err("Region lies outside the monitor".to_string());
+    }
+
+    let full = monitor
+        .capture_image()
+        .map_err(|e| format!("Screen capture failed: {}", e))?;
+
+    // Captured images are in physical pixels; monitor geometry may be logical
+    // (HiDPI). Scale the rectangle by the observed ratio.
+    let sx = f64::from(full.width()) / f64::from(monitor.width().max(1));
+    let sy = f64::from(full.height()) / f64::from(monitor.height().max(1));
+    let px = ((f64::from(left) * sx).round() as u32).min(full.width().saturating_sub(1));
+    let py = ((f64::from(top) * sy).round() as u32).min(full.height().saturating_sub(1));
+    let pw = ((f64::from(width) * sx).round() as u32).clamp(1, full.width() - px);
+    let ph = ((f64::from(height) * sy).round() as u32).clamp(1, full.height() - py);
+
+    let cropped = image::imageops::crop_imm(&full, px, py, pw, ph).to_image();
+
+    Ok(RegionCapture {
+        image: encode_png(&cropped)?,
+        width: cropped.width(),
+        height: cropped.height(),
+        x: monitor.x() + left as i32,
+        y: monitor.y() + top as i32,
+        region_width: width,
+        region_height: height,
+        monitor: MonitorInfo::from(&monitor),
+    })
+}
+
+/// List capturable top-level windows (minimized windows are included but
+/// flagged, since most platforms cannot capture them).
+#[tauri::command]
+pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+    let windows = Window::all().map_err(|e| format!("Failed to enumerate windows: {}", e))?;
+    Ok(windows.iter().map(WindowInfo::from).collect())
+}
+
+/// Capture a single window by ID.
+#[tauri::command]
+pub fn capture_window(window_id: u32) -> Result<WindowCapture, String> {
+    let window = Window::all()
+        .map_err(|e| format!("Failed to enumerate windows: {}", e))?
+        .into_iter()
+        .find(|w| w.id() == window_id)
+        .ok_or_else(|| format!("Window not found: {}", window_id))?;
+    if window.is_minimized() {
+        return Err(format!("Window {} is minimized", window_id));
+    }
+
+    let image = window
+        .capture_image()
+        .map_err(|e| format!("Window capture failed: {}", e))?;
+
+    Ok(WindowCapture {
+        image: encode_png(&image)?,
+        width: image.width(),
+        height: image.height(),
+        window: WindowInfo::from(&window),
+    })
+}
+
+/// Open the interactive region selector overlay on a monitor.
+///
+/// The overlay (`/region-select/:monitorId`) shows a frozen screenshot, lets the
+/// user drag a rectangle, then calls [`capture_region`] and emits the result to
+/// the main window as `cloto://region-captured`.
+/// Async because creating windows from a synchronous command deadlocks on Windows.
+#[tauri::command]
+pub async fn open_region_selector<R: Runtime>(
+    app: AppHandle<R>,
+    monitor_id: Option<u32>,
+) -> Result<(), String> {
+    let monitor = find_monitor(monitor_id)?;
+
+    if let Some(existing) = app.get_webview_window(REGION_SELECTOR_LABEL) {
+        let _ = existing.close();
+    }
+
+    WebviewWindowBuilder::new(
+        &app,
+        REGION_SELECTOR_LABEL,
+        WebviewUrl::App(format!("region-select/{}", monitor.id()).into()),
+    )
+    .title("Select Region")
+    .position(f64::from(monitor.x()), f64::from(monitor.y()))
+    .inner_size(f64::from(monitor.width()), f64::from(monitor.height()))
+    .decorations(false)
+    .resizable(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .focused(true)
+    .build()
+    .map_err(|e| format!("Failed to open region selector: {}", e))?;
+
+    Ok(())
+}
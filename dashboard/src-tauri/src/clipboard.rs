@@ -0,0 +1,51 @@
+//! System clipboard access.
+//!
+//! Exposed twice: as Tauri commands for the dashboard (user-initiated), and as
+//! a [`ClipboardCapability`] registered with the kernel, which only hands it to
+//! plugins holding an approved `ClipboardAccess` permission.
+
+use cloto_shared::ClipboardCapability;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Kernel-facing clipboard backed by the Tauri clipboard plugin.
+pub struct TauriClipboard<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriClipboard<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl<R: Runtime> ClipboardCapability for TauriClipboard<R> {
+    async fn read_text(&self) -> anyhow::Result<String> {
+        self.app
+            .clipboard()
+            .read_text()
+            .map_err(|e| anyhow::anyhow!("Clipboard read failed: {}", e))
+    }
+
+    async fn write_text(&self, text: &str) -> anyhow::Result<()> {
+        self.app
+            .clipboard()
+            .write_text(text.to_string())
+            .map_err(|e| anyhow::anyhow!("Clipboard write failed: {}", e))
+    }
+}
+
+#[tauri::command]
+pub fn clipboard_read_text<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    app.clipboard()
+        .read_text()
+        .map_err(|e| format!("Clipboard read failed: {}", e))
+}
+
+#[tauri::command]
+pub fn clipboard_write_text<R: Runtime>(app: AppHandle<R>, text: String) -> Result<(), String> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Clipboard write failed: {}", e))
+}
@@ -0,0 +1,87 @@
+//! `cloto://` deep link handling.
+//!
+//! Supported links:
+//! - `cloto://agent/<agent_id>` — open the agent's chat
+//! - `cloto://permission/<request_id>` — open a pending permission request for review
+//! - `cloto://mcp/<server_id>` — open an MCP server's settings
+//!
+//! Links only ever navigate the dashboard. Permission links surface the request
+//! for explicit confirmation rather than approving it, since any web page or
+//! message can embed a `cloto://` URL.
+
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::notifications::NAVIGATE_EVENT;
+
+pub const SCHEME: &str = "cloto";
+
+/// Translate a `cloto://` URL into a dashboard route.
+fn route_for(url: &Url) -> Option<String> {
+    if url.scheme() != SCHEME {
+        return None;
+    }
+    // `cloto://agent/abc` parses with host "agent" and path "/abc"
+    let target = url.host_str()?;
+    let id = url.path().trim_matches('/');
+    if !is_valid_id(id) {
+        return None;
+    }
+    match target {
+        "agent" => Some(format!("/?agent={}", id)),
+        "permission" => Some(format!("/?permission={}", id)),
+        "mcp" => Some(format!("/mcp-servers?server={}", id)),
+        _ => None,
+    }
+}
+
+/// Agent, request and server IDs are restricted to a URL-safe charset, so
+/// they can be spliced into routes without encoding.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Show the dashboard and navigate to the view referenced by the URL(s).
+pub fn handle_urls<R: Runtime>(app: &AppHandle<R>, urls: &[Url]) {
+    for url in urls {
+        let Some(route) = route_for(url) else {
+            log::warn!("Ignoring unsupported deep link: {}", url);
+            continue;
+        };
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        let _ = app.emit(NAVIGATE_EVENT, route);
+    }
+}
+
+/// Register the URL scheme handler. Links received while the app is already
+/// running are forwarded by the single-instance plugin to `on_open_url`.
+pub fn setup<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
+    // Linux and Windows dev builds need runtime registration; installers
+    // register the scheme on other platforms.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    app.deep_link().register_all()?;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_urls(&handle, &event.urls());
+    });
+
+    // Cold start via a link: the URL arrives before the webview exists, so
+    // defer navigation until the window is ready.
+    if let Some(urls) = app.deep_link().get_current()? {
+        let handle = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            handle_urls(&handle, &urls);
+        });
+    }
+
+    Ok(())
+}
@@ -1,7 +1,14 @@
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tauri::tray::TrayIconBuilder;
 use tauri::Manager;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+mod capture;
+mod clipboard;
+mod deep_link;
+mod notifications;
+mod settings;
+mod shortcuts;
+mod updates;
 
 /// Returns the kernel HTTP port (used by frontend to construct API URLs).
 #[tauri::command]
@@ -12,31 +19,6 @@ fn get_kernel_port() -> u16 {
         .unwrap_or(8081)
 }
 
-/// Capture the primary screen and return a base64-encoded PNG.
-#[tauri::command]
-fn capture_screen() -> Result<String, String> {
-    use base64::Engine;
-    use xcap::Monitor;
-
-    let monitors = Monitor::all().map_err(|e| format!("Failed to enumerate monitors: {}", e))?;
-    let primary = monitors
-        .into_iter()
-        .find(xcap::Monitor::is_primary)
-        .or_else(|| Monitor::all().ok().and_then(|m| m.into_iter().next()))
-        .ok_or_else(|| "No monitor found".to_string())?;
-
-    let image = primary
-        .capture_image()
-        .map_err(|e| format!("Screen capture failed: {}", e))?;
-
-    let mut buf = std::io::Cursor::new(Vec::new());
-    image
-        .write_to(&mut buf, image::ImageFormat::Png)
-        .map_err(|e| format!("PNG encoding failed: {}", e))?;
-
-    Ok(base64::engine::general_purpose::STANDARD.encode(buf.into_inner()))
-}
-
 /// Select a file within the scripts/ directory. Returns a relative path.
 #[tauri::command]
 fn select_script_file(base_dir: String) -> Result<Option<String>, String> {
@@ -69,16 +51,56 @@ pub fn run() {
     std::env::set_var("CORS_ORIGINS", combined);
 
     let app = tauri::Builder::default()
+        // Must be registered first: a second launch (e.g. a cloto:// link) is
+        // forwarded here and the new process exits.
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .plugin(tauri_plugin_window_state::Builder::new().build())
+        // Visibility is decided at startup (autostart / background mode), not restored
+        .plugin(
+            tauri_plugin_window_state::Builder::new()
+                .with_state_flags(
+                    tauri_plugin_window_state::StateFlags::all()
+                        - tauri_plugin_window_state::StateFlags::VISIBLE,
+                )
+                .build(),
+        )
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![settings::MINIMIZED_ARG]),
+        ))
+        .manage(notifications::PendingNavigation::default())
+        .manage(updates::PendingUpdate::default())
         .invoke_handler(tauri::generate_handler![
             get_kernel_port,
-            capture_screen,
-            select_script_file
+            capture::list_monitors,
+            capture::capture_screen,
+            capture::capture_region,
+            capture::list_windows,
+            capture::capture_window,
+            capture::open_region_selector,
+            select_script_file,
+            notifications::notify_kernel_event,
+            settings::get_desktop_settings,
+            settings::set_autostart,
+            settings::set_background_mode,
+            clipboard::clipboard_read_text,
+            clipboard::clipboard_write_text,
+            updates::set_update_channel,
+            updates::check_for_updates,
+            updates::install_update,
+            shortcuts::set_shortcut,
+            shortcuts::reset_shortcuts
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -89,6 +111,24 @@ pub fn run() {
                 )?;
             }
 
+            // --- Desktop Settings / Startup Visibility ---
+            // The main window is created hidden (tauri.conf.json) and only shown
+            // here unless launched at login or running in background mode.
+            let store = settings::SettingsStore::load(app.handle());
+            let start_hidden = settings::start_hidden(&store.get());
+            app.manage(store);
+            if !start_hidden {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+
+            // --- Deep Links (cloto://) ---
+            if let Err(e) = deep_link::setup(app.handle()) {
+                log::warn!("Deep link registration failed: {}", e);
+            }
+
             // --- System Tray ---
             let status_item =
                 MenuItem::with_id(app, "status", "Cloto: Online", false, None::<&str>)?;
@@ -125,26 +165,16 @@ pub fn run() {
                 })
                 .build(app)?;
 
-            // --- Global Shortcut: CmdOrCtrl+Shift+E to toggle dashboard ---
-            app.global_shortcut()
-                .on_shortcut(
-                    "CmdOrCtrl+Shift+E",
-                    |app_handle: &tauri::AppHandle,
-                     _shortcut: &tauri_plugin_global_shortcut::Shortcut,
-                     event: tauri_plugin_global_shortcut::ShortcutEvent| {
-                        if event.state == ShortcutState::Pressed {
-                            if let Some(window) = app_handle.get_webview_window("main") {
-                                if window.is_visible().unwrap_or(false) {
-                                    let _ = window.hide();
-                                } else {
-                                    let _ = window.show();
-                                    let _ = window.set_focus();
-                                }
-                            }
-                        }
-                    },
-                )
-                .ok();
+            // --- Global Shortcuts (settings-backed, see shortcuts.rs) ---
+            let bindings = app.state::<settings::SettingsStore>().get().shortcuts;
+            if let Err(e) = shortcuts::register_all(app.handle(), &bindings) {
+                log::warn!("Global shortcut registration failed: {}", e);
+            }
+
+            // --- HAL: expose the system clipboard to the kernel (ClipboardAccess) ---
+            cloto_core::capabilities::register_clipboard(std::sync::Arc::new(
+                clipboard::TauriClipboard::new(app.handle().clone()),
+            ));
 
             // --- Launch the Cloto Kernel Server ---
             tauri::async_runtime::spawn(async move {
@@ -157,11 +187,17 @@ pub fn run() {
             Ok(())
         })
         // Intercept window close: minimize to tray instead of quitting
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        .on_window_event(|window, event| match event {
+            // Only the main window hides to tray; auxiliary windows close normally
+            tauri::WindowEvent::CloseRequested { api, .. } if window.label() == "main" => {
                 api.prevent_close();
                 let _ = window.hide();
             }
+            // Notification click-through: open the view of the last notification
+            tauri::WindowEvent::Focused(true) if window.label() == "main" => {
+                notifications::on_focus(window.app_handle());
+            }
+            _ => {}
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
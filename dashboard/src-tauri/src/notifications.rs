@@ -0,0 +1,121 @@
+//! Native OS notifications for kernel events.
+//!
+//! The dashboard forwards selected SSE events to [`notify_kernel_event`]; this
+//! module decides whether the event deserves an OS notification and remembers
+//! which dashboard view it refers to. Desktop notification backends do not
+//! report clicks uniformly, so click-through is implemented as "the next time
+//! the main window gains focus, navigate to the last notified view".
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+/// Event emitted to the webview with the route to open after click-through.
+pub const NAVIGATE_EVENT: &str = "cloto://navigate";
+
+/// Maximum characters of an agent reply shown in the notification body.
+const BODY_PREVIEW_CHARS: usize = 160;
+
+/// Route of the most recent notification, consumed on the next window focus.
+#[derive(Default)]
+pub struct PendingNavigation(Mutex<Option<String>>);
+
+impl PendingNavigation {
+    fn set(&self, route: String) {
+        if let Ok(mut slot) = self.0.lock() {
+            *slot = Some(route);
+        }
+    }
+
+    fn take(&self) -> Option<String> {
+        self.0.lock().ok().and_then(|mut slot| slot.take())
+    }
+}
+
+/// A notification derived from a kernel event.
+struct KernelNotification {
+    title: String,
+    body: String,
+    route: String,
+}
+
+/// Map a serialized `ClotoEvent` (`{type, data, ...}`) to a notification.
+///
+/// `window_active` suppresses agent replies while the user is already looking
+/// at the dashboard. Permission requests always notify because they block the
+/// requesting server until approved.
+fn build_notification(
+    event: &serde_json::Value,
+    window_active: bool,
+) -> Option<KernelNotification> {
+    let data = event.get("data")?;
+    match event.get("type")?.as_str()? {
+        "PermissionRequested" => {
+            let plugin_id = data.get("plugin_id")?.as_str()?;
+            let permission = data
+                .get("permission")
+                .map(|p| p.as_str().map_or_else(|| p.to_string(), str::to_string))
+                .unwrap_or_default();
+            let reason = data.get("reason").and_then(|r| r.as_str()).unwrap_or("");
+            Some(KernelNotification {
+                title: format!("Permission requested: {}", plugin_id),
+                body: if reason.is_empty() {
+                    permission
+                } else {
+                    format!("{} — {}", permission, reason)
+                },
+                // SecurityGuard on the home view lists pending approvals
+                route: "/".to_string(),
+            })
+        }
+        "ThoughtResponse" if !window_active => {
+            let agent_id = data.get("agent_id")?.as_str()?;
+            let content = data.get("content").and_then(|c| c.as_str()).unwrap_or("");
+            let mut body: String = content.chars().take(BODY_PREVIEW_CHARS).collect();
+            if content.chars().count() > BODY_PREVIEW_CHARS {
+                body.push('…');
+            }
+            Some(KernelNotification {
+                title: format!("Reply from {}", agent_id),
+                body,
+                route: format!("/?agent={}", agent_id),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn main_window_active<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.get_webview_window("main")
+        .is_some_and(|w| w.is_visible().unwrap_or(false) && w.is_focused().unwrap_or(false))
+}
+
+/// Show an OS notification for a kernel event forwarded by the dashboard.
+/// Returns `true` if a notification was shown.
+#[tauri::command]
+pub fn notify_kernel_event<R: Runtime>(
+    app: AppHandle<R>,
+    event: serde_json::Value,
+) -> Result<bool, String> {
+    let Some(notification) = build_notification(&event, main_window_active(&app)) else {
+        return Ok(false);
+    };
+
+    app.notification()
+        .builder()
+        .title(&notification.title)
+        .body(&notification.body)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+
+    app.state::<PendingNavigation>().set(notification.route);
+    Ok(true)
+}
+
+/// Called when the main window gains focus: forward the pending route (if any)
+/// so the dashboard opens the view the notification referred to.
+pub fn on_focus<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(route) = app.state::<PendingNavigation>().take() {
+        let _ = app.emit(NAVIGATE_EVENT, route);
+    }
+}
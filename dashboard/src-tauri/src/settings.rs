@@ -0,0 +1,122 @@
+//! Persisted desktop-shell settings (`desktop-settings.json` in the app config dir).
+//!
+//! These are preferences of the Tauri shell itself, not kernel configuration;
+//! the kernel keeps reading its own `.env` / environment variables.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_autostart::ManagerExt;
+
+const SETTINGS_FILE: &str = "desktop-settings.json";
+
+/// Command-line flag passed by the OS login launcher.
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopSettings {
+    /// Launch at login (minimized to tray).
+    pub autostart: bool,
+    /// Keep the kernel running without ever showing the window at startup;
+    /// the dashboard only appears via the global shortcut or tray menu.
+    pub background_mode: bool,
+    /// Release channel queried by the in-app updater.
+    pub update_channel: crate::updates::UpdateChannel,
+    /// Global shortcut bindings (see `shortcuts.rs`).
+    pub shortcuts: crate::shortcuts::ShortcutBindings,
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<DesktopSettings>,
+}
+
+impl SettingsStore {
+    /// Load settings from the app config dir. Missing or malformed files fall
+    /// back to defaults so a corrupt file never prevents startup.
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .unwrap_or_else(|_| cloto_core::config::exe_dir())
+            .join(SETTINGS_FILE);
+        let settings = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(s) => Some(s),
+                Err(e) => {
+                    log::warn!("Ignoring malformed {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> DesktopSettings {
+        self.settings.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Apply `f` to the settings and persist the result.
+    pub fn update(&self, f: impl FnOnce(&mut DesktopSettings)) -> Result<DesktopSettings, String> {
+        let mut settings = self
+            .settings
+            .lock()
+            .map_err(|_| "Settings lock poisoned".to_string())?;
+        let mut next = settings.clone();
+        f(&mut next);
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create settings dir: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(&next)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        std::fs::write(&self.path, json).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+        *settings = next.clone();
+        Ok(next)
+    }
+}
+
+/// Whether the main window should stay hidden at startup.
+pub fn start_hidden(settings: &DesktopSettings) -> bool {
+    settings.background_mode || std::env::args().any(|a| a == MINIMIZED_ARG)
+}
+
+#[tauri::command]
+pub fn get_desktop_settings(store: State<'_, SettingsStore>) -> DesktopSettings {
+    store.get()
+}
+
+/// Enable or disable launching at login. The OS registration and the persisted
+/// flag are kept in sync; the OS call happens first so a failure leaves the
+/// stored setting unchanged.
+#[tauri::command]
+pub fn set_autostart<R: Runtime>(
+    app: AppHandle<R>,
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<DesktopSettings, String> {
+    let launcher = app.autolaunch();
+    let result = if enabled {
+        launcher.enable()
+    } else {
+        launcher.disable()
+    };
+    result.map_err(|e| format!("Failed to update login item: {}", e))?;
+    store.update(|s| s.autostart = enabled)
+}
+
+#[tauri::command]
+pub fn set_background_mode(
+    store: State<'_, SettingsStore>,
+    enabled: bool,
+) -> Result<DesktopSettings, String> {
+    store.update(|s| s.background_mode = enabled)
+}
@@ -0,0 +1,197 @@
+//! Settings-backed global shortcut registry.
+//!
+//! Each [`ShortcutAction`] is bound to an accelerator string (e.g.
+//! `CmdOrCtrl+Shift+E`) persisted in [`DesktopSettings`]. Bindings are validated
+//! for duplicates before anything is registered, and a failed re-registration
+//! rolls back to the previous bindings.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::settings::{DesktopSettings, SettingsStore};
+
+/// Window label of the quick-chat popup.
+pub const QUICK_CHAT_LABEL: &str = "quick-chat";
+/// Event carrying a quick capture (`ScreenCapture`) to the main window.
+pub const QUICK_CAPTURE_EVENT: &str = "cloto://quick-capture";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ToggleWindow,
+    QuickCapture,
+    QuickChat,
+}
+
+/// Accelerators per action. An empty string leaves the action unbound.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutBindings {
+    pub toggle_window: String,
+    pub quick_capture: String,
+    pub quick_chat: String,
+}
+
+impl Default for ShortcutBindings {
+    fn default() -> Self {
+        Self {
+            toggle_window: "CmdOrCtrl+Shift+E".to_string(),
+            quick_capture: "CmdOrCtrl+Shift+S".to_string(),
+            quick_chat: "CmdOrCtrl+Shift+Space".to_string(),
+        }
+    }
+}
+
+impl ShortcutBindings {
+    fn entries(&self) -> [(ShortcutAction, &str); 3] {
+        [
+            (ShortcutAction::ToggleWindow, &self.toggle_window),
+            (ShortcutAction::QuickCapture, &self.quick_capture),
+            (ShortcutAction::QuickChat, &self.quick_chat),
+        ]
+    }
+
+    fn set(&mut self, action: ShortcutAction, accelerator: String) {
+        match action {
+            ShortcutAction::ToggleWindow => self.toggle_window = accelerator,
+            ShortcutAction::QuickCapture => self.quick_capture = accelerator,
+            ShortcutAction::QuickChat => self.quick_chat = accelerator,
+        }
+    }
+
+    /// Parse all bound accelerators, rejecting invalid ones and duplicates.
+    /// Comparison is on the parsed shortcut, so `Ctrl+Shift+E` and
+    /// `Shift+Ctrl+E` are detected as the same binding.
+    fn parse(&self) -> Result<Vec<(ShortcutAction, Shortcut)>, String> {
+        let mut parsed: Vec<(ShortcutAction, Shortcut)> = Vec::new();
+        for (action, accelerator) in self.entries() {
+            if accelerator.trim().is_empty() {
+                continue;
+            }
+            let shortcut: Shortcut = accelerator
+                .parse()
+                .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+            if let Some((other, _)) = parsed.iter().find(|(_, s)| *s == shortcut) {
+                return Err(format!(
+                    "Shortcut '{}' is already bound to {:?}",
+                    accelerator, other
+                ));
+            }
+            parsed.push((action, shortcut));
+        }
+        Ok(parsed)
+    }
+}
+
+fn run_action<R: Runtime>(app: &AppHandle<R>, action: ShortcutAction) {
+    match action {
+        ShortcutAction::ToggleWindow => {
+            if let Some(window) = app.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        }
+        ShortcutAction::QuickCapture => {
+            // Capturing and encoding takes a while; keep it off the shortcut thread
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                match crate::capture::capture_screen(None) {
+                    Ok(capture) => {
+                        // The main window's chat composer picks the capture up
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                        }
+                        let _ = app.emit_to("main", QUICK_CAPTURE_EVENT, capture);
+                    }
+                    Err(e) => log::warn!("Quick capture failed: {}", e),
+                }
+            });
+        }
+        ShortcutAction::QuickChat => {
+            if let Some(window) = app.get_webview_window(QUICK_CHAT_LABEL) {
+                let _ = window.close();
+                return;
+            }
+            let result = WebviewWindowBuilder::new(
+                app,
+                QUICK_CHAT_LABEL,
+                WebviewUrl::App("quick-chat".into()),
+            )
+            .title("Quick Chat")
+            .inner_size(520.0, 140.0)
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .center()
+            .focused(true)
+            .build();
+            if let Err(e) = result {
+                log::warn!("Failed to open quick chat: {}", e);
+            }
+        }
+    }
+}
+
+/// Replace all global shortcuts with `bindings`.
+pub fn register_all<R: Runtime>(
+    app: &AppHandle<R>,
+    bindings: &ShortcutBindings,
+) -> Result<(), String> {
+    let parsed = bindings.parse()?;
+    let manager = app.global_shortcut();
+    manager
+        .unregister_all()
+        .map_err(|e| format!("Failed to clear shortcuts: {}", e))?;
+
+    for (action, shortcut) in parsed {
+        manager
+            .on_shortcut(shortcut, move |app_handle, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    run_action(app_handle, action);
+                }
+            })
+            // Typically another application owns the accelerator
+            .map_err(|e| format!("Cannot register {:?} ({}): {}", action, shortcut, e))?;
+    }
+    Ok(())
+}
+
+/// Rebind one action at runtime. On failure the previous bindings are
+/// re-registered and the stored settings are left untouched.
+#[tauri::command]
+pub fn set_shortcut<R: Runtime>(
+    app: AppHandle<R>,
+    store: State<'_, SettingsStore>,
+    action: ShortcutAction,
+    accelerator: String,
+) -> Result<DesktopSettings, String> {
+    let previous = store.get().shortcuts;
+    let mut next = previous.clone();
+    next.set(action, accelerator.trim().to_string());
+
+    if let Err(e) = register_all(&app, &next) {
+        if let Err(rollback) = register_all(&app, &previous) {
+            log::error!("Failed to restore previous shortcuts: {}", rollback);
+        }
+        return Err(e);
+    }
+    store.update(|s| s.shortcuts = next)
+}
+
+/// Restore the default bindings.
+#[tauri::command]
+pub fn reset_shortcuts<R: Runtime>(
+    app: AppHandle<R>,
+    store: State<'_, SettingsStore>,
+) -> Result<DesktopSettings, String> {
+    let defaults = ShortcutBindings::default();
+    register_all(&app, &defaults)?;
+    store.update(|s| s.shortcuts = defaults)
+}
@@ -0,0 +1,142 @@
+//! In-app update flow with release channels.
+//!
+//! `check_for_updates` queries the endpoint of the configured channel and keeps
+//! the found update in memory; `install_update` performs the kernel maintenance
+//! handshake, downloads with progress events and restarts the app.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime, State, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::settings::SettingsStore;
+
+/// Progress events emitted during `install_update`.
+pub const PROGRESS_EVENT: &str = "cloto://update-progress";
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/Cloto-dev/ClotoCore/releases/latest/download/latest.json";
+/// Pre-releases publish their manifest under a moving `beta` tag.
+const BETA_ENDPOINT: &str =
+    "https://github.com/Cloto-dev/ClotoCore/releases/download/beta/latest.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    fn endpoint(self) -> &'static str {
+        match self {
+            Self::Stable => STABLE_ENDPOINT,
+            Self::Beta => BETA_ENDPOINT,
+        }
+    }
+}
+
+/// Update found by the last check, awaiting `install_update`.
+#[derive(Default)]
+pub struct PendingUpdate(Mutex<Option<Update>>);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub channel: UpdateChannel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+enum UpdateProgress {
+    Maintenance,
+    Downloading { downloaded: u64, total: Option<u64> },
+    Installing,
+}
+
+#[tauri::command]
+pub fn set_update_channel(
+    store: State<'_, SettingsStore>,
+    channel: UpdateChannel,
+) -> Result<crate::settings::DesktopSettings, String> {
+    store.update(|s| s.update_channel = channel)
+}
+
+/// Check the configured channel for a newer release.
+#[tauri::command]
+pub async fn check_for_updates<R: Runtime>(
+    app: AppHandle<R>,
+    store: State<'_, SettingsStore>,
+    pending: State<'_, PendingUpdate>,
+) -> Result<Option<UpdateInfo>, String> {
+    let channel = store.get().update_channel;
+    let endpoint = Url::parse(channel.endpoint()).map_err(|e| e.to_string())?;
+    let update = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(tauri_plugin_updater::UpdaterBuilder::build)
+        .map_err(|e| format!("Failed to configure updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))?;
+
+    let info = update.as_ref().map(|u| UpdateInfo {
+        version: u.version.clone(),
+        current_version: u.current_version.clone(),
+        notes: u.body.clone(),
+        channel,
+    });
+    if let Ok(mut slot) = pending.0.lock() {
+        *slot = update;
+    }
+    Ok(info)
+}
+
+/// Apply the update found by the last `check_for_updates` and restart.
+///
+/// The kernel is put into maintenance mode first so connected clients are
+/// notified and in-flight work stops before files are replaced.
+#[tauri::command]
+pub async fn install_update<R: Runtime>(
+    app: AppHandle<R>,
+    pending: State<'_, PendingUpdate>,
+) -> Result<(), String> {
+    let update = pending
+        .0
+        .lock()
+        .map_err(|_| "Update state poisoned".to_string())?
+        .take()
+        .ok_or_else(|| "No pending update; run check_for_updates first".to_string())?;
+
+    let _ = app.emit(PROGRESS_EVENT, UpdateProgress::Maintenance);
+    if let Some(kernel) = cloto_core::kernel_handle() {
+        kernel
+            .enter_maintenance(&format!("Applying update v{}...", update.version))
+            .await
+            .map_err(|e| format!("Kernel maintenance handshake failed: {}", e))?;
+    }
+
+    let mut downloaded: u64 = 0;
+    let progress_app = app.clone();
+    let installing_app = app.clone();
+    update
+        .download_and_install(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = progress_app.emit(
+                    PROGRESS_EVENT,
+                    UpdateProgress::Downloading { downloaded, total },
+                );
+            },
+            move || {
+                let _ = installing_app.emit(PROGRESS_EVENT, UpdateProgress::Installing);
+            },
+        )
+        .await
+        .map_err(|e| format!("Update failed: {}", e))?;
+
+    app.restart();
+}
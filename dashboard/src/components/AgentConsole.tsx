@@ -1,6 +1,6 @@
 import { useState, useEffect, useRef, useCallback } from 'react';
-import { Activity, Send, Zap, User as UserIcon, RotateCcw, ArrowLeft } from 'lucide-react';
-import { AgentMetadata, ClotoMessage, ChatMessage } from '../types';
+import { Activity, Send, Zap, User as UserIcon, RotateCcw, ArrowLeft, Clock, X } from 'lucide-react';
+import { AgentMetadata, ClotoMessage, ChatMessage, ContentBlock } from '../types';
 import { useEventStream } from '../hooks/useEventStream';
 import { AgentIcon, agentColor } from '../lib/agentIdentity';
 import { useLongPress } from '../hooks/useLongPress';
@@ -11,6 +11,7 @@ import { SkeletonThinking } from './SkeletonThinking';
 import { TypewriterMessage } from './TypewriterMessage';
 import { ArtifactPanel } from './ArtifactPanel';
 import { useArtifacts } from '../hooks/useArtifacts';
+import { onQuickCapture, onRegionCaptured } from '../lib/tauri';
 
 // Legacy localStorage key prefix for migration
 const LEGACY_SESSION_KEY_PREFIX = 'cloto-chat-';
@@ -69,11 +70,15 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
   const { apiKey } = useApiKey();
   const [messages, setMessages] = useState<ChatMessage[]>([]);
   const [input, setInput] = useState('');
+  // Screenshot waiting to be sent with the next message (data URL)
+  const [capture, setCapture] = useState<string | null>(null);
   const [isTyping, setIsTyping] = useState(false);
   const [isLoading, setIsLoading] = useState(true);
   const [hasMore, setHasMore] = useState(false);
   const [isLoadingMore, setIsLoadingMore] = useState(false);
   const [pendingResponse, setPendingResponse] = useState<{ id: string; text: string; elapsedSecs: number } | null>(null);
+  // Reply text streamed so far (ThoughtResponseChunk), keyed by the user message it answers
+  const [streamingResponse, setStreamingResponse] = useState<{ sourceId: string; text: string } | null>(null);
   const [thinkingSteps, setThinkingSteps] = useState<Array<{ id: number; icon: string; text: string; ts: number }>>([]);
   const thinkingIdRef = useRef(0);
   const scrollRef = useRef<HTMLDivElement>(null);
@@ -81,6 +86,10 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
   const initialLoadDone = useRef(false);
   const isScrolledToBottom = useRef(true);
   const sendTimestampRef = useRef<number>(0);
+  // IDs of messages sent from this console; replies to anything else (e.g. cron
+  // runs) are persisted by the kernel.
+  const sentIdsRef = useRef(new Set<string>());
+  const streamingSourceRef = useRef<string | null>(null);
   const artifactPanel = useArtifacts();
 
   // Load initial messages from server
@@ -111,12 +120,19 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
     loadMessages();
   }, [agent.id, apiKey]);
 
+  // Desktop screenshots (QuickCapture shortcut, region selector) attach to the composer
+  useEffect(() => {
+    const attach = (shot: { image: string }) => setCapture(`data:image/png;base64,${shot.image}`);
+    const unlisteners = [onQuickCapture(attach), onRegionCaptured(attach)];
+    return () => unlisteners.forEach(p => p.then(unlisten => unlisten()));
+  }, []);
+
   // Scroll to bottom on initial load and new messages (only if user is at bottom)
   useEffect(() => {
     if (!isLoading && isScrolledToBottom.current && scrollRef.current) {
       scrollRef.current.scrollTop = scrollRef.current.scrollHeight;
     }
-  }, [messages.length, isLoading, pendingResponse]);
+  }, [messages.length, isLoading, pendingResponse, streamingResponse]);
 
   const handleScroll = useCallback(() => {
     const el = scrollRef.current;
@@ -176,6 +192,24 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
 
   // Subscribe to system-wide events
   useEventStream(EVENTS_URL, (event) => {
+    if (event.type === 'AgentMessage'
+        && (event.data?.from_agent === agent.id || event.data?.to_agent === agent.id)) {
+      const outgoing = event.data.from_agent === agent.id;
+      setThinkingSteps(prev => [...prev, {
+        id: thinkingIdRef.current++,
+        icon: '📨',
+        text: outgoing ? `→ ${event.data.to_agent}` : `← ${event.data.from_agent}`,
+        ts: Date.now(),
+      }]);
+    }
+    if (event.type === 'ToolApprovalRequested' && isTyping) {
+      setThinkingSteps(prev => [...prev, {
+        id: thinkingIdRef.current++,
+        icon: '✋',
+        text: `${event.data.tool_name} awaiting approval`,
+        ts: Date.now(),
+      }]);
+    }
     // Thinking process visualization
     if (event.data?.agent_id === agent.id || event.data?.engine_id?.startsWith('mind.')) {
       if (event.type === 'ToolInvoked' && event.data.agent_id === agent.id) {
@@ -205,6 +239,14 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
           ts: Date.now(),
         }]);
       }
+      if (event.type === 'AgenticLoopAborted' && event.data.agent_id === agent.id) {
+        setThinkingSteps(prev => [...prev, {
+          id: thinkingIdRef.current++,
+          icon: '🔁',
+          text: `Stopped: ${event.data.reason === 'oscillating_tool_calls' ? 'cycling tool calls' : 'repeated tool calls'}`,
+          ts: Date.now(),
+        }]);
+      }
       if (event.type === 'Thought' && event.payload?.content) {
         setThinkingSteps(prev => [...prev, {
           id: thinkingIdRef.current++,
@@ -215,6 +257,16 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
       }
     }
 
+    if (event.type === 'ThoughtResponseChunk' && event.data.agent_id === agent.id
+        && sentIdsRef.current.has(event.data.source_message_id)) {
+      const sourceId: string = event.data.source_message_id;
+      const delta: string = event.data.delta || '';
+      streamingSourceRef.current = sourceId;
+      setStreamingResponse(prev => prev?.sourceId === sourceId
+        ? { sourceId, text: prev.text + delta }
+        : { sourceId, text: delta });
+    }
+
     if (event.type === 'ThoughtResponse' && event.data.agent_id === agent.id) {
       setIsTyping(false);
       setThinkingSteps([]);
@@ -223,22 +275,37 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
         ? Math.round((Date.now() - sendTimestampRef.current) / 100) / 10
         : 0;
 
-      // If a previous typewriter is still running, finalize it immediately
-      setPendingResponse(prev => {
-        if (prev) {
-          const prevMsg: ChatMessage = {
-            id: prev.id, agent_id: agent.id, user_id: 'default',
-            source: 'agent',
-            content: [{ type: 'text', text: prev.text }],
-            metadata: { elapsed_secs: prev.elapsedSecs },
-            created_at: Date.now(),
-          };
-          setMessages(msgs => [...msgs, prevMsg]);
-        }
-        return { id: msgId, text: event.data.content, elapsedSecs };
-      });
+      // A streamed reply is already on screen: store it without the typewriter
+      const wasStreamed = streamingSourceRef.current === event.data.source_message_id;
+      streamingSourceRef.current = null;
+      setStreamingResponse(null);
+      if (wasStreamed) {
+        setMessages(msgs => [...msgs, {
+          id: msgId, agent_id: agent.id, user_id: 'default',
+          source: 'agent',
+          content: [{ type: 'text', text: event.data.content }],
+          metadata: { elapsed_secs: elapsedSecs },
+          created_at: Date.now(),
+        }]);
+      } else {
+        // If a previous typewriter is still running, finalize it immediately
+        setPendingResponse(prev => {
+          if (prev) {
+            const prevMsg: ChatMessage = {
+              id: prev.id, agent_id: agent.id, user_id: 'default',
+              source: 'agent',
+              content: [{ type: 'text', text: prev.text }],
+              metadata: { elapsed_secs: prev.elapsedSecs },
+              created_at: Date.now(),
+            };
+            setMessages(msgs => [...msgs, prevMsg]);
+          }
+          return { id: msgId, text: event.data.content, elapsedSecs };
+        });
+      }
 
       // Persist agent response to server (fire-and-forget)
+      if (!sentIdsRef.current.delete(event.data.source_message_id)) return;
       api.postChatMessage(agent.id, {
         id: msgId,
         source: 'agent',
@@ -270,24 +337,29 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
   }, [artifactPanel.addArtifact]);
 
   const sendMessage = async () => {
-    if (!input.trim() || isTyping || pendingResponse) return;
+    if ((!input.trim() && !capture) || isTyping || pendingResponse) return;
     artifactPanel.clearArtifacts();
 
     const msgId = Date.now().toString();
+    const content: ContentBlock[] = [];
+    if (input.trim()) content.push({ type: 'text', text: input });
+    if (capture) content.push({ type: 'image', url: capture, mime_type: 'image/png', filename: 'screenshot.png' });
     const userMsg: ChatMessage = {
       id: msgId,
       agent_id: agent.id,
       user_id: 'default',
       source: 'user',
-      content: [{ type: 'text', text: input }],
+      content,
       created_at: Date.now(),
     };
 
     setMessages(prev => [...prev, userMsg]);
     setInput('');
+    setCapture(null);
     setIsTyping(true);
     setThinkingSteps([]);
     sendTimestampRef.current = Date.now();
+    sentIdsRef.current.add(msgId);
 
     try {
       // Persist user message first — cancel send if this fails
@@ -312,6 +384,7 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
       // Rollback: remove the user message from UI and show error
       setMessages(prev => prev.filter(m => m.id !== msgId));
       setInput(input); // Restore input so user can retry
+      setCapture(capture);
       setIsTyping(false);
       const errMsg = err instanceof Error ? err.message : 'Failed to send message';
       console.error("Failed to send message:", errMsg);
@@ -408,6 +481,12 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
                     ? 'p-4 rounded-2xl rounded-tr-none shadow-sm bg-surface-primary text-content-primary'
                     : 'pt-1 text-content-primary'
                 }`}>
+                  {msg.metadata?.cron_job_id != null && (
+                    <div className="mb-1 flex items-center gap-1 text-[10px] font-mono text-content-tertiary uppercase tracking-wider">
+                      <Clock size={10} />
+                      {msg.source === 'system' ? `Cron · ${msg.metadata.cron_job_name ?? msg.metadata.cron_job_id}` : 'Cron result'}
+                    </div>
+                  )}
                   <MessageContent content={msg.content} />
                   {!isUser && msg.metadata?.elapsed_secs != null && (
                     <div className="mt-2 text-xs font-mono text-content-tertiary">
@@ -440,8 +519,20 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
             </div>
           </div>
         )}
+        {/* Reply streamed so far */}
+        {streamingResponse && (
+          <div className="flex items-start gap-3 message-enter">
+            <div className="w-8 h-8 rounded-lg text-white flex items-center justify-center shrink-0 shadow-sm"
+                 style={{ backgroundColor: agentColor(agent) }}>
+              <AgentIcon agent={agent} size={14} />
+            </div>
+            <div className="max-w-[80%] pt-1 text-base leading-7 select-text text-content-primary">
+              <MessageContent content={[{ type: 'text', text: streamingResponse.text }]} />
+            </div>
+          </div>
+        )}
         {/* Thinking process steps (real-time tool invocations) */}
-        {isTyping && thinkingSteps.length > 0 && (
+        {isTyping && !streamingResponse && thinkingSteps.length > 0 && (
           <div className="flex items-start gap-3">
             <div className="w-8 h-8 rounded-lg text-white flex items-center justify-center shrink-0 shadow-sm opacity-60"
                  style={{ backgroundColor: agentColor(agent) }}>
@@ -458,7 +549,7 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
           </div>
         )}
         {/* Skeleton (waiting for SSE response) */}
-        {isTyping && (
+        {isTyping && !streamingResponse && (
           <SkeletonThinking
             agentColor={agentColor(agent)}
             agentIcon={<AgentIcon agent={agent} size={14} />}
@@ -468,6 +559,18 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
 
       {/* Input Area */}
       <div className="p-4 bg-glass-strong border-t border-edge-subtle">
+        {capture && (
+          <div className="relative inline-block mb-2">
+            <img src={capture} alt="screenshot" className="h-16 rounded-lg border border-edge" />
+            <button
+              onClick={() => setCapture(null)}
+              className="absolute -top-2 -right-2 p-0.5 bg-surface-primary border border-edge rounded-full text-content-tertiary hover:text-content-primary"
+              aria-label="Remove screenshot"
+            >
+              <X size={10} />
+            </button>
+          </div>
+        )}
         <div className="relative flex items-center">
           <input
             type="text"
@@ -480,7 +583,7 @@ export function AgentConsole({ agent, onBack }: { agent: AgentMetadata, onBack:
           />
           <button
             onClick={sendMessage}
-            disabled={isTyping || !!pendingResponse || !input.trim()}
+            disabled={isTyping || !!pendingResponse || (!input.trim() && !capture)}
             className="absolute right-2 p-2 bg-brand text-white rounded-lg hover:scale-105 active:scale-95 transition-all disabled:opacity-30 disabled:grayscale disabled:scale-100 shadow-lg shadow-brand/20"
           >
             <Send size={16} />
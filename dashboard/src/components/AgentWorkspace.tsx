@@ -6,13 +6,13 @@ import { WindowAgentNavigator } from './WindowAgentNavigator';
 import { KernelMonitor } from './KernelMonitor';
 import { useAgents } from '../hooks/useAgents';
 
-export function AgentWorkspace({ onBack }: { onBack?: () => void }) {
+export function AgentWorkspace({ onBack, initialAgentId }: { onBack?: () => void; initialAgentId?: string | null }) {
   const { agents, refetch: refetchAgents } = useAgents();
 
   const fetchInitialData = () => {
     refetchAgents();
   };
-  const [selectedAgentId, setSelectedAgentId] = useState<string | null>(null);
+  const [selectedAgentId, setSelectedAgentId] = useState<string | null>(initialAgentId ?? null);
   const [systemActive, setSystemActive] = useState(false);
 
   const handleSelectAgent = (id: string) => {
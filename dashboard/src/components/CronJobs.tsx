@@ -1,12 +1,23 @@
 import { useState, useEffect, useCallback } from 'react';
 import { memo } from 'react';
-import { Clock, Plus, Trash2, Play, Power } from 'lucide-react';
+import { Clock, Plus, Trash2, Play, Power, History } from 'lucide-react';
 import { ViewHeader } from './ViewHeader';
-import { CronJob, AgentMetadata } from '../types';
+import { CronJob, CronJobRun, AgentMetadata } from '../types';
 import { api } from '../services/api';
 import { useApiKey } from '../contexts/ApiKeyContext';
 
-function formatSchedule(type: string, value: string): string {
+const CHAIN_ON_LABEL: Record<string, string> = {
+  success: 'succeeds',
+  failure: 'fails',
+  always: 'finishes',
+};
+
+function formatSchedule(job: CronJob, jobs: CronJob[]): string {
+  const { schedule_type: type, schedule_value: value } = job;
+  if (type === 'after') {
+    const upstream = jobs.find(j => j.id === value);
+    return `When ${upstream?.name ?? value} ${CHAIN_ON_LABEL[job.chain_on] ?? 'succeeds'}`;
+  }
   if (type === 'interval') {
     const secs = parseInt(value, 10);
     if (secs >= 3600) return `Every ${Math.floor(secs / 3600)}h${secs % 3600 ? ` ${Math.floor((secs % 3600) / 60)}m` : ''}`;
@@ -14,7 +25,7 @@ function formatSchedule(type: string, value: string): string {
     return `Every ${secs}s`;
   }
   if (type === 'once') return `Once at ${new Date(value).toLocaleString()}`;
-  return value; // cron expression
+  return job.timezone ? `${value} (${job.timezone})` : value; // cron expression
 }
 
 function formatTimestamp(ms?: number | null): string {
@@ -22,11 +33,71 @@ function formatTimestamp(ms?: number | null): string {
   return new Date(ms).toLocaleString();
 }
 
+function formatDuration(ms?: number | null): string {
+  if (ms == null) return '—';
+  if (ms < 1000) return `${ms}ms`;
+  if (ms < 60_000) return `${(ms / 1000).toFixed(1)}s`;
+  return `${Math.floor(ms / 60_000)}m ${Math.round((ms % 60_000) / 1000)}s`;
+}
+
+const RUN_STATUS_STYLE: Record<string, string> = {
+  success: 'bg-green-500/20 text-green-400',
+  running: 'bg-brand/20 text-brand',
+  timeout: 'bg-yellow-500/20 text-yellow-400',
+  error: 'bg-red-500/20 text-red-400',
+};
+
+function RunHistory({ jobId, apiKey }: { jobId: string; apiKey: string }) {
+  const [runs, setRuns] = useState<CronJobRun[] | null>(null);
+
+  useEffect(() => {
+    api.listCronJobRuns(jobId, apiKey)
+      .then(data => setRuns(data.runs))
+      .catch(e => { console.error('Failed to fetch cron job runs', e); setRuns([]); });
+  }, [jobId, apiKey]);
+
+  if (runs === null) {
+    return <div className="mt-3 text-[10px] font-mono text-content-muted">Loading history...</div>;
+  }
+  if (runs.length === 0) {
+    return <div className="mt-3 text-[10px] font-mono text-content-muted">No runs recorded yet.</div>;
+  }
+  return (
+    <table className="mt-3 w-full text-[10px] font-mono text-content-tertiary">
+      <thead>
+        <tr className="text-left text-content-muted uppercase">
+          <th className="font-normal py-1">Started</th>
+          <th className="font-normal py-1">Trigger</th>
+          <th className="font-normal py-1">Duration</th>
+          <th className="font-normal py-1">Status</th>
+          <th className="font-normal py-1">Result</th>
+        </tr>
+      </thead>
+      <tbody>
+        {runs.map(run => (
+          <tr key={run.id} className="border-t border-edge-subtle">
+            <td className="py-1 text-content-secondary">{formatTimestamp(run.started_at)}</td>
+            <td className="py-1">{run.trigger}</td>
+            <td className="py-1">{formatDuration(run.duration_ms)}</td>
+            <td className="py-1">
+              <span className={`px-1 py-0.5 rounded text-[9px] ${RUN_STATUS_STYLE[run.status] ?? ''}`}>{run.status}</span>
+            </td>
+            <td className="py-1 truncate max-w-[240px]" title={run.error ?? run.response_message_id}>
+              {run.error ?? run.response_message_id ?? '—'}
+            </td>
+          </tr>
+        ))}
+      </tbody>
+    </table>
+  );
+}
+
 export const CronJobs = memo(function CronJobs() {
   const { apiKey } = useApiKey();
   const [jobs, setJobs] = useState<CronJob[]>([]);
   const [agents, setAgents] = useState<AgentMetadata[]>([]);
   const [showForm, setShowForm] = useState(false);
+  const [historyJobId, setHistoryJobId] = useState<string | null>(null);
   const [form, setForm] = useState({
     agent_id: '',
     name: '',
@@ -34,6 +105,13 @@ export const CronJobs = memo(function CronJobs() {
     schedule_value: '3600',
     message: '',
     engine_id: '',
+    misfire_policy: 'run_once' as string,
+    jitter_secs: '0',
+    chain_on: 'success' as string,
+    weekdays_only: false,
+    skip_dates: '',
+    skip_ics: '',
+    timezone: '',
   });
 
   const fetchJobs = useCallback(async () => {
@@ -63,9 +141,16 @@ export const CronJobs = memo(function CronJobs() {
         schedule_value: form.schedule_value,
         message: form.message,
         engine_id: form.engine_id || undefined,
+        misfire_policy: form.misfire_policy,
+        jitter_secs: parseInt(form.jitter_secs, 10) || 0,
+        chain_on: form.schedule_type === 'after' ? form.chain_on : undefined,
+        weekdays_only: form.weekdays_only,
+        skip_dates: form.skip_dates.split(/[\s,]+/).filter(Boolean),
+        skip_ics: form.skip_ics || undefined,
+        timezone: form.timezone.trim() || undefined,
       }, apiKey);
       setShowForm(false);
-      setForm({ agent_id: '', name: '', schedule_type: 'interval', schedule_value: '3600', message: '', engine_id: '' });
+      setForm({ agent_id: '', name: '', schedule_type: 'interval', schedule_value: '3600', message: '', engine_id: '', misfire_policy: 'run_once', jitter_secs: '0', chain_on: 'success', weekdays_only: false, skip_dates: '', skip_ics: '', timezone: '' });
       fetchJobs();
     } catch (e: any) { alert(e.message); }
   };
@@ -147,18 +232,45 @@ export const CronJobs = memo(function CronJobs() {
                 <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">Schedule Type</label>
                 <select
                   value={form.schedule_type}
-                  onChange={e => setForm({ ...form, schedule_type: e.target.value })}
+                  onChange={e => setForm({ ...form, schedule_type: e.target.value, schedule_value: e.target.value === 'after' ? '' : form.schedule_value })}
                   className="w-full bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
                 >
                   <option value="interval">Interval (seconds)</option>
                   <option value="cron">Cron Expression</option>
                   <option value="once">One-shot (ISO 8601)</option>
+                  <option value="after">After another job</option>
                 </select>
               </div>
+              {form.schedule_type === 'after' ? (
+                <div>
+                  <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">Upstream Job</label>
+                  <div className="flex gap-2">
+                    <select
+                      value={form.schedule_value}
+                      onChange={e => setForm({ ...form, schedule_value: e.target.value })}
+                      className="flex-1 min-w-0 bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
+                    >
+                      <option value="">Select job...</option>
+                      {jobs.map(j => (
+                        <option key={j.id} value={j.id}>{j.name}</option>
+                      ))}
+                    </select>
+                    <select
+                      value={form.chain_on}
+                      onChange={e => setForm({ ...form, chain_on: e.target.value })}
+                      className="bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
+                    >
+                      <option value="success">on success</option>
+                      <option value="failure">on failure</option>
+                      <option value="always">always</option>
+                    </select>
+                  </div>
+                </div>
+              ) : (
               <div>
                 <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">
                   {form.schedule_type === 'interval' ? 'Interval (seconds, min 60)' :
-                   form.schedule_type === 'cron' ? 'Cron Expression (e.g. 0 9 * * *)' :
+                   form.schedule_type === 'cron' ? 'Cron Expression (5 or 6 fields, e.g. 0 9 * * 1-5)' :
                    'Run At (ISO 8601)'}
                 </label>
                 <input
@@ -168,6 +280,70 @@ export const CronJobs = memo(function CronJobs() {
                   className="w-full bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
                 />
               </div>
+              )}
+              <div>
+                <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">Timezone (IANA, default UTC)</label>
+                <input
+                  value={form.timezone}
+                  onChange={e => setForm({ ...form, timezone: e.target.value })}
+                  placeholder="Asia/Tokyo"
+                  className="w-full bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
+                />
+              </div>
+              <div>
+                <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">If Missed (kernel offline)</label>
+                <select
+                  value={form.misfire_policy}
+                  onChange={e => setForm({ ...form, misfire_policy: e.target.value })}
+                  className="w-full bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
+                >
+                  <option value="run_once">Run once</option>
+                  <option value="skip">Skip</option>
+                  <option value="run_all">Run all missed (max 10)</option>
+                </select>
+              </div>
+              <div>
+                <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">Jitter (seconds, max 3600)</label>
+                <input
+                  type="number"
+                  min={0}
+                  max={3600}
+                  value={form.jitter_secs}
+                  onChange={e => setForm({ ...form, jitter_secs: e.target.value })}
+                  className="w-full bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
+                />
+              </div>
+              <div>
+                <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">Skip Dates (YYYY-MM-DD, job timezone)</label>
+                <input
+                  value={form.skip_dates}
+                  onChange={e => setForm({ ...form, skip_dates: e.target.value })}
+                  placeholder="2026-12-25, 2027-01-01"
+                  className="w-full bg-surface-secondary border border-edge rounded px-3 py-2 text-xs font-mono text-content-primary"
+                />
+              </div>
+              <div className="flex flex-col justify-end gap-2">
+                <label className="flex items-center gap-2 text-[10px] font-mono text-content-tertiary uppercase">
+                  <input
+                    type="checkbox"
+                    checked={form.weekdays_only}
+                    onChange={e => setForm({ ...form, weekdays_only: e.target.checked })}
+                  />
+                  Weekdays only (job timezone)
+                </label>
+                <label className="flex items-center gap-2 px-3 py-2 w-fit bg-surface-secondary border border-edge rounded text-[10px] font-mono text-content-secondary uppercase cursor-pointer hover:bg-surface-secondary/80">
+                  {form.skip_ics ? 'Holiday calendar loaded' : 'Import holidays (.ics)'}
+                  <input
+                    type="file"
+                    accept=".ics,text/calendar"
+                    className="hidden"
+                    onChange={async e => {
+                      const file = e.target.files?.[0];
+                      if (file) setForm({ ...form, skip_ics: await file.text() });
+                    }}
+                  />
+                </label>
+              </div>
               <div className="md:col-span-2">
                 <label className="block text-[10px] font-mono text-content-tertiary uppercase mb-1">Message (prompt sent to agent)</label>
                 <textarea
@@ -182,7 +358,7 @@ export const CronJobs = memo(function CronJobs() {
             <div className="flex gap-3 pt-2">
               <button
                 onClick={handleCreate}
-                disabled={!form.agent_id || !form.name || !form.message}
+                disabled={!form.agent_id || !form.name || !form.message || !form.schedule_value}
                 className="px-4 py-2 bg-brand text-white rounded text-xs font-mono uppercase tracking-wider hover:bg-brand/80 disabled:opacity-40 disabled:cursor-not-allowed transition-colors"
               >
                 Create Job
@@ -215,12 +391,17 @@ export const CronJobs = memo(function CronJobs() {
                   </div>
                   <div className="text-[10px] font-mono text-content-tertiary space-y-0.5">
                     <div>Agent: <span className="text-content-secondary">{job.agent_id}</span></div>
-                    <div>Schedule: <span className="text-content-secondary">{formatSchedule(job.schedule_type, job.schedule_value)}</span></div>
+                    <div>Schedule: <span className="text-content-secondary">{formatSchedule(job, jobs)}</span></div>
+                    {(job.weekdays_only || job.skip_dates) && (
+                      <div>Calendar: <span className="text-content-secondary">
+                        {[job.weekdays_only && 'weekdays only', job.skip_dates && `${job.skip_dates.split(',').length} skip date(s)`].filter(Boolean).join(', ')}
+                      </span></div>
+                    )}
                     <div>Next: <span className="text-content-secondary">{job.next_run_at < Number.MAX_SAFE_INTEGER ? formatTimestamp(job.next_run_at) : '—'}</span></div>
                     {job.last_run_at && (
                       <div>Last: <span className="text-content-secondary">{formatTimestamp(job.last_run_at)}</span>
                         {job.last_status && (
-                          <span className={`ml-2 px-1 py-0.5 rounded text-[9px] ${job.last_status === 'success' ? 'bg-green-500/20 text-green-400' : 'bg-red-500/20 text-red-400'}`}>
+                          <span className={`ml-2 px-1 py-0.5 rounded text-[9px] ${job.last_status === 'success' ? 'bg-green-500/20 text-green-400' : job.last_status === 'skipped' ? 'bg-yellow-500/20 text-yellow-400' : 'bg-red-500/20 text-red-400'}`}>
                             {job.last_status}
                           </span>
                         )}
@@ -232,6 +413,9 @@ export const CronJobs = memo(function CronJobs() {
                   </div>
                 </div>
                 <div className="flex items-center gap-2 shrink-0">
+                  <button onClick={() => setHistoryJobId(historyJobId === job.id ? null : job.id)} title="Run history" className={`p-1.5 rounded hover:bg-brand/10 transition-colors ${historyJobId === job.id ? 'text-brand' : 'text-content-tertiary hover:text-brand'}`}>
+                    <History size={14} />
+                  </button>
                   <button onClick={() => handleRunNow(job.id)} title="Run now" className="p-1.5 rounded hover:bg-brand/10 text-content-tertiary hover:text-brand transition-colors">
                     <Play size={14} />
                   </button>
@@ -243,6 +427,7 @@ export const CronJobs = memo(function CronJobs() {
                   </button>
                 </div>
               </div>
+              {historyJobId === job.id && apiKey && <RunHistory key={job.last_run_at ?? 0} jobId={job.id} apiKey={apiKey} />}
             </div>
           )) : (
             <div className="py-12 text-center text-content-tertiary bg-glass rounded-lg border border-edge border-dashed font-mono text-xs">
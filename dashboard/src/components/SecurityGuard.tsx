@@ -1,5 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import { Shield, Lock, Unlock, AlertTriangle, X, Check, ShieldAlert } from 'lucide-react';
+import { useSearchParams } from 'react-router-dom';
 import { PermissionRequest } from '../types';
 import { api } from '../services/api';
 import { useApiKey } from '../contexts/ApiKeyContext';
@@ -11,6 +12,9 @@ export function SecurityGuard() {
   const [authorizingIds, setAuthorizingIds] = useState<string[]>([]);
   const [grantedIds, setGrantedIds] = useState<string[]>([]);
   const [error, setError] = useState<string | null>(null);
+  // Deep link (cloto://permission/<id>): surface the linked request first for review
+  const [searchParams] = useSearchParams();
+  const linkedId = searchParams.get('permission');
 
   // M-23: Poll for pending permission requests with AbortController
   useEffect(() => {
@@ -83,7 +87,7 @@ export function SecurityGuard() {
         </div>
       )}
 
-      {requests.map((req, idx) => {
+      {[...requests].sort((a, b) => Number(b.request_id === linkedId) - Number(a.request_id === linkedId)).map((req, idx) => {
         const reqId = req.request_id;
         const isAuthorizing = authorizingIds.includes(reqId);
         const isGranted = grantedIds.includes(reqId);
@@ -93,7 +97,7 @@ export function SecurityGuard() {
             key={req.request_id}
             className={`bg-surface-primary/90 backdrop-blur-2xl border rounded-[2rem] shadow-2xl overflow-hidden shadow-brand/20 flex flex-col transition-all duration-500 ${
               isGranted ? 'border-emerald-500 scale-95 opacity-50' : 'border-edge'
-            }`}
+            } ${reqId === linkedId && !isGranted ? 'ring-2 ring-brand' : ''}`}
           >
             {/* Header */}
             <div className={`p-4 flex items-center justify-between text-white transition-colors duration-500 ${
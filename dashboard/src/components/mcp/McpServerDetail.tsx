@@ -69,6 +69,11 @@ export function McpServerDetail({ server, apiKey, onRefresh, onDelete, onStart,
           </span>
           <span>Tools: {server.tools.length} registered</span>
           {server.is_cloto_sdk && <span className="text-brand">CLOTO SDK</span>}
+          {server.instances && server.instances.length > 1 && (
+            <span title={server.instances.map(i => `#${i.index + 1}: ${i.alive ? 'alive' : 'dead'}, ${i.in_flight} in flight, ${i.errors_total}/${i.requests_total} errors`).join('\n')}>
+              Instances: {server.instances.filter(i => i.alive).length}/{server.instances.length} alive
+            </span>
+          )}
           <span className={server.source === 'config' ? 'text-amber-500' : 'text-blue-400'}>
             {server.source === 'config' ? 'CONFIG' : 'DYNAMIC'}
           </span>
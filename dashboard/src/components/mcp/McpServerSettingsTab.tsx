@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
-import { McpServerInfo, McpServerSettings, DefaultPolicy } from '../../types';
+import { McpServerInfo, McpServerSettings, McpServerModels, DefaultPolicy } from '../../types';
 import { api } from '../../services/api';
 import { Save, RotateCcw, Plus, X, Eye, EyeOff } from 'lucide-react';
 
@@ -22,6 +22,11 @@ export function McpServerSettingsTab({ server, apiKey, onRefresh }: Props) {
   const [newValue, setNewValue] = useState('');
   const [visibleKeys, setVisibleKeys] = useState<Set<string>>(new Set());
 
+  // Models offered by mind servers with a list_models tool (e.g. local Ollama models)
+  const listsModels = server.tools.includes('list_models');
+  const [models, setModels] = useState<McpServerModels | null>(null);
+  const [modelsError, setModelsError] = useState<string | null>(null);
+
   const loadSettings = useCallback(async () => {
     try {
       setError(null);
@@ -45,6 +50,14 @@ export function McpServerSettingsTab({ server, apiKey, onRefresh }: Props) {
     loadSettings();
   }, [loadSettings]);
 
+  useEffect(() => {
+    if (!listsModels) return;
+    setModelsError(null);
+    api.getMcpServerModels(server.id, apiKey)
+      .then(setModels)
+      .catch(err => setModelsError(err instanceof Error ? err.message : 'Failed to list models'));
+  }, [server.id, apiKey, listsModels]);
+
   async function handleSave() {
     setSaving(true);
     setError(null);
@@ -214,6 +227,33 @@ export function McpServerSettingsTab({ server, apiKey, onRefresh }: Props) {
         </div>
       </section>
 
+      {/* Models */}
+      {listsModels && (
+        <section>
+          <h3 className="text-[10px] font-mono uppercase tracking-widest text-content-tertiary mb-2">Available Models</h3>
+          {modelsError ? (
+            <p className="text-[9px] font-mono text-red-500">{modelsError}</p>
+          ) : !models ? (
+            <p className="text-[9px] font-mono text-content-muted">Loading...</p>
+          ) : models.models.length === 0 ? (
+            <p className="text-[9px] font-mono text-content-muted">No models installed.</p>
+          ) : (
+            <div className="space-y-1">
+              {models.models.map(m => (
+                <div key={m.name} className="flex gap-2 text-[10px] font-mono">
+                  <span className={m.name === models.active_model ? 'text-brand' : 'text-content-secondary'}>
+                    {m.name}{m.name === models.active_model ? ' (active)' : ''}
+                  </span>
+                  <span className="text-content-muted">
+                    {[m.parameter_size, m.quantization, m.size].filter(Boolean).join(' · ')}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+        </section>
+      )}
+
       {/* Default Policy */}
       <section>
         <h3 className="text-[10px] font-mono uppercase tracking-widest text-content-tertiary mb-2">Default Policy</h3>
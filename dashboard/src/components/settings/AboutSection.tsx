@@ -1,4 +1,108 @@
+import { useEffect, useState } from 'react';
 import { SectionCard } from './common';
+import {
+  isTauri, UpdateChannel, UpdateInfo, UpdateProgress,
+  getDesktopSettings, setUpdateChannel, checkForUpdates, installUpdate,
+} from '../../lib/tauri';
+
+/** Tauri-only: release channel selection and in-app update flow. */
+function UpdatesCard() {
+  const [channel, setChannel] = useState<UpdateChannel>('stable');
+  const [update, setUpdate] = useState<UpdateInfo | null>(null);
+  const [status, setStatus] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    getDesktopSettings().then((s) => s && setChannel(s.update_channel)).catch(() => {});
+  }, []);
+
+  const changeChannel = async (next: UpdateChannel) => {
+    setUpdate(null);
+    setStatus(null);
+    try {
+      const s = await setUpdateChannel(next);
+      if (s) setChannel(s.update_channel);
+    } catch (e) {
+      setStatus(String(e));
+    }
+  };
+
+  const check = async () => {
+    setBusy(true);
+    setStatus('Checking...');
+    try {
+      const found = await checkForUpdates();
+      setUpdate(found);
+      setStatus(found ? null : 'Up to date');
+    } catch (e) {
+      setStatus(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const describe = (p: UpdateProgress) => {
+    if (p.stage === 'maintenance') return 'Entering maintenance mode...';
+    if (p.stage === 'installing') return 'Installing...';
+    const pct = p.total ? ` ${Math.floor((p.downloaded / p.total) * 100)}%` : '';
+    return `Downloading...${pct}`;
+  };
+
+  const install = async () => {
+    setBusy(true);
+    try {
+      await installUpdate((p) => setStatus(describe(p)));
+    } catch (e) {
+      setStatus(String(e));
+      setBusy(false);
+    }
+  };
+
+  return (
+    <SectionCard title="Updates">
+      <div className="space-y-3">
+        <div className="flex gap-2">
+          {(['stable', 'beta'] as UpdateChannel[]).map((c) => (
+            <button
+              key={c}
+              disabled={busy}
+              onClick={() => changeChannel(c)}
+              className={`px-4 py-1.5 rounded-lg text-[10px] font-bold uppercase tracking-widest transition-all ${
+                channel === c
+                  ? 'bg-brand text-white'
+                  : 'bg-surface-secondary text-content-secondary border border-edge hover:border-brand'
+              }`}
+            >
+              {c}
+            </button>
+          ))}
+        </div>
+        {update ? (
+          <div className="space-y-2">
+            <p className="text-xs text-content-primary font-mono">v{update.current_version} → v{update.version}</p>
+            {update.notes && <p className="text-[10px] text-content-secondary whitespace-pre-wrap">{update.notes}</p>}
+            <button
+              disabled={busy}
+              onClick={install}
+              className="px-4 py-1.5 rounded-lg text-[10px] font-bold uppercase tracking-widest bg-brand text-white disabled:opacity-50"
+            >
+              Install &amp; Restart
+            </button>
+          </div>
+        ) : (
+          <button
+            disabled={busy}
+            onClick={check}
+            className="px-4 py-1.5 rounded-lg text-[10px] font-bold uppercase tracking-widest bg-surface-secondary text-content-secondary border border-edge hover:border-brand disabled:opacity-50"
+          >
+            Check for updates
+          </button>
+        )}
+        {status && <p className="text-[10px] text-content-tertiary font-mono">{status}</p>}
+      </div>
+    </SectionCard>
+  );
+}
 
 export function AboutSection() {
   return (
@@ -12,6 +116,8 @@ export function AboutSection() {
         </div>
       </SectionCard>
 
+      {isTauri && <UpdatesCard />}
+
       <SectionCard title="License">
         <div className="space-y-2">
           <p className="text-xs text-content-secondary">Business Source License 1.1</p>
@@ -1,6 +1,116 @@
+import { useEffect, useState } from 'react';
 import { Sun, Moon, Monitor } from 'lucide-react';
-import { SectionCard } from './common';
+import { SectionCard, Toggle } from './common';
 import { useTheme } from '../../hooks/useTheme';
+import {
+  isTauri, DesktopSettings, ShortcutAction, getDesktopSettings, setAutostart, setBackgroundMode,
+  setShortcut, resetShortcuts,
+} from '../../lib/tauri';
+
+/** Tauri-only: launch at login and background mode. */
+function StartupCard() {
+  const [settings, setSettings] = useState<DesktopSettings | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getDesktopSettings().then(setSettings).catch((e) => setError(String(e)));
+  }, []);
+
+  if (!settings) return null;
+
+  const apply = (update: Promise<DesktopSettings | null>) => {
+    setError(null);
+    update.then((s) => s && setSettings(s)).catch((e) => setError(String(e)));
+  };
+
+  return (
+    <SectionCard title="Startup">
+      <div className="space-y-3">
+        <Toggle
+          label="Launch at login (minimized to tray)"
+          enabled={settings.autostart}
+          onToggle={() => apply(setAutostart(!settings.autostart))}
+        />
+        <Toggle
+          label="Background mode (show window only via shortcut or tray)"
+          enabled={settings.background_mode}
+          onToggle={() => apply(setBackgroundMode(!settings.background_mode))}
+        />
+        {error && <p className="text-[10px] text-red-500 font-mono">{error}</p>}
+      </div>
+    </SectionCard>
+  );
+}
+
+const SHORTCUT_LABELS: Record<ShortcutAction, string> = {
+  toggle_window: 'Toggle dashboard',
+  quick_capture: 'Quick screen capture',
+  quick_chat: 'Quick chat popup',
+};
+
+/** Tauri-only: global shortcut bindings (e.g. CmdOrCtrl+Shift+E). */
+function ShortcutsCard() {
+  const [settings, setSettings] = useState<DesktopSettings | null>(null);
+  const [drafts, setDrafts] = useState<Partial<Record<ShortcutAction, string>>>({});
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getDesktopSettings().then(setSettings).catch((e) => setError(String(e)));
+  }, []);
+
+  if (!settings) return null;
+
+  const save = async (action: ShortcutAction) => {
+    const value = drafts[action];
+    if (value === undefined || value === settings.shortcuts[action]) return;
+    setError(null);
+    try {
+      const s = await setShortcut(action, value);
+      if (s) setSettings(s);
+    } catch (e) {
+      setError(String(e));
+    }
+    setDrafts((d) => ({ ...d, [action]: undefined }));
+  };
+
+  const reset = async () => {
+    setError(null);
+    try {
+      const s = await resetShortcuts();
+      if (s) setSettings(s);
+      setDrafts({});
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <SectionCard title="Global Shortcuts">
+      <div className="space-y-3">
+        {(Object.keys(SHORTCUT_LABELS) as ShortcutAction[]).map((action) => (
+          <div key={action} className="flex items-center justify-between gap-3">
+            <span className="text-xs text-content-secondary">{SHORTCUT_LABELS[action]}</span>
+            <input
+              value={drafts[action] ?? settings.shortcuts[action]}
+              placeholder="Unbound"
+              onChange={(e) => setDrafts((d) => ({ ...d, [action]: e.target.value }))}
+              onBlur={() => save(action)}
+              onKeyDown={(e) => { if (e.key === 'Enter') save(action); }}
+              className="w-56 px-3 py-1.5 rounded-lg bg-surface-secondary border border-edge text-xs font-mono text-content-primary focus:border-brand outline-none"
+            />
+          </div>
+        ))}
+        <button
+          onClick={reset}
+          className="text-[10px] font-bold uppercase tracking-widest text-content-tertiary hover:text-brand transition-colors"
+        >
+          Reset to defaults
+        </button>
+        {error && <p className="text-[10px] text-red-500 font-mono">{error}</p>}
+      </div>
+    </SectionCard>
+  );
+}
 
 export function GeneralSection() {
   const { preference, setPreference } = useTheme();
@@ -31,6 +141,9 @@ export function GeneralSection() {
         </div>
       </SectionCard>
 
+      {isTauri && <StartupCard />}
+      {isTauri && <ShortcutsCard />}
+
       <SectionCard title="Version">
         <div className="flex items-center gap-3">
           <span className="text-2xl font-mono font-black text-brand">v{__APP_VERSION__}</span>
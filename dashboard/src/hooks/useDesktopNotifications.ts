@@ -0,0 +1,40 @@
+import { useCallback, useEffect, useRef } from 'react';
+import { useNavigate } from 'react-router-dom';
+import { useEventStream } from './useEventStream';
+import { EVENTS_URL } from '../services/api';
+import { isTauri, notifyKernelEvent, onNotificationNavigate } from '../lib/tauri';
+
+const NOTIFIABLE_EVENTS = new Set(['PermissionRequested', 'ThoughtResponse']);
+
+/**
+ * Bridges kernel events to native OS notifications (Tauri only) and handles
+ * click-through navigation back into the dashboard.
+ */
+export function useDesktopNotifications() {
+  const navigate = useNavigate();
+  // Auxiliary windows (region selector, quick chat) share this bundle; only
+  // the main window forwards events, to avoid duplicate notifications.
+  const isMainWindow = useRef(false);
+
+  useEffect(() => {
+    if (!isTauri) return;
+    import('@tauri-apps/api/window').then(({ getCurrentWindow }) => {
+      isMainWindow.current = getCurrentWindow().label === 'main';
+    });
+  }, []);
+
+  const handleEvent = useCallback((event: any) => {
+    if (!isTauri || !isMainWindow.current || !NOTIFIABLE_EVENTS.has(event?.type)) return;
+    notifyKernelEvent(event).catch(() => {
+      // Notification plugin unavailable - silently skip
+    });
+  }, []);
+
+  useEventStream(EVENTS_URL, handleEvent);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onNotificationNavigate((route) => navigate(route)).then((fn) => { unlisten = fn; });
+    return () => unlisten?.();
+  }, [navigate]);
+}
@@ -46,3 +46,154 @@ export async function closeWindow() {
   const { getCurrentWindow } = await import('@tauri-apps/api/window');
   await getCurrentWindow().close();
 }
+
+// ── Native Notifications ──
+
+/**
+ * Forward a kernel event to the Tauri shell, which decides whether to show an
+ * OS notification (permission requests, replies while the window is hidden).
+ */
+export async function notifyKernelEvent(event: unknown): Promise<void> {
+  if (!isTauri) return;
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('notify_kernel_event', { event });
+}
+
+/**
+ * Subscribe to notification click-through navigation requests.
+ * Returns an unsubscribe function.
+ */
+export async function onNotificationNavigate(handler: (route: string) => void): Promise<() => void> {
+  if (!isTauri) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<string>('cloto://navigate', (e) => handler(e.payload));
+}
+
+// ── Screen Capture ──
+
+/** Open the interactive region selector on a monitor (primary by default). */
+export async function openRegionSelector(monitorId?: number): Promise<void> {
+  if (!isTauri) return;
+  const { invoke } = await import('@tauri-apps/api/core');
+  await invoke('open_region_selector', { monitorId: monitorId ?? null });
+}
+
+/** A screenshot; `image` is a base64-encoded PNG. */
+export interface ScreenCapture {
+  image: string;
+  width: number;
+  height: number;
+}
+
+/**
+ * Subscribe to region captures completed in the selector overlay.
+ * Returns an unsubscribe function.
+ */
+export async function onRegionCaptured(handler: (capture: ScreenCapture) => void): Promise<() => void> {
+  if (!isTauri) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<ScreenCapture>('cloto://region-captured', (e) => handler(e.payload));
+}
+
+/**
+ * Subscribe to full-screen captures taken with the QuickCapture shortcut.
+ * Returns an unsubscribe function.
+ */
+export async function onQuickCapture(handler: (capture: ScreenCapture) => void): Promise<() => void> {
+  if (!isTauri) return () => {};
+  const { listen } = await import('@tauri-apps/api/event');
+  return listen<ScreenCapture>('cloto://quick-capture', (e) => handler(e.payload));
+}
+
+// ── Desktop Settings ──
+
+export type UpdateChannel = 'stable' | 'beta';
+
+export type ShortcutAction = 'toggle_window' | 'quick_capture' | 'quick_chat';
+
+export type ShortcutBindings = Record<ShortcutAction, string>;
+
+export interface DesktopSettings {
+  autostart: boolean;
+  background_mode: boolean;
+  update_channel: UpdateChannel;
+  shortcuts: ShortcutBindings;
+}
+
+export async function getDesktopSettings(): Promise<DesktopSettings | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<DesktopSettings>('get_desktop_settings');
+}
+
+/** Launch at login, minimized to tray. */
+export async function setAutostart(enabled: boolean): Promise<DesktopSettings | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<DesktopSettings>('set_autostart', { enabled });
+}
+
+/** Keep the window hidden at startup; show it via the global shortcut or tray. */
+export async function setBackgroundMode(enabled: boolean): Promise<DesktopSettings | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<DesktopSettings>('set_background_mode', { enabled });
+}
+
+// ── Updates ──
+
+export interface UpdateInfo {
+  version: string;
+  current_version: string;
+  notes: string | null;
+  channel: UpdateChannel;
+}
+
+export type UpdateProgress =
+  | { stage: 'maintenance' }
+  | { stage: 'downloading'; downloaded: number; total: number | null }
+  | { stage: 'installing' };
+
+export async function setUpdateChannel(channel: UpdateChannel): Promise<DesktopSettings | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<DesktopSettings>('set_update_channel', { channel });
+}
+
+/** Returns the available update, or null if up to date. */
+export async function checkForUpdates(): Promise<UpdateInfo | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<UpdateInfo | null>('check_for_updates');
+}
+
+/**
+ * Install the update found by checkForUpdates(). The kernel enters maintenance
+ * mode and the app restarts on success; progress is reported via onProgress.
+ */
+export async function installUpdate(onProgress: (p: UpdateProgress) => void): Promise<void> {
+  if (!isTauri) return;
+  const { invoke } = await import('@tauri-apps/api/core');
+  const { listen } = await import('@tauri-apps/api/event');
+  const unlisten = await listen<UpdateProgress>('cloto://update-progress', (e) => onProgress(e.payload));
+  try {
+    await invoke('install_update');
+  } finally {
+    unlisten();
+  }
+}
+
+// ── Global Shortcuts ──
+
+/** Rebind a global shortcut. Rejects on invalid/duplicate/OS-conflicting accelerators. */
+export async function setShortcut(action: ShortcutAction, accelerator: string): Promise<DesktopSettings | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<DesktopSettings>('set_shortcut', { action, accelerator });
+}
+
+export async function resetShortcuts(): Promise<DesktopSettings | null> {
+  if (!isTauri) return null;
+  const { invoke } = await import('@tauri-apps/api/core');
+  return invoke<DesktopSettings>('reset_shortcuts');
+}
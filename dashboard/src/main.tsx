@@ -7,12 +7,21 @@ import { ThemeProvider } from './components/ThemeProvider'
 import { ApiKeyProvider } from './contexts/ApiKeyContext'
 import { ConnectionProvider } from './contexts/ConnectionContext'
 import { CustomCursor } from './components/CustomCursor'
+import { useDesktopNotifications } from './hooks/useDesktopNotifications'
 import './compiled-tailwind.css'
 
 const StatusCore = lazy(() => import('./components/StatusCore').then(m => ({ default: m.StatusCore })));
 const MemoryCore = lazy(() => import('./components/MemoryCore').then(m => ({ default: m.MemoryCore })));
 const McpServersPage = lazy(() => import('./pages/McpServersPage').then(m => ({ default: m.McpServersPage })));
 const CronJobs = lazy(() => import('./components/CronJobs').then(m => ({ default: m.CronJobs })));
+const QuickChatPage = lazy(() => import('./pages/QuickChatPage').then(m => ({ default: m.QuickChatPage })));
+const RegionSelectPage = lazy(() => import('./pages/RegionSelectPage').then(m => ({ default: m.RegionSelectPage })));
+
+/** Mounted inside the Router so notification click-through can navigate. */
+function DesktopNotifications() {
+  useDesktopNotifications();
+  return null;
+}
 
 function App() {
   const [cursorEnabled, setCursorEnabled] = useState(() => localStorage.getItem('cloto-cursor') !== 'off');
@@ -32,8 +41,11 @@ function App() {
           <Route path="/dashboard" element={<MemoryCore />} />
           <Route path="/mcp-servers" element={<McpServersPage />} />
           <Route path="/cron" element={<CronJobs />} />
+          <Route path="/region-select/:monitorId" element={<RegionSelectPage />} />
+          <Route path="/quick-chat" element={<QuickChatPage />} />
         </Routes>
       </Suspense>
+      <DesktopNotifications />
       {cursorEnabled && <CustomCursor />}
     </Router>
   );
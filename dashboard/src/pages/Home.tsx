@@ -1,6 +1,6 @@
-import { useRef, useState, useMemo } from 'react';
+import { useRef, useState, useMemo, useEffect } from 'react';
 import { Suspense, lazy } from 'react';
-import { useNavigate } from 'react-router-dom';
+import { useNavigate, useSearchParams } from 'react-router-dom';
 import { Activity, Database, MessageSquare, Puzzle, Clock, Settings, Cpu, Brain, Zap, Shield, Eye, Power, Play, Pause, RefreshCw, LucideIcon } from 'lucide-react';
 import { InteractiveGrid } from '../components/InteractiveGrid';
 import { ViewHeader } from '../components/ViewHeader';
@@ -17,7 +17,15 @@ export function Home() {
   const containerRef = useRef<HTMLDivElement>(null);
   const navigate = useNavigate();
 
-  const [activeMainView, setActiveMainView] = useState<string | null>(null);
+  const [searchParams] = useSearchParams();
+  const linkedAgentId = searchParams.get('agent');
+
+  const [activeMainView, setActiveMainView] = useState<string | null>(linkedAgentId ? 'sandbox' : null);
+
+  // Notification click-through (/?agent=<id>) opens the agent workspace
+  useEffect(() => {
+    if (linkedAgentId) setActiveMainView('sandbox');
+  }, [linkedAgentId]);
 
   const handleItemClick = async (item: any) => {
     if (item.path.startsWith('api:')) {
@@ -81,7 +89,7 @@ export function Home() {
           <div className="absolute inset-0 flex flex-col">
             <div className="flex-1 overflow-hidden animate-in fade-in duration-300">
               <Suspense fallback={<div className="flex items-center justify-center h-full text-xs font-mono text-content-tertiary">SYNCHRONIZING...</div>}>
-                {activeMainView === 'sandbox' && <ClotoWorkspace onBack={() => setActiveMainView(null)} />}
+                {activeMainView === 'sandbox' && <ClotoWorkspace initialAgentId={linkedAgentId} onBack={() => setActiveMainView(null)} />}
                 {activeMainView === 'settings' && <SettingsView onBack={() => setActiveMainView(null)} />}
               </Suspense>
             </div>
@@ -1,4 +1,5 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect } from 'react';
+import { useSearchParams } from 'react-router-dom';
 import { Server } from 'lucide-react';
 import { ViewHeader } from '../components/ViewHeader';
 import { useMcpServers } from '../hooks/useMcpServers';
@@ -12,7 +13,13 @@ export function McpServersPage() {
   // Allow empty apiKey — debug backend skips auth when CLOTO_API_KEY is unset
   const effectiveKey = apiKey || '';
   const { servers, isLoading, error: fetchError, refetch } = useMcpServers(effectiveKey);
-  const [selectedId, setSelectedId] = useState<string | null>(null);
+  // Deep link (cloto://mcp/<id>) preselects a server
+  const [searchParams] = useSearchParams();
+  const linkedServerId = searchParams.get('server');
+  const [selectedId, setSelectedId] = useState<string | null>(linkedServerId);
+  useEffect(() => {
+    if (linkedServerId) setSelectedId(linkedServerId);
+  }, [linkedServerId]);
   const [addModalOpen, setAddModalOpen] = useState(false);
 
   // Add server form state
@@ -0,0 +1,61 @@
+import { useEffect, useRef, useState } from 'react';
+import { api } from '../services/api';
+import { useApiKey } from '../contexts/ApiKeyContext';
+
+/**
+ * Compact always-on-top popup opened by the quick-chat global shortcut.
+ * Sends a message to the default agent; the reply arrives as a native
+ * notification while the dashboard is hidden.
+ */
+export function QuickChatPage() {
+  const { apiKey } = useApiKey();
+  const [text, setText] = useState('');
+  const [status, setStatus] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  const close = async () => {
+    const { getCurrentWindow } = await import('@tauri-apps/api/window');
+    await getCurrentWindow().close();
+  };
+
+  useEffect(() => {
+    inputRef.current?.focus();
+    const onKey = (e: KeyboardEvent) => { if (e.key === 'Escape') close(); };
+    window.addEventListener('keydown', onKey);
+    return () => window.removeEventListener('keydown', onKey);
+  }, []);
+
+  const send = async () => {
+    const content = text.trim();
+    if (!content) return;
+    setStatus('Sending...');
+    try {
+      await api.postChat({
+        id: crypto.randomUUID(),
+        source: { type: 'User', id: 'user', name: 'User' },
+        content,
+        timestamp: new Date().toISOString(),
+        metadata: {},
+      }, apiKey || '');
+      close();
+    } catch (e) {
+      setStatus(`Failed: ${e}`);
+    }
+  };
+
+  return (
+    <div className="h-screen w-screen bg-surface-primary border border-edge rounded-xl p-4 flex flex-col justify-center gap-2">
+      <input
+        ref={inputRef}
+        value={text}
+        onChange={(e) => setText(e.target.value)}
+        onKeyDown={(e) => { if (e.key === 'Enter') send(); }}
+        placeholder="Ask your agent..."
+        className="w-full px-4 py-3 rounded-lg bg-surface-secondary border border-edge text-sm text-content-primary focus:border-brand outline-none"
+      />
+      <p className="text-[10px] font-mono text-content-tertiary">
+        {status ?? 'ENTER TO SEND — ESC TO CLOSE'}
+      </p>
+    </div>
+  );
+}
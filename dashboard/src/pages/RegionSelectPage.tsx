@@ -0,0 +1,118 @@
+import { useEffect, useRef, useState } from 'react';
+import { useParams } from 'react-router-dom';
+import { isTauri } from '../lib/tauri';
+
+interface MonitorInfo {
+  id: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+interface Rect { x: number; y: number; w: number; h: number; }
+
+function normalize(a: { x: number; y: number }, b: { x: number; y: number }): Rect {
+  return {
+    x: Math.min(a.x, b.x),
+    y: Math.min(a.y, b.y),
+    w: Math.abs(a.x - b.x),
+    h: Math.abs(a.y - b.y),
+  };
+}
+
+/**
+ * Fullscreen overlay opened by the `open_region_selector` Tauri command.
+ * Shows a frozen screenshot of the monitor; the user drags a rectangle, which
+ * is captured via `capture_region` and sent to the main window.
+ */
+export function RegionSelectPage() {
+  const { monitorId } = useParams();
+  const [background, setBackground] = useState<string | null>(null);
+  const [monitor, setMonitor] = useState<MonitorInfo | null>(null);
+  const [selection, setSelection] = useState<Rect | null>(null);
+  const dragStart = useRef<{ x: number; y: number } | null>(null);
+
+  const close = async () => {
+    const { getCurrentWindow } = await import('@tauri-apps/api/window');
+    await getCurrentWindow().close();
+  };
+
+  useEffect(() => {
+    if (!isTauri) return;
+    (async () => {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const capture = await invoke<{ image: string; monitor: MonitorInfo }>('capture_screen', {
+        monitorId: monitorId ? Number(monitorId) : null,
+      });
+      setBackground(`data:image/png;base64,${capture.image}`);
+      setMonitor(capture.monitor);
+    })().catch((err) => {
+      console.error('Region selector failed to capture screen:', err);
+      close();
+    });
+
+    const onKey = (e: KeyboardEvent) => { if (e.key === 'Escape') close(); };
+    window.addEventListener('keydown', onKey);
+    return () => window.removeEventListener('keydown', onKey);
+  }, [monitorId]);
+
+  const finish = async (rect: Rect) => {
+    if (!monitor || rect.w < 4 || rect.h < 4) {
+      setSelection(null);
+      return;
+    }
+    // Map viewport pixels to monitor (desktop) coordinates
+    const sx = monitor.width / window.innerWidth;
+    const sy = monitor.height / window.innerHeight;
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      const { emitTo } = await import('@tauri-apps/api/event');
+      const { getCurrentWindow } = await import('@tauri-apps/api/window');
+      // Hide the overlay first so it does not appear in the capture
+      await getCurrentWindow().hide();
+      const capture = await invoke('capture_region', {
+        x: Math.round(monitor.x + rect.x * sx),
+        y: Math.round(monitor.y + rect.y * sy),
+        width: Math.round(rect.w * sx),
+        height: Math.round(rect.h * sy),
+      });
+      await emitTo('main', 'cloto://region-captured', capture);
+    } catch (err) {
+      console.error('Region capture failed:', err);
+    } finally {
+      close();
+    }
+  };
+
+  return (
+    <div
+      className="fixed inset-0 cursor-crosshair select-none"
+      style={{ backgroundImage: background ? `url(${background})` : undefined, backgroundSize: '100% 100%' }}
+      onMouseDown={(e) => {
+        dragStart.current = { x: e.clientX, y: e.clientY };
+        setSelection({ x: e.clientX, y: e.clientY, w: 0, h: 0 });
+      }}
+      onMouseMove={(e) => {
+        if (dragStart.current) setSelection(normalize(dragStart.current, { x: e.clientX, y: e.clientY }));
+      }}
+      onMouseUp={(e) => {
+        if (!dragStart.current) return;
+        const rect = normalize(dragStart.current, { x: e.clientX, y: e.clientY });
+        dragStart.current = null;
+        finish(rect);
+      }}
+    >
+      <div className="absolute inset-0 bg-black/30 pointer-events-none" />
+      {selection && (
+        <div
+          className="absolute border-2 border-brand bg-white/10 pointer-events-none"
+          style={{ left: selection.x, top: selection.y, width: selection.w, height: selection.h }}
+        />
+      )}
+      <div className="absolute top-4 left-1/2 -translate-x-1/2 px-3 py-1 rounded bg-black/70 text-white font-mono text-xs pointer-events-none">
+        DRAG TO SELECT — ESC TO CANCEL
+      </div>
+    </div>
+  );
+}
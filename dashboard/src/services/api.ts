@@ -1,4 +1,4 @@
-import { AgentMetadata, ContentBlock, ChatMessage, ClotoMessage, PermissionRequest, Metrics, Memory, Episode, StrictSystemEvent, McpServerInfo, McpServerSettings, AccessTreeResponse, AccessControlEntry } from '../types';
+import { AgentMetadata, ContentBlock, ChatMessage, ClotoMessage, PermissionRequest, Metrics, Memory, Episode, StrictSystemEvent, McpServerInfo, McpServerSettings, McpServerModels, AccessTreeResponse, AccessControlEntry } from '../types';
 import { isTauri } from '../lib/tauri';
 
 // In Tauri mode, window.location.origin returns "tauri://localhost" which cannot reach
@@ -174,6 +174,14 @@ export const api = {
     return res.json();
   },
 
+  getMcpServerModels: async (name: string, apiKey: string): Promise<McpServerModels> => {
+    const res = await fetch(`${API_BASE}/mcp/servers/${encodeURIComponent(name)}/models`, {
+      headers: { 'X-API-Key': apiKey },
+    });
+    await throwIfNotOk(res, 'list models');
+    return res.json();
+  },
+
   updateMcpServerSettings: (name: string, settings: { default_policy?: string; env?: Record<string, string> }, apiKey: string) =>
     mutate(`/mcp/servers/${encodeURIComponent(name)}/settings`, 'PUT', 'update server settings', settings, { 'X-API-Key': apiKey }).then(() => {}),
 
@@ -213,8 +221,8 @@ export const api = {
       .then(r => { if (!r.ok) throw new Error(r.statusText); return r.json(); });
   },
 
-  createCronJob: (payload: { agent_id: string; name: string; schedule_type: string; schedule_value: string; message: string; engine_id?: string; max_iterations?: number }, apiKey: string) =>
-    mutate('/cron/jobs', 'POST', 'create cron job', payload, { 'X-API-Key': apiKey }).then(r => r.json()),
+  createCronJob: (payload: { agent_id: string; name: string; schedule_type: string; schedule_value: string; message: string; engine_id?: string; max_iterations?: number; misfire_policy?: string; jitter_secs?: number; chain_on?: string; weekdays_only?: boolean; skip_dates?: string[]; skip_ics?: string; timezone?: string }, apiKey: string) =>
+    mutate('/cron/jobs', 'POST', 'create cron job', payload, { 'X-API-Key': apiKey }).then(r => r.json() as Promise<{ id: string; next_run_at: number; next_runs: string[] }>),
 
   deleteCronJob: (jobId: string, apiKey: string) =>
     mutate(`/cron/jobs/${encodeURIComponent(jobId)}`, 'DELETE', 'delete cron job', undefined, { 'X-API-Key': apiKey }).then(() => {}),
@@ -225,6 +233,10 @@ export const api = {
   runCronJobNow: (jobId: string, apiKey: string) =>
     mutate(`/cron/jobs/${encodeURIComponent(jobId)}/run`, 'POST', 'run cron job', undefined, { 'X-API-Key': apiKey }).then(r => r.json()),
 
+  listCronJobRuns: (jobId: string, apiKey: string, limit = 20): Promise<{ runs: import('../types').CronJobRun[]; count: number }> =>
+    fetch(`${API_BASE}/cron/jobs/${encodeURIComponent(jobId)}/runs?limit=${limit}`, { headers: { 'X-API-Key': apiKey } })
+      .then(r => { if (!r.ok) throw new Error(r.statusText); return r.json(); }),
+
   // LLM Provider Management (MGP §13.4)
   listLlmProviders: (apiKey: string): Promise<{ providers: Array<{ id: string; display_name: string; api_url: string; has_key: boolean; model_id: string; timeout_secs: number; enabled: boolean }> }> =>
     fetch(`${API_BASE}/llm/providers`, { headers: { 'X-API-Key': apiKey } })
@@ -33,7 +33,10 @@ export type Permission =
   | 'ProcessExecution'
   | 'MemoryRead'
   | 'MemoryWrite'
-  | 'AdminAccess';
+  | 'AdminAccess'
+  | 'ClipboardAccess'
+  | 'CameraRead'
+  | 'ChannelRelay';
 
 export type CapabilityType =
   | 'Reasoning'
@@ -166,6 +169,15 @@ export interface McpServerInfo {
   tools: string[];
   is_cloto_sdk: boolean;
   source: ServerSource;
+  instances?: McpInstanceHealth[];
+}
+
+export interface McpInstanceHealth {
+  index: number;
+  alive: boolean;
+  in_flight: number;
+  requests_total: number;
+  errors_total: number;
 }
 
 export interface AccessControlEntry {
@@ -199,8 +211,17 @@ export interface McpServerSettings {
   description?: string;
 }
 
+/** Response of a mind server's `list_models` tool (e.g. mind.ollama). */
+export interface McpServerModels {
+  active_model?: string;
+  models: { name: string; size?: string; parameter_size?: string; quantization?: string }[];
+  count: number;
+}
+
 // Cron Job Scheduler (Layer 2: Autonomous Trigger)
-export type ScheduleType = 'interval' | 'cron' | 'once';
+export type ScheduleType = 'interval' | 'cron' | 'once' | 'after';
+export type MisfirePolicy = 'run_once' | 'skip' | 'run_all';
+export type ChainOn = 'success' | 'failure' | 'always';
 
 export interface CronJob {
   id: string;
@@ -213,8 +234,33 @@ export interface CronJob {
   message: string;
   next_run_at: number;
   last_run_at?: number;
-  last_status?: 'success' | 'error';
+  last_status?: 'success' | 'error' | 'skipped';
   last_error?: string;
   max_iterations?: number;
   created_at: string;
+  misfire_policy: MisfirePolicy;
+  jitter_secs: number;
+  /** For `after` jobs: upstream outcome that triggers this job (schedule_value is the upstream job ID). */
+  chain_on: ChainOn;
+  /** Skip occurrences on Saturday/Sunday (in the job's timezone). */
+  weekdays_only: boolean;
+  /** Comma-separated YYYY-MM-DD dates (job's timezone) on which occurrences are skipped. */
+  skip_dates: string;
+  /** IANA timezone of cron expressions and calendar modifiers; UTC when unset. */
+  timezone?: string;
+}
+
+export type CronRunStatus = 'running' | 'success' | 'error' | 'timeout';
+
+export interface CronJobRun {
+  id: number;
+  job_id: string;
+  trigger: 'scheduler' | 'manual';
+  message_id: string;
+  started_at: number;
+  finished_at?: number;
+  duration_ms?: number;
+  status: CronRunStatus;
+  response_message_id?: string;
+  error?: string;
 }
\ No newline at end of file
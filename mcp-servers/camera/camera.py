@@ -0,0 +1,105 @@
+"""
+Webcam access for the camera server.
+
+Devices are addressed by OpenCV index (0 = default camera). Each capture
+opens the device, discards a few warm-up frames (auto exposure), grabs one
+frame and releases the device again, so the camera indicator is only on
+while a frame is being taken and other servers (e.g. vision.gaze_webcam)
+can use the camera between captures.
+"""
+
+import os
+import tempfile
+import time
+from dataclasses import dataclass
+
+import cv2
+
+# Captured frames referenced by `image_ref` are written here
+CAPTURE_DIR = os.environ.get("CLOTO_CAMERA_DIR", os.path.join(tempfile.gettempdir(), "cloto-camera"))
+# Keep at most this many frames on disk (oldest are deleted first)
+MAX_STORED_FRAMES = int(os.environ.get("CLOTO_CAMERA_MAX_FRAMES", "50"))
+# Highest device index probed by list_devices()
+MAX_PROBE_INDEX = int(os.environ.get("CLOTO_CAMERA_MAX_PROBE", "5"))
+# Frames discarded after opening so exposure/white balance can settle
+WARMUP_FRAMES = int(os.environ.get("CLOTO_CAMERA_WARMUP_FRAMES", "5"))
+JPEG_QUALITY = int(os.environ.get("CLOTO_CAMERA_JPEG_QUALITY", "85"))
+
+
+class CameraError(Exception):
+    pass
+
+
+@dataclass
+class Frame:
+    # BGR image as returned by OpenCV
+    image: object
+    device: int
+    width: int
+    height: int
+    captured_at: float
+
+
+def list_devices() -> list[dict]:
+    """Probe device indices 0..MAX_PROBE_INDEX and return those that open."""
+    devices = []
+    for index in range(MAX_PROBE_INDEX + 1):
+        cap = cv2.VideoCapture(index)
+        try:
+            if cap.isOpened():
+                devices.append({
+                    "device": index,
+                    "width": int(cap.get(cv2.CAP_PROP_FRAME_WIDTH)),
+                    "height": int(cap.get(cv2.CAP_PROP_FRAME_HEIGHT)),
+                    "backend": cap.getBackendName(),
+                })
+        finally:
+            cap.release()
+    return devices
+
+
+def grab(device: int = 0, width: int | None = None, height: int | None = None) -> Frame:
+    """Open a device, take a single frame and release it."""
+    if device < 0:
+        raise CameraError("Device index must be non-negative")
+    cap = cv2.VideoCapture(device)
+    try:
+        if not cap.isOpened():
+            raise CameraError(f"Camera {device} could not be opened")
+        if width:
+            cap.set(cv2.CAP_PROP_FRAME_WIDTH, width)
+        if height:
+            cap.set(cv2.CAP_PROP_FRAME_HEIGHT, height)
+        for _ in range(WARMUP_FRAMES):
+            cap.grab()
+        ok, image = cap.read()
+        if not ok or image is None:
+            raise CameraError(f"Camera {device} returned no frame")
+        h, w = image.shape[:2]
+        return Frame(image=image, device=device, width=w, height=h, captured_at=time.time())
+    finally:
+        cap.release()
+
+
+def store(frame: Frame) -> str:
+    """Write the frame as JPEG under CAPTURE_DIR and return its path."""
+    os.makedirs(CAPTURE_DIR, exist_ok=True)
+    path = os.path.join(
+        CAPTURE_DIR, f"camera{frame.device}-{int(frame.captured_at * 1000)}.jpg"
+    )
+    if not cv2.imwrite(path, frame.image, [cv2.IMWRITE_JPEG_QUALITY, JPEG_QUALITY]):
+        raise CameraError(f"Failed to write {path}")
+    prune()
+    return path
+
+
+def prune() -> None:
+    frames = sorted(
+        (f for f in os.listdir(CAPTURE_DIR) if f.startswith("camera") and f.endswith(".jpg")),
+        key=lambda f: os.path.getmtime(os.path.join(CAPTURE_DIR, f)),
+    )
+    for name in frames[:-MAX_STORED_FRAMES] if MAX_STORED_FRAMES > 0 else []:
+        try:
+            os.remove(os.path.join(CAPTURE_DIR, name))
+        except OSError:
+            pass
@@ -0,0 +1,252 @@
+"""
+Cloto MCP Server: Camera
+Webcam frame capture so agents can see the room, not just the screen.
+
+Single frames are returned in the shape of `cloto_shared::ColorVisionData`
+({captured_at, detected_elements, image_ref}). In interval mode each frame
+is pushed to the kernel as `notifications/cloto.vision_updated` and published
+as a VisionUpdated event. Requires the CameraRead permission.
+"""
+
+import asyncio
+import json
+import sys
+import time
+from datetime import datetime, timezone
+
+from mcp.server import Server
+from mcp.server.stdio import stdio_server
+from mcp.types import Notification, TextContent, Tool
+
+import camera
+
+VISION_UPDATED_NOTIFICATION = "notifications/cloto.vision_updated"
+MIN_INTERVAL_SECS = 1.0
+
+# ============================================================
+# Server setup
+# ============================================================
+
+server = Server("vision.camera")
+
+DEVICE_SCHEMA = {
+    "type": "integer",
+    "description": "Camera device index (see list_cameras; default 0)",
+}
+
+
+def vision_data(frame: camera.Frame, image_ref: str) -> dict:
+    return {
+        "captured_at": datetime.fromtimestamp(frame.captured_at, timezone.utc).isoformat(),
+        "detected_elements": [],
+        "image_ref": image_ref,
+    }
+
+
+def error_result(message: str) -> list[TextContent]:
+    return [TextContent(type="text", text=json.dumps({"error": message}))]
+
+
+# ============================================================
+# Interval mode
+# ============================================================
+
+
+class IntervalCapture:
+    """Background task capturing a frame every `interval_secs`."""
+
+    def __init__(self):
+        self._task: asyncio.Task | None = None
+        self._session = None
+        self.device = 0
+        self.interval_secs = 0.0
+        self.started_at: float | None = None
+        self.frames_emitted = 0
+        self.last_image_ref: str | None = None
+        self.last_error: str | None = None
+
+    @property
+    def running(self) -> bool:
+        return self._task is not None and not self._task.done()
+
+    def start(self, session, device: int, interval_secs: float) -> None:
+        self.stop()
+        self._session = session
+        self.device = device
+        self.interval_secs = max(interval_secs, MIN_INTERVAL_SECS)
+        self.started_at = time.time()
+        self.frames_emitted = 0
+        self.last_error = None
+        self._task = asyncio.get_running_loop().create_task(self._run())
+
+    def stop(self) -> bool:
+        if not self.running:
+            return False
+        self._task.cancel()
+        self._task = None
+        return True
+
+    async def _run(self) -> None:
+        loop = asyncio.get_running_loop()
+        while True:
+            try:
+                frame = await loop.run_in_executor(None, camera.grab, self.device, None, None)
+                image_ref = await loop.run_in_executor(None, camera.store, frame)
+                await self._session.send_notification(
+                    Notification(
+                        method=VISION_UPDATED_NOTIFICATION,
+                        params=vision_data(frame, image_ref),
+                    )
+                )
+                self.frames_emitted += 1
+                self.last_image_ref = image_ref
+                self.last_error = None
+            except asyncio.CancelledError:
+                raise
+            except Exception as e:
+                self.last_error = str(e)
+            await asyncio.sleep(self.interval_secs)
+
+    def status(self) -> dict:
+        return {
+            "running": self.running,
+            "device": self.device,
+            "interval_secs": self.interval_secs,
+            "started_at": (
+                datetime.fromtimestamp(self.started_at, timezone.utc).isoformat()
+                if self.started_at is not None
+                else None
+            ),
+            "frames_emitted": self.frames_emitted,
+            "last_image_ref": self.last_image_ref,
+            "last_error": self.last_error,
+        }
+
+
+interval = IntervalCapture()
+
+# ============================================================
+# Tool definitions
+# ============================================================
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    return [
+        Tool(
+            name="list_cameras",
+            description="List the camera devices that can be opened.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+        Tool(
+            name="capture_frame",
+            description=(
+                "Take a single photo with a camera and return a reference to "
+                "the saved JPEG image."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "device": DEVICE_SCHEMA,
+                    "width": {"type": "integer", "description": "Requested frame width"},
+                    "height": {"type": "integer", "description": "Requested frame height"},
+                },
+                "required": [],
+            },
+        ),
+        Tool(
+            name="start_interval_capture",
+            description=(
+                "Take a photo every interval_secs in the background and emit each "
+                "as a VisionUpdated event. Replaces any running interval capture."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "device": DEVICE_SCHEMA,
+                    "interval_secs": {
+                        "type": "number",
+                        "description": "Seconds between frames (default 10, min 1)",
+                    },
+                },
+                "required": [],
+            },
+        ),
+        Tool(
+            name="stop_interval_capture",
+            description="Stop the running interval capture.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+        Tool(
+            name="get_capture_status",
+            description="Show the interval capture configuration and counters.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+    ]
+
+
+# ============================================================
+# Tool handlers
+# ============================================================
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    loop = asyncio.get_event_loop()
+
+    if name == "list_cameras":
+        devices = await loop.run_in_executor(None, camera.list_devices)
+        return [TextContent(type="text", text=json.dumps({"devices": devices}))]
+
+    elif name == "capture_frame":
+        try:
+            device = int(arguments.get("device", 0))
+            width = arguments.get("width")
+            height = arguments.get("height")
+            frame = await loop.run_in_executor(
+                None,
+                camera.grab,
+                device,
+                int(width) if width else None,
+                int(height) if height else None,
+            )
+            image_ref = await loop.run_in_executor(None, camera.store, frame)
+        except Exception as e:
+            return error_result(f"Camera capture failed: {e}")
+        return [TextContent(type="text", text=json.dumps(vision_data(frame, image_ref)))]
+
+    elif name == "start_interval_capture":
+        try:
+            device = int(arguments.get("device", 0))
+            interval_secs = float(arguments.get("interval_secs", 10.0))
+        except (TypeError, ValueError) as e:
+            return error_result(f"Invalid arguments: {e}")
+        if device < 0:
+            return error_result("Device index must be non-negative")
+        interval.start(server.request_context.session, device, interval_secs)
+        return [TextContent(type="text", text=json.dumps(interval.status()))]
+
+    elif name == "stop_interval_capture":
+        stopped = interval.stop()
+        return [TextContent(type="text", text=json.dumps({"stopped": stopped}))]
+
+    elif name == "get_capture_status":
+        return [TextContent(type="text", text=json.dumps(interval.status()))]
+
+    else:
+        return error_result(f"Unknown tool: {name}")
+
+
+# ============================================================
+# Entry point
+# ============================================================
+
+
+async def main():
+    async with stdio_server() as (read_stream, write_stream):
+        await server.run(read_stream, write_stream, server.create_initialization_options())
+
+
+if __name__ == "__main__":
+    print("Cloto MCP Camera Server starting...", file=sys.stderr)
+    asyncio.run(main())
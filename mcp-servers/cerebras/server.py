@@ -17,6 +17,7 @@ import sys
 _script_dir = os.path.dirname(os.path.abspath(__file__))
 sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
 
+from common.events import EventEmitter
 from common.llm_provider import (
     ProviderConfig,
     THINK_INPUT_SCHEMA,
@@ -47,6 +48,7 @@ config = ProviderConfig(
 # ============================================================
 
 server = Server("cloto-mcp-cerebras")
+emitter = EventEmitter()
 
 
 @server.list_tools()
@@ -65,8 +67,9 @@ async def list_tools() -> list[Tool]:
 
 @server.call_tool()
 async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    emitter.bind(server.request_context.session)
     if name == "think":
-        return await handle_think(config, arguments)
+        return await handle_think(config, arguments, emitter)
     else:
         return [
             TextContent(
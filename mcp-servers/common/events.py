@@ -0,0 +1,72 @@
+"""
+Event streaming from MCP servers to the Cloto kernel.
+
+Servers that produce data continuously (gaze tracking, sensors) push events
+instead of waiting to be polled. Events are sent as the JSON-RPC notification
+`notifications/cloto.emit` with a serialized `ClotoEventData` payload
+({"type": ..., "data": ...}); the kernel publishes them on its event bus with
+the server as issuer. Only observation events are accepted (GazeUpdated,
+SystemNotification, ThoughtResponseChunk from reasoning engines, and
+VisionUpdated for servers holding VisionRead or CameraRead), and each server
+is rate limited (CLOTO_MCP_EVENT_RATE_PER_SEC).
+
+Usage:
+
+    emitter = EventEmitter()
+
+    @server.call_tool()
+    async def call_tool(name, arguments):
+        emitter.bind(server.request_context.session)
+        ...
+
+    await emitter.emit("GazeUpdated", {...})            # from async code
+    emitter.emit_threadsafe("GazeUpdated", {...})       # from worker threads
+    await emitter.stream(some_async_generator())        # yields (type, data)
+"""
+
+import asyncio
+import sys
+from typing import AsyncIterator
+
+from mcp.types import Notification
+
+EMIT_NOTIFICATION = "notifications/cloto.emit"
+
+
+class EventEmitter:
+    def __init__(self) -> None:
+        self._session = None
+        self._loop: asyncio.AbstractEventLoop | None = None
+
+    @property
+    def bound(self) -> bool:
+        return self._session is not None
+
+    def bind(self, session) -> None:
+        """Attach to the kernel session. Call from inside a request handler."""
+        self._session = session
+        self._loop = asyncio.get_running_loop()
+
+    async def emit(self, event_type: str, data) -> bool:
+        """Send one event. Returns False if not bound or the send failed."""
+        if self._session is None:
+            return False
+        try:
+            await self._session.send_notification(
+                Notification(method=EMIT_NOTIFICATION, params={"type": event_type, "data": data})
+            )
+            return True
+        except Exception as e:
+            print(f"Failed to emit {event_type}: {e}", file=sys.stderr)
+            return False
+
+    def emit_threadsafe(self, event_type: str, data) -> None:
+        """Schedule an event from a non-asyncio thread (fire-and-forget)."""
+        if self._loop is None or self._loop.is_closed():
+            return
+        asyncio.run_coroutine_threadsafe(self.emit(event_type, data), self._loop)
+
+    async def stream(self, events: AsyncIterator[tuple[str, object]]) -> None:
+        """Forward every (event_type, data) pair yielded by an async generator."""
+        async for event_type, data in events:
+            await self.emit(event_type, data)
@@ -4,20 +4,26 @@ Shared logic for OpenAI-compatible LLM provider MCP servers.
 Extracted from deepseek/server.py and cerebras/server.py.
 
 Provides:
-- LLM API call via the kernel proxy (MGP S13.4)
+- LLM API call via the kernel proxy (MGP S13.4), streamed when an
+  EventEmitter is bound so the dashboard can show the reply as it is generated
 - Message building (system prompt, chat messages)
 - Response parsing (content extraction, tool-call parsing)
 - Common MCP tool definitions and handlers
 """
 
 import json
+import re
+import time
 from dataclasses import dataclass
+from typing import Awaitable, Callable
 
 import httpx
 from mcp.server import Server
 from mcp.server.stdio import stdio_server
 from mcp.types import TextContent
 
+from common.events import EventEmitter
+
 
 # ============================================================
 # Provider Configuration
@@ -33,6 +39,7 @@ class ProviderConfig:
     api_url: str = "http://127.0.0.1:8082/v1/chat/completions"
     request_timeout: int = 120
     supports_tools: bool = True
+    supports_vision: bool = False
     display_name: str = ""
 
     def __post_init__(self):
@@ -56,11 +63,34 @@ def model_supports_tools(config: ProviderConfig) -> bool:
     return "reasoner" not in config.model_id
 
 
-def build_system_prompt(agent: dict) -> str:
+def render_system_prompt(template: str, agent: dict, context: list[dict]) -> str:
+    """Fill in an agent's system_prompt template.
+
+    Ported from llm::render_system_prompt(). Unknown placeholders are left as-is.
+    """
+    values = {
+        "agent_name": agent.get("name", "Agent"),
+        "agent_id": agent.get("id", ""),
+        "description": agent.get("description", ""),
+        "date": time.strftime("%Y-%m-%d", time.gmtime()),
+        "memories": "\n".join(f"- {m.get('content', '')}" for m in context),
+    }
+    return re.sub(
+        r"\{([A-Za-z0-9_]+)\}",
+        lambda m: values.get(m.group(1), m.group(0)),
+        template,
+    )
+
+
+def build_system_prompt(agent: dict, context: list[dict] | None = None) -> str:
     """Build the system prompt for a Cloto agent.
 
-    Ported from llm::build_system_prompt().
+    Ported from llm::build_system_prompt(). The agent's own system_prompt
+    template, when set, replaces the built-in prompt.
     """
+    if agent.get("system_prompt"):
+        return render_system_prompt(agent["system_prompt"], agent, context or [])
+
     name = agent.get("name", "Agent")
     description = agent.get("description", "")
     metadata = agent.get("metadata", {})
@@ -97,7 +127,7 @@ def build_chat_messages(
     Returns [system_message, ...context_messages, user_message].
     Ported from llm::build_chat_messages().
     """
-    messages = [{"role": "system", "content": build_system_prompt(agent)}]
+    messages = [{"role": "system", "content": build_system_prompt(agent, context)}]
 
     for msg in context:
         source = msg.get("source", {})
@@ -112,10 +142,31 @@ def build_chat_messages(
             role = "system"
         messages.append({"role": role, "content": msg.get("content", "")})
 
-    messages.append({"role": "user", "content": message.get("content", "")})
+    messages.append({"role": "user", "content": user_content(message)})
     return messages
 
 
+def user_content(message: dict) -> str | list[dict]:
+    """Content of the user message: plain text, or text and image_url parts
+    when image attachments carry data.
+
+    Ported from llm::user_content().
+    """
+    images = [
+        {
+            "type": "image_url",
+            "image_url": {"url": f"data:{a['mime_type']};base64,{a['data']}"},
+        }
+        for a in message.get("attachments") or []
+        if a.get("data")
+        and a.get("mime_type", "").startswith("image/")
+        and a.get("mime_type") != "image/svg+xml"
+    ]
+    if not images:
+        return message.get("content", "")
+    return [{"type": "text", "text": message.get("content", "")}, *images]
+
+
 def parse_chat_content(config: ProviderConfig, response_data: dict) -> str:
     """Extract text content from a chat completions response.
 
@@ -237,6 +288,139 @@ async def call_llm_api(
         return response.json()
 
 
+# Streamed text is forwarded at most this often, keeping chunk notifications
+# well under the kernel's per-server event rate limit.
+STREAM_FLUSH_INTERVAL_SECS = 0.1
+
+
+async def stream_llm_api(
+    config: ProviderConfig,
+    messages: list[dict],
+    on_delta: Callable[[str], Awaitable[None]],
+    tools: list[dict] | None = None,
+) -> dict:
+    """Send a streaming request via the kernel LLM proxy.
+
+    Content deltas are passed to on_delta as they arrive. Returns the
+    response assembled into the non-streaming chat completions shape, so
+    parse_chat_content() / parse_chat_think_result() apply unchanged.
+    """
+    body: dict = {
+        "model": config.model_id,
+        "messages": messages,
+        "stream": True,
+    }
+
+    if tools and model_supports_tools(config):
+        body["tools"] = tools
+
+    content = ""
+    tool_calls: dict[int, dict] = {}
+    finish_reason = "stop"
+
+    async with httpx.AsyncClient(timeout=config.request_timeout) as client:
+        async with client.stream(
+            "POST",
+            config.api_url,
+            json=body,
+            headers={
+                "X-LLM-Provider": config.provider_id,
+                "Content-Type": "application/json",
+            },
+        ) as response:
+            if response.is_error:
+                # Error bodies are plain JSON; let the parsers report them
+                await response.aread()
+                try:
+                    return response.json()
+                except ValueError:
+                    response.raise_for_status()
+
+            async for line in response.aiter_lines():
+                if not line.startswith("data:"):
+                    continue
+                data = line[len("data:"):].strip()
+                if data == "[DONE]":
+                    break
+                try:
+                    chunk = json.loads(data)
+                except json.JSONDecodeError:
+                    continue
+                if "error" in chunk:
+                    return chunk
+                for choice in chunk.get("choices", []):
+                    delta = choice.get("delta") or {}
+                    text = delta.get("content")
+                    if text:
+                        content += text
+                        await on_delta(text)
+                    for tc in delta.get("tool_calls") or []:
+                        entry = tool_calls.setdefault(
+                            tc.get("index", 0),
+                            {"id": "", "type": "function", "function": {"name": "", "arguments": ""}},
+                        )
+                        if tc.get("id"):
+                            entry["id"] = tc["id"]
+                        function = tc.get("function") or {}
+                        entry["function"]["name"] += function.get("name") or ""
+                        entry["function"]["arguments"] += function.get("arguments") or ""
+                    if choice.get("finish_reason"):
+                        finish_reason = choice["finish_reason"]
+
+    message: dict = {"role": "assistant", "content": content}
+    if tool_calls:
+        message["tool_calls"] = [tool_calls[i] for i in sorted(tool_calls)]
+    return {"choices": [{"message": message, "finish_reason": finish_reason}]}
+
+
+class ChunkStreamer:
+    """Batches content deltas into ThoughtResponseChunk events."""
+
+    def __init__(self, emitter: EventEmitter, agent: dict, message: dict) -> None:
+        self._emitter = emitter
+        self._agent_id = agent.get("id", "")
+        self._message_id = message.get("id", "")
+        self._buffer = ""
+        self._last_flush = time.monotonic()
+
+    async def push(self, text: str) -> None:
+        self._buffer += text
+        if time.monotonic() - self._last_flush >= STREAM_FLUSH_INTERVAL_SECS:
+            await self.flush()
+
+    async def flush(self) -> None:
+        if not self._buffer:
+            return
+        # engine_id is filled in by the kernel
+        await self._emitter.emit(
+            "ThoughtResponseChunk",
+            {
+                "agent_id": self._agent_id,
+                "source_message_id": self._message_id,
+                "delta": self._buffer,
+            },
+        )
+        self._buffer = ""
+        self._last_flush = time.monotonic()
+
+
+async def complete(
+    config: ProviderConfig,
+    agent: dict,
+    message: dict,
+    messages: list[dict],
+    tools: list[dict] | None = None,
+    emitter: EventEmitter | None = None,
+) -> dict:
+    """Call the LLM, streaming the reply text when an emitter is bound."""
+    if emitter is None or not emitter.bound or not message.get("id"):
+        return await call_llm_api(config, messages, tools)
+    streamer = ChunkStreamer(emitter, agent, message)
+    response_data = await stream_llm_api(config, messages, streamer.push, tools)
+    await streamer.flush()
+    return response_data
+
+
 # ============================================================
 # Common MCP Tool Definitions
 # ============================================================
@@ -297,6 +481,27 @@ THINK_WITH_TOOLS_INPUT_SCHEMA = {
     ],
 }
 
+ATTACHMENTS_SCHEMA = {
+    "type": "array",
+    "description": "Image attachments (id, filename, mime_type, base64 data)",
+    "items": {"type": "object"},
+}
+
+
+def input_schema(config: ProviderConfig, schema: dict) -> dict:
+    """A think schema for this provider.
+
+    The kernel only sends image data to engines whose think schema declares
+    message.attachments, so it is added for providers with supports_vision.
+    """
+    if not config.supports_vision:
+        return schema
+    message = {
+        **schema["properties"]["message"],
+        "properties": {"attachments": ATTACHMENTS_SCHEMA},
+    }
+    return {**schema, "properties": {**schema["properties"], "message": message}}
+
 
 # ============================================================
 # Common MCP Tool Handlers
@@ -304,7 +509,7 @@ THINK_WITH_TOOLS_INPUT_SCHEMA = {
 
 
 async def handle_think(
-    config: ProviderConfig, arguments: dict
+    config: ProviderConfig, arguments: dict, emitter: EventEmitter | None = None
 ) -> list[TextContent]:
     """Handle 'think' tool: simple text generation."""
     try:
@@ -313,7 +518,7 @@ async def handle_think(
         context = arguments.get("context", [])
 
         messages = build_chat_messages(agent, message, context)
-        response_data = await call_llm_api(config, messages)
+        response_data = await complete(config, agent, message, messages, emitter=emitter)
         content = parse_chat_content(config, response_data)
 
         return [
@@ -330,7 +535,7 @@ async def handle_think(
 
 
 async def handle_think_with_tools(
-    config: ProviderConfig, arguments: dict
+    config: ProviderConfig, arguments: dict, emitter: EventEmitter | None = None
 ) -> list[TextContent]:
     """Handle 'think_with_tools' tool: may return tool calls or final text."""
     try:
@@ -344,7 +549,9 @@ async def handle_think_with_tools(
         # Append tool history (assistant messages with tool_calls + tool results)
         messages.extend(tool_history)
 
-        response_data = await call_llm_api(config, messages, tools)
+        response_data = await complete(
+            config, agent, message, messages, tools, emitter=emitter
+        )
         result = parse_chat_think_result(config, response_data)
 
         return [TextContent(type="text", text=json.dumps(result))]
@@ -0,0 +1,129 @@
+"""
+Channel relay helpers for adapter servers (Slack, Telegram, ...).
+
+Adapters bridge an external chat service to Cloto agents. Each inbound chat
+message is sent to the kernel as an MCP `sampling/createMessage` request; the
+kernel publishes it to the bound agent and answers with the agent's reply.
+This requires the ChannelRelay permission (required_permissions in mcp.toml).
+
+Admin actions (approving permissions, powering agents on/off) go through the
+kernel HTTP API with CLOTO_API_KEY, so adapters should only expose them to
+users on their admin allowlist.
+
+Usage:
+
+    relay = ChannelRelay()
+
+    @server.list_tools()
+    async def list_tools():
+        relay.bind(server.request_context.session)   # kernel lists tools on connect
+        ...
+
+    reply = await relay.ask("hello", agent_id="agent.cloto_default",
+                            user_id="U123", user_name="alice", conversation="C1:1700.1")
+"""
+
+import os
+
+import httpx
+from mcp.types import SamplingMessage, TextContent
+
+# Upper bound passed to the kernel; the agent's engine applies its own limits
+MAX_TOKENS = 4096
+
+API_URL = os.environ.get("CLOTO_API_URL", "http://127.0.0.1:8081/api").rstrip("/")
+API_KEY = os.environ.get("CLOTO_API_KEY", "")
+
+
+def parse_bindings(value: str) -> dict[str, str]:
+    """Parse "key=agent.a,key2=agent.b" into {key: agent_id}."""
+    bindings = {}
+    for item in value.split(","):
+        key, sep, agent = item.partition("=")
+        if sep and key.strip() and agent.strip():
+            bindings[key.strip()] = agent.strip()
+    return bindings
+
+
+def parse_list(value: str) -> set[str]:
+    """Parse a comma-separated allowlist. Empty means nobody."""
+    return {v.strip() for v in value.split(",") if v.strip()}
+
+
+class ChannelRelay:
+    def __init__(self) -> None:
+        self._session = None
+
+    @property
+    def bound(self) -> bool:
+        return self._session is not None
+
+    def bind(self, session) -> None:
+        """Attach to the kernel session. Call from inside a request handler."""
+        self._session = session
+
+    async def ask(
+        self,
+        text: str,
+        *,
+        agent_id: str | None = None,
+        user_id: str | None = None,
+        user_name: str | None = None,
+        conversation: str | None = None,
+        audio_ref: str | None = None,
+        audio_mime: str | None = None,
+    ) -> str:
+        """Relay one user message to an agent and return its reply.
+
+        audio_ref is a local path of a voice note, passed through to the agent
+        as message metadata for speech-to-text.
+        """
+        if self._session is None:
+            raise RuntimeError("Not connected to the kernel yet")
+        metadata = {
+            key: value
+            for key, value in {
+                "agent_id": agent_id,
+                "user_id": user_id,
+                "user_name": user_name,
+                "conversation": conversation,
+                "audio_ref": audio_ref,
+                "audio_mime": audio_mime,
+            }.items()
+            if value
+        }
+        result = await self._session.create_message(
+            messages=[SamplingMessage(role="user", content=TextContent(type="text", text=text))],
+            max_tokens=MAX_TOKENS,
+            metadata=metadata,
+        )
+        content = result.content
+        return content.text if getattr(content, "type", None) == "text" else ""
+
+
+class KernelAdmin:
+    """Kernel admin API client for adapter slash commands / buttons."""
+
+    def __init__(self) -> None:
+        self._client = httpx.AsyncClient(
+            base_url=API_URL, headers={"X-API-Key": API_KEY}, timeout=15.0
+        )
+
+    async def pending_permissions(self) -> list[dict]:
+        resp = await self._client.get("/permissions/pending")
+        resp.raise_for_status()
+        return resp.json()
+
+    async def decide_permission(self, request_id: str, approve: bool) -> None:
+        action = "approve" if approve else "deny"
+        resp = await self._client.post(f"/permissions/{request_id}/{action}", json={})
+        resp.raise_for_status()
+
+    async def set_agent_power(self, agent_id: str, enabled: bool) -> None:
+        resp = await self._client.post(f"/agents/{agent_id}/power", json={"enabled": enabled})
+        resp.raise_for_status()
+
+    async def agents(self) -> list[dict]:
+        resp = await self._client.get("/agents")
+        resp.raise_for_status()
+        return resp.json()
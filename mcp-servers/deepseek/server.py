@@ -14,6 +14,7 @@ import sys
 _script_dir = os.path.dirname(os.path.abspath(__file__))
 sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
 
+from common.events import EventEmitter
 from common.llm_provider import (
     ProviderConfig,
     THINK_INPUT_SCHEMA,
@@ -48,6 +49,7 @@ config = ProviderConfig(
 # ============================================================
 
 server = Server("cloto-mcp-deepseek")
+emitter = EventEmitter()
 
 
 @server.list_tools()
@@ -80,10 +82,11 @@ async def list_tools() -> list[Tool]:
 
 @server.call_tool()
 async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    emitter.bind(server.request_context.session)
     if name == "think":
-        return await handle_think(config, arguments)
+        return await handle_think(config, arguments, emitter)
     elif name == "think_with_tools":
-        return await handle_think_with_tools(config, arguments)
+        return await handle_think_with_tools(config, arguments, emitter)
     else:
         return [
             TextContent(
@@ -1,7 +1,8 @@
 """
 Cloto MCP Server: Vector Embedding
 Pluggable embedding provider with HTTP endpoint for inter-server communication.
-Providers: api_openai (OpenAI-compatible API), onnx_miniml (local MiniLM ONNX).
+Providers: api_openai (OpenAI-compatible API), onnx_miniml (local MiniLM ONNX),
+fastembed (local models via the fastembed library).
 
 Design: docs/KS22_MEMORY_DESIGN.md Section 5
 """
@@ -241,6 +242,60 @@ class OnnxMiniLMProvider(EmbeddingProvider):
         self._tokenizer = None
 
 
+# ============================================================
+# fastembed Provider
+# ============================================================
+
+
+class FastEmbedProvider(EmbeddingProvider):
+    """Local embedding provider backed by fastembed (downloads the model on first use)."""
+
+    def __init__(self, model: str):
+        self._model_name = model or "BAAI/bge-small-en-v1.5"
+        self._model = None
+        self._dimensions = 384  # bge-small / MiniLM default, updated on first embed
+        self._lock = asyncio.Lock()
+
+    async def initialize(self) -> None:
+        try:
+            from fastembed import TextEmbedding
+        except ImportError:
+            raise ImportError(
+                "fastembed provider requires: pip install fastembed\n"
+                "Or: pip install cloto-mcp-embedding[fastembed]"
+            )
+
+        self._model = await asyncio.get_event_loop().run_in_executor(
+            None, lambda: TextEmbedding(model_name=self._model_name)
+        )
+        logger.info("fastembed provider initialized (model=%s)", self._model_name)
+
+    async def embed(self, texts: list[str]) -> list[list[float]]:
+        if not self._model:
+            raise RuntimeError("Provider not initialized")
+
+        async with self._lock:
+            return await asyncio.get_event_loop().run_in_executor(
+                None, self._embed_sync, texts
+            )
+
+    def _embed_sync(self, texts: list[str]) -> list[list[float]]:
+        """Synchronous embedding (run in executor to avoid blocking)."""
+        vectors = np.array(list(self._model.embed(texts)), dtype=np.float32)
+        if vectors.size:
+            self._dimensions = vectors.shape[1]
+
+        # L2-normalize for consistent cosine similarity via dot product
+        norms = np.clip(np.linalg.norm(vectors, axis=1, keepdims=True), 1e-9, None)
+        return (vectors / norms).tolist()
+
+    def dimensions(self) -> int:
+        return self._dimensions
+
+    async def shutdown(self) -> None:
+        self._model = None
+
+
 # ============================================================
 # Provider Factory
 # ============================================================
@@ -257,10 +312,12 @@ def create_provider() -> EmbeddingProvider:
         )
     elif EMBEDDING_PROVIDER == "onnx_miniml":
         return OnnxMiniLMProvider(model_dir=ONNX_MODEL_DIR)
+    elif EMBEDDING_PROVIDER == "fastembed":
+        return FastEmbedProvider(model=EMBEDDING_MODEL)
     else:
         raise ValueError(
             f"Unknown embedding provider: {EMBEDDING_PROVIDER}. "
-            f"Supported: api_openai, onnx_miniml"
+            f"Supported: api_openai, onnx_miniml, fastembed"
         )
 
 
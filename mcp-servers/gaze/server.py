@@ -5,17 +5,27 @@ Webcam-based eye gaze detection via MediaPipe FaceLandmarker.
 Provides AI agents with awareness of where the user is looking,
 whether they are present at the screen, and attention status.
 Camera capture and ML inference run in a background thread;
-MCP tools return the latest result instantly.
+MCP tools return the latest result instantly. While tracking, gaze changes
+are also streamed to the kernel as GazeUpdated events.
 """
 
 import asyncio
 import json
+import math
+import os
 import sys
+import time
+
+# Resolve parent directory for common module import.
+# Handle Windows UNC paths (\\?\...) that Python may receive from the kernel.
+_script_dir = os.path.dirname(os.path.abspath(__file__))
+sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
 
 from mcp.server import Server
 from mcp.server.stdio import stdio_server
 from mcp.types import TextContent, Tool
 
+from common.events import EventEmitter
 from gaze_engine import GazeEngine
 
 # ============================================================
@@ -24,6 +34,49 @@ from gaze_engine import GazeEngine
 
 server = Server("vision.gaze_webcam")
 engine = GazeEngine()
+emitter = EventEmitter()
+
+# GazeData carries pixel coordinates; normalized gaze is scaled to this size
+SCREEN_WIDTH, SCREEN_HEIGHT = (
+    int(v) for v in os.environ.get("CLOTO_GAZE_SCREEN_SIZE", "1920x1080").lower().split("x")
+)
+# Events per second while the gaze is moving
+EMIT_HZ = float(os.environ.get("CLOTO_GAZE_EMIT_HZ", "10"))
+# Normalized movement below this is not re-emitted (unless fixation changes)
+MOVE_EPSILON = 0.01
+# Gaze staying within FIXATION_RADIUS for FIXATION_SECS counts as fixated
+FIXATION_RADIUS = 0.03
+FIXATION_SECS = 0.5
+
+
+async def gaze_events():
+    """Yield GazeUpdated events while tracking; quiet while the gaze is still."""
+    last_sent = None
+    anchor = None
+    anchor_since = 0.0
+    fixated_sent = False
+    while engine.is_running:
+        gaze = engine.get_gaze()
+        if gaze.face_detected:
+            now = time.monotonic()
+            if anchor is None or math.dist(anchor, (gaze.gaze_x, gaze.gaze_y)) > FIXATION_RADIUS:
+                anchor = (gaze.gaze_x, gaze.gaze_y)
+                anchor_since = now
+            fixated = now - anchor_since >= FIXATION_SECS
+            moved = last_sent is None or math.dist(last_sent, (gaze.gaze_x, gaze.gaze_y)) > MOVE_EPSILON
+            if moved or fixated != fixated_sent:
+                last_sent = (gaze.gaze_x, gaze.gaze_y)
+                fixated_sent = fixated
+                yield "GazeUpdated", {
+                    "x": round(gaze.gaze_x * (SCREEN_WIDTH - 1)),
+                    "y": round(gaze.gaze_y * (SCREEN_HEIGHT - 1)),
+                    "confidence": gaze.confidence,
+                    "fixated": fixated,
+                }
+        await asyncio.sleep(1.0 / EMIT_HZ)
+
+
+_stream_task: asyncio.Task | None = None
 
 # ============================================================
 # Tool definitions
@@ -42,7 +95,12 @@ async def list_tools() -> list[Tool]:
             ),
             inputSchema={
                 "type": "object",
-                "properties": {},
+                "properties": {
+                    "stream_events": {
+                        "type": "boolean",
+                        "description": "Stream GazeUpdated events to the kernel while tracking (default true)",
+                    },
+                },
                 "required": [],
             },
         ),
@@ -103,10 +161,18 @@ async def list_tools() -> list[Tool]:
 
 @server.call_tool()
 async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    global _stream_task
     if name == "start_tracking":
         result = await asyncio.get_event_loop().run_in_executor(
             None, engine.start
         )
+        emitter.bind(server.request_context.session)
+        if bool(arguments.get("stream_events", True)) and (
+            _stream_task is None or _stream_task.done()
+        ):
+            # Give the capture thread a moment to mark itself running
+            await asyncio.sleep(0.5)
+            _stream_task = asyncio.get_running_loop().create_task(emitter.stream(gaze_events()))
         return [TextContent(type="text", text=json.dumps({
             "status": result,
             "message": {
@@ -0,0 +1,329 @@
+"""
+Cloto MCP Server: Gemini
+Google Gemini reasoning engine via MCP protocol, using the native
+Generative Language API (generateContent) with function calling.
+
+The kernel hands engines OpenAI-style messages and tool schemas; this server
+converts them to Gemini `contents` / `functionDeclarations` and converts the
+reply back into the ThinkResult shape the other engines return.
+"""
+
+import asyncio
+import json
+import os
+import sys
+import uuid
+
+import httpx
+
+# Resolve parent directory for common module import.
+# Handle Windows UNC paths (\\?\...) that Python may receive from the kernel.
+_script_dir = os.path.dirname(os.path.abspath(__file__))
+sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
+
+from common.llm_provider import (
+    ProviderConfig,
+    THINK_INPUT_SCHEMA,
+    THINK_WITH_TOOLS_INPUT_SCHEMA,
+    build_chat_messages,
+    input_schema,
+    run_server,
+)
+from mcp.server import Server
+from mcp.types import TextContent, Tool
+
+# ============================================================
+# Configuration (from environment variables)
+# ============================================================
+
+# API key is managed by kernel LLM proxy (MGP §13.4). The proxy fills the
+# model into the provider URL and sends the key as x-goog-api-key.
+config = ProviderConfig(
+    provider_id=os.environ.get("GEMINI_PROVIDER", "gemini"),
+    model_id=os.environ.get("GEMINI_MODEL", "gemini-2.0-flash"),
+    api_url=os.environ.get(
+        "GEMINI_API_URL", "http://127.0.0.1:8082/v1/chat/completions"
+    ),
+    request_timeout=int(os.environ.get("GEMINI_TIMEOUT_SECS", "120")),
+    supports_tools=True,
+    supports_vision=True,
+    display_name="Gemini",
+)
+
+# JSON Schema keywords Gemini accepts in function parameters (an OpenAPI
+# subset). Anything else ($schema, additionalProperties, default, ...) is
+# rejected by the API, so it is dropped.
+SCHEMA_KEYS = {
+    "type",
+    "format",
+    "description",
+    "nullable",
+    "enum",
+    "properties",
+    "required",
+    "items",
+    "minItems",
+    "maxItems",
+    "minimum",
+    "maximum",
+    "anyOf",
+}
+
+# ============================================================
+# Request Conversion (OpenAI-style → Gemini)
+# ============================================================
+
+
+def convert_schema(schema: dict) -> dict:
+    """Reduce a JSON Schema to the subset Gemini accepts."""
+    result = {}
+    for key, value in schema.items():
+        if key not in SCHEMA_KEYS:
+            continue
+        if key == "properties" and isinstance(value, dict):
+            result[key] = {
+                name: convert_schema(prop)
+                for name, prop in value.items()
+                if isinstance(prop, dict)
+            }
+        elif key == "items" and isinstance(value, dict):
+            result[key] = convert_schema(value)
+        elif key == "anyOf" and isinstance(value, list):
+            result[key] = [convert_schema(s) for s in value if isinstance(s, dict)]
+        elif key == "type" and isinstance(value, list):
+            # ["string", "null"] → type string, nullable
+            types = [t for t in value if t != "null"]
+            result["type"] = types[0] if types else "string"
+            if "null" in value:
+                result["nullable"] = True
+        else:
+            result[key] = value
+    if "required" in result and "properties" in result:
+        result["required"] = [r for r in result["required"] if r in result["properties"]]
+    return result
+
+
+def convert_tools(tools: list[dict]) -> list[dict]:
+    """OpenAI tool schemas (as collected by McpClientManager) → Gemini tools."""
+    declarations = []
+    for tool in tools:
+        function = tool.get("function", tool)
+        name = function.get("name")
+        if not name:
+            continue
+        declaration = {"name": name, "description": function.get("description", "")}
+        parameters = convert_schema(function.get("parameters") or {})
+        # Gemini rejects OBJECT parameters without properties
+        if parameters.get("properties"):
+            declaration["parameters"] = parameters
+        declarations.append(declaration)
+    return [{"functionDeclarations": declarations}] if declarations else []
+
+
+def _append(contents: list[dict], role: str, part: dict) -> None:
+    """Add a part, merging consecutive turns of the same role."""
+    if contents and contents[-1]["role"] == role:
+        contents[-1]["parts"].append(part)
+    else:
+        contents.append({"role": role, "parts": [part]})
+
+
+def convert_messages(messages: list[dict]) -> tuple[dict | None, list[dict]]:
+    """OpenAI-style messages → (systemInstruction, contents).
+
+    The leading system message becomes the system instruction; later system
+    messages (loop-guard corrections) are sent as user text. Tool results are
+    matched to their calls by tool_call_id to recover the function name.
+    """
+    system_instruction = None
+    contents: list[dict] = []
+    call_names: dict[str, str] = {}
+
+    for index, msg in enumerate(messages):
+        role = msg.get("role")
+        content = msg.get("content")
+        if role == "system":
+            if index == 0:
+                system_instruction = {"parts": [{"text": content or ""}]}
+            elif content:
+                _append(contents, "user", {"text": f"[System] {content}"})
+        elif role == "assistant":
+            if content:
+                _append(contents, "model", {"text": content})
+            for call in msg.get("tool_calls") or []:
+                function = call.get("function", {})
+                name = function.get("name", "")
+                call_names[call.get("id", "")] = name
+                arguments = function.get("arguments") or "{}"
+                if isinstance(arguments, str):
+                    try:
+                        arguments = json.loads(arguments)
+                    except json.JSONDecodeError:
+                        arguments = {}
+                _append(contents, "model", {"functionCall": {"name": name, "args": arguments}})
+        elif role == "tool":
+            name = call_names.get(msg.get("tool_call_id", ""), "unknown")
+            _append(
+                contents,
+                "user",
+                {"functionResponse": {"name": name, "response": {"content": content}}},
+            )
+        elif isinstance(content, list):
+            for part in content:
+                _append(contents, "user", convert_part(part))
+        elif content:
+            _append(contents, "user", {"text": content})
+
+    return system_instruction, contents
+
+
+def convert_part(part: dict) -> dict:
+    """OpenAI content part → Gemini part. Images arrive as base64 data URLs."""
+    if part.get("type") == "image_url":
+        url = part.get("image_url", {}).get("url", "")
+        header, _, data = url.partition(",")
+        mime_type = header.removeprefix("data:").removesuffix(";base64")
+        return {"inlineData": {"mimeType": mime_type, "data": data}}
+    return {"text": part.get("text", "")}
+
+
+# ============================================================
+# Response Parsing (Gemini → ThinkResult)
+# ============================================================
+
+
+def parse_think_result(response_data: dict) -> dict:
+    """Parse a generateContent response into a ThinkResult.
+
+    Returns either:
+      {"type": "final", "content": "..."}
+    or:
+      {"type": "tool_calls", "assistant_content": "...", "calls": [...]}
+    """
+    if "error" in response_data:
+        error = response_data["error"]
+        msg = error.get("message", str(error)) if isinstance(error, dict) else str(error)
+        raise ValueError(f"Gemini API Error: {msg}")
+
+    candidates = response_data.get("candidates") or []
+    if not candidates:
+        reason = (response_data.get("promptFeedback") or {}).get("blockReason")
+        if reason:
+            raise ValueError(f"Gemini blocked the prompt: {reason}")
+        raise ValueError("Invalid Gemini API response: no candidates")
+
+    candidate = candidates[0]
+    parts = (candidate.get("content") or {}).get("parts") or []
+    text = "".join(p.get("text", "") for p in parts if not p.get("thought"))
+    calls = [
+        {
+            # Gemini only returns call ids on some models
+            "id": p["functionCall"].get("id") or f"gemini-{uuid.uuid4().hex[:12]}",
+            "name": p["functionCall"].get("name", ""),
+            "arguments": p["functionCall"].get("args") or {},
+        }
+        for p in parts
+        if "functionCall" in p and p["functionCall"].get("name")
+    ]
+
+    if calls:
+        return {
+            "type": "tool_calls",
+            "assistant_content": text or None,
+            "calls": calls,
+        }
+    if not parts and candidate.get("finishReason") not in (None, "STOP"):
+        raise ValueError(f"Gemini stopped without a reply: {candidate['finishReason']}")
+    return {"type": "final", "content": text}
+
+
+# ============================================================
+# LLM API Call
+# ============================================================
+
+
+async def generate(messages: list[dict], tools: list[dict] | None = None) -> dict:
+    """Send a generateContent request via the kernel LLM proxy (MGP S13.4)."""
+    system_instruction, contents = convert_messages(messages)
+    body: dict = {"model": config.model_id, "contents": contents}
+    if system_instruction:
+        body["systemInstruction"] = system_instruction
+    gemini_tools = convert_tools(tools or [])
+    if gemini_tools:
+        body["tools"] = gemini_tools
+
+    async with httpx.AsyncClient(timeout=config.request_timeout) as client:
+        response = await client.post(
+            config.api_url,
+            json=body,
+            headers={
+                "X-LLM-Provider": config.provider_id,
+                "Content-Type": "application/json",
+            },
+        )
+        try:
+            return response.json()
+        except ValueError:
+            response.raise_for_status()
+            raise
+
+
+# ============================================================
+# MCP Server
+# ============================================================
+
+server = Server("cloto-mcp-gemini")
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    return [
+        Tool(
+            name="think",
+            description=(
+                "Generate a text response using Google Gemini. "
+                "Use this for simple text generation without tool support."
+            ),
+            inputSchema=input_schema(config, THINK_INPUT_SCHEMA),
+        ),
+        Tool(
+            name="think_with_tools",
+            description=(
+                "Generate a response that may include tool calls. "
+                "Returns either final text or a list of tool calls to execute."
+            ),
+            inputSchema=input_schema(config, THINK_WITH_TOOLS_INPUT_SCHEMA),
+        ),
+    ]
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    if name not in ("think", "think_with_tools"):
+        return [
+            TextContent(
+                type="text",
+                text=json.dumps({"error": f"Unknown tool: {name}"}),
+            )
+        ]
+    try:
+        messages = build_chat_messages(
+            arguments.get("agent", {}),
+            arguments.get("message", {}),
+            arguments.get("context", []),
+        )
+        tools = None
+        if name == "think_with_tools":
+            messages.extend(arguments.get("tool_history", []))
+            tools = arguments.get("tools", [])
+
+        result = parse_think_result(await generate(messages, tools))
+        if name == "think" and result["type"] != "final":
+            result = {"type": "final", "content": result.get("assistant_content") or ""}
+        return [TextContent(type="text", text=json.dumps(result))]
+    except Exception as e:
+        return [TextContent(type="text", text=json.dumps({"error": str(e)}))]
+
+
+if __name__ == "__main__":
+    asyncio.run(run_server(server))
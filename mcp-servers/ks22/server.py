@@ -5,6 +5,7 @@ Ported from plugins/ks22/src/lib.rs + ai_karin KS2.1 architecture.
 
 Phase 1: store, recall (FTS5 + keyword), update_profile (stub), archive_episode (simple)
 Phase 2: Vector embedding integration (cosine similarity search)
+Phase 3: compact_memories (kernel-driven summarization of old memories)
 """
 
 import asyncio
@@ -662,6 +663,57 @@ async def do_archive_episode(agent_id: str, history: list[dict]) -> dict:
     return {"ok": True, "episode_id": cursor.lastrowid}
 
 
+async def do_compact_memories(agent_id: str, memory_ids: list[int], summary: str) -> dict:
+    """Replace memories with one summary entry (written by the agent's engine)."""
+    db = await get_db()
+
+    summary = summary.strip()
+    if not agent_id or not memory_ids or not summary:
+        return {"error": "agent_id, memory_ids and summary are required"}
+
+    placeholders = ",".join("?" for _ in memory_ids)
+    rows = await db.execute_fetchall(
+        f"SELECT id, timestamp, created_at FROM memories WHERE agent_id = ? AND id IN ({placeholders})",
+        (agent_id, *memory_ids),
+    )
+    if not rows:
+        return {"ok": True, "memory_id": None, "compacted": 0}
+    ids = [row[0] for row in rows]
+    # Take the place of the newest memory covered, so the next compaction
+    # folds this summary in again together with the memories that follow it
+    timestamp = max(row[1] for row in rows)
+    created_at = max(row[2] for row in rows)
+
+    embedding_blob = None
+    if _embedding_client:
+        try:
+            embeddings = await _embedding_client.embed([summary])
+            if embeddings and embeddings[0]:
+                embedding_blob = EmbeddingClient.pack_embedding(embeddings[0])
+        except Exception as e:
+            logger.warning("Embedding failed for memory summary: %s", e)
+
+    cursor = await db.execute(
+        """INSERT INTO memories
+               (agent_id, msg_id, content, source, timestamp, metadata, embedding, created_at)
+           VALUES (?, '', ?, ?, ?, ?, ?, ?)""",
+        (
+            agent_id,
+            summary,
+            json.dumps({"type": "System", "kind": "summary"}),
+            timestamp,
+            json.dumps({"compacted": len(ids)}),
+            embedding_blob,
+            created_at,
+        ),
+    )
+    await db.execute(
+        f"DELETE FROM memories WHERE id IN ({','.join('?' for _ in ids)})", ids
+    )
+    await db.commit()
+    return {"ok": True, "memory_id": cursor.lastrowid, "compacted": len(ids)}
+
+
 def _try_parse_json(s: str) -> dict:
     """Try to parse a string as JSON, return empty dict on failure."""
     try:
@@ -774,10 +826,38 @@ async def list_tools() -> list[Tool]:
                         "description": "Max memories to return",
                         "default": 100,
                     },
+                    "oldest_first": {
+                        "type": "boolean",
+                        "description": "Return the oldest memories first",
+                        "default": False,
+                    },
                 },
                 "required": [],
             },
         ),
+        Tool(
+            name="compact_memories",
+            description="Replace old memories with a single summary entry.",
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "agent_id": {
+                        "type": "string",
+                        "description": "Agent identifier",
+                    },
+                    "memory_ids": {
+                        "type": "array",
+                        "description": "Memories covered by the summary",
+                        "items": {"type": "integer"},
+                    },
+                    "summary": {
+                        "type": "string",
+                        "description": "Summary that replaces them",
+                    },
+                },
+                "required": ["agent_id", "memory_ids", "summary"],
+            },
+        ),
         Tool(
             name="list_episodes",
             description="List archived episodes for an agent (for dashboard display).",
@@ -828,6 +908,13 @@ async def call_tool(name: str, arguments: dict) -> list[TextContent]:
             result = await do_list_memories(
                 arguments.get("agent_id", ""),
                 arguments.get("limit", 100),
+                arguments.get("oldest_first", False),
+            )
+        elif name == "compact_memories":
+            result = await do_compact_memories(
+                arguments.get("agent_id", ""),
+                arguments.get("memory_ids", []),
+                arguments.get("summary", ""),
             )
         elif name == "list_episodes":
             result = await do_list_episodes(
@@ -844,21 +931,26 @@ async def call_tool(name: str, arguments: dict) -> list[TextContent]:
         ]
 
 
-async def do_list_memories(agent_id: str, limit: int) -> dict:
-    """List recent memories for dashboard display."""
+async def do_list_memories(agent_id: str, limit: int, oldest_first: bool = False) -> dict:
+    """List recent memories for dashboard display (oldest first for compaction)."""
     db = await get_db()
+    order = "ASC" if oldest_first else "DESC"
     if agent_id:
         rows = await db.execute_fetchall(
             "SELECT id, agent_id, msg_id, content, source, timestamp, created_at "
-            "FROM memories WHERE agent_id = ? ORDER BY created_at DESC LIMIT ?",
+            f"FROM memories WHERE agent_id = ? ORDER BY created_at {order}, id {order} LIMIT ?",
             (agent_id, min(limit, 500)),
         )
+        total_rows = await db.execute_fetchall(
+            "SELECT COUNT(*) FROM memories WHERE agent_id = ?", (agent_id,)
+        )
     else:
         rows = await db.execute_fetchall(
             "SELECT id, agent_id, msg_id, content, source, timestamp, created_at "
-            "FROM memories ORDER BY created_at DESC LIMIT ?",
+            f"FROM memories ORDER BY created_at {order}, id {order} LIMIT ?",
             (min(limit, 500),),
         )
+        total_rows = await db.execute_fetchall("SELECT COUNT(*) FROM memories")
     memories = []
     for row in rows:
         source = {}
@@ -874,7 +966,7 @@ async def do_list_memories(agent_id: str, limit: int) -> dict:
             "timestamp": row[5],
             "created_at": row[6],
         })
-    return {"memories": memories, "count": len(memories)}
+    return {"memories": memories, "count": len(memories), "total": total_rows[0][0]}
 
 
 async def do_list_episodes(agent_id: str, limit: int) -> dict:
@@ -0,0 +1,277 @@
+"""
+Cloto MCP Server: Matrix Adapter
+Connects a Matrix account to Cloto agents, so a self-hosted homeserver can
+serve as the chat frontend.
+
+Messages in joined rooms are relayed to the agent bound to the room
+(MATRIX_ROOM_AGENTS, else MATRIX_DEFAULT_AGENT, else the kernel's default
+agent) and answered as replies. End-to-end encrypted rooms are supported:
+the olm/megolm keys live in MATRIX_STORE_DIR, which must persist across
+restarts. Only MATRIX_ALLOWED_USERS are answered and may invite the bot;
+MATRIX_ADMIN_USERS may rebind a room with `!bind <agent_id>`.
+Requires the ChannelRelay permission.
+"""
+
+import asyncio
+import json
+import os
+import sys
+
+# Resolve parent directory for common module import.
+# Handle Windows UNC paths (\\?\...) that Python may receive from the kernel.
+_script_dir = os.path.dirname(os.path.abspath(__file__))
+sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
+
+from mcp.server import Server
+from mcp.server.stdio import stdio_server
+from mcp.types import TextContent, Tool
+from nio import (
+    AsyncClient,
+    AsyncClientConfig,
+    InviteMemberEvent,
+    LoginResponse,
+    MatrixRoom,
+    MegolmEvent,
+    RoomMessageText,
+)
+
+from common.relay import ChannelRelay, parse_bindings, parse_list
+
+HOMESERVER = os.environ.get("MATRIX_HOMESERVER", "")
+USER_ID = os.environ.get("MATRIX_USER_ID", "")
+PASSWORD = os.environ.get("MATRIX_PASSWORD", "")
+ACCESS_TOKEN = os.environ.get("MATRIX_ACCESS_TOKEN", "")
+DEVICE_ID = os.environ.get("MATRIX_DEVICE_ID", "")
+STORE_DIR = os.environ.get("MATRIX_STORE_DIR", os.path.join("data", "matrix-store"))
+DEFAULT_AGENT = os.environ.get("MATRIX_DEFAULT_AGENT", "") or None
+# Unlike the other adapters, an empty allowlist answers nobody: a Matrix
+# account is reachable from the whole federation.
+ALLOWED_USERS = parse_list(os.environ.get("MATRIX_ALLOWED_USERS", ""))
+ADMIN_USERS = parse_list(os.environ.get("MATRIX_ADMIN_USERS", ""))
+
+SYNC_TIMEOUT_MS = 30000
+
+# ============================================================
+# Server setup
+# ============================================================
+
+server = Server("adapter.matrix")
+relay = ChannelRelay()
+
+# Matrix room ID -> agent ID. `!bind` updates it until restart.
+bindings = parse_bindings(os.environ.get("MATRIX_ROOM_AGENTS", ""))
+
+client: AsyncClient | None = None
+_sync_task: asyncio.Task | None = None
+
+
+def agent_for(room_id: str) -> str | None:
+    return bindings.get(room_id, DEFAULT_AGENT)
+
+
+def error_result(message: str) -> list[TextContent]:
+    return [TextContent(type="text", text=json.dumps({"error": message}))]
+
+
+async def send_text(room_id: str, text: str, reply_to: str | None = None) -> str:
+    content = {"msgtype": "m.text", "body": text}
+    if reply_to:
+        content["m.relates_to"] = {"m.in_reply_to": {"event_id": reply_to}}
+    # Devices of room members are trusted on first use; the allowlist is the
+    # access control, not device verification.
+    resp = await client.room_send(
+        room_id, "m.room.message", content, ignore_unverified_devices=True
+    )
+    return getattr(resp, "event_id", "")
+
+
+# ============================================================
+# Matrix events
+# ============================================================
+
+
+async def on_invite(room: MatrixRoom, event: InviteMemberEvent) -> None:
+    if event.state_key != USER_ID or event.membership != "invite":
+        return
+    if event.sender in ALLOWED_USERS:
+        await client.join(room.room_id)
+        print(f"Joined {room.room_id} (invited by {event.sender})", file=sys.stderr)
+
+
+async def on_message(room: MatrixRoom, event: RoomMessageText) -> None:
+    if event.sender == USER_ID or event.sender not in ALLOWED_USERS:
+        return
+    text = event.body.strip()
+    if not text:
+        return
+
+    if text.startswith("!bind"):
+        args = text.split()
+        if event.sender not in ADMIN_USERS:
+            answer = "You are not allowed to change the agent binding."
+        elif len(args) != 2:
+            answer = "Usage: !bind <agent_id>"
+        else:
+            bindings[room.room_id] = args[1]
+            answer = f"This room now talks to {args[1]}."
+        await send_text(room.room_id, answer, event.event_id)
+        return
+    if text == "!status":
+        agent = agent_for(room.room_id) or "the default agent"
+        await send_text(room.room_id, f"This room talks to {agent}.", event.event_id)
+        return
+
+    try:
+        await client.room_typing(room.room_id, True)
+        reply = await relay.ask(
+            text,
+            agent_id=agent_for(room.room_id),
+            user_id=event.sender,
+            user_name=room.user_name(event.sender),
+            conversation=room.room_id,
+        )
+    except Exception as e:
+        print(f"Relay failed: {e}", file=sys.stderr)
+        reply = f"⚠️ {e}"
+    finally:
+        await client.room_typing(room.room_id, False)
+    if reply:
+        await send_text(room.room_id, reply, event.event_id)
+
+
+async def on_undecryptable(room: MatrixRoom, event: MegolmEvent) -> None:
+    print(
+        f"Cannot decrypt message {event.event_id} in {room.room_id}; "
+        "was the key store reset?",
+        file=sys.stderr,
+    )
+
+
+# ============================================================
+# Client lifecycle
+# ============================================================
+
+
+async def run_client() -> None:
+    global client
+    os.makedirs(STORE_DIR, exist_ok=True)
+    config = AsyncClientConfig(store_sync_tokens=True, encryption_enabled=True)
+    client = AsyncClient(
+        HOMESERVER, USER_ID, device_id=DEVICE_ID or None, store_path=STORE_DIR, config=config
+    )
+    if ACCESS_TOKEN:
+        client.restore_login(USER_ID, DEVICE_ID, ACCESS_TOKEN)
+    else:
+        resp = await client.login(PASSWORD, device_name="Cloto")
+        if not isinstance(resp, LoginResponse):
+            print(f"Matrix login failed: {resp}", file=sys.stderr)
+            return
+        print(
+            f"Logged in as {USER_ID}; set MATRIX_DEVICE_ID={resp.device_id} "
+            "to keep the encryption keys across restarts",
+            file=sys.stderr,
+        )
+    if client.should_upload_keys:
+        await client.keys_upload()
+
+    # Skip the backlog: register callbacks after the first sync
+    await client.sync(timeout=SYNC_TIMEOUT_MS, full_state=True)
+    client.add_event_callback(on_invite, InviteMemberEvent)
+    client.add_event_callback(on_message, RoomMessageText)
+    client.add_event_callback(on_undecryptable, MegolmEvent)
+    await client.sync_forever(timeout=SYNC_TIMEOUT_MS, full_state=True)
+
+
+def start_client() -> None:
+    global _sync_task
+    if _sync_task is not None:
+        return
+    if not (HOMESERVER and USER_ID and (PASSWORD or (ACCESS_TOKEN and DEVICE_ID))):
+        print(
+            "MATRIX_HOMESERVER, MATRIX_USER_ID and MATRIX_PASSWORD (or "
+            "MATRIX_ACCESS_TOKEN + MATRIX_DEVICE_ID) are required; adapter disabled",
+            file=sys.stderr,
+        )
+        return
+    _sync_task = asyncio.create_task(run_client())
+
+
+# ============================================================
+# Tool definitions
+# ============================================================
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    # The kernel lists tools on connect; bind the relay and go online then
+    relay.bind(server.request_context.session)
+    start_client()
+    return [
+        Tool(
+            name="matrix_send_message",
+            description="Send a message to a Matrix room the bot has joined.",
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "room_id": {"type": "string", "description": "Matrix room ID (!id:server)"},
+                    "text": {"type": "string", "description": "Message text"},
+                },
+                "required": ["room_id", "text"],
+            },
+        ),
+        Tool(
+            name="matrix_list_rooms",
+            description="List joined Matrix rooms, whether they are encrypted and their agent.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+    ]
+
+
+# ============================================================
+# Tool handlers
+# ============================================================
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    if client is None or not client.logged_in:
+        return error_result("Matrix client is not connected")
+
+    if name == "matrix_send_message":
+        room_id = arguments["room_id"]
+        if room_id not in client.rooms:
+            return error_result(f"Not a member of {room_id}")
+        try:
+            event_id = await send_text(room_id, arguments["text"])
+        except Exception as e:
+            return error_result(str(e))
+        return [TextContent(type="text", text=json.dumps({"ok": True, "event_id": event_id}))]
+
+    if name == "matrix_list_rooms":
+        rooms = [
+            {
+                "room_id": room.room_id,
+                "name": room.display_name,
+                "encrypted": room.encrypted,
+                "agent_id": agent_for(room.room_id),
+            }
+            for room in client.rooms.values()
+        ]
+        return [TextContent(type="text", text=json.dumps({"rooms": rooms}))]
+
+    return error_result(f"Unknown tool: {name}")
+
+
+# ============================================================
+# Entry point
+# ============================================================
+
+
+async def main():
+    async with stdio_server() as (read_stream, write_stream):
+        await server.run(read_stream, write_stream, server.create_initialization_options())
+
+
+if __name__ == "__main__":
+    print("Cloto MCP Matrix Server starting...", file=sys.stderr)
+    asyncio.run(main())
@@ -5,6 +5,7 @@ Supports dynamic model switching and local model discovery.
 
 Tools:
   - think:         Generate a text response using the active Ollama model
+  - think_with_tools: Same, but the model may answer with tool calls
   - list_models:   List locally installed Ollama models
   - switch_model:  Change the active model for this session
 """
@@ -12,6 +13,8 @@ Tools:
 import asyncio
 import json
 import os
+import re
+import time
 
 import httpx
 from mcp.server import Server
@@ -34,8 +37,30 @@ _active_model = MODEL_ID
 # ============================================================
 
 
-def build_system_prompt(agent: dict) -> str:
+def render_system_prompt(template: str, agent: dict, context: list[dict]) -> str:
+    """Fill in an agent's system_prompt template.
+
+    Same as common.llm_provider.render_system_prompt(). Unknown placeholders are left as-is.
+    """
+    values = {
+        "agent_name": agent.get("name", "Agent"),
+        "agent_id": agent.get("id", ""),
+        "description": agent.get("description", ""),
+        "date": time.strftime("%Y-%m-%d", time.gmtime()),
+        "memories": "\n".join(f"- {m.get('content', '')}" for m in context),
+    }
+    return re.sub(
+        r"\{([A-Za-z0-9_]+)\}",
+        lambda m: values.get(m.group(1), m.group(0)),
+        template,
+    )
+
+
+def build_system_prompt(agent: dict, context: list[dict] | None = None) -> str:
     """Build the system prompt for a Cloto agent."""
+    if agent.get("system_prompt"):
+        return render_system_prompt(agent["system_prompt"], agent, context or [])
+
     name = agent.get("name", "Agent")
     description = agent.get("description", "")
     metadata = agent.get("metadata", {})
@@ -66,7 +91,7 @@ def build_chat_messages(
     agent: dict, message: dict, context: list[dict]
 ) -> list[dict]:
     """Build the standard OpenAI-compatible messages array."""
-    messages = [{"role": "system", "content": build_system_prompt(agent)}]
+    messages = [{"role": "system", "content": build_system_prompt(agent, context)}]
 
     for msg in context:
         source = msg.get("source", {})
@@ -112,13 +137,76 @@ def parse_chat_content(response_data: dict) -> str:
 # ============================================================
 
 
-async def call_ollama_api(messages: list[dict]) -> dict:
+def to_ollama_messages(messages: list[dict]) -> list[dict]:
+    """Adapt OpenAI-format tool history for /api/chat.
+
+    The kernel sends assistant tool calls with JSON-string arguments and
+    tool results keyed by tool_call_id; Ollama expects argument objects.
+    """
+    adapted = []
+    for msg in messages:
+        if msg.get("tool_calls"):
+            calls = []
+            for tc in msg["tool_calls"]:
+                function = tc.get("function", {})
+                arguments = function.get("arguments", {})
+                if isinstance(arguments, str):
+                    try:
+                        arguments = json.loads(arguments)
+                    except json.JSONDecodeError:
+                        arguments = {}
+                calls.append(
+                    {"function": {"name": function.get("name", ""), "arguments": arguments}}
+                )
+            msg = {**msg, "content": msg.get("content") or "", "tool_calls": calls}
+        adapted.append(msg)
+    return adapted
+
+
+def parse_think_result(response_data: dict) -> dict:
+    """Parse an /api/chat response into a ThinkResult.
+
+    Returns either {"type": "final", "content": ...} or
+    {"type": "tool_calls", "assistant_content": ..., "calls": [...]}.
+    Ollama does not assign call IDs, so they are generated here.
+    """
+    if "error" in response_data:
+        error = response_data["error"]
+        msg = error.get("message", str(error)) if isinstance(error, dict) else str(error)
+        raise ValueError(f"Ollama API Error: {msg}")
+
+    message = response_data.get("message", {})
+    calls = []
+    for i, tc in enumerate(message.get("tool_calls") or []):
+        function = tc.get("function", {})
+        name = function.get("name", "")
+        arguments = function.get("arguments", {})
+        if isinstance(arguments, str):
+            try:
+                arguments = json.loads(arguments)
+            except json.JSONDecodeError:
+                arguments = {}
+        if name:
+            calls.append({"id": f"call_{i}", "name": name, "arguments": arguments})
+
+    if calls:
+        return {
+            "type": "tool_calls",
+            "assistant_content": message.get("content") or None,
+            "calls": calls,
+        }
+    return {"type": "final", "content": message.get("content") or ""}
+
+
+async def call_ollama_api(messages: list[dict], tools: list[dict] | None = None) -> dict:
     """Send a request to the Ollama native chat API (/api/chat)."""
     body: dict = {
         "model": _active_model,
         "messages": messages,
         "stream": False,
     }
+    if tools:
+        body["tools"] = tools
 
     async with httpx.AsyncClient(timeout=REQUEST_TIMEOUT) as client:
         response = await client.post(
@@ -180,6 +268,43 @@ async def list_tools() -> list[Tool]:
                 "required": ["agent", "message", "context"],
             },
         ),
+        Tool(
+            name="think_with_tools",
+            description=(
+                "Generate a response that may include tool calls. "
+                "Returns either final text or a list of tool calls to execute. "
+                "Requires a model with tool support (e.g. llama3.1, qwen2.5)."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "agent": {
+                        "type": "object",
+                        "description": "Agent metadata (name, description, metadata)",
+                    },
+                    "message": {
+                        "type": "object",
+                        "description": "User message with 'content' field",
+                    },
+                    "context": {
+                        "type": "array",
+                        "description": "Conversation context messages",
+                        "items": {"type": "object"},
+                    },
+                    "tools": {
+                        "type": "array",
+                        "description": "Available tool schemas (OpenAI function format)",
+                        "items": {"type": "object"},
+                    },
+                    "tool_history": {
+                        "type": "array",
+                        "description": "Previous tool calls and results in this turn",
+                        "items": {"type": "object"},
+                    },
+                },
+                "required": ["agent", "message", "context", "tools"],
+            },
+        ),
         Tool(
             name="list_models",
             description=(
@@ -214,6 +339,8 @@ async def list_tools() -> list[Tool]:
 async def call_tool(name: str, arguments: dict) -> list[TextContent]:
     if name == "think":
         return await handle_think(arguments)
+    elif name == "think_with_tools":
+        return await handle_think_with_tools(arguments)
     elif name == "list_models":
         return await handle_list_models()
     elif name == "switch_model":
@@ -261,6 +388,39 @@ async def handle_think(arguments: dict) -> list[TextContent]:
         ]
 
 
+async def handle_think_with_tools(arguments: dict) -> list[TextContent]:
+    """Handle 'think_with_tools' tool: may return tool calls or final text."""
+    try:
+        agent = arguments.get("agent", {})
+        message = arguments.get("message", {})
+        context = arguments.get("context", [])
+        tools = arguments.get("tools", [])
+        tool_history = arguments.get("tool_history", [])
+
+        messages = build_chat_messages(agent, message, context)
+        messages.extend(tool_history)
+        response_data = await call_ollama_api(to_ollama_messages(messages), tools)
+        result = parse_think_result(response_data)
+
+        return [TextContent(type="text", text=json.dumps(result))]
+    except httpx.ConnectError:
+        return [
+            TextContent(
+                type="text",
+                text=json.dumps({
+                    "error": f"Cannot connect to Ollama at {BASE_URL}. "
+                             f"Is Ollama running? Start it with: ollama serve"
+                }),
+            )
+        ]
+    except Exception as e:
+        return [
+            TextContent(
+                type="text", text=json.dumps({"error": str(e)})
+            )
+        ]
+
+
 async def handle_list_models() -> list[TextContent]:
     """Handle 'list_models' tool: list locally installed models."""
     try:
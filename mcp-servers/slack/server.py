@@ -0,0 +1,253 @@
+"""
+Cloto MCP Server: Slack Adapter
+Connects a Slack workspace to Cloto agents over Socket Mode (no public URL).
+
+Mentions of the bot in channels and direct messages are relayed to the agent
+bound to the channel (SLACK_CHANNEL_AGENTS, else SLACK_DEFAULT_AGENT, else
+the kernel's default agent) and answered in the message's thread. Each Slack
+thread is one conversation for the agent.
+
+The `/cloto` slash command exposes admin actions (pending permissions,
+approve/deny, agent power) to users listed in SLACK_ADMIN_USERS.
+SLACK_ALLOWED_USERS restricts who may talk to agents at all.
+Requires the ChannelRelay permission.
+"""
+
+import asyncio
+import json
+import os
+import re
+import sys
+
+# Resolve parent directory for common module import.
+# Handle Windows UNC paths (\\?\...) that Python may receive from the kernel.
+_script_dir = os.path.dirname(os.path.abspath(__file__))
+sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
+
+from mcp.server import Server
+from mcp.server.stdio import stdio_server
+from mcp.types import TextContent, Tool
+from slack_bolt.adapter.socket_mode.async_handler import AsyncSocketModeHandler
+from slack_bolt.async_app import AsyncApp
+
+from common.relay import ChannelRelay, KernelAdmin, parse_bindings, parse_list
+
+BOT_TOKEN = os.environ.get("SLACK_BOT_TOKEN", "")
+APP_TOKEN = os.environ.get("SLACK_APP_TOKEN", "")
+DEFAULT_AGENT = os.environ.get("SLACK_DEFAULT_AGENT", "") or None
+ALLOWED_USERS = parse_list(os.environ.get("SLACK_ALLOWED_USERS", ""))
+ADMIN_USERS = parse_list(os.environ.get("SLACK_ADMIN_USERS", ""))
+
+MENTION_RE = re.compile(r"<@[A-Z0-9]+>")
+
+# ============================================================
+# Server setup
+# ============================================================
+
+server = Server("adapter.slack")
+relay = ChannelRelay()
+admin = KernelAdmin()
+
+# Slack channel ID -> agent ID. `/cloto bind` updates it until restart.
+bindings = parse_bindings(os.environ.get("SLACK_CHANNEL_AGENTS", ""))
+
+app = AsyncApp(token=BOT_TOKEN) if BOT_TOKEN else None
+_socket_task: asyncio.Task | None = None
+
+
+def agent_for(channel: str) -> str | None:
+    return bindings.get(channel, DEFAULT_AGENT)
+
+
+def is_allowed(user: str) -> bool:
+    return not ALLOWED_USERS or user in ALLOWED_USERS
+
+
+def error_result(message: str) -> list[TextContent]:
+    return [TextContent(type="text", text=json.dumps({"error": message}))]
+
+
+# ============================================================
+# Slack events
+# ============================================================
+
+
+async def relay_message(event: dict, say) -> None:
+    user = event.get("user")
+    if not user or event.get("bot_id") or event.get("subtype"):
+        return
+    if not is_allowed(user):
+        return
+    text = MENTION_RE.sub("", event.get("text", "")).strip()
+    if not text:
+        return
+
+    channel = event["channel"]
+    thread_ts = event.get("thread_ts") or event["ts"]
+    try:
+        reply = await relay.ask(
+            text,
+            agent_id=agent_for(channel),
+            user_id=user,
+            conversation=f"{channel}:{thread_ts}",
+        )
+    except Exception as e:
+        print(f"Relay failed: {e}", file=sys.stderr)
+        reply = f":warning: {e}"
+    if reply:
+        await say(text=reply, thread_ts=thread_ts)
+
+
+if app is not None:
+
+    @app.event("app_mention")
+    async def on_mention(event, say):
+        await relay_message(event, say)
+
+    @app.event("message")
+    async def on_message(event, say):
+        # Channel messages arrive as app_mention; only DMs are handled here
+        if event.get("channel_type") == "im":
+            await relay_message(event, say)
+
+    @app.command("/cloto")
+    async def on_command(ack, command, respond):
+        await ack()
+        if command["user_id"] not in ADMIN_USERS:
+            await respond("You are not allowed to run Cloto admin commands.")
+            return
+        try:
+            await respond(await run_admin_command(command["channel_id"], command.get("text", "")))
+        except Exception as e:
+            await respond(f":warning: {e}")
+
+
+# ============================================================
+# Admin commands
+# ============================================================
+
+HELP = (
+    "`/cloto status` - agent bound to this channel\n"
+    "`/cloto pending` - pending permission requests\n"
+    "`/cloto approve <request_id>` / `/cloto deny <request_id>`\n"
+    "`/cloto agent <agent_id> on|off` - power an agent on or off\n"
+    "`/cloto bind <agent_id>` - bind this channel to an agent (until restart)"
+)
+
+
+async def run_admin_command(channel: str, text: str) -> str:
+    args = text.split()
+    cmd = args[0].lower() if args else "help"
+
+    if cmd == "status":
+        return f"This channel talks to `{agent_for(channel) or 'default agent'}`."
+    if cmd == "pending":
+        pending = await admin.pending_permissions()
+        if not pending:
+            return "No pending permission requests."
+        return "\n".join(
+            f"`{p['request_id']}` {p['plugin_id']} requests {p['permission_type']}: "
+            f"{p['justification']}"
+            for p in pending
+        )
+    if cmd in ("approve", "deny") and len(args) == 2:
+        await admin.decide_permission(args[1], cmd == "approve")
+        return f"Request `{args[1]}` {'approved' if cmd == 'approve' else 'denied'}."
+    if cmd == "agent" and len(args) == 3 and args[2] in ("on", "off"):
+        await admin.set_agent_power(args[1], args[2] == "on")
+        return f"Agent `{args[1]}` powered {args[2]}."
+    if cmd == "bind" and len(args) == 2:
+        bindings[channel] = args[1]
+        return f"This channel now talks to `{args[1]}`."
+    return HELP
+
+
+async def start_socket_mode() -> None:
+    global _socket_task
+    if _socket_task is not None or app is None:
+        return
+    if not APP_TOKEN:
+        print("SLACK_APP_TOKEN is not set; Socket Mode disabled", file=sys.stderr)
+        return
+    handler = AsyncSocketModeHandler(app, APP_TOKEN)
+    _socket_task = asyncio.create_task(handler.start_async())
+
+
+# ============================================================
+# Tool definitions
+# ============================================================
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    # The kernel lists tools on connect; bind the relay and go online then
+    relay.bind(server.request_context.session)
+    await start_socket_mode()
+    return [
+        Tool(
+            name="slack_post_message",
+            description="Post a message to a Slack channel, optionally as a thread reply.",
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "channel": {"type": "string", "description": "Slack channel ID"},
+                    "text": {"type": "string", "description": "Message text (mrkdwn)"},
+                    "thread_ts": {
+                        "type": "string",
+                        "description": "Timestamp of the parent message to reply in its thread",
+                    },
+                },
+                "required": ["channel", "text"],
+            },
+        ),
+        Tool(
+            name="slack_list_bindings",
+            description="List which Slack channels are bound to which agents.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+    ]
+
+
+# ============================================================
+# Tool handlers
+# ============================================================
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    if name == "slack_post_message":
+        if app is None:
+            return error_result("SLACK_BOT_TOKEN is not set")
+        try:
+            kwargs = {"channel": arguments["channel"], "text": arguments["text"]}
+            if arguments.get("thread_ts"):
+                kwargs["thread_ts"] = arguments["thread_ts"]
+            resp = await app.client.chat_postMessage(**kwargs)
+        except Exception as e:
+            return error_result(str(e))
+        return [TextContent(type="text", text=json.dumps({"ok": True, "ts": resp.get("ts")}))]
+
+    if name == "slack_list_bindings":
+        return [
+            TextContent(
+                type="text",
+                text=json.dumps({"bindings": bindings, "default_agent": DEFAULT_AGENT}),
+            )
+        ]
+
+    return error_result(f"Unknown tool: {name}")
+
+
+# ============================================================
+# Entry point
+# ============================================================
+
+
+async def main():
+    async with stdio_server() as (read_stream, write_stream):
+        await server.run(read_stream, write_stream, server.create_initialization_options())
+
+
+if __name__ == "__main__":
+    print("Cloto MCP Slack Server starting...", file=sys.stderr)
+    asyncio.run(main())
@@ -0,0 +1,371 @@
+"""
+Cloto MCP Server: Telegram Adapter
+Connects a Telegram bot to Cloto agents via Bot API long polling.
+
+Private messages, and group messages that mention the bot or reply to it,
+are relayed to the agent bound to the chat (TELEGRAM_CHAT_AGENTS, else
+TELEGRAM_DEFAULT_AGENT, else the kernel's default agent). Voice notes are
+downloaded and passed through as `audio_ref` message metadata for
+speech-to-text.
+
+Pending permission requests are posted to TELEGRAM_ADMIN_CHATS with
+Approve / Deny inline buttons; only TELEGRAM_ADMIN_USERS may press them.
+Requires the ChannelRelay permission.
+"""
+
+import asyncio
+import json
+import os
+import sys
+import tempfile
+
+# Resolve parent directory for common module import.
+# Handle Windows UNC paths (\\?\...) that Python may receive from the kernel.
+_script_dir = os.path.dirname(os.path.abspath(__file__))
+sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
+
+import httpx
+from mcp.server import Server
+from mcp.server.stdio import stdio_server
+from mcp.types import TextContent, Tool
+
+from common.relay import ChannelRelay, KernelAdmin, parse_bindings, parse_list
+
+BOT_TOKEN = os.environ.get("TELEGRAM_BOT_TOKEN", "")
+DEFAULT_AGENT = os.environ.get("TELEGRAM_DEFAULT_AGENT", "") or None
+ALLOWED_USERS = parse_list(os.environ.get("TELEGRAM_ALLOWED_USERS", ""))
+ADMIN_USERS = parse_list(os.environ.get("TELEGRAM_ADMIN_USERS", ""))
+ADMIN_CHATS = parse_list(os.environ.get("TELEGRAM_ADMIN_CHATS", ""))
+VOICE_DIR = os.environ.get(
+    "TELEGRAM_VOICE_DIR", os.path.join(tempfile.gettempdir(), "cloto-telegram")
+)
+APPROVAL_POLL_SECS = float(os.environ.get("TELEGRAM_APPROVAL_POLL_SECS", "10"))
+
+API_BASE = f"https://api.telegram.org/bot{BOT_TOKEN}"
+FILE_BASE = f"https://api.telegram.org/file/bot{BOT_TOKEN}"
+POLL_TIMEOUT_SECS = 30
+# Telegram rejects messages longer than this
+MAX_MESSAGE_LEN = 4096
+
+# ============================================================
+# Server setup
+# ============================================================
+
+server = Server("adapter.telegram")
+relay = ChannelRelay()
+admin = KernelAdmin()
+
+# Telegram chat ID -> agent ID. `/bind` updates it until restart.
+bindings = parse_bindings(os.environ.get("TELEGRAM_CHAT_AGENTS", ""))
+
+http = httpx.AsyncClient(timeout=POLL_TIMEOUT_SECS + 10)
+_tasks: list[asyncio.Task] = []
+_bot_username = ""
+
+
+def agent_for(chat_id: str) -> str | None:
+    return bindings.get(chat_id, DEFAULT_AGENT)
+
+
+def is_allowed(user_id: str) -> bool:
+    return not ALLOWED_USERS or user_id in ALLOWED_USERS
+
+
+def error_result(message: str) -> list[TextContent]:
+    return [TextContent(type="text", text=json.dumps({"error": message}))]
+
+
+async def bot_api(method: str, **params) -> dict:
+    resp = await http.post(f"{API_BASE}/{method}", json=params)
+    body = resp.json()
+    if not body.get("ok"):
+        raise RuntimeError(body.get("description", f"{method} failed"))
+    return body["result"]
+
+
+async def send_message(chat_id: str, text: str, **params) -> dict:
+    return await bot_api("sendMessage", chat_id=chat_id, text=text[:MAX_MESSAGE_LEN], **params)
+
+
+# ============================================================
+# Inbound messages
+# ============================================================
+
+
+def addressed_to_bot(message: dict) -> bool:
+    if message["chat"]["type"] == "private":
+        return True
+    text = message.get("text") or message.get("caption") or ""
+    if _bot_username and f"@{_bot_username}" in text:
+        return True
+    reply_to = message.get("reply_to_message") or {}
+    return (reply_to.get("from") or {}).get("username") == _bot_username
+
+
+async def download_voice(voice: dict) -> str:
+    """Save a voice note to VOICE_DIR and return its path."""
+    info = await bot_api("getFile", file_id=voice["file_id"])
+    resp = await http.get(f"{FILE_BASE}/{info['file_path']}")
+    resp.raise_for_status()
+    os.makedirs(VOICE_DIR, exist_ok=True)
+    path = os.path.join(VOICE_DIR, f"{voice['file_unique_id']}.ogg")
+    with open(path, "wb") as f:
+        f.write(resp.content)
+    return path
+
+
+async def run_command(chat_id: str, user_id: str, text: str) -> str | None:
+    """Handle /status and /bind. Returns None for non-commands."""
+    args = text.split()
+    cmd = args[0].split("@")[0].lower() if args else ""
+    if cmd == "/status":
+        return f"This chat talks to {agent_for(chat_id) or 'the default agent'}."
+    if cmd == "/bind":
+        if user_id not in ADMIN_USERS:
+            return "You are not allowed to change the agent binding."
+        if len(args) != 2:
+            return "Usage: /bind <agent_id>"
+        bindings[chat_id] = args[1]
+        return f"This chat now talks to {args[1]}."
+    if cmd == "/start":
+        return "Hi! Send me a message and I'll pass it to your agent."
+    return None
+
+
+async def handle_message(message: dict) -> None:
+    sender = message.get("from") or {}
+    if sender.get("is_bot") or not addressed_to_bot(message):
+        return
+    user_id = str(sender.get("id", ""))
+    if not is_allowed(user_id):
+        return
+    chat_id = str(message["chat"]["id"])
+    text = (message.get("text") or message.get("caption") or "").strip()
+    if _bot_username:
+        text = text.replace(f"@{_bot_username}", "").strip()
+
+    if text.startswith("/"):
+        answer = await run_command(chat_id, user_id, text)
+        if answer is not None:
+            await send_message(chat_id, answer)
+            return
+
+    audio_ref = audio_mime = None
+    voice = message.get("voice")
+    if voice:
+        audio_ref = await download_voice(voice)
+        audio_mime = voice.get("mime_type", "audio/ogg")
+        text = text or "[Voice message]"
+    if not text:
+        return
+
+    try:
+        reply = await relay.ask(
+            text,
+            agent_id=agent_for(chat_id),
+            user_id=user_id,
+            user_name=sender.get("username") or sender.get("first_name"),
+            conversation=chat_id,
+            audio_ref=audio_ref,
+            audio_mime=audio_mime,
+        )
+    except Exception as e:
+        print(f"Relay failed: {e}", file=sys.stderr)
+        reply = f"⚠️ {e}"
+    if reply:
+        await send_message(chat_id, reply, reply_to_message_id=message["message_id"])
+
+
+# ============================================================
+# Permission approvals
+# ============================================================
+
+APPROVE, DENY = "approve", "deny"
+
+
+async def handle_callback(query: dict) -> None:
+    user_id = str(query["from"]["id"])
+    action, _, request_id = (query.get("data") or "").partition(":")
+    if action not in (APPROVE, DENY) or not request_id:
+        return
+    if user_id not in ADMIN_USERS:
+        await bot_api(
+            "answerCallbackQuery", callback_query_id=query["id"], text="Not allowed"
+        )
+        return
+    try:
+        await admin.decide_permission(request_id, action == APPROVE)
+        result = "✅ Approved" if action == APPROVE else "❌ Denied"
+    except Exception as e:
+        result = f"⚠️ {e}"
+    await bot_api("answerCallbackQuery", callback_query_id=query["id"], text=result)
+    message = query.get("message")
+    if message:
+        await bot_api(
+            "editMessageText",
+            chat_id=message["chat"]["id"],
+            message_id=message["message_id"],
+            text=f"{message.get('text', '')}\n\n{result} by {query['from'].get('username', user_id)}",
+        )
+
+
+async def watch_permissions() -> None:
+    """Post each new pending permission request to the admin chats."""
+    announced: set[str] = set()
+    while True:
+        try:
+            for request in await admin.pending_permissions():
+                request_id = request["request_id"]
+                if request_id in announced:
+                    continue
+                announced.add(request_id)
+                text = (
+                    f"🔐 {request['plugin_id']} requests {request['permission_type']}\n"
+                    f"{request['justification']}"
+                )
+                # callback_data is limited to 64 bytes
+                data = (f"{APPROVE}:{request_id}", f"{DENY}:{request_id}")
+                if any(len(d.encode()) > 64 for d in data):
+                    text += f"\n\nRequest ID too long for buttons: {request_id}"
+                    markup = None
+                else:
+                    markup = {
+                        "inline_keyboard": [[
+                            {"text": "Approve", "callback_data": data[0]},
+                            {"text": "Deny", "callback_data": data[1]},
+                        ]]
+                    }
+                for chat_id in ADMIN_CHATS:
+                    params = {"reply_markup": markup} if markup else {}
+                    await send_message(chat_id, text, **params)
+        except Exception as e:
+            print(f"Permission watch failed: {e}", file=sys.stderr)
+        await asyncio.sleep(APPROVAL_POLL_SECS)
+
+
+# ============================================================
+# Polling loop
+# ============================================================
+
+
+async def poll_updates() -> None:
+    global _bot_username
+    _bot_username = (await bot_api("getMe")).get("username", "")
+    offset = 0
+    while True:
+        try:
+            updates = await bot_api(
+                "getUpdates",
+                offset=offset,
+                timeout=POLL_TIMEOUT_SECS,
+                allowed_updates=["message", "callback_query"],
+            )
+        except Exception as e:
+            print(f"getUpdates failed: {e}", file=sys.stderr)
+            await asyncio.sleep(5)
+            continue
+        for update in updates:
+            offset = update["update_id"] + 1
+            if "message" in update:
+                handler = handle_message(update["message"])
+            elif "callback_query" in update:
+                handler = handle_callback(update["callback_query"])
+            else:
+                continue
+            # Agent replies can take a while; don't block other chats
+            asyncio.create_task(run_handler(handler))
+
+
+async def run_handler(handler) -> None:
+    try:
+        await handler
+    except Exception as e:
+        print(f"Update handling failed: {e}", file=sys.stderr)
+
+
+def start_bot() -> None:
+    if _tasks:
+        return
+    if not BOT_TOKEN:
+        print("TELEGRAM_BOT_TOKEN is not set; bot disabled", file=sys.stderr)
+        return
+    _tasks.append(asyncio.create_task(poll_updates()))
+    if ADMIN_CHATS:
+        _tasks.append(asyncio.create_task(watch_permissions()))
+
+
+# ============================================================
+# Tool definitions
+# ============================================================
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    # The kernel lists tools on connect; bind the relay and go online then
+    relay.bind(server.request_context.session)
+    start_bot()
+    return [
+        Tool(
+            name="telegram_send_message",
+            description="Send a message to a Telegram chat.",
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "chat_id": {"type": "string", "description": "Telegram chat ID"},
+                    "text": {"type": "string", "description": "Message text"},
+                },
+                "required": ["chat_id", "text"],
+            },
+        ),
+        Tool(
+            name="telegram_list_bindings",
+            description="List which Telegram chats are bound to which agents.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+    ]
+
+
+# ============================================================
+# Tool handlers
+# ============================================================
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    if name == "telegram_send_message":
+        if not BOT_TOKEN:
+            return error_result("TELEGRAM_BOT_TOKEN is not set")
+        try:
+            sent = await send_message(str(arguments["chat_id"]), arguments["text"])
+        except Exception as e:
+            return error_result(str(e))
+        return [
+            TextContent(
+                type="text", text=json.dumps({"ok": True, "message_id": sent["message_id"]})
+            )
+        ]
+
+    if name == "telegram_list_bindings":
+        return [
+            TextContent(
+                type="text",
+                text=json.dumps({"bindings": bindings, "default_agent": DEFAULT_AGENT}),
+            )
+        ]
+
+    return error_result(f"Unknown tool: {name}")
+
+
+# ============================================================
+# Entry point
+# ============================================================
+
+
+async def main():
+    async with stdio_server() as (read_stream, write_stream):
+        await server.run(read_stream, write_stream, server.create_initialization_options())
+
+
+if __name__ == "__main__":
+    print("Cloto MCP Telegram Server starting...", file=sys.stderr)
+    asyncio.run(main())
@@ -6,9 +6,14 @@ Ported from plugins/terminal/src/lib.rs + sandbox.rs
 
 import asyncio
 import json
+import ntpath
 import os
 import shlex
+import shutil
+import sys
+import tempfile
 import unicodedata
+import uuid
 
 from mcp.server import Server
 from mcp.server.stdio import stdio_server
@@ -18,14 +23,57 @@ from mcp.types import TextContent, Tool
 # Configuration (from environment variables)
 # ============================================================
 
-WORKING_DIR = os.environ.get("CLOTO_SANDBOX_DIR", "/tmp/cloto-sandbox")
+IS_WINDOWS = sys.platform == "win32"
+
+WORKING_DIR = os.environ.get(
+    "CLOTO_SANDBOX_DIR",
+    os.path.join(tempfile.gettempdir(), "cloto-sandbox") if IS_WINDOWS else "/tmp/cloto-sandbox",
+)
 MAX_OUTPUT_BYTES = int(os.environ.get("CLOTO_MAX_OUTPUT_BYTES", "65536"))
+# Hard cap on captured output per stream; the command is killed beyond it
+MAX_CAPTURE_BYTES = int(os.environ.get("CLOTO_MAX_CAPTURE_BYTES", str(16 * 1024 * 1024)))
+
+# Resource limits applied to every command; 0 disables a limit.
+# NPROC is per-user on POSIX (counts all of the user's processes), so it is
+# off by default.
+RLIMIT_CPU_SECS = int(os.environ.get("CLOTO_RLIMIT_CPU_SECS", "120"))
+RLIMIT_MEMORY_MB = int(os.environ.get("CLOTO_RLIMIT_MEMORY_MB", "4096"))
+RLIMIT_NPROC = int(os.environ.get("CLOTO_RLIMIT_NPROC", "0"))
+
+# Disk quota per agent workspace (WORKING_DIR/agents/<agent_id>); 0 disables
+AGENT_QUOTA_BYTES = int(os.environ.get("CLOTO_AGENT_QUOTA_BYTES", str(256 * 1024 * 1024)))
 ALLOWED_COMMANDS_STR = os.environ.get("CLOTO_ALLOWED_COMMANDS", "")
 
 ALLOWED_COMMANDS: list[str] | None = None
 if ALLOWED_COMMANDS_STR:
     ALLOWED_COMMANDS = [c.strip() for c in ALLOWED_COMMANDS_STR.split(",") if c.strip()]
 
+# Environment passed to commands: only variables named here are inherited from
+# the server's environment, and only these may be overridden per command.
+DEFAULT_ENV_ALLOWLIST = (
+    "PATH,HOME,LANG,LC_ALL,TZ,"
+    "SYSTEMROOT,COMSPEC,PATHEXT,TEMP,TMP"  # required by most Windows programs
+)
+ENV_ALLOWLIST = frozenset(
+    v.strip()
+    for v in os.environ.get("CLOTO_ENV_ALLOWLIST", DEFAULT_ENV_ALLOWLIST).split(",")
+    if v.strip()
+)
+MAX_ENV_OVERRIDES = 32
+
+# Execution backend: "host" (subprocess in the agent's workspace) or "container"
+# (disposable Docker/Podman container with only the agent's workspace mounted)
+EXECUTION_BACKEND = os.environ.get("CLOTO_TERMINAL_BACKEND", "host").strip().lower()
+CONTAINER_RUNTIME = os.environ.get("CLOTO_CONTAINER_RUNTIME", "").strip()  # docker | podman (auto-detected if empty)
+CONTAINER_IMAGE = os.environ.get("CLOTO_CONTAINER_IMAGE", "debian:stable-slim")
+CONTAINER_NETWORK = os.environ.get("CLOTO_CONTAINER_NETWORK", "none")
+CONTAINER_WORKDIR = "/workspace"
+
+# Interpreter for host commands on Windows: "cmd" (cmd.exe /C), "powershell"
+# (powershell.exe -Command) or "none" (run the program directly). POSIX hosts
+# never go through a shell.
+WINDOWS_SHELL = os.environ.get("CLOTO_WINDOWS_SHELL", "cmd").strip().lower()
+
 # ============================================================
 # Sandbox: Command Validation (ported from sandbox.rs)
 # ============================================================
@@ -46,10 +94,87 @@ BLOCKED_METACHAR_PATTERNS = [
     "$(", "`", "|", ";", "&&", "||", ">", "<",
 ]
 
+WINDOWS_BLOCKED_PATTERNS = [
+    "diskpart", "bcdedit", "vssadmin", "wmic", "cipher /w",
+    "reg delete", "reg add", "takeown", "icacls", "runas ",
+    "format-volume", "clear-disk", "stop-computer", "restart-computer",
+    "invoke-expression", "iex ", "start-process", "set-executionpolicy",
+    "-encodedcommand",
+]
+
+# Characters with meaning to the selected Windows interpreter: command chaining
+# and variable expansion in cmd.exe, call operator/variables/script blocks in
+# PowerShell.
+WINDOWS_BLOCKED_METACHARS = {
+    "cmd": ["&", "^", "%", "!"],
+    "powershell": ["&", "$", "{", "@("],
+    "none": [],
+}
+
+# Programs that would start a nested interpreter and bypass the checks above
+WINDOWS_BLOCKED_PROGRAMS = frozenset({
+    "cmd", "powershell", "pwsh", "wscript", "cscript", "mshta",
+    "rundll32", "regsvr32", "format", "bash", "sh", "wsl",
+})
+
+# Suffixes stripped from the program name before allowlist/blocklist matching
+WINDOWS_EXECUTABLE_SUFFIXES = (".exe", ".com", ".bat", ".cmd", ".ps1")
+
+
+def program_name(command: str, windows: bool = IS_WINDOWS) -> str:
+    """The command's program as matched against the allowlist.
+
+    On Windows the directory and executable suffix are dropped and the name is
+    case-folded, so `C:\\Windows\\System32\\WHERE.EXE` matches `where`.
+    """
+    first = command.split()[0] if command.split() else ""
+    if not windows:
+        return first
+    try:
+        # Keeps a quoted path with spaces ("C:\\Program Files\\...") together
+        first = split_windows_args(command)[0]
+    except (ValueError, IndexError):
+        pass
+    name = ntpath.basename(first.strip('"')).lower()
+    for suffix in WINDOWS_EXECUTABLE_SUFFIXES:
+        if name.endswith(suffix):
+            return name[: -len(suffix)]
+    return name
+
+
+def validate_windows_command(command: str, shell: str) -> None:
+    """Windows-only rules for the interpreter the command will run under."""
+    lower = command.lower()
+    for meta in WINDOWS_BLOCKED_METACHARS.get(shell, []):
+        if meta in lower:
+            raise ValueError(f"Command contains blocked {shell} metacharacter: '{meta}'")
+    for pattern in WINDOWS_BLOCKED_PATTERNS:
+        if pattern in lower:
+            raise ValueError(f"Command contains blocked pattern: '{pattern}'")
+
+    program = program_name(command, windows=True)
+    if program in WINDOWS_BLOCKED_PROGRAMS:
+        raise ValueError(f"Command '{program}' is not allowed")
 
-def validate_command(command: str) -> None:
+    # Block recursive deletes (del /s, rd /s, Remove-Item -Recurse)
+    tokens = lower.split()
+    # Switches may be combined (`/q/s`); a path such as `src/sub` never starts with "/"
+    switches = {part for t in tokens[1:] if t.startswith("/") for part in t.split("/")}
+    if program in ("del", "erase", "rd", "rmdir") and "s" in switches:
+        raise ValueError("Command contains recursive delete flag (/s)")
+    if program in ("remove-item", "ri", "rm", "rmdir", "del", "erase") and any(
+        t.startswith("-r") for t in tokens[1:]
+    ):
+        raise ValueError("Command contains recursive delete flag (-Recurse)")
+
+
+def validate_command(command: str, windows_shell: str | None = None) -> None:
     """Validate a command against security rules. Raises ValueError on failure.
 
+    `windows_shell` is the interpreter the command will run under on a Windows
+    host; it adds that interpreter's metacharacters and destructive commands to
+    the checks.
+
     NOTE: The caller MUST pass an already-NFKC-normalized string so that
     the same string that is validated is also the one that gets executed.
     """
@@ -89,12 +214,17 @@ def validate_command(command: str) -> None:
         if has_recursive and has_force:
             raise ValueError("Command contains dangerous rm flags (-r and -f)")
 
-    # If an allowlist is configured, check the first word
+    windows = windows_shell is not None
+    if windows:
+        validate_windows_command(command, windows_shell)
+
+    # If an allowlist is configured, check the program name
     if ALLOWED_COMMANDS is not None:
-        first_word = command.split()[0] if command.split() else ""
-        if first_word not in ALLOWED_COMMANDS:
+        program = program_name(command, windows)
+        allowed = {program_name(c, windows) for c in ALLOWED_COMMANDS}
+        if program not in allowed:
             raise ValueError(
-                f"Command '{first_word}' is not in the allowlist. "
+                f"Command '{program}' is not in the allowlist. "
                 f"Allowed: {ALLOWED_COMMANDS}"
             )
 
@@ -108,6 +238,323 @@ def safe_truncate(s: str, max_bytes: int) -> str:
     return truncated.decode("utf-8", errors="ignore")
 
 
+# ============================================================
+# Per-Agent Workspaces
+# ============================================================
+
+# Workspace used when a call carries no agent_id (e.g. direct API calls)
+SHARED_WORKSPACE = "_shared"
+
+
+def agent_workspace(agent_id: str | None) -> str:
+    """Return (and create) the agent's private working directory."""
+    name = agent_id or SHARED_WORKSPACE
+    if (
+        len(name) > 128
+        or name in (".", "..")
+        or not all(c.isascii() and (c.isalnum() or c in "._-") for c in name)
+    ):
+        raise ValueError(f"Invalid agent_id: '{agent_id}'")
+    path = os.path.join(WORKING_DIR, "agents", name)
+    os.makedirs(path, exist_ok=True)
+    return path
+
+
+def workspace_usage(path: str) -> int:
+    """Total size in bytes of regular files under `path` (symlinks not followed)."""
+    total = 0
+    for root, _dirs, files in os.walk(path):
+        for f in files:
+            try:
+                st = os.lstat(os.path.join(root, f))
+            except OSError:
+                continue
+            total += st.st_size
+    return total
+
+
+EXTENDED_PATH_PREFIX = "\\\\?\\"
+EXTENDED_UNC_PREFIX = "\\\\?\\UNC\\"
+
+
+def canonical_path(path: str) -> str:
+    r"""Symlink-resolved, case-normalized form of `path` for containment checks.
+
+    On Windows, realpath may return extended-length forms (\\?\C:\... and
+    \\?\UNC\server\share\...); they are reduced to plain drive-letter and
+    UNC paths so both spellings of the same location compare equal.
+    """
+    resolved = os.path.realpath(path)
+    if resolved.startswith(EXTENDED_UNC_PREFIX):
+        resolved = "\\\\" + resolved[len(EXTENDED_UNC_PREFIX):]
+    elif resolved.startswith(EXTENDED_PATH_PREFIX):
+        resolved = resolved[len(EXTENDED_PATH_PREFIX):]
+    return os.path.normcase(resolved)
+
+
+def is_within(base: str, target: str) -> bool:
+    """Whether canonical `target` is `base` or lies below it."""
+    try:
+        return os.path.commonpath([base, target]) == base
+    except ValueError:
+        # Different drives, or a drive-letter path against a UNC share
+        return False
+
+
+def resolve_in_workspace(workspace: str, relative: str) -> str:
+    """Resolve `relative` inside `workspace`. Raises ValueError on escape attempts."""
+    # Reject absolute paths, drive letters (including drive-relative `C:foo`)
+    # and UNC shares up front rather than relying on join() semantics
+    if os.path.isabs(relative) or os.path.splitdrive(relative)[0]:
+        raise ValueError(f"Path escapes the workspace: '{relative}'")
+    target = os.path.realpath(os.path.join(workspace, relative))
+    if not is_within(canonical_path(workspace), canonical_path(target)):
+        raise ValueError(f"Path escapes the workspace: '{relative}'")
+    return target
+
+
+# ============================================================
+# Environment
+# ============================================================
+
+# Host-specific variables that are meaningless inside a container image
+HOST_ONLY_ENV = frozenset({"PATH", "HOME", "SYSTEMROOT", "COMSPEC", "PATHEXT", "TEMP", "TMP"})
+
+
+def validate_env_overrides(overrides) -> dict[str, str]:
+    """Validate per-command env overrides. Raises ValueError on failure."""
+    if overrides is None:
+        return {}
+    if not isinstance(overrides, dict):
+        raise ValueError("'env' must be an object of NAME: value strings")
+    if len(overrides) > MAX_ENV_OVERRIDES:
+        raise ValueError(f"Too many env overrides (max {MAX_ENV_OVERRIDES})")
+    for name, value in overrides.items():
+        if name not in ENV_ALLOWLIST:
+            raise ValueError(
+                f"Environment variable '{name}' is not in the allowlist. "
+                f"Allowed: {sorted(ENV_ALLOWLIST)}"
+            )
+        if not isinstance(value, str) or "\0" in value:
+            raise ValueError(f"Environment variable '{name}' must be a string without NUL bytes")
+    return dict(overrides)
+
+
+def build_env(overrides: dict[str, str]) -> dict[str, str]:
+    """Allowlisted variables from the server's environment, plus overrides."""
+    env = {name: value for name, value in os.environ.items() if name in ENV_ALLOWLIST}
+    env.update(overrides)
+    return env
+
+
+# ============================================================
+# Resource Limits
+# ============================================================
+
+
+def apply_posix_rlimits() -> None:
+    """preexec_fn for host commands: runs in the child before exec."""
+    import resource
+
+    if RLIMIT_CPU_SECS > 0:
+        resource.setrlimit(resource.RLIMIT_CPU, (RLIMIT_CPU_SECS, RLIMIT_CPU_SECS))
+    if RLIMIT_MEMORY_MB > 0:
+        limit = RLIMIT_MEMORY_MB * 1024 * 1024
+        resource.setrlimit(resource.RLIMIT_AS, (limit, limit))
+    if RLIMIT_NPROC > 0:
+        resource.setrlimit(resource.RLIMIT_NPROC, (RLIMIT_NPROC, RLIMIT_NPROC))
+
+
+def assign_windows_job(pid: int) -> None:
+    """Put a spawned process into a Job Object carrying the limits.
+
+    The job handle is intentionally leaked: the job (and its limits) lives as
+    long as any process in it. Children spawned before assignment escape the
+    job, which is unavoidable without CREATE_SUSPENDED.
+    """
+    import ctypes
+    from ctypes import wintypes
+
+    class IO_COUNTERS(ctypes.Structure):
+        _fields_ = [(name, ctypes.c_ulonglong) for name in (
+            "ReadOperationCount", "WriteOperationCount", "OtherOperationCount",
+            "ReadTransferCount", "WriteTransferCount", "OtherTransferCount",
+        )]
+
+    class JOBOBJECT_BASIC_LIMIT_INFORMATION(ctypes.Structure):
+        _fields_ = [
+            ("PerProcessUserTimeLimit", ctypes.c_int64),
+            ("PerJobUserTimeLimit", ctypes.c_int64),
+            ("LimitFlags", wintypes.DWORD),
+            ("MinimumWorkingSetSize", ctypes.c_size_t),
+            ("MaximumWorkingSetSize", ctypes.c_size_t),
+            ("ActiveProcessLimit", wintypes.DWORD),
+            ("Affinity", ctypes.c_size_t),
+            ("PriorityClass", wintypes.DWORD),
+            ("SchedulingClass", wintypes.DWORD),
+        ]
+
+    class JOBOBJECT_EXTENDED_LIMIT_INFORMATION(ctypes.Structure):
+        _fields_ = [
+            ("BasicLimitInformation", JOBOBJECT_BASIC_LIMIT_INFORMATION),
+            ("IoInfo", IO_COUNTERS),
+            ("ProcessMemoryLimit", ctypes.c_size_t),
+            ("JobMemoryLimit", ctypes.c_size_t),
+            ("PeakProcessMemoryUsed", ctypes.c_size_t),
+            ("PeakJobMemoryUsed", ctypes.c_size_t),
+        ]
+
+    JOB_OBJECT_LIMIT_PROCESS_TIME = 0x2
+    JOB_OBJECT_LIMIT_ACTIVE_PROCESS = 0x8
+    JOB_OBJECT_LIMIT_JOB_MEMORY = 0x200
+    JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE = 0x2000
+    JobObjectExtendedLimitInformation = 9
+    PROCESS_SET_QUOTA = 0x0100
+    PROCESS_TERMINATE = 0x0001
+
+    info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION()
+    flags = 0
+    if RLIMIT_CPU_SECS > 0:
+        # 100-nanosecond units
+        info.BasicLimitInformation.PerProcessUserTimeLimit = RLIMIT_CPU_SECS * 10_000_000
+        flags |= JOB_OBJECT_LIMIT_PROCESS_TIME
+    if RLIMIT_MEMORY_MB > 0:
+        info.JobMemoryLimit = RLIMIT_MEMORY_MB * 1024 * 1024
+        flags |= JOB_OBJECT_LIMIT_JOB_MEMORY
+    if RLIMIT_NPROC > 0:
+        info.BasicLimitInformation.ActiveProcessLimit = RLIMIT_NPROC
+        flags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS
+    if not flags:
+        return
+    info.BasicLimitInformation.LimitFlags = flags
+
+    kernel32 = ctypes.WinDLL("kernel32", use_last_error=True)
+    job = kernel32.CreateJobObjectW(None, None)
+    if not job:
+        raise OSError(ctypes.get_last_error(), "CreateJobObjectW failed")
+    if not kernel32.SetInformationJobObject(
+        job, JobObjectExtendedLimitInformation, ctypes.byref(info), ctypes.sizeof(info)
+    ):
+        raise OSError(ctypes.get_last_error(), "SetInformationJobObject failed")
+    handle = kernel32.OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, False, pid)
+    if not handle:
+        raise OSError(ctypes.get_last_error(), "OpenProcess failed")
+    try:
+        if not kernel32.AssignProcessToJobObject(job, handle):
+            raise OSError(ctypes.get_last_error(), "AssignProcessToJobObject failed")
+    finally:
+        kernel32.CloseHandle(handle)
+
+
+async def read_capped(stream: asyncio.StreamReader, on_exceeded) -> tuple[bytes, int]:
+    """Read a stream keeping at most MAX_CAPTURE_BYTES; calls `on_exceeded` once
+    the stream produces more. Returns (captured bytes, total bytes read)."""
+    captured = bytearray()
+    total = 0
+    while chunk := await stream.read(65536):
+        total += len(chunk)
+        room = MAX_CAPTURE_BYTES - len(captured)
+        if room > 0:
+            captured.extend(chunk[:room])
+        if total > MAX_CAPTURE_BYTES:
+            on_exceeded()
+            break
+    return bytes(captured), total
+
+
+# ============================================================
+# Execution Backends
+# ============================================================
+
+
+def resolve_container_runtime() -> str:
+    """Return the container runtime executable. Raises RuntimeError if none is available."""
+    candidates = [CONTAINER_RUNTIME] if CONTAINER_RUNTIME else ["podman", "docker"]
+    for candidate in candidates:
+        path = shutil.which(candidate)
+        if path:
+            return path
+    raise RuntimeError(
+        f"Container backend selected but no runtime found (tried: {', '.join(candidates)})"
+    )
+
+
+def container_argv(
+    runtime: str, name: str, argv: list[str], env: dict[str, str], workspace: str
+) -> list[str]:
+    """Wrap `argv` in a disposable container run.
+
+    Only the agent's workspace is mounted; the root filesystem is read-only, all
+    capabilities are dropped and networking is disabled unless
+    CLOTO_CONTAINER_NETWORK says otherwise.
+    """
+    wrapped = [
+        runtime, "run", "--rm",
+        "--name", name,
+        "--network", CONTAINER_NETWORK,
+        "--read-only",
+        "--tmpfs", "/tmp",
+        "--cap-drop", "ALL",
+        "--security-opt", "no-new-privileges",
+        "--pids-limit", str(RLIMIT_NPROC or 256),
+        "-v", f"{os.path.abspath(workspace)}:{CONTAINER_WORKDIR}",
+        "-w", CONTAINER_WORKDIR,
+    ]
+    # Keep files written to the mount owned by the kernel's user
+    if hasattr(os, "getuid"):
+        wrapped += ["--user", f"{os.getuid()}:{os.getgid()}"]
+    if RLIMIT_CPU_SECS > 0:
+        wrapped += ["--ulimit", f"cpu={RLIMIT_CPU_SECS}"]
+    if RLIMIT_MEMORY_MB > 0:
+        wrapped += ["--memory", f"{RLIMIT_MEMORY_MB}m"]
+    for key, value in env.items():
+        if key not in HOST_ONLY_ENV:
+            wrapped += ["-e", f"{key}={value}"]
+    return wrapped + [CONTAINER_IMAGE, *argv]
+
+
+def split_windows_args(command: str) -> list[str]:
+    """Split a command line the way most Windows programs parse theirs.
+
+    Backslashes are literal (`C:\\Users\\me` survives intact, unlike with
+    shlex's POSIX mode) and double quotes group arguments.
+    """
+    return [
+        token[1:-1] if len(token) >= 2 and token[0] == token[-1] == '"' else token
+        for token in shlex.split(command, posix=False)
+    ]
+
+
+def windows_argv(command: str, shell: str) -> list[str]:
+    """argv for a host command on Windows under `shell` ("powershell" or "none").
+
+    cmd.exe is not handled here: it does not parse the backslash-escaped quotes
+    an argv list is serialized with, so it is started through the shell API
+    instead (see execute_command).
+    """
+    if shell == "powershell":
+        return [
+            shutil.which("powershell") or "powershell.exe",
+            "-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Restricted",
+            "-Command", command,
+        ]
+    if shell == "none":
+        return split_windows_args(command)
+    raise RuntimeError(
+        f"Unknown CLOTO_WINDOWS_SHELL '{shell}' (expected 'cmd', 'powershell' or 'none')"
+    )
+
+
+async def remove_container(runtime: str, name: str) -> None:
+    """Force-remove a container; killing the CLI client alone leaves it running."""
+    proc = await asyncio.create_subprocess_exec(
+        runtime, "rm", "-f", name,
+        stdout=asyncio.subprocess.DEVNULL,
+        stderr=asyncio.subprocess.DEVNULL,
+    )
+    await proc.wait()
+
+
 # ============================================================
 # MCP Server
 # ============================================================
@@ -136,22 +583,130 @@ async def list_tools() -> list[Tool]:
                         "type": "integer",
                         "description": "Timeout in seconds (default: 30, max: 120)",
                     },
+                    "agent_id": {
+                        "type": "string",
+                        "description": "Calling agent (set by the kernel)",
+                    },
+                    "env": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": (
+                            "Environment variable overrides for this command. "
+                            "Only variables granted by the administrator are accepted"
+                        ),
+                    },
                 },
                 "required": ["command"],
             },
-        )
+        ),
+        Tool(
+            name="list_workspace",
+            description=(
+                "List the files in your private working directory with their sizes, "
+                "and report disk usage against your quota."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "agent_id": {
+                        "type": "string",
+                        "description": "Calling agent (set by the kernel)",
+                    },
+                },
+            },
+        ),
+        Tool(
+            name="clean_workspace",
+            description=(
+                "Delete a file or directory from your working directory, "
+                "or everything in it when no path is given."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "agent_id": {
+                        "type": "string",
+                        "description": "Calling agent (set by the kernel)",
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Relative path to delete (default: entire workspace)",
+                    },
+                },
+            },
+        ),
     ]
 
 
 @server.call_tool()
 async def call_tool(name: str, arguments: dict) -> list[TextContent]:
-    if name != "execute_command":
+    if name == "execute_command":
+        return await execute_command(arguments)
+    if name == "list_workspace":
+        return list_workspace(arguments)
+    if name == "clean_workspace":
+        return clean_workspace(arguments)
+    return [TextContent(type="text", text=json.dumps({
+        "exit_code": -1,
+        "stdout": "",
+        "stderr": f"Unknown tool: {name}",
+    }))]
+
+
+def list_workspace(arguments: dict) -> list[TextContent]:
+    try:
+        workspace = agent_workspace(arguments.get("agent_id"))
+    except ValueError as e:
+        return [TextContent(type="text", text=json.dumps({"error": str(e)}))]
+
+    files = []
+    for root, _dirs, names in os.walk(workspace):
+        for f in sorted(names):
+            full = os.path.join(root, f)
+            try:
+                size = os.lstat(full).st_size
+            except OSError:
+                continue
+            files.append({"path": os.path.relpath(full, workspace), "size_bytes": size})
+    files.sort(key=lambda entry: entry["path"])
+    return [TextContent(type="text", text=json.dumps({
+        "files": files[:500],
+        "file_count": len(files),
+        "used_bytes": sum(entry["size_bytes"] for entry in files),
+        "quota_bytes": AGENT_QUOTA_BYTES,
+    }))]
+
+
+def clean_workspace(arguments: dict) -> list[TextContent]:
+    try:
+        workspace = agent_workspace(arguments.get("agent_id"))
+        target = resolve_in_workspace(workspace, arguments.get("path") or ".")
+    except ValueError as e:
+        return [TextContent(type="text", text=json.dumps({"error": str(e)}))]
+
+    before = workspace_usage(workspace)
+    if canonical_path(target) == canonical_path(workspace):
+        for entry in os.listdir(workspace):
+            remove_path(os.path.join(workspace, entry))
+    elif os.path.lexists(target):
+        remove_path(target)
+    else:
         return [TextContent(type="text", text=json.dumps({
-            "exit_code": -1,
-            "stdout": "",
-            "stderr": f"Unknown tool: {name}",
+            "error": f"Not found: '{arguments.get('path')}'",
         }))]
+    return [TextContent(type="text", text=json.dumps({
+        "freed_bytes": before - workspace_usage(workspace),
+    }))]
+
+
+def remove_path(path: str) -> None:
+    if os.path.isdir(path) and not os.path.islink(path):
+        shutil.rmtree(path, ignore_errors=True)
+    else:
+        os.remove(path)
+
 
+async def execute_command(arguments: dict) -> list[TextContent]:
     command = arguments.get("command")
     if not command:
         return [TextContent(type="text", text=json.dumps({
@@ -165,9 +720,19 @@ async def call_tool(name: str, arguments: dict) -> list[TextContent]:
 
     timeout_secs = min(arguments.get("timeout_secs", 30), 120)
 
+    # Windows host commands run under an interpreter; containers always get argv
+    windows_shell = WINDOWS_SHELL if IS_WINDOWS and EXECUTION_BACKEND == "host" else None
+
     # Validate command against sandbox rules
     try:
-        validate_command(command)
+        validate_command(command, windows_shell)
+        env = build_env(validate_env_overrides(arguments.get("env")))
+        workspace = agent_workspace(arguments.get("agent_id"))
+        if windows_shell == "cmd" and workspace.startswith("\\\\"):
+            raise ValueError(
+                "cmd.exe cannot run in a UNC workspace; set CLOTO_SANDBOX_DIR to a "
+                "drive-letter path or CLOTO_WINDOWS_SHELL to 'powershell'"
+            )
     except ValueError as e:
         return [TextContent(type="text", text=json.dumps({
             "exit_code": -1,
@@ -175,30 +740,95 @@ async def call_tool(name: str, arguments: dict) -> list[TextContent]:
             "stderr": str(e),
         }))]
 
-    # Ensure working directory exists
-    os.makedirs(WORKING_DIR, exist_ok=True)
+    if AGENT_QUOTA_BYTES > 0:
+        used = workspace_usage(workspace)
+        if used >= AGENT_QUOTA_BYTES:
+            return [TextContent(type="text", text=json.dumps({
+                "exit_code": -1,
+                "stdout": "",
+                "stderr": (
+                    f"Workspace quota exceeded ({used} of {AGENT_QUOTA_BYTES} bytes used). "
+                    "Free space with clean_workspace before running more commands."
+                ),
+            }))]
 
+    runtime = None
+    container_name = None
     try:
-        argv = shlex.split(command)
-        proc = await asyncio.create_subprocess_exec(
-            *argv,
-            stdout=asyncio.subprocess.PIPE,
-            stderr=asyncio.subprocess.PIPE,
-            cwd=WORKING_DIR,
-        )
+        if windows_shell == "cmd":
+            argv = None
+        elif windows_shell:
+            argv = windows_argv(command, windows_shell)
+        else:
+            argv = shlex.split(command)
+        if EXECUTION_BACKEND == "container":
+            runtime = resolve_container_runtime()
+            container_name = f"cloto-terminal-{uuid.uuid4().hex[:12]}"
+            argv = container_argv(runtime, container_name, argv, env, workspace)
+        elif EXECUTION_BACKEND != "host":
+            raise RuntimeError(
+                f"Unknown CLOTO_TERMINAL_BACKEND '{EXECUTION_BACKEND}' (expected 'host' or 'container')"
+            )
+
+        # The container runtime enforces limits itself (see container_argv)
+        host_posix = runtime is None and not IS_WINDOWS
+        if argv is None:
+            # Runs `%COMSPEC% /c "<command>"`, the quoting cmd.exe parses back reliably
+            proc = await asyncio.create_subprocess_shell(
+                command,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE,
+                cwd=workspace,
+                env=env,
+            )
+        else:
+            proc = await asyncio.create_subprocess_exec(
+                *argv,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE,
+                cwd=workspace,
+                env=env,
+                preexec_fn=apply_posix_rlimits if host_posix else None,
+            )
+        if runtime is None and IS_WINDOWS:
+            try:
+                assign_windows_job(proc.pid)
+            except OSError as e:
+                proc.kill()
+                await proc.wait()
+                raise RuntimeError(f"failed to apply resource limits: {e}") from e
+
+        output_exceeded = False
+
+        def kill_for_output() -> None:
+            nonlocal output_exceeded
+            output_exceeded = True
+            if proc.returncode is None:
+                proc.kill()
 
         try:
-            stdout_bytes, stderr_bytes = await asyncio.wait_for(
-                proc.communicate(), timeout=timeout_secs
+            (stdout_bytes, stdout_total), (stderr_bytes, stderr_total), _ = (
+                await asyncio.wait_for(
+                    asyncio.gather(
+                        read_capped(proc.stdout, kill_for_output),
+                        read_capped(proc.stderr, kill_for_output),
+                        proc.wait(),
+                    ),
+                    timeout=timeout_secs,
+                )
             )
         except asyncio.TimeoutError:
             proc.kill()
             await proc.wait()
+            if runtime and container_name:
+                await remove_container(runtime, container_name)
             return [TextContent(type="text", text=json.dumps({
                 "exit_code": -1,
                 "stdout": "",
                 "stderr": f"Command timed out after {timeout_secs} seconds",
             }))]
+        if output_exceeded and runtime and container_name:
+            await remove_container(runtime, container_name)
 
         stdout = stdout_bytes.decode("utf-8", errors="replace")
         stderr = stderr_bytes.decode("utf-8", errors="replace")
@@ -207,13 +837,15 @@ async def call_tool(name: str, arguments: dict) -> list[TextContent]:
         if len(stdout.encode("utf-8")) > MAX_OUTPUT_BYTES:
             stdout = (
                 safe_truncate(stdout, MAX_OUTPUT_BYTES)
-                + f"...[truncated, {len(stdout_bytes)} bytes total]"
+                + f"...[truncated, {stdout_total} bytes total]"
             )
         if len(stderr.encode("utf-8")) > MAX_OUTPUT_BYTES:
             stderr = (
                 safe_truncate(stderr, MAX_OUTPUT_BYTES)
-                + f"...[truncated, {len(stderr_bytes)} bytes total]"
+                + f"...[truncated, {stderr_total} bytes total]"
             )
+        if output_exceeded:
+            stderr += f"\n[killed: output exceeded {MAX_CAPTURE_BYTES} bytes]"
 
         exit_code = proc.returncode if proc.returncode is not None else -1
 
@@ -0,0 +1,210 @@
+"""
+Cloto MCP Server: Twilio Adapter
+Lets agents be reached by SMS from a plain phone.
+
+Runs a small HTTP listener (TWILIO_LISTEN) for Twilio's inbound SMS webhook;
+point the phone number's "A message comes in" webhook at
+TWILIO_PUBLIC_URL + /sms (e.g. through a reverse proxy or tunnel). Requests
+are checked against the X-Twilio-Signature header, acknowledged at once, and
+the agent's reply is sent back through the Twilio REST API (agent replies
+routinely exceed Twilio's 15 s webhook timeout).
+
+Senders must be listed in TWILIO_ALLOWED_NUMBERS; a phone number is
+reachable by anyone, so an empty list answers nobody. Voice calls are not
+handled yet; they need the speech capability.
+Requires the ChannelRelay permission.
+"""
+
+import asyncio
+import base64
+import hashlib
+import hmac
+import json
+import os
+import sys
+
+# Resolve parent directory for common module import.
+# Handle Windows UNC paths (\\?\...) that Python may receive from the kernel.
+_script_dir = os.path.dirname(os.path.abspath(__file__))
+sys.path.insert(0, os.path.normpath(os.path.join(_script_dir, "..")))
+
+import httpx
+from aiohttp import web
+from mcp.server import Server
+from mcp.server.stdio import stdio_server
+from mcp.types import TextContent, Tool
+
+from common.relay import ChannelRelay, parse_bindings, parse_list
+
+ACCOUNT_SID = os.environ.get("TWILIO_ACCOUNT_SID", "")
+AUTH_TOKEN = os.environ.get("TWILIO_AUTH_TOKEN", "")
+FROM_NUMBER = os.environ.get("TWILIO_FROM_NUMBER", "")
+PUBLIC_URL = os.environ.get("TWILIO_PUBLIC_URL", "").rstrip("/")
+LISTEN = os.environ.get("TWILIO_LISTEN", "127.0.0.1:8090")
+DEFAULT_AGENT = os.environ.get("TWILIO_DEFAULT_AGENT", "") or None
+ALLOWED_NUMBERS = parse_list(os.environ.get("TWILIO_ALLOWED_NUMBERS", ""))
+
+API_BASE = f"https://api.twilio.com/2010-04-01/Accounts/{ACCOUNT_SID}"
+# Twilio splits longer bodies itself but rejects anything over this
+MAX_SMS_LEN = 1600
+EMPTY_TWIML = '<?xml version="1.0" encoding="UTF-8"?><Response></Response>'
+
+# ============================================================
+# Server setup
+# ============================================================
+
+server = Server("adapter.twilio")
+relay = ChannelRelay()
+
+# Sender phone number -> agent ID
+bindings = parse_bindings(os.environ.get("TWILIO_NUMBER_AGENTS", ""))
+
+rest = httpx.AsyncClient(auth=(ACCOUNT_SID, AUTH_TOKEN), timeout=15.0)
+_runner: web.AppRunner | None = None
+
+
+def agent_for(number: str) -> str | None:
+    return bindings.get(number, DEFAULT_AGENT)
+
+
+def error_result(message: str) -> list[TextContent]:
+    return [TextContent(type="text", text=json.dumps({"error": message}))]
+
+
+def valid_signature(url: str, params: dict[str, str], signature: str) -> bool:
+    """Twilio signs the full URL followed by the sorted POST parameters."""
+    payload = url + "".join(f"{key}{params[key]}" for key in sorted(params))
+    digest = hmac.new(AUTH_TOKEN.encode(), payload.encode(), hashlib.sha1).digest()
+    return hmac.compare_digest(base64.b64encode(digest).decode(), signature)
+
+
+async def send_sms(to: str, text: str) -> str:
+    resp = await rest.post(
+        f"{API_BASE}/Messages.json",
+        data={"From": FROM_NUMBER, "To": to, "Body": text[:MAX_SMS_LEN]},
+    )
+    body = resp.json()
+    if resp.status_code >= 400:
+        raise RuntimeError(body.get("message", f"Twilio error {resp.status_code}"))
+    return body.get("sid", "")
+
+
+# ============================================================
+# Inbound SMS
+# ============================================================
+
+
+async def relay_sms(sender: str, text: str) -> None:
+    try:
+        reply = await relay.ask(
+            text, agent_id=agent_for(sender), user_id=sender, conversation=sender
+        )
+    except Exception as e:
+        print(f"Relay failed: {e}", file=sys.stderr)
+        reply = "Sorry, the agent is not available right now."
+    if reply:
+        try:
+            await send_sms(sender, reply)
+        except Exception as e:
+            print(f"Failed to send SMS reply: {e}", file=sys.stderr)
+
+
+async def on_sms(request: web.Request) -> web.Response:
+    params = {key: value for key, value in (await request.post()).items()}
+    signature = request.headers.get("X-Twilio-Signature", "")
+    if not valid_signature(f"{PUBLIC_URL}/sms", params, signature):
+        return web.Response(status=403, text="Invalid signature")
+
+    sender = params.get("From", "")
+    text = params.get("Body", "").strip()
+    if sender in ALLOWED_NUMBERS and text:
+        asyncio.create_task(relay_sms(sender, text))
+    # Acknowledge with empty TwiML; the reply goes out through the REST API
+    return web.Response(text=EMPTY_TWIML, content_type="application/xml")
+
+
+async def start_listener() -> None:
+    global _runner
+    if _runner is not None:
+        return
+    if not (ACCOUNT_SID and AUTH_TOKEN and FROM_NUMBER and PUBLIC_URL):
+        print(
+            "TWILIO_ACCOUNT_SID, TWILIO_AUTH_TOKEN, TWILIO_FROM_NUMBER and "
+            "TWILIO_PUBLIC_URL are required; adapter disabled",
+            file=sys.stderr,
+        )
+        return
+    host, _, port = LISTEN.rpartition(":")
+    app = web.Application()
+    app.router.add_post("/sms", on_sms)
+    _runner = web.AppRunner(app)
+    await _runner.setup()
+    await web.TCPSite(_runner, host or "127.0.0.1", int(port)).start()
+    print(f"Twilio webhook listening on {LISTEN}", file=sys.stderr)
+
+
+# ============================================================
+# Tool definitions
+# ============================================================
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    # The kernel lists tools on connect; bind the relay and go online then
+    relay.bind(server.request_context.session)
+    await start_listener()
+    return [
+        Tool(
+            name="send_sms",
+            description=(
+                "Send an SMS to a phone number on TWILIO_ALLOWED_NUMBERS "
+                "(E.164 format, e.g. +15551234567)."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "to": {"type": "string", "description": "Recipient phone number"},
+                    "text": {"type": "string", "description": "Message text"},
+                },
+                "required": ["to", "text"],
+            },
+        ),
+    ]
+
+
+# ============================================================
+# Tool handlers
+# ============================================================
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    if name == "send_sms":
+        to = arguments["to"]
+        # Agents may only text people who are allowed to text them
+        if to not in ALLOWED_NUMBERS:
+            return error_result(f"{to} is not in TWILIO_ALLOWED_NUMBERS")
+        if not (ACCOUNT_SID and AUTH_TOKEN and FROM_NUMBER):
+            return error_result("Twilio credentials are not configured")
+        try:
+            sid = await send_sms(to, arguments["text"])
+        except Exception as e:
+            return error_result(str(e))
+        return [TextContent(type="text", text=json.dumps({"ok": True, "sid": sid}))]
+
+    return error_result(f"Unknown tool: {name}")
+
+
+# ============================================================
+# Entry point
+# ============================================================
+
+
+async def main():
+    async with stdio_server() as (read_stream, write_stream):
+        await server.run(read_stream, write_stream, server.create_initialization_options())
+
+
+if __name__ == "__main__":
+    print("Cloto MCP Twilio Server starting...", file=sys.stderr)
+    asyncio.run(main())
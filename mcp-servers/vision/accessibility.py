@@ -0,0 +1,201 @@
+"""
+UI element enumeration via OS accessibility APIs.
+
+Backends: UI Automation on Windows (`uiautomation`), the AX API on macOS
+(pyobjc), and AT-SPI on Linux (`pyatspi`). Each yields elements in the
+`DetectedElement` shape with exact bounds and roles, which is far more
+reliable for clicking than OCR or pixel heuristics.
+
+The platform packages are imported lazily so the server still starts (with
+OCR only) where they are not installed.
+"""
+
+import sys
+
+MAX_DEPTH = 25
+
+# Normalized roles considered clickable / interactive
+INTERACTIVE_ROLES = frozenset({
+    "button", "link", "menu_item", "check_box", "radio_button", "tab",
+    "combo_box", "text_field", "list_item", "tree_item", "slider", "toggle_button",
+})
+
+
+class AccessibilityUnavailable(RuntimeError):
+    pass
+
+
+def list_elements(interactive_only: bool = True, max_elements: int = 500) -> list[dict]:
+    """Enumerate elements of the foreground window."""
+    if sys.platform == "win32":
+        raw = _walk_uia(max_elements * 4)
+    elif sys.platform == "darwin":
+        raw = _walk_ax(max_elements * 4)
+    else:
+        raw = _walk_atspi(max_elements * 4)
+
+    elements = []
+    for role, name, bounds, enabled, app in raw:
+        x, y, w, h = bounds
+        if w <= 0 or h <= 0:
+            continue
+        if interactive_only and role not in INTERACTIVE_ROLES:
+            continue
+        elements.append({
+            "label": name,
+            "bounds": [int(x), int(y), int(w), int(h)],
+            "confidence": 1.0,
+            "attributes": {
+                "source": "accessibility",
+                "role": role,
+                "enabled": "true" if enabled else "false",
+                "app": app,
+            },
+        })
+        if len(elements) >= max_elements:
+            break
+    return elements
+
+
+def _normalize_role(role: str) -> str:
+    """'ButtonControl' / 'AXButton' / 'push button' → 'button'."""
+    role = role.removesuffix("Control").removeprefix("AX")
+    aliases = {
+        "pushbutton": "button", "hyperlink": "link", "menuitem": "menu_item",
+        "checkbox": "check_box", "radiobutton": "radio_button", "tabitem": "tab",
+        "combobox": "combo_box", "popupbutton": "combo_box", "edit": "text_field",
+        "textfield": "text_field", "entry": "text_field",
+        "listitem": "list_item", "treeitem": "tree_item", "togglebutton": "toggle_button",
+        "pagetab": "tab", "statictext": "label",
+    }
+    key = role.replace(" ", "").replace("_", "").lower()
+    return aliases.get(key, key)
+
+
+# ------------------------------------------------------------
+# Windows: UI Automation
+# ------------------------------------------------------------
+
+
+def _walk_uia(limit: int):
+    try:
+        import uiautomation as auto
+    except ImportError as e:
+        raise AccessibilityUnavailable("Install 'uiautomation' for UI element detection") from e
+
+    window = auto.GetForegroundControl()
+    if window is None:
+        return []
+    app = window.Name or ""
+    out = []
+    for control, _depth in auto.WalkControl(window, includeTop=True, maxDepth=MAX_DEPTH):
+        rect = control.BoundingRectangle
+        out.append((
+            _normalize_role(control.ControlTypeName),
+            control.Name or "",
+            (rect.left, rect.top, rect.width(), rect.height()),
+            bool(control.IsEnabled),
+            app,
+        ))
+        if len(out) >= limit:
+            break
+    return out
+
+
+# ------------------------------------------------------------
+# macOS: AX API
+# ------------------------------------------------------------
+
+
+def _walk_ax(limit: int):
+    try:
+        from AppKit import NSWorkspace
+        from ApplicationServices import (
+            AXUIElementCopyAttributeValue,
+            AXUIElementCreateApplication,
+            AXValueGetValue,
+            kAXValueCGPointType,
+            kAXValueCGSizeType,
+        )
+    except ImportError as e:
+        raise AccessibilityUnavailable(
+            "Install 'pyobjc' and grant Accessibility access for UI element detection"
+        ) from e
+
+    def attr(element, name):
+        err, value = AXUIElementCopyAttributeValue(element, name, None)
+        return value if err == 0 else None
+
+    front = NSWorkspace.sharedWorkspace().frontmostApplication()
+    app = str(front.localizedName() or "")
+    root = attr(AXUIElementCreateApplication(front.processIdentifier()), "AXFocusedWindow")
+    if root is None:
+        return []
+
+    out = []
+    stack = [(root, 0)]
+    while stack and len(out) < limit:
+        element, depth = stack.pop()
+        position, size = attr(element, "AXPosition"), attr(element, "AXSize")
+        if position is not None and size is not None:
+            _, point = AXValueGetValue(position, kAXValueCGPointType, None)
+            _, dims = AXValueGetValue(size, kAXValueCGSizeType, None)
+            name = attr(element, "AXTitle") or attr(element, "AXDescription") or attr(element, "AXValue")
+            out.append((
+                _normalize_role(str(attr(element, "AXRole") or "")),
+                str(name) if isinstance(name, str) else "",
+                (point.x, point.y, dims.width, dims.height),
+                bool(attr(element, "AXEnabled")),
+                app,
+            ))
+        if depth < MAX_DEPTH:
+            stack.extend((child, depth + 1) for child in reversed(attr(element, "AXChildren") or []))
+    return out
+
+
+# ------------------------------------------------------------
+# Linux: AT-SPI
+# ------------------------------------------------------------
+
+
+def _walk_atspi(limit: int):
+    try:
+        import pyatspi
+    except ImportError as e:
+        raise AccessibilityUnavailable(
+            "Install 'pyatspi' (python3-pyatspi) for UI element detection"
+        ) from e
+
+    def active_window():
+        desktop = pyatspi.Registry.getDesktop(0)
+        for app in desktop:
+            if app is None:
+                continue
+            for window in app:
+                if window is not None and window.getState().contains(pyatspi.STATE_ACTIVE):
+                    return app.name or "", window
+        return "", None
+
+    app, root = active_window()
+    if root is None:
+        return []
+
+    out = []
+    stack = [(root, 0)]
+    while stack and len(out) < limit:
+        element, depth = stack.pop()
+        try:
+            extents = element.queryComponent().getExtents(pyatspi.DESKTOP_COORDS)
+            state = element.getState()
+            out.append((
+                _normalize_role(element.getRoleName()),
+                element.name or "",
+                (extents.x, extents.y, extents.width, extents.height),
+                state.contains(pyatspi.STATE_ENABLED),
+                app,
+            ))
+        except NotImplementedError:
+            pass  # element has no Component interface (no geometry)
+        if depth < MAX_DEPTH:
+            stack.extend((child, depth + 1) for child in reversed(list(element)) if child is not None)
+    return out
@@ -0,0 +1,73 @@
+"""
+Screen capture for the vision server.
+
+Frames are returned as PIL images together with the desktop offset of the
+captured area, so element bounds can be reported in desktop coordinates
+(multi-monitor layouts) and used directly for mouse actions.
+"""
+
+import os
+import tempfile
+import time
+from dataclasses import dataclass
+
+import mss
+from PIL import Image
+
+# Captured frames referenced by `image_ref` are written here
+CAPTURE_DIR = os.environ.get("CLOTO_VISION_DIR", os.path.join(tempfile.gettempdir(), "cloto-vision"))
+# Keep at most this many frames on disk (oldest are deleted first)
+MAX_STORED_FRAMES = int(os.environ.get("CLOTO_VISION_MAX_FRAMES", "50"))
+
+
+@dataclass
+class Frame:
+    image: Image.Image
+    # Top-left corner of the frame in desktop coordinates
+    left: int
+    top: int
+    captured_at: float
+
+
+def grab(monitor: int = 1, region: dict | None = None) -> Frame:
+    """Capture a monitor (1-based, mss numbering; 0 = all monitors combined)
+    or a desktop-coordinate region {x, y, width, height}."""
+    with mss.mss() as sct:
+        if region is not None:
+            area = {
+                "left": int(region["x"]),
+                "top": int(region["y"]),
+                "width": int(region["width"]),
+                "height": int(region["height"]),
+            }
+            if area["width"] <= 0 or area["height"] <= 0:
+                raise ValueError("Region width and height must be positive")
+        else:
+            if monitor < 0 or monitor >= len(sct.monitors):
+                raise ValueError(
+                    f"Monitor {monitor} not found ({len(sct.monitors) - 1} connected)"
+                )
+            area = sct.monitors[monitor]
+        shot = sct.grab(area)
+        image = Image.frombytes("RGB", shot.size, shot.bgra, "raw", "BGRX")
+        return Frame(image=image, left=area["left"], top=area["top"], captured_at=time.time())
+
+
+def store(frame: Frame) -> str:
+    """Write the frame as PNG under CAPTURE_DIR and return its path."""
+    os.makedirs(CAPTURE_DIR, exist_ok=True)
+    path = os.path.join(CAPTURE_DIR, f"frame-{int(frame.captured_at * 1000)}.png")
+    frame.image.save(path, "PNG")
+    prune()
+    return path
+
+
+def prune() -> None:
+    frames = sorted(
+        (f for f in os.listdir(CAPTURE_DIR) if f.startswith("frame-") and f.endswith(".png")),
+    )
+    for name in frames[:-MAX_STORED_FRAMES] if MAX_STORED_FRAMES > 0 else []:
+        try:
+            os.remove(os.path.join(CAPTURE_DIR, name))
+        except OSError:
+            pass
@@ -0,0 +1,53 @@
+"""
+OCR stage: converts a captured frame into DetectedElements.
+
+Uses Tesseract (via pytesseract). Words are grouped into text lines; each
+line becomes one element whose bounds are in desktop coordinates, matching
+`cloto_shared::DetectedElement` ({label, bounds: [x, y, w, h], confidence,
+attributes}).
+"""
+
+import os
+
+import pytesseract
+
+from capture import Frame
+
+OCR_LANG = os.environ.get("CLOTO_OCR_LANG", "eng")
+# Optional explicit path to the tesseract binary (e.g. on Windows)
+TESSERACT_CMD = os.environ.get("CLOTO_TESSERACT_CMD", "")
+if TESSERACT_CMD:
+    pytesseract.pytesseract.tesseract_cmd = TESSERACT_CMD
+
+
+def detect_text(frame: Frame, min_confidence: float = 0.5) -> list[dict]:
+    """Run OCR on a frame and return one element per recognized text line."""
+    data = pytesseract.image_to_data(
+        frame.image, lang=OCR_LANG, output_type=pytesseract.Output.DICT
+    )
+
+    lines: dict[tuple[int, int, int], list[int]] = {}
+    for i, word in enumerate(data["text"]):
+        if not word.strip() or float(data["conf"][i]) < 0:
+            continue
+        key = (data["block_num"][i], data["par_num"][i], data["line_num"][i])
+        lines.setdefault(key, []).append(i)
+
+    elements = []
+    for indices in lines.values():
+        confidence = sum(float(data["conf"][i]) for i in indices) / len(indices) / 100.0
+        if confidence < min_confidence:
+            continue
+        left = min(data["left"][i] for i in indices)
+        top = min(data["top"][i] for i in indices)
+        right = max(data["left"][i] + data["width"][i] for i in indices)
+        bottom = max(data["top"][i] + data["height"][i] for i in indices)
+        elements.append({
+            "label": " ".join(data["text"][i].strip() for i in indices),
+            "bounds": [frame.left + left, frame.top + top, right - left, bottom - top],
+            "confidence": round(confidence, 3),
+            "attributes": {"source": "ocr", "role": "text"},
+        })
+
+    elements.sort(key=lambda e: (e["bounds"][1], e["bounds"][0]))
+    return elements
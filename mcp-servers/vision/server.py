@@ -0,0 +1,311 @@
+"""
+Cloto MCP Server: Screen Vision
+Screen capture, OCR and accessibility-based UI element detection for agents.
+
+Tool results use the shape of `cloto_shared::ColorVisionData`
+({captured_at, detected_elements, image_ref}), so reasoning engines without
+vision models can still "read" the screen through text elements.
+
+In watch mode, significant screen changes are pushed to the kernel as
+`notifications/cloto.vision_updated` and published as VisionUpdated events.
+"""
+
+import asyncio
+import json
+import sys
+from datetime import datetime, timezone
+
+from mcp.server import Server
+from mcp.server.stdio import stdio_server
+from mcp.types import Notification, TextContent, Tool
+
+import accessibility
+import capture
+import ocr
+import watcher
+
+VISION_UPDATED_NOTIFICATION = "notifications/cloto.vision_updated"
+
+# ============================================================
+# Server setup
+# ============================================================
+
+server = Server("vision.screen")
+
+REGION_SCHEMA = {
+    "type": "object",
+    "description": "Desktop-coordinate rectangle to capture instead of a whole monitor",
+    "properties": {
+        "x": {"type": "integer"},
+        "y": {"type": "integer"},
+        "width": {"type": "integer"},
+        "height": {"type": "integer"},
+    },
+    "required": ["x", "y", "width", "height"],
+}
+
+MONITOR_SCHEMA = {
+    "type": "integer",
+    "description": "Monitor number (1 = primary, 0 = all monitors combined; default 1)",
+}
+
+
+def vision_data(frame: capture.Frame, elements: list[dict], image_ref: str | None) -> dict:
+    return {
+        "captured_at": datetime.fromtimestamp(frame.captured_at, timezone.utc).isoformat(),
+        "detected_elements": elements,
+        "image_ref": image_ref,
+    }
+
+
+def error_result(message: str) -> list[TextContent]:
+    return [TextContent(type="text", text=json.dumps({"error": message}))]
+
+
+# ============================================================
+# Watch mode
+# ============================================================
+
+# Session of the kernel connection, captured when a watch is started
+_session = None
+
+
+async def report_change(frame: capture.Frame, ratio: float) -> None:
+    loop = asyncio.get_running_loop()
+    config = _watcher.status.config
+    elements = []
+    if config.ocr:
+        try:
+            elements = await loop.run_in_executor(None, ocr.detect_text, frame, 0.5)
+        except Exception as e:
+            print(f"Watch OCR failed: {e}", file=sys.stderr)
+    image_ref = await loop.run_in_executor(None, capture.store, frame)
+    data = vision_data(frame, elements, image_ref)
+    if _session is not None:
+        await _session.send_notification(
+            Notification(method=VISION_UPDATED_NOTIFICATION, params=data)
+        )
+
+
+_watcher = watcher.Watcher(report_change)
+
+
+def watch_status() -> dict:
+    status = _watcher.status
+    if status is None:
+        return {"running": False}
+    return {
+        "running": _watcher.running,
+        "interval_secs": status.config.interval_secs,
+        "threshold": status.config.threshold,
+        "monitor": status.config.monitor,
+        "region": status.config.region,
+        "ocr": status.config.ocr,
+        "started_at": datetime.fromtimestamp(status.started_at, timezone.utc).isoformat(),
+        "frames_checked": status.frames_checked,
+        "changes_reported": status.changes_reported,
+        "last_change_ratio": round(status.last_change_ratio, 4),
+        "last_reported_at": (
+            datetime.fromtimestamp(status.last_reported_at, timezone.utc).isoformat()
+            if status.last_reported_at is not None
+            else None
+        ),
+        "last_error": status.last_error,
+    }
+
+
+# ============================================================
+# Tool definitions
+# ============================================================
+
+
+@server.list_tools()
+async def list_tools() -> list[Tool]:
+    return [
+        Tool(
+            name="capture_screen",
+            description=(
+                "Capture the screen (or a region) and return a reference to the "
+                "saved PNG image."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {"monitor": MONITOR_SCHEMA, "region": REGION_SCHEMA},
+                "required": [],
+            },
+        ),
+        Tool(
+            name="read_screen",
+            description=(
+                "Capture the screen (or a region) and read its text with OCR. "
+                "Returns each text line with its bounds in desktop coordinates "
+                "[x, y, width, height], usable for mouse actions."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "monitor": MONITOR_SCHEMA,
+                    "region": REGION_SCHEMA,
+                    "min_confidence": {
+                        "type": "number",
+                        "description": "Drop lines below this OCR confidence (0-1, default 0.5)",
+                    },
+                },
+                "required": [],
+            },
+        ),
+        Tool(
+            name="list_ui_elements",
+            description=(
+                "List the UI elements of the foreground window via the OS "
+                "accessibility API, with exact bounds (desktop coordinates) and "
+                "roles such as button, link or text_field. Prefer this over OCR "
+                "when locating something to click."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "interactive_only": {
+                        "type": "boolean",
+                        "description": "Only clickable/editable elements (default true)",
+                    },
+                    "max_elements": {
+                        "type": "integer",
+                        "description": "Maximum elements to return (default 200, max 1000)",
+                    },
+                },
+                "required": [],
+            },
+        ),
+        Tool(
+            name="start_watch",
+            description=(
+                "Watch the screen (or a region) in the background and emit a "
+                "VisionUpdated event whenever it changes significantly, e.g. to "
+                "notice when a build or download finishes. Replaces any running watch."
+            ),
+            inputSchema={
+                "type": "object",
+                "properties": {
+                    "monitor": MONITOR_SCHEMA,
+                    "region": REGION_SCHEMA,
+                    "interval_secs": {
+                        "type": "number",
+                        "description": "Seconds between captures (default 2, min 0.5)",
+                    },
+                    "threshold": {
+                        "type": "number",
+                        "description": (
+                            "Fraction of the watched area that must change to "
+                            "emit an event (0-1, default 0.02)"
+                        ),
+                    },
+                    "ocr": {
+                        "type": "boolean",
+                        "description": "Include OCR text elements in emitted events (default false)",
+                    },
+                },
+                "required": [],
+            },
+        ),
+        Tool(
+            name="stop_watch",
+            description="Stop the running screen watch.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+        Tool(
+            name="get_watch_status",
+            description="Show the screen watch configuration and counters.",
+            inputSchema={"type": "object", "properties": {}, "required": []},
+        ),
+    ]
+
+
+# ============================================================
+# Tool handlers
+# ============================================================
+
+
+@server.call_tool()
+async def call_tool(name: str, arguments: dict) -> list[TextContent]:
+    global _session
+    loop = asyncio.get_event_loop()
+    monitor = int(arguments.get("monitor", 1))
+    region = arguments.get("region")
+
+    if name == "capture_screen":
+        try:
+            frame = await loop.run_in_executor(None, capture.grab, monitor, region)
+            image_ref = await loop.run_in_executor(None, capture.store, frame)
+        except Exception as e:
+            return error_result(f"Screen capture failed: {e}")
+        return [TextContent(type="text", text=json.dumps(vision_data(frame, [], image_ref)))]
+
+    elif name == "read_screen":
+        min_confidence = float(arguments.get("min_confidence", 0.5))
+        try:
+            frame = await loop.run_in_executor(None, capture.grab, monitor, region)
+            elements = await loop.run_in_executor(None, ocr.detect_text, frame, min_confidence)
+            image_ref = await loop.run_in_executor(None, capture.store, frame)
+        except Exception as e:
+            return error_result(f"OCR failed: {e}")
+        return [TextContent(type="text", text=json.dumps(vision_data(frame, elements, image_ref)))]
+
+    elif name == "list_ui_elements":
+        interactive_only = bool(arguments.get("interactive_only", True))
+        max_elements = max(1, min(int(arguments.get("max_elements", 200)), 1000))
+        try:
+            elements = await loop.run_in_executor(
+                None, accessibility.list_elements, interactive_only, max_elements
+            )
+        except accessibility.AccessibilityUnavailable as e:
+            return error_result(str(e))
+        except Exception as e:
+            return error_result(f"Accessibility query failed: {e}")
+        return [TextContent(type="text", text=json.dumps({
+            "captured_at": datetime.now(timezone.utc).isoformat(),
+            "detected_elements": elements,
+            "image_ref": None,
+        }))]
+
+    elif name == "start_watch":
+        try:
+            config = watcher.WatchConfig(
+                interval_secs=float(arguments.get("interval_secs", 2.0)),
+                threshold=float(arguments.get("threshold", 0.02)),
+                monitor=monitor,
+                region=region,
+                ocr=bool(arguments.get("ocr", False)),
+            )
+        except (TypeError, ValueError) as e:
+            return error_result(f"Invalid watch arguments: {e}")
+        if not 0.0 < config.threshold <= 1.0:
+            return error_result("threshold must be in (0, 1]")
+        _session = server.request_context.session
+        _watcher.start(config)
+        return [TextContent(type="text", text=json.dumps(watch_status()))]
+
+    elif name == "stop_watch":
+        stopped = _watcher.stop()
+        return [TextContent(type="text", text=json.dumps({"stopped": stopped}))]
+
+    elif name == "get_watch_status":
+        return [TextContent(type="text", text=json.dumps(watch_status()))]
+
+    else:
+        return error_result(f"Unknown tool: {name}")
+
+
+# ============================================================
+# Entry point
+# ============================================================
+
+
+async def main():
+    async with stdio_server() as (read_stream, write_stream):
+        await server.run(read_stream, write_stream, server.create_initialization_options())
+
+
+if __name__ == "__main__":
+    print("Cloto MCP Vision Server starting...", file=sys.stderr)
+    asyncio.run(main())
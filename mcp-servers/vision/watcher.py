@@ -0,0 +1,117 @@
+"""
+Screen change detection for the vision server.
+
+A watcher captures periodically, compares each frame against the last
+reported one on a downsampled grayscale copy, and only reports when the
+changed-pixel ratio reaches the threshold. This keeps the event bus quiet
+(cursor blinks, clocks) while still catching "the build finished" changes.
+"""
+
+import asyncio
+import os
+import time
+from dataclasses import dataclass, field
+from typing import Awaitable, Callable
+
+from PIL import Image, ImageChops
+
+import capture
+
+# Frames are compared at this resolution; small enough to be cheap,
+# large enough that a status line changing is still visible
+DIFF_SIZE = (160, 90)
+# Per-pixel grayscale delta (0-255) below which a pixel counts as unchanged
+PIXEL_TOLERANCE = int(os.environ.get("CLOTO_VISION_PIXEL_TOLERANCE", "24"))
+MIN_INTERVAL_SECS = float(os.environ.get("CLOTO_VISION_MIN_INTERVAL", "0.5"))
+
+
+def thumbnail(image: Image.Image) -> Image.Image:
+    return image.convert("L").resize(DIFF_SIZE, Image.BILINEAR)
+
+
+def change_ratio(previous: Image.Image, current: Image.Image) -> float:
+    """Fraction of thumbnail pixels that differ by more than PIXEL_TOLERANCE."""
+    diff = ImageChops.difference(previous, current)
+    histogram = diff.histogram()
+    changed = sum(histogram[PIXEL_TOLERANCE + 1:])
+    return changed / (DIFF_SIZE[0] * DIFF_SIZE[1])
+
+
+@dataclass
+class WatchConfig:
+    interval_secs: float = 2.0
+    threshold: float = 0.02
+    monitor: int = 1
+    region: dict | None = None
+    ocr: bool = False
+
+
+@dataclass
+class WatchStatus:
+    config: WatchConfig
+    started_at: float = field(default_factory=time.time)
+    frames_checked: int = 0
+    changes_reported: int = 0
+    last_change_ratio: float = 0.0
+    last_reported_at: float | None = None
+    last_error: str | None = None
+
+
+class Watcher:
+    """Runs the capture/diff loop as an asyncio task.
+
+    `on_change(frame, ratio)` is awaited for every significant change; the
+    first frame only establishes the baseline and is not reported.
+    """
+
+    def __init__(self, on_change: Callable[[capture.Frame, float], Awaitable[None]]):
+        self._on_change = on_change
+        self._task: asyncio.Task | None = None
+        self.status: WatchStatus | None = None
+
+    @property
+    def running(self) -> bool:
+        return self._task is not None and not self._task.done()
+
+    def start(self, config: WatchConfig) -> None:
+        self.stop()
+        config.interval_secs = max(config.interval_secs, MIN_INTERVAL_SECS)
+        self.status = WatchStatus(config=config)
+        self._task = asyncio.get_running_loop().create_task(self._run(self.status))
+
+    def stop(self) -> bool:
+        if not self.running:
+            return False
+        self._task.cancel()
+        self._task = None
+        return True
+
+    async def _run(self, status: WatchStatus) -> None:
+        loop = asyncio.get_running_loop()
+        config = status.config
+        baseline: Image.Image | None = None
+        while True:
+            try:
+                frame = await loop.run_in_executor(
+                    None, capture.grab, config.monitor, config.region
+                )
+                current = await loop.run_in_executor(None, thumbnail, frame.image)
+                status.frames_checked += 1
+                status.last_error = None
+                if baseline is None:
+                    baseline = current
+                else:
+                    ratio = change_ratio(baseline, current)
+                    status.last_change_ratio = ratio
+                    if ratio >= config.threshold:
+                        # Compare future frames against what was reported, so a
+                        # slow gradual change is still reported once it adds up
+                        baseline = current
+                        status.changes_reported += 1
+                        status.last_reported_at = frame.captured_at
+                        await self._on_change(frame, ratio)
+            except asyncio.CancelledError:
+                raise
+            except Exception as e:
+                status.last_error = str(e)
+            await asyncio.sleep(config.interval_secs)